@@ -175,6 +175,192 @@ export function activate(
         }),
     );
 
+    context.subscriptions.push(
+        vscode.commands.registerCommand("slint.exportBundle", async function () {
+            const ae = vscode.window.activeTextEditor;
+            if (!ae) {
+                return;
+            }
+
+            const target = await vscode.window.showOpenDialog({
+                canSelectFiles: false,
+                canSelectFolders: true,
+                canSelectMany: false,
+                openLabel: "Export Here",
+            });
+            if (!target || target.length === 0) {
+                return;
+            }
+
+            const files = await lsp_commands.exportBundle(
+                ae.document.uri.toString(),
+                target[0].fsPath,
+            );
+            if (files) {
+                vscode.window.showInformationMessage(
+                    `Exported ${files.length} files to ${target[0].fsPath}`,
+                );
+            }
+        }),
+    );
+
+    context.subscriptions.push(
+        vscode.commands.registerCommand("slint.importSchema", async function () {
+            const schema = await vscode.window.showOpenDialog({
+                canSelectFiles: true,
+                canSelectFolders: false,
+                canSelectMany: false,
+                openLabel: "Import",
+                filters: { "JSON Schema or OpenAPI": ["json"] },
+            });
+            if (!schema || schema.length === 0) {
+                return;
+            }
+
+            const output = await vscode.window.showSaveDialog({
+                filters: { Slint: ["slint"] },
+                saveLabel: "Generate",
+            });
+            if (!output) {
+                return;
+            }
+
+            const result = await lsp_commands.importSchema(
+                schema[0].fsPath,
+                output.fsPath,
+            );
+            if (result) {
+                await vscode.window.showTextDocument(output);
+            }
+        }),
+    );
+
+    context.subscriptions.push(
+        vscode.commands.registerCommand(
+            "slint.flashElementInPreview",
+            async function () {
+                const ae = vscode.window.activeTextEditor;
+                if (!ae) {
+                    return;
+                }
+                await lsp_commands.flashElement(
+                    ae.document.uri.toString(),
+                    ae.selection.active,
+                );
+            },
+        ),
+    );
+
+    context.subscriptions.push(
+        vscode.commands.registerCommand("slint.resyncSchema", async function () {
+            const ae = vscode.window.activeTextEditor;
+            if (!ae) {
+                return;
+            }
+
+            const schema = await lsp_commands.resyncSchema(
+                ae.document.uri.toString(),
+            );
+            if (schema) {
+                vscode.window.showInformationMessage(`Re-synced with ${schema}`);
+            }
+        }),
+    );
+
+    context.subscriptions.push(
+        vscode.commands.registerCommand(
+            "slint.generateRustGlue",
+            async function () {
+                const ae = vscode.window.activeTextEditor;
+                if (!ae) {
+                    return;
+                }
+                const range = ae.document.getWordRangeAtPosition(
+                    ae.selection.active,
+                    /[a-zA-Z_][a-zA-Z0-9_-]*/,
+                );
+                if (!range) {
+                    return;
+                }
+
+                const compiledModule = await vscode.window.showInputBox({
+                    prompt: "The Rust module calling slint::include_modules!()",
+                    value: "crate",
+                });
+                if (compiledModule === undefined) {
+                    return;
+                }
+
+                const code = await lsp_commands.generateRustGlue(
+                    ae.document.uri.toString(),
+                    ae.document.getText(range),
+                    compiledModule,
+                );
+                if (code) {
+                    const document = await vscode.workspace.openTextDocument({
+                        language: "rust",
+                        content: code,
+                    });
+                    await vscode.window.showTextDocument(document);
+                }
+            },
+        ),
+    );
+
+    context.subscriptions.push(
+        vscode.commands.registerCommand(
+            "slint.structuralReplace",
+            async function () {
+                const query = await vscode.window.showInputBox({
+                    prompt: "The elements to find, like `!Rectangle > Image[source]`",
+                });
+                if (!query) {
+                    return;
+                }
+                const template = await vscode.window.showInputBox({
+                    prompt: "The code replacing each element, like `Rectangle { clip: true; $element }`. Leave empty to only find them",
+                });
+                if (template === undefined) {
+                    return;
+                }
+
+                if (template !== "") {
+                    const count = await lsp_commands.structuralReplace(
+                        query,
+                        template,
+                    );
+                    if (count !== undefined) {
+                        vscode.window.showInformationMessage(
+                            `Replaced ${count} elements`,
+                        );
+                    }
+                    return;
+                }
+
+                const matches =
+                    (await lsp_commands.structuralSearch(query)) ?? [];
+                const picked = await vscode.window.showQuickPick(
+                    matches.map((m) => ({
+                        label: m.element,
+                        description: `${vscode.workspace.asRelativePath(vscode.Uri.parse(m.location.uri))}:${m.location.range.start.line + 1}`,
+                        location: m.location,
+                    })),
+                    { placeHolder: `${matches.length} elements match` },
+                );
+                if (picked) {
+                    const start = new vscode.Position(
+                        picked.location.range.start.line,
+                        picked.location.range.start.character,
+                    );
+                    await vscode.window.showTextDocument(
+                        vscode.Uri.parse(picked.location.uri),
+                        { selection: new vscode.Range(start, start) },
+                    );
+                }
+            },
+        ),
+    );
+
     context.subscriptions.push(
         vscode.commands.registerCommand("slint.reload", async function () {
             statusBar.hide();
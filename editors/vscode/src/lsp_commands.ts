@@ -1,7 +1,10 @@
 // Copyright © SixtyFPS GmbH <info@slint.dev>
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
-import type { URI as LspURI } from "vscode-languageserver-types";
+import type {
+    URI as LspURI,
+    Range as LspRange,
+} from "vscode-languageserver-types";
 import * as vscode from "vscode";
 
 // Use the auto-registered VSCode command for the custom executables offered
@@ -24,3 +27,66 @@ export async function showPreview(
 ): Promise<unknown> {
     return vscode.commands.executeCommand("slint/showPreview", url, component);
 }
+
+export async function flashElement(
+    url: LspURI,
+    position: vscode.Position,
+): Promise<unknown> {
+    return vscode.commands.executeCommand("slint/flashElement", url, {
+        line: position.line,
+        character: position.character,
+    });
+}
+
+export async function exportBundle(
+    url: LspURI,
+    directory: string,
+): Promise<string[] | undefined> {
+    return vscode.commands.executeCommand("slint/exportBundle", url, directory);
+}
+
+export async function importSchema(
+    schema: string,
+    output: string,
+): Promise<string | undefined> {
+    return vscode.commands.executeCommand("slint/importSchema", schema, output);
+}
+
+export async function resyncSchema(url: LspURI): Promise<string | undefined> {
+    return vscode.commands.executeCommand("slint/resyncSchema", url);
+}
+
+export async function generateRustGlue(
+    url: LspURI,
+    name: string,
+    compiledModule: string,
+): Promise<string | undefined> {
+    return vscode.commands.executeCommand(
+        "slint/generateRustGlue",
+        url,
+        name,
+        compiledModule,
+    );
+}
+
+export interface StructuralMatch {
+    location: { uri: LspURI; range: LspRange };
+    element: string;
+}
+
+export async function structuralSearch(
+    query: string,
+): Promise<StructuralMatch[] | undefined> {
+    return vscode.commands.executeCommand("slint/structuralSearch", query);
+}
+
+export async function structuralReplace(
+    query: string,
+    template: string,
+): Promise<number | undefined> {
+    return vscode.commands.executeCommand(
+        "slint/structuralSearch",
+        query,
+        template,
+    );
+}
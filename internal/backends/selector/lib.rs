@@ -159,6 +159,11 @@ pub fn with_global_context<R>(f: impl FnOnce(&SlintContext) -> R) -> Result<R, P
         i_slint_backend_testing::systest::init();
     }
 
+    #[cfg(feature = "inspector")]
+    if result.is_ok() && platform_created {
+        i_slint_backend_testing::inspector::init();
+    }
+
     result
 }
 
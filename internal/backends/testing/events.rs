@@ -0,0 +1,71 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Simulating the input of the user, by sending the events of the windowing system to a window
+//! right away. See [`crate::ElementHandle::single_click()`] for clicks that take their time.
+
+use i_slint_core::api::{LogicalPosition, Window};
+use i_slint_core::platform::{PointerEventButton, WindowEvent};
+use i_slint_core::SharedString;
+
+/// Simulate pressing a key, like `'a'` or [`i_slint_core::platform::Key::Shift`], without
+/// releasing it
+pub fn send_key_press(window: &Window, key: impl Into<SharedString>) {
+    window.dispatch_event(WindowEvent::KeyPressed { text: key.into() });
+}
+
+pub fn send_key_release(window: &Window, key: impl Into<SharedString>) {
+    window.dispatch_event(WindowEvent::KeyReleased { text: key.into() });
+}
+
+/// Simulate pressing and releasing a key, like [`i_slint_core::platform::Key::Return`]
+pub fn send_key(window: &Window, key: impl Into<SharedString>) {
+    let key = key.into();
+    send_key_press(window, key.clone());
+    send_key_release(window, key);
+}
+
+/// Simulate typing `text` character by character
+pub fn send_text(window: &Window, text: &str) {
+    for c in text.chars() {
+        send_key(window, c);
+    }
+}
+
+pub fn send_mouse_move(window: &Window, position: LogicalPosition) {
+    window.dispatch_event(WindowEvent::PointerMoved { position });
+}
+
+/// Simulate moving the mouse to `position`, and pressing and releasing `button` there
+pub fn send_mouse_click(window: &Window, position: LogicalPosition, button: PointerEventButton) {
+    send_mouse_move(window, position);
+    window.dispatch_event(WindowEvent::PointerPressed { position, button });
+    window.dispatch_event(WindowEvent::PointerReleased { position, button });
+}
+
+/// Simulate pressing `button` at `from`, moving the mouse to `to` in `steps` and releasing it
+pub fn send_mouse_drag(
+    window: &Window,
+    from: LogicalPosition,
+    to: LogicalPosition,
+    button: PointerEventButton,
+    steps: usize,
+) {
+    send_mouse_move(window, from);
+    window.dispatch_event(WindowEvent::PointerPressed { position: from, button });
+    let steps = steps.max(1);
+    for step in 1..=steps {
+        let t = step as f32 / steps as f32;
+        send_mouse_move(
+            window,
+            LogicalPosition::new(from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t),
+        );
+    }
+    window.dispatch_event(WindowEvent::PointerReleased { position: to, button });
+}
+
+/// Simulate turning the mouse wheel at `position`
+pub fn send_scroll(window: &Window, position: LogicalPosition, delta_x: f32, delta_y: f32) {
+    send_mouse_move(window, position);
+    window.dispatch_event(WindowEvent::PointerScrolled { position, delta_x, delta_y });
+}
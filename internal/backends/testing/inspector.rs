@@ -0,0 +1,247 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Inspecting the elements of a running application, like the live-preview does for `.slint`
+//! files: With the inspector enabled, clicking while holding control and shift selects the element
+//! under the mouse cursor instead of clicking it, and a right click logs the outline of all the
+//! elements in the window with the selected one marked.
+//!
+//! Like in the live-preview, clicking again at the same spot selects the element behind the selected
+//! one. The selected element gets logged with its properties, unless the application passes it to
+//! its own tooling with [`set_selection_handler`]. The selection rectangle and the property editor
+//! of the live-preview are not available here, as they need the element tree of the Slint compiler.
+
+use i_slint_core::api::LogicalPosition;
+use i_slint_core::debug_log;
+use i_slint_core::input::MouseEvent;
+use i_slint_core::item_tree::ItemTreeRc;
+use i_slint_core::platform::{PointerEventButton, WindowAdapter};
+use i_slint_core::window::WindowInner;
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+use crate::{ElementHandle, ElementRoot};
+
+struct RootWrapper(ItemTreeRc);
+
+impl ElementRoot for RootWrapper {
+    fn item_tree(&self) -> ItemTreeRc {
+        self.0.clone()
+    }
+}
+
+impl super::Sealed for RootWrapper {}
+
+type WindowShownHook = Box<dyn FnMut(&Rc<dyn WindowAdapter>)>;
+type SelectionHandler = Box<dyn FnMut(&ElementHandle)>;
+
+thread_local! {
+    static SELECTION_HANDLER: RefCell<Option<SelectionHandler>> = Default::default();
+}
+
+/// Sets the function that gets called with the element selected in any window, instead of
+/// logging it. Pass `None` to log the selected elements again.
+pub fn set_selection_handler(handler: Option<impl FnMut(&ElementHandle) + 'static>) {
+    SELECTION_HANDLER.set(handler.map(|h| Box::new(h) as SelectionHandler));
+}
+
+fn contains(element: &ElementHandle, position: LogicalPosition) -> bool {
+    let origin = element.absolute_position();
+    let size = element.size();
+    position.x >= origin.x
+        && position.y >= origin.y
+        && position.x < origin.x + size.width
+        && position.y < origin.y + size.height
+}
+
+/// Returns all elements at the `position` in window coordinates, starting with the one that is
+/// drawn above all others there, like the selection stack of the live-preview.
+pub fn elements_at(root: &impl ElementRoot, position: LogicalPosition) -> Vec<ElementHandle> {
+    let root = root.root_element();
+    let mut found = Vec::new();
+    if contains(&root, position) {
+        found.push(root.clone());
+    }
+    root.visit_descendants(|element| {
+        if contains(&element, position) {
+            found.push(element);
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    found.reverse();
+    found
+}
+
+/// Returns the element at the `position` in window coordinates that is drawn above all other
+/// elements there.
+pub fn element_at(root: &impl ElementRoot, position: LogicalPosition) -> Option<ElementHandle> {
+    elements_at(root, position).into_iter().next()
+}
+
+/// Returns the element to select when clicking at the `position` in window coordinates: The
+/// element behind the `selected` one if that is at the position, or the topmost element otherwise.
+pub fn select_at(
+    root: &impl ElementRoot,
+    position: LogicalPosition,
+    selected: Option<&ElementHandle>,
+) -> Option<ElementHandle> {
+    let stack = elements_at(root, position);
+    let next = selected
+        .and_then(|selected| stack.iter().position(|e| e.is_same_element(selected)))
+        .map_or(0, |index| index + 1);
+    stack.get(next).or(stack.first()).cloned()
+}
+
+fn label(element: &ElementHandle) -> String {
+    let type_name = element.type_name().unwrap_or_default();
+    match element.id() {
+        Some(id) if !id.is_empty() => format!("{id} := {type_name}"),
+        _ => type_name.to_string(),
+    }
+}
+
+/// Returns a description of the `element` with its type, geometry and accessible properties.
+pub fn describe(element: &ElementHandle) -> String {
+    let mut lines = vec![label(element)];
+    let bases = element.bases().map(|bases| bases.collect::<Vec<_>>()).unwrap_or_default();
+    if !bases.is_empty() {
+        lines.push(format!("  inherits: {}", bases.join(", ")));
+    }
+    let position = element.absolute_position();
+    let size = element.size();
+    lines.push(format!("  absolute-position: {}px, {}px", position.x, position.y));
+    lines.push(format!("  size: {}px x {}px", size.width, size.height));
+    lines.push(format!("  opacity: {}", element.computed_opacity()));
+
+    let strings = [
+        ("accessible-label", element.accessible_label()),
+        ("accessible-value", element.accessible_value()),
+        ("accessible-description", element.accessible_description()),
+        ("accessible-placeholder-text", element.accessible_placeholder_text()),
+    ];
+    for (name, value) in strings {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            lines.push(format!("  {name}: {value:?}"));
+        }
+    }
+    if let Some(role) = element.accessible_role().filter(|r| *r != crate::AccessibleRole::None) {
+        lines.push(format!("  accessible-role: {role:?}"));
+    }
+    let flags = [
+        ("accessible-enabled", element.accessible_enabled()),
+        ("accessible-checked", element.accessible_checked()),
+        ("accessible-expanded", element.accessible_expanded()),
+        ("accessible-read-only", element.accessible_read_only()),
+    ];
+    for (name, value) in flags {
+        if let Some(value) = value {
+            lines.push(format!("  {name}: {value}"));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Returns the outline of all visible elements below the `root`, one element per line and
+/// indented by their depth. The line of the `selected` element starts with `>`.
+pub fn outline(root: &impl ElementRoot, selected: Option<&ElementHandle>) -> String {
+    let root = root.root_element();
+    let root_depth = root.depth();
+    let line = |element: &ElementHandle, depth: usize| {
+        let marker = if selected.is_some_and(|s| s.is_same_element(element)) { ">" } else { " " };
+        format!("{marker} {}{}", "  ".repeat(depth), label(element))
+    };
+    let mut lines = vec![line(&root, 0)];
+    root.visit_descendants(|element| {
+        lines.push(line(&element, element.depth().saturating_sub(root_depth)));
+        ControlFlow::<()>::Continue(())
+    });
+    lines.join("\n")
+}
+
+fn selection_changed(element: &ElementHandle) {
+    let handled = SELECTION_HANDLER
+        .with_borrow_mut(|handler| handler.as_mut().map(|handler| handler(element)).is_some());
+    if !handled {
+        debug_log!("{}", describe(element));
+    }
+}
+
+fn inspect_mouse_event(
+    item_tree: ItemTreeRc,
+    event: &MouseEvent,
+    selected: &RefCell<Option<ElementHandle>>,
+) -> bool {
+    let root = RootWrapper(item_tree);
+    match event {
+        MouseEvent::Pressed { position, button: PointerEventButton::Left, .. } => {
+            let position = i_slint_core::lengths::logical_position_to_api(*position);
+            let previous = selected.take().filter(|e| e.is_valid());
+            match select_at(&root, position, previous.as_ref()) {
+                Some(element) => {
+                    selection_changed(&element);
+                    *selected.borrow_mut() = Some(element);
+                }
+                None => debug_log!("No element at {}px, {}px", position.x, position.y),
+            }
+            true
+        }
+        MouseEvent::Pressed { button: PointerEventButton::Right, .. } => {
+            let selected = selected.borrow().clone().filter(|e| e.is_valid());
+            debug_log!("{}", outline(&root, selected.as_ref()));
+            true
+        }
+        MouseEvent::Released {
+            button: PointerEventButton::Left | PointerEventButton::Right,
+            ..
+        } => true,
+        _ => false,
+    }
+}
+
+fn install(adapter: &Rc<dyn WindowAdapter>) {
+    let window_adapter = Rc::downgrade(adapter);
+    let selected = RefCell::new(None);
+    WindowInner::from_pub(adapter.window()).set_mouse_input_filter(Some(Box::new(
+        move |event, modifiers| {
+            if !(modifiers.control && modifiers.shift) {
+                return false;
+            }
+            let Some(adapter) = window_adapter.upgrade() else {
+                return false;
+            };
+            inspect_mouse_event(
+                WindowInner::from_pub(adapter.window()).component(),
+                event,
+                &selected,
+            )
+        },
+    )));
+    debug_log!(
+        "Slint inspector: <ctrl+shift+click> an element to select it, <ctrl+shift+right-click> for the outline"
+    );
+}
+
+/// Enables the inspector for all windows shown from now on, if the `SLINT_INSPECT` environment
+/// variable is set and this is a debug build. The element ids and type names require the debug
+/// info that the Slint compiler generates when `SLINT_EMIT_DEBUG_INFO=1` is set at build time.
+pub fn init() {
+    if !cfg!(debug_assertions) || std::env::var_os("SLINT_INSPECT").is_none() {
+        return;
+    }
+
+    // Keep calling the hook that was set before, like the one of system testing
+    let previous_hook: Rc<RefCell<Option<WindowShownHook>>> = Default::default();
+    let hook: WindowShownHook = Box::new({
+        let previous_hook = previous_hook.clone();
+        move |adapter| {
+            if let Some(hook) = previous_hook.borrow_mut().as_mut() {
+                hook(adapter);
+            }
+            install(adapter);
+        }
+    });
+    if let Ok(previous) = i_slint_core::context::set_window_shown_hook(Some(hook)) {
+        *previous_hook.borrow_mut() = previous;
+    }
+}
@@ -6,6 +6,8 @@
 
 mod search_api;
 pub use search_api::*;
+mod events;
+pub use events::*;
 #[cfg(feature = "internal")]
 mod internal_tests;
 #[cfg(feature = "internal")]
@@ -15,6 +17,14 @@
 pub use testing_backend::*;
 #[cfg(all(feature = "ffi", not(test)))]
 mod ffi;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+#[cfg(feature = "screenshots")]
+mod screenshots;
+#[cfg(feature = "screenshots")]
+pub use screenshots::{
+    assert_screenshot, assert_screenshot_with_tolerance, Tolerance, UPDATE_REFERENCES_ENV_VAR,
+};
 #[cfg(feature = "system-testing")]
 pub mod systest;
 
@@ -60,6 +70,16 @@ pub fn init_integration_test_with_system_time() {
     .expect("platform already initialized");
 }
 
+/// Initialize the testing backend with support for simple event loop, like
+/// [`init_integration_test_with_mock_time()`], but with windows that get rendered by the software
+/// renderer, so that [`assert_screenshot()`] can take screenshots of them.
+/// This function can only be called once per process.
+#[cfg(feature = "screenshots")]
+pub fn init_integration_test_with_screenshots() {
+    i_slint_core::platform::set_platform(Box::new(screenshots::HeadlessBackend::new(true)))
+        .expect("platform already initialized");
+}
+
 /// Advance the simulated mock time by the specified duration. Use in combination with
 /// [`init_integration_test_with_mock_time()`] or [`init_no_event_loop()`].
 #[cfg(not(feature = "internal"))]
@@ -0,0 +1,270 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Rendering windows without a display with the software renderer, and comparing the result
+//! with reference images.
+
+use std::cell::Cell;
+use std::path::Path;
+use std::rc::{Rc, Weak};
+
+use i_slint_core::api::{PhysicalSize, Window, WindowSize};
+use i_slint_core::graphics::{Rgba8Pixel, SharedPixelBuffer};
+use i_slint_core::platform::{Platform, PlatformError};
+use i_slint_core::renderer::Renderer;
+use i_slint_core::software_renderer::{
+    PremultipliedRgbaColor, RepaintBufferType, SoftwareRenderer,
+};
+use i_slint_core::window::{WindowAdapter, WindowAdapterInternal, WindowInner};
+
+use crate::testing_backend::{TestingBackend, TestingBackendOptions};
+
+/// When this environment variable is set, [`assert_screenshot()`] writes the screenshots as the
+/// new reference images instead of comparing them.
+pub const UPDATE_REFERENCES_ENV_VAR: &str = "SLINT_UPDATE_SCREENSHOTS";
+
+/// The testing backend, with windows that render with the software renderer
+pub(crate) struct HeadlessBackend(TestingBackend);
+
+impl HeadlessBackend {
+    /// With `mock_time`, the time only advances with [`crate::mock_elapsed_time()`]
+    pub(crate) fn new(mock_time: bool) -> Self {
+        Self(TestingBackend::new(TestingBackendOptions { mock_time, threading: true }))
+    }
+}
+
+impl Platform for HeadlessBackend {
+    fn create_window_adapter(&self) -> Result<Rc<dyn WindowAdapter>, PlatformError> {
+        Ok(HeadlessWindow::new())
+    }
+
+    fn duration_since_start(&self) -> core::time::Duration {
+        self.0.duration_since_start()
+    }
+
+    fn set_clipboard_text(&self, text: &str, clipboard: i_slint_core::platform::Clipboard) {
+        self.0.set_clipboard_text(text, clipboard)
+    }
+
+    fn clipboard_text(&self, clipboard: i_slint_core::platform::Clipboard) -> Option<String> {
+        self.0.clipboard_text(clipboard)
+    }
+
+    fn run_event_loop(&self) -> Result<(), PlatformError> {
+        self.0.run_event_loop()
+    }
+
+    fn new_event_loop_proxy(&self) -> Option<Box<dyn i_slint_core::platform::EventLoopProxy>> {
+        self.0.new_event_loop_proxy()
+    }
+}
+
+/// A window that is not shown anywhere, but rendered into memory with [`HeadlessWindow::render()`].
+/// Without a size set, it takes the preferred size of its component.
+pub(crate) struct HeadlessWindow {
+    window: Window,
+    renderer: SoftwareRenderer,
+    size: Cell<PhysicalSize>,
+}
+
+impl HeadlessWindow {
+    fn new() -> Rc<Self> {
+        Rc::new_cyclic(|self_weak: &Weak<Self>| Self {
+            window: Window::new(self_weak.clone()),
+            renderer: SoftwareRenderer::new_with_repaint_buffer_type(RepaintBufferType::NewBuffer),
+            size: Default::default(),
+        })
+    }
+
+    /// Render the whole window, keeping the transparency of its background
+    fn render(&self) -> SharedPixelBuffer<Rgba8Pixel> {
+        let size = self.size.get();
+        let mut buffer =
+            vec![PremultipliedRgbaColor::default(); size.width as usize * size.height as usize];
+        self.renderer.render(&mut buffer, size.width as usize);
+
+        let mut pixels = SharedPixelBuffer::<Rgba8Pixel>::new(size.width, size.height);
+        for (pixel, p) in pixels.make_mut_slice().iter_mut().zip(buffer) {
+            let demultiply = |c: u8| match p.alpha {
+                0 => 0,
+                a => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+            };
+            *pixel = Rgba8Pixel::new(
+                demultiply(p.red),
+                demultiply(p.green),
+                demultiply(p.blue),
+                p.alpha,
+            );
+        }
+        pixels
+    }
+}
+
+impl WindowAdapterInternal for HeadlessWindow {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl WindowAdapter for HeadlessWindow {
+    fn window(&self) -> &Window {
+        &self.window
+    }
+
+    fn size(&self) -> PhysicalSize {
+        self.size.get()
+    }
+
+    fn set_size(&self, size: WindowSize) {
+        let scale_factor = self.window.scale_factor();
+        self.size.set(size.to_physical(scale_factor));
+        self.window.dispatch_event(i_slint_core::platform::WindowEvent::Resized {
+            size: size.to_logical(scale_factor),
+        });
+    }
+
+    fn renderer(&self) -> &dyn Renderer {
+        &self.renderer
+    }
+
+    fn update_window_properties(&self, properties: i_slint_core::window::WindowProperties<'_>) {
+        if self.size.get().width == 0 {
+            let c = properties.layout_constraints();
+            self.size.set(c.preferred.to_physical(self.window.scale_factor()));
+        }
+    }
+
+    fn internal(&self, _: i_slint_core::InternalToken) -> Option<&dyn WindowAdapterInternal> {
+        Some(self)
+    }
+}
+
+/// Render `window`. Windows created by the [`HeadlessBackend`] keep the transparency of their
+/// background, others are rendered with [`Window::take_snapshot()`].
+fn render(window: &Window) -> Result<SharedPixelBuffer<Rgba8Pixel>, PlatformError> {
+    let window_adapter = WindowInner::from_pub(window).window_adapter();
+    match window_adapter
+        .internal(i_slint_core::InternalToken)
+        .and_then(|w| w.as_any().downcast_ref::<HeadlessWindow>())
+    {
+        Some(headless_window) => Ok(headless_window.render()),
+        None => window.take_snapshot(),
+    }
+}
+
+/// How much a screenshot may differ from its reference image
+#[derive(Clone, Debug)]
+pub struct Tolerance {
+    /// The largest difference of a pixel that is not counted as differing, as distance of the
+    /// red, green, blue and alpha values
+    pub max_color_difference: f32,
+    /// How many pixels may differ
+    pub max_differing_pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self { max_color_difference: 3.0, max_differing_pixels: 0 }
+    }
+}
+
+fn color_difference(a: &Rgba8Pixel, b: &Rgba8Pixel) -> f32 {
+    [(a.r, b.r), (a.g, b.g), (a.b, b.b), (a.a, b.a)]
+        .iter()
+        .map(|(a, b)| (*a as f32 - *b as f32).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// Compare a screenshot with its reference image, the error describes how they differ
+fn compare(
+    screenshot: &SharedPixelBuffer<Rgba8Pixel>,
+    reference: &SharedPixelBuffer<Rgba8Pixel>,
+    tolerance: &Tolerance,
+) -> Result<(), String> {
+    if screenshot.size() != reference.size() {
+        return Err(format!(
+            "The screenshot is {}x{} pixels large, the reference image {}x{}",
+            screenshot.width(),
+            screenshot.height(),
+            reference.width(),
+            reference.height()
+        ));
+    }
+
+    let differences = screenshot
+        .as_slice()
+        .iter()
+        .zip(reference.as_slice())
+        .map(|(a, b)| color_difference(a, b))
+        .filter(|d| *d > tolerance.max_color_difference)
+        .collect::<Vec<_>>();
+    if differences.len() > tolerance.max_differing_pixels {
+        let largest = differences.iter().copied().fold(0.0, f32::max);
+        return Err(format!("{} pixels differ, by up to {largest:.1}", differences.len()));
+    }
+    Ok(())
+}
+
+fn load_png(path: &Path) -> Result<SharedPixelBuffer<Rgba8Pixel>, String> {
+    let image = image::open(path).map_err(|e| format!("{}: {e}", path.display()))?.into_rgba8();
+    Ok(SharedPixelBuffer::<Rgba8Pixel>::clone_from_slice(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+    ))
+}
+
+fn save_png(pixels: &SharedPixelBuffer<Rgba8Pixel>, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+    }
+    image::save_buffer(
+        path,
+        pixels.as_bytes(),
+        pixels.width(),
+        pixels.height(),
+        image::ColorType::Rgba8,
+    )
+    .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Assert that `window` looks like the PNG image at `reference`.
+///
+/// When they differ, the screenshot is saved next to the reference image, with `.actual.png`
+/// as extension. Set the [`UPDATE_REFERENCES_ENV_VAR`] environment variable to write the
+/// screenshots as new reference images.
+#[track_caller]
+pub fn assert_screenshot(window: &Window, reference: impl AsRef<Path>) {
+    assert_screenshot_with_tolerance(window, reference, &Tolerance::default())
+}
+
+/// Like [`assert_screenshot()`], with a tolerance for rendering differences
+#[track_caller]
+pub fn assert_screenshot_with_tolerance(
+    window: &Window,
+    reference: impl AsRef<Path>,
+    tolerance: &Tolerance,
+) {
+    let reference = reference.as_ref();
+    let screenshot = render(window).unwrap_or_else(|e| panic!("Can not render the window: {e}"));
+
+    if std::env::var_os(UPDATE_REFERENCES_ENV_VAR).is_some() {
+        if let Err(e) = save_png(&screenshot, reference) {
+            panic!("Can not write the reference image: {e}");
+        }
+        return;
+    }
+
+    let expected = load_png(reference).unwrap_or_else(|e| {
+        panic!("Can not load the reference image: {e}. Set {UPDATE_REFERENCES_ENV_VAR}=1 to create it.")
+    });
+    if let Err(e) = compare(&screenshot, &expected, tolerance) {
+        let actual = reference.with_extension("actual.png");
+        let saved = match save_png(&screenshot, &actual) {
+            Ok(()) => format!("The screenshot is saved as {}", actual.display()),
+            Err(e) => format!("The screenshot can not be saved: {e}"),
+        };
+        panic!("The window does not look like {}: {e}. {saved}", reference.display());
+    }
+}
@@ -255,6 +255,20 @@ fn collect_elements(item: ItemRc) -> impl Iterator<Item = ElementHandle> {
             .map(move |element_index| ElementHandle { item: item.downgrade(), element_index })
     }
 
+    /// The number of items above the item of this element, across components
+    #[cfg(feature = "inspector")]
+    pub(crate) fn depth(&self) -> usize {
+        self.item.upgrade().map_or(0, |item| {
+            core::iter::successors(item.parent_item(), |item| item.parent_item()).count()
+        })
+    }
+
+    /// Whether both handles refer to the same element
+    #[cfg(feature = "inspector")]
+    pub(crate) fn is_same_element(&self, other: &ElementHandle) -> bool {
+        self.item == other.item && self.element_index == other.element_index
+    }
+
     /// Visit all descendants of this element and call the visitor to each of them, until the visitor returns [`ControlFlow::Break`].
     /// When the visitor breaks, the function returns the value. If it doesn't break, the function returns None.
     pub fn visit_descendants<R>(
@@ -0,0 +1,76 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+#![cfg(feature = "inspector")]
+
+use i_slint_backend_testing::inspector;
+use slint::LogicalPosition;
+
+#[test]
+fn test_inspect() {
+    i_slint_backend_testing::init_no_event_loop();
+
+    slint::slint! {
+        export component App inherits Window {
+            width: 100px;
+            height: 100px;
+            panel := Rectangle {
+                x: 10px;
+                y: 10px;
+                width: 50px;
+                height: 50px;
+                label := Text {
+                    x: 5px;
+                    y: 5px;
+                    width: 20px;
+                    height: 20px;
+                    text: "Hello";
+                }
+            }
+        }
+    }
+
+    let app = App::new().unwrap();
+
+    let element = inspector::element_at(&app, LogicalPosition::new(20., 20.)).unwrap();
+    assert_eq!(element.id().unwrap(), "App::label");
+    let element = inspector::element_at(&app, LogicalPosition::new(50., 50.)).unwrap();
+    assert_eq!(element.id().unwrap(), "App::panel");
+    let element = inspector::element_at(&app, LogicalPosition::new(90., 90.)).unwrap();
+    assert_eq!(element.type_name().unwrap(), "Window");
+
+    let description =
+        inspector::describe(&inspector::element_at(&app, LogicalPosition::new(20., 20.)).unwrap());
+    assert!(description.starts_with("App::label := Text"));
+    assert!(description.contains("absolute-position: 15px, 15px"));
+    assert!(description.contains("size: 20px x 20px"));
+    assert!(description.contains("accessible-label: \"Hello\""));
+
+    // Clicking again at the same spot selects the element behind, down to the window
+    let position = LogicalPosition::new(20., 20.);
+    let selected = inspector::select_at(&app, position, None).unwrap();
+    assert_eq!(selected.id().unwrap(), "App::label");
+    let selected = inspector::select_at(&app, position, Some(&selected)).unwrap();
+    assert_eq!(selected.id().unwrap(), "App::panel");
+    let behind = inspector::select_at(&app, position, Some(&selected)).unwrap();
+    assert_eq!(behind.type_name().unwrap(), "Window");
+    let top = inspector::select_at(&app, position, Some(&behind)).unwrap();
+    assert_eq!(top.id().unwrap(), "App::label");
+    // A selection elsewhere starts over with the topmost element
+    let selected = inspector::select_at(&app, LogicalPosition::new(50., 50.), Some(&top)).unwrap();
+    assert_eq!(selected.id().unwrap(), "App::panel");
+
+    let outline = inspector::outline(&app, None);
+    let lines = outline.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "  App::root := Window");
+    let panel = lines.iter().position(|l| l.trim() == "App::panel := Rectangle").unwrap();
+    let label = lines.iter().position(|l| l.trim() == "App::label := Text").unwrap();
+    assert!(panel < label);
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    assert!(indent(lines[panel]) < indent(lines[label]));
+
+    let outline = inspector::outline(&app, Some(&selected));
+    let marked = outline.lines().filter(|l| l.starts_with('>')).collect::<Vec<_>>();
+    assert_eq!(marked.len(), 1);
+    assert_eq!(marked[0].trim_start_matches('>').trim(), "App::panel := Rectangle");
+}
@@ -0,0 +1,60 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+#![cfg(feature = "screenshots")]
+
+use i_slint_backend_testing::{
+    assert_screenshot, assert_screenshot_with_tolerance, send_mouse_click, Tolerance,
+    UPDATE_REFERENCES_ENV_VAR,
+};
+use slint::platform::PointerEventButton;
+use slint::{LogicalPosition, LogicalSize};
+
+#[test]
+fn test_screenshots() {
+    i_slint_backend_testing::init_integration_test_with_screenshots();
+
+    slint::slint! {
+        export component App inherits Window {
+            out property <int> click-count: 0;
+            background: transparent;
+            Rectangle {
+                x: 0; y: 0; width: 10px; height: 10px;
+                background: red;
+            }
+            Rectangle {
+                x: 10px; y: 0; width: 10px; height: 10px;
+                background: root.click-count > 0 ? green : blue;
+                TouchArea {
+                    clicked => { root.click-count += 1; }
+                }
+            }
+        }
+    }
+
+    let app = App::new().unwrap();
+    app.window().set_size(LogicalSize::new(20., 10.));
+
+    let reference = std::env::temp_dir().join("slint-testing-screenshot.png");
+    std::env::set_var(UPDATE_REFERENCES_ENV_VAR, "1");
+    assert_screenshot(app.window(), &reference);
+    std::env::remove_var(UPDATE_REFERENCES_ENV_VAR);
+    assert!(reference.exists());
+    assert_screenshot(app.window(), &reference);
+
+    send_mouse_click(app.window(), LogicalPosition::new(5., 5.), PointerEventButton::Left);
+    assert_eq!(app.get_click_count(), 0);
+    send_mouse_click(app.window(), LogicalPosition::new(15., 5.), PointerEventButton::Left);
+    assert_eq!(app.get_click_count(), 1);
+
+    // The second rectangle is green now
+    let actual = reference.with_extension("actual.png");
+    let check = std::panic::AssertUnwindSafe(|| assert_screenshot(app.window(), &reference));
+    assert!(std::panic::catch_unwind(check).is_err());
+    assert!(actual.exists());
+    let tolerance = Tolerance { max_differing_pixels: 100, ..Default::default() };
+    assert_screenshot_with_tolerance(app.window(), &reference, &tolerance);
+
+    std::fs::remove_file(reference).unwrap();
+    std::fs::remove_file(actual).unwrap();
+}
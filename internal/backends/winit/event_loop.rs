@@ -480,6 +480,10 @@ macro_rules! winit_key_to_char {
                     delta_y,
                 });
             }
+            WindowEvent::PinchGesture { delta, .. } if delta.is_finite() => {
+                // A magnification of 1% counts as much as 4 pixels of the wheel
+                runtime_window.process_pinch_gesture(self.cursor_pos, (delta * 400.) as _);
+            }
             WindowEvent::MouseInput { state, button, .. } => {
                 let button = match button {
                     winit::event::MouseButton::Left => PointerEventButton::Left,
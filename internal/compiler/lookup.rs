@@ -17,7 +17,7 @@
 use smol_str::{SmolStr, ToSmolStr};
 use std::cell::RefCell;
 
-mod named_colors;
+pub mod named_colors;
 
 /// Contains information which allow to lookup identifier in expressions
 pub struct LookupCtx<'a> {
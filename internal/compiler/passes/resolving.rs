@@ -1499,7 +1499,9 @@ fn continue_lookup_within_element(
                 }
                 ElementType::Component(c) => format!("Element '{}'", c.id),
                 ElementType::Builtin(b) => format!("Element '{}'", b.name),
-                ElementType::Native(_) => unreachable!("the native pass comes later"),
+                // Only when resolving against a compiled component, like the interpreter does
+                // for expressions typed in by the user
+                ElementType::Native(n) => format!("Element '{}'", n.class_name),
                 ElementType::Error => {
                     assert!(ctx.diag.has_errors());
                     return;
@@ -21,6 +21,8 @@ pub(crate) struct SlintContextInner {
     pub(crate) translations_dirty: core::pin::Pin<Box<Property<usize>>>,
     pub(crate) translations_bundle_languages:
         core::cell::RefCell<Option<alloc::vec::Vec<&'static str>>>,
+    /// Whether translated strings get pseudo-localized, see [`crate::translations::set_pseudo_localization`]
+    pub(crate) pseudo_localization: core::cell::Cell<bool>,
     pub(crate) window_shown_hook:
         core::cell::RefCell<Option<Box<dyn FnMut(&Rc<dyn crate::platform::WindowAdapter>)>>>,
     #[cfg(all(unix, not(target_os = "macos")))]
@@ -41,6 +43,7 @@ pub fn new(platform: Box<dyn Platform + 'static>) -> Self {
             window_count: 0.into(),
             translations_dirty: Box::pin(Property::new_named(0, "SlintContext::translations")),
             translations_bundle_languages: Default::default(),
+            pseudo_localization: Default::default(),
             window_shown_hook: Default::default(),
             #[cfg(all(unix, not(target_os = "macos")))]
             xdg_app_id: Default::default(),
@@ -76,6 +76,20 @@ pub fn translate(&mut self, vec: LogicalVector) {
         }
     }
 
+    /// Scale the position by the given factor
+    fn scale(&mut self, factor: f32) {
+        let pos = match self {
+            MouseEvent::Pressed { position, .. } => Some(position),
+            MouseEvent::Released { position, .. } => Some(position),
+            MouseEvent::Moved { position } => Some(position),
+            MouseEvent::Wheel { position, .. } => Some(position),
+            MouseEvent::Exit => None,
+        };
+        if let Some(pos) = pos {
+            *pos = (pos.cast::<f32>() * factor).cast();
+        }
+    }
+
     /// Set the click count of the pressed or released event
     fn set_click_count(&mut self, count: u8) {
         match self {
@@ -195,6 +209,12 @@ pub(crate) struct InternalKeyboardModifierState {
 }
 
 impl InternalKeyboardModifierState {
+    /// The same state, with the control key pressed
+    pub(crate) fn with_control(mut self) -> Self {
+        self.left_control = true;
+        self
+    }
+
     /// Updates a flag of the modifiers if the key of the given text is pressed.
     /// Returns an updated modifier if detected; None otherwise;
     pub(crate) fn state_update(mut self, pressed: bool, text: &SharedString) -> Option<Self> {
@@ -546,6 +566,9 @@ pub struct MouseInputState {
     grabbed: bool,
     delayed: Option<(crate::timers::Timer, MouseEvent)>,
     delayed_exit_items: Vec<ItemWeak>,
+    /// true if an item of the stack zooms its children, so that the position of the events
+    /// for the items above it need to be scaled
+    zoomed: bool,
 }
 
 impl MouseInputState {
@@ -560,6 +583,12 @@ pub fn top_item_including_delayed(&self) -> Option<ItemRc> {
     }
 }
 
+/// The factor by which the children of `item` are zoomed, see `ComponentContainer::zoom`
+fn children_zoom(item: &ItemRc) -> f32 {
+    item.downcast::<crate::items::ComponentContainer>()
+        .map_or(1., |container| container.as_pin_ref().effective_zoom())
+}
+
 /// Try to handle the mouse grabber. Return None if the event has been handled, otherwise
 /// return the event that must be handled
 pub(crate) fn handle_mouse_grab(
@@ -574,6 +603,8 @@ pub(crate) fn handle_mouse_grab(
     let mut event = mouse_event;
     let mut intercept = false;
     let mut invalid = false;
+    let zoomed = mouse_input_state.zoomed;
+    let mut zoom = 1.;
 
     event.translate(-mouse_input_state.offset.to_vector());
 
@@ -591,6 +622,12 @@ pub(crate) fn handle_mouse_grab(
             item.borrow().as_ref().input_event(MouseEvent::Exit, window_adapter, &item);
             return false;
         }
+        if zoomed {
+            if zoom != 1. {
+                event.scale(1. / zoom);
+            }
+            zoom = children_zoom(&item);
+        }
         let g = item.geometry();
         event.translate(-g.origin.to_vector());
 
@@ -642,8 +679,15 @@ pub(crate) fn send_exit_events(
     }
 
     let mut clipped = false;
+    let mut zoom = 1.;
     for (idx, it) in old_input_state.item_stack.iter().enumerate() {
         let Some(item) = it.0.upgrade() else { break };
+        if old_input_state.zoomed {
+            if let Some(p) = pos.as_mut().filter(|_| zoom != 1.) {
+                *p = (p.cast::<f32>() / zoom).cast();
+            }
+            zoom = children_zoom(&item);
+        }
         let g = item.geometry();
         let contains = pos.is_some_and(|p| g.contains(p));
         if let Some(p) = pos.as_mut() {
@@ -798,6 +842,11 @@ fn send_mouse_event_to_item(
 
     result.item_stack.push((item_rc.downgrade(), filter_result));
     if forward_to_children {
+        let zoom = children_zoom(&item_rc);
+        if zoom != 1. {
+            event_for_children.scale(1. / zoom);
+            result.zoomed = true;
+        }
         let mut actual_visitor =
             |component: &ItemTreeRc, index: u32, _: Pin<ItemRef>| -> VisitChildrenResult {
                 send_mouse_event_to_item(
@@ -181,6 +181,50 @@ pub fn is_empty(&self) -> bool {
     }
 }
 
+/// Called with every item that got rendered and the time the renderer spent on it,
+/// not counting its children. See [`set_item_render_profiler`].
+#[cfg(feature = "std")]
+pub type ItemRenderProfiler = Box<dyn FnMut(&ItemRc, core::time::Duration)>;
+
+#[cfg(feature = "std")]
+crate::thread_local!(static ITEM_RENDER_PROFILER: RefCell<Option<ItemRenderProfiler>> = RefCell::new(None));
+
+/// Install a profiler that gets told how long rendering each item took on this thread,
+/// or remove it again by passing `None`.
+///
+/// WARNING: this is not part of the public API
+#[cfg(feature = "std")]
+pub fn set_item_render_profiler(profiler: Option<ItemRenderProfiler>) {
+    ITEM_RENDER_PROFILER.with(|p| *p.borrow_mut() = profiler);
+}
+
+/// Render `item`, reporting the time spent to the item render profiler if there is one
+fn render_item(
+    item: Pin<ItemRef>,
+    renderer: &mut dyn ItemRenderer,
+    item_rc: &ItemRc,
+    size: LogicalSize,
+) -> RenderingResult {
+    #[cfg(feature = "std")]
+    if ITEM_RENDER_PROFILER.with(|p| p.borrow().is_some()) {
+        #[cfg(not(target_arch = "wasm32"))]
+        use std::time::Instant;
+        #[cfg(target_arch = "wasm32")]
+        use web_time::Instant;
+
+        let start = Instant::now();
+        let result = item.as_ref().render(&mut (renderer as &mut dyn ItemRenderer), item_rc, size);
+        let elapsed = start.elapsed();
+        ITEM_RENDER_PROFILER.with(|p| {
+            if let Some(profiler) = p.borrow_mut().as_mut() {
+                profiler(item_rc, elapsed);
+            }
+        });
+        return result;
+    }
+    item.as_ref().render(&mut (renderer as &mut dyn ItemRenderer), item_rc, size)
+}
+
 /// Renders the children of the item with the specified index into the renderer.
 pub fn render_item_children(
     renderer: &mut dyn ItemRenderer,
@@ -205,11 +249,7 @@ pub fn render_item_children(
                // HACK, the geometry of the box shadow does not include the shadow, because when the shadow is the root for repeated elements it would translate the children
                || ItemRef::downcast_pin::<BoxShadow>(item).is_some()
             {
-                item.as_ref().render(
-                    &mut (renderer as &mut dyn ItemRenderer),
-                    &item_rc,
-                    item_geometry.size,
-                )
+                render_item(item, renderer, &item_rc, item_geometry.size)
             } else {
                 RenderingResult::ContinueRenderingChildren
             };
@@ -469,6 +509,10 @@ fn translation(&self) -> LogicalVector {
         unimplemented!()
     }
     fn rotate(&mut self, angle_in_degrees: f32);
+    /// Scale all following drawing operations by the given factors, until the next call to
+    /// restore_state. Renderers that do not support scaling ignore it, see
+    /// [`ItemRendererFeatures::SUPPORTS_SCALING`].
+    fn scale(&mut self, _x_factor: f32, _y_factor: f32) {}
     /// Apply the opacity (between 0 and 1) for all following items until the next call to restore_state.
     fn apply_opacity(&mut self, opacity: f32);
 
@@ -528,6 +572,9 @@ fn metrics(&self) -> crate::graphics::rendering_metrics_collector::RenderingMetr
 pub trait ItemRendererFeatures {
     /// The renderer supports applying 2D transformations to items.
     const SUPPORTS_TRANSFORMATIONS: bool;
+    /// The renderer supports scaling items with [`ItemRenderer::scale`], even if it does not
+    /// support other transformations.
+    const SUPPORTS_SCALING: bool = Self::SUPPORTS_TRANSFORMATIONS;
 }
 
 /// After rendering an item, we cache the geometry and the transform it applies to
@@ -595,8 +642,10 @@ fn new<T: ItemRendererFeatures>(
             item_rc.bounding_rect(&geometry, window_adapter)
         });
 
-        if let Some(complex_child_transform) =
-            T::SUPPORTS_TRANSFORMATIONS.then(|| item_rc.children_transform()).flatten()
+        if let Some(complex_child_transform) = (T::SUPPORTS_TRANSFORMATIONS || T::SUPPORTS_SCALING)
+            .then(|| item_rc.children_transform())
+            .flatten()
+            .filter(|t| T::SUPPORTS_TRANSFORMATIONS || (t.m12 == 0. && t.m21 == 0.))
         {
             Self::ItemWithTransform {
                 bounding_rect,
@@ -1084,6 +1133,10 @@ fn rotate(&mut self, angle_in_degrees: f32) {
         self.actual_renderer.rotate(angle_in_degrees)
     }
 
+    fn scale(&mut self, x_factor: f32, y_factor: f32) {
+        self.actual_renderer.scale(x_factor, y_factor)
+    }
+
     fn apply_opacity(&mut self, opacity: f32) {
         self.actual_renderer.apply_opacity(opacity)
     }
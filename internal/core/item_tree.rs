@@ -797,6 +797,10 @@ pub fn visit_descendants<R>(
     /// Returns the transform to apply to children to map them into the local coordinate space of this item.
     /// Typically this is None, but rotation for example may return Some.
     pub fn children_transform(&self) -> Option<ItemTransform> {
+        if let Some(container) = self.downcast::<crate::items::ComponentContainer>() {
+            let zoom = container.as_pin_ref().effective_zoom();
+            return (zoom != 1.).then(|| ItemTransform::scale(zoom, zoom));
+        }
         self.downcast::<crate::items::Rotate>().map(|rotate_item| {
             let origin = euclid::Vector2D::<f32, crate::lengths::LogicalPx>::from_lengths(
                 rotate_item.as_pin_ref().rotation_origin_x().cast(),
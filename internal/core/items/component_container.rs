@@ -22,6 +22,7 @@
 #[cfg(feature = "rtti")]
 use crate::rtti::*;
 use crate::window::WindowAdapter;
+use crate::Coord;
 use alloc::boxed::Box;
 use alloc::rc::Rc;
 use const_field_offset::FieldOffsets;
@@ -39,6 +40,9 @@ pub struct ComponentContainer {
     pub height: Property<LogicalLength>,
     pub component_factory: Property<ComponentFactory>,
     pub has_component: Property<bool>,
+    /// The factor by which the embedded component is scaled when rendering
+    /// and for mouse events
+    pub zoom: Property<f32>,
 
     pub cached_rendering_data: CachedRenderingData,
 
@@ -51,6 +55,16 @@ pub struct ComponentContainer {
 }
 
 impl ComponentContainer {
+    /// The `zoom`, falling back to 1 for values that can not be used to scale
+    pub fn effective_zoom(self: Pin<&Self>) -> f32 {
+        let zoom = self.zoom();
+        if zoom.is_finite() && zoom > 0. {
+            zoom
+        } else {
+            1.
+        }
+    }
+
     pub fn ensure_updated(self: Pin<&Self>) {
         let factory = self
             .component_tracker
@@ -95,7 +109,7 @@ pub fn ensure_updated(self: Pin<&Self>) {
                         let self_pin = self_rc.borrow();
                         if let Some(self_cc) = crate::items::ItemRef::downcast_pin::<Self>(self_pin)
                         {
-                            return self_cc.width();
+                            return self_cc.width() / self_cc.effective_zoom();
                         }
                     }
                     Default::default()
@@ -106,7 +120,7 @@ pub fn ensure_updated(self: Pin<&Self>) {
                         let self_pin = self_rc.borrow();
                         if let Some(self_cc) = crate::items::ItemRef::downcast_pin::<Self>(self_pin)
                         {
-                            return self_cc.height();
+                            return self_cc.height() / self_cc.effective_zoom();
                         }
                     }
                     Default::default()
@@ -170,7 +184,18 @@ fn layout_info(
     ) -> LayoutInfo {
         self.ensure_updated();
         if let Some(rc) = self.item_tree.borrow().clone() {
-            vtable::VRc::borrow_pin(&rc).as_ref().layout_info(orientation)
+            let info = vtable::VRc::borrow_pin(&rc).as_ref().layout_info(orientation);
+            let zoom = self.effective_zoom();
+            if zoom == 1. {
+                return info;
+            }
+            let scale = |v: Coord| (v as f32 * zoom).min(Coord::MAX as f32) as Coord;
+            LayoutInfo {
+                min: scale(info.min),
+                max: scale(info.max),
+                preferred: scale(info.preferred),
+                ..info
+            }
         } else {
             Default::default()
         }
@@ -219,6 +244,10 @@ fn render(
         size: LogicalSize,
     ) -> RenderingResult {
         backend.draw_rectangle(self, item_rc, size, &self.cached_rendering_data);
+        let zoom = self.effective_zoom();
+        if zoom != 1. {
+            (*backend).scale(zoom, zoom);
+        }
         RenderingResult::ContinueRenderingChildren
     }
 
@@ -1399,6 +1399,7 @@ fn new(
                     LogicalPoint::default(),
                     (screen_size.cast() / scale_factor).cast(),
                 ),
+                scale_factor,
             },
             scale_factor,
             window,
@@ -1774,6 +1775,8 @@ struct RenderState {
     alpha: f32,
     offset: LogicalPoint,
     clip: LogicalRect,
+    /// The scale factor of the window, times the factors the renderer got scaled by
+    scale_factor: ScaleFactor,
 }
 
 impl<T: ProcessScene> crate::item_rendering::ItemRenderer for SceneBuilder<'_, T> {
@@ -2330,6 +2333,20 @@ fn rotate(&mut self, _angle_in_degrees: f32) {
         // TODO (#6068)
     }
 
+    fn scale(&mut self, x_factor: f32, _y_factor: f32) {
+        // Only uniform scaling is supported, by rendering with a larger scale factor
+        self.current_state.offset = (self.current_state.offset.cast::<f32>() / x_factor).cast();
+        self.current_state.clip = self
+            .current_state
+            .clip
+            .cast::<f32>()
+            .scale(1. / x_factor, 1. / x_factor)
+            .round_out()
+            .cast();
+        self.scale_factor = ScaleFactor::new(self.scale_factor.get() * x_factor);
+        self.current_state.scale_factor = self.scale_factor;
+    }
+
     fn apply_opacity(&mut self, opacity: f32) {
         self.current_state.alpha *= opacity;
     }
@@ -2340,6 +2357,7 @@ fn save_state(&mut self) {
 
     fn restore_state(&mut self) {
         self.current_state = self.state_stack.pop().unwrap();
+        self.scale_factor = self.current_state.scale_factor;
     }
 
     fn scale_factor(&self) -> f32 {
@@ -2446,4 +2464,5 @@ fn as_any(&mut self) -> Option<&mut dyn core::any::Any> {
 
 impl<T: ProcessScene> crate::item_rendering::ItemRendererFeatures for SceneBuilder<'_, T> {
     const SUPPORTS_TRANSFORMATIONS: bool = false;
+    const SUPPORTS_SCALING: bool = true;
 }
@@ -193,11 +193,96 @@ pub fn translate(
     let translated = if plural.is_empty() || n == 1 { original } else { plural };
     #[cfg(all(target_family = "unix", feature = "gettext-rs"))]
     let translated = translate_gettext(original, contextid, domain, n, plural);
-    use core::fmt::Write;
-    write!(output, "{}", formatter::format(&translated, &WithPlural(arguments, n))).unwrap();
+    write_translation(&mut output, &translated, &WithPlural(arguments, n));
     output
 }
 
+/// Format the `translated` string into `output`, pseudo-localized if enabled
+fn write_translation(
+    output: &mut SharedString,
+    translated: &str,
+    arguments: &(impl FormatArgs + ?Sized),
+) {
+    use core::fmt::Write;
+    if is_pseudo_localization_enabled() {
+        let pseudo_localized = pseudo_localize(translated);
+        write!(output, "[{}]", formatter::format(&pseudo_localized, arguments)).unwrap();
+    } else {
+        write!(output, "{}", formatter::format(translated, arguments)).unwrap();
+    }
+}
+
+/// Replace the ASCII letters of a format string with accented ones and make it longer by
+/// about a third, keeping the `{}` placeholders intact
+fn pseudo_localize(string: &str) -> alloc::string::String {
+    const LOWER: [char; 26] = [
+        'á', 'ƀ', 'ç', 'đ', 'é', 'ƒ', 'ĝ', 'ĥ', 'í', 'ĵ', 'ķ', 'ļ', 'ɱ', 'ñ', 'ó', 'þ', 'ǫ', 'ŕ',
+        'š', 'ţ', 'ú', 'ṽ', 'ŵ', 'ẋ', 'ý', 'ž',
+    ];
+    const UPPER: [char; 26] = [
+        'Á', 'Ɓ', 'Ç', 'Đ', 'É', 'Ƒ', 'Ĝ', 'Ĥ', 'Í', 'Ĵ', 'Ķ', 'Ļ', 'Ṁ', 'Ñ', 'Ó', 'Þ', 'Ǫ', 'Ŕ',
+        'Š', 'Ţ', 'Ú', 'Ṽ', 'Ŵ', 'Ẋ', 'Ý', 'Ž',
+    ];
+    let mut result = alloc::string::String::with_capacity(string.len() * 2);
+    let mut letters = 0_usize;
+    let mut in_placeholder = false;
+    let mut chars = string.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                result.push_str("{{");
+                chars.next();
+            }
+            '}' if !in_placeholder && chars.peek() == Some(&'}') => {
+                result.push_str("}}");
+                chars.next();
+            }
+            '{' => {
+                in_placeholder = true;
+                result.push(c);
+            }
+            '}' => {
+                in_placeholder = false;
+                result.push(c);
+            }
+            'a'..='z' if !in_placeholder => {
+                letters += 1;
+                result.push(LOWER[(c as u8 - b'a') as usize]);
+            }
+            'A'..='Z' if !in_placeholder => {
+                letters += 1;
+                result.push(UPPER[(c as u8 - b'A') as usize]);
+            }
+            c => result.push(c),
+        }
+    }
+    if letters > 0 {
+        result.push(' ');
+        result.extend(core::iter::repeat_n('~', letters.div_ceil(3)));
+    }
+    result
+}
+
+/// Returns whether translated strings get pseudo-localized, and make sure to register a
+/// dependency
+fn is_pseudo_localization_enabled() -> bool {
+    global_translation_property();
+    crate::context::GLOBAL_CONTEXT
+        .with(|ctx| ctx.get().is_some_and(|ctx| ctx.0.pseudo_localization.get()))
+}
+
+/// Enable or disable the pseudo-localization of all translated strings: Their letters get
+/// accented, they get longer and enclosed in brackets, which reveals strings that are not
+/// translated and texts that get cut off when translated to a more verbose language.
+pub fn set_pseudo_localization(enabled: bool) {
+    crate::context::GLOBAL_CONTEXT.with(|ctx| {
+        let Some(ctx) = ctx.get() else { return };
+        if ctx.0.pseudo_localization.replace(enabled) != enabled {
+            ctx.0.translations_dirty.mark_dirty();
+        }
+    })
+}
+
 #[cfg(all(target_family = "unix", feature = "gettext-rs"))]
 fn translate_gettext(
     string: &str,
@@ -290,8 +375,7 @@ pub fn translate_from_bundle(
     else {
         return output;
     };
-    use core::fmt::Write;
-    write!(output, "{}", formatter::format(translated, arguments)).unwrap();
+    write_translation(&mut output, translated, arguments);
     output
 }
 
@@ -315,8 +399,7 @@ pub fn translate_from_bundle_with_plural(
     else {
         return output;
     };
-    use core::fmt::Write;
-    write!(output, "{}", formatter::format(translated, &WithPlural(arguments, n))).unwrap();
+    write_translation(&mut output, translated, &WithPlural(arguments, n));
     output
 }
 
@@ -510,3 +593,18 @@ pub extern "C" fn slint_translate_select_bundled_translation(language: Slice<u8>
         select_bundled_translation(language).is_ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_pseudo_localize() {
+        assert_eq!(pseudo_localize("Hello"), "Ĥéļļó ~~");
+        assert_eq!(pseudo_localize("Save {} of {n}"), "Šáṽé {} óƒ {n} ~~");
+        assert_eq!(pseudo_localize("{{x}} 42"), "{{ẋ}} 42 ~");
+        assert_eq!(pseudo_localize("42"), "42");
+        assert_eq!(formatter::format(&pseudo_localize("Hi {0}"), &["Bob"]).to_string(), "Ĥí Bob ~");
+    }
+}
@@ -421,8 +421,15 @@ struct WindowPinnedFields {
     active: Property<bool>,
     #[pin]
     text_input_focused: Property<bool>,
+    /// Used instead of the color scheme of the platform, unless it is `Unknown`
+    #[pin]
+    color_scheme_override: Property<ColorScheme>,
 }
 
+/// A filter for the mouse events of a window, see [`WindowInner::set_mouse_input_filter`]
+#[cfg(feature = "inspector")]
+pub type MouseInputFilter = dyn Fn(&MouseEvent, crate::input::KeyboardModifiers) -> bool;
+
 /// Inner datastructure for the [`crate::api::Window`]
 pub struct WindowInner {
     window_adapter_weak: Weak<dyn WindowAdapter>,
@@ -453,6 +460,9 @@ pub struct WindowInner {
     had_popup_on_press: Cell<bool>,
     close_requested: Callback<(), CloseRequestResponse>,
     click_state: ClickState,
+    /// Sees the mouse events before the items, and can keep them from the items
+    #[cfg(feature = "inspector")]
+    mouse_input_filter: RefCell<Option<Rc<MouseInputFilter>>>,
     pub(crate) ctx: once_cell::unsync::Lazy<crate::SlintContext>,
 }
 
@@ -500,6 +510,10 @@ pub fn new(window_adapter_weak: Weak<dyn WindowAdapter>) -> Self {
                     false,
                     "i_slint_core::Window::text_input_focused",
                 ),
+                color_scheme_override: Property::new_named(
+                    ColorScheme::Unknown,
+                    "i_slint_core::Window::color_scheme_override",
+                ),
             }),
             maximized: Cell::new(false),
             minimized: Cell::new(false),
@@ -511,6 +525,8 @@ pub fn new(window_adapter_weak: Weak<dyn WindowAdapter>) -> Self {
             had_popup_on_press: Default::default(),
             close_requested: Default::default(),
             click_state: ClickState::default(),
+            #[cfg(feature = "inspector")]
+            mouse_input_filter: Default::default(),
             prevent_focus_change: Default::default(),
             // The ctx is lazy so that a Window can be initialized before the backend.
             // (for example in test_empty_window)
@@ -580,6 +596,14 @@ pub fn active_popups(&self) -> core::cell::Ref<'_, [PopupWindow]> {
     pub fn process_mouse_input(&self, mut event: MouseEvent) {
         crate::animations::update_animations();
 
+        #[cfg(feature = "inspector")]
+        {
+            let filter = self.mouse_input_filter.borrow().clone();
+            if filter.is_some_and(|filter| filter(&event, self.modifiers.get().into())) {
+                return;
+            }
+        }
+
         // handle multiple press release
         event = self.click_state.check_repeat(event, self.ctx.platform().click_interval());
 
@@ -711,6 +735,31 @@ pub fn process_mouse_input(&self, mut event: MouseEvent) {
         crate::properties::ChangeTracker::run_change_handlers();
     }
 
+    /// Set a filter that sees all mouse events before the items of the component, together
+    /// with the keyboard modifiers pressed at the time. When the filter returns true, the
+    /// event is not passed on to the items.
+    #[cfg(feature = "inspector")]
+    pub fn set_mouse_input_filter(&self, filter: Option<Box<MouseInputFilter>>) {
+        *self.mouse_input_filter.borrow_mut() = filter.map(Rc::from);
+    }
+
+    /// Receive a two-finger pinch gesture of a touchpad and pass it to the items of the
+    /// component.
+    ///
+    /// The gesture is delivered like a wheel event with the control key held, which is
+    /// how precision touchpads report pinching on Windows, so that items that zoom with
+    /// control and the wheel also zoom with a pinch on all platforms.
+    ///
+    /// Arguments:
+    /// * `position`: The position of the gesture in window logical coordinates.
+    /// * `delta_y`: The magnification in wheel pixels, positive to zoom in.
+    pub fn process_pinch_gesture(&self, position: LogicalPoint, delta_y: Coord) {
+        let modifiers = self.modifiers.get();
+        self.modifiers.set(modifiers.with_control());
+        self.process_mouse_input(MouseEvent::Wheel { position, delta_x: 0., delta_y });
+        self.modifiers.set(modifiers);
+    }
+
     /// Called by the input code's internal timer to send an event that was delayed
     pub(crate) fn process_delayed_event(&self) {
         self.mouse_input_state.set(crate::input::process_delayed_event(
@@ -1064,11 +1113,22 @@ pub fn hide(&self) -> Result<(), PlatformError> {
 
     /// returns the color theme used
     pub fn color_scheme(&self) -> ColorScheme {
+        let color_scheme_override =
+            self.pinned_fields.as_ref().project_ref().color_scheme_override.get();
+        if color_scheme_override != ColorScheme::Unknown {
+            return color_scheme_override;
+        }
         self.window_adapter()
             .internal(crate::InternalToken)
             .map_or(ColorScheme::Unknown, |x| x.color_scheme())
     }
 
+    /// Use `color_scheme` instead of the color scheme of the platform, or go back to the
+    /// one of the platform with `ColorScheme::Unknown`
+    pub fn set_color_scheme_override(&self, color_scheme: ColorScheme) {
+        self.pinned_fields.color_scheme_override.set(color_scheme)
+    }
+
     /// Return whether the platform supports native menu bars
     pub fn supports_native_menu_bar(&self) -> bool {
         self.window_adapter()
@@ -1757,9 +1817,7 @@ fn call(&self) -> CloseRequestResponse {
         handle: *const WindowAdapterRcOpaque,
     ) -> ColorScheme {
         let window_adapter = &*(handle as *const Rc<dyn WindowAdapter>);
-        window_adapter
-            .internal(crate::InternalToken)
-            .map_or(ColorScheme::Unknown, |x| x.color_scheme())
+        WindowInner::from_pub(window_adapter.window()).color_scheme()
     }
 
     /// Return whether the platform supports native menu bars
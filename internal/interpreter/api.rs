@@ -1543,6 +1543,18 @@ pub fn element_node_at_source_code_position(
     ) -> Vec<(i_slint_compiler::object_tree::ElementRc, usize)> {
         crate::highlight::element_node_at_source_code_position(&self.inner, path, offset)
     }
+
+    /// Evaluate the Slint `expression` in the scope of the root element of this component,
+    /// with the current values of the properties it uses. Returns the compile errors if the
+    /// expression is not valid.
+    ///
+    /// WARNING: this is not part of the public API
+    #[cfg(feature = "internal-highlight")]
+    pub fn evaluate_expression(&self, expression: &str) -> Result<Value, String> {
+        generativity::make_guard!(guard);
+        let comp = self.inner.unerase(guard);
+        crate::eval::eval_source_expression(comp.borrow_instance(), expression)
+    }
 }
 
 impl ComponentHandle for ComponentInstance {
@@ -1456,6 +1456,24 @@ fn make_callback_eval_closure(
     }
 }
 
+/// Like `make_callback_eval_closure`, but calls the callback hook before evaluating `expr`
+fn make_hooked_callback_eval_closure(
+    expr: Expression,
+    self_weak: &ErasedItemTreeBoxWeak,
+    name: SmolStr,
+    location: Option<i_slint_compiler::diagnostics::SourceLocation>,
+) -> impl Fn(&[Value]) -> Value {
+    let eval_closure = make_callback_eval_closure(expr, self_weak);
+    move |args| {
+        eval::call_callback_hook(&eval::CallbackInvocation {
+            name: &name,
+            arguments: args,
+            location: location.as_ref(),
+        });
+        eval_closure(args)
+    }
+}
+
 fn make_binding_eval_closure(
     expr: Expression,
     self_weak: &ErasedItemTreeBoxWeak,
@@ -1590,22 +1608,31 @@ pub fn instantiate(
             } else if let Type::Callback { .. } = property_type {
                 if !matches!(binding.expression, Expression::Invalid) {
                     let expr = binding.expression.clone();
+                    let make_closure = || -> Box<dyn Fn(&[Value]) -> Value> {
+                        if eval::has_callback_hook() {
+                            Box::new(make_hooked_callback_eval_closure(
+                                expr.clone(),
+                                &self_weak,
+                                prop_name.clone(),
+                                binding.span.clone(),
+                            ))
+                        } else {
+                            Box::new(make_callback_eval_closure(expr.clone(), &self_weak))
+                        }
+                    };
                     let description = description.clone();
                     if let Some(callback_offset) =
                         description.custom_callbacks.get(prop_name).filter(|_| is_root)
                     {
                         let callback = callback_offset.apply(instance_ref.as_ref());
-                        callback.set_handler(make_callback_eval_closure(expr, &self_weak));
+                        callback.set_handler(make_closure());
                     } else {
                         let item_within_component = &description.items[&elem.id];
                         let item = item_within_component.item_from_item_tree(instance_ref.as_ptr());
                         if let Some(callback) =
                             item_within_component.rtti.callbacks.get(prop_name.as_str())
                         {
-                            callback.set_handler(
-                                item,
-                                Box::new(make_callback_eval_closure(expr, &self_weak)),
-                            );
+                            callback.set_handler(item, make_closure());
                         } else {
                             panic!("unknown callback {prop_name}")
                         }
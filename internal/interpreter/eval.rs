@@ -5,12 +5,13 @@
 use crate::dynamic_item_tree::{CallbackHandler, InstanceRef};
 use core::pin::Pin;
 use corelib::graphics::{GradientStop, LinearGradientBrush, PathElement, RadialGradientBrush};
-use corelib::items::{ColorScheme, ItemRef, MenuEntry, PropertyAnimation};
+use corelib::items::{ItemRef, MenuEntry, PropertyAnimation};
 use corelib::menus::{Menu, MenuFromItemTree, MenuVTable};
 use corelib::model::{Model, ModelExt, ModelRc, VecModel};
 use corelib::rtti::AnimatedBindingKind;
 use corelib::window::WindowInner;
 use corelib::{Brush, Color, PathData, SharedString, SharedVector};
+use i_slint_compiler::diagnostics::SourceLocation;
 use i_slint_compiler::expression_tree::{
     BuiltinFunction, Callable, EasingCurve, Expression, MinMaxOp, Path as ExprPath,
     PathElement as ExprPathElement,
@@ -20,9 +21,89 @@
 use i_slint_compiler::object_tree::ElementRc;
 use i_slint_core as corelib;
 use smol_str::SmolStr;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// The severity of a message the interpreter logs while running a component
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Output of `debug()`
+    Debug,
+    /// Something that did not work out, like an image that could not be loaded
+    Warning,
+    /// Something that failed, like a font that could not be loaded
+    Error,
+}
+
+type LogHandler = Box<dyn Fn(LogLevel, Option<&SourceLocation>, &str)>;
+
+thread_local! {static LOG_HANDLER: RefCell<Option<LogHandler>> = const { RefCell::new(None) };}
+
+/// Receive the output of `debug()` and the runtime warnings of the interpreter in this thread
+/// instead of having them printed. `None` restores the default.
+pub fn set_log_handler(handler: Option<LogHandler>) {
+    LOG_HANDLER.with(|h| *h.borrow_mut() = handler);
+}
+
+/// Pass the message to the log handler. Returns false if there is none.
+fn handle_log(
+    level: LogLevel,
+    location: Option<&SourceLocation>,
+    args: core::fmt::Arguments,
+) -> bool {
+    LOG_HANDLER.with(|h| {
+        h.borrow().as_ref().map(|handler| handler(level, location, &args.to_string())).is_some()
+    })
+}
+
+/// Log the message, with `debug_log!` if there is no log handler
+fn log(level: LogLevel, location: Option<&SourceLocation>, args: core::fmt::Arguments) {
+    if !handle_log(level, location, args) {
+        corelib::debug_log!("{}", args);
+    }
+}
+
+/// Log the message, to stderr if there is no log handler
+fn log_to_stderr(level: LogLevel, location: Option<&SourceLocation>, args: core::fmt::Arguments) {
+    if !handle_log(level, location, args) {
+        eprintln!("{args}");
+    }
+}
+
+/// A callback handler declared in Slint that is about to run, see [`set_callback_hook`]
+pub struct CallbackInvocation<'a> {
+    /// The name of the callback
+    pub name: &'a SmolStr,
+    /// The arguments the handler gets invoked with
+    pub arguments: &'a [Value],
+    /// Where the handler is declared
+    pub location: Option<&'a SourceLocation>,
+}
+
+type CallbackHook = Box<dyn Fn(&CallbackInvocation)>;
+
+thread_local! {static CALLBACK_HOOK: RefCell<Option<CallbackHook>> = const { RefCell::new(None) };}
+
+/// Have `hook` called before each callback handler declared in Slint runs in this thread.
+/// Only the handlers of components instantiated while a hook is set call it, the others run
+/// without the cost of checking for one. `None` removes the hook.
+pub fn set_callback_hook(hook: Option<CallbackHook>) {
+    CALLBACK_HOOK.with(|h| *h.borrow_mut() = hook);
+}
+
+pub(crate) fn has_callback_hook() -> bool {
+    CALLBACK_HOOK.with(|h| h.borrow().is_some())
+}
+
+pub(crate) fn call_callback_hook(invocation: &CallbackInvocation) {
+    CALLBACK_HOOK.with(|h| {
+        if let Some(hook) = h.borrow().as_ref() {
+            hook(invocation)
+        }
+    })
+}
+
 pub trait ErasedPropertyInfo {
     fn get(&self, item: Pin<ItemRef>) -> Value;
     fn set(
@@ -133,6 +214,91 @@ pub fn from_function_arguments(
     }
 }
 
+/// Resolve `source` as if it was the binding of a property of the root element of `component`,
+/// and evaluate it with the current values of the properties it refers to.
+///
+/// `component` must be the top-level component, which knows its type loader.
+#[cfg(feature = "internal-highlight")]
+pub(crate) fn eval_source_expression(
+    component: InstanceRef,
+    source: &str,
+) -> Result<Value, String> {
+    use i_slint_compiler::diagnostics::{BuildDiagnostics, DiagnosticLevel};
+    use i_slint_compiler::lookup::LookupCtx;
+    use i_slint_compiler::parser::{self, SyntaxKind};
+
+    let type_loader = component
+        .description
+        .type_loader
+        .get()
+        .ok_or_else(|| "The component has no type loader".to_string())?;
+    let root_element = component.description.original.root_element.clone();
+    let path =
+        component.description.original.node.as_ref().map(|n| n.source_file.path().to_owned());
+    let global_registry = type_loader.global_type_registry.borrow();
+    let type_register = match path.as_deref().and_then(|p| type_loader.get_document(p)) {
+        Some(document) => &document.local_registry,
+        None => &*global_registry,
+    };
+
+    let errors = |diag: &BuildDiagnostics| {
+        let errors = diag
+            .iter()
+            .filter(|d| d.level() == DiagnosticLevel::Error)
+            .map(|d| d.message().to_string())
+            .collect::<Vec<_>>();
+        (!errors.is_empty()).then(|| errors.join("\n"))
+    };
+
+    let mut diag = BuildDiagnostics::default();
+    // The new line ends a trailing `//` comment
+    let node = parser::parse(format!("component X {{ x: {source}\n; }}"), None, &mut diag);
+    if let Some(errors) = errors(&diag) {
+        return Err(errors);
+    }
+    let binding = node
+        .descendants()
+        .find(|n| n.kind() == SyntaxKind::BindingExpression)
+        .ok_or_else(|| "Not an expression".to_string())?;
+
+    // The passes made the ids unique, like `root-1`. Give the elements their ids from the
+    // source back while resolving, so that `root` and the other ids can be looked up. `root`
+    // is the root element of the component, even if a pass wrapped the element of the source
+    // into a window.
+    let mut renamed = Vec::new();
+    let component_prefix = format!("{}::", component.description.original.id);
+    i_slint_compiler::object_tree::recurse_elem(&root_element, &(), &mut |element, _| {
+        let source_id = if Rc::ptr_eq(element, &root_element) {
+            Some(SmolStr::new_static("root"))
+        } else {
+            element
+                .borrow()
+                .debug
+                .first()
+                .and_then(|d| d.qualified_id.as_ref()?.strip_prefix(component_prefix.as_str()))
+                .filter(|id| *id != "root")
+                .map(SmolStr::from)
+        };
+        if let Some(source_id) = source_id {
+            let id = std::mem::replace(&mut element.borrow_mut().id, source_id);
+            renamed.push((element.clone(), id));
+        }
+    });
+
+    let scope = [root_element];
+    let mut ctx = LookupCtx::empty_context(type_register, &mut diag);
+    ctx.component_scope = &scope;
+    ctx.type_loader = Some(type_loader);
+    let expression = Expression::from_binding_expression_node(binding, &mut ctx);
+    for (element, id) in renamed {
+        element.borrow_mut().id = id;
+    }
+    if let Some(errors) = errors(&diag) {
+        return Err(errors);
+    }
+    Ok(eval_expression(&expression, &mut EvalLocalContext::from_component_instance(component)))
+}
+
 /// Evaluate an expression and return a Value as the result of this expression
 pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalContext) -> Value {
     if let Some(r) = &local_context.return_value {
@@ -202,7 +368,7 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
             }
             v
         }
-        Expression::FunctionCall { function, arguments, source_location: _ } => match &function {
+        Expression::FunctionCall { function, arguments, source_location } => match &function {
             Callable::Function(nr) => {
                 let args = arguments.iter().map(|e| eval_expression(e, local_context)).collect::<Vec<_>>();
                 call_function(&local_context.component_instance, &nr.element(), nr.name(), args).unwrap()
@@ -211,7 +377,9 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                 let args = arguments.iter().map(|e| eval_expression(e, local_context)).collect::<Vec<_>>();
                 invoke_callback(&local_context.component_instance, &nr.element(), nr.name(), &args).unwrap()
             }
-            Callable::Builtin(f) => call_builtin_function(f.clone(), arguments, local_context),
+            Callable::Builtin(f) => {
+                call_builtin_function(f.clone(), arguments, local_context, source_location.as_ref())
+            }
         }
         Expression::SelfAssignment { lhs, rhs, op, .. } => {
             let rhs = eval_expression(rhs, local_context);
@@ -287,7 +455,7 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                     todo!()
                 }
             }.unwrap_or_else(|_| {
-                eprintln!("Could not load image {resource_ref:?}" );
+                log_to_stderr(LogLevel::Warning, None, format_args!("Could not load image {resource_ref:?}"));
                 Default::default()
             });
             if let Some(n) = nine_slice {
@@ -398,6 +566,7 @@ fn call_builtin_function(
     f: BuiltinFunction,
     arguments: &[Expression],
     local_context: &mut EvalLocalContext,
+    source_location: Option<&SourceLocation>,
 ) -> Value {
     match f {
         BuiltinFunction::GetWindowScaleFactor => match local_context.component_instance {
@@ -424,7 +593,7 @@ fn call_builtin_function(
         BuiltinFunction::Debug => {
             let to_print: SharedString =
                 eval_expression(&arguments[0], local_context).try_into().unwrap();
-            corelib::debug_log!("{}", to_print);
+            log(LogLevel::Debug, source_location, format_args!("{}", to_print));
             Value::Void
         }
         BuiltinFunction::Mod => {
@@ -1178,11 +1347,11 @@ fn call_builtin_function(
             Value::Brush(Brush::SolidColor(Color::from_hsva(h, s, v, a)))
         }
         BuiltinFunction::ColorScheme => match local_context.component_instance {
-            ComponentInstance::InstanceRef(component) => component
-                .window_adapter()
-                .internal(corelib::InternalToken)
-                .map_or(ColorScheme::Unknown, |x| x.color_scheme())
-                .into(),
+            ComponentInstance::InstanceRef(component) => {
+                corelib::window::WindowInner::from_pub(component.window_adapter().window())
+                    .color_scheme()
+                    .into()
+            }
             ComponentInstance::GlobalComponent(_) => {
                 panic!("Cannot get the window from a global component")
             }
@@ -1398,7 +1567,11 @@ fn call_builtin_function(
                     .register_font_from_path(&std::path::PathBuf::from(s.as_str()))
                     .err()
                 {
-                    corelib::debug_log!("Error loading custom font {}: {}", s.as_str(), err);
+                    log(
+                        LogLevel::Error,
+                        source_location,
+                        format_args!("Error loading custom font {}: {}", s.as_str(), err),
+                    );
                 }
                 Value::Void
             } else {
@@ -1571,7 +1744,11 @@ fn eval_assignment(lhs: &Expression, op: char, rhs: Value, local_context: &mut E
                     }
                 }
                 _ => {
-                    eprintln!("Attempting to write into an array that cannot be written");
+                    log_to_stderr(
+                        LogLevel::Error,
+                        None,
+                        format_args!("Attempting to write into an array that cannot be written"),
+                    );
                 }
             }
         }
@@ -97,6 +97,10 @@
 #[doc(hidden)]
 pub use eval::default_value_for_type;
 
+#[cfg(feature = "internal")]
+#[doc(hidden)]
+pub use eval::{set_callback_hook, set_log_handler, CallbackInvocation, LogLevel};
+
 /// (Re-export from corelib.)
 #[doc(inline)]
 pub use i_slint_core::{Brush, Color, SharedString, SharedVector};
@@ -10,10 +10,20 @@
 use std::path::Path;
 use std::{collections::HashMap, path::PathBuf};
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod asset_bundle;
 pub mod component_catalog;
 pub mod document_cache;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod schema_import;
 pub use document_cache::{DocumentCache, SourceFileVersion};
+#[cfg(any(test, feature = "preview-engine"))]
+pub mod edit_builder;
+#[cfg(any(test, feature = "preview-engine"))]
+pub use edit_builder::WorkspaceEditBuilder;
 pub mod rename_component;
+pub mod rust_glue;
+pub mod structural_search;
 #[cfg(test)]
 pub mod test;
 #[cfg(any(test, feature = "preview-engine"))]
@@ -39,6 +49,16 @@ pub fn is_element_node_ignored(node: &syntax_nodes::Element) -> bool {
     })
 }
 
+/// Properties that hold text shown to the user
+pub const USER_VISIBLE_TEXT_PROPERTIES: &[&str] = &[
+    "text",
+    "title",
+    "placeholder-text",
+    "accessible-label",
+    "accessible-description",
+    "accessible-placeholder-text",
+];
+
 pub fn uri_to_file(uri: &Url) -> Option<PathBuf> {
     if uri.scheme() == "builtin" {
         Some(PathBuf::from(uri.to_string()))
@@ -482,12 +502,45 @@ pub struct PreviewComponent {
 #[allow(unused)]
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub enum LspToPreviewMessage {
-    InvalidateContents { url: lsp_types::Url },
-    ForgetFile { url: lsp_types::Url },
-    SetContents { url: VersionedUrl, contents: String },
-    SetConfiguration { config: PreviewConfig },
+    InvalidateContents {
+        url: lsp_types::Url,
+    },
+    ForgetFile {
+        url: lsp_types::Url,
+    },
+    SetContents {
+        url: VersionedUrl,
+        contents: String,
+    },
+    SetConfiguration {
+        config: PreviewConfig,
+    },
     ShowPreview(PreviewComponent),
-    HighlightFromEditor { url: Option<Url>, offset: u32 },
+    HighlightFromEditor {
+        url: Option<Url>,
+        offset: u32,
+    },
+    /// Briefly outline all instances of the element at `offset` in `url`
+    FlashElement {
+        url: Url,
+        offset: u32,
+    },
+    DocumentSaved {
+        url: lsp_types::Url,
+    },
+    /// The text cursor of the editor moved to `offset` in `url`
+    EditorCursorMoved {
+        url: Url,
+        offset: u32,
+    },
+    /// Save a bug report of what the preview shows to `path`, or next to the previewed file
+    ReportIssue {
+        path: Option<PathBuf>,
+    },
+    /// The answer to `PreviewToLspMessage::Ping`
+    Pong {
+        id: u32,
+    },
 }
 
 impl lsp_types::notification::Notification for LspToPreviewMessage {
@@ -535,6 +588,8 @@ pub enum PreviewToLspMessage {
     SendWorkspaceEdit { label: Option<String>, edit: lsp_types::WorkspaceEdit },
     /// Pass a `ShowMessage` notification on to the editor
     SendShowMessage { message: lsp_types::ShowMessageParams },
+    /// Check that the LSP is still responsive, it answers with `LspToPreviewMessage::Pong`
+    Ping { id: u32 },
 }
 
 /// Information on the Element types available
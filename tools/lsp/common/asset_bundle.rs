@@ -0,0 +1,261 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Package a document with all the documents, images and fonts it uses into one
+//! self-contained directory, so that it can be moved to another project.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+
+use i_slint_compiler::parser::{NodeOrToken, SyntaxKind, SyntaxToken, TextRange};
+use i_slint_compiler::pathutils;
+
+use crate::common::{self, DocumentCache};
+
+/// One file of the bundle
+#[derive(Clone, Debug, PartialEq)]
+pub struct BundleFile {
+    /// The location of the file, relative to the bundle directory
+    pub path: PathBuf,
+    pub contents: Vec<u8>,
+}
+
+/// A string literal in a document pointing to another file
+struct Reference {
+    range: TextRange,
+    target: PathBuf,
+}
+
+fn resolve(document_cache: &DocumentCache, token: &SyntaxToken, uri: &str) -> Option<PathBuf> {
+    match document_cache.resolve_import_path(Some(&NodeOrToken::Token(token.clone())), uri) {
+        // Built into the compiler, like the std-widgets
+        Some((_, Some(_))) => None,
+        Some((path, None)) => Some(path),
+        None => pathutils::join(&pathutils::dirname(token.source_file.path()), Path::new(uri)),
+    }
+    .map(|p| pathutils::clean_path(&p))
+}
+
+fn references(document_cache: &DocumentCache, path: &Path) -> Vec<Reference> {
+    let Some(document) = document_cache.get_document_by_path(path) else {
+        return vec![];
+    };
+
+    let imports = document.imports.iter().map(|i| (i.import_uri_token.clone(), i.file.clone()));
+    let images = document.node.iter().flat_map(|node| {
+        node.descendants()
+            .filter(|n| n.kind() == SyntaxKind::AtImageUrl)
+            .filter_map(|n| n.child_token(SyntaxKind::StringLiteral))
+            .filter_map(|t| {
+                let uri = i_slint_compiler::literals::unescape_string(t.text())?;
+                Some((t, uri.to_string()))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    imports
+        .chain(images)
+        .filter_map(|(token, uri)| {
+            Some(Reference {
+                range: token.text_range(),
+                target: resolve(document_cache, &token, &uri)?,
+            })
+        })
+        .collect()
+}
+
+/// Pick the location of `file` in the bundle: Files next to or below the main document
+/// keep their relative location, all others move into `external`.
+fn bundle_path(base: &Path, file: &Path, taken: &mut HashSet<PathBuf>) -> PathBuf {
+    if let Ok(relative) = file.strip_prefix(base) {
+        taken.insert(relative.to_path_buf());
+        return relative.to_path_buf();
+    }
+
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let extension =
+        file.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let mut candidate = PathBuf::from("external").join(format!("{stem}{extension}"));
+    let mut counter = 2;
+    while taken.contains(&candidate) {
+        candidate = PathBuf::from("external").join(format!("{stem}-{counter}{extension}"));
+        counter += 1;
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
+
+/// The path to `to` as seen from the directory `from_dir`, both relative to the bundle
+pub fn relative_path(from_dir: &Path, to: &Path) -> String {
+    let from = from_dir.components().collect::<Vec<_>>();
+    let to = to.components().collect::<Vec<_>>();
+    let common = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+
+    std::iter::repeat_n("..".to_string(), from.len() - common)
+        .chain(to[common..].iter().filter_map(|c| match c {
+            Component::Normal(c) => Some(c.to_string_lossy().to_string()),
+            _ => None,
+        }))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Collect the document at `path` and everything it refers to, with all relative
+/// paths rewritten to match the layout of the bundle
+///
+/// `read_file` is used to read images and fonts.
+pub fn collect_bundle(
+    document_cache: &DocumentCache,
+    path: &Path,
+    read_file: impl Fn(&Path) -> std::io::Result<Vec<u8>>,
+) -> common::Result<Vec<BundleFile>> {
+    let path = pathutils::clean_path(path);
+    if document_cache.get_document_by_path(&path).is_none() {
+        return Err(format!("{path:?} is not a known document").into());
+    }
+    let base = pathutils::dirname(&path);
+
+    let mut taken = HashSet::new();
+    let mut locations = HashMap::from([(path.clone(), bundle_path(&base, &path, &mut taken))]);
+    let mut order = vec![path.clone()];
+    let mut queue = VecDeque::from([path]);
+    let mut all_references = HashMap::new();
+
+    while let Some(current) = queue.pop_front() {
+        let references = references(document_cache, &current);
+        for r in &references {
+            if !locations.contains_key(&r.target) {
+                locations.insert(r.target.clone(), bundle_path(&base, &r.target, &mut taken));
+                order.push(r.target.clone());
+                queue.push_back(r.target.clone());
+            }
+        }
+        all_references.insert(current, references);
+    }
+
+    order
+        .into_iter()
+        .map(|file| {
+            let location = locations[&file].clone();
+            let contents = if let Some(node) =
+                document_cache.get_document_by_path(&file).and_then(|d| d.node.as_ref())
+            {
+                let mut text = node.text().to_string();
+                let mut references = all_references.remove(&file).unwrap_or_default();
+                references.sort_by_key(|r| std::cmp::Reverse(r.range.start()));
+                let dir = location.parent().unwrap_or(Path::new(""));
+                for r in references {
+                    let new_path = relative_path(dir, &locations[&r.target]);
+                    text.replace_range(
+                        usize::from(r.range.start())..usize::from(r.range.end()),
+                        &format!("{new_path:?}"),
+                    );
+                }
+                text.into_bytes()
+            } else {
+                read_file(&file).map_err(|e| format!("Failed to read {file:?}: {e}"))?
+            };
+            Ok(BundleFile { path: location, contents })
+        })
+        .collect()
+}
+
+/// Write the `files` of a bundle into `directory`
+pub fn write_bundle(files: &[BundleFile], directory: &Path) -> std::io::Result<()> {
+    for file in files {
+        let path = directory.join(&file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, &file.contents)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(Path::new(""), Path::new("a/b.slint")), "a/b.slint");
+        assert_eq!(relative_path(Path::new("a"), Path::new("a/b.slint")), "b.slint");
+        assert_eq!(relative_path(Path::new("a/c"), Path::new("b.png")), "../../b.png");
+        assert_eq!(
+            relative_path(Path::new("a/c"), Path::new("external/x.slint")),
+            "../../external/x.slint"
+        );
+    }
+
+    #[test]
+    fn test_bundle_path() {
+        let mut taken = HashSet::new();
+        let base = Path::new("/project/ui");
+        assert_eq!(
+            bundle_path(base, Path::new("/project/ui/a/b.slint"), &mut taken),
+            PathBuf::from("a/b.slint")
+        );
+        assert_eq!(
+            bundle_path(base, Path::new("/lib/button.slint"), &mut taken),
+            PathBuf::from("external/button.slint")
+        );
+        assert_eq!(
+            bundle_path(base, Path::new("/other/button.slint"), &mut taken),
+            PathBuf::from("external/button-2.slint")
+        );
+    }
+
+    #[test]
+    fn test_collect_bundle() {
+        let main = test::main_test_file_name();
+        let widgets = test::test_file_name("widgets/button.slint");
+        let source = r#"import { Button } from "widgets/button.slint";
+import { Button as B2 } from "./widgets/button.slint";
+import { VerticalBox } from "std-widgets.slint";
+
+export component Main {
+    VerticalBox {
+        Button { }
+        Image { source: @image-url("assets/logo.png"); }
+    }
+}
+"#;
+        let button_source = r#"export component Button {
+    Image { source: @image-url("../assets/icon.svg"); }
+}
+"#;
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([
+                (lsp_types::Url::from_file_path(&main).unwrap(), source.to_string()),
+                (lsp_types::Url::from_file_path(&widgets).unwrap(), button_source.to_string()),
+            ]),
+            true,
+        );
+
+        let files = collect_bundle(&document_cache, &main, |p| {
+            Ok(p.file_name().unwrap().to_string_lossy().as_bytes().to_vec())
+        })
+        .unwrap();
+
+        let paths = files.iter().map(|f| f.path.to_string_lossy().to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec!["test_data.slint", "widgets/button.slint", "assets/logo.png", "assets/icon.svg"]
+        );
+
+        let main_text = String::from_utf8(files[0].contents.clone()).unwrap();
+        assert!(main_text.contains(r#"import { Button } from "widgets/button.slint";"#));
+        assert!(main_text.contains(r#"import { Button as B2 } from "widgets/button.slint";"#));
+        assert!(main_text.contains(r#"import { VerticalBox } from "std-widgets.slint";"#));
+        assert!(main_text.contains(r#"@image-url("assets/logo.png")"#));
+        let button_text = String::from_utf8(files[1].contents.clone()).unwrap();
+        assert_eq!(button_text, button_source);
+        assert_eq!(files[2].contents, b"logo.png");
+
+        assert!(
+            collect_bundle(&document_cache, Path::new("/unknown.slint"), |_| Ok(vec![])).is_err()
+        );
+    }
+}
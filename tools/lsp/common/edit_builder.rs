@@ -0,0 +1,287 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Collect all text edits making up one designer operation into one `WorkspaceEdit`
+//!
+//! The editor applies a `WorkspaceEdit` as a whole, so a single undo reverts all of it.
+
+use lsp_types::{TextEdit, Url, WorkspaceEdit};
+
+use crate::common::{self, SourceFileVersion};
+
+struct DocumentEdits {
+    url: Url,
+    version: SourceFileVersion,
+    edits: Vec<TextEdit>,
+}
+
+pub struct WorkspaceEditBuilder {
+    label: String,
+    /// Files that do not exist yet, created before any text gets edited
+    created_files: Vec<Url>,
+    documents: Vec<DocumentEdits>,
+    errors: Vec<String>,
+}
+
+impl WorkspaceEditBuilder {
+    /// Start collecting edits for the operation called `label`
+    pub fn new(label: impl Into<String>) -> Self {
+        Self { label: label.into(), created_files: vec![], documents: vec![], errors: vec![] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.iter().all(|d| d.edits.is_empty()) && self.errors.is_empty()
+    }
+
+    /// Create a new file with `contents` at `url`
+    pub fn create_file(&mut self, url: Url, contents: String) {
+        if self.created_files.contains(&url) {
+            self.errors.push(format!("{url} is created twice"));
+            return;
+        }
+        self.created_files.push(url.clone());
+        self.add_text_edit(
+            url,
+            None,
+            TextEdit { range: lsp_types::Range::default(), new_text: contents },
+        );
+    }
+
+    pub fn add_text_edit(&mut self, url: Url, version: SourceFileVersion, edit: TextEdit) {
+        if let Some(document) = self.documents.iter_mut().find(|d| d.url == url) {
+            if document.version != version {
+                self.errors.push(format!(
+                    "{url} is edited in version {:?} and {version:?}",
+                    document.version
+                ));
+                return;
+            }
+            document.edits.push(edit);
+        } else {
+            self.documents.push(DocumentEdits { url, version, edits: vec![edit] });
+        }
+    }
+
+    pub fn add_single_text_edit(&mut self, edit: common::SingleTextEdit) {
+        self.add_text_edit(edit.url, edit.version, edit.edit);
+    }
+
+    fn add_text_document_edit(&mut self, edit: lsp_types::TextDocumentEdit) {
+        for e in edit.edits {
+            let e = match e {
+                lsp_types::OneOf::Left(e) => e,
+                lsp_types::OneOf::Right(e) => e.text_edit,
+            };
+            self.add_text_edit(edit.text_document.uri.clone(), edit.text_document.version, e);
+        }
+    }
+
+    /// Merge all text edits in `edit` into this operation
+    pub fn add_workspace_edit(&mut self, edit: WorkspaceEdit) {
+        match edit.document_changes {
+            Some(lsp_types::DocumentChanges::Edits(edits)) => {
+                edits.into_iter().for_each(|e| self.add_text_document_edit(e))
+            }
+            Some(lsp_types::DocumentChanges::Operations(operations)) => {
+                for operation in operations {
+                    match operation {
+                        lsp_types::DocumentChangeOperation::Edit(e) => {
+                            self.add_text_document_edit(e)
+                        }
+                        lsp_types::DocumentChangeOperation::Op(lsp_types::ResourceOp::Create(
+                            c,
+                        )) => self.created_files.push(c.uri),
+                        lsp_types::DocumentChangeOperation::Op(_) => self.errors.push(
+                            "Resource operations other than creating files are not supported"
+                                .to_string(),
+                        ),
+                    }
+                }
+            }
+            None => {}
+        }
+
+        for (url, edits) in edit.changes.into_iter().flatten() {
+            for e in edits {
+                self.add_text_edit(url.clone(), None, e);
+            }
+        }
+    }
+
+    /// Turn the collected edits into the label and `WorkspaceEdit` to send to the editor
+    ///
+    /// This fails if the edits can not be applied as one unit: When a document
+    /// is edited in different versions or when edits to a document overlap.
+    pub fn build(self) -> common::Result<(String, WorkspaceEdit)> {
+        if let Some(error) = self.errors.into_iter().next() {
+            return Err(error.into());
+        }
+
+        for document in &self.documents {
+            let mut ranges = document.edits.iter().map(|e| e.range).collect::<Vec<_>>();
+            ranges.sort_by_key(|r| (r.start, r.end));
+            if let Some(w) = ranges.windows(2).find(|w| w[0].end > w[1].start) {
+                return Err(format!(
+                    "Overlapping edits in {} at {}:{}",
+                    document.url, w[1].start.line, w[1].start.character
+                )
+                .into());
+            }
+        }
+
+        let edits = self
+            .documents
+            .into_iter()
+            .filter(|d| !d.edits.is_empty())
+            .map(|d| common::create_text_document_edit(d.url, d.version, d.edits))
+            .collect::<Vec<_>>();
+
+        if self.created_files.is_empty() {
+            return Ok((self.label, common::create_workspace_edit_from_text_document_edits(edits)));
+        }
+
+        let operations = self
+            .created_files
+            .into_iter()
+            .map(|uri| {
+                lsp_types::DocumentChangeOperation::Op(lsp_types::ResourceOp::Create(
+                    lsp_types::CreateFile {
+                        uri,
+                        options: Some(lsp_types::CreateFileOptions {
+                            overwrite: Some(false),
+                            ignore_if_exists: Some(false),
+                        }),
+                        annotation_id: None,
+                    },
+                ))
+            })
+            .chain(edits.into_iter().map(lsp_types::DocumentChangeOperation::Edit))
+            .collect();
+        let edit = WorkspaceEdit {
+            document_changes: Some(lsp_types::DocumentChanges::Operations(operations)),
+            ..Default::default()
+        };
+        Ok((self.label, edit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(line: u32, start: u32, end: u32, text: &str) -> TextEdit {
+        TextEdit {
+            range: lsp_types::Range::new(
+                lsp_types::Position::new(line, start),
+                lsp_types::Position::new(line, end),
+            ),
+            new_text: text.to_string(),
+        }
+    }
+
+    fn text_document_edits(edit: &WorkspaceEdit) -> Vec<(String, Option<i32>, usize)> {
+        let Some(lsp_types::DocumentChanges::Edits(edits)) = &edit.document_changes else {
+            panic!("Expected document edits");
+        };
+        edits
+            .iter()
+            .map(|e| (e.text_document.uri.to_string(), e.text_document.version, e.edits.len()))
+            .collect()
+    }
+
+    #[test]
+    fn test_build_combined_edit() {
+        let a = Url::parse("file:///a.slint").unwrap();
+        let b = Url::parse("file:///b.slint").unwrap();
+
+        let mut builder = WorkspaceEditBuilder::new("Wrap in layout");
+        assert!(builder.is_empty());
+        builder.add_text_edit(b.clone(), Some(3), edit(1, 0, 4, "Foo"));
+        builder.add_workspace_edit(common::create_workspace_edit(
+            a.clone(),
+            Some(7),
+            vec![edit(2, 0, 0, "import"), edit(2, 0, 0, " { X }")],
+        ));
+        builder.add_single_text_edit(common::SingleTextEdit {
+            url: b.clone(),
+            version: Some(3),
+            edit: edit(1, 4, 6, "Bar"),
+        });
+        assert!(!builder.is_empty());
+
+        let (label, workspace_edit) = builder.build().unwrap();
+        assert_eq!(label, "Wrap in layout");
+        assert_eq!(
+            text_document_edits(&workspace_edit),
+            vec![(b.to_string(), Some(3), 2), (a.to_string(), Some(7), 2)]
+        );
+    }
+
+    #[test]
+    fn test_build_with_created_file() {
+        let a = Url::parse("file:///a.slint").unwrap();
+        let b = Url::parse("file:///b.slint").unwrap();
+
+        let mut builder = WorkspaceEditBuilder::new("Extract component");
+        builder.add_text_edit(a.clone(), Some(3), edit(1, 0, 4, "B"));
+        builder.create_file(b.clone(), "export component B {}".to_string());
+
+        let (_, workspace_edit) = builder.build().unwrap();
+        let Some(lsp_types::DocumentChanges::Operations(operations)) =
+            &workspace_edit.document_changes
+        else {
+            panic!("Expected document operations");
+        };
+        // The file is created before anything gets edited
+        assert!(matches!(
+            &operations[0],
+            lsp_types::DocumentChangeOperation::Op(lsp_types::ResourceOp::Create(c)) if c.uri == b
+        ));
+        assert!(matches!(
+            &operations[1],
+            lsp_types::DocumentChangeOperation::Edit(e) if e.text_document.uri == a
+        ));
+        assert!(matches!(
+            &operations[2],
+            lsp_types::DocumentChangeOperation::Edit(e) if e.text_document.uri == b && e.text_document.version.is_none()
+        ));
+        assert_eq!(crate::common::text_edit::created_files(&workspace_edit), vec![&b]);
+    }
+
+    #[test]
+    fn test_build_rejects_conflicts() {
+        let a = Url::parse("file:///a.slint").unwrap();
+
+        let mut builder = WorkspaceEditBuilder::new("Overlap");
+        builder.add_text_edit(a.clone(), Some(1), edit(1, 0, 4, "Foo"));
+        builder.add_text_edit(a.clone(), Some(1), edit(1, 3, 6, "Bar"));
+        assert!(builder.build().is_err());
+
+        let mut builder = WorkspaceEditBuilder::new("Versions");
+        builder.add_text_edit(a.clone(), Some(1), edit(1, 0, 4, "Foo"));
+        builder.add_text_edit(a.clone(), Some(2), edit(2, 0, 4, "Bar"));
+        assert!(builder.build().is_err());
+
+        let mut builder = WorkspaceEditBuilder::new("Operations");
+        builder.add_workspace_edit(WorkspaceEdit {
+            document_changes: Some(lsp_types::DocumentChanges::Operations(vec![
+                lsp_types::DocumentChangeOperation::Op(lsp_types::ResourceOp::Delete(
+                    lsp_types::DeleteFile { uri: a.clone(), options: None },
+                )),
+            ])),
+            ..Default::default()
+        });
+        assert!(builder.build().is_err());
+
+        let mut builder = WorkspaceEditBuilder::new("Create twice");
+        builder.create_file(a.clone(), "component A {}".to_string());
+        builder.create_file(a.clone(), "component B {}".to_string());
+        assert!(builder.build().is_err());
+
+        // Adjacent edits are fine
+        let mut builder = WorkspaceEditBuilder::new("Adjacent");
+        builder.add_text_edit(a.clone(), Some(1), edit(1, 0, 4, "Foo"));
+        builder.add_text_edit(a, Some(1), edit(1, 4, 6, "Bar"));
+        assert!(builder.build().is_ok());
+    }
+}
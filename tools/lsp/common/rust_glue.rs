@@ -0,0 +1,517 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Generate plain Rust types mirroring a Slint `struct`, `enum` or `global`, together
+//! with the conversions from and to `slint_interpreter::Value` and the types generated
+//! by the Rust code generator, so that host applications do not need to write that
+//! mapping code by hand.
+
+use std::fmt::Write;
+
+use i_slint_compiler::langtype::{ElementType, Enumeration, Type};
+use i_slint_compiler::object_tree::{Document, PropertyVisibility};
+use smol_str::SmolStr;
+
+use crate::common;
+
+/// `my-struct` becomes `my_struct`, matching the Rust code generator
+fn rust_ident(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// A declaration the generated code depends on
+enum Declaration {
+    Struct(SmolStr, Vec<(SmolStr, Type)>),
+    Enum(std::rc::Rc<Enumeration>),
+}
+
+struct Generator<'a> {
+    /// The path of the module containing the types generated by the Rust code generator
+    compiled_module: &'a str,
+    declarations: Vec<Declaration>,
+    uses_arrays: bool,
+}
+
+impl Generator<'_> {
+    fn is_declared(&self, name: &str) -> bool {
+        self.declarations.iter().any(|d| match d {
+            Declaration::Struct(n, _) => n == name,
+            Declaration::Enum(e) => e.name == name,
+        })
+    }
+
+    /// Declare all structs and enums `ty` uses, failing if it can not be converted
+    fn declare_type(&mut self, ty: &Type) -> common::Result<()> {
+        match ty {
+            Type::Int32
+            | Type::Float32
+            | Type::LogicalLength
+            | Type::PhysicalLength
+            | Type::Rem
+            | Type::Angle
+            | Type::Percent
+            | Type::Duration
+            | Type::Bool
+            | Type::String
+            | Type::Color
+            | Type::Brush
+            | Type::Image => Ok(()),
+            Type::Array(inner) => {
+                if matches!(**inner, Type::Array(_)) {
+                    return Err("Nested arrays are not supported".into());
+                }
+                self.uses_arrays = true;
+                self.declare_type(inner)
+            }
+            Type::Struct(s) => {
+                let (Some(name), Some(_)) = (&s.name, &s.node) else {
+                    return Err(
+                        format!("The anonymous or builtin struct {ty} is not supported").into()
+                    );
+                };
+                self.declare_struct(name, s.fields.iter().map(|(n, t)| (n.clone(), t.clone())))
+            }
+            Type::Enumeration(e) => {
+                if e.node.is_none() {
+                    return Err(format!("The builtin enum {} is not supported", e.name).into());
+                }
+                if !self.is_declared(&e.name) {
+                    self.declarations.push(Declaration::Enum(e.clone()));
+                }
+                Ok(())
+            }
+            _ => Err(format!("The type {ty} is not supported").into()),
+        }
+    }
+
+    fn declare_struct(
+        &mut self,
+        name: &SmolStr,
+        fields: impl Iterator<Item = (SmolStr, Type)>,
+    ) -> common::Result<()> {
+        if self.is_declared(name) {
+            return Ok(());
+        }
+        let index = self.declarations.len();
+        self.declarations.push(Declaration::Struct(name.clone(), vec![]));
+        let fields = fields.collect::<Vec<_>>();
+        for (_, ty) in &fields {
+            self.declare_type(ty)?;
+        }
+        self.declarations[index] = Declaration::Struct(name.clone(), fields);
+        Ok(())
+    }
+
+    fn write_struct(&self, out: &mut String, name: &str, fields: &[(SmolStr, Type)]) {
+        let ident = rust_ident(name);
+        let compiled = format!("{}::{ident}", self.compiled_module);
+
+        writeln!(out, "#[derive(Clone, Debug, Default, PartialEq)]").unwrap();
+        writeln!(out, "pub struct {ident} {{").unwrap();
+        for (field, ty) in fields {
+            writeln!(out, "    pub {}: {},", rust_ident(field), rust_type(ty)).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(out, "impl From<{ident}> for slint_interpreter::Value {{").unwrap();
+        writeln!(out, "    fn from(value: {ident}) -> Self {{").unwrap();
+        writeln!(out, "        let mut s = slint_interpreter::Struct::default();").unwrap();
+        for (field, ty) in fields {
+            let value = to_value(ty, &format!("value.{}", rust_ident(field)));
+            writeln!(out, "        s.set_field({field:?}.into(), {value});").unwrap();
+        }
+        writeln!(out, "        slint_interpreter::Value::Struct(s)").unwrap();
+        writeln!(out, "    }}\n}}\n").unwrap();
+
+        writeln!(out, "impl TryFrom<slint_interpreter::Value> for {ident} {{").unwrap();
+        writeln!(out, "    type Error = ();").unwrap();
+        writeln!(
+            out,
+            "    fn try_from(value: slint_interpreter::Value) -> Result<Self, Self::Error> {{"
+        )
+        .unwrap();
+        writeln!(out, "        let slint_interpreter::Value::Struct(s) = value else {{").unwrap();
+        writeln!(out, "            return Err(());\n        }};").unwrap();
+        writeln!(out, "        Ok(Self {{").unwrap();
+        for (field, ty) in fields {
+            let value = from_value(ty, &format!("s.get_field({field:?}).cloned().ok_or(())?"));
+            writeln!(out, "            {}: {value}?,", rust_ident(field)).unwrap();
+        }
+        writeln!(out, "        }})\n    }}\n}}\n").unwrap();
+
+        writeln!(out, "impl From<{ident}> for {compiled} {{").unwrap();
+        writeln!(out, "    fn from(value: {ident}) -> Self {{").unwrap();
+        writeln!(out, "        Self {{").unwrap();
+        for (field, ty) in fields {
+            let field = rust_ident(field);
+            writeln!(out, "            {field}: {},", to_compiled(ty, &format!("value.{field}")))
+                .unwrap();
+        }
+        writeln!(out, "        }}\n    }}\n}}\n").unwrap();
+
+        writeln!(out, "impl From<{compiled}> for {ident} {{").unwrap();
+        writeln!(out, "    fn from(value: {compiled}) -> Self {{").unwrap();
+        writeln!(out, "        Self {{").unwrap();
+        for (field, ty) in fields {
+            let field = rust_ident(field);
+            writeln!(out, "            {field}: {},", from_compiled(ty, &format!("value.{field}")))
+                .unwrap();
+        }
+        writeln!(out, "        }}\n    }}\n}}\n").unwrap();
+    }
+
+    fn write_enum(&self, out: &mut String, e: &Enumeration) {
+        let ident = rust_ident(&e.name);
+        let compiled = format!("{}::{ident}", self.compiled_module);
+        let variants = e
+            .values
+            .iter()
+            .map(|v| (v, i_slint_compiler::generator::to_pascal_case(v)))
+            .collect::<Vec<_>>();
+
+        writeln!(out, "#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]").unwrap();
+        writeln!(out, "pub enum {ident} {{").unwrap();
+        for (i, (_, variant)) in variants.iter().enumerate() {
+            if i == e.default_value {
+                writeln!(out, "    #[default]").unwrap();
+            }
+            writeln!(out, "    {variant},").unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(out, "impl From<{ident}> for slint_interpreter::Value {{").unwrap();
+        writeln!(out, "    fn from(value: {ident}) -> Self {{").unwrap();
+        writeln!(out, "        let value = match value {{").unwrap();
+        for (value, variant) in &variants {
+            writeln!(out, "            {ident}::{variant} => {value:?},").unwrap();
+        }
+        writeln!(out, "        }};").unwrap();
+        writeln!(
+            out,
+            "        slint_interpreter::Value::EnumerationValue({:?}.into(), value.into())",
+            e.name
+        )
+        .unwrap();
+        writeln!(out, "    }}\n}}\n").unwrap();
+
+        writeln!(out, "impl TryFrom<slint_interpreter::Value> for {ident} {{").unwrap();
+        writeln!(out, "    type Error = ();").unwrap();
+        writeln!(
+            out,
+            "    fn try_from(value: slint_interpreter::Value) -> Result<Self, Self::Error> {{"
+        )
+        .unwrap();
+        writeln!(out, "        match value {{").unwrap();
+        writeln!(
+            out,
+            "            slint_interpreter::Value::EnumerationValue(e, value) if e == {:?} => {{",
+            e.name
+        )
+        .unwrap();
+        writeln!(out, "                match value.as_str() {{").unwrap();
+        for (value, variant) in &variants {
+            writeln!(out, "                    {value:?} => Ok(Self::{variant}),").unwrap();
+        }
+        writeln!(out, "                    _ => Err(()),\n                }}\n            }}")
+            .unwrap();
+        writeln!(out, "            _ => Err(()),\n        }}\n    }}\n}}\n").unwrap();
+
+        for (from, to) in [(&ident, &compiled), (&compiled, &ident)] {
+            writeln!(out, "impl From<{from}> for {to} {{").unwrap();
+            writeln!(out, "    fn from(value: {from}) -> Self {{").unwrap();
+            writeln!(out, "        match value {{").unwrap();
+            for (_, variant) in &variants {
+                writeln!(out, "            {from}::{variant} => Self::{variant},").unwrap();
+            }
+            writeln!(out, "        }}\n    }}\n}}\n").unwrap();
+        }
+    }
+
+    fn write_global(&self, out: &mut String, name: &str, properties: &[(SmolStr, Type, bool)]) {
+        let ident = rust_ident(name);
+        let compiled = format!("{}::{ident}", self.compiled_module);
+
+        writeln!(out, "#[derive(Clone, Debug, Default, PartialEq)]").unwrap();
+        writeln!(out, "pub struct {ident} {{").unwrap();
+        for (property, ty, _) in properties {
+            writeln!(out, "    pub {}: {},", rust_ident(property), rust_type(ty)).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(out, "impl {ident} {{").unwrap();
+        writeln!(out, "    /// Read the properties of the global from an interpreted component")
+            .unwrap();
+        writeln!(
+            out,
+            "    pub fn from_instance(instance: &slint_interpreter::ComponentInstance) -> Option<Self> {{"
+        )
+        .unwrap();
+        writeln!(out, "        Some(Self {{").unwrap();
+        for (property, ty, _) in properties {
+            let value = from_value(
+                ty,
+                &format!("instance.get_global_property({name:?}, {property:?}).ok()?"),
+            );
+            writeln!(out, "            {}: {value}.ok()?,", rust_ident(property)).unwrap();
+        }
+        writeln!(out, "        }})\n    }}\n").unwrap();
+
+        writeln!(
+            out,
+            "    /// Set the properties of the global that can be set from the outside on an interpreted component"
+        )
+        .unwrap();
+        writeln!(out, "    pub fn apply_to_instance(").unwrap();
+        writeln!(out, "        &self,").unwrap();
+        writeln!(out, "        instance: &slint_interpreter::ComponentInstance,").unwrap();
+        writeln!(out, "    ) -> Result<(), slint_interpreter::SetPropertyError> {{").unwrap();
+        for (property, ty, _) in properties.iter().filter(|(_, _, writable)| *writable) {
+            let value = to_value(ty, &format!("self.{}.clone()", rust_ident(property)));
+            writeln!(
+                out,
+                "        instance.set_global_property({name:?}, {property:?}, {value})?;"
+            )
+            .unwrap();
+        }
+        writeln!(out, "        Ok(())\n    }}\n").unwrap();
+
+        writeln!(out, "    /// Read the properties of the global from a compiled component")
+            .unwrap();
+        writeln!(out, "    pub fn from_global(global: &{compiled}<'_>) -> Self {{").unwrap();
+        writeln!(out, "        Self {{").unwrap();
+        for (property, ty, _) in properties {
+            let property = rust_ident(property);
+            let value = from_compiled(ty, &format!("global.get_{property}()"));
+            writeln!(out, "            {property}: {value},").unwrap();
+        }
+        writeln!(out, "        }}\n    }}\n").unwrap();
+
+        writeln!(
+            out,
+            "    /// Set the properties of the global that can be set from the outside on a compiled component"
+        )
+        .unwrap();
+        writeln!(out, "    pub fn apply_to_global(&self, global: &{compiled}<'_>) {{").unwrap();
+        for (property, ty, _) in properties.iter().filter(|(_, _, writable)| *writable) {
+            let property = rust_ident(property);
+            let value = to_compiled(ty, &format!("self.{property}.clone()"));
+            writeln!(out, "        global.set_{property}({value});").unwrap();
+        }
+        writeln!(out, "    }}\n}}\n").unwrap();
+    }
+}
+
+/// The Rust type of the glue code for `ty`
+fn rust_type(ty: &Type) -> String {
+    match ty {
+        Type::Int32 => "i32".into(),
+        Type::Duration => "i64".into(),
+        Type::Bool => "bool".into(),
+        Type::String => "slint::SharedString".into(),
+        Type::Color => "slint::Color".into(),
+        Type::Brush => "slint::Brush".into(),
+        Type::Image => "slint::Image".into(),
+        Type::Array(inner) => format!("Vec<{}>", rust_type(inner)),
+        Type::Struct(s) => rust_ident(s.name.as_deref().unwrap_or_default()),
+        Type::Enumeration(e) => rust_ident(&e.name),
+        _ => "f32".into(),
+    }
+}
+
+/// An expression converting `expr` of type `ty` into a `slint_interpreter::Value`
+fn to_value(ty: &Type, expr: &str) -> String {
+    match ty {
+        Type::Array(_) => format!("vec_to_value({expr})"),
+        _ => format!("{expr}.into()"),
+    }
+}
+
+/// An expression converting the `slint_interpreter::Value` `expr` to `ty`, as a `Result<_, ()>`
+fn from_value(ty: &Type, expr: &str) -> String {
+    match ty {
+        Type::Array(_) => format!("vec_from_value({expr})"),
+        _ => format!("{expr}.try_into().map_err(|_| ())"),
+    }
+}
+
+/// An expression converting `expr` of type `ty` into the type used by the Rust code generator
+fn to_compiled(ty: &Type, expr: &str) -> String {
+    match ty {
+        Type::Array(_) => format!(
+            "slint::ModelRc::new(slint::VecModel::from({expr}.into_iter().map(Into::into).collect::<Vec<_>>()))"
+        ),
+        Type::Struct(_) | Type::Enumeration(_) => format!("{expr}.into()"),
+        _ => expr.to_string(),
+    }
+}
+
+/// An expression converting `expr` from the type used by the Rust code generator to `ty`
+fn from_compiled(ty: &Type, expr: &str) -> String {
+    match ty {
+        Type::Array(_) => format!("slint::Model::iter(&{expr}).map(Into::into).collect()"),
+        Type::Struct(_) | Type::Enumeration(_) => format!("{expr}.into()"),
+        _ => expr.to_string(),
+    }
+}
+
+const ARRAY_HELPERS: &str = r#"fn vec_to_value<T: Into<slint_interpreter::Value>>(values: Vec<T>) -> slint_interpreter::Value {
+    let values = values.into_iter().map(Into::into).collect::<Vec<slint_interpreter::Value>>();
+    slint_interpreter::Value::Model(slint::ModelRc::new(slint::VecModel::from(values)))
+}
+
+fn vec_from_value<T: TryFrom<slint_interpreter::Value>>(
+    value: slint_interpreter::Value,
+) -> Result<Vec<T>, ()> {
+    let slint_interpreter::Value::Model(model) = value else {
+        return Err(());
+    };
+    slint::Model::iter(&model).map(|v| T::try_from(v).map_err(|_| ())).collect()
+}
+"#;
+
+/// Generate the Rust glue code for the struct, enum or global called `name` in `document`
+///
+/// `compiled_module` is the path of the module the Rust code generator put its types into,
+/// usually the one calling `slint::include_modules!()`.
+pub fn generate_rust_glue(
+    document: &Document,
+    name: &str,
+    compiled_module: &str,
+) -> common::Result<String> {
+    let mut generator = Generator { compiled_module, declarations: vec![], uses_arrays: false };
+    let mut global = None;
+
+    match document.local_registry.lookup(name) {
+        ty @ (Type::Struct(_) | Type::Enumeration(_)) => {
+            generator.declare_type(&ty)?;
+        }
+        _ => match document.local_registry.lookup_element(name) {
+            Ok(ElementType::Component(c)) if c.is_global() => {
+                let properties = c
+                    .root_element
+                    .borrow()
+                    .property_declarations
+                    .iter()
+                    .filter(|(_, d)| {
+                        !matches!(d.property_type, Type::Callback(_) | Type::Function(_))
+                            && matches!(
+                                d.visibility,
+                                PropertyVisibility::Input
+                                    | PropertyVisibility::Output
+                                    | PropertyVisibility::InOut
+                            )
+                    })
+                    .map(|(n, d)| {
+                        (
+                            n.clone(),
+                            d.property_type.clone(),
+                            d.visibility != PropertyVisibility::Output,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                for (_, ty, _) in &properties {
+                    generator.declare_type(ty)?;
+                }
+                global = Some(properties);
+            }
+            _ => return Err(format!("{name} is not a struct, enum or global").into()),
+        },
+    }
+
+    let mut out = format!(
+        "// Generated from `{name}` in {}\n\n",
+        document.node.as_ref().map(|n| n.source_file.path().to_string_lossy()).unwrap_or_default()
+    );
+    for declaration in &generator.declarations {
+        match declaration {
+            Declaration::Struct(n, fields) => generator.write_struct(&mut out, n, fields),
+            Declaration::Enum(e) => generator.write_enum(&mut out, e),
+        }
+    }
+    if let Some(properties) = global {
+        generator.write_global(&mut out, name, &properties);
+    }
+    if generator.uses_arrays {
+        out.push_str(ARRAY_HELPERS);
+    }
+    Ok(out.trim_end().to_string() + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate(source: &str, name: &str) -> common::Result<String> {
+        let (document_cache, url, _) = crate::language::test::loaded_document_cache(source.into());
+        let document = document_cache.get_document(&url).unwrap();
+        generate_rust_glue(document, name, "crate::ui")
+    }
+
+    #[test]
+    fn test_struct_glue() {
+        let source = r#"
+enum Kind { small, extra-large }
+struct Inner { kind: Kind, tags: [string] }
+export struct Outer { display-name: string, size: length, inner: Inner, items: [Inner] }
+export component Main {}
+"#;
+        let glue = generate(source, "Outer").unwrap();
+
+        assert!(glue.contains("pub struct Outer {\n    pub display_name: slint::SharedString,\n    pub inner: Inner,\n    pub items: Vec<Inner>,\n    pub size: f32,\n}"));
+        assert!(glue.contains(
+            "pub struct Inner {\n    pub kind: Kind,\n    pub tags: Vec<slint::SharedString>,\n}"
+        ));
+        assert!(glue.contains("pub enum Kind {\n    #[default]\n    Small,\n    ExtraLarge,\n}"));
+        assert!(glue.contains(r#"s.set_field("display-name".into(), value.display_name.into());"#));
+        assert!(glue.contains(r#"Kind::ExtraLarge => "extra-large","#));
+        assert!(glue.contains("impl From<Outer> for crate::ui::Outer {"));
+        assert!(glue.contains("impl From<crate::ui::Kind> for Kind {"));
+        assert!(glue.contains("fn vec_from_value<"));
+        // Every type is declared once
+        assert_eq!(glue.matches("pub struct Inner").count(), 1);
+
+        let glue = generate(source, "Kind").unwrap();
+        assert!(!glue.contains("struct"));
+        assert!(!glue.contains("fn vec_from_value<"));
+    }
+
+    #[test]
+    fn test_global_glue() {
+        let source = r#"
+export global Settings {
+    in-out property <bool> dark-mode;
+    out property <int> count;
+    in property <duration> delay;
+    property <string> private-state;
+    callback changed();
+}
+export component Main {}
+"#;
+        let glue = generate(source, "Settings").unwrap();
+
+        assert!(glue.contains(
+            "pub struct Settings {\n    pub count: i32,\n    pub dark_mode: bool,\n    pub delay: i64,\n}"
+        ));
+        assert!(glue.contains(r#"count: instance.get_global_property("Settings", "count").ok()?.try_into().map_err(|_| ()).ok()?,"#));
+        assert!(glue.contains(r#"instance.set_global_property("Settings", "dark-mode", self.dark_mode.clone().into())?;"#));
+        assert!(!glue.contains(r#"set_global_property("Settings", "count""#));
+        assert!(glue.contains("pub fn from_global(global: &crate::ui::Settings<'_>) -> Self {"));
+        assert!(glue.contains("global.set_delay(self.delay.clone());"));
+        assert!(!glue.contains("global.set_count("));
+        // A global is no value that can be converted
+        assert!(!glue.contains("impl From<Settings>"));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let source = r#"
+export struct Anonymous { point: { x: int, y: int } }
+export struct Nested { grid: [[int]] }
+export component Main {}
+"#;
+        assert!(generate(source, "Anonymous").is_err());
+        assert!(generate(source, "Nested").is_err());
+        assert!(generate(source, "Main").is_err());
+        assert!(generate(source, "Unknown").is_err());
+    }
+}
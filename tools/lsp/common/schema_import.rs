@@ -0,0 +1,440 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Generate Slint `struct` and `enum` declarations from the data models of a
+//! JSON Schema or OpenAPI document, so that the UI uses the same data model as
+//! the backend.
+//!
+//! The generated file remembers the schema it was generated from, so that it
+//! can be re-synced whenever the schema changes.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::common;
+
+/// Marks the line of a generated file naming the schema it was generated from
+const SOURCE_MARKER: &str = "// Generated from schema: ";
+
+/// `user_profile` or `userProfile` become `UserProfile`
+fn type_name(name: &str) -> String {
+    let name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+                .collect::<String>()
+        })
+        .collect::<String>();
+    if name.chars().next().is_none_or(|c| c.is_ascii_digit()) {
+        format!("T{name}")
+    } else {
+        name
+    }
+}
+
+/// `user_name` or `userName` become `user-name`
+fn field_name(name: &str) -> String {
+    let mut result = String::new();
+    let mut previous_is_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if previous_is_lower {
+                result.push('-');
+            }
+            result.push(c.to_ascii_lowercase());
+            previous_is_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            result.push(c);
+            previous_is_lower = true;
+        } else if !result.is_empty() && !result.ends_with('-') {
+            result.push('-');
+            previous_is_lower = false;
+        }
+    }
+    let result = result.trim_end_matches('-').to_string();
+    if result.chars().next().is_none_or(|c| c.is_ascii_digit()) {
+        format!("_{result}")
+    } else {
+        result
+    }
+}
+
+fn description(schema: &Value, indent: &str) -> String {
+    schema
+        .get("description")
+        .and_then(Value::as_str)
+        .map(|d| d.lines().map(|l| format!("{indent}// {}\n", l.trim_end())).collect())
+        .unwrap_or_default()
+}
+
+struct Generator<'a> {
+    document: &'a Value,
+    declarations: Vec<String>,
+    declared: HashSet<String>,
+    /// Named schemas currently being declared, to detect recursion
+    in_progress: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+    /// Make sure the schema `name` is declared and return the name of its type
+    fn declare(&mut self, name: &str, schema: &Value) -> common::Result<String> {
+        let name = type_name(name);
+        if self.declared.contains(&name) {
+            return Ok(name);
+        }
+        if self.in_progress.contains(&name) {
+            return Err(format!("{name} refers to itself, which Slint structs can not do").into());
+        }
+
+        self.in_progress.push(name.clone());
+        let declaration = self.declaration(&name, schema);
+        self.in_progress.pop();
+
+        if let Some(declaration) = declaration? {
+            self.declarations.push(declaration);
+            self.declared.insert(name.clone());
+            Ok(name)
+        } else {
+            // Not an object or enum, so there is nothing to declare
+            self.type_of(schema, &name)
+        }
+    }
+
+    /// The `struct` or `enum` declaration for `schema`, if it needs one
+    fn declaration(&mut self, name: &str, schema: &Value) -> common::Result<Option<String>> {
+        let schema = self.resolve(schema)?;
+        let doc = description(schema, "");
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            let values = values
+                .iter()
+                .filter(|v| !v.is_null())
+                .map(|v| {
+                    v.as_str().map(field_name).ok_or_else(|| {
+                        format!("{name}: Only enums of strings are supported, got {v}")
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(Some(format!("{doc}export enum {name} {{ {} }}\n", values.join(", "))));
+        }
+
+        let properties = self.properties(schema, name)?;
+        if properties.is_empty() && !is_object(schema) {
+            return Ok(None);
+        }
+
+        let mut fields = String::new();
+        for (field, schema) in properties {
+            let ty = self.type_of(&schema, &format!("{name}-{field}"))?;
+            fields.push_str(&description(&schema, "    "));
+            fields.push_str(&format!("    {}: {ty},\n", field_name(&field)));
+        }
+        Ok(Some(format!("{doc}export struct {name} {{\n{fields}}}\n")))
+    }
+
+    /// The properties of an object schema, including the ones merged in via `allOf`
+    fn properties(&self, schema: &Value, name: &str) -> common::Result<Vec<(String, Value)>> {
+        let mut result = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .map(|p| p.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for part in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+            for (field, schema) in self.properties(self.resolve(part)?, name)? {
+                if result.iter().any(|(f, _)| *f == field) {
+                    return Err(format!("{name}: Property {field} is defined twice").into());
+                }
+                result.push((field, schema));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Follow a `$ref` to the schema it points to
+    fn resolve<'b>(&self, schema: &'b Value) -> common::Result<&'b Value>
+    where
+        'a: 'b,
+    {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.document.pointer(pointer))
+                .ok_or_else(|| format!("Can not resolve reference {reference}").into()),
+            None => Ok(schema),
+        }
+    }
+
+    /// The Slint type of `schema`, declaring structs and enums as needed
+    ///
+    /// `name_hint` names anonymous objects and enums.
+    fn type_of(&mut self, schema: &Value, name_hint: &str) -> common::Result<String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = self.resolve(schema)?.clone();
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+            return self.declare(name, &target);
+        }
+
+        for key in ["anyOf", "oneOf"] {
+            if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+                let variants = variants.iter().filter(|v| !is_null(v)).collect::<Vec<_>>();
+                return match variants.as_slice() {
+                    [variant] => self.type_of(variant, name_hint),
+                    _ => {
+                        Err(format!("{name_hint}: Unions of several types are not supported")
+                            .into())
+                    }
+                };
+            }
+        }
+        if let Some([part]) = schema.get("allOf").and_then(Value::as_array).map(Vec::as_slice) {
+            if schema.get("properties").is_none() {
+                return self.type_of(part, name_hint);
+            }
+        }
+
+        let ty = match schema.get("type") {
+            Some(Value::Array(types)) => {
+                let types = types.iter().filter(|t| t.as_str() != Some("null")).collect::<Vec<_>>();
+                match types.as_slice() {
+                    [ty] => ty.as_str(),
+                    _ => {
+                        return Err(format!(
+                            "{name_hint}: Unions of several types are not supported"
+                        )
+                        .into())
+                    }
+                }
+            }
+            Some(ty) => ty.as_str(),
+            None => None,
+        };
+
+        match ty {
+            _ if schema.get("enum").is_some() => self.declare(name_hint, schema),
+            Some("string") => Ok("string".into()),
+            Some("integer") => Ok("int".into()),
+            Some("number") => Ok("float".into()),
+            Some("boolean") => Ok("bool".into()),
+            Some("array") => {
+                let items = schema
+                    .get("items")
+                    .ok_or_else(|| format!("{name_hint}: Arrays need to define their items"))?;
+                Ok(format!("[{}]", self.type_of(items, &format!("{name_hint}-item"))?))
+            }
+            _ if schema.get("properties").is_some() || schema.get("allOf").is_some() => {
+                self.declare(name_hint, schema)
+            }
+            Some("object") => {
+                Err(format!("{name_hint}: Objects without properties are not supported").into())
+            }
+            _ => Err(format!("{name_hint}: The schema does not define a type").into()),
+        }
+    }
+}
+
+fn is_null(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+fn is_object(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+        || schema.get("properties").is_some()
+        || schema.get("allOf").is_some()
+}
+
+/// Generate the contents of a `.slint` file declaring all data models in `document`
+///
+/// `document` is either an OpenAPI document, whose `components/schemas` (or
+/// `definitions` for Swagger 2) get declared, or a JSON Schema, which declares
+/// its `$defs` and `definitions` and, if it is an object itself, a struct named
+/// after its `title` or `root_name`. `source` ends up in the header of the file.
+pub fn generate_slint(document: &Value, root_name: &str, source: &str) -> common::Result<String> {
+    let mut generator =
+        Generator { document, declarations: vec![], declared: HashSet::new(), in_progress: vec![] };
+
+    let named = ["/components/schemas", "/definitions", "/$defs"]
+        .iter()
+        .filter_map(|pointer| document.pointer(pointer).and_then(Value::as_object))
+        .flat_map(|schemas| schemas.iter())
+        .collect::<Vec<_>>();
+    for (name, schema) in &named {
+        generator.declare(name, schema)?;
+    }
+
+    if document.get("openapi").is_none() && document.get("swagger").is_none() && is_object(document)
+    {
+        let name = document.get("title").and_then(Value::as_str).unwrap_or(root_name);
+        generator.declare(name, document)?;
+    }
+
+    if generator.declarations.is_empty() {
+        return Err("The schema does not contain any data models".into());
+    }
+
+    Ok(format!(
+        "{SOURCE_MARKER}{source}\n// Changes to this file get lost when re-syncing it with the schema.\n\n{}",
+        generator.declarations.join("\n")
+    ))
+}
+
+/// The schema a generated `.slint` file was generated from, as written into its header
+pub fn schema_source(slint_source: &str) -> Option<&str> {
+    slint_source.lines().next()?.strip_prefix(SOURCE_MARKER).map(str::trim)
+}
+
+/// Generate the `.slint` file at `output` from the JSON file at `schema`
+///
+/// The schema is referred to relative to `output` in the header of the file.
+pub fn import_schema(schema: &Path, output: &Path) -> common::Result<()> {
+    let data = std::fs::read(schema).map_err(|e| format!("Failed to read {schema:?}: {e}"))?;
+    let document: Value = serde_json::from_slice(&data)
+        .map_err(|e| format!("{schema:?} is not a JSON document: {e}"))?;
+
+    let output_dir = i_slint_compiler::pathutils::dirname(output);
+    let source = if schema.is_absolute() == output_dir.is_absolute() {
+        common::asset_bundle::relative_path(&output_dir, schema)
+    } else {
+        schema.to_string_lossy().to_string()
+    };
+    let root_name = schema.file_stem().unwrap_or_default().to_string_lossy();
+
+    let text = generate_slint(&document, &root_name, &source)?;
+    std::fs::write(output, text).map_err(|e| format!("Failed to write {output:?}: {e}").into())
+}
+
+/// Regenerate the `.slint` file at `path` from the schema named in its header
+///
+/// Returns the path of the schema.
+pub fn resync_schema(path: &Path) -> common::Result<PathBuf> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+    let source =
+        schema_source(&text).ok_or_else(|| format!("{path:?} was not generated from a schema"))?;
+    let schema = i_slint_compiler::pathutils::join(
+        &i_slint_compiler::pathutils::dirname(path),
+        Path::new(source),
+    )
+    .ok_or_else(|| format!("Invalid schema location {source}"))?;
+    let schema = i_slint_compiler::pathutils::clean_path(&schema);
+    import_schema(&schema, path)?;
+    Ok(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(type_name("user_profile"), "UserProfile");
+        assert_eq!(type_name("userProfile"), "UserProfile");
+        assert_eq!(type_name("Pet-Item"), "PetItem");
+        assert_eq!(type_name("2fa"), "T2fa");
+        assert_eq!(field_name("userName"), "user-name");
+        assert_eq!(field_name("user_name"), "user-name");
+        assert_eq!(field_name("ID"), "id");
+        assert_eq!(field_name("in-stock"), "in-stock");
+        assert_eq!(field_name("2fa"), "_2fa");
+    }
+
+    #[test]
+    fn test_generate_from_openapi() {
+        let document = serde_json::json!({
+            "openapi": "3.0.0",
+            "components": { "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "description": "A pet in the store",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "age": { "type": "integer", "description": "In years" },
+                        "weight": { "type": ["number", "null"] },
+                        "status": { "type": "string", "enum": ["available", "sold", "onHold"] },
+                        "owner": { "$ref": "#/components/schemas/person" },
+                        "tags": { "type": "array", "items": {
+                            "type": "object",
+                            "properties": { "label": { "type": "string" } }
+                        } },
+                    }
+                },
+                "person": {
+                    "allOf": [
+                        { "properties": { "name": { "type": "string" } } },
+                        { "properties": { "vip": { "anyOf": [{ "type": "boolean" }, { "type": "null" }] } } }
+                    ]
+                },
+            } }
+        });
+
+        let result = generate_slint(&document, "api", "api.json").unwrap();
+        assert_eq!(schema_source(&result), Some("api.json"));
+        assert_eq!(
+            result,
+            r#"// Generated from schema: api.json
+// Changes to this file get lost when re-syncing it with the schema.
+
+export struct Person {
+    name: string,
+    vip: bool,
+}
+
+export enum PetStatus { available, sold, on-hold }
+
+export struct PetTagsItem {
+    label: string,
+}
+
+// A pet in the store
+export struct Pet {
+    // In years
+    age: int,
+    name: string,
+    owner: Person,
+    status: PetStatus,
+    tags: [PetTagsItem],
+    weight: float,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_generate_from_json_schema() {
+        let document = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "size": { "$ref": "#/$defs/size" },
+                "scores": { "type": "array", "items": { "type": "number" } }
+            },
+            "$defs": { "size": { "type": "string", "enum": ["small", "large"] } }
+        });
+        let result = generate_slint(&document, "settings", "settings.json").unwrap();
+        assert!(result.contains("export enum Size { small, large }\n"));
+        assert!(
+            result.contains("export struct Settings {\n    scores: [float],\n    size: Size,\n}\n")
+        );
+
+        let recursive = serde_json::json!({ "$defs": { "node": {
+            "type": "object",
+            "properties": { "children": { "type": "array", "items": { "$ref": "#/$defs/node" } } }
+        } } });
+        assert!(generate_slint(&recursive, "tree", "tree.json").is_err());
+
+        let map = serde_json::json!({ "type": "object", "properties": {
+            "extra": { "type": "object", "additionalProperties": { "type": "string" } }
+        } });
+        assert!(generate_slint(&map, "map", "map.json").is_err());
+
+        assert!(generate_slint(&serde_json::json!({ "type": "string" }), "x", "x.json").is_err());
+        assert_eq!(schema_source("export struct Foo {}"), None);
+    }
+}
@@ -0,0 +1,511 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Find elements by their structure and rewrite them from a template.
+//!
+//! A query lists element patterns from the outermost to the innermost one, like
+//! `Flickable > VerticalLayout Image[source, !width]`:
+//!
+//! * `Image` matches elements of that type, `*` elements of any type, `Image#logo` the one
+//!   with id `logo`.
+//! * `[name]` requires a binding or declaration of the property or callback `name`, `[!name]`
+//!   requires there to be none, `[name: value]` requires the binding to be `value`.
+//! * `A > B` matches `B` that are direct children of an `A`, `A B` those nested anywhere in
+//!   an `A`.
+//! * `!A > B` and `!A B` match `B` that are not a child or not nested in an `A`.
+//!
+//! The last pattern of the query is the element that matches. A template replaces it, with
+//! `$element` standing for the element's code, `$type` for its type, `$id` for its id,
+//! `$body` for the code between its braces and `$[name]` for the binding of property `name`.
+//! `Rectangle { clip: true; $element }` wraps every match in a clipping `Rectangle`.
+
+use i_slint_compiler::parser::{SyntaxKind, SyntaxNode, TextRange};
+use lsp_types::Url;
+
+use crate::common;
+use crate::util;
+
+/// A condition on the bindings of an element
+#[derive(Clone, Debug, PartialEq)]
+struct Condition {
+    name: String,
+    negated: bool,
+    value: Option<String>,
+}
+
+/// One element pattern of a query
+#[derive(Clone, Debug, PartialEq)]
+struct Step {
+    /// Must not match the element's parent or ancestors, instead of matching one
+    negated: bool,
+    /// Applies to the direct parent only, not to all ancestors
+    direct: bool,
+    /// `None` for any type
+    type_name: Option<String>,
+    id: Option<String>,
+    conditions: Vec<Condition>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    /// Outermost pattern first
+    steps: Vec<Step>,
+}
+
+/// An element matching a query
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub url: Url,
+    /// The code of the element, including its id
+    pub range: lsp_types::Range,
+    /// The offset of the element, as the preview selects elements by
+    pub offset: u32,
+    /// The type and id of the element, like `Image#logo`
+    pub label: String,
+}
+
+/// `text` with runs of whitespace collapsed and without a trailing `;`
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").trim_end_matches(';').trim().to_string()
+}
+
+fn parse_conditions(text: &str) -> Result<Vec<Condition>, String> {
+    text.split(',')
+        .map(|condition| {
+            let condition = condition.trim();
+            let (negated, condition) = match condition.strip_prefix('!') {
+                Some(c) => (true, c.trim()),
+                None => (false, condition),
+            };
+            let (name, value) = match condition.split_once(':') {
+                Some((name, value)) => (name.trim(), Some(normalize(value))),
+                None => (condition, None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || "-_".contains(c)) {
+                return Err(format!("Invalid property name \"{name}\""));
+            }
+            Ok(Condition { name: name.to_string(), negated, value })
+        })
+        .collect()
+}
+
+fn parse_step(text: &str, direct: bool) -> Result<Step, String> {
+    let (negated, text) = match text.strip_prefix('!') {
+        Some(t) => (true, t),
+        None => (false, text),
+    };
+    let (head, mut rest) = text.split_at(text.find('[').unwrap_or(text.len()));
+    let (type_name, id) = match head.split_once('#') {
+        Some((type_name, id)) => (type_name, Some(id.to_string())),
+        None => (head, None),
+    };
+    if type_name.is_empty() {
+        return Err(format!("\"{text}\" lacks an element type, use * for any"));
+    }
+    let type_name = (type_name != "*").then(|| type_name.to_string());
+
+    let mut conditions = vec![];
+    while !rest.is_empty() {
+        let Some(end) = rest.find(']').filter(|_| rest.starts_with('[')) else {
+            return Err(format!("Unbalanced brackets in \"{text}\""));
+        };
+        conditions.extend(parse_conditions(&rest[1..end])?);
+        rest = &rest[end + 1..];
+    }
+    Ok(Step { negated, direct, type_name, id, conditions })
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // Split into patterns at whitespace and `>`, but not inside of brackets
+        let mut tokens = vec![];
+        let mut current = String::new();
+        let mut depth = 0;
+        for c in text.chars() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 && (c.is_whitespace() || c == '>') {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                if c == '>' {
+                    tokens.push(">".into());
+                }
+            } else {
+                current.push(c);
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+
+        let mut steps = vec![];
+        let mut direct = false;
+        for token in tokens {
+            if token == ">" {
+                if direct || steps.is_empty() {
+                    return Err("\">\" must stand between two element patterns".into());
+                }
+                direct = true;
+                continue;
+            }
+            steps.push(parse_step(&token, direct)?);
+            direct = false;
+        }
+        if direct {
+            return Err("\">\" must stand between two element patterns".into());
+        }
+        match steps.last() {
+            None => Err("The query is empty".into()),
+            Some(step) if step.negated => {
+                Err("The last pattern names the elements to find and can not be negated".into())
+            }
+            Some(_) => Ok(Self { steps }),
+        }
+    }
+
+    /// Whether the `Element` node `element` matches the query
+    pub fn matches(&self, element: &SyntaxNode) -> bool {
+        self.matches_step(self.steps.len() - 1, element)
+    }
+
+    fn matches_step(&self, index: usize, element: &SyntaxNode) -> bool {
+        step_matches(&self.steps[index], element) && self.matches_context(index, element)
+    }
+
+    /// Whether the patterns before `index` match around `element`
+    fn matches_context(&self, index: usize, element: &SyntaxNode) -> bool {
+        if index == 0 {
+            return true;
+        }
+        let direct = self.steps[index].direct;
+        let outer = &self.steps[index - 1];
+        let mut candidates = ancestors(element).take(if direct { 1 } else { usize::MAX });
+        if outer.negated {
+            // A negated pattern only filters, the patterns before it apply to `element` again
+            !candidates.any(|a| step_matches(outer, &a)) && self.matches_context(index - 1, element)
+        } else {
+            candidates.any(|a| self.matches_step(index - 1, &a))
+        }
+    }
+}
+
+/// The elements `element` is nested in, innermost first
+fn ancestors(element: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> {
+    std::iter::successors(element.parent(), |n| n.parent())
+        .filter(|n| n.kind() == SyntaxKind::Element)
+}
+
+fn type_name(element: &SyntaxNode) -> Option<String> {
+    element.child_node(SyntaxKind::QualifiedName).map(|n| n.text().to_string().trim().to_string())
+}
+
+fn element_id(element: &SyntaxNode) -> Option<String> {
+    element
+        .parent()
+        .filter(|p| p.kind() == SyntaxKind::SubElement)?
+        .child_text(SyntaxKind::Identifier)
+        .map(|id| id.to_string())
+}
+
+/// The properties and callbacks `element` binds or declares, with their normalized binding
+fn bindings(element: &SyntaxNode) -> Vec<(String, Option<String>)> {
+    let expression = |node: &SyntaxNode| {
+        node.child_node(SyntaxKind::BindingExpression)
+            .or_else(|| {
+                node.child_node(SyntaxKind::TwoWayBinding)?.child_node(SyntaxKind::Expression)
+            })
+            .or_else(|| node.child_node(SyntaxKind::Expression))
+            .map(|e| normalize(&e.text().to_string()))
+    };
+    element
+        .children()
+        .filter_map(|child| match child.kind() {
+            SyntaxKind::Binding | SyntaxKind::TwoWayBinding => {
+                Some((child.child_text(SyntaxKind::Identifier)?.to_string(), expression(&child)))
+            }
+            SyntaxKind::PropertyDeclaration => Some((
+                child.child_node(SyntaxKind::DeclaredIdentifier)?.text().to_string().trim().into(),
+                expression(&child),
+            )),
+            SyntaxKind::CallbackConnection | SyntaxKind::CallbackDeclaration => {
+                let name = child
+                    .child_text(SyntaxKind::Identifier)
+                    .map(|n| n.to_string())
+                    .or_else(|| {
+                        Some(child.child_node(SyntaxKind::DeclaredIdentifier)?.text().to_string())
+                    })?;
+                Some((name.trim().to_string(), None))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn step_matches(step: &Step, element: &SyntaxNode) -> bool {
+    if step.type_name.as_ref().is_some_and(|t| Some(t) != type_name(element).as_ref()) {
+        return false;
+    }
+    if step.id.is_some() && step.id != element_id(element) {
+        return false;
+    }
+    let bindings = bindings(element);
+    step.conditions.iter().all(|condition| {
+        let found = bindings.iter().any(|(name, value)| {
+            name == &condition.name
+                && condition.value.as_ref().is_none_or(|v| value.as_ref() == Some(v))
+        });
+        found != condition.negated
+    })
+}
+
+/// The node holding the code of `element`, with its id
+fn element_code(element: &SyntaxNode) -> SyntaxNode {
+    element
+        .parent()
+        .filter(|p| p.kind() == SyntaxKind::SubElement)
+        .unwrap_or_else(|| element.clone())
+}
+
+/// All elements in the documents of the cache matching `query`, in source order
+pub fn find(document_cache: &common::DocumentCache, query: &Query) -> Vec<Match> {
+    let mut matches = vec![];
+    for (url, document) in document_cache.all_url_documents() {
+        if url.scheme() == "builtin" {
+            continue;
+        }
+        for element in document.descendants().filter(|n| n.kind() == SyntaxKind::Element) {
+            if !query.matches(&element) {
+                continue;
+            }
+            let label = match element_id(&element) {
+                Some(id) => format!("{}#{id}", type_name(&element).unwrap_or_default()),
+                None => type_name(&element).unwrap_or_default(),
+            };
+            matches.push(Match {
+                url: url.clone(),
+                range: util::node_to_lsp_range(&element_code(&element)),
+                offset: element.text_range().start().into(),
+                label,
+            });
+        }
+    }
+    matches
+}
+
+/// The text `template` turns `element` into. Lines of the template get indented by
+/// `indentation`, and lines of the code put into the template by the indentation of the line
+/// of the template it gets put in.
+fn expand(template: &str, element: &SyntaxNode, indentation: &str) -> Result<String, String> {
+    let code = element_code(element);
+    let body =
+        match (element.child_token(SyntaxKind::LBrace), element.child_token(SyntaxKind::RBrace)) {
+            (Some(l), Some(r)) => {
+                let start = l.text_range().end() - element.text_range().start();
+                let end = r.text_range().start() - element.text_range().start();
+                element.text().to_string()[TextRange::new(start, end)].trim().to_string()
+            }
+            _ => String::new(),
+        };
+    let bindings = bindings(element);
+    let template_indentation = format!("\n{indentation}");
+
+    let mut result = String::new();
+    let mut rest = template;
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index].replace('\n', &template_indentation));
+        let line = &template[..template.len() - rest.len() + index];
+        let line = &line[line.rfind('\n').map_or(0, |i| i + 1)..];
+        let line_indentation = &line[..line.len() - line.trim_start().len()];
+        rest = &rest[index..];
+
+        let (replacement, length) = if rest.starts_with("$element") {
+            (code.text().to_string(), "$element".len())
+        } else if rest.starts_with("$type") {
+            (type_name(element).unwrap_or_default(), "$type".len())
+        } else if rest.starts_with("$id") {
+            (element_id(element).unwrap_or_default(), "$id".len())
+        } else if rest.starts_with("$body") {
+            (body.clone(), "$body".len())
+        } else if let Some(name) = rest.strip_prefix("$[").and_then(|r| Some(&r[..r.find(']')?])) {
+            let value = bindings
+                .iter()
+                .find(|(n, _)| n == name.trim())
+                .and_then(|(_, v)| v.clone())
+                .ok_or_else(|| format!("{} has no binding for {}", code.text(), name.trim()))?;
+            (value, name.len() + 3)
+        } else {
+            ("$".to_string(), 1)
+        };
+        result.push_str(&replacement.replace('\n', &format!("\n{line_indentation}")));
+        rest = &rest[length..];
+    }
+    result.push_str(&rest.replace('\n', &template_indentation));
+    Ok(result)
+}
+
+/// Rewrite all elements matching `query` with `template`. Matches nested in another match are
+/// left alone, as their code is part of that match's replacement.
+pub fn replace(
+    document_cache: &common::DocumentCache,
+    query: &Query,
+    template: &str,
+) -> Result<(lsp_types::WorkspaceEdit, usize), String> {
+    let mut edits = vec![];
+    let mut count = 0;
+    for (url, document) in document_cache.all_url_documents() {
+        if url.scheme() == "builtin" {
+            continue;
+        }
+        let source = document.text().to_string();
+        let mut replaced: Vec<TextRange> = vec![];
+        let mut text_edits = vec![];
+        for element in document.descendants().filter(|n| n.kind() == SyntaxKind::Element) {
+            let code = element_code(&element);
+            let range = code.text_range();
+            if replaced.iter().any(|r| r.contains_range(range)) || !query.matches(&element) {
+                continue;
+            }
+            let start = usize::from(range.start() - document.text_range().start());
+            let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+            let indentation = &source[line_start..start];
+            let indentation = if indentation.trim().is_empty() { indentation } else { "" };
+            text_edits.push(lsp_types::TextEdit {
+                range: util::node_to_lsp_range(&code),
+                new_text: expand(template, &element, indentation)?,
+            });
+            replaced.push(range);
+        }
+        if !text_edits.is_empty() {
+            count += text_edits.len();
+            let version = document_cache.document_version(&url);
+            edits.push(common::create_text_document_edit(url, version, text_edits));
+        }
+    }
+    if edits.is_empty() {
+        return Err("No element matches the query".into());
+    }
+    Ok((common::create_workspace_edit_from_text_document_edits(edits), count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    const SOURCE: &str = r#"
+export component Main {
+    Rectangle {
+        clip: true;
+        Image { source: @image-url("a.png"); }
+    }
+    logo := Image {
+        source: @image-url("logo.png");
+        width: 20px;
+    }
+    VerticalLayout {
+        Image { }
+        Rectangle { Image { } }
+    }
+}
+"#;
+
+    fn labels(query: &str) -> Vec<(String, u32)> {
+        let (document_cache, _, _) = loaded_document_cache(SOURCE.into());
+        find(&document_cache, &Query::parse(query).unwrap())
+            .into_iter()
+            .map(|m| (m.label, m.range.start.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(Query::parse("").is_err());
+        assert!(Query::parse("> Image").is_err());
+        assert!(Query::parse("Rectangle >").is_err());
+        assert!(Query::parse("Rectangle > > Image").is_err());
+        assert!(Query::parse("!Image").is_err());
+        assert!(Query::parse("Image[source").is_err());
+        assert!(Query::parse("Image[so urce]").is_err());
+        assert!(Query::parse("#logo").is_err());
+
+        let query = Query::parse("!Rectangle>Image#logo[source, !clip][width: 20px ;]").unwrap();
+        assert_eq!(query.steps.len(), 2);
+        assert!(query.steps[0].negated);
+        assert!(query.steps[1].direct);
+        assert_eq!(query.steps[1].id.as_deref(), Some("logo"));
+        assert_eq!(
+            query.steps[1].conditions,
+            vec![
+                Condition { name: "source".into(), negated: false, value: None },
+                Condition { name: "clip".into(), negated: true, value: None },
+                Condition { name: "width".into(), negated: false, value: Some("20px".into()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(labels("Image").len(), 4);
+        assert_eq!(labels("Image[source]"), vec![("Image".into(), 4), ("Image#logo".into(), 6)]);
+        assert_eq!(labels("Image[!source]").len(), 2);
+        assert_eq!(labels("Image[width: 20px]"), vec![("Image#logo".into(), 6)]);
+        assert_eq!(labels("*#logo"), vec![("Image#logo".into(), 6)]);
+        assert_eq!(labels("Rectangle[clip: true] > Image"), vec![("Image".into(), 4)]);
+        assert_eq!(
+            labels("VerticalLayout Image"),
+            vec![("Image".into(), 11), ("Image".into(), 12)]
+        );
+        assert_eq!(labels("VerticalLayout > Image"), vec![("Image".into(), 11)]);
+        // The bare images, not wrapped in a Rectangle
+        assert_eq!(
+            labels("!Rectangle > Image"),
+            vec![("Image#logo".into(), 6), ("Image".into(), 11)]
+        );
+        assert_eq!(
+            labels("!Rectangle VerticalLayout Image"),
+            vec![("Image".into(), 11), ("Image".into(), 12)]
+        );
+    }
+
+    #[test]
+    fn test_replace() {
+        let (document_cache, url, _) = loaded_document_cache(SOURCE.into());
+        let query = Query::parse("!Rectangle > Image").unwrap();
+        let (edit, count) =
+            replace(&document_cache, &query, "Rectangle {\n    clip: true;\n    $element\n}")
+                .unwrap();
+        assert_eq!(count, 2);
+
+        let edits = common::text_edit::EditIterator::new(&edit)
+            .map(|(document, edit)| (document.uri, edit.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[0].0, url);
+        assert_eq!(
+            edits[0].1.new_text,
+            "Rectangle {\n        clip: true;\n        logo := Image {\n            source: @image-url(\"logo.png\");\n            width: 20px;\n        }\n    }"
+        );
+        assert_eq!(
+            edits[1].1.new_text,
+            "Rectangle {\n            clip: true;\n            Image { }\n        }"
+        );
+
+        let query = Query::parse("Image#logo").unwrap();
+        let (edit, _) =
+            replace(&document_cache, &query, "$type { $body height: $[width]; }").unwrap();
+        let edits = common::text_edit::EditIterator::new(&edit).collect::<Vec<_>>();
+        assert_eq!(
+            edits[0].1.new_text,
+            "Image { source: @image-url(\"logo.png\");\n        width: 20px; height: 20px; }"
+        );
+
+        assert!(replace(&document_cache, &query, "$[height]").is_err());
+        let query = Query::parse("Text").unwrap();
+        assert!(replace(&document_cache, &query, "$element").is_err());
+    }
+}
@@ -67,6 +67,22 @@ pub fn test_file_name(name: &str) -> PathBuf {
     test_file_prefix().join(name)
 }
 
+/// An exported builtin element called `name` for the library, in `category`
+pub fn test_component(name: &str, category: &str) -> common::ComponentInformation {
+    common::ComponentInformation {
+        name: name.to_string(),
+        category: category.to_string(),
+        is_global: false,
+        is_builtin: true,
+        is_std_widget: false,
+        is_exported: true,
+        is_interactive: false,
+        is_layout: false,
+        defined_at: None,
+        default_properties: Vec::new(),
+    }
+}
+
 #[track_caller]
 pub fn compile_test_with_sources(
     style: &str,
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
 use std::collections::HashMap;
+use std::rc::Rc;
 
 use i_slint_compiler::parser::TextSize;
 
@@ -143,22 +144,29 @@ fn next(&mut self) -> Option<Self::Item> {
                 self.next()
             }
             EditIteratorState::DocumentChanges { main_index, index } => {
-                if let Some(lsp_types::DocumentChanges::Edits(edits)) =
-                    &self.workspace_edit.document_changes
-                {
-                    if let Some(doc_edit) = edits.get(*main_index) {
-                        if let Some(edit) = doc_edit.edits.get(*index) {
-                            *index += 1;
-                            let te = match edit {
-                                lsp_types::OneOf::Left(te) => te,
-                                lsp_types::OneOf::Right(ate) => &ate.text_edit,
-                            };
-                            return Some((doc_edit.text_document.clone(), te));
-                        } else {
-                            *index = 0;
-                            *main_index += 1;
-                            return self.next();
-                        }
+                let doc_edits = match &self.workspace_edit.document_changes {
+                    Some(lsp_types::DocumentChanges::Edits(edits)) => edits.iter().collect(),
+                    Some(lsp_types::DocumentChanges::Operations(operations)) => operations
+                        .iter()
+                        .filter_map(|o| match o {
+                            lsp_types::DocumentChangeOperation::Edit(e) => Some(e),
+                            lsp_types::DocumentChangeOperation::Op(_) => None,
+                        })
+                        .collect(),
+                    None => Vec::new(),
+                };
+                if let Some(doc_edit) = doc_edits.get(*main_index).copied() {
+                    if let Some(edit) = doc_edit.edits.get(*index) {
+                        *index += 1;
+                        let te = match edit {
+                            lsp_types::OneOf::Left(te) => te,
+                            lsp_types::OneOf::Right(ate) => &ate.text_edit,
+                        };
+                        return Some((doc_edit.text_document.clone(), te));
+                    } else {
+                        *index = 0;
+                        *main_index += 1;
+                        return self.next();
                     }
                 }
 
@@ -238,22 +246,48 @@ pub struct EditedText {
     pub contents: String,
 }
 
+/// The files created by `workspace_edit`
+pub fn created_files(workspace_edit: &lsp_types::WorkspaceEdit) -> Vec<&lsp_types::Url> {
+    let Some(lsp_types::DocumentChanges::Operations(operations)) = &workspace_edit.document_changes
+    else {
+        return Vec::new();
+    };
+    operations
+        .iter()
+        .filter_map(|o| match o {
+            lsp_types::DocumentChangeOperation::Op(lsp_types::ResourceOp::Create(c)) => {
+                Some(&c.uri)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn apply_workspace_edit(
     document_cache: &common::DocumentCache,
     workspace_edit: &lsp_types::WorkspaceEdit,
 ) -> common::Result<Vec<EditedText>> {
     let mut processing = HashMap::new();
+    let created_files = created_files(workspace_edit);
 
     for (doc, edit) in EditIterator::new(workspace_edit) {
         // This is ugly but necessary since the constructor might error out:-/
         if !processing.contains_key(&doc.uri) {
-            let Some(document) = document_cache.get_document(&doc.uri) else {
-                continue;
-            };
-            let Some(document_node) = &document.node else {
-                continue;
+            let source_file = if created_files.contains(&&doc.uri) {
+                let Some(path) = common::uri_to_file(&doc.uri) else {
+                    continue;
+                };
+                Rc::new(i_slint_compiler::diagnostics::SourceFileInner::new(path, String::new()))
+            } else {
+                let Some(document) = document_cache.get_document(&doc.uri) else {
+                    continue;
+                };
+                let Some(document_node) = &document.node else {
+                    continue;
+                };
+                document_node.source_file.clone()
             };
-            let editor = TextEditor::new(document_node.source_file.clone())?;
+            let editor = TextEditor::new(source_file)?;
             processing.insert(doc.uri.clone(), editor);
         }
 
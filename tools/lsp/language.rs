@@ -9,6 +9,7 @@
 mod hover;
 mod semantic_tokens;
 mod signature_help;
+pub mod spell_check;
 #[cfg(test)]
 pub mod test;
 
@@ -45,12 +46,36 @@
 
 const POPULATE_COMMAND: &str = "slint/populate";
 pub const SHOW_PREVIEW_COMMAND: &str = "slint/showPreview";
+#[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+const FLASH_ELEMENT_COMMAND: &str = "slint/flashElement";
+#[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+const REPORT_ISSUE_COMMAND: &str = "slint/reportIssue";
+#[cfg(not(target_arch = "wasm32"))]
+const EXPORT_BUNDLE_COMMAND: &str = "slint/exportBundle";
+#[cfg(not(target_arch = "wasm32"))]
+const IMPORT_SCHEMA_COMMAND: &str = "slint/importSchema";
+#[cfg(not(target_arch = "wasm32"))]
+const RESYNC_SCHEMA_COMMAND: &str = "slint/resyncSchema";
+const GENERATE_RUST_GLUE_COMMAND: &str = "slint/generateRustGlue";
+const STRUCTURAL_SEARCH_COMMAND: &str = "slint/structuralSearch";
 
 fn command_list() -> Vec<String> {
     vec![
         POPULATE_COMMAND.into(),
+        #[cfg(not(target_arch = "wasm32"))]
+        EXPORT_BUNDLE_COMMAND.into(),
+        #[cfg(not(target_arch = "wasm32"))]
+        IMPORT_SCHEMA_COMMAND.into(),
+        #[cfg(not(target_arch = "wasm32"))]
+        RESYNC_SCHEMA_COMMAND.into(),
+        GENERATE_RUST_GLUE_COMMAND.into(),
+        STRUCTURAL_SEARCH_COMMAND.into(),
         #[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
         SHOW_PREVIEW_COMMAND.into(),
+        #[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+        FLASH_ELEMENT_COMMAND.into(),
+        #[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+        REPORT_ISSUE_COMMAND.into(),
     ]
 }
 
@@ -150,6 +175,8 @@ pub struct Context {
     pub to_show: RefCell<Option<common::PreviewComponent>>,
     /// File currently open in the editor
     pub open_urls: RefCell<HashSet<lsp_types::Url>>,
+    /// Set when spell checking is enabled and a dictionary was found
+    pub spell_checker: RefCell<Option<spell_check::SpellChecker>>,
 }
 
 /// An error from a LSP request
@@ -247,8 +274,13 @@ pub fn server_initialize_result(client_cap: &ClientCapabilities) -> InitializeRe
                 completion_item: None,
             }),
             definition_provider: Some(OneOf::Left(true)),
-            text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                lsp_types::TextDocumentSyncKind::FULL,
+            text_document_sync: Some(TextDocumentSyncCapability::Options(
+                lsp_types::TextDocumentSyncOptions {
+                    open_close: Some(true),
+                    change: Some(lsp_types::TextDocumentSyncKind::FULL),
+                    save: Some(lsp_types::TextDocumentSyncSaveOptions::Supported(true)),
+                    ..Default::default()
+                },
             )),
             code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
             execute_command_provider: Some(lsp_types::ExecuteCommandOptions {
@@ -355,10 +387,18 @@ pub fn register_request_handlers(rh: &mut RequestHandler) {
     rh.register::<CodeActionRequest, _>(|params, ctx| async move {
         let document_cache = &mut ctx.document_cache.borrow_mut();
 
-        let result = token_descr(document_cache, &params.text_document.uri, &params.range.start)
-            .and_then(|(token, _)| {
-                get_code_actions(document_cache, token, &ctx.init_param.capabilities)
-            });
+        let mut result =
+            token_descr(document_cache, &params.text_document.uri, &params.range.start).and_then(
+                |(token, _)| get_code_actions(document_cache, token, &ctx.init_param.capabilities),
+            );
+        let spelling_fixes = spell_check::quick_fixes(
+            document_cache,
+            &params.text_document.uri,
+            &params.context.diagnostics,
+        );
+        if !spelling_fixes.is_empty() {
+            result.get_or_insert_with(Vec::new).extend(spelling_fixes);
+        }
         Ok(result)
     });
     rh.register::<ExecuteCommand, _>(|params, ctx| async move {
@@ -367,10 +407,38 @@ pub fn register_request_handlers(rh: &mut RequestHandler) {
             show_preview_command(&params.arguments, &ctx)?;
             return Ok(None::<serde_json::Value>);
         }
+        #[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+        if params.command.as_str() == FLASH_ELEMENT_COMMAND {
+            flash_element_command(&params.arguments, &ctx)?;
+            return Ok(None::<serde_json::Value>);
+        }
+        #[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+        if params.command.as_str() == REPORT_ISSUE_COMMAND {
+            report_issue_command(&params.arguments, &ctx);
+            return Ok(None::<serde_json::Value>);
+        }
         if params.command.as_str() == POPULATE_COMMAND {
             populate_command(&params.arguments, &ctx).await?;
             return Ok(None::<serde_json::Value>);
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        if params.command.as_str() == EXPORT_BUNDLE_COMMAND {
+            return export_bundle_command(&params.arguments, &ctx).map(Some);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if params.command.as_str() == IMPORT_SCHEMA_COMMAND {
+            return import_schema_command(&params.arguments).map(Some);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if params.command.as_str() == RESYNC_SCHEMA_COMMAND {
+            return resync_schema_command(&params.arguments).map(Some);
+        }
+        if params.command.as_str() == GENERATE_RUST_GLUE_COMMAND {
+            return generate_rust_glue_command(&params.arguments, &ctx).map(Some);
+        }
+        if params.command.as_str() == STRUCTURAL_SEARCH_COMMAND {
+            return structural_search_command(&params.arguments, &ctx).await.map(Some);
+        }
         Ok(None::<serde_json::Value>)
     });
     rh.register::<DocumentColor, _>(|params, ctx| async move {
@@ -416,6 +484,17 @@ pub fn register_request_handlers(rh: &mut RequestHandler) {
     rh.register::<DocumentHighlightRequest, _>(|params, ctx| async move {
         let document_cache = &mut ctx.document_cache.borrow_mut();
         let uri = params.text_document_position_params.text_document.uri;
+        // Editors ask for the highlights whenever the text cursor moves
+        if let Some((_, offset)) = document_cache
+            .get_document_and_offset(&uri, &params.text_document_position_params.position)
+        {
+            ctx.server_notifier.send_message_to_preview(
+                common::LspToPreviewMessage::EditorCursorMoved {
+                    url: uri.clone(),
+                    offset: offset.into(),
+                },
+            );
+        }
         if let Some((tk, _)) =
             token_descr(document_cache, &uri, &params.text_document_position_params.position)
         {
@@ -585,6 +664,181 @@ pub fn show_preview_command(
     Ok(())
 }
 
+/// Briefly outline the element at the position passed as second parameter in the document
+/// at the url passed as first parameter in the preview
+#[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+pub fn flash_element_command(
+    params: &[serde_json::Value],
+    ctx: &Rc<Context>,
+) -> Result<(), LspError> {
+    let url: Url = extract_param(params, 0, "url")?;
+    let position: lsp_types::Position = extract_param(params, 1, "position")?;
+
+    let document_cache = &ctx.document_cache.borrow();
+    let element = document_cache.element_at_position(&url, &position).ok_or_else(|| LspError {
+        code: LspErrorCode::InvalidParameter,
+        message: "no element at position".into(),
+    })?;
+    let offset = element.with_element_node(|node| node.text_range().start());
+
+    ctx.server_notifier.send_message_to_preview(common::LspToPreviewMessage::FlashElement {
+        url,
+        offset: offset.into(),
+    });
+    Ok(())
+}
+
+/// Save a bug report of what the preview shows, to the file named by the optional first
+/// parameter or next to the previewed file
+#[cfg(any(feature = "preview-builtin", feature = "preview-external"))]
+pub fn report_issue_command(params: &[serde_json::Value], ctx: &Rc<Context>) {
+    let path = params.first().and_then(|p| p.as_str()).filter(|p| !p.is_empty()).map(PathBuf::from);
+    ctx.server_notifier.send_message_to_preview(common::LspToPreviewMessage::ReportIssue { path });
+}
+
+/// Write the document at the url passed as first parameter, together with everything
+/// it uses, into the directory passed as second parameter.
+///
+/// Returns the list of files written.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_bundle_command(
+    params: &[serde_json::Value],
+    ctx: &Rc<Context>,
+) -> Result<serde_json::Value, LspError> {
+    let url: Url = extract_param(params, 0, "url")?;
+    let directory: PathBuf = extract_param(params, 1, "directory")?;
+
+    let path = common::uri_to_file(&url).ok_or_else(|| LspError {
+        code: LspErrorCode::InvalidParameter,
+        message: "invalid document url".into(),
+    })?;
+
+    let files = {
+        let document_cache = &ctx.document_cache.borrow();
+        common::asset_bundle::collect_bundle(document_cache, &path, |p| std::fs::read(p))
+    }
+    .map_err(|e| LspError { code: LspErrorCode::RequestFailed, message: e.to_string() })?;
+
+    common::asset_bundle::write_bundle(&files, &directory).map_err(|e| LspError {
+        code: LspErrorCode::RequestFailed,
+        message: format!("Failed to write bundle to {directory:?}: {e}"),
+    })?;
+
+    Ok(files.iter().map(|f| directory.join(&f.path).to_string_lossy().to_string()).collect())
+}
+
+/// Generate Slint structs from the JSON Schema or OpenAPI document at the path passed as
+/// first parameter into the `.slint` file at the path passed as second parameter.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn import_schema_command(params: &[serde_json::Value]) -> Result<serde_json::Value, LspError> {
+    let schema: PathBuf = extract_param(params, 0, "schema")?;
+    let output: PathBuf = extract_param(params, 1, "output")?;
+
+    common::schema_import::import_schema(&schema, &output)
+        .map_err(|e| LspError { code: LspErrorCode::RequestFailed, message: e.to_string() })?;
+    Ok(output.to_string_lossy().into())
+}
+
+/// Regenerate the `.slint` file at the url passed as first parameter from the schema
+/// it was generated from.
+///
+/// Returns the path of the schema.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn resync_schema_command(params: &[serde_json::Value]) -> Result<serde_json::Value, LspError> {
+    let url: Url = extract_param(params, 0, "url")?;
+    let path = common::uri_to_file(&url).ok_or_else(|| LspError {
+        code: LspErrorCode::InvalidParameter,
+        message: "invalid document url".into(),
+    })?;
+
+    let schema = common::schema_import::resync_schema(&path)
+        .map_err(|e| LspError { code: LspErrorCode::RequestFailed, message: e.to_string() })?;
+    Ok(schema.to_string_lossy().into())
+}
+
+/// Generate Rust types and conversions for the struct, enum or global named by the second
+/// parameter in the document at the url passed as first parameter. The optional third
+/// parameter is the path of the module containing the code generated by Slint.
+///
+/// Returns the generated Rust code.
+pub fn generate_rust_glue_command(
+    params: &[serde_json::Value],
+    ctx: &Rc<Context>,
+) -> Result<serde_json::Value, LspError> {
+    let url: Url = extract_param(params, 0, "url")?;
+    let name: String = extract_param(params, 1, "name")?;
+    let compiled_module = params
+        .get(2)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+        .unwrap_or("crate")
+        .to_string();
+
+    let document_cache = &ctx.document_cache.borrow();
+    let document = document_cache.get_document(&url).ok_or_else(|| LspError {
+        code: LspErrorCode::InvalidParameter,
+        message: "unknown document".into(),
+    })?;
+    common::rust_glue::generate_rust_glue(document, &name, &compiled_module)
+        .map(Into::into)
+        .map_err(|e| LspError { code: LspErrorCode::RequestFailed, message: e.to_string() })
+}
+
+/// Find the elements matching the structural query passed as first parameter in all documents
+/// (see `common::structural_search`). With a template as second parameter, rewrite them.
+///
+/// Returns the locations of the matches, or the number of rewritten elements.
+pub async fn structural_search_command(
+    params: &[serde_json::Value],
+    ctx: &Rc<Context>,
+) -> Result<serde_json::Value, LspError> {
+    let query: String = extract_param(params, 0, "query")?;
+    let template = params.get(1).and_then(|v| v.as_str()).map(|t| t.to_string());
+    let query = common::structural_search::Query::parse(&query)
+        .map_err(|message| LspError { code: LspErrorCode::InvalidParameter, message })?;
+
+    let Some(template) = template else {
+        let document_cache = &ctx.document_cache.borrow();
+        let matches = common::structural_search::find(document_cache, &query)
+            .into_iter()
+            .map(|m| {
+                serde_json::json!({
+                    "location": lsp_types::Location::new(m.url, m.range),
+                    "element": m.label,
+                })
+            })
+            .collect::<Vec<_>>();
+        return Ok(matches.into());
+    };
+
+    let (edit, count) = {
+        let document_cache = &ctx.document_cache.borrow();
+        common::structural_search::replace(document_cache, &query, &template)
+            .map_err(|message| LspError { code: LspErrorCode::RequestFailed, message })?
+    };
+    let response = ctx
+        .server_notifier
+        .send_request::<lsp_types::request::ApplyWorkspaceEdit>(
+            lsp_types::ApplyWorkspaceEditParams { label: Some("Structural replace".into()), edit },
+        )
+        .map_err(|_| LspError {
+            code: LspErrorCode::RequestFailed,
+            message: "Failed to send structural replace edit".into(),
+        })?
+        .await
+        .map_err(|_| LspError {
+            code: LspErrorCode::RequestFailed,
+            message: "Failed to send structural replace edit".into(),
+        })?;
+    if !response.applied {
+        return Err(LspError {
+            code: LspErrorCode::RequestFailed,
+            message: "Failed to apply structural replace edit".into(),
+        });
+    }
+    Ok(count.into())
+}
+
 fn populate_command_range(node: &SyntaxNode) -> Option<lsp_types::Range> {
     let range = node.text_range();
 
@@ -803,7 +1057,7 @@ pub async fn reload_document(
     let (extra_files, diag) =
         reload_document_impl(Some(ctx), content, url.clone(), version, document_cache).await;
 
-    send_diagnostics(&ctx.server_notifier, document_cache, &extra_files, diag);
+    send_diagnostics(ctx, document_cache, &extra_files, diag);
 
     Ok(())
 }
@@ -833,18 +1087,26 @@ pub fn convert_diagnostics(
 }
 
 fn send_diagnostics(
-    _server_notifier: &crate::ServerNotifier,
+    ctx: &Context,
     document_cache: &common::DocumentCache,
     extra_files: &HashSet<PathBuf>,
     diag: BuildDiagnostics,
 ) {
-    let lsp_diags = convert_diagnostics(extra_files, diag);
+    let mut lsp_diags = convert_diagnostics(extra_files, diag);
+    if let Some(spell_checker) = ctx.spell_checker.borrow().as_ref() {
+        for (uri, diagnostics) in lsp_diags.iter_mut() {
+            if let Some(node) = document_cache.get_document(uri).and_then(|d| d.node.as_ref()) {
+                diagnostics.extend(spell_checker.check_document(node));
+            }
+        }
+    }
+
     for (uri, _diagnostics) in lsp_diags {
         let _version = document_cache.document_version(&uri);
 
         #[cfg(feature = "preview-engine")]
         let _ = common::lsp_to_editor::notify_lsp_diagnostics(
-            _server_notifier,
+            &ctx.server_notifier,
             uri,
             _version,
             _diagnostics,
@@ -852,6 +1114,12 @@ fn send_diagnostics(
     }
 }
 
+pub async fn save_document(ctx: &Rc<Context>, url: lsp_types::Url) -> common::Result<()> {
+    // The preview might wait for a save before recompiling
+    ctx.server_notifier.send_message_to_preview(common::LspToPreviewMessage::DocumentSaved { url });
+    Ok(())
+}
+
 pub async fn invalidate_document(ctx: &Rc<Context>, url: lsp_types::Url) -> common::Result<()> {
     // The preview cares about resources and slint files, so forward everything
     ctx.server_notifier.send_message_to_preview(common::LspToPreviewMessage::InvalidateContents {
@@ -1368,7 +1636,7 @@ fn get_code_lenses(
 }
 
 /// If the token is matching a Element ID, return the list of all element id in the same component
-fn find_element_id_for_highlight(
+pub fn find_element_id_for_highlight(
     token: &SyntaxToken,
     parent: &SyntaxNode,
 ) -> Option<Vec<TextRange>> {
@@ -1462,8 +1730,9 @@ pub async fn load_configuration(ctx: &Context) -> common::Result<()> {
         )?
         .await?;
 
-    let (hide_ui, include_paths, library_paths, style) = {
+    let (hide_ui, include_paths, library_paths, style, spell_check) = {
         let mut hide_ui = None;
+        let mut spell_check = spell_check::SpellCheckConfig::default();
         let mut include_paths = None;
         let mut library_paths = None;
         let mut style = None;
@@ -1495,11 +1764,17 @@ pub async fn load_configuration(ctx: &Context) -> common::Result<()> {
                     }
                 }
                 hide_ui = o.get("preview").and_then(|v| v.as_object()?.get("hide_ui")?.as_bool());
+                if let Some(sc) = o.get("spellCheck") {
+                    spell_check = spell_check::SpellCheckConfig::from_json(sc);
+                }
             }
         }
-        (hide_ui, include_paths, library_paths, style)
+        (hide_ui, include_paths, library_paths, style, spell_check)
     };
 
+    *ctx.spell_checker.borrow_mut() =
+        spell_check.enabled.then(|| spell_check::SpellChecker::new(&spell_check)).flatten();
+
     let document_cache = &mut ctx.document_cache.borrow_mut();
     let cc = document_cache.reconfigure(style, include_paths, library_paths).await?;
 
@@ -1520,7 +1795,7 @@ pub async fn load_configuration(ctx: &Context) -> common::Result<()> {
         .send_message_to_preview(common::LspToPreviewMessage::SetConfiguration { config });
 
     send_diagnostics(
-        &ctx.server_notifier,
+        ctx,
         document_cache,
         &all_urls.iter().filter_map(common::uri_to_file).collect(),
         diag,
@@ -0,0 +1,533 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Optional spell checking of translatable strings and texts shown to the user,
+//! based on hunspell dictionaries
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, SyntaxToken, TextRange, TextSize};
+use lsp_types::{CodeActionOrCommand, Diagnostic, Url};
+
+use crate::common;
+use crate::util;
+
+/// The `code` of the diagnostics reported for unknown words
+const DIAGNOSTIC_CODE: &str = "spelling";
+/// Never suggest more than this many replacements
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The `slint.spellCheck` settings
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpellCheckConfig {
+    pub enabled: bool,
+    /// The dictionary to use, e.g. `en_US`
+    pub locale: String,
+    /// Directories to look for `<locale>.aff` and `<locale>.dic` in, before the system directories
+    pub dictionary_paths: Vec<PathBuf>,
+    /// Words of the project that are not in the dictionary
+    pub words: Vec<String>,
+}
+
+impl SpellCheckConfig {
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let strings = |key: &str| -> Vec<String> {
+            value
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str()).map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+        Self {
+            enabled: value.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false),
+            locale: value
+                .get("locale")
+                .and_then(|v| v.as_str())
+                .filter(|l| !l.is_empty())
+                .unwrap_or("en_US")
+                .to_string(),
+            dictionary_paths: strings("dictionaryPaths").into_iter().map(PathBuf::from).collect(),
+            words: strings("words"),
+        }
+    }
+}
+
+/// A part of a word in the condition of an affix rule
+#[derive(Debug)]
+enum ConditionPart {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl ConditionPart {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionPart::Any => true,
+            ConditionPart::OneOf(chars) => chars.contains(&c),
+            ConditionPart::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<ConditionPart> {
+    let mut result = vec![];
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => result.push(ConditionPart::Any),
+            '[' => {
+                let set = chars.by_ref().take_while(|c| *c != ']').collect::<Vec<_>>();
+                match set.split_first() {
+                    Some(('^', rest)) => result.push(ConditionPart::NoneOf(rest.to_vec())),
+                    _ => result.push(ConditionPart::OneOf(set)),
+                }
+            }
+            c => result.push(ConditionPart::OneOf(vec![c])),
+        }
+    }
+    result
+}
+
+/// A `PFX` or `SFX` rule of a `.aff` file
+#[derive(Debug)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<ConditionPart>,
+}
+
+impl AffixRule {
+    fn apply_suffix(&self, word: &str) -> Option<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let start = chars.len().checked_sub(self.condition.len())?;
+        if !self.condition.iter().zip(&chars[start..]).all(|(p, c)| p.matches(*c)) {
+            return None;
+        }
+        Some(format!("{}{}", word.strip_suffix(self.strip.as_str())?, self.add))
+    }
+
+    fn apply_prefix(&self, word: &str) -> Option<String> {
+        if !self.condition.iter().zip(word.chars()).all(|(p, c)| p.matches(c))
+            || word.chars().count() < self.condition.len()
+        {
+            return None;
+        }
+        Some(format!("{}{}", self.add, word.strip_prefix(self.strip.as_str())?))
+    }
+}
+
+#[derive(Default)]
+struct AffixClass {
+    is_prefix: bool,
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum FlagType {
+    #[default]
+    Char,
+    Long,
+    Num,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => flags
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|c| c.iter().collect::<String>())
+                .collect(),
+            FlagType::Num => flags.split(',').map(|f| f.trim().to_string()).collect(),
+        }
+    }
+}
+
+/// Checks words against a dictionary
+#[derive(Debug, Default)]
+pub struct SpellChecker {
+    words: HashSet<String>,
+    /// The characters to try when looking for suggestions, most common first
+    try_chars: Vec<char>,
+}
+
+impl SpellChecker {
+    /// Create a spell checker from the contents of a `.aff` and a `.dic` file
+    pub fn parse(aff: &str, dic: &str) -> Self {
+        let mut flag_type = FlagType::default();
+        let mut try_chars = vec![];
+        let mut classes: HashMap<String, AffixClass> = HashMap::new();
+
+        for line in aff.lines() {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            match parts.as_slice() {
+                ["FLAG", "long"] => flag_type = FlagType::Long,
+                ["FLAG", "num"] => flag_type = FlagType::Num,
+                ["TRY", chars] => try_chars = chars.chars().collect(),
+                [kind @ ("PFX" | "SFX"), flag, cross_product @ ("Y" | "N"), count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    let class = classes.entry(flag.to_string()).or_default();
+                    class.is_prefix = *kind == "PFX";
+                    class.cross_product = *cross_product == "Y";
+                }
+                ["PFX" | "SFX", flag, strip, add, condition, ..] => {
+                    let class = classes.entry(flag.to_string()).or_default();
+                    let unset = |s: &str| if s == "0" { String::new() } else { s.to_string() };
+                    // Continuation classes are not supported
+                    let add = add.split('/').next().unwrap_or_default();
+                    class.rules.push(AffixRule {
+                        strip: unset(strip),
+                        add: unset(add),
+                        condition: parse_condition(condition),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let mut words = HashSet::new();
+        // The first line holds the number of words
+        for line in dic.lines().skip(1) {
+            let entry = line.split_whitespace().next().unwrap_or_default();
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            if word.is_empty() {
+                continue;
+            }
+            let flags = flag_type.split(flags);
+            let classes = flags.iter().filter_map(|f| classes.get(f)).collect::<Vec<_>>();
+
+            let mut cross_product_suffixed = vec![];
+            for class in classes.iter().filter(|c| !c.is_prefix) {
+                for form in class.rules.iter().filter_map(|r| r.apply_suffix(word)) {
+                    if class.cross_product {
+                        cross_product_suffixed.push(form.clone());
+                    }
+                    words.insert(form);
+                }
+            }
+            for class in classes.iter().filter(|c| c.is_prefix) {
+                for rule in &class.rules {
+                    words.extend(rule.apply_prefix(word));
+                    if class.cross_product {
+                        words.extend(
+                            cross_product_suffixed.iter().filter_map(|w| rule.apply_prefix(w)),
+                        );
+                    }
+                }
+            }
+            words.insert(word.to_string());
+        }
+
+        if try_chars.is_empty() {
+            try_chars = ('a'..='z').collect();
+        }
+        Self { words, try_chars }
+    }
+
+    /// Find the dictionary for the configured locale and add the project words to it
+    pub fn new(config: &SpellCheckConfig) -> Option<Self> {
+        let system_paths = ["/usr/share/hunspell", "/usr/share/myspell", "/Library/Spelling"];
+        let directory = config
+            .dictionary_paths
+            .iter()
+            .map(PathBuf::as_path)
+            .chain(system_paths.iter().map(Path::new))
+            .find(|d| d.join(format!("{}.dic", config.locale)).is_file())?;
+        let read = |extension: &str| {
+            std::fs::read_to_string(directory.join(format!("{}.{extension}", config.locale)))
+        };
+        let mut checker = Self::parse(&read("aff").unwrap_or_default(), &read("dic").ok()?);
+        checker.words.extend(config.words.iter().cloned());
+        Some(checker)
+    }
+
+    pub fn is_correct(&self, word: &str) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else {
+            return true;
+        };
+        // Words at the start of a sentence and all upper case words
+        let lower_case = word.to_lowercase();
+        let capitalized =
+            first.to_uppercase().chain(chars.as_str().to_lowercase().chars()).collect::<String>();
+        let is_all_upper_case = word.chars().all(|c| !c.is_lowercase());
+        ((word == capitalized || is_all_upper_case) && self.words.contains(&lower_case))
+            || (is_all_upper_case && self.words.contains(&capitalized))
+    }
+
+    /// Known words that are a single edit away from `word`
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let capitalize = word.chars().next().is_some_and(char::is_uppercase);
+        let chars = word.to_lowercase().chars().collect::<Vec<_>>();
+
+        let mut candidates = vec![];
+        for i in 0..chars.len() {
+            let mut c = chars.clone();
+            c.remove(i);
+            candidates.push(c);
+        }
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut c = chars.clone();
+            c.swap(i, i + 1);
+            candidates.push(c);
+        }
+        for &t in &self.try_chars {
+            for i in 0..chars.len() {
+                if chars[i] != t {
+                    let mut c = chars.clone();
+                    c[i] = t;
+                    candidates.push(c);
+                }
+            }
+            for i in 0..=chars.len() {
+                let mut c = chars.clone();
+                c.insert(i, t);
+                candidates.push(c);
+            }
+        }
+
+        let mut result: Vec<String> = vec![];
+        for candidate in candidates {
+            let mut candidate = candidate.into_iter().collect::<String>();
+            if capitalize {
+                let mut c = candidate.chars();
+                candidate = c.next().into_iter().flat_map(char::to_uppercase).chain(c).collect();
+            }
+            if !result.contains(&candidate) && self.is_correct(&candidate) {
+                result.push(candidate);
+                if result.len() == MAX_SUGGESTIONS {
+                    break;
+                }
+            }
+        }
+        result
+    }
+
+    /// Report the unknown words in the texts of `document`
+    pub fn check_document(&self, document: &syntax_nodes::Document) -> Vec<Diagnostic> {
+        checked_strings(document)
+            .iter()
+            .flat_map(|token| {
+                words(token.text()).into_iter().filter_map(move |(offset, word)| {
+                    if self.is_correct(word) {
+                        return None;
+                    }
+                    let start = token.text_range().start() + TextSize::from(offset as u32);
+                    let range = TextRange::at(start, TextSize::of(word));
+                    Some(Diagnostic {
+                        range: util::text_range_to_lsp_range(&token.source_file, range),
+                        severity: Some(lsp_types::DiagnosticSeverity::INFORMATION),
+                        code: Some(lsp_types::NumberOrString::String(DIAGNOSTIC_CODE.into())),
+                        source: Some("slint spell check".into()),
+                        message: format!("Unknown word: \"{word}\""),
+                        data: Some(serde_json::json!({ "suggestions": self.suggestions(word) })),
+                        ..Default::default()
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+/// The string literals of `document` that are shown to the user
+fn checked_strings(document: &syntax_nodes::Document) -> Vec<SyntaxToken> {
+    let mut result: Vec<SyntaxToken> = vec![];
+    let mut push = |token: SyntaxToken| {
+        if !result.iter().any(|t| t.text_range() == token.text_range()) {
+            result.push(token);
+        }
+    };
+
+    for at_tr in document.descendants().filter(|n| n.kind() == SyntaxKind::AtTr) {
+        at_tr.child_token(SyntaxKind::StringLiteral).into_iter().for_each(&mut push);
+        at_tr
+            .child_node(SyntaxKind::TrPlural)
+            .and_then(|p| p.child_token(SyntaxKind::StringLiteral))
+            .into_iter()
+            .for_each(&mut push);
+    }
+
+    for binding in document.descendants().filter_map(syntax_nodes::Binding::new) {
+        let name = i_slint_compiler::parser::identifier_text(&binding).unwrap_or_default();
+        if !common::USER_VISIBLE_TEXT_PROPERTIES.contains(&name.as_str()) {
+            continue;
+        }
+        let expression = binding.BindingExpression();
+        expression
+            .node
+            .descendants_with_tokens()
+            .filter_map(|t| t.into_token())
+            .filter(|t| t.kind() == SyntaxKind::StringLiteral)
+            // Translation contexts are never shown
+            .filter(|t| t.parent().is_some_and(|p| p.kind() != SyntaxKind::TrContext))
+            .map(|token| SyntaxToken { token, source_file: expression.source_file.clone() })
+            .for_each(&mut push);
+    }
+
+    result.sort_by_key(|t| t.text_range().start());
+    result
+}
+
+/// The words in the source text of a string literal, with their offset
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut result = vec![];
+    let mut start = None;
+    let mut is_escaped = false;
+    for (offset, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        let is_word_char = !is_escaped && (c.is_alphabetic() || (c == '\'' && start.is_some()));
+        is_escaped = c == '\\' && !is_escaped;
+        match (start, is_word_char) {
+            (None, true) => start = Some(offset),
+            (Some(s), false) => {
+                let word = text[s..offset].trim_end_matches('\'');
+                // Skip abbreviations and words glued to numbers or identifiers
+                let is_glued = text[offset..].starts_with(|c: char| c.is_ascii_digit() || c == '_');
+                if word.chars().count() > 1 && !word.chars().all(char::is_uppercase) && !is_glued {
+                    result.push((s, word));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Replace unknown words reported in `diagnostics` with the suggestions attached to them
+pub fn quick_fixes(
+    document_cache: &common::DocumentCache,
+    uri: &Url,
+    diagnostics: &[Diagnostic],
+) -> Vec<CodeActionOrCommand> {
+    let version = document_cache.document_version(uri);
+    diagnostics
+        .iter()
+        .filter(|d| d.code == Some(lsp_types::NumberOrString::String(DIAGNOSTIC_CODE.into())))
+        .flat_map(|d| {
+            let suggestions = d
+                .data
+                .as_ref()
+                .and_then(|data| data.get("suggestions")?.as_array().cloned())
+                .unwrap_or_default();
+            suggestions.into_iter().filter_map(move |s| {
+                let suggestion = s.as_str()?.to_string();
+                Some(CodeActionOrCommand::CodeAction(lsp_types::CodeAction {
+                    title: format!("Change to \"{suggestion}\""),
+                    kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![d.clone()]),
+                    edit: Some(common::create_workspace_edit(
+                        uri.clone(),
+                        version,
+                        vec![lsp_types::TextEdit::new(d.range, suggestion)],
+                    )),
+                    ..Default::default()
+                }))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = r#"
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX D Y 2
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+
+SFX S Y 1
+SFX S   0     s          .
+"#;
+
+    const DIC: &str = "5\nhello\nworld/S\ncreate/DU\ncopy/D\nbutton/S\n";
+
+    fn checker() -> SpellChecker {
+        SpellChecker::parse(AFF, DIC)
+    }
+
+    #[test]
+    fn test_affixes() {
+        let checker = checker();
+        for word in
+            ["hello", "worlds", "created", "uncreate", "uncreated", "copied", "Hello", "HELLO"]
+        {
+            assert!(checker.is_correct(word), "{word}");
+        }
+        for word in ["helo", "copyd", "unhello", "hELLO", "helloS"] {
+            assert!(!checker.is_correct(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let checker = checker();
+        assert_eq!(checker.suggestions("wrold"), vec!["world".to_string()]);
+        assert_eq!(checker.suggestions("Helo"), vec!["Hello".to_string()]);
+        assert_eq!(checker.suggestions("xyzzy"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words(r#""Hello\nworld, it's OK to use v2 or {} and \{""#),
+            vec![
+                (1, "Hello"),
+                (8, "world"),
+                (15, "it's"),
+                (23, "to"),
+                (26, "use"),
+                (33, "or"),
+                (39, "and")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_document() {
+        let source = r#"
+export component Main {
+    property <string> not-shown: "Helo";
+    Text { text: "Helo wrold"; }
+    Text { text: @tr("Ctxt" => "Create buton"); }
+    Button { text: cond ? "hello" : "wrold"; }
+    in property <bool> cond;
+}
+"#;
+        let (document_cache, url, _) =
+            crate::language::test::loaded_document_cache(source.to_string());
+        let document = document_cache.get_document(&url).unwrap().node.clone().unwrap();
+
+        let diagnostics = checker().check_document(&document);
+        let unknown = diagnostics
+            .iter()
+            .map(|d| {
+                let start = util::lsp_position_to_text_size(&document.source_file, d.range.start);
+                let end = util::lsp_position_to_text_size(&document.source_file, d.range.end);
+                source[usize::from(start)..usize::from(end)].to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(unknown, vec!["Helo", "wrold", "buton", "wrold"]);
+        assert_eq!(diagnostics[2].data, Some(serde_json::json!({ "suggestions": ["button"] })));
+
+        let fixes = quick_fixes(&document_cache, &url, &diagnostics[1..2]);
+        assert_eq!(fixes.len(), 1);
+        let CodeActionOrCommand::CodeAction(fix) = &fixes[0] else { panic!("Not a code action") };
+        assert_eq!(fix.title, "Change to \"world\"");
+    }
+}
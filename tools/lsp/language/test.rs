@@ -174,6 +174,7 @@ fn accurate_diagnostics_in_dependencies() {
         #[cfg(any(feature = "preview-external", feature = "preview-engine"))]
         to_show: Default::default(),
         open_urls: RefCell::new(HashSet::from_iter([foo_url.clone(), bar_url.clone()])),
+        spell_checker: Default::default(),
     }));
 
     let (bar_url, diag) = load(
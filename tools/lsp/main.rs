@@ -19,11 +19,11 @@
 
 use lsp_types::notification::{
     DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument,
-    DidOpenTextDocument, Notification,
+    DidOpenTextDocument, DidSaveTextDocument, Notification,
 };
 use lsp_types::{
     DidChangeTextDocumentParams, DidChangeWatchedFilesParams, DidCloseTextDocumentParams,
-    DidOpenTextDocumentParams, InitializeParams, Url,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, InitializeParams, Url,
 };
 
 use clap::{Args, Parser, Subcommand};
@@ -82,6 +82,9 @@ pub struct Cli {
 enum Commands {
     /// Format slint files
     Format(Format),
+    /// Render all exported components of slint files to PNG files
+    #[cfg(feature = "preview-engine")]
+    Screenshots(Screenshots),
 }
 
 #[derive(Args, Clone)]
@@ -94,6 +97,34 @@ struct Format {
     inline: bool,
 }
 
+#[cfg(feature = "preview-engine")]
+#[derive(Args, Clone)]
+struct Screenshots {
+    /// The .slint files, or directories to search for .slint files
+    #[arg(name = "path to .slint file(s) or directories", action)]
+    paths: Vec<std::path::PathBuf>,
+
+    /// The directory to write the PNG files to
+    #[arg(short, long, default_value = ".", action)]
+    output: std::path::PathBuf,
+
+    /// Render the components in this size instead of their preferred size. Can be repeated
+    #[arg(long = "size", value_name = "WIDTHxHEIGHT", action)]
+    sizes: Vec<String>,
+
+    /// Render the components in this style. Can be repeated, defaults to 'fluent'
+    #[arg(long = "with-style", value_name = "style name", action)]
+    styles: Vec<String>,
+
+    /// Render the components in this color scheme ('light' or 'dark'). Can be repeated
+    #[arg(long = "color-scheme", value_name = "scheme", action)]
+    color_schemes: Vec<String>,
+
+    /// Physical pixels per logical pixel
+    #[arg(long, default_value_t = 1.0, action)]
+    scale: f32,
+}
+
 enum OutgoingRequest {
     Start,
     Pending(Waker),
@@ -172,9 +203,10 @@ pub fn send_message_to_preview(&self, message: common::LspToPreviewMessage) {
         }
     }
 
+    /// Returns false when the LSP is gone
     #[cfg(feature = "preview-engine")]
-    pub fn send_message_to_lsp(&self, message: common::PreviewToLspMessage) {
-        let _ = self.preview_to_lsp_sender.send(message);
+    pub fn send_message_to_lsp(&self, message: common::PreviewToLspMessage) -> bool {
+        self.preview_to_lsp_sender.send(message).is_ok()
     }
 
     #[cfg(test)]
@@ -233,6 +265,29 @@ fn main() {
         std::process::exit(0);
     }
 
+    #[cfg(feature = "preview-engine")]
+    if let Some(Commands::Screenshots(screenshots)) = &args.command {
+        let result = screenshot_settings(&args, screenshots).and_then(|settings| {
+            preview::screenshot::run_batch_export(
+                &screenshots.paths,
+                &settings,
+                &screenshots.output,
+            )
+        });
+        match result {
+            Ok(written) => {
+                for path in written {
+                    println!("{}", path.display());
+                }
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
     if let Ok(panic_log_file) = std::env::var("SLINT_LSP_PANIC_LOG") {
         let default_hook = std::panic::take_hook();
         std::panic::set_hook(Box::new(move |info| {
@@ -298,6 +353,49 @@ fn drop(&mut self) {
     }
 }
 
+#[cfg(feature = "preview-engine")]
+fn screenshot_settings(
+    cli_args: &Cli,
+    args: &Screenshots,
+) -> std::result::Result<preview::screenshot::BatchSettings, String> {
+    use i_slint_core::items::ColorScheme;
+
+    let sizes = args
+        .sizes
+        .iter()
+        .map(|size| {
+            size.split_once('x')
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                .ok_or_else(|| format!("Invalid size '{size}', expected WIDTHxHEIGHT"))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let color_schemes = args
+        .color_schemes
+        .iter()
+        .map(|scheme| match scheme.as_str() {
+            "light" => Ok(ColorScheme::Light),
+            "dark" => Ok(ColorScheme::Dark),
+            _ => Err(format!("Invalid color scheme '{scheme}', expected 'light' or 'dark'")),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(preview::screenshot::BatchSettings {
+        sizes,
+        styles: if args.styles.is_empty() { vec!["fluent".into()] } else { args.styles.clone() },
+        color_schemes: if color_schemes.is_empty() {
+            vec![ColorScheme::Unknown]
+        } else {
+            color_schemes
+        },
+        scale_factor: args.scale,
+        include_paths: cli_args.include_paths.clone(),
+        library_paths: cli_args
+            .library_paths
+            .iter()
+            .filter_map(|entry| entry.split('=').collect_tuple().map(|(k, v)| (k.into(), v.into())))
+            .collect(),
+    })
+}
+
 fn run_lsp_server(args: Cli) -> Result<IoThreads> {
     let (connection, io_threads) = Connection::stdio();
     let (id, params) = connection.initialize_start()?;
@@ -373,6 +471,7 @@ fn main_loop(connection: Connection, init_param: InitializeParams, cli_args: Cli
         #[cfg(any(feature = "preview-external", feature = "preview-engine"))]
         to_show: Default::default(),
         open_urls: Default::default(),
+        spell_checker: Default::default(),
     });
 
     let mut futures = Vec::<Pin<Box<dyn Future<Output = Result<()>>>>>::new();
@@ -475,6 +574,10 @@ async fn handle_notification(req: lsp_server::Notification, ctx: &Rc<Context>) -
             )
             .await
         }
+        DidSaveTextDocument::METHOD => {
+            let params: DidSaveTextDocumentParams = serde_json::from_value(req.params)?;
+            save_document(ctx, params.text_document.uri).await
+        }
         DidChangeConfiguration::METHOD => load_configuration(ctx).await,
         DidChangeWatchedFiles::METHOD => {
             let params: DidChangeWatchedFilesParams = serde_json::from_value(req.params)?;
@@ -573,6 +676,9 @@ async fn handle_preview_to_lsp_message(
             ctx.server_notifier
                 .send_notification::<lsp_types::notification::ShowMessage>(message)?;
         }
+        M::Ping { id } => {
+            ctx.server_notifier.send_message_to_preview(common::LspToPreviewMessage::Pong { id });
+        }
     }
     Ok(())
 }
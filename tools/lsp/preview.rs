@@ -11,7 +11,8 @@
 use i_slint_compiler::parser::{syntax_nodes, TextSize};
 use i_slint_compiler::{diagnostics, EmbedResourcesKind};
 use i_slint_core::component_factory::FactoryContext;
-use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
+use i_slint_core::items::ColorScheme;
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize, LogicalVector};
 use lsp_types::Url;
 use slint::PlatformError;
 use slint_interpreter::{ComponentDefinition, ComponentHandle, ComponentInstance};
@@ -25,11 +26,64 @@
 #[cfg(target_arch = "wasm32")]
 use crate::wasm_prelude::*;
 
+mod accessibility;
+mod annotations;
+mod apply_to_source;
+mod baseline_grid;
+mod binding_dependencies;
+mod breakpoints;
+mod brush_presets;
+mod bug_report;
+mod color_audit;
+mod component_usage;
+mod connection;
+mod console;
 mod debug;
+mod devices;
 mod drop_location;
+mod easing;
+mod edit_journal;
+mod edit_review;
+mod element_locks;
 mod element_selection;
+mod environment;
+mod examples;
+mod expression_editor;
 mod ext;
+mod eyedropper;
+mod file_drop;
+mod focus_chain;
+mod fonts;
+mod form_scaffold;
+mod frame_stats;
+mod fuzz;
+mod globals;
+mod grid_cells;
+mod image_assets;
+mod input_regions;
+mod json_schema;
+mod layer_overrides;
+mod layout_anomalies;
+mod layout_gaps;
+mod localization;
+mod model_fixtures;
+mod outline;
+mod output_search;
+mod panel_layout;
+mod partial_preview;
 mod preview_data;
+mod preview_windows;
+mod project_settings;
+mod recorder;
+mod render_layers;
+mod render_stats;
+pub mod screenshot;
+mod states;
+mod style_library;
+mod text_contrast;
+mod text_styles;
+mod thumbnails;
+mod watch;
 use ext::ElementRcNodeExt;
 mod properties;
 pub mod ui;
@@ -77,6 +131,21 @@ struct SourceCodeCacheEntry {
 }
 type SourceCodeCache = HashMap<Url, SourceCodeCacheEntry>;
 
+/// How the preview reacts to changes of the sources it shows
+#[derive(Clone, Debug, PartialEq)]
+struct ReloadSettings {
+    /// Wait this long after the last change before recompiling
+    debounce: std::time::Duration,
+    /// Only recompile when a file gets saved or when the user asks for it
+    on_save_only: bool,
+}
+
+impl Default for ReloadSettings {
+    fn default() -> Self {
+        Self { debounce: std::time::Duration::from_millis(50), on_save_only: false }
+    }
+}
+
 #[derive(Default)]
 struct ContentCache {
     source_code: SourceCodeCache,
@@ -87,6 +156,18 @@ struct ContentCache {
     current_load_behavior: Option<LoadBehavior>,
     loading_state: PreviewFutureState,
     ui_is_visible: bool,
+    reload_settings: ReloadSettings,
+    /// Changes were seen, but the preview was not recompiled for them
+    preview_is_stale: bool,
+    /// Do the next reload even if `on_save_only` is set, e.g. since the
+    /// change was made by the preview itself
+    force_next_reload: bool,
+    /// The state of the previewed document that is active no matter its condition
+    forced_state: Option<(Url, TextSize)>,
+    /// The elements hidden in the preview from the outline, until the sources change
+    layer_overrides: layer_overrides::Overrides,
+    /// The files the components shown in further preview windows depend on
+    window_dependencies: HashSet<Url>,
 }
 
 static CONTENT_CACHE: std::sync::OnceLock<Mutex<ContentCache>> = std::sync::OnceLock::new();
@@ -97,6 +178,16 @@ pub fn current_component(&self) -> Option<PreviewComponent> {
     }
 
     pub fn set_current_component(&mut self, component: PreviewComponent) {
+        if self.current_previewed_component.as_ref().map(|pc| (&pc.url, &pc.component))
+            != Some((&component.url, &component.component))
+        {
+            self.forced_state = None;
+            self.layer_overrides = Default::default();
+        }
+        // The documents synthesized to preview library components are only needed while
+        // they are previewed
+        self.source_code
+            .retain(|url, _| url.scheme() != LIBRARY_WRAPPER_SCHEME || *url == component.url);
         self.current_previewed_component = Some(component);
     }
 
@@ -122,10 +213,77 @@ struct PreviewState {
     handle: Rc<RefCell<Option<slint_interpreter::ComponentInstance>>>,
     document_cache: Rc<RefCell<Option<Rc<common::DocumentCache>>>>,
     selected: Option<element_selection::ElementSelection>,
+    /// Further elements selected together with `selected` (via shift-click or rubber band)
+    selected_group: Vec<element_selection::ElementSelection>,
     notify_editor_about_selection_after_update: bool,
     workspace_edit_sent: bool,
+    /// Elements with errors are replaced by placeholders, so the offsets of the elements in
+    /// the preview do not match the document and no edits may be made from the preview
+    preview_is_partial: bool,
+    /// The edit sent to the editor, kept in the journal until it comes back
+    journaled_edit: Option<edit_journal::JournalEntry>,
+    /// The edits of a previous session the user did not yet replay or discard
+    recovered_edits: Vec<edit_journal::JournalEntry>,
+    /// The edits to replay, one after the other
+    replayed_edits: Vec<edit_journal::JournalEntry>,
     known_components: Vec<ComponentInformation>,
+    /// The index of the previewed component in `known_components`
+    current_component_index: usize,
+    /// The project the previewed component belongs to, and the library components used in it
+    project: Option<PathBuf>,
+    component_usage: component_usage::ProjectUsage,
+    /// The rows kept for the array properties of the components of the project
+    fixtures: model_fixtures::Fixtures,
+    /// Stores the project settings a while after the last change to them
+    project_settings_timer: Option<slint::Timer>,
+    /// The settings of the projects previewed in this session, also of those the user did not
+    /// ask to keep the settings in
+    session_settings: HashMap<PathBuf, project_settings::ProjectSettings>,
+    /// Where the text cursor of the editor moved to, waiting to get selected
+    editor_cursor: Option<(Url, TextSize)>,
+    editor_cursor_timer: Option<slint::Timer>,
     preview_loading_delay_timer: Option<slint::Timer>,
+    clipboard: Option<drop_location::ElementClipboard>,
+    render_costs_timer: Option<slint::Timer>,
+    performance_overlay_timer: Option<slint::Timer>,
+    input_regions_timer: Option<slint::Timer>,
+    input_regions: Vec<input_regions::InputRegion>,
+    render_layers_timer: Option<slint::Timer>,
+    layout_anomalies_timer: Option<slint::Timer>,
+    /// The safe area insets of the device the preview simulates, applied to each new instance
+    safe_area_insets: Rc<std::cell::Cell<devices::Insets>>,
+    /// The simulated environment, applied to each new instance
+    environment: Rc<RefCell<environment::Environment>>,
+    /// The last recorded interaction, until it gets saved or discarded
+    recording: Option<recorder::Trace>,
+    /// The component shown before the latest change, to compare the preview with
+    compare_definition: Option<slint_interpreter::ComponentDefinition>,
+    accessibility_timer: Option<slint::Timer>,
+    accessible_elements: Vec<accessibility::AccessibleElement>,
+    focus_chain_timer: Option<slint::Timer>,
+    /// The elements taking the focus, in tab order
+    focus_chain: Vec<focus_chain::FocusStop>,
+    notes_timer: Option<slint::Timer>,
+    /// Counts down until the color under the mouse pointer gets picked from the screen
+    color_pick_timer: Option<slint::Timer>,
+    /// The previewed file and the notes on its components
+    notes: Option<(PathBuf, Vec<annotations::Note>)>,
+    /// Clears the elements flashed on request of the editor
+    flash_timer: Option<slint::Timer>,
+    /// What the element currently dragged around can snap to
+    snap_targets: Option<element_selection::SnapTargets>,
+    /// The element tree shown in the outline view
+    outline: Vec<outline::OutlineNode>,
+    localization: localization::Report,
+    color_audit: color_audit::Audit,
+    text_styles: text_styles::TextStyles,
+    brush_palette: brush_presets::Palette,
+    /// The callback handlers of the previewed document, to put breakpoints on
+    callback_handlers: Vec<breakpoints::Handler>,
+    /// Sets new random values while fuzzing
+    fuzz_timer: Option<slint::Timer>,
+    /// The destructive edit shown for review, until it gets applied or canceled
+    pending_edit: Option<edit_review::PendingEdit>,
 }
 
 impl PreviewState {
@@ -193,7 +351,9 @@ fn set_contents(url: &common::VersionedUrl, content: String) {
         return;
     }
 
-    if cache.dependencies.contains(url.url()) {
+    if cache.dependencies.contains(url.url()) || cache.window_dependencies.contains(url.url()) {
+        // The hidden elements are known by their position, which the change may move
+        cache.layer_overrides = Default::default();
         let ui_is_visible = cache.ui_is_visible;
         let Some(current) = cache.current_component() else {
             return;
@@ -236,28 +396,29 @@ fn property_declaration_ranges(name: slint::SharedString) -> ui::PropertyDeclara
         .unwrap_or_default()
 }
 
-// triggered from the UI, running in UI thread
-fn add_new_component() {
-    fn find_component_name() -> Option<String> {
-        PREVIEW_STATE.with(|preview_state| {
-            let preview_state = preview_state.borrow();
+/// A name for a new component that no known component uses yet
+fn find_component_name() -> Option<String> {
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
 
-            for i in 0..preview_state.known_components.len() {
-                let name =
-                    format!("MyComponent{}", if i == 0 { "".to_string() } else { i.to_string() });
+        for i in 0..=preview_state.known_components.len() {
+            let name =
+                format!("MyComponent{}", if i == 0 { "".to_string() } else { i.to_string() });
 
-                if preview_state
-                    .known_components
-                    .binary_search_by_key(&name.as_str(), |ci| ci.name.as_str())
-                    .is_err()
-                {
-                    return Some(name);
-                }
+            if preview_state
+                .known_components
+                .binary_search_by_key(&name.as_str(), |ci| ci.name.as_str())
+                .is_err()
+            {
+                return Some(name);
             }
-            None
-        })
-    }
+        }
+        None
+    })
+}
 
+// triggered from the UI, running in UI thread
+fn add_new_component() {
     let Some(document_cache) = document_cache() else {
         return;
     };
@@ -302,7 +463,118 @@ fn find_component_name() -> Option<String> {
             })
         }
 
-        send_workspace_edit(format!("Add {component_name}"), edit, true);
+        let mut builder = common::WorkspaceEditBuilder::new(format!("Add {component_name}"));
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, true);
+    }
+}
+
+/// `my-component.slint` for `MyComponent`
+fn component_file_name(component_name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in component_name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            result.push('-');
+        }
+        result.extend(c.to_lowercase());
+    }
+    result + ".slint"
+}
+
+// triggered from the UI, running in UI thread
+fn extract_selected_elements_into_component(in_new_file: bool) {
+    let Some(selected) = selected_element() else {
+        return;
+    };
+    let selected_nodes = std::iter::once(selected)
+        .chain(selected_group())
+        .filter_map(|s| s.as_element_node())
+        .collect::<Vec<_>>();
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(component_name) = find_component_name() else {
+        return;
+    };
+
+    let new_file = if in_new_file {
+        let Some((path, _)) = selected_nodes.first().map(|n| n.path_and_offset()) else {
+            return;
+        };
+        let path = path.with_file_name(component_file_name(&component_name));
+        if path.exists() {
+            set_status_text(&format!("Can not extract component: {} exists", path.display()));
+            return;
+        }
+        let Ok(url) = Url::from_file_path(path) else {
+            return;
+        };
+        Some(url)
+    } else {
+        None
+    };
+
+    if let Some((edit, drop_data)) = drop_location::extract_component(
+        &document_cache,
+        &selected_nodes,
+        &component_name,
+        new_file.as_ref(),
+    ) {
+        element_selection::select_element_at_source_code_position(
+            drop_data.path,
+            drop_data.selection_offset,
+            None,
+            SelectionNotification::AfterUpdate,
+        );
+
+        let mut builder = common::WorkspaceEditBuilder::new(format!("Extract {component_name}"));
+        builder.add_workspace_edit(edit);
+        review_workspace_edit(builder, false);
+    } else {
+        set_status_text("Can not extract the selection into a component");
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn add_responsive_variants() {
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let current = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.current_component()
+    };
+    let Some(current) = current else {
+        return;
+    };
+    let root = document_cache
+        .get_document(&current.url)
+        .and_then(|document| document.node.as_ref())
+        .and_then(|document| match &current.component {
+            Some(name) => find_component_identifiers(document, name).last().cloned(),
+            None => find_last_component_identifier(document),
+        })
+        .and_then(|identifier| {
+            document_cache.element_at_offset(&current.url, identifier.text_range().start())
+        });
+    let Some(root) = root else {
+        return;
+    };
+
+    if let Some((edit, drop_data)) = drop_location::add_responsive_variants(&document_cache, &root)
+    {
+        element_selection::select_element_at_source_code_position(
+            drop_data.path,
+            drop_data.selection_offset,
+            None,
+            SelectionNotification::AfterUpdate,
+        );
+
+        let mut builder = common::WorkspaceEditBuilder::new("Add size classes");
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
+    } else {
+        set_status_text("Can not add size classes to the previewed component");
     }
 }
 
@@ -412,7 +684,10 @@ fn rename_component(
             }
         }
 
-        send_workspace_edit(format!("Rename component {old_name} to {new_name}"), edit, true);
+        let mut builder =
+            common::WorkspaceEditBuilder::new(format!("Rename component {old_name} to {new_name}"));
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, true);
     }
 }
 
@@ -514,6 +789,16 @@ fn set_color_binding(
     )
 }
 
+/// The file, line and column of `offset` in the document at `url`
+fn source_location(url: &str, offset: i32) -> Option<(PathBuf, usize, usize)> {
+    let url = Url::parse(url).ok()?;
+    let path = url.to_file_path().ok()?;
+    let document_cache = document_cache()?;
+    let document = document_cache.get_document(&url)?.node.as_ref()?;
+    let (line, column) = document.source_file.line_column(usize::try_from(offset).ok()?);
+    Some((path, line, column))
+}
+
 /// Internal function called by all the `set_*_binding` functions
 fn set_binding(
     element_url: slint::SharedString,
@@ -522,6 +807,15 @@ fn set_binding(
     property_name: slint::SharedString,
     property_value: String,
 ) {
+    let group_edits =
+        group_binding_edits(&element_url, element_offset, &property_name, &property_value);
+
+    output_search::record_property_edit(
+        &property_name,
+        &property_value,
+        source_location(&element_url, element_offset),
+    );
+
     if let Some(edit) = evaluate_binding(
         element_url,
         element_version,
@@ -529,8 +823,369 @@ fn set_binding(
         property_name,
         property_value,
     ) {
-        send_workspace_edit("Edit property".to_string(), edit, false);
+        let mut builder = common::WorkspaceEditBuilder::new(if group_edits.is_empty() {
+            "Edit property"
+        } else {
+            "Edit properties"
+        });
+        builder.add_workspace_edit(edit);
+        let test_edit = !group_edits.is_empty();
+        group_edits.into_iter().for_each(|e| builder.add_workspace_edit(e));
+        send_workspace_edit(builder, test_edit);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn set_animation(
+    element_url: slint::SharedString,
+    element_version: i32,
+    element_offset: i32,
+    property_name: slint::SharedString,
+    settings: ui::AnimationSettings,
+) {
+    let Ok(element_url) = Url::parse(element_url.as_ref()) else {
+        return;
+    };
+    let element_version = if element_version < 0 { None } else { Some(element_version) };
+    let Some(element_offset) = u32::try_from(element_offset).ok() else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(element) = document_cache.element_at_offset(&element_url, element_offset.into())
+    else {
+        return;
+    };
+
+    let settings = properties::AnimationSettings {
+        duration: settings.duration.to_string(),
+        delay: settings.delay.to_string(),
+        easing: settings.easing.to_string(),
+        iteration_count: settings.iteration_count.to_string(),
+    };
+    let Some(edit) = properties::set_animation(
+        element_url,
+        element_version,
+        &element,
+        &property_name,
+        &settings,
+    )
+    .filter(|edit| drop_location::workspace_edit_compiles(&document_cache, edit)) else {
+        set_status_text("The animation is not valid");
+        return;
+    };
+
+    let mut builder = common::WorkspaceEditBuilder::new("Edit animation");
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, false);
+}
+
+// triggered from the UI, running in UI thread
+fn show_two_way_binding_peer(
+    element_url: slint::SharedString,
+    element_offset: i32,
+    property_name: slint::SharedString,
+) {
+    let Some((document_cache, _, element)) = expression_element(&element_url, element_offset)
+    else {
+        return;
+    };
+    let properties = properties::get_properties(&element, properties::LayoutKind::None);
+    let Some(peer) = properties
+        .iter()
+        .find(|p| p.name == property_name.as_str())
+        .and_then(|p| p.two_way_binding_peer.as_ref())
+    else {
+        set_status_text("The property is not bound to another one");
+        return;
+    };
+    let Some(document) = document_cache.get_document_by_path(&peer.path) else {
+        set_status_text(&format!("{} is not loaded", peer.path.display()));
+        return;
+    };
+    let Some(document) = document.node.as_ref() else {
+        return;
+    };
+
+    let start = util::text_size_to_lsp_position(&document.source_file, peer.start_position);
+    ask_editor_to_show_document(
+        &peer.path.to_string_lossy(),
+        lsp_types::Range::new(start, start),
+        true,
+    );
+}
+
+// triggered from the UI, running in UI thread
+fn break_two_way_binding(
+    element_url: slint::SharedString,
+    element_version: i32,
+    element_offset: i32,
+    property_name: slint::SharedString,
+) {
+    let element_version = if element_version < 0 { None } else { Some(element_version) };
+    let Some((_, url, element)) = expression_element(&element_url, element_offset) else {
+        return;
+    };
+    let Some(edit) =
+        properties::break_two_way_binding(url, element_version, &element, &property_name)
+    else {
+        set_status_text("The property is not bound to another one");
+        return;
+    };
+
+    let mut builder =
+        common::WorkspaceEditBuilder::new(format!("Break binding of {property_name}"));
+    builder.add_workspace_edit(edit);
+    // The type of a declared property can come from its peer only
+    send_workspace_edit(builder, true);
+}
+
+/// The element edited in the property panel, with the document cache it is in
+fn expression_element(
+    element_url: &str,
+    element_offset: i32,
+) -> Option<(Rc<common::DocumentCache>, Url, common::ElementRcNode)> {
+    let url = Url::parse(element_url).ok()?;
+    let offset = u32::try_from(element_offset).ok()?;
+    let document_cache = document_cache()?;
+    let element = document_cache.element_at_offset(&url, offset.into())?;
+    Some((document_cache, url, element))
+}
+
+// triggered from the UI, running in UI thread
+fn complete_expression(
+    element_url: &str,
+    element_offset: i32,
+    property_name: &str,
+    expression: &str,
+) -> Vec<expression_editor::ExpressionCompletion> {
+    let Some((document_cache, url, element)) = expression_element(element_url, element_offset)
+    else {
+        return vec![];
+    };
+    expression_editor::complete(&document_cache, &url, &element, property_name, expression)
+}
+
+// triggered from the UI, running in UI thread
+fn check_expression(
+    element_url: &str,
+    element_offset: i32,
+    property_name: &str,
+    expression: &str,
+) -> Vec<String> {
+    let Some((document_cache, url, element)) = expression_element(element_url, element_offset)
+    else {
+        return vec![];
+    };
+    expression_editor::check(&document_cache, &url, &element, property_name, expression)
+}
+
+// triggered from the UI, running in UI thread
+fn evaluate_expression(
+    element_url: &str,
+    element_offset: i32,
+    property_name: &str,
+    expression: &str,
+) -> Result<String, String> {
+    let (document_cache, url, element) = expression_element(element_url, element_offset)
+        .ok_or_else(|| "The element is gone".to_string())?;
+    let (source, component) =
+        expression_editor::evaluation_source(&element, property_name, expression)
+            .ok_or_else(|| "The property can not be evaluated".to_string())?;
+    let (config, style) = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        (cache.config.clone(), cache.current_component().map(|pc| pc.style).unwrap_or_default())
+    };
+    let path = url.to_file_path().map_err(|()| "The document is not a file".to_string())?;
+
+    let (diagnostics, compiled, _, _) = poll_once(parse_source(
+        config.include_paths,
+        config.library_paths,
+        path,
+        document_cache.document_version(&url),
+        source,
+        style,
+        Some(component),
+        file_loader,
+    ))
+    .ok_or_else(|| "The evaluation did not finish".to_string())?;
+    let Some(definition) = compiled else {
+        return Err(diagnostics
+            .iter()
+            .find(|d| d.level() == diagnostics::DiagnosticLevel::Error)
+            .map(|d| d.message().to_string())
+            .unwrap_or_else(|| "The expression can not be evaluated".to_string()));
+    };
+    let instance = definition.create().map_err(|e| e.to_string())?;
+    expression_editor::evaluated_value(&instance)
+        .map(|v| expression_editor::value_text(&v))
+        .ok_or_else(|| "The expression can not be evaluated".to_string())
+}
+
+/// The text color suggested for the `background` of the element, and how many texts it has
+fn text_contrast_suggestion_impl(
+    element_url: &str,
+    element_offset: i32,
+    background: &slint::Brush,
+) -> Option<(Rc<common::DocumentCache>, Url, common::ElementRcNode, text_contrast::Suggestion)> {
+    let (document_cache, url, element) = expression_element(element_url, element_offset)?;
+    let background = background.color().as_argb_encoded();
+    let suggestion = PREVIEW_STATE.with(|preview_state| {
+        text_contrast::suggest(background, &preview_state.borrow().color_audit.palette)
+    });
+    Some((document_cache, url, element, suggestion))
+}
+
+// triggered from the UI, running in UI thread
+fn text_contrast_suggestion(
+    element_url: &str,
+    element_offset: i32,
+    background: &slint::Brush,
+) -> Option<(usize, text_contrast::Suggestion)> {
+    let (_, _, element, suggestion) =
+        text_contrast_suggestion_impl(element_url, element_offset, background)?;
+    Some((text_contrast::text_elements(&element).len(), suggestion))
+}
+
+// triggered from the UI, running in UI thread
+fn apply_text_contrast_suggestion(
+    element_url: &str,
+    element_offset: i32,
+    background: &slint::Brush,
+) {
+    let Some((document_cache, url, element, suggestion)) =
+        text_contrast_suggestion_impl(element_url, element_offset, background)
+    else {
+        return;
+    };
+    let Some(edit) = PREVIEW_STATE.with(|preview_state| {
+        let palette = &preview_state.borrow().color_audit.palette;
+        text_contrast::apply(&document_cache, &url, &element, &suggestion, palette)
+    }) else {
+        return;
+    };
+
+    let mut builder = common::WorkspaceEditBuilder::new("Set text colors for contrast");
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, true);
+}
+
+/// Run `f` on the previewed component as found in the source code of the editor, which
+/// is not what the preview compiled while a state is forced
+fn with_previewed_component<R>(
+    f: impl FnOnce(&syntax_nodes::Component, &Url, SourceFileVersion) -> R,
+) -> Option<R> {
+    let (pc, entry) = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        let pc = cache.current_component()?;
+        let entry = cache.source_code.get(&pc.url)?.clone();
+        (pc, entry)
+    };
+    let mut diag = diagnostics::BuildDiagnostics::default();
+    let path = pc.url.to_file_path().ok();
+    let node = i_slint_compiler::parser::parse(entry.code, path.as_deref(), &mut diag);
+    let document = syntax_nodes::Document::new(node)?;
+    let component = states::component(&document, pc.component.as_deref())?;
+    Some(f(&component, &pc.url, entry.version))
+}
+
+fn update_states(ui: &ui::PreviewUi) {
+    let states = with_previewed_component(|c, _, _| states::states(c)).unwrap_or_default();
+    let forced = CONTENT_CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .forced_state
+        .as_ref()
+        .map(|(_, offset)| *offset);
+    ui::ui_set_states(ui, &states, forced);
+}
+
+// triggered from the UI, running in UI thread
+fn force_state(offset: i32) {
+    {
+        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        let url = cache.current_component().map(|pc| pc.url);
+        cache.forced_state =
+            url.zip(u32::try_from(offset).ok()).map(|(url, offset)| (url, offset.into()));
+    }
+    reload_preview();
+}
+
+fn send_state_edit(
+    offset: i32,
+    label: &str,
+    edit: impl FnOnce(&syntax_nodes::State) -> Option<lsp_types::TextEdit>,
+) {
+    let Some(offset) = u32::try_from(offset).ok() else {
+        return;
+    };
+    let Some(edit) = with_previewed_component(|component, url, version| {
+        let state = states::find_state(component, offset.into())?;
+        Some(common::create_workspace_edit(url.clone(), version, vec![edit(&state)?]))
+    })
+    .flatten() else {
+        return;
+    };
+    if document_cache().is_some_and(|dc| !drop_location::workspace_edit_compiles(&dc, &edit)) {
+        set_status_text("The state is not valid");
+        return;
     }
+
+    let mut builder = common::WorkspaceEditBuilder::new(label);
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, false);
+}
+
+// triggered from the UI, running in UI thread
+fn set_state_condition(offset: i32, condition: slint::SharedString) {
+    send_state_edit(offset, "Edit state condition", |state| {
+        states::condition_edit(state, &condition)
+    });
+}
+
+// triggered from the UI, running in UI thread
+fn set_state_override(offset: i32, property: slint::SharedString, value: slint::SharedString) {
+    send_state_edit(offset, "Edit state", |state| states::override_edit(state, &property, &value));
+}
+
+/// The edits needed to apply a binding set on the primary selected element
+/// to all other elements selected along with it
+fn group_binding_edits(
+    element_url: &str,
+    element_offset: i32,
+    property_name: &str,
+    property_value: &str,
+) -> Vec<lsp_types::WorkspaceEdit> {
+    let Some(selected) = selected_element() else {
+        return Vec::new();
+    };
+    let is_primary = Url::from_file_path(&selected.path).is_ok_and(|u| u.as_str() == element_url)
+        && u32::try_from(element_offset).is_ok_and(|o| TextSize::from(o) == selected.offset);
+    let Some(document_cache) = document_cache().filter(|_| is_primary) else {
+        return Vec::new();
+    };
+
+    selected_group()
+        .iter()
+        .filter_map(|s| {
+            let url = Url::from_file_path(&s.path).ok()?;
+            let version = document_cache.document_version(&url);
+            let element = document_cache.element_at_offset(&url, s.offset)?;
+            if property_value.is_empty() {
+                properties::remove_binding(url, version, &element, property_name).ok()
+            } else {
+                properties::set_binding(
+                    url,
+                    version,
+                    &element,
+                    property_name,
+                    property_value.to_string(),
+                )
+            }
+        })
+        .collect()
 }
 
 // triggered from the UI, running in UI thread
@@ -593,9 +1248,31 @@ fn internal(
 
 // triggered from the UI, running in UI thread
 fn show_preview_for(name: slint::SharedString, url: slint::SharedString) {
+    let wrapper = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        preview_state
+            .known_components
+            .iter()
+            .find(|ci| {
+                ci.name == name.as_str()
+                    && ci.defined_at.as_ref().map(|da| da.url().to_string()).unwrap_or_default()
+                        == url.as_str()
+            })
+            .and_then(library_component_wrapper)
+    });
+
     let name = name.to_string();
-    let Ok(url) = Url::parse(url.as_ref()) else {
-        return;
+    let url = if let Some((wrapper_url, source_code)) = wrapper {
+        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache
+            .source_code
+            .insert(wrapper_url.clone(), SourceCodeCacheEntry { version: None, code: source_code });
+        wrapper_url
+    } else {
+        let Ok(url) = Url::parse(url.as_ref()) else {
+            return;
+        };
+        url
     };
 
     let current = PreviewComponent { url, component: Some(name), style: String::new() };
@@ -604,10 +1281,61 @@ fn show_preview_for(name: slint::SharedString, url: slint::SharedString) {
 }
 
 // triggered from the UI, running in UI thread
-fn can_drop_component(component_index: i32, x: f32, y: f32, on_drop_area: bool) -> bool {
-    if !on_drop_area {
-        set_drop_mark(&None);
-        return false;
+fn open_example(index: i32) {
+    let Some(example) = examples::available().get(index as usize) else {
+        return;
+    };
+    let path = match examples::copy_to_scratch_workspace(example) {
+        Ok(path) => path,
+        Err(e) => {
+            set_status_text(&format!("Can not open example {}: {e}", example.name));
+            return;
+        }
+    };
+    let Ok(url) = Url::from_file_path(&path) else {
+        return;
+    };
+
+    CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().source_code.insert(
+        url.clone(),
+        SourceCodeCacheEntry { version: None, code: example.source.to_string() },
+    );
+    ask_editor_to_show_document(&path.to_string_lossy(), lsp_types::Range::default(), true);
+    load_preview(
+        PreviewComponent { url, component: None, style: String::new() },
+        LoadBehavior::Load,
+    );
+}
+
+/// URL scheme of the documents synthesized by `library_component_wrapper`
+const LIBRARY_WRAPPER_SCHEME: &str = "slint-preview";
+
+/// Synthesize a document that makes a std-widget or `@library` component
+/// previewable without a file in the workspace
+///
+/// Returns the URL to use for the document and its source code.
+fn library_component_wrapper(component: &ComponentInformation) -> Option<(Url, String)> {
+    if component.is_global || component.is_builtin {
+        return None;
+    }
+    let import_file = component.import_file_name(&None)?;
+    if !component.is_std_widget && !import_file.starts_with('@') {
+        return None;
+    }
+
+    let name = &component.name;
+    let url = Url::parse(&format!("{LIBRARY_WRAPPER_SCHEME}:/{import_file}/{name}.slint")).ok()?;
+    let source_code = format!(
+        "import {{ {name} as Base }} from \"{import_file}\";\nexport component {name} inherits Base {{ }}\n"
+    );
+    Some((url, source_code))
+}
+
+// triggered from the UI, running in UI thread
+fn can_drop_component(component_index: i32, x: f32, y: f32, on_drop_area: bool) -> bool {
+    if !on_drop_area {
+        set_drop_mark(&None);
+        return false;
     }
 
     let Some(document_cache) = document_cache() else {
@@ -645,6 +1373,24 @@ fn drop_component(component_index: i32, x: f32, y: f32) {
     });
 
     if let Some((edit, drop_data, component_name)) = drop_result {
+        PREVIEW_STATE.with(|preview_state| {
+            let preview_state = &mut *preview_state.borrow_mut();
+            let Some(component) = preview_state.known_components.get(component_index as usize)
+            else {
+                return;
+            };
+            preview_state.component_usage.record(component);
+            schedule_storing_project_settings(preview_state);
+            if let Some(ui) = &preview_state.ui {
+                ui::ui_set_known_components(
+                    ui,
+                    &preview_state.known_components,
+                    preview_state.current_component_index,
+                    &preview_state.component_usage,
+                );
+            }
+        });
+
         element_selection::select_element_at_source_code_position(
             drop_data.path,
             drop_data.selection_offset,
@@ -652,53 +1398,363 @@ fn drop_component(component_index: i32, x: f32, y: f32) {
             SelectionNotification::AfterUpdate,
         );
 
-        send_workspace_edit(format!("Add element {component_name}"), edit, false);
+        let mut builder =
+            common::WorkspaceEditBuilder::new(format!("Add element {component_name}"));
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
     };
 }
 
-fn placeholder_node_text(selected: &common::ElementRcNode) -> String {
-    let Some(parent) = selected.parent() else {
-        return Default::default();
+// The center of the selected element, falling back to the center of the root element.
+fn insertion_point() -> Option<LogicalPoint> {
+    let component_instance = component_instance()?;
+
+    let geometry = selected_element()
+        .and_then(|selected| {
+            let en = selected.as_element_node()?;
+            let geometries = en.geometries(&component_instance);
+            geometries.get(selected.instance_index).or_else(|| geometries.first()).cloned()
+        })
+        .or_else(|| {
+            component_instance
+                .element_positions(&element_selection::root_element(&component_instance))
+                .first()
+                .cloned()
+        });
+
+    geometry.map(|g| g.center())
+}
+
+// triggered from the UI, running in UI thread
+fn insert_component(component_index: i32) {
+    let Some(center) = insertion_point() else {
+        return;
     };
 
-    if parent.layout_kind() != ui::LayoutKind::None && parent.children().len() == 1 {
-        return format!("Rectangle {{ /* {} */ }}", common::NODE_IGNORE_COMMENT);
+    drop_component(component_index, center.x, center.y);
+}
+
+/// A file got dropped onto the preview from outside. Images become an `Image` element,
+/// fonts get imported.
+///
+/// The windowing system does not tell where the file was dropped, so images get placed
+/// like inserted components.
+pub fn drop_file(path: PathBuf) {
+    let Some(kind) = file_drop::file_kind(&path) else {
+        set_status_text(&format!("Can not use {} in the preview", path.display()));
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let current = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.current_component()
+    };
+    let Some(document_path) = current.and_then(|c| c.url.to_file_path().ok()) else {
+        return;
+    };
+
+    let project = project_settings::project_of(&document_path);
+    let asset = match file_drop::asset_for(&path, &document_path, &project) {
+        Ok(asset) => asset,
+        Err(e) => {
+            set_status_text(&format!("Can not copy {} into the project: {e}", path.display()));
+            return;
+        }
+    };
+    let Some(relative_path) =
+        file_drop::relative_path(document_path.parent().unwrap_or(&document_path), &asset)
+    else {
+        return;
+    };
+
+    match kind {
+        file_drop::FileKind::Image => {
+            let Some(position) = insertion_point() else {
+                return;
+            };
+            let drop_result = PREVIEW_STATE.with(|preview_state| {
+                let preview_state = preview_state.borrow();
+                let image =
+                    file_drop::image_component(&preview_state.known_components, &relative_path)?;
+                drop_location::drop_at(&document_cache, position, &image)
+            });
+            let Some((edit, drop_data)) = drop_result else {
+                set_status_text("Can not add an image here");
+                return;
+            };
+            element_selection::select_element_at_source_code_position(
+                drop_data.path,
+                drop_data.selection_offset,
+                None,
+                SelectionNotification::AfterUpdate,
+            );
+
+            let mut builder =
+                common::WorkspaceEditBuilder::new(format!("Add image {relative_path}"));
+            builder.add_workspace_edit(edit);
+            send_workspace_edit(builder, false);
+        }
+        file_drop::FileKind::Font => {
+            let edit = document_cache
+                .get_document_by_path(&document_path)
+                .and_then(|d| d.node.as_ref())
+                .and_then(|d| file_drop::font_import_edit(d, &relative_path));
+            let Some(edit) = edit else {
+                set_status_text(&format!("{relative_path} is imported already"));
+                return;
+            };
+            let Some(edit) = common::create_workspace_edit_from_path(
+                &document_cache,
+                &document_path,
+                vec![edit],
+            ) else {
+                return;
+            };
+
+            let mut builder = common::WorkspaceEditBuilder::new(format!("Import {relative_path}"));
+            builder.add_workspace_edit(edit);
+            send_workspace_edit(builder, false);
+        }
     }
+}
 
-    Default::default()
+fn copy_to_clipboard(element_node: &ElementRcNode) {
+    let clipboard = drop_location::copy_element(element_node);
+    set_status_text(&format!("Copied {}", clipboard.component_type));
+    PREVIEW_STATE.with(|preview_state| preview_state.borrow_mut().clipboard = Some(clipboard));
 }
 
 // triggered from the UI, running in UI thread
-fn delete_selected_element() {
+fn copy_selected_element() {
+    if let Some(selected_node) = selected_element().and_then(|s| s.as_element_node()) {
+        copy_to_clipboard(&selected_node);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn cut_selected_element() {
+    if report_locked_selection() {
+        return;
+    }
+    if let Some(selected_node) = selected_element().and_then(|s| s.as_element_node()) {
+        copy_to_clipboard(&selected_node);
+        delete_elements(vec![selected_node], "Cut element", false);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn duplicate_selected_element() {
     let Some(selected) = selected_element() else {
         return;
     };
-
-    let Ok(url) = Url::from_file_path(&selected.path) else {
+    let Some(selected_node) = selected.as_element_node() else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
         return;
     };
 
-    let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-    let Some(cache_entry) = cache.source_code.get(&url) else {
+    // Elements placed by a layout stay in it, all others get moved off the original
+    let position = (element_selection::parent_layout_kind(&selected_node) == ui::LayoutKind::None)
+        .then(component_instance)
+        .flatten()
+        .and_then(|component_instance| {
+            let geometry = selected_node
+                .geometries(&component_instance)
+                .get(selected.instance_index)
+                .copied()?;
+            let root_element = element_selection::root_element(&component_instance);
+            let parent = search_for_parent_element(&root_element, selected_node.as_element())
+                .and_then(|parent_element| {
+                    component_instance
+                        .element_positions(&parent_element)
+                        .iter()
+                        .find(|g| g.contains(geometry.origin))
+                        .map(|g| g.origin)
+                })
+                .unwrap_or_default();
+            Some(LogicalPoint::new(geometry.origin.x - parent.x, geometry.origin.y - parent.y))
+        });
+
+    let Some((edit, drop_data)) =
+        drop_location::duplicate_element(&document_cache, &selected_node, position)
+    else {
+        set_status_text(&format!("Can not duplicate {}", selected_node.component_type().trim()));
         return;
     };
 
-    let Some(selected_node) = selected.as_element_node() else {
+    element_selection::select_element_at_source_code_position(
+        drop_data.path,
+        drop_data.selection_offset,
+        None,
+        SelectionNotification::AfterUpdate,
+    );
+
+    let mut builder = common::WorkspaceEditBuilder::new(format!(
+        "Duplicate element {}",
+        selected_node.component_type().trim()
+    ));
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, false);
+}
+
+// triggered from the UI, running in UI thread
+fn paste_element(x: f32, y: f32) {
+    let Some(clipboard) =
+        PREVIEW_STATE.with(|preview_state| preview_state.borrow().clipboard.clone())
+    else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
         return;
     };
 
-    let range = selected_node.with_decorated_node(|n| util::node_to_lsp_range(&n));
+    // Paste into the element under the cursor if known
+    let position = if x >= 0.0 && y >= 0.0 { Some(LogicalPoint::new(x, y)) } else { None };
+    let Some(position) = position.or_else(insertion_point) else {
+        return;
+    };
 
-    // Insert a placeholder node into layouts if those end up empty:
-    let new_text = placeholder_node_text(&selected_node);
+    let Some((edit, drop_data)) = drop_location::paste_at(&document_cache, position, &clipboard)
+    else {
+        set_status_text(&format!("Can not paste {} here", clipboard.component_type));
+        return;
+    };
 
-    let edit = common::create_workspace_edit(
-        url,
-        cache_entry.version,
-        vec![lsp_types::TextEdit { range, new_text }],
+    element_selection::select_element_at_source_code_position(
+        drop_data.path,
+        drop_data.selection_offset,
+        None,
+        SelectionNotification::AfterUpdate,
     );
 
-    send_workspace_edit("Delete element".to_string(), edit, true);
+    let mut builder =
+        common::WorkspaceEditBuilder::new(format!("Paste element {}", clipboard.component_type));
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, false);
+}
+
+// triggered from the UI, running in UI thread
+fn filter_palette_entries(filter_text: slint::SharedString) -> slint::ModelRc<ui::PaletteEntry> {
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let entries = ui::palette_entries(&preview_state.known_components, filter_text.as_str());
+        Rc::new(slint::VecModel::from(entries)).into()
+    })
+}
+
+fn placeholder_node_text(
+    selected: &common::ElementRcNode,
+    deleted: &[(PathBuf, TextSize)],
+) -> String {
+    let Some(parent) = selected.parent() else {
+        return Default::default();
+    };
+
+    if parent.layout_kind() == ui::LayoutKind::None {
+        return Default::default();
+    }
+
+    // Only the first of the deleted children gets replaced by a placeholder
+    let children = parent.children();
+    if children.iter().all(|c| deleted.contains(&c.path_and_offset()))
+        && children.first().map(|c| c.path_and_offset()) == Some(selected.path_and_offset())
+    {
+        return format!("Rectangle {{ /* {} */ }}", common::NODE_IGNORE_COMMENT);
+    }
+
+    Default::default()
+}
+
+fn is_descendant_of_any(
+    element_node: &common::ElementRcNode,
+    ancestors: &[(PathBuf, TextSize)],
+) -> bool {
+    let mut current = element_node.parent();
+    while let Some(p) = current {
+        if ancestors.contains(&p.path_and_offset()) {
+            return true;
+        }
+        current = p.parent();
+    }
+    false
+}
+
+/// Whether the selected element or any element of the selected group is locked from the outline
+fn is_selection_locked() -> bool {
+    selected_element()
+        .into_iter()
+        .chain(selected_group())
+        .filter_map(|s| s.as_element_node())
+        .any(|en| element_locks::is_locked(&en))
+}
+
+/// Tells the user when the selection is locked and must not be changed from the preview
+fn report_locked_selection() -> bool {
+    let locked = is_selection_locked();
+    if locked {
+        set_status_text("The selection is locked, unlock it in the outline to change it");
+    }
+    locked
+}
+
+// triggered from the UI, running in UI thread
+fn delete_selected_element() {
+    let Some(selected) = selected_element() else {
+        return;
+    };
+    if report_locked_selection() {
+        return;
+    }
+
+    let selected_nodes = std::iter::once(selected)
+        .chain(selected_group())
+        .filter_map(|s| s.as_element_node())
+        .collect::<Vec<_>>();
+    let label = if selected_nodes.len() > 1 { "Delete elements" } else { "Delete element" };
+
+    delete_elements(selected_nodes, label, true);
+}
+
+/// Deletes the `selected_nodes`, after the user reviewed the edit if `review` is set
+fn delete_elements(selected_nodes: Vec<ElementRcNode>, label: &str, review: bool) {
+    let deleted = selected_nodes.iter().map(|en| en.path_and_offset()).collect::<Vec<_>>();
+
+    let mut builder = common::WorkspaceEditBuilder::new(label);
+
+    {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+
+        // Children get deleted along with their parents already
+        for selected_node in selected_nodes.iter().filter(|en| !is_descendant_of_any(en, &deleted))
+        {
+            let Ok(url) = Url::from_file_path(selected_node.path_and_offset().0) else {
+                return;
+            };
+            let Some(cache_entry) = cache.source_code.get(&url) else {
+                return;
+            };
+
+            let range = selected_node.with_decorated_node(|n| util::node_to_lsp_range(&n));
+
+            // Insert a placeholder node into layouts if those end up empty:
+            let new_text = placeholder_node_text(selected_node, &deleted);
+
+            builder.add_text_edit(
+                url,
+                cache_entry.version,
+                lsp_types::TextEdit { range, new_text },
+            );
+        }
+    }
+
+    if review {
+        review_workspace_edit(builder, true);
+    } else {
+        send_workspace_edit(builder, true);
+    }
 }
 
 // triggered from the UI, running in UI thread
@@ -706,6 +1762,9 @@ fn resize_selected_element(x: f32, y: f32, width: f32, height: f32) {
     let Some(element_selection) = &selected_element() else {
         return;
     };
+    if report_locked_selection() {
+        return;
+    }
     let Some(element_node) = element_selection.as_element_node() else {
         return;
     };
@@ -718,7 +1777,9 @@ fn resize_selected_element(x: f32, y: f32, width: f32, height: f32) {
         return;
     };
 
-    send_workspace_edit(label, edit, true);
+    let mut builder = common::WorkspaceEditBuilder::new(label);
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, true);
 }
 
 fn resize_selected_element_impl(
@@ -793,6 +1854,79 @@ fn resize_selected_element_impl(
     .map(|edit| (edit, format!("{op} element")))
 }
 
+// triggered from the UI, running in UI thread
+fn move_selected_element_to_grid_cell(x: f32, y: f32) {
+    edit_grid_cell_of_selected_element(LogicalPoint::new(x, y), false);
+}
+
+// triggered from the UI, running in UI thread
+fn span_selected_element_to_grid_cell(x: f32, y: f32) {
+    edit_grid_cell_of_selected_element(LogicalPoint::new(x, y), true);
+}
+
+/// Move the selected element into the grid cell at `position`, or make it span up to that
+/// cell when `span` is set
+fn edit_grid_cell_of_selected_element(position: LogicalPoint, span: bool) {
+    let Some(element_selection) = &selected_element() else {
+        return;
+    };
+    if report_locked_selection() {
+        return;
+    }
+    let Some(element_node) = element_selection.as_element_node() else {
+        return;
+    };
+    let Some(component_instance) = component_instance() else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(grid) = element_node
+        .geometries(&component_instance)
+        .get(element_selection.instance_index)
+        .and_then(|geometry| grid_cells::grid(&component_instance, &element_node, *geometry))
+    else {
+        return;
+    };
+
+    let current = grid.selected_cell();
+    let (row, col) = grid.cell_at(position);
+    let (target, label) = if span {
+        let target = grid_cells::Cell {
+            rowspan: (row + 1).saturating_sub(current.row).max(1),
+            colspan: (col + 1).saturating_sub(current.col).max(1),
+            ..current
+        };
+        (target, "Span element over cells")
+    } else {
+        (grid_cells::Cell { row, col, ..current }, "Move element to cell")
+    };
+
+    let children = grid.children.iter().map(|(child, _)| *child).collect::<Vec<_>>();
+    let mut builder = common::WorkspaceEditBuilder::new(label);
+    for (index, bindings) in grid_cells::edits(&children, grid.selected, target) {
+        let (path, offset) = grid.children[index].1.path_and_offset();
+        let Ok(url) = Url::from_file_path(&path) else {
+            continue;
+        };
+        let version = document_cache.document_version(&url);
+        let properties = bindings
+            .into_iter()
+            .map(|(name, value)| common::PropertyChange::new(name, value.to_string()))
+            .collect();
+        if let Some(edit) = properties::update_element_properties(
+            &document_cache,
+            common::VersionedPosition::new(common::VersionedUrl::new(url, version), offset),
+            properties,
+        ) {
+            builder.add_workspace_edit(edit);
+        }
+    }
+
+    send_workspace_edit(builder, true);
+}
+
 // triggered from the UI, running in UI thread
 fn can_move_selected_element(x: f32, y: f32, mouse_x: f32, mouse_y: f32) -> bool {
     let position = LogicalPoint::new(x, y);
@@ -803,6 +1937,16 @@ fn can_move_selected_element(x: f32, y: f32, mouse_x: f32, mouse_y: f32) -> bool
     let Some(selected_element_node) = selected.as_element_node() else {
         return false;
     };
+    if is_selection_locked() {
+        return false;
+    }
+    let group = selected_group();
+    if !group.is_empty() {
+        // Groups only move freely, without getting reparented
+        return std::iter::once(selected_element_node)
+            .chain(group.iter().filter_map(|s| s.as_element_node()))
+            .all(|en| element_selection::parent_layout_kind(&en) == ui::LayoutKind::None);
+    }
     let Some(document_cache) = document_cache() else {
         return false;
     };
@@ -823,9 +1967,18 @@ fn move_selected_element(x: f32, y: f32, mouse_x: f32, mouse_y: f32) {
     let Some(selected) = selected_element() else {
         return;
     };
+    if report_locked_selection() {
+        element_selection::reselect_element();
+        return;
+    }
     let Some(selected_element_node) = selected.as_element_node() else {
         return;
     };
+    let group = selected_group();
+    if !group.is_empty() {
+        move_selected_elements(&selected_element_node, selected.instance_index, &group, position);
+        return;
+    }
     let Some(document_cache) = document_cache() else {
         return;
     };
@@ -844,325 +1997,1518 @@ fn move_selected_element(x: f32, y: f32, mouse_x: f32, mouse_y: f32) {
             SelectionNotification::AfterUpdate,
         );
 
-        send_workspace_edit("Move element".to_string(), edit, false);
+        let mut builder = common::WorkspaceEditBuilder::new("Move element");
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
     } else {
         element_selection::reselect_element();
     }
 }
 
-fn test_workspace_edit(edit: &lsp_types::WorkspaceEdit, test_edit: bool) -> bool {
-    if test_edit {
-        let Some(document_cache) = document_cache() else {
-            return false;
-        };
-        drop_location::workspace_edit_compiles(&document_cache, edit)
-    } else {
-        true
-    }
+// triggered from the UI, running in UI thread
+fn select_outline_node(index: i32) {
+    let Some(node) = PREVIEW_STATE
+        .with(|preview_state| preview_state.borrow().outline.get(index as usize).cloned())
+    else {
+        return;
+    };
+    element_selection::select_element_at_source_code_position(
+        node.path,
+        node.offset,
+        None,
+        SelectionNotification::Now,
+    );
 }
 
-fn send_workspace_edit(label: String, edit: lsp_types::WorkspaceEdit, test_edit: bool) -> bool {
-    if !test_workspace_edit(&edit, test_edit) {
-        return false;
+/// The elements hidden in the preview from the outline
+fn layer_overrides() -> layer_overrides::Overrides {
+    CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().layer_overrides.clone()
+}
+
+fn change_layer_overrides(
+    change: impl FnOnce(&mut layer_overrides::Overrides, &[outline::OutlineNode]),
+) {
+    let outline = PREVIEW_STATE.with(|preview_state| preview_state.borrow().outline.clone());
+    {
+        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        change(&mut cache.layer_overrides, &outline);
     }
+    reload_preview();
+}
 
-    let workspace_edit_sent = PREVIEW_STATE.with(|preview_state| {
-        let mut ps = preview_state.borrow_mut();
-        let result = ps.workspace_edit_sent;
-        ps.workspace_edit_sent = true;
-        result
+// triggered from the UI, running in UI thread
+fn toggle_outline_node_hidden(index: i32) {
+    change_layer_overrides(|overrides, outline| {
+        overrides.toggle_hidden(outline, index as usize);
     });
+}
 
-    if !workspace_edit_sent {
-        send_message_to_lsp(PreviewToLspMessage::SendWorkspaceEdit { label: Some(label), edit });
-        return true;
-    }
-    false
+// triggered from the UI, running in UI thread
+fn toggle_outline_node_locked(index: i32) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(address) =
+            preview_state.outline.get(index as usize).and_then(|n| n.address.clone())
+        else {
+            return;
+        };
+        element_locks::toggle(&address);
+        schedule_storing_project_settings(&mut preview_state);
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_outline(
+                ui,
+                &preview_state.outline,
+                preview_state.selected.as_ref(),
+                &layer_overrides(),
+            );
+        }
+    });
 }
 
-fn change_style() {
-    let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-    let ui_is_visible = cache.ui_is_visible;
-    let Some(current) = cache.current_component() else {
-        return;
-    };
+// triggered from the UI, running in UI thread
+fn toggle_outline_node_solo(index: i32) {
+    change_layer_overrides(|overrides, outline| {
+        overrides.toggle_solo(outline, index as usize);
+    });
+}
 
-    drop(cache);
+// triggered from the UI, running in UI thread
+fn show_all_outline_nodes() {
+    change_layer_overrides(|overrides, _| *overrides = Default::default());
+}
 
-    if ui_is_visible {
-        load_preview(current, LoadBehavior::Reload);
-    }
+// triggered from the UI, running in UI thread
+fn show_localization_finding(index: i32) {
+    let Some(finding) = PREVIEW_STATE.with(|preview_state| {
+        preview_state.borrow().localization.findings.get(index as usize).cloned()
+    }) else {
+        return;
+    };
+    ask_editor_to_show_document(&finding.path.to_string_lossy(), finding.range, false);
 }
 
-fn start_parsing() {
-    set_status_text("Updating Preview...");
+// triggered from the UI, running in UI thread
+fn color_audit_scope_changed() {
     PREVIEW_STATE.with(|preview_state| {
-        let preview_state = preview_state.borrow_mut();
-
+        let preview_state = preview_state.borrow();
         if let Some(ui) = &preview_state.ui {
-            ui::set_diagnostics(ui, &[]);
+            ui::ui_set_color_audit(ui, &preview_state.color_audit);
         }
     });
 }
 
-fn extract_resources(
-    dependencies: &HashSet<Url>,
-    component_instance: &ComponentInstance,
-) -> HashSet<Url> {
-    let tl = component_instance.definition().type_loader();
-
-    let mut result: HashSet<Url> = Default::default();
-
-    for d in dependencies {
-        let Ok(path) = d.to_file_path() else {
-            continue;
-        };
-        let Some(doc) = tl.get_document(&path) else {
-            continue;
-        };
+// triggered from the UI, running in UI thread
+fn show_color_usage(index: i32) {
+    let Some(usage) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let whole_project =
+            preview_state.ui.as_ref()?.global::<ui::Api>().get_color_audit_whole_project();
+        let color =
+            preview_state.color_audit.colors_in(whole_project).get(index as usize).copied()?;
+        let document = preview_state.color_audit.document.as_ref();
+        color.usages.iter().find(|u| whole_project || Some(&u.url) == document).cloned()
+    }) else {
+        return;
+    };
+    let Some(path) = common::uri_to_file(&usage.url) else {
+        return;
+    };
+    ask_editor_to_show_document(&path.to_string_lossy(), usage.range, false);
+}
 
-        result.extend(
-            doc.embedded_file_resources
-                .borrow()
-                .keys()
-                .filter_map(|fp| Url::from_file_path(fp).ok()),
-        );
-    }
+// triggered from the UI, running in UI thread
+fn replace_color_usages(index: i32, palette_entry: i32) {
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(edit) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let whole_project =
+            preview_state.ui.as_ref()?.global::<ui::Api>().get_color_audit_whole_project();
+        let audit = &preview_state.color_audit;
+        let color = audit.colors_in(whole_project).get(index as usize).copied()?;
+        let entry = audit.palette.get(palette_entry as usize)?;
+        let url = if whole_project { None } else { audit.document.as_ref() };
+        color_audit::replace_with_palette_entry(&document_cache, color, entry, url)
+    }) else {
+        return;
+    };
 
-    result
+    let mut builder = common::WorkspaceEditBuilder::new("Replace color with palette entry");
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, true);
 }
 
-fn finish_parsing(preview_url: &Url, previewed_component: Option<String>) {
-    set_status_text("");
+// triggered from the UI, running in UI thread
+fn apply_text_style(index: i32) {
+    let Some(selected) = selected_element() else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let elements = std::iter::once(selected)
+        .chain(selected_group())
+        .filter_map(|s| s.as_element_node())
+        .filter(|e| matches!(e.component_type().as_str(), "Text" | "TextInput"))
+        .collect::<Vec<_>>();
+    let Some(builder) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let styles = &preview_state.text_styles;
+        let style = styles.styles.get(usize::try_from(index).ok()?)?;
+        text_styles::apply_style(&document_cache, styles, style, &elements)
+    }) else {
+        return;
+    };
+    send_workspace_edit(builder, true);
+}
 
-    let (previewed_url, component, source_code) = {
+// triggered from the UI, running in UI thread
+fn set_text_style(style: ui::TextStylePreset) {
+    let style = text_styles::TextStyle {
+        name: style.name.trim().to_string(),
+        values: [style.font_family, style.font_size, style.font_weight, style.letter_spacing]
+            .map(|v| v.trim().to_string()),
+    };
+    if !text_styles::is_valid_name(&style.name) {
+        set_status_text("A text style name starts with a letter and has no spaces");
+        return;
+    }
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let url = {
         let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-        let pc = cache.current_component();
-        (
-            pc.as_ref().map(|pc| pc.url.clone()),
-            pc.as_ref().and_then(|pc| pc.component.clone()),
-            cache.source_code.clone(),
-        )
+        cache.current_component().map(|pc| pc.url)
+    };
+    let Some(edit) = url.and_then(|url| text_styles::set_style(&document_cache, &url, &style))
+    else {
+        return;
     };
 
-    if let Some(document_cache) = document_cache() {
-        let mut document_cache = document_cache.snapshot().unwrap();
-
-        for (url, cache_entry) in &source_code {
-            let mut diag = diagnostics::BuildDiagnostics::default();
-            if document_cache.get_document(url).is_none() {
-                poll_once(document_cache.load_url(
-                    url,
-                    cache_entry.version,
-                    cache_entry.code.clone(),
-                    &mut diag,
-                ));
-            }
-        }
-
-        let uses_widgets = document_cache
-            .get_document(preview_url)
-            .and_then(|d| d.node.as_ref())
-            .map(|n| {
-                n.ImportSpecifier().any(|is| {
-                    is.child_token(i_slint_compiler::parser::SyntaxKind::StringLiteral)
-                        .map(|sl| sl.text() == "\"std-widgets.slint\"")
-                        .unwrap_or_default()
-                })
-            })
-            .unwrap_or_default();
-
-        let mut components = Vec::new();
-        component_catalog::builtin_components(&document_cache, &mut components);
-        component_catalog::all_exported_components(
-            &document_cache,
-            &mut |ci| !ci.is_global,
-            &mut components,
-        );
+    let mut builder = common::WorkspaceEditBuilder::new("Change text style");
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, true);
+}
 
-        for url in document_cache.all_urls().filter(|u| u.scheme() != "builtin") {
-            component_catalog::file_local_components(&document_cache, &url, &mut components);
+// triggered from the UI, running in UI thread
+fn add_form(
+    name: slint::SharedString,
+    struct_name: slint::SharedString,
+    json_example: slint::SharedString,
+) {
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let url = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.current_component().map(|pc| pc.url)
+    };
+    let Some(url) = url else {
+        return;
+    };
+    let name = name.trim();
+    let result = if struct_name.is_empty() {
+        form_scaffold::fields_from_json(&json_example)
+            .and_then(|fields| form_scaffold::add_form(&document_cache, &url, name, Err(&fields)))
+    } else {
+        let declaration = form_scaffold::structs(&document_cache)
+            .into_iter()
+            .find(|s| s.name == struct_name.as_str())
+            .ok_or_else(|| format!("There is no struct called `{struct_name}`"));
+        declaration.and_then(|d| form_scaffold::add_form(&document_cache, &url, name, Ok(&d)))
+    };
+    match result {
+        Ok(builder) => {
+            send_workspace_edit(builder, true);
         }
+        Err(error) => set_status_text(&error),
+    }
+}
 
-        let index = if let Some(component) = component {
-            components
-                .iter()
-                .position(|ci| {
-                    ci.name == component
-                        && ci.defined_at.as_ref().map(|da| da.url()) == previewed_url.as_ref()
-                })
-                .unwrap_or(usize::MAX)
-        } else {
-            usize::MAX
-        };
-
-        PREVIEW_STATE.with(|preview_state| {
-            let mut preview_state = preview_state.borrow_mut();
-            preview_state.known_components = components;
-
-            preview_state.document_cache.borrow_mut().replace(Some(Rc::new(document_cache)));
-
-            let preview_data = preview_state
-                .component_instance()
-                .map(|component_instance| {
-                    preview_data::query_preview_data_properties_and_callbacks(&component_instance)
-                })
-                .unwrap_or_default();
+/// Run `f` with the document cache and the brush preset at `index`
+fn with_brush_preset<R>(
+    index: i32,
+    f: impl FnOnce(&common::DocumentCache, &brush_presets::Palette, &brush_presets::Preset) -> R,
+) -> Option<R> {
+    let document_cache = document_cache()?;
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let palette = &preview_state.brush_palette;
+        let preset = palette.presets.get(usize::try_from(index).ok()?)?;
+        Some(f(&document_cache, palette, preset))
+    })
+}
 
-            if let Some(ui) = &preview_state.ui {
-                ui::ui_set_uses_widgets(ui, uses_widgets);
-                ui::ui_set_known_components(ui, &preview_state.known_components, index);
-                ui::ui_set_preview_data(ui, preview_data, previewed_component);
-            }
-        });
+// triggered from the UI, running in UI thread
+fn apply_brush_preset(
+    element_url: slint::SharedString,
+    _element_version: i32,
+    element_offset: i32,
+    property_name: slint::SharedString,
+    index: i32,
+) {
+    let Ok(element_url) = Url::parse(element_url.as_ref()) else {
+        return;
+    };
+    let Ok(element_offset) = u32::try_from(element_offset) else {
+        return;
+    };
+    let builder = with_brush_preset(index, |document_cache, palette, preset| {
+        let element = document_cache.element_at_offset(&element_url, element_offset.into())?;
+        brush_presets::apply_preset(document_cache, palette, preset, &element, &property_name)
+    })
+    .flatten();
+    if let Some(builder) = builder {
+        send_workspace_edit(builder, true);
     }
+}
 
-    let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-    if let Some(component_instance) = component_instance() {
-        cache.resources = extract_resources(&cache.dependencies, &component_instance);
-    } else {
-        cache.resources.clear();
+// triggered from the UI, running in UI thread
+fn save_brush_preset(name: slint::SharedString, brush: slint::SharedString) {
+    let name = name.trim();
+    if !brush_presets::is_valid_name(name) {
+        set_status_text("A preset name starts with a letter and has no spaces");
+        return;
+    }
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let builder = PREVIEW_STATE.with(|preview_state| {
+        let palette = &preview_state.borrow().brush_palette;
+        brush_presets::save_preset(&document_cache, palette, name, &brush)
+    });
+    if let Some(builder) = builder {
+        send_workspace_edit(builder, false);
     }
 }
 
-fn config_changed(config: PreviewConfig) {
-    let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-
-    if cache.config != config {
-        cache.config = config.clone();
+// triggered from the UI, running in UI thread
+fn replace_with_brush_preset(index: i32) {
+    let builder = with_brush_preset(index, brush_presets::replace_literals).flatten();
+    match builder {
+        Some(builder) => {
+            send_workspace_edit(builder, true);
+        }
+        None => set_status_text("No brush in the project equals the preset"),
+    }
+}
 
-        let current = cache.current_component();
-        let ui_is_visible = cache.ui_is_visible;
-        let hide_ui = cache.config.hide_ui;
+// triggered from the UI, running in UI thread
+fn move_outline_node(source: i32, target: i32, position: ui::OutlineDropPosition) {
+    let Some((source, target)) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        Some((
+            preview_state.outline.get(source as usize)?.as_element_selection(),
+            preview_state.outline.get(target as usize)?.as_element_selection(),
+        ))
+    }) else {
+        return;
+    };
+    let (Some(source), Some(target)) = (source.as_element_node(), target.as_element_node()) else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let position = match position {
+        ui::OutlineDropPosition::Before => drop_location::TreePosition::Before,
+        ui::OutlineDropPosition::Into => drop_location::TreePosition::Into,
+        ui::OutlineDropPosition::After => drop_location::TreePosition::After,
+    };
 
-        drop(cache);
+    if let Some((edit, drop_data)) =
+        drop_location::move_element_in_tree(&document_cache, &source, &target, position)
+    {
+        element_selection::select_element_at_source_code_position(
+            drop_data.path,
+            drop_data.selection_offset,
+            None,
+            SelectionNotification::AfterUpdate,
+        );
 
-        if ui_is_visible {
-            if let Some(hide_ui) = hide_ui {
-                set_show_preview_ui(!hide_ui);
-            }
-            if let Some(current) = current {
-                load_preview(current, LoadBehavior::Reload);
-            }
-        }
+        let mut builder = common::WorkspaceEditBuilder::new("Move element");
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
+    } else {
+        element_selection::reselect_element();
     }
 }
 
-/// If the file is in the cache, returns it.
-///
-/// If the file is not known, the return an empty string marked as "from disk". This is fine:
-/// The LSP side will load the file and inform us about it soon.
-///
-/// In any way, register it as a dependency
-fn get_url_from_cache(url: &Url) -> (SourceFileVersion, String) {
-    let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-    cache.dependencies.insert(url.to_owned());
+// triggered from the UI, running in UI thread
+fn change_z_order_of_selected_element(change: ui::ZOrderChange) {
+    let Some(selected_element_node) = selected_element().and_then(|s| s.as_element_node()) else {
+        return;
+    };
+    let Some(siblings) = selected_element_node.parent().map(|p| p.children()) else {
+        return;
+    };
+    let Some(index) = siblings.iter().position(|s| s == &selected_element_node) else {
+        return;
+    };
+    // Elements declared later are drawn on top
+    let (target, position) = match change {
+        ui::ZOrderChange::Raise => (siblings.get(index + 1), drop_location::TreePosition::After),
+        ui::ZOrderChange::Lower => (
+            index.checked_sub(1).and_then(|i| siblings.get(i)),
+            drop_location::TreePosition::Before,
+        ),
+        ui::ZOrderChange::BringToFront => (siblings.last(), drop_location::TreePosition::After),
+        ui::ZOrderChange::SendToBack => (siblings.first(), drop_location::TreePosition::Before),
+    };
+    let Some(target) = target.filter(|t| *t != &selected_element_node) else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+
+    if let Some((edit, drop_data)) = drop_location::move_element_in_tree(
+        &document_cache,
+        &selected_element_node,
+        target,
+        position,
+    ) {
+        element_selection::select_element_at_source_code_position(
+            drop_data.path,
+            drop_data.selection_offset,
+            None,
+            SelectionNotification::AfterUpdate,
+        );
 
-    cache.source_code.get(url).map(|r| (r.version, r.code.clone())).unwrap_or_default().clone()
+        let mut builder = common::WorkspaceEditBuilder::new("Change stacking order");
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
+    }
 }
 
-fn get_path_from_cache(path: &Path) -> std::io::Result<(SourceFileVersion, String)> {
-    let url = Url::from_file_path(path).map_err(|()| {
-        std::io::Error::new(std::io::ErrorKind::NotFound, "Failed to convert path to URL")
-    })?;
-    Ok(get_url_from_cache(&url))
+// triggered from the UI, running in UI thread
+fn wrap_selected_elements_in_layout(kind: ui::LayoutKind) {
+    let layout = match kind {
+        ui::LayoutKind::None => return,
+        ui::LayoutKind::Horizontal => "HorizontalLayout",
+        ui::LayoutKind::Vertical => "VerticalLayout",
+        ui::LayoutKind::Grid => "GridLayout",
+    };
+    let Some(selected) = selected_element() else {
+        return;
+    };
+    let selected_nodes = std::iter::once(selected)
+        .chain(selected_group())
+        .filter_map(|s| s.as_element_node())
+        .collect::<Vec<_>>();
+    let Some(first) = selected_nodes.first() else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+
+    // Keep the elements where they are unless a layout places them
+    let position = (element_selection::parent_layout_kind(first) == ui::LayoutKind::None)
+        .then(component_instance)
+        .flatten()
+        .and_then(|ci| {
+            let parent = first.parent()?.geometries(&ci).first().copied()?;
+            let bounds = selected_nodes
+                .iter()
+                .filter_map(|n| n.geometries(&ci).first().copied())
+                .reduce(|a, b| a.union(&b))?;
+            Some(LogicalPoint::new(
+                bounds.origin.x - parent.origin.x,
+                bounds.origin.y - parent.origin.y,
+            ))
+        });
+
+    if let Some((edit, drop_data)) =
+        drop_location::wrap_in_layout(&document_cache, &selected_nodes, layout, position)
+    {
+        element_selection::select_element_at_source_code_position(
+            drop_data.path,
+            drop_data.selection_offset,
+            None,
+            SelectionNotification::AfterUpdate,
+        );
+
+        let mut builder = common::WorkspaceEditBuilder::new(format!("Wrap in {layout}"));
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
+    }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum LoadBehavior {
-    /// We reload the preview, most likely because a file has changed
-    Reload,
-    /// Load the preview and make the window visible if it wasn't already.
-    Load,
-    /// We show the preview because the user asked for it. The UI should become visible and focused if it wasn't already
-    BringWindowToFront,
+// triggered from the UI, running in UI thread
+fn assign_ids_to_selected_elements() {
+    let Some(selected) = selected_element() else {
+        return;
+    };
+    let selected_nodes = std::iter::once(selected)
+        .chain(selected_group())
+        .filter_map(|s| s.as_element_node())
+        .collect::<Vec<_>>();
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+
+    if let Some((edit, drop_data)) = drop_location::assign_ids(&document_cache, &selected_nodes) {
+        element_selection::select_element_at_source_code_position(
+            drop_data.path,
+            drop_data.selection_offset,
+            None,
+            SelectionNotification::AfterUpdate,
+        );
+
+        let mut builder = common::WorkspaceEditBuilder::new("Assign ids");
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
+    } else {
+        set_status_text("The selected elements have ids already");
+    }
 }
 
-pub fn reload_preview() {
-    let pc = {
-        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-        cache.current_previewed_component.clone()
+// triggered from the UI, running in UI thread
+fn rename_element_id(
+    element_url: slint::SharedString,
+    element_offset: i32,
+    new_id: slint::SharedString,
+) {
+    let Ok(url) = Url::parse(element_url.as_str()) else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(element) = u32::try_from(element_offset)
+        .ok()
+        .and_then(|offset| document_cache.element_at_offset(&url, offset.into()))
+    else {
+        return;
     };
 
-    let Some(pc) = pc else {
+    match drop_location::rename_element_id(&document_cache, &element, &new_id) {
+        Ok((edit, drop_data)) => {
+            element_selection::select_element_at_source_code_position(
+                drop_data.path,
+                drop_data.selection_offset,
+                None,
+                SelectionNotification::AfterUpdate,
+            );
+
+            let mut builder = common::WorkspaceEditBuilder::new("Rename element id");
+            builder.add_workspace_edit(edit);
+            send_workspace_edit(builder, false);
+        }
+        Err(message) => set_status_text(&message),
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn nudge_selected_element(dx: f32, dy: f32) {
+    let Some(selected) = selected_element() else {
+        return;
+    };
+    if report_locked_selection() {
+        return;
+    }
+    let Some(selected_element_node) = selected.as_element_node() else {
+        return;
+    };
+    let group = selected_group();
+    // Nudging only ever changes x and y, it never moves elements into a different parent
+    if !std::iter::once(selected_element_node.clone())
+        .chain(group.iter().filter_map(|s| s.as_element_node()))
+        .all(|en| element_selection::parent_layout_kind(&en) == ui::LayoutKind::None)
+    {
+        return;
+    }
+    let Some(component_instance) = component_instance() else {
+        return;
+    };
+    let Some(geometry) =
+        selected_element_node.geometries(&component_instance).get(selected.instance_index).cloned()
+    else {
         return;
     };
 
-    load_preview(pc, LoadBehavior::Load);
+    move_selected_elements(
+        &selected_element_node,
+        selected.instance_index,
+        &group,
+        geometry.origin + LogicalVector::new(dx, dy),
+    );
 }
 
-async fn reload_timer_function() {
-    let (selected, notify_editor) = PREVIEW_STATE.with(|preview_state| {
-        let mut preview_state = preview_state.borrow_mut();
-        let notify_editor = preview_state.notify_editor_about_selection_after_update;
-        preview_state.notify_editor_about_selection_after_update = false;
-        (preview_state.selected.take(), notify_editor)
-    });
+/// How close (in logical pixels) an edge needs to get to a sibling's edge to snap to it
+const SNAP_DISTANCE: f32 = 4.0;
 
-    loop {
-        let (preview_component, config, behavior) = {
-            let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-            let Some(behavior) = cache.current_load_behavior.take() else { return };
+// triggered from the UI, running in UI thread
+fn snap_selected_element(
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    resizing: bool,
+) -> ui::SelectionRectangle {
+    let unchanged = ui::SelectionRectangle { x, y, width, height };
+    let Some(selected) = selected_element() else {
+        return unchanged;
+    };
+    let Some(selected_element_node) = selected.as_element_node() else {
+        return unchanged;
+    };
+    // Layouts position their children, there is nothing to align
+    if element_selection::parent_layout_kind(&selected_element_node) != ui::LayoutKind::None {
+        return unchanged;
+    }
+    let Some(component_instance) = component_instance() else {
+        return unchanged;
+    };
+    let Some(original) =
+        selected_element_node.geometries(&component_instance).get(selected.instance_index).cloned()
+    else {
+        return unchanged;
+    };
 
-            let Some(preview_component) = cache.current_component() else {
-                return;
-            };
-            cache.clear_style_of_component();
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let targets = preview_state
+            .snap_targets
+            .get_or_insert_with(|| {
+                element_selection::snap_targets(&component_instance, &selected_element_node)
+            })
+            .clone();
+        let Some(ui) = preview_state.ui.as_ref() else {
+            return unchanged;
+        };
+        let api = ui.global::<ui::Api>();
 
-            assert_eq!(cache.loading_state, PreviewFutureState::PreLoading);
+        let grid = api.get_snap_to_grid().then(|| api.get_grid_size().max(1) as f32);
+        let (mut snapped, mut guides) = element_selection::snap_rect(
+            &targets,
+            original,
+            LogicalRect::new(LogicalPoint::new(x, y), LogicalSize::new(width, height)),
+            resizing,
+            grid,
+            SNAP_DISTANCE,
+        );
 
-            if !cache.ui_is_visible && behavior == LoadBehavior::Reload {
-                cache.loading_state = PreviewFutureState::Pending;
-                return;
+        // Texts keep the vertical rhythm rather than aligning with their siblings
+        if api.get_show_baseline_grid() && !resizing {
+            if let Some(baseline) = baseline_grid::text_baseline(ui.window(), original) {
+                let grid = baseline_grid::BaselineGrid {
+                    rhythm: api.get_baseline_rhythm().max(2) as f32,
+                    offset: 0.0,
+                };
+                let (rect, line) = grid.snap(snapped, baseline);
+                snapped = rect;
+                guides.retain(|g| matches!(g, element_selection::AlignmentGuide::Vertical(_)));
+                guides.push(element_selection::AlignmentGuide::Horizontal(line));
             }
-            cache.loading_state = PreviewFutureState::Loading;
-            cache.dependencies.clear();
-            (preview_component, cache.config.clone(), behavior)
+        }
+
+        let guides = guides
+            .into_iter()
+            .map(|g| match g {
+                element_selection::AlignmentGuide::Vertical(position) => {
+                    ui::AlignmentGuide { vertical: true, position }
+                }
+                element_selection::AlignmentGuide::Horizontal(position) => {
+                    ui::AlignmentGuide { vertical: false, position }
+                }
+            })
+            .collect::<Vec<_>>();
+        api.set_alignment_guides(slint::ModelRc::from(Rc::new(slint::VecModel::from(guides))));
+
+        ui::SelectionRectangle {
+            x: snapped.origin.x,
+            y: snapped.origin.y,
+            width: snapped.size.width,
+            height: snapped.size.height,
+        }
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn snap_selected_element_done() {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        preview_state.snap_targets = None;
+        if let Some(ui) = &preview_state.ui {
+            ui.global::<ui::Api>().set_alignment_guides(Default::default());
+        }
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn measure_at(x: f32, y: f32) {
+    let measurements = (|| {
+        let selected = selected_element()?;
+        let selected_element_node = selected.as_element_node()?;
+        let component_instance = component_instance()?;
+        let from = selected_element_node
+            .geometries(&component_instance)
+            .get(selected.instance_index)
+            .copied()?;
+        let to = element_selection::measure_target_at(
+            &component_instance,
+            &selected_element_node,
+            LogicalPoint::new(x, y),
+        )?;
+        Some((to, element_selection::measure(&from, &to)))
+    })();
+
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let Some(api) = preview_state.ui.as_ref().map(|ui| ui.global::<ui::Api>()) else {
+            return;
         };
-        let style = if preview_component.style.is_empty() {
-            get_current_style()
-        } else {
-            set_current_style(preview_component.style.clone());
-            preview_component.style.clone()
+        let Some((target, measurements)) = measurements else {
+            api.set_measurements(Default::default());
+            return;
         };
 
-        match reload_preview_impl(preview_component, behavior, style, config).await {
-            Ok(()) => {}
-            Err(e) => {
-                CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().loading_state =
-                    PreviewFutureState::Pending;
-                send_platform_error_notification(&e.to_string());
-                return;
-            }
+        let measurements = measurements
+            .into_iter()
+            .map(|m| ui::Measurement {
+                x1: m.start.x,
+                y1: m.start.y,
+                x2: m.end.x,
+                y2: m.end.y,
+                label: format!("{}", (m.distance() * 10.0).round() / 10.0).into(),
+            })
+            .collect::<Vec<_>>();
+        api.set_measure_target(ui::SelectionRectangle {
+            x: target.origin.x,
+            y: target.origin.y,
+            width: target.size.width,
+            height: target.size.height,
+        });
+        api.set_measurements(slint::ModelRc::from(Rc::new(slint::VecModel::from(measurements))));
+    })
+}
+
+/// Move the primary selected element to `position` and all other selected elements
+/// by the same distance
+fn move_selected_elements(
+    selected_element_node: &ElementRcNode,
+    instance_index: usize,
+    group: &[ElementSelection],
+    position: LogicalPoint,
+) {
+    let Some(component_instance) = component_instance() else {
+        return;
+    };
+    let Some(geometry) =
+        selected_element_node.geometries(&component_instance).get(instance_index).cloned()
+    else {
+        return;
+    };
+    let delta = position - geometry.origin;
+
+    let nodes = std::iter::once((selected_element_node.clone(), instance_index))
+        .chain(group.iter().filter_map(|s| Some((s.as_element_node()?, s.instance_index))))
+        .collect::<Vec<_>>();
+    let moved = nodes.iter().map(|(en, _)| en.path_and_offset()).collect::<Vec<_>>();
+
+    let mut builder = common::WorkspaceEditBuilder::new(if group.is_empty() {
+        "Move element"
+    } else {
+        "Move elements"
+    });
+    for (en, index) in nodes.iter().filter(|(en, _)| !is_descendant_of_any(en, &moved)) {
+        let Some(rect) = en.geometries(&component_instance).get(*index).cloned() else {
+            continue;
+        };
+        if let Some((edit, _)) = resize_selected_element_impl(en, *index, rect.translate(delta)) {
+            builder.add_workspace_edit(edit);
         }
+    }
 
-        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-        match cache.loading_state {
-            PreviewFutureState::Loading => {
-                cache.loading_state = PreviewFutureState::Pending;
-                break;
-            }
-            PreviewFutureState::NeedsReload => {
-                cache.loading_state = PreviewFutureState::PreLoading;
-                continue;
-            }
-            PreviewFutureState::Pending | PreviewFutureState::PreLoading => unreachable!(),
+    if !send_workspace_edit(builder, true) {
+        element_selection::reselect_element();
+    }
+}
+
+fn test_workspace_edit(edit: &lsp_types::WorkspaceEdit, test_edit: bool) -> bool {
+    if test_edit {
+        let Some(document_cache) = document_cache() else {
+            return false;
         };
+        drop_location::workspace_edit_compiles(&document_cache, edit)
+    } else {
+        true
     }
+}
 
-    if let Some(se) = selected {
-        element_selection::select_element_at_source_code_position(
-            se.path.clone(),
-            se.offset,
-            None,
-            SelectionNotification::Never,
-        );
+/// Whether the preview is partial, telling the user why nothing can be edited then
+fn preview_is_partial() -> bool {
+    let is_partial = PREVIEW_STATE.with(|preview_state| preview_state.borrow().preview_is_partial);
+    if is_partial {
+        set_status_text("Fix the errors in the document to edit it in the preview");
+    }
+    is_partial
+}
 
-        if notify_editor {
-            if let Some(component_instance) = component_instance() {
-                if let Some((element, debug_index)) = component_instance
-                    .element_node_at_source_code_position(&se.path, se.offset.into())
-                    .first()
+/// Send all edits of one operation to the editor, so they get applied (and undone) together
+fn send_workspace_edit(edit: common::WorkspaceEditBuilder, test_edit: bool) -> bool {
+    if edit.is_empty() {
+        return false;
+    }
+    let (label, edit) = match edit.build() {
+        Ok(r) => r,
+        Err(e) => {
+            set_status_text(&format!("Can not apply edit: {e}"));
+            return false;
+        }
+    };
+    if !test_workspace_edit(&edit, test_edit) {
+        return false;
+    }
+    send_built_workspace_edit(label, edit)
+}
+
+fn send_built_workspace_edit(label: String, edit: lsp_types::WorkspaceEdit) -> bool {
+    if preview_is_partial() {
+        return false;
+    }
+    let entry = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        edit_journal::JournalEntry::new(label, edit, |url| {
+            cache.source_code.get(url).map(|entry| entry.code.clone())
+        })
+    };
+    send_journaled_edit(entry, false)
+}
+
+/// Send the edit of `entry` to the editor, keeping it in the journal until it comes back.
+/// Replayed edits are in the journal already.
+fn send_journaled_edit(entry: edit_journal::JournalEntry, replay: bool) -> bool {
+    let workspace_edit_sent = PREVIEW_STATE.with(|preview_state| {
+        let mut ps = preview_state.borrow_mut();
+        let result = ps.workspace_edit_sent;
+        if !result {
+            ps.workspace_edit_sent = true;
+            ps.journaled_edit = Some(entry.clone());
+        }
+        result
+    });
+
+    if !workspace_edit_sent {
+        // The preview needs to show its own changes right away
+        CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().force_next_reload = true;
+        let edit = if replay { entry.unversioned_edit() } else { entry.edit.clone() };
+        let label = entry.label.clone();
+        if !replay {
+            edit_journal::record(entry);
+        }
+        send_message_to_lsp(PreviewToLspMessage::SendWorkspaceEdit { label: Some(label), edit });
+        return true;
+    }
+    false
+}
+
+/// Show the diff of a destructive edit to the user, who then applies all or parts of it, or
+/// cancels it
+fn review_workspace_edit(edit: common::WorkspaceEditBuilder, test_edit: bool) {
+    if edit.is_empty() || preview_is_partial() {
+        return;
+    }
+    let (label, edit) = match edit.build() {
+        Ok(r) => r,
+        Err(e) => {
+            set_status_text(&format!("Can not apply edit: {e}"));
+            return;
+        }
+    };
+    if !test_workspace_edit(&edit, test_edit) {
+        return;
+    }
+
+    let pending = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        edit_review::PendingEdit::new(label, edit, test_edit, |url| {
+            cache.source_code.get(url).map(|entry| entry.code.clone())
+        })
+    };
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_edit_review(ui, Some(&pending));
+        }
+        preview_state.pending_edit = Some(pending);
+    });
+}
+
+// triggered from the UI, running in UI thread
+fn edit_review_toggle_hunk(index: i32) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let preview_state = &mut *preview_state;
+        let Some(pending) = &mut preview_state.pending_edit else {
+            return;
+        };
+        let Some(hunk) = usize::try_from(index).ok().and_then(|i| pending.hunks.get_mut(i)) else {
+            return;
+        };
+        hunk.accepted = !hunk.accepted;
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_edit_review(ui, Some(&*pending));
+        }
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn edit_review_apply() {
+    let Some((label, edit, needs_test)) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let pending = preview_state.pending_edit.as_ref()?;
+        // The whole edit got tested before, but parts of it may not compile
+        let needs_test = pending.test_edit && pending.is_partially_accepted();
+        Some((pending.label.clone(), pending.accepted_edit(), needs_test))
+    }) else {
+        return;
+    };
+
+    if let Some(edit) = &edit {
+        if needs_test && !test_workspace_edit(edit, true) {
+            set_status_text("The accepted changes alone do not compile, accept more of them");
+            return;
+        }
+    }
+
+    edit_review_cancel();
+    if let Some(edit) = edit {
+        send_built_workspace_edit(label, edit);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn edit_review_cancel() {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        preview_state.pending_edit = None;
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_edit_review(ui, None);
+        }
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn replay_recovered_edits() {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let recovered = std::mem::take(&mut preview_state.recovered_edits);
+        preview_state.replayed_edits.extend(recovered);
+    });
+    set_recovered_edits(Vec::new());
+    replay_next_edit();
+}
+
+/// Send the next of the replayed edits to the editor, once the previous one came back.
+/// Edits made for another text of their documents get dropped.
+fn replay_next_edit() {
+    loop {
+        let entry = PREVIEW_STATE.with(|preview_state| {
+            let mut preview_state = preview_state.borrow_mut();
+            if preview_state.workspace_edit_sent || preview_state.replayed_edits.is_empty() {
+                return None;
+            }
+            Some(preview_state.replayed_edits.remove(0))
+        });
+        let Some(entry) = entry else {
+            return;
+        };
+        let applies = {
+            let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+            entry.applies_to(|url| cache.source_code.get(url).map(|entry| entry.code.clone()))
+        };
+        if applies {
+            send_journaled_edit(entry, true);
+            return;
+        }
+        set_status_text(&format!(
+            "Dropped the edit \"{}\": The document changed since it was made",
+            entry.label
+        ));
+        edit_journal::remove(entry);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn discard_recovered_edits() {
+    let recovered = PREVIEW_STATE
+        .with(|preview_state| std::mem::take(&mut preview_state.borrow_mut().recovered_edits));
+    for entry in recovered {
+        edit_journal::remove(entry);
+    }
+    set_recovered_edits(Vec::new());
+}
+
+fn set_recovered_edits(entries: Vec<edit_journal::JournalEntry>) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        if let Some(ui) = &preview_state.ui {
+            ui::set_recovered_edits(ui, &entries);
+        }
+        preview_state.recovered_edits = entries;
+    });
+}
+
+/// Restore the settings of the project of `preview_component` when the preview switches to
+/// another project. Previewing a file without naming a component shows the component that was
+/// previewed last, if it is in that file.
+fn restore_project_settings(mut preview_component: PreviewComponent) -> PreviewComponent {
+    let Ok(path) = preview_component.url.to_file_path() else {
+        return preview_component;
+    };
+    let project = project_settings::project_of(&path);
+    let settings = PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        if preview_state.project.as_ref() == Some(&project) {
+            return None;
+        }
+        let settings = preview_state
+            .session_settings
+            .get(&project)
+            .cloned()
+            .unwrap_or_else(|| project_settings::load(&project));
+        preview_state.project = Some(project.clone());
+        edit_journal::open(&project, |entries| {
+            let _ = i_slint_core::api::invoke_from_event_loop(move || set_recovered_edits(entries));
+        });
+        preview_state.component_usage = settings.component_usage.clone();
+        preview_state.fixtures = settings.fixtures.clone();
+        element_locks::restore(&project, &settings.locked_elements);
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_project_settings(ui, &settings);
+            let api = ui.global::<ui::Api>();
+            api.set_store_project_settings(project_settings::is_stored(&project));
+        }
+        Some(settings)
+    });
+    let Some(settings) = settings else {
+        return preview_component;
+    };
+
+    if preview_component.component.is_none() {
+        if let Some(name) = settings.component_in(&project, &preview_component.url) {
+            preview_component.component = Some(name);
+            CONTENT_CACHE
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap()
+                .set_current_component(preview_component.clone());
+        }
+    }
+    preview_component
+}
+
+/// Store the project settings, once they stopped changing for a while
+fn schedule_storing_project_settings(preview_state: &mut PreviewState) {
+    preview_state.project_settings_timer.get_or_insert_with(slint::Timer::default).start(
+        slint::TimerMode::SingleShot,
+        std::time::Duration::from_secs(1),
+        store_project_settings,
+    );
+}
+
+// triggered from the UI, running in UI thread
+fn project_settings_changed() {
+    PREVIEW_STATE.with(|preview_state| {
+        schedule_storing_project_settings(&mut preview_state.borrow_mut());
+    });
+}
+
+fn store_project_settings() {
+    let url = CONTENT_CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .current_component()
+        .map(|pc| pc.url);
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let (Some(project), Some(ui)) = (preview_state.project.clone(), &preview_state.ui) else {
+            return;
+        };
+        let mut settings = ui::project_settings(ui);
+        let stored = ui.global::<ui::Api>().get_store_project_settings();
+        if let (Some(url), Some(component_instance)) = (url, preview_state.component_instance()) {
+            settings.set_component(&project, &url, component_instance.definition().name());
+        }
+        settings.component_usage = preview_state.component_usage.clone();
+        settings.locked_elements = element_locks::project_locks(&project);
+        settings.fixtures = preview_state.fixtures.clone();
+        preview_state.session_settings.insert(project.clone(), settings.clone());
+
+        let result = if !stored {
+            project_settings::forget(&project)
+        } else if project_settings::load(&project) != settings {
+            project_settings::store(&project, &settings)
+        } else {
+            Ok(())
+        };
+        if let Err(e) = result {
+            set_status_text(&e);
+        }
+    });
+}
+
+fn change_style() {
+    project_settings_changed();
+    let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    let ui_is_visible = cache.ui_is_visible;
+    let Some(current) = cache.current_component() else {
+        return;
+    };
+
+    drop(cache);
+
+    if ui_is_visible {
+        load_preview(current, LoadBehavior::Reload);
+    }
+}
+
+fn start_parsing() {
+    set_status_text("Updating Preview...");
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow_mut();
+
+        if let Some(ui) = &preview_state.ui {
+            ui::set_diagnostics(ui, &[]);
+        }
+    });
+}
+
+fn extract_resources(
+    dependencies: &HashSet<Url>,
+    component_instance: &ComponentInstance,
+) -> HashSet<Url> {
+    let tl = component_instance.definition().type_loader();
+
+    let mut result: HashSet<Url> = Default::default();
+
+    for d in dependencies {
+        let Ok(path) = d.to_file_path() else {
+            continue;
+        };
+        let Some(doc) = tl.get_document(&path) else {
+            continue;
+        };
+
+        result.extend(
+            doc.embedded_file_resources
+                .borrow()
+                .keys()
+                .filter_map(|fp| Url::from_file_path(fp).ok()),
+        );
+    }
+
+    result
+}
+
+fn finish_parsing(preview_url: &Url, previewed_component: Option<String>) {
+    set_status_text("");
+
+    let (previewed_url, component, source_code) = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        let pc = cache.current_component();
+        (
+            pc.as_ref().map(|pc| pc.url.clone()),
+            pc.as_ref().and_then(|pc| pc.component.clone()),
+            cache.source_code.clone(),
+        )
+    };
+
+    if let Some(document_cache) = document_cache() {
+        let mut document_cache = document_cache.snapshot().unwrap();
+
+        for (url, cache_entry) in &source_code {
+            let mut diag = diagnostics::BuildDiagnostics::default();
+            if document_cache.get_document(url).is_none() {
+                poll_once(document_cache.load_url(
+                    url,
+                    cache_entry.version,
+                    cache_entry.code.clone(),
+                    &mut diag,
+                ));
+            }
+        }
+
+        let uses_widgets = document_cache
+            .get_document(preview_url)
+            .and_then(|d| d.node.as_ref())
+            .map(|n| {
+                n.ImportSpecifier().any(|is| {
+                    is.child_token(i_slint_compiler::parser::SyntaxKind::StringLiteral)
+                        .map(|sl| sl.text() == "\"std-widgets.slint\"")
+                        .unwrap_or_default()
+                })
+            })
+            .unwrap_or_default();
+
+        let mut components = Vec::new();
+        component_catalog::builtin_components(&document_cache, &mut components);
+        component_catalog::all_exported_components(
+            &document_cache,
+            &mut |ci| !ci.is_global,
+            &mut components,
+        );
+
+        for url in document_cache.all_urls().filter(|u| u.scheme() != "builtin") {
+            component_catalog::file_local_components(&document_cache, &url, &mut components);
+        }
+
+        let index = if let Some(component) = component {
+            components
+                .iter()
+                .position(|ci| {
+                    ci.name == component
+                        && ci.defined_at.as_ref().map(|da| da.url()) == previewed_url.as_ref()
+                })
+                .unwrap_or(usize::MAX)
+        } else {
+            usize::MAX
+        };
+
+        PREVIEW_STATE.with(|preview_state| {
+            let mut preview_state = preview_state.borrow_mut();
+            preview_state.known_components = components;
+            preview_state.current_component_index = index;
+            // Remember the previewed component
+            schedule_storing_project_settings(&mut preview_state);
+            preview_state.localization = preview_state
+                .component_instance()
+                .map(|ci| localization::report(&document_cache, &ci))
+                .unwrap_or_default();
+            preview_state.color_audit = color_audit::audit(&document_cache, previewed_url.clone());
+            preview_state.text_styles = text_styles::text_styles(&document_cache);
+            preview_state.brush_palette = previewed_url
+                .as_ref()
+                .and_then(brush_presets::palette_url)
+                .map(|url| brush_presets::load(&document_cache, url))
+                .unwrap_or_default();
+            preview_state.callback_handlers = previewed_url
+                .as_ref()
+                .and_then(|url| document_cache.get_document(url)?.node.as_ref())
+                .map(breakpoints::handlers)
+                .unwrap_or_default();
+            if let Some(path) = previewed_url.as_ref().and_then(|u| u.to_file_path().ok()) {
+                breakpoints::retain(&path, &preview_state.callback_handlers);
+            }
+            let form_structs = form_scaffold::structs(&document_cache);
+            let global_names = previewed_url
+                .as_ref()
+                .map(|url| globals::exported_globals(&document_cache, url))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>();
+            let current_global = preview_state
+                .ui
+                .as_ref()
+                .map(|ui| ui.global::<ui::Api>().get_current_global().to_string())
+                .filter(|name| global_names.contains(name))
+                .or_else(|| global_names.first().cloned())
+                .unwrap_or_default();
+            let global_properties = previewed_url
+                .as_ref()
+                .and_then(|url| globals::global_properties(&document_cache, url, &current_global));
+
+            preview_state.document_cache.borrow_mut().replace(Some(Rc::new(document_cache)));
+
+            if let Some(component_instance) = preview_state.component_instance() {
+                let errors = model_fixtures::apply(&preview_state.fixtures, &component_instance);
+                if !errors.is_empty() {
+                    set_status_text(&errors.join("\n"));
+                }
+            }
+
+            let preview_data = preview_state
+                .component_instance()
+                .map(|component_instance| {
+                    preview_data::query_preview_data_properties_and_callbacks(&component_instance)
+                })
+                .unwrap_or_default();
+
+            preview_state.outline = preview_state
+                .component_instance()
+                .map(|ci| outline::outline(&ci))
+                .unwrap_or_default();
+
+            if let Some(ui) = &preview_state.ui {
+                ui::ui_set_uses_widgets(ui, uses_widgets);
+                ui::ui_set_known_components(
+                    ui,
+                    &preview_state.known_components,
+                    index,
+                    &preview_state.component_usage,
+                );
+                ui::ui_set_preview_data(ui, preview_data, previewed_component);
+                ui::ui_set_outline(
+                    ui,
+                    &preview_state.outline,
+                    preview_state.selected.as_ref(),
+                    &layer_overrides(),
+                );
+                ui::ui_set_localization_report(ui, &preview_state.localization);
+                ui::ui_set_color_audit(ui, &preview_state.color_audit);
+                ui::ui_set_text_styles(ui, &preview_state.text_styles);
+                ui::ui_set_brush_presets(ui, &preview_state.brush_palette);
+                ui::ui_set_callback_handlers(ui, &preview_state.callback_handlers);
+                ui::ui_set_form_structs(ui, &form_structs);
+                ui::ui_set_global_names(ui, &global_names);
+                if let Some(document_cache) = document_cache_from(&preview_state) {
+                    ui::ui_set_global_properties(
+                        ui,
+                        &document_cache,
+                        &current_global,
+                        global_properties,
+                    );
+                }
+                update_states(ui);
+            }
+        });
+
+        PREVIEW_STATE.with(|preview_state| {
+            thumbnails::update(&preview_state.borrow().known_components);
+        });
+    }
+
+    let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    if let Some(component_instance) = component_instance() {
+        cache.resources = extract_resources(&cache.dependencies, &component_instance);
+    } else {
+        cache.resources.clear();
+    }
+}
+
+/// A file was saved in the editor: Catch up on all changes if reloads only happen on save
+fn document_saved(url: &Url) {
+    let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    if !cache.preview_is_stale || !cache.dependencies.contains(url) {
+        return;
+    }
+    let Some(current) = cache.current_component() else {
+        return;
+    };
+    cache.force_next_reload = true;
+    drop(cache);
+
+    load_preview(current, LoadBehavior::Reload);
+}
+
+// triggered from the UI, running in UI thread
+fn reload_settings_changed(on_save_only: bool, debounce_ms: i32) {
+    let (current, catch_up) = {
+        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.reload_settings = ReloadSettings {
+            debounce: std::time::Duration::from_millis(debounce_ms.max(0) as u64),
+            on_save_only,
+        };
+        (cache.current_component(), !on_save_only && cache.preview_is_stale)
+    };
+
+    // Switching back to automatic reloading: Show all changes made meanwhile
+    if let (Some(current), true) = (current, catch_up) {
+        load_preview(current, LoadBehavior::Reload);
+    }
+}
+
+fn set_preview_is_stale(is_stale: bool) {
+    let _ = run_in_ui_thread(move || async move {
+        PREVIEW_STATE.with(|preview_state| {
+            let preview_state = preview_state.borrow();
+            if let Some(ui) = &preview_state.ui {
+                let api = ui.global::<ui::Api>();
+                api.set_preview_is_stale(is_stale)
+            }
+        })
+    });
+}
+
+fn config_changed(config: PreviewConfig) {
+    let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+
+    if cache.config != config {
+        cache.config = config.clone();
+
+        let current = cache.current_component();
+        let ui_is_visible = cache.ui_is_visible;
+        let hide_ui = cache.config.hide_ui;
+
+        drop(cache);
+
+        if ui_is_visible {
+            if let Some(hide_ui) = hide_ui {
+                set_show_preview_ui(!hide_ui);
+            }
+            if let Some(current) = current {
+                load_preview(current, LoadBehavior::Reload);
+            }
+        }
+    }
+}
+
+/// If the file is in the cache, returns it.
+///
+/// If the file is not known, the return an empty string marked as "from disk". This is fine:
+/// The LSP side will load the file and inform us about it soon.
+///
+/// In any way, register it as a dependency
+fn get_url_from_cache(url: &Url) -> (SourceFileVersion, String) {
+    let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    cache.dependencies.insert(url.to_owned());
+
+    let (version, code) =
+        cache.source_code.get(url).map(|r| (r.version, r.code.clone())).unwrap_or_default();
+    let code = match &cache.forced_state {
+        Some((forced_url, offset)) if forced_url == url => {
+            let mut diag = diagnostics::BuildDiagnostics::default();
+            let document = i_slint_compiler::parser::parse(code.clone(), None, &mut diag);
+            states::force_state(&document, *offset).unwrap_or(code)
+        }
+        _ => code,
+    };
+    let hidden =
+        url.to_file_path().map(|path| cache.layer_overrides.hidden_in(&path)).unwrap_or_default();
+    if hidden.is_empty() {
+        return (version, code);
+    }
+    let mut diag = diagnostics::BuildDiagnostics::default();
+    let document = i_slint_compiler::parser::parse(code.clone(), None, &mut diag);
+    (version, layer_overrides::hide_elements(&document, &hidden).unwrap_or(code))
+}
+
+fn get_path_from_cache(path: &Path) -> std::io::Result<(SourceFileVersion, String)> {
+    let url = Url::from_file_path(path).map_err(|()| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "Failed to convert path to URL")
+    })?;
+    Ok(get_url_from_cache(&url))
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LoadBehavior {
+    /// We reload the preview, most likely because a file has changed
+    Reload,
+    /// Load the preview and make the window visible if it wasn't already.
+    Load,
+    /// We show the preview because the user asked for it. The UI should become visible and focused if it wasn't already
+    BringWindowToFront,
+}
+
+pub fn reload_preview() {
+    let pc = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.current_previewed_component.clone()
+    };
+
+    let Some(pc) = pc else {
+        return;
+    };
+
+    load_preview(pc, LoadBehavior::Load);
+}
+
+async fn reload_timer_function() {
+    let (selected, selected_group, notify_editor) = PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let notify_editor = preview_state.notify_editor_about_selection_after_update;
+        preview_state.notify_editor_about_selection_after_update = false;
+        (
+            preview_state.selected.take(),
+            std::mem::take(&mut preview_state.selected_group),
+            notify_editor,
+        )
+    });
+
+    loop {
+        let (preview_component, config, behavior) = {
+            let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+            let Some(behavior) = cache.current_load_behavior.take() else { return };
+
+            let Some(preview_component) = cache.current_component() else {
+                return;
+            };
+            cache.clear_style_of_component();
+
+            assert_eq!(cache.loading_state, PreviewFutureState::PreLoading);
+
+            if !cache.ui_is_visible && behavior == LoadBehavior::Reload {
+                cache.loading_state = PreviewFutureState::Pending;
+                return;
+            }
+            cache.loading_state = PreviewFutureState::Loading;
+            cache.dependencies.clear();
+            if std::mem::take(&mut cache.preview_is_stale) {
+                set_preview_is_stale(false);
+            }
+            (preview_component, cache.config.clone(), behavior)
+        };
+        let custom_styles =
+            style_library::custom_styles(&config.include_paths, &config.library_paths);
+        PREVIEW_STATE.with(|preview_state| {
+            if let Some(ui) = &preview_state.borrow().ui {
+                ui::ui_set_custom_styles(ui, &custom_styles);
+            }
+        });
+        let preview_component = restore_project_settings(preview_component);
+        let style = if preview_component.style.is_empty() {
+            get_current_style()
+        } else {
+            set_current_style(preview_component.style.clone());
+            preview_component.style.clone()
+        };
+        style_library::watch(custom_styles.iter().find(|s| s.name == style));
+
+        match reload_preview_impl(preview_component, behavior, style, config).await {
+            Ok(()) => {}
+            Err(e) => {
+                CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().loading_state =
+                    PreviewFutureState::Pending;
+                send_platform_error_notification(&e.to_string());
+                return;
+            }
+        }
+
+        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        match cache.loading_state {
+            PreviewFutureState::Loading => {
+                cache.loading_state = PreviewFutureState::Pending;
+                break;
+            }
+            PreviewFutureState::NeedsReload => {
+                cache.loading_state = PreviewFutureState::PreLoading;
+                continue;
+            }
+            PreviewFutureState::Pending | PreviewFutureState::PreLoading => unreachable!(),
+        };
+    }
+
+    let errors = preview_windows::reload().await;
+    if !errors.is_empty() {
+        set_status_text(&format!("Failed to update preview windows: {}", errors.join("; ")));
+    }
+
+    if let Some(se) = selected {
+        element_selection::select_element_at_source_code_position(
+            se.path.clone(),
+            se.offset,
+            None,
+            SelectionNotification::Never,
+        );
+
+        if !selected_group.is_empty() {
+            PREVIEW_STATE.with(|preview_state| {
+                preview_state.borrow_mut().selected_group = selected_group;
+            });
+            element_selection::reselect_element();
+        }
+
+        if notify_editor {
+            if let Some(component_instance) = component_instance() {
+                if let Some((element, debug_index)) = component_instance
+                    .element_node_at_source_code_position(&se.path, se.offset.into())
+                    .first()
                 {
                     let Some(element_node) = ElementRcNode::new(element.clone(), *debug_index)
                     else {
@@ -1180,325 +3526,2025 @@ async fn reload_timer_function() {
                 }
             }
         }
-    }
+    }
+}
+
+pub fn load_preview(preview_component: PreviewComponent, behavior: LoadBehavior) {
+    {
+        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+
+        match behavior {
+            LoadBehavior::Reload => {
+                if !cache.ui_is_visible {
+                    return;
+                }
+                let forced = std::mem::take(&mut cache.force_next_reload);
+                if cache.reload_settings.on_save_only && !forced {
+                    if !cache.preview_is_stale {
+                        cache.preview_is_stale = true;
+                        set_preview_is_stale(true);
+                    }
+                    return;
+                }
+            }
+            LoadBehavior::Load | LoadBehavior::BringWindowToFront => {
+                cache.set_current_component(preview_component)
+            }
+        }
+
+        cache.current_load_behavior = Some(behavior);
+
+        match cache.loading_state {
+            PreviewFutureState::Pending => {}
+            PreviewFutureState::Loading => {
+                cache.loading_state = PreviewFutureState::NeedsReload;
+                return;
+            }
+            PreviewFutureState::NeedsReload | PreviewFutureState::PreLoading => {
+                return;
+            }
+        }
+        cache.loading_state = PreviewFutureState::PreLoading;
+    };
+
+    let delay = if behavior == LoadBehavior::Reload {
+        CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().reload_settings.debounce
+    } else {
+        core::time::Duration::from_millis(50)
+    };
+
+    if let Err(e) = run_in_ui_thread(move || async move {
+        PREVIEW_STATE.with(|preview_state| {
+            // Starting a running timer again restarts it, so the delay is
+            // counted from the last change.
+            preview_state
+                .borrow_mut()
+                .preview_loading_delay_timer
+                .get_or_insert_with(slint::Timer::default)
+                .start(slint::TimerMode::SingleShot, delay, || {
+                    let _ = slint::spawn_local(reload_timer_function());
+                });
+        });
+    }) {
+        send_platform_error_notification(&e);
+    }
+}
+
+async fn parse_source(
+    include_paths: Vec<PathBuf>,
+    library_paths: HashMap<String, PathBuf>,
+    path: PathBuf,
+    version: common::SourceFileVersion,
+    source_code: String,
+    style: String,
+    component: Option<String>,
+    file_loader_fallback: impl Fn(
+            String,
+        ) -> core::pin::Pin<
+            Box<
+                dyn core::future::Future<
+                    Output = Option<std::io::Result<(common::SourceFileVersion, String)>>,
+                >,
+            >,
+        > + 'static,
+) -> (
+    Vec<diagnostics::Diagnostic>,
+    Option<ComponentDefinition>,
+    common::document_cache::OpenImportFallback,
+    Rc<RefCell<common::document_cache::SourceFileVersionMap>>,
+) {
+    let mut builder = slint_interpreter::Compiler::default();
+
+    let cc = builder.compiler_configuration(i_slint_core::InternalToken);
+    cc.components_to_generate = if let Some(name) = component {
+        i_slint_compiler::ComponentSelection::Named(name)
+    } else {
+        i_slint_compiler::ComponentSelection::LastExported
+    };
+    #[cfg(target_arch = "wasm32")]
+    {
+        cc.resource_url_mapper = resource_url_mapper();
+    }
+    cc.embed_resources = EmbedResourcesKind::ListAllResources;
+
+    cc.include_paths = include_paths;
+    // The compiler looks for custom styles in the include paths only
+    cc.include_paths.extend(style_library::include_path(&style, &library_paths));
+    cc.library_paths = library_paths;
+    if !style.is_empty() {
+        cc.style = Some(style);
+    }
+
+    let (open_file_fallback, source_file_versions) =
+        common::document_cache::document_cache_parts_setup(
+            cc,
+            Some(Rc::new(file_loader_fallback)),
+            common::document_cache::SourceFileVersionMap::from([(path.clone(), version)]),
+        );
+
+    let result = builder.build_from_source(source_code, path).await;
+
+    let compiled = result.components().next();
+    (result.diagnostics().collect(), compiled, open_file_fallback, source_file_versions)
+}
+
+/// The result of compiling the previewed document
+enum Compiled {
+    Complete(ComponentDefinition),
+    /// The document has errors, so this was compiled from a copy with the
+    /// erroneous elements replaced by placeholders. Its source locations do not
+    /// match the document!
+    Partial(ComponentDefinition),
+    /// Compiled from a copy with the elements hidden from the outline set
+    /// invisible. Its source locations do not match the document exactly!
+    LayersHidden(ComponentDefinition),
+}
+
+/// Compile the document again, with the elements causing `diagnostics` replaced
+/// by placeholders, until it compiles or recovery is not possible.
+#[allow(clippy::too_many_arguments)]
+async fn parse_partial_source(
+    config: &PreviewConfig,
+    path: PathBuf,
+    version: common::SourceFileVersion,
+    mut source: String,
+    style: String,
+    component: Option<String>,
+    diagnostics: &[diagnostics::Diagnostic],
+    file_loader_fallback: impl Fn(
+            String,
+        ) -> core::pin::Pin<
+            Box<
+                dyn core::future::Future<
+                    Output = Option<std::io::Result<(common::SourceFileVersion, String)>>,
+                >,
+            >,
+        > + Copy
+        + 'static,
+) -> Option<ComponentDefinition> {
+    let mut errors = partial_preview::error_positions(diagnostics, &path)?;
+    for _ in 0..partial_preview::MAX_ATTEMPTS {
+        source = partial_preview::replace_erroneous_elements(&source, &path, &errors)?;
+        let (diagnostics, compiled, _, _) = parse_source(
+            config.include_paths.clone(),
+            config.library_paths.clone(),
+            path.clone(),
+            version,
+            source.clone(),
+            style.clone(),
+            component.clone(),
+            file_loader_fallback,
+        )
+        .await;
+        if compiled.is_some() {
+            return compiled;
+        }
+        errors = partial_preview::error_positions(&diagnostics, &path)?;
+    }
+    None
+}
+
+/// Loads the files imported by the previewed documents from the cache
+fn file_loader(
+    path: String,
+) -> core::pin::Pin<
+    Box<
+        dyn core::future::Future<
+            Output = Option<std::io::Result<(common::SourceFileVersion, String)>>,
+        >,
+    >,
+> {
+    Box::pin(async move {
+        let path = PathBuf::from(&path);
+        // Always return Some to stop the compiler from trying to load itself...
+        // All loading is done by the LSP for us!
+        Some(get_path_from_cache(&path))
+    })
+}
+
+// Must be inside the thread running the slint event loop
+async fn reload_preview_impl(
+    component: PreviewComponent,
+    behavior: LoadBehavior,
+    style: String,
+    config: PreviewConfig,
+) -> Result<(), PlatformError> {
+    start_parsing();
+
+    let path = component.url.to_file_path().unwrap_or(PathBuf::from(&component.url.to_string()));
+    let (version, source) = get_url_from_cache(&component.url);
+    let layers_hidden = !layer_overrides().is_empty();
+
+    let (diagnostics, compiled, open_import_fallback, source_file_versions) = parse_source(
+        config.include_paths.clone(),
+        config.library_paths.clone(),
+        path.clone(),
+        version,
+        source.clone(),
+        style.clone(),
+        component.component.clone(),
+        file_loader,
+    )
+    .await;
+
+    let loaded_component_name = compiled.as_ref().map(|c| c.name().to_string());
+
+    {
+        PREVIEW_STATE.with(|preview_state| {
+            let preview_state = preview_state.borrow_mut();
+
+            if let Some(ui) = &preview_state.ui {
+                ui::set_diagnostics(ui, &diagnostics);
+            }
+        });
+        output_search::set_diagnostics(&diagnostics);
+        update_output_search();
+        let diags = convert_diagnostics(&diagnostics, &source_file_versions.borrow());
+        notify_diagnostics(diags);
+    }
+
+    let compiled = match compiled {
+        Some(compiled) if layers_hidden => Some(Compiled::LayersHidden(compiled)),
+        Some(compiled) => Some(Compiled::Complete(compiled)),
+        None => parse_partial_source(
+            &config,
+            path,
+            version,
+            source,
+            style,
+            component.component.clone(),
+            &diagnostics,
+            file_loader,
+        )
+        .await
+        .map(Compiled::Partial),
+    };
+
+    update_preview_area(compiled, behavior, open_import_fallback, source_file_versions)?;
+
+    finish_parsing(&component.url, loaded_component_name);
+    Ok(())
+}
+
+/// Sends a notification back to the editor when the preview fails to load because of a slint::PlatformError.
+fn send_platform_error_notification(platform_error_str: &str) {
+    let message = format!("Error displaying the Slint preview window: {platform_error_str}");
+    // Also output the message in the console in case the user missed the notification in the editor
+    eprintln!("{message}");
+    send_message_to_lsp(PreviewToLspMessage::SendShowMessage {
+        message: lsp_types::ShowMessageParams { typ: lsp_types::MessageType::ERROR, message },
+    })
+}
+
+/// This sets up the preview area to show the ComponentInstance
+///
+/// This must be run in the UI thread.
+fn set_preview_factory(
+    ui: &ui::PreviewUi,
+    compiled: ComponentDefinition,
+    callback: Box<dyn Fn(ComponentInstance)>,
+    behavior: LoadBehavior,
+) {
+    // Ensure that any popups are closed as they are related to the old factory
+    i_slint_core::window::WindowInner::from_pub(ui.window()).close_all_popups();
+
+    // The magnifier shows an instance of its own, which must not replace the one of the preview
+    let magnified = compiled.clone();
+    let magnifier_factory = slint::ComponentFactory::new(move |ctx: FactoryContext| {
+        magnified.create_embedded(ctx).ok()
+    });
+
+    let factory = slint::ComponentFactory::new(move |ctx: FactoryContext| {
+        let instance = compiled.create_embedded(ctx).unwrap();
+
+        callback(instance.clone_strong());
+
+        Some(instance)
+    });
+
+    let api = ui.global::<ui::Api>();
+    api.set_preview_area(factory);
+    api.set_magnifier_area(magnifier_factory);
+    api.set_resize_to_preferred_size(behavior != LoadBehavior::Reload);
+}
+
+/// Highlight the element pointed at the offset in the path.
+/// When path is None, remove the highlight.
+pub fn highlight(url: Option<Url>, offset: TextSize) {
+    let Some(path) = url.as_ref().and_then(|u| Url::to_file_path(u).ok()) else {
+        return;
+    };
+
+    let selected = selected_element();
+
+    if let Some(selected) = &selected {
+        if selected.path == path && selected.offset == offset {
+            return;
+        }
+    }
+
+    let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    if url.as_ref().is_none_or(|url| cache.dependencies.contains(url)) {
+        let _ = run_in_ui_thread(move || async move {
+            if Some((path.clone(), offset)) == selected.map(|s| (s.path, s.offset)) {
+                // Already selected!
+                return;
+            }
+            element_selection::select_element_at_source_code_position(
+                path,
+                offset,
+                None,
+                SelectionNotification::Never,
+            );
+        });
+    }
+}
+
+/// How often the selection follows the text cursor of the editor at most
+const FOLLOW_CURSOR_INTERVAL: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Select the element containing `offset` in `url`, if the preview follows the text cursor
+/// of the editor
+pub fn follow_editor_cursor(url: Url, offset: TextSize) {
+    let is_dependency =
+        CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().dependencies.contains(&url);
+    if !is_dependency {
+        return;
+    }
+
+    let _ = run_in_ui_thread(move || async move {
+        PREVIEW_STATE.with(|preview_state| {
+            let mut preview_state = preview_state.borrow_mut();
+            let Some(ui) = &preview_state.ui else {
+                return;
+            };
+            if !ui.global::<ui::Api>().get_follow_editor_cursor() {
+                return;
+            }
+            preview_state.editor_cursor = Some((url, offset));
+            // Not restarted on every move, so that the selection keeps up while the cursor moves
+            let timer = preview_state.editor_cursor_timer.get_or_insert_with(slint::Timer::default);
+            if !timer.running() {
+                timer.start(
+                    slint::TimerMode::SingleShot,
+                    FOLLOW_CURSOR_INTERVAL,
+                    select_at_editor_cursor,
+                );
+            }
+        });
+    });
+}
+
+fn select_at_editor_cursor() {
+    let Some((url, offset)) =
+        PREVIEW_STATE.with(|preview_state| preview_state.borrow_mut().editor_cursor.take())
+    else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(element) = document_cache.element_at_offset(&url, offset) else {
+        return;
+    };
+    let (path, offset) = element.path_and_offset();
+    if selected_element().is_some_and(|s| s.path == path && s.offset == offset) {
+        return;
+    }
+    element_selection::select_element_at_source_code_position(
+        path,
+        offset,
+        None,
+        SelectionNotification::Never,
+    );
+}
+
+/// How long elements flashed on request of the editor stay visible, matching the fade
+/// out animation in the UI
+const FLASH_DURATION: std::time::Duration = std::time::Duration::from_millis(1200);
+
+/// Briefly outline all instances of the element at `offset` in `url`
+pub fn flash_element(url: Url, offset: TextSize) {
+    let Ok(path) = url.to_file_path() else {
+        return;
+    };
+
+    let _ = run_in_ui_thread(move || async move {
+        let Some(component_instance) = component_instance() else {
+            return;
+        };
+        let geometries = component_instance
+            .element_node_at_source_code_position(&path, offset.into())
+            .iter()
+            .flat_map(|(element, _)| component_instance.element_positions(element))
+            .collect::<Vec<_>>();
+        if geometries.is_empty() {
+            set_status_text("The element is not shown in the preview");
+            return;
+        }
+
+        PREVIEW_STATE
+            .with(|preview_state| flash_geometries(&mut preview_state.borrow_mut(), &geometries));
+    });
+}
+
+/// Briefly outline the `geometries` in the preview
+fn flash_geometries(preview_state: &mut PreviewState, geometries: &[LogicalRect]) {
+    let Some(ui) = &preview_state.ui else {
+        return;
+    };
+    ui::ui_set_flashes(ui, geometries);
+
+    let ui_weak = ui.as_weak();
+    let timer = slint::Timer::default();
+    timer.start(slint::TimerMode::SingleShot, FLASH_DURATION, move || {
+        if let Some(ui) = ui_weak.upgrade() {
+            ui::ui_set_flashes(&ui, &[]);
+        }
+    });
+    preview_state.flash_timer = Some(timer);
+}
+
+pub fn get_component_info(component_type: &str) -> Option<ComponentInformation> {
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let index = preview_state
+            .known_components
+            .binary_search_by(|ci| ci.name.as_str().cmp(component_type))
+            .ok()?;
+        preview_state.known_components.get(index).cloned()
+    })
+}
+
+fn convert_diagnostics(
+    diagnostics: &[slint_interpreter::Diagnostic],
+    file_versions: &common::document_cache::SourceFileVersionMap,
+) -> HashMap<Url, (SourceFileVersion, Vec<lsp_types::Diagnostic>)> {
+    let mut result: HashMap<Url, (SourceFileVersion, Vec<lsp_types::Diagnostic>)> =
+        Default::default();
+
+    fn path_to_url(path: &Path) -> Url {
+        Url::from_file_path(path).ok().unwrap_or_else(|| Url::parse("file:/unknown").unwrap())
+    }
+
+    // Pre-fill version info and an empty diagnostics to reset the state for the url
+    for (path, version) in file_versions.iter() {
+        result.insert(path_to_url(path), (*version, Vec::new()));
+    }
+
+    {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+
+        // Fill in actual diagnostics now
+        for d in diagnostics {
+            if d.source_file().is_none_or(|f| !i_slint_compiler::pathutils::is_absolute(f)) {
+                continue;
+            }
+            let uri = path_to_url(d.source_file().unwrap());
+            let new_version = cache.source_code.get(&uri).and_then(|e| e.version);
+            if let Some(data) = result.get_mut(&uri) {
+                if data.0.is_some() && new_version.is_some() && data.0 != new_version {
+                    continue;
+                }
+                data.1.push(crate::util::to_lsp_diag(d));
+            }
+        }
+    }
+
+    result
+}
+
+fn reset_selections(ui: &ui::PreviewUi) {
+    let model = Rc::new(slint::VecModel::from(Vec::new()));
+    let api = ui.global::<ui::Api>();
+    api.set_selections(slint::ModelRc::from(model));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn set_selections(
+    ui: Option<&ui::PreviewUi>,
+    main_index: usize,
+    layout_kind: ui::LayoutKind,
+    is_interactive: bool,
+    is_moveable: bool,
+    is_resizable: bool,
+    positions: &[i_slint_core::lengths::LogicalRect],
+    group_positions: &[i_slint_core::lengths::LogicalRect],
+) {
+    let Some(ui) = ui else {
+        return;
+    };
+
+    let to_rectangle = |g: &i_slint_core::lengths::LogicalRect| ui::SelectionRectangle {
+        width: g.size.width,
+        height: g.size.height,
+        x: g.origin.x,
+        y: g.origin.y,
+    };
+
+    let values = positions
+        .iter()
+        .enumerate()
+        .map(|(i, g)| ui::Selection {
+            geometry: to_rectangle(g),
+            layout_data: layout_kind,
+            is_primary: i == main_index,
+            is_interactive,
+            is_moveable,
+            is_resizable,
+        })
+        .chain(group_positions.iter().map(|g| ui::Selection {
+            geometry: to_rectangle(g),
+            layout_data: ui::LayoutKind::None,
+            is_primary: false,
+            is_interactive: false,
+            is_moveable: false,
+            is_resizable: false,
+        }))
+        .collect::<Vec<_>>();
+    let model = Rc::new(slint::VecModel::from(values));
+    let api = ui.global::<ui::Api>();
+    api.set_selections(slint::ModelRc::from(model));
+}
+
+// triggered from the UI, running in UI thread
+fn render_costs_toggled(enabled: bool) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        let api = ui.global::<ui::Api>();
+
+        if !enabled {
+            render_stats::stop();
+            api.set_render_costs(Default::default());
+            preview_state.render_costs_timer = None;
+            return;
+        }
+
+        let stats = render_stats::start();
+        let ui_weak = ui.as_weak();
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_secs(1), move || {
+            let costs = RefCell::borrow_mut(&stats).take_costs();
+            // Nothing got rendered: Keep showing the last costs
+            if costs.is_empty() {
+                return;
+            }
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            let values = costs
+                .iter()
+                .map(|c| ui::RenderCost {
+                    geometry: ui::SelectionRectangle {
+                        x: c.geometry.origin.x,
+                        y: c.geometry.origin.y,
+                        width: c.geometry.size.width,
+                        height: c.geometry.size.height,
+                    },
+                    relative_cost: c.relative,
+                    label: format!("{}: {:.2}ms", c.kind.label(), c.average.as_secs_f64() * 1000.0)
+                        .into(),
+                })
+                .collect::<Vec<_>>();
+            ui.global::<ui::Api>()
+                .set_render_costs(slint::ModelRc::from(Rc::new(slint::VecModel::from(values))));
+        });
+        // Make sure there is something to sample
+        ui.window().request_redraw();
+        preview_state.render_costs_timer = Some(timer);
+    })
+}
+
+/// How often the performance overlay gets updated
+const PERFORMANCE_OVERLAY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+// triggered from the UI, running in UI thread
+fn performance_overlay_toggled(enabled: bool) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        let api = ui.global::<ui::Api>();
+
+        if !enabled {
+            frame_stats::stop();
+            api.set_performance_stats(Default::default());
+            preview_state.performance_overlay_timer = None;
+            return;
+        }
+
+        if frame_stats::start(ui.window()).is_err() {
+            api.set_show_performance_overlay(false);
+            set_status_text("The renderer does not support measuring frames");
+            return;
+        }
+        let ui_weak = ui.as_weak();
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, PERFORMANCE_OVERLAY_INTERVAL, move || {
+            let Some(summary) = frame_stats::take_summary(PERFORMANCE_OVERLAY_INTERVAL) else {
+                return;
+            };
+            let Some(ui) = ui_weak.upgrade() else {
+                return;
+            };
+            ui.global::<ui::Api>().set_performance_stats(ui::PerformanceStats {
+                frames_per_second: summary.frames_per_second,
+                frame_time_ms: summary.average_frame_time.as_secs_f32() * 1000.0,
+                max_frame_time_ms: summary.max_frame_time.as_secs_f32() * 1000.0,
+                items_per_frame: summary.average_items,
+            });
+        });
+        preview_state.performance_overlay_timer = Some(timer);
+    })
+}
+
+fn update_input_regions(preview_state: &mut PreviewState) {
+    let Some(ui) = &preview_state.ui else {
+        return;
+    };
+    let regions = input_regions::collect(ui.window());
+    let values = regions
+        .iter()
+        .map(|r| ui::InputRegion {
+            geometry: ui::SelectionRectangle {
+                x: r.geometry.origin.x,
+                y: r.geometry.origin.y,
+                width: r.geometry.size.width,
+                height: r.geometry.size.height,
+            },
+            label: if r.enabled {
+                r.kind.label().into()
+            } else {
+                format!("{} (disabled)", r.kind.label()).into()
+            },
+            enabled: r.enabled,
+        })
+        .collect::<Vec<_>>();
+    ui.global::<ui::Api>()
+        .set_input_regions(slint::ModelRc::from(Rc::new(slint::VecModel::from(values))));
+    preview_state.input_regions = regions;
+}
+
+// triggered from the UI, running in UI thread
+fn input_regions_toggled(enabled: bool) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+
+        if !enabled {
+            let api = ui.global::<ui::Api>();
+            api.set_input_regions(Default::default());
+            api.set_hovered_input_region(-1);
+            preview_state.input_regions.clear();
+            preview_state.input_regions_timer = None;
+            return;
+        }
+
+        // The regions move with animations and the preview getting resized
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_millis(500), || {
+            PREVIEW_STATE
+                .with(|preview_state| update_input_regions(&mut preview_state.borrow_mut()))
+        });
+        preview_state.input_regions_timer = Some(timer);
+        update_input_regions(&mut preview_state);
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn hit_test_at(x: f32, y: f32) {
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        let index = input_regions::region_at(&preview_state.input_regions, LogicalPoint::new(x, y))
+            .map_or(-1, |i| i as i32);
+        ui.global::<ui::Api>().set_hovered_input_region(index);
+    })
+}
+
+fn update_render_layers(ui: &ui::PreviewUi) {
+    let values = render_layers::collect(ui.window())
+        .into_iter()
+        .map(|l| ui::RenderLayer {
+            geometry: ui::SelectionRectangle {
+                x: l.geometry.origin.x,
+                y: l.geometry.origin.y,
+                width: l.geometry.size.width,
+                height: l.geometry.size.height,
+            },
+            kind: match l.kind {
+                render_layers::LayerKind::Opacity(_) => ui::RenderLayerKind::Opacity,
+                render_layers::LayerKind::Clip => ui::RenderLayerKind::Clip,
+                render_layers::LayerKind::Cache => ui::RenderLayerKind::Cache,
+            },
+            label: l.kind.label().into(),
+        })
+        .collect::<Vec<_>>();
+    ui.global::<ui::Api>()
+        .set_render_layers(slint::ModelRc::from(Rc::new(slint::VecModel::from(values))));
+}
+
+// triggered from the UI, running in UI thread
+fn load_image_assets(source_uri: slint::SharedString) {
+    let Some(document) = Url::parse(source_uri.as_str()).ok().and_then(|u| u.to_file_path().ok())
+    else {
+        return;
+    };
+    let directory = document.parent().unwrap_or(&document).to_path_buf();
+    let root = project().unwrap_or_else(|| directory.clone());
+    let images = image_assets::find_images(&root)
+        .into_iter()
+        .map(|file| {
+            let path = file_drop::relative_path(&directory, &file)
+                .unwrap_or_else(|| file.to_string_lossy().replace('\\', "/"));
+            (file, path)
+        })
+        .collect::<Vec<_>>();
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_image_assets(ui, &images);
+        }
+    });
+}
+
+// triggered from the UI, running in UI thread
+fn load_font_families() {
+    let families = fonts::font_families();
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_font_families(ui, &families);
+        }
+    });
+}
+
+// triggered from the UI, running in UI thread
+fn open_preview_window() {
+    #[cfg(target_arch = "wasm32")]
+    set_status_text("Further preview windows are not available in the browser");
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let Some(pc) =
+            CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().current_component()
+        else {
+            return;
+        };
+        let color_scheme = PREVIEW_STATE.with(|preview_state| {
+            let preview_state = preview_state.borrow();
+            let ui = preview_state.ui.as_ref()?;
+            Some(ui.global::<ui::Api>().get_preview_color_scheme())
+        });
+        let content = preview_windows::WindowContent {
+            url: pc.url,
+            component: pc.component,
+            style: get_current_style(),
+            color_scheme: color_scheme.unwrap_or(ColorScheme::Unknown),
+        };
+        let _ = slint::spawn_local(async move {
+            match preview_windows::open(content).await {
+                Ok(description) => {
+                    set_status_text(&format!("Opened {description} in a new window"))
+                }
+                Err(e) => set_status_text(&format!("Failed to open a preview window: {e}")),
+            }
+        });
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn preview_color_scheme_changed(color_scheme: ColorScheme) {
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        if let Some(ui) = &preview_state.ui {
+            set_color_scheme_override(ui.window(), color_scheme);
+        }
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn safe_area_changed(insets: devices::Insets) {
+    let component_instance = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        preview_state.safe_area_insets.set(insets);
+        preview_state.component_instance()
+    });
+    if let Some(component_instance) = component_instance {
+        devices::apply_insets(&component_instance, insets);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn environment_changed(environment: environment::Environment) {
+    let component_instance = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        preview_state.environment.replace(environment.clone());
+        preview_state.component_instance()
+    });
+    if let Some(component_instance) = component_instance {
+        environment::apply(&component_instance, &environment);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn prepare_screenshot() {
+    let Some(component_instance) = component_instance() else {
+        return;
+    };
+    let size = component_instance
+        .element_positions(&element_selection::root_element(&component_instance))
+        .first()
+        .map(|g| g.size)
+        .unwrap_or_default();
+    let url = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.current_component().map(|pc| pc.url)
+    };
+    let file_name = format!("{}.png", component_instance.definition().name());
+    let path = url
+        .and_then(|url| url.to_file_path().ok())
+        .and_then(|path| Some(path.parent()?.join(&file_name)))
+        .unwrap_or_else(|| file_name.into());
+
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        let api = ui.global::<ui::Api>();
+        let previous = api.get_screenshot_settings();
+        api.set_screenshot_settings(ui::ScreenshotSettings {
+            path: path.to_string_lossy().as_ref().into(),
+            svg: false,
+            width: size.width.round() as i32,
+            height: size.height.round() as i32,
+            scale: if previous.scale > 0.0 { previous.scale } else { 1.0 },
+        });
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn save_screenshot(settings: ui::ScreenshotSettings) {
+    let Some(component_instance) = component_instance() else {
+        set_status_text("Nothing to take a screenshot of");
+        return;
+    };
+    let format = if settings.svg { screenshot::Format::Svg } else { screenshot::Format::Png };
+    let mut path = std::path::PathBuf::from(settings.path.as_str());
+    // Keep the extension in line with the format picked last
+    if matches!(path.extension().and_then(|e| e.to_str()), Some("png" | "svg")) {
+        path.set_extension(format.extension());
+    }
+    let result = screenshot::render(
+        &component_instance.definition(),
+        Some(&component_instance),
+        settings.width.max(1) as f32,
+        settings.height.max(1) as f32,
+        settings.scale.max(1.0),
+        format,
+    )
+    .and_then(|data| std::fs::write(&path, data).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => set_status_text(&format!("Saved the screenshot to {}", path.display())),
+        Err(e) => set_status_text(&format!("Failed to save the screenshot: {e}")),
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn export_all_screenshots(settings: ui::ScreenshotSettings) {
+    let mut files = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        preview_state
+            .known_components
+            .iter()
+            .filter(|ci| ci.is_exported && !ci.is_global && !ci.is_builtin && !ci.is_std_widget)
+            .filter_map(|ci| ci.defined_at.as_ref()?.url().to_file_path().ok())
+            // Only the components of the project, not those of the libraries it uses
+            .filter(|path| preview_state.project.as_ref().is_none_or(|p| path.starts_with(p)))
+            .collect::<Vec<_>>()
+    });
+    files.sort();
+    files.dedup();
+    let config = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().config.clone();
+    let output_dir = std::path::Path::new(settings.path.as_str())
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let batch = screenshot::BatchSettings {
+        sizes: vec![],
+        styles: vec![get_current_style()],
+        color_schemes: vec![ColorScheme::Unknown],
+        scale_factor: settings.scale.max(1.0),
+        include_paths: config.include_paths,
+        library_paths: config.library_paths,
+    };
+    let report = screenshot::export_all(&files, &batch, &output_dir);
+    if report.errors.is_empty() {
+        set_status_text(&format!(
+            "Saved {} screenshots to {}",
+            report.written.len(),
+            output_dir.display()
+        ));
+    } else {
+        set_status_text(&format!(
+            "Saved {} screenshots to {}, {} failed: {}",
+            report.written.len(),
+            output_dir.display(),
+            report.errors.len(),
+            report.errors.join(", ")
+        ));
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn recording_toggled(enabled: bool) {
+    if !enabled {
+        let trace = recorder::stop();
+        PREVIEW_STATE.with(|preview_state| {
+            let mut preview_state = preview_state.borrow_mut();
+            if let Some(ui) = &preview_state.ui {
+                let api = ui.global::<ui::Api>();
+                api.set_recorded_events(trace.as_ref().map_or(0, |t| t.events.len() as i32));
+            }
+            preview_state.recording = trace;
+        });
+        return;
+    }
+
+    let Some(component_instance) = component_instance() else {
+        return;
+    };
+    let size = component_instance
+        .element_positions(&element_selection::root_element(&component_instance))
+        .first()
+        .map(|g| g.size)
+        .unwrap_or_default();
+    let file = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.current_component().and_then(|pc| pc.url.to_file_path().ok())
+    };
+    let name = component_instance.definition().name().to_string();
+    let path = file
+        .as_ref()
+        .and_then(|f| f.parent())
+        .unwrap_or(std::path::Path::new(""))
+        .join(format!("{name}-interaction.json"));
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        if let Some(ui) = &preview_state.ui {
+            ui.global::<ui::Api>().set_recording_path(path.to_string_lossy().as_ref().into());
+        }
+    });
+    recorder::start(name, file, size.width, size.height);
+}
+
+// triggered from the UI, running in UI thread
+fn save_recording(path: slint::SharedString) {
+    let Some(trace) = PREVIEW_STATE.with(|preview_state| preview_state.borrow().recording.clone())
+    else {
+        return;
+    };
+    let path = std::path::Path::new(path.as_str());
+    // Rust files get a test, everything else the event trace to replay
+    let contents = if path.extension().is_some_and(|e| e == "rs") {
+        trace.to_rust_test()
+    } else {
+        trace.to_json()
+    };
+    match std::fs::write(path, contents) {
+        Ok(()) => {
+            set_status_text(&format!("Saved the recording to {}", path.display()));
+            discard_recording();
+        }
+        Err(e) => set_status_text(&format!("Failed to save the recording: {e}")),
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn discard_recording() {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        preview_state.recording = None;
+        if let Some(ui) = &preview_state.ui {
+            ui.global::<ui::Api>().set_recorded_events(0);
+        }
+    })
+}
+
+/// Save everything needed to reproduce what the preview shows to `path`, or next to the
+/// previewed file
+///
+/// Runs in the UI thread
+fn report_issue(path: Option<PathBuf>) {
+    let Some(component_instance) = component_instance() else {
+        set_status_text("Nothing to report, the preview shows no component");
+        return;
+    };
+    let (url, config, files) = {
+        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        let mut files = cache
+            .dependencies
+            .iter()
+            .chain(cache.resources.iter())
+            .filter_map(|url| {
+                let code = cache.source_code.get(url).map(|entry| entry.code.clone());
+                Some((url.to_file_path().ok()?, code))
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+        (cache.current_component().map(|pc| pc.url), cache.config.clone(), files)
+    };
+    let Some(main_file) = url.and_then(|url| url.to_file_path().ok()) else {
+        set_status_text("Nothing to report, the previewed component is not in a file");
+        return;
+    };
+    let (root, settings, fixtures) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let root = preview_state.project.clone().or_else(|| Some(main_file.parent()?.into()));
+        let settings = preview_state.ui.as_ref().map(ui::project_settings);
+        (root.unwrap_or_default(), settings, preview_state.fixtures.clone())
+    });
+    let archive_path = |path: &Path| bug_report::archive_path(&root, path);
+
+    let name = component_instance.definition().name().to_string();
+    let mut report = bug_report::Report::new();
+    report.manifest.component = Some((archive_path(&main_file), name.clone()));
+    report.manifest.style = get_current_style();
+    report.manifest.include_paths = config.include_paths.iter().map(|p| archive_path(p)).collect();
+    report.manifest.library_paths =
+        config.library_paths.iter().map(|(n, p)| (n.clone(), archive_path(p))).collect();
+    let diagnostics = output_search::Query {
+        sources: vec![output_search::Source::Diagnostic],
+        ..Default::default()
+    };
+    report.manifest.diagnostics = output_search::search(&diagnostics)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|d| match d.location {
+            Some((path, line, column)) => {
+                format!("{}:{line}:{column}: {}", archive_path(&path), d.text)
+            }
+            None => d.text,
+        })
+        .collect();
+
+    for (path, code) in files {
+        // Files that are not open in the editor get read from disk, like images and fonts
+        if let Some(data) = code.map(String::into_bytes).or_else(|| std::fs::read(&path).ok()) {
+            report.add_file(archive_path(&path), data);
+        }
+    }
+
+    let size = component_instance
+        .element_positions(&element_selection::root_element(&component_instance))
+        .first()
+        .map(|g| g.size)
+        .unwrap_or_default();
+    match screenshot::render(
+        &component_instance.definition(),
+        Some(&component_instance),
+        size.width.max(1.0),
+        size.height.max(1.0),
+        1.0,
+        screenshot::Format::Png,
+    ) {
+        Ok(png) => report.add_file("screenshot.png".into(), png),
+        Err(e) => report.manifest.screenshot_error = Some(e),
+    }
+
+    let path = path.unwrap_or_else(|| main_file.with_file_name(format!("{name}-bug-report.tar")));
+    let result = report
+        .add_json("overrides.json", &preview_data::get_json_preview_data(&component_instance))
+        .and_then(|()| report.add_json("fixtures.json", &fixtures))
+        .and_then(|()| report.add_json("settings.json", &settings))
+        .and_then(|()| report.to_tar())
+        .and_then(|tar| std::fs::write(&path, tar).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => set_status_text(&format!("Saved the bug report to {}", path.display())),
+        Err(e) => set_status_text(&format!("Failed to save the bug report: {e}")),
+    }
+}
+
+/// The file to export the preview data to or import it from: `path` relative to the previewed
+/// file, or a file named after the previewed component next to it
+fn preview_data_file(path: &str, component_name: &str) -> Option<PathBuf> {
+    let url = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().current_component()?.url;
+    let directory = url.to_file_path().ok()?.parent()?.to_path_buf();
+    let path = if path.is_empty() { format!("{component_name}-data.json") } else { path.into() };
+    Some(directory.join(path))
+}
+
+// triggered from the UI, running in UI thread
+fn export_preview_data(path: slint::SharedString) {
+    let Some(component_instance) = component_instance() else {
+        set_status_text("Nothing to export, the preview shows no component");
+        return;
+    };
+    let Some(path) = preview_data_file(&path, component_instance.definition().name()) else {
+        set_status_text("Nothing to export, the previewed component is not in a file");
+        return;
+    };
+    let result =
+        serde_json::to_vec_pretty(&preview_data::get_json_preview_data(&component_instance))
+            .map_err(|e| e.to_string())
+            .and_then(|data| std::fs::write(&path, data).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => set_status_text(&format!("Saved the preview data to {}", path.display())),
+        Err(e) => set_status_text(&format!("Failed to save the preview data: {e}")),
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn import_preview_data(path: slint::SharedString) {
+    let Some(component_instance) = component_instance() else {
+        set_status_text("Nothing to import into, the preview shows no component");
+        return;
+    };
+    let Some(path) = preview_data_file(&path, component_instance.definition().name()) else {
+        set_status_text("Nothing to import into, the previewed component is not in a file");
+        return;
+    };
+    let result = std::fs::read(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|data| {
+            serde_json::from_slice::<serde_json::Value>(&data).map_err(|e| e.to_string())
+        })
+        .and_then(|json| {
+            preview_data::set_all_json_preview_data(&component_instance, &json)
+                .map_err(|errors| errors.join("\n"))
+        });
+    match result {
+        Ok(count) => set_status_text(&format!("Set {count} properties from {}", path.display())),
+        Err(e) => set_status_text(&format!("Failed to load the preview data: {e}")),
+    }
+
+    // Show the new values, also after errors as the other properties got set
+    let previewed_component = CONTENT_CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .current_component()
+        .and_then(|pc| pc.component);
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_preview_data(
+                ui,
+                preview_data::query_preview_data_properties_and_callbacks(&component_instance),
+                previewed_component,
+            );
+        }
+    });
+}
+
+/// Write the runtime value of a property as its binding in the source code, after the user
+/// reviewed the change
+///
+/// Runs in the UI thread
+fn apply_preview_data_to_source(container: preview_data::PropertyContainer, property_name: &str) {
+    let (Some(component_instance), Some(document_cache)) = (component_instance(), document_cache())
+    else {
+        return;
+    };
+    let Some(url) = CONTENT_CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .current_component()
+        .map(|pc| pc.url)
+    else {
+        return;
+    };
+    match apply_to_source::binding_edit(
+        &document_cache,
+        &url,
+        &component_instance,
+        &container,
+        property_name,
+    ) {
+        Ok(edit) => {
+            let mut builder =
+                common::WorkspaceEditBuilder::new(format!("Apply {property_name} to source"));
+            builder.add_workspace_edit(edit);
+            review_workspace_edit(builder, true);
+        }
+        Err(e) => set_status_text(&e),
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn replay_recording() {
+    let (trace, ui) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        (preview_state.recording.clone(), preview_state.ui.as_ref().map(|ui| ui.as_weak()))
+    });
+    if let (Some(trace), Some(ui)) = (trace, ui.and_then(|ui| ui.upgrade())) {
+        recorder::replay(ui.window(), &trace);
+    }
+}
+
+/// Show the known components in the component library again, e.g. with new thumbnails
+fn update_known_components_ui() {
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_known_components(
+                ui,
+                &preview_state.known_components,
+                preview_state.current_component_index,
+                &preview_state.component_usage,
+            );
+        }
+    });
+}
+
+/// Render the component as it was before the latest change, with the size and the property
+/// values of the preview
+fn update_compare_image() {
+    let (previous, component_instance) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        (preview_state.compare_definition.clone(), preview_state.component_instance())
+    });
+    let Some(component_instance) = component_instance else {
+        return;
+    };
+    // Another component got previewed in the meantime
+    let previous =
+        previous.filter(|previous| previous.name() == component_instance.definition().name());
+    let size = component_instance
+        .element_positions(&element_selection::root_element(&component_instance))
+        .first()
+        .map(|g| g.size)
+        .unwrap_or_default();
+    let scale_factor = component_instance.window().scale_factor();
+    let image = match previous {
+        Some(previous) => match screenshot::render_image(
+            &previous,
+            Some(&component_instance),
+            size.width.max(1.0),
+            size.height.max(1.0),
+            scale_factor,
+        ) {
+            Ok(image) => Some(image),
+            Err(e) => {
+                set_status_text(&format!("Failed to render the previous version: {e}"));
+                None
+            }
+        },
+        None => None,
+    };
+
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        let api = ui.global::<ui::Api>();
+        api.set_compare_available(image.is_some());
+        api.set_compare_before(image.unwrap_or_default());
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn compare_toggled(enabled: bool) {
+    if enabled {
+        update_compare_image();
+        return;
+    }
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        if let Some(ui) = &preview_state.ui {
+            let api = ui.global::<ui::Api>();
+            api.set_compare_available(false);
+            api.set_compare_before(Default::default());
+        }
+    })
+}
+
+/// How often fuzzing sets new random values
+const FUZZ_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Set the properties of the previewed component to the random values of `seed`
+fn fuzz_with(seed: u64) {
+    let Some(instance) = component_instance() else {
+        return;
+    };
+    let seed_text = fuzz::format_seed(seed);
+    // The preview may crash with these values, so keep the seed where it outlives it
+    eprintln!("Fuzzing the preview with seed {seed_text}");
+    let errors = fuzz::randomize(&instance, seed);
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui.global::<ui::Api>().set_fuzz_seed(seed_text.as_str().into());
+        }
+    });
+    if !errors.is_empty() {
+        set_status_text(&format!("Fuzzing could not set {}", errors.join(", ")));
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn fuzzing_toggled(enabled: bool) {
+    use std::hash::BuildHasher;
+
+    let first_seed = PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        if let Some(ui) = &preview_state.ui {
+            ui.global::<ui::Api>().set_fuzzing_paused(!enabled);
+        }
+        if !enabled {
+            preview_state.fuzz_timer = None;
+            return None;
+        }
+
+        // The seeds of the rounds come from a seed of their own, to not repeat between runs
+        let mut seeds = fuzz::Rng::new(std::collections::hash_map::RandomState::new().hash_one(()));
+        let first_seed = seeds.next_u64();
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, FUZZ_INTERVAL, move || fuzz_with(seeds.next_u64()));
+        preview_state.fuzz_timer = Some(timer);
+        Some(first_seed)
+    });
+    if let Some(seed) = first_seed {
+        fuzz_with(seed);
+    }
+}
+
+// triggered from the UI, running in UI thread
+fn fuzz_with_seed(seed: slint::SharedString) {
+    let Some(seed) = fuzz::parse_seed(&seed) else {
+        set_status_text(&format!("{seed} is no fuzzing seed"));
+        return;
+    };
+    // Keep the values of the seed to look into what they do
+    fuzzing_toggled(false);
+    fuzz_with(seed);
+}
+
+/// Show the preview in `window` in the `color_scheme` instead of the one of the platform,
+/// or follow the platform again for `ColorScheme::Unknown`
+fn set_color_scheme_override(window: &slint::Window, color_scheme: ColorScheme) {
+    i_slint_core::window::WindowInner::from_pub(window).set_color_scheme_override(color_scheme);
+}
+
+// triggered from the UI, running in UI thread
+fn render_layers_toggled(enabled: bool) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+
+        if !enabled {
+            ui.global::<ui::Api>().set_render_layers(Default::default());
+            preview_state.render_layers_timer = None;
+            return;
+        }
+
+        update_render_layers(ui);
+        let ui_weak = ui.as_weak();
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_millis(500), move || {
+            if let Some(ui) = ui_weak.upgrade() {
+                update_render_layers(&ui);
+            }
+        });
+        preview_state.render_layers_timer = Some(timer);
+    })
+}
+
+fn update_layout_anomalies(ui: &ui::PreviewUi) {
+    let anomalies = component_instance()
+        .map(|instance| layout_anomalies::collect(&instance))
+        .unwrap_or_default();
+    ui::ui_set_layout_anomalies(ui, &anomalies);
+
+    let overflow_warnings = anomalies
+        .iter()
+        .flat_map(|a| {
+            a.problems.iter().filter(|p| p.is_overflow()).map(|p| {
+                (format!("{} {}", a.element, p.label()), Some((a.path.clone(), a.line, a.column)))
+            })
+        })
+        .collect();
+    output_search::set_overflow_warnings(overflow_warnings);
+    update_output_search();
+}
+
+// triggered from the UI, running in UI thread
+fn layout_anomalies_toggled(enabled: bool) {
+    let Some(ui) = PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let ui = preview_state.ui.as_ref()?.clone_strong();
+
+        if !enabled {
+            ui.global::<ui::Api>().set_layout_anomalies(Default::default());
+            preview_state.layout_anomalies_timer = None;
+            output_search::set_overflow_warnings(Vec::new());
+            return None;
+        }
+
+        let ui_weak = ui.as_weak();
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_millis(500), move || {
+            if let Some(ui) = ui_weak.upgrade() {
+                update_layout_anomalies(&ui);
+            }
+        });
+        preview_state.layout_anomalies_timer = Some(timer);
+        Some(ui)
+    }) else {
+        return;
+    };
+    // Not borrowing the preview state: Collecting needs the component instance
+    update_layout_anomalies(&ui);
+}
+
+fn update_accessibility(preview_state: &mut PreviewState) {
+    let Some(ui) = &preview_state.ui else {
+        return;
+    };
+    let elements = accessibility::collect(ui.window());
+    ui::ui_set_accessible_elements(ui, &elements);
+    preview_state.accessible_elements = elements;
+}
+
+// triggered from the UI, running in UI thread
+fn update_console() {
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_console_messages(ui);
+        }
+    });
+    update_output_search();
+}
+
+fn update_connection_health() {
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_connection_health(ui);
+        }
+    })
+}
+
+fn update_output_search() {
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_output_search_results(ui);
+        }
+    })
+}
+
+fn update_watch_expressions() {
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_watch_expressions(ui);
+        }
+    })
+}
+
+fn update_breakpoints() {
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_callback_handlers(ui, &preview_state.callback_handlers);
+            ui::ui_set_breakpoint_hit(ui);
+        }
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn set_breakpoint(file: slint::SharedString, offset: i32, enabled: bool) {
+    breakpoints::set_breakpoint(PathBuf::from(file.as_str()), offset as u32, enabled);
+    update_breakpoints();
+}
+
+// triggered from the UI, running in UI thread
+fn show_binding_dependencies(
+    element_url: slint::SharedString,
+    element_offset: i32,
+    property_name: slint::SharedString,
+) {
+    let Ok(element_url) = Url::parse(element_url.as_ref()) else {
+        return;
+    };
+    let Ok(element_offset) = u32::try_from(element_offset) else {
+        return;
+    };
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(element) = document_cache.element_at_offset(&element_url, element_offset.into())
+    else {
+        return;
+    };
+    let dependencies = binding_dependencies::dependencies(&element.element, &property_name);
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_binding_dependencies(ui, &property_name, &dependencies);
+        }
+    })
+}
+
+// triggered from the UI, running in UI thread
+fn show_global(name: slint::SharedString) {
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some(url) = CONTENT_CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .current_component()
+        .map(|pc| pc.url)
+    else {
+        return;
+    };
+    let properties = globals::global_properties(&document_cache, &url, &name);
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_global_properties(ui, &document_cache, &name, properties);
+        }
+    })
 }
 
-pub fn load_preview(preview_component: PreviewComponent, behavior: LoadBehavior) {
-    {
-        let mut cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+// triggered from the UI, running in UI thread
+fn clear_console() {
+    console::clear();
+    update_console();
+}
 
-        match behavior {
-            LoadBehavior::Reload => {
-                if !cache.ui_is_visible {
-                    return;
-                }
-            }
-            LoadBehavior::Load | LoadBehavior::BringWindowToFront => {
-                cache.set_current_component(preview_component)
-            }
-        }
+/// How long the user has to move the mouse pointer to the color to pick from the screen
+const SCREEN_COLOR_PICK_SECONDS: u32 = 3;
 
-        cache.current_load_behavior = Some(behavior);
+// triggered from the UI, running in UI thread
+fn pick_color() {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        ui.global::<ui::Api>().set_picking_color(true);
+        if !eyedropper::CAN_PICK_FROM_SCREEN {
+            set_status_text("Click into the preview to pick a color from it");
+            return;
+        }
 
-        match cache.loading_state {
-            PreviewFutureState::Pending => {}
-            PreviewFutureState::Loading => {
-                cache.loading_state = PreviewFutureState::NeedsReload;
+        // Clicks outside of the preview window go to other applications, so the color under
+        // the mouse pointer gets picked once a countdown ran out. A click into the preview
+        // picks the color there right away.
+        let countdown = std::cell::Cell::new(SCREEN_COLOR_PICK_SECONDS);
+        let show_countdown = |seconds: u32| {
+            set_status_text(&format!(
+                "Move the mouse pointer to the color to pick from the screen ({seconds}s), or click into the preview"
+            ))
+        };
+        show_countdown(countdown.get());
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_secs(1), move || {
+            countdown.set(countdown.get() - 1);
+            if countdown.get() > 0 {
+                show_countdown(countdown.get());
                 return;
             }
-            PreviewFutureState::NeedsReload | PreviewFutureState::PreLoading => {
-                return;
+            PREVIEW_STATE.with(|preview_state| {
+                let preview_state = preview_state.borrow();
+                if let Some(timer) = &preview_state.color_pick_timer {
+                    timer.stop();
+                }
+                let Some(ui) = &preview_state.ui else {
+                    return;
+                };
+                match eyedropper::pick_from_screen() {
+                    Ok(rgba) => {
+                        set_status_text("");
+                        ui::ui_set_picked_color(ui, Some(rgba));
+                    }
+                    Err(e) => set_status_text(&format!(
+                        "{e}. Click into the preview to pick a color from it"
+                    )),
+                }
+            });
+        });
+        preview_state.color_pick_timer = Some(timer);
+    });
+}
+
+// triggered from the UI, running in UI thread
+fn pick_preview_color(x: f32, y: f32) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        preview_state.color_pick_timer = None;
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        match eyedropper::pick_from_window(ui.window(), x, y) {
+            Ok(rgba) => ui::ui_set_picked_color(ui, Some(rgba)),
+            Err(e) => {
+                set_status_text(&e);
+                ui::ui_set_picked_color(ui, None);
             }
         }
-        cache.loading_state = PreviewFutureState::PreLoading;
-    };
-
-    if let Err(e) = run_in_ui_thread(move || async move {
-        PREVIEW_STATE.with(|preview_state| {
-            preview_state
-                .borrow_mut()
-                .preview_loading_delay_timer
-                .get_or_insert_with(|| {
-                    let timer = slint::Timer::default();
-                    timer.start(
-                        slint::TimerMode::SingleShot,
-                        core::time::Duration::from_millis(50),
-                        || {
-                            let _ = slint::spawn_local(reload_timer_function());
-                        },
-                    );
-                    timer
-                })
-                .restart();
-        });
-    }) {
-        send_platform_error_notification(&e);
-    }
+    });
 }
 
-async fn parse_source(
-    include_paths: Vec<PathBuf>,
-    library_paths: HashMap<String, PathBuf>,
-    path: PathBuf,
-    version: common::SourceFileVersion,
-    source_code: String,
-    style: String,
-    component: Option<String>,
-    file_loader_fallback: impl Fn(
-            String,
-        ) -> core::pin::Pin<
-            Box<
-                dyn core::future::Future<
-                    Output = Option<std::io::Result<(common::SourceFileVersion, String)>>,
-                >,
-            >,
-        > + 'static,
-) -> (
-    Vec<diagnostics::Diagnostic>,
-    Option<ComponentDefinition>,
-    common::document_cache::OpenImportFallback,
-    Rc<RefCell<common::document_cache::SourceFileVersionMap>>,
-) {
-    let mut builder = slint_interpreter::Compiler::default();
+// triggered from the UI, running in UI thread
+fn cancel_color_picking() {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        preview_state.color_pick_timer = None;
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_picked_color(ui, None);
+        }
+        set_status_text("");
+    });
+}
 
-    let cc = builder.compiler_configuration(i_slint_core::InternalToken);
-    cc.components_to_generate = if let Some(name) = component {
-        i_slint_compiler::ComponentSelection::Named(name)
-    } else {
-        i_slint_compiler::ComponentSelection::LastExported
+// triggered from the UI, running in UI thread
+fn structural_search() {
+    let Some(document_cache) = document_cache() else {
+        return;
     };
-    #[cfg(target_arch = "wasm32")]
-    {
-        cc.resource_url_mapper = resource_url_mapper();
-    }
-    cc.embed_resources = EmbedResourcesKind::ListAllResources;
-
-    if !style.is_empty() {
-        cc.style = Some(style);
-    }
-    cc.include_paths = include_paths;
-    cc.library_paths = library_paths;
-
-    let (open_file_fallback, source_file_versions) =
-        common::document_cache::document_cache_parts_setup(
-            cc,
-            Some(Rc::new(file_loader_fallback)),
-            common::document_cache::SourceFileVersionMap::from([(path.clone(), version)]),
-        );
+    PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+        let query = ui.global::<ui::Api>().get_structural_query();
+        let matches = common::structural_search::Query::parse(&query)
+            .map(|query| common::structural_search::find(&document_cache, &query));
+        ui::ui_set_structural_matches(ui, matches);
+    })
+}
 
-    let result = builder.build_from_source(source_code, path).await;
+// triggered from the UI, running in UI thread
+fn structural_replace() {
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let Some((query, template)) = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        let api = preview_state.ui.as_ref()?.global::<ui::Api>();
+        Some((api.get_structural_query(), api.get_structural_template()))
+    }) else {
+        return;
+    };
 
-    let compiled = result.components().next();
-    (result.diagnostics().collect(), compiled, open_file_fallback, source_file_versions)
+    let result = common::structural_search::Query::parse(&query)
+        .and_then(|query| common::structural_search::replace(&document_cache, &query, &template));
+    let matches = match result {
+        Ok((edit, count)) => {
+            let mut builder =
+                common::WorkspaceEditBuilder::new(format!("Rewrite {count} elements"));
+            builder.add_workspace_edit(edit);
+            review_workspace_edit(builder, false);
+            // The matches move or go away with the edit
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e),
+    };
+    PREVIEW_STATE.with(|preview_state| {
+        if let Some(ui) = &preview_state.borrow().ui {
+            ui::ui_set_structural_matches(ui, matches);
+        }
+    })
 }
 
-// Must be inside the thread running the slint event loop
-async fn reload_preview_impl(
-    component: PreviewComponent,
-    behavior: LoadBehavior,
-    style: String,
-    config: PreviewConfig,
-) -> Result<(), PlatformError> {
-    start_parsing();
+// triggered from the UI, running in UI thread
+fn clear_output_history() {
+    output_search::clear();
+    update_output_search();
+}
 
-    let path = component.url.to_file_path().unwrap_or(PathBuf::from(&component.url.to_string()));
-    let (version, source) = get_url_from_cache(&component.url);
+// triggered from the UI, running in UI thread
+fn accessibility_inspector_toggled(enabled: bool) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
 
-    let (diagnostics, compiled, open_import_fallback, source_file_versions) = parse_source(
-        config.include_paths,
-        config.library_paths,
-        path,
-        version,
-        source,
-        style,
-        component.component.clone(),
-        move |path| {
-            let path = path.to_owned();
-            Box::pin(async move {
-                let path = PathBuf::from(&path);
-                // Always return Some to stop the compiler from trying to load itself...
-                // All loading is done by the LSP for us!
-                Some(get_path_from_cache(&path))
-            })
-        },
-    )
-    .await;
+        if !enabled {
+            ui.global::<ui::Api>().set_accessible_elements(Default::default());
+            preview_state.accessible_elements.clear();
+            preview_state.accessibility_timer = None;
+            return;
+        }
 
-    let loaded_component_name = compiled.as_ref().map(|c| c.name().to_string());
+        // Labels and values follow the state of the preview
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_millis(500), || {
+            PREVIEW_STATE
+                .with(|preview_state| update_accessibility(&mut preview_state.borrow_mut()))
+        });
+        preview_state.accessibility_timer = Some(timer);
+        update_accessibility(&mut preview_state);
+    })
+}
 
-    {
-        PREVIEW_STATE.with(|preview_state| {
-            let preview_state = preview_state.borrow_mut();
+// triggered from the UI, running in UI thread
+fn show_accessible_element(index: i32) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(geometry) =
+            preview_state.accessible_elements.get(index as usize).map(|e| e.geometry)
+        else {
+            return;
+        };
+        flash_geometries(&mut preview_state, &[geometry]);
+    })
+}
 
-            if let Some(ui) = &preview_state.ui {
-                ui::set_diagnostics(ui, &diagnostics);
-            }
-        });
-        let diags = convert_diagnostics(&diagnostics, &source_file_versions.borrow());
-        notify_diagnostics(diags);
+// triggered from the UI, running in UI thread
+fn perform_accessibility_action(index: i32, action: slint::SharedString) {
+    // The action runs code of the preview, which must not find the preview state borrowed
+    let element = PREVIEW_STATE.with(|preview_state| {
+        preview_state.borrow().accessible_elements.get(index as usize).cloned()
+    });
+    if !element.is_some_and(|e| e.perform(&action)) {
+        set_status_text("The element is gone from the preview");
+        return;
     }
+    PREVIEW_STATE.with(|preview_state| update_accessibility(&mut preview_state.borrow_mut()));
+}
 
-    update_preview_area(compiled, behavior, open_import_fallback, source_file_versions)?;
-
-    finish_parsing(&component.url, loaded_component_name);
-    Ok(())
+fn update_focus_chain(preview_state: &mut PreviewState) {
+    let Some(ui) = &preview_state.ui else {
+        return;
+    };
+    let stops = focus_chain::collect(ui.window());
+    ui::ui_set_focus_chain(ui, &stops);
+    preview_state.focus_chain = stops;
 }
 
-/// Sends a notification back to the editor when the preview fails to load because of a slint::PlatformError.
-fn send_platform_error_notification(platform_error_str: &str) {
-    let message = format!("Error displaying the Slint preview window: {platform_error_str}");
-    // Also output the message in the console in case the user missed the notification in the editor
-    eprintln!("{message}");
-    send_message_to_lsp(PreviewToLspMessage::SendShowMessage {
-        message: lsp_types::ShowMessageParams { typ: lsp_types::MessageType::ERROR, message },
+// triggered from the UI, running in UI thread
+fn focus_chain_toggled(enabled: bool) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
+
+        if !enabled {
+            ui.global::<ui::Api>().set_focus_chain(Default::default());
+            preview_state.focus_chain.clear();
+            preview_state.focus_chain_timer = None;
+            return;
+        }
+
+        // Elements get enabled, shown and hidden with the state of the preview
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_millis(500), || {
+            PREVIEW_STATE.with(|preview_state| update_focus_chain(&mut preview_state.borrow_mut()))
+        });
+        preview_state.focus_chain_timer = Some(timer);
+        update_focus_chain(&mut preview_state);
     })
 }
 
-/// This sets up the preview area to show the ComponentInstance
-///
-/// This must be run in the UI thread.
-fn set_preview_factory(
-    ui: &ui::PreviewUi,
-    compiled: ComponentDefinition,
-    callback: Box<dyn Fn(ComponentInstance)>,
-    behavior: LoadBehavior,
-) {
-    // Ensure that any popups are closed as they are related to the old factory
-    i_slint_core::window::WindowInner::from_pub(ui.window()).close_all_popups();
+// triggered from the UI, running in UI thread
+fn select_focus_stop(index: i32) {
+    let Some(center) = PREVIEW_STATE.with(|preview_state| {
+        preview_state.borrow().focus_chain.get(index as usize).map(|s| s.geometry.center())
+    }) else {
+        return;
+    };
+    element_selection::select_element_at(center.x, center.y, false);
+}
 
-    let factory = slint::ComponentFactory::new(move |ctx: FactoryContext| {
-        let instance = compiled.create_embedded(ctx).unwrap();
+// triggered from the UI, running in UI thread
+fn move_selected_element_in_focus_chain(earlier: bool) {
+    let Some(selected_element_node) = selected_element().and_then(|s| s.as_element_node()) else {
+        return;
+    };
+    let Some(siblings) = selected_element_node.parent().map(|p| p.children()) else {
+        return;
+    };
+    let Some(index) = siblings.iter().position(|s| s == &selected_element_node) else {
+        return;
+    };
+    let (Some(component_instance), Some(document_cache)) = (component_instance(), document_cache())
+    else {
+        return;
+    };
+    let stops = PREVIEW_STATE.with(|preview_state| {
+        let preview_state = preview_state.borrow();
+        preview_state.ui.as_ref().map(|ui| focus_chain::collect(ui.window())).unwrap_or_default()
+    });
+    let geometries = siblings
+        .iter()
+        .map(|s| s.geometries(&component_instance).first().copied())
+        .collect::<Vec<_>>();
 
-        callback(instance.clone_strong());
+    let Some(target) = focus_chain::tab_order_neighbor(&geometries, &stops, index, earlier)
+        .and_then(|i| siblings.get(i))
+    else {
+        set_status_text(if earlier {
+            "No sibling before the element takes the focus"
+        } else {
+            "No sibling after the element takes the focus"
+        });
+        return;
+    };
+    let position = if earlier {
+        drop_location::TreePosition::Before
+    } else {
+        drop_location::TreePosition::After
+    };
 
-        Some(instance)
-    });
+    if let Some((edit, drop_data)) = drop_location::move_element_in_tree(
+        &document_cache,
+        &selected_element_node,
+        target,
+        position,
+    ) {
+        element_selection::select_element_at_source_code_position(
+            drop_data.path,
+            drop_data.selection_offset,
+            None,
+            SelectionNotification::AfterUpdate,
+        );
 
-    let api = ui.global::<ui::Api>();
-    api.set_preview_area(factory);
-    api.set_resize_to_preferred_size(behavior != LoadBehavior::Reload);
+        let mut builder = common::WorkspaceEditBuilder::new("Change focus order");
+        builder.add_workspace_edit(edit);
+        send_workspace_edit(builder, false);
+    }
 }
 
-/// Highlight the element pointed at the offset in the path.
-/// When path is None, remove the highlight.
-pub fn highlight(url: Option<Url>, offset: TextSize) {
-    let Some(path) = url.as_ref().and_then(|u| Url::to_file_path(u).ok()) else {
+// triggered from the UI, running in UI thread
+fn focus_selected_element_first() {
+    let Some(selected_element_node) = selected_element().and_then(|s| s.as_element_node()) else {
         return;
     };
-
-    let selected = selected_element();
-
-    if let Some(selected) = &selected {
-        if selected.path == path && selected.offset == offset {
-            return;
-        }
+    let Some(document_cache) = document_cache() else {
+        return;
+    };
+    let mut root = selected_element_node.clone();
+    while let Some(parent) = root.parent() {
+        root = parent;
+    }
+    if root == selected_element_node {
+        set_status_text("Select an element inside of the component to focus it first");
+        return;
     }
+    let id = selected_element_node.with_element_node(|node| {
+        node.parent()
+            .filter(|p| p.kind() == i_slint_compiler::parser::SyntaxKind::SubElement)
+            .and_then(|p| p.child_text(i_slint_compiler::parser::SyntaxKind::Identifier))
+    });
+    let Some(id) = id else {
+        set_status_text("Give the element an id to focus it first");
+        return;
+    };
+    let (path, _) = root.path_and_offset();
+    let Ok(url) = Url::from_file_path(&path) else {
+        return;
+    };
+    let version = document_cache.document_version(&url);
+
+    // The component forwards the focus it gets at startup
+    let Some(edit) = properties::set_binding(url, version, &root, "forward-focus", id.to_string())
+    else {
+        set_status_text("Failed to set forward-focus on the root element");
+        return;
+    };
+    let mut builder = common::WorkspaceEditBuilder::new("Focus element first");
+    builder.add_workspace_edit(edit);
+    send_workspace_edit(builder, false);
+}
 
+/// The file of the previewed component
+fn previewed_file() -> Option<PathBuf> {
     let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
-    if url.as_ref().is_none_or(|url| cache.dependencies.contains(url)) {
-        let _ = run_in_ui_thread(move || async move {
-            if Some((path.clone(), offset)) == selected.map(|s| (s.path, s.offset)) {
-                // Already selected!
-                return;
-            }
-            element_selection::select_element_at_source_code_position(
-                path,
-                offset,
-                None,
-                SelectionNotification::Never,
-            );
-        });
+    cache.current_component()?.url.to_file_path().ok()
+}
+
+fn update_notes(preview_state: &mut PreviewState) {
+    let Some(path) = previewed_file() else {
+        return;
+    };
+    if preview_state.notes.as_ref().map(|(p, _)| p) != Some(&path) {
+        let notes = annotations::load(&path);
+        preview_state.notes = Some((path, notes));
     }
+    let (Some(ui), Some(component_instance)) =
+        (&preview_state.ui, preview_state.component_instance())
+    else {
+        return;
+    };
+    let notes = preview_state.notes.as_ref().map(|(_, n)| n.as_slice()).unwrap_or_default();
+    ui::ui_set_notes(ui, &annotations::place(notes, &component_instance));
 }
 
-pub fn get_component_info(component_type: &str) -> Option<ComponentInformation> {
+/// Change the notes of the previewed file and store them
+fn change_notes(change: impl FnOnce(&mut Vec<annotations::Note>)) {
     PREVIEW_STATE.with(|preview_state| {
-        let preview_state = preview_state.borrow();
-        let index = preview_state
-            .known_components
-            .binary_search_by(|ci| ci.name.as_str().cmp(component_type))
-            .ok()?;
-        preview_state.known_components.get(index).cloned()
+        let mut preview_state = preview_state.borrow_mut();
+        update_notes(&mut preview_state);
+        let Some((path, notes)) = &mut preview_state.notes else {
+            return;
+        };
+        change(notes);
+        if let Err(e) = annotations::store(path, notes) {
+            set_status_text(&e);
+        }
+        update_notes(&mut preview_state);
     })
 }
 
-fn convert_diagnostics(
-    diagnostics: &[slint_interpreter::Diagnostic],
-    file_versions: &common::document_cache::SourceFileVersionMap,
-) -> HashMap<Url, (SourceFileVersion, Vec<lsp_types::Diagnostic>)> {
-    let mut result: HashMap<Url, (SourceFileVersion, Vec<lsp_types::Diagnostic>)> =
-        Default::default();
+// triggered from the UI, running in UI thread
+fn notes_toggled(enabled: bool) {
+    PREVIEW_STATE.with(|preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        let Some(ui) = &preview_state.ui else {
+            return;
+        };
 
-    fn path_to_url(path: &Path) -> Url {
-        Url::from_file_path(path).ok().unwrap_or_else(|| Url::parse("file:/unknown").unwrap())
-    }
+        if !enabled {
+            ui.global::<ui::Api>().set_notes(Default::default());
+            preview_state.notes = None;
+            preview_state.notes_timer = None;
+            return;
+        }
 
-    // Pre-fill version info and an empty diagnostics to reset the state for the url
-    for (path, version) in file_versions.iter() {
-        result.insert(path_to_url(path), (*version, Vec::new()));
-    }
+        // Notes follow their elements when the preview changes
+        let timer = slint::Timer::default();
+        timer.start(slint::TimerMode::Repeated, std::time::Duration::from_millis(500), || {
+            PREVIEW_STATE.with(|preview_state| update_notes(&mut preview_state.borrow_mut()))
+        });
+        preview_state.notes_timer = Some(timer);
+        update_notes(&mut preview_state);
+    })
+}
 
-    {
-        let cache = CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+// triggered from the UI, running in UI thread
+fn add_note(text: slint::SharedString) {
+    if text.trim().is_empty() {
+        return;
+    }
+    let Some(component_instance) = component_instance() else {
+        return;
+    };
+    let component = component_instance.definition().name().to_string();
+    let count = PREVIEW_STATE.with(|preview_state| {
+        preview_state.borrow().notes.as_ref().map(|(_, n)| n.len()).unwrap_or_default()
+    });
 
-        // Fill in actual diagnostics now
-        for d in diagnostics {
-            if d.source_file().is_none_or(|f| !i_slint_compiler::pathutils::is_absolute(f)) {
-                continue;
-            }
-            let uri = path_to_url(d.source_file().unwrap());
-            let new_version = cache.source_code.get(&uri).and_then(|e| e.version);
-            if let Some(data) = result.get_mut(&uri) {
-                if data.0.is_some() && new_version.is_some() && data.0 != new_version {
-                    continue;
-                }
-                data.1.push(crate::util::to_lsp_diag(d));
+    // Notes go to the top right corner of the selected element, or stack up on the canvas
+    let selected = selected_element().and_then(|s| Some((s.as_element_node()?, s.instance_index)));
+    let note = match selected.as_ref().and_then(|(en, index)| {
+        Some((en, en.geometries(&component_instance).get(*index).copied()?))
+    }) {
+        Some((element_node, geometry)) => {
+            let position = LogicalPoint::new(geometry.width(), 0.0);
+            let note = annotations::Note::new(&component, Some(element_node), position, &text);
+            if note.element.is_some() {
+                note
+            } else {
+                // Elements of other components end up with a note on the canvas next to them
+                annotations::Note { x: geometry.max_x(), y: geometry.min_y(), ..note }
             }
         }
-    }
-
-    result
+        None => {
+            let offset = 16.0 * (count % 8) as f32;
+            annotations::Note::new(&component, None, LogicalPoint::new(offset, offset), &text)
+        }
+    };
+    change_notes(|notes| notes.push(note));
 }
 
-fn reset_selections(ui: &ui::PreviewUi) {
-    let model = Rc::new(slint::VecModel::from(Vec::new()));
-    let api = ui.global::<ui::Api>();
-    api.set_selections(slint::ModelRc::from(model));
+// triggered from the UI, running in UI thread
+fn edit_note(index: i32, text: slint::SharedString) {
+    change_notes(|notes| {
+        if let Some(note) = notes.get_mut(index as usize) {
+            note.text = text.to_string();
+        }
+    });
 }
 
-fn set_selections(
-    ui: Option<&ui::PreviewUi>,
-    main_index: usize,
-    layout_kind: ui::LayoutKind,
-    is_interactive: bool,
-    is_moveable: bool,
-    is_resizable: bool,
-    positions: &[i_slint_core::lengths::LogicalRect],
-) {
-    let Some(ui) = ui else {
-        return;
-    };
+// triggered from the UI, running in UI thread
+fn move_note(index: i32, dx: f32, dy: f32) {
+    change_notes(|notes| {
+        if let Some(note) = notes.get_mut(index as usize) {
+            note.x += dx;
+            note.y += dy;
+        }
+    });
+}
 
-    let values = positions
-        .iter()
-        .enumerate()
-        .map(|(i, g)| ui::Selection {
-            geometry: ui::SelectionRectangle {
-                width: g.size.width,
-                height: g.size.height,
-                x: g.origin.x,
-                y: g.origin.y,
-            },
-            layout_data: layout_kind,
-            is_primary: i == main_index,
-            is_interactive,
-            is_moveable,
-            is_resizable,
-        })
-        .collect::<Vec<_>>();
-    let model = Rc::new(slint::VecModel::from(values));
-    let api = ui.global::<ui::Api>();
-    api.set_selections(slint::ModelRc::from(model));
+// triggered from the UI, running in UI thread
+fn remove_note(index: i32) {
+    change_notes(|notes| {
+        if (index as usize) < notes.len() {
+            notes.remove(index as usize);
+        }
+    });
 }
 
 fn set_drop_mark(mark: &Option<drop_location::DropMark>) {
@@ -1535,6 +5581,40 @@ fn set_selected_element(
     positions: &[i_slint_core::lengths::LogicalRect],
     editor_notification: SelectionNotification,
 ) {
+    set_selected_elements(selection, Vec::new(), positions, editor_notification);
+}
+
+fn properties_layout_kind(layout_kind: ui::LayoutKind) -> properties::LayoutKind {
+    match layout_kind {
+        ui::LayoutKind::None => properties::LayoutKind::None,
+        ui::LayoutKind::Horizontal => properties::LayoutKind::HorizontalBox,
+        ui::LayoutKind::Vertical => properties::LayoutKind::VerticalBox,
+        ui::LayoutKind::Grid => properties::LayoutKind::GridLayout,
+    }
+}
+
+/// Select `selection` as the primary element and `group` as further elements
+/// selected along with it.
+///
+/// The property editor shows the properties of the primary element, limited
+/// to those all elements in the group have in common.
+fn set_selected_elements(
+    selection: Option<element_selection::ElementSelection>,
+    group: Vec<element_selection::ElementSelection>,
+    positions: &[i_slint_core::lengths::LogicalRect],
+    editor_notification: SelectionNotification,
+) {
+    let group = if let Some(selection) = &selection {
+        group
+            .into_iter()
+            .filter(|g| (&g.path, g.offset) != (&selection.path, selection.offset))
+            .filter(|g| g.as_element_node().is_some())
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    let group_nodes = group.iter().filter_map(|g| g.as_element_node()).collect::<Vec<_>>();
+
     let (layout_kind, parent_layout_kind, type_name) = {
         let selection_node = selection.as_ref().and_then(|s| s.as_element_node());
         let (layout_kind, parent_layout_kind) = selection_node
@@ -1556,10 +5636,15 @@ fn set_selected_element(
     set_drop_mark(&None);
 
     let element_node = selection.as_ref().and_then(|s| s.as_element_node());
+    let layout_node = element_node.clone().filter(|_| layout_kind != ui::LayoutKind::None);
+    let grid_child_node = element_node
+        .clone()
+        .filter(|_| parent_layout_kind == ui::LayoutKind::Grid && group_nodes.is_empty());
     let notify_editor_about_selection_after_update =
         editor_notification == SelectionNotification::AfterUpdate;
     PREVIEW_STATE.with(move |preview_state| {
         let mut preview_state = preview_state.borrow_mut();
+        preview_state.snap_targets = None;
 
         let is_in_layout = parent_layout_kind != ui::LayoutKind::None;
         let is_layout = layout_kind != ui::LayoutKind::None;
@@ -1575,16 +5660,50 @@ fn set_selected_element(
                 .unwrap_or_default()
         };
 
+        let group_positions = preview_state
+            .component_instance()
+            .map(|ci| group_nodes.iter().flat_map(|en| en.geometries(&ci)).collect::<Vec<_>>())
+            .unwrap_or_default();
+
         set_selections(
             preview_state.ui.as_ref(),
             selection.as_ref().map(|s| s.instance_index).unwrap_or_default(),
             layout_kind,
             is_interactive,
             true,
-            !is_in_layout && !is_layout,
+            !is_in_layout && !is_layout && group_nodes.is_empty(),
             positions,
+            &group_positions,
         );
 
+        if let Some(ui) = &preview_state.ui {
+            let gaps = layout_node
+                .as_ref()
+                .zip(preview_state.component_instance())
+                .and_then(|(element_node, ci)| {
+                    let index = selection.as_ref().map(|s| s.instance_index).unwrap_or_default();
+                    let layout = positions.get(index).or(positions.first()).copied()?;
+                    let children = element_node
+                        .children()
+                        .iter()
+                        .flat_map(|c| c.geometries(&ci))
+                        .filter(|g| layout.contains_rect(g))
+                        .collect::<Vec<_>>();
+                    Some(layout_gaps::gaps(layout, &children))
+                })
+                .unwrap_or_default();
+            ui::ui_set_layout_gaps(ui, &gaps);
+
+            let grid = grid_child_node.as_ref().zip(preview_state.component_instance()).and_then(
+                |(element_node, ci)| {
+                    let index = selection.as_ref().map(|s| s.instance_index).unwrap_or_default();
+                    let geometry = positions.get(index).or(positions.first()).copied()?;
+                    grid_cells::grid(&ci, element_node, geometry)
+                },
+            );
+            ui::ui_set_grid_cells(ui, grid.as_ref());
+        }
+
         if let Some(ui) = &preview_state.ui {
             if let Some(document_cache) = document_cache_from(&preview_state) {
                 if let Some((uri, version, selection)) = selection
@@ -1620,22 +5739,39 @@ fn set_selected_element(
                         ))
                     })
                 {
-                    let in_layout = match parent_layout_kind {
-                        ui::LayoutKind::None => properties::LayoutKind::None,
-                        ui::LayoutKind::Horizontal => properties::LayoutKind::HorizontalBox,
-                        ui::LayoutKind::Vertical => properties::LayoutKind::VerticalBox,
-                        ui::LayoutKind::Grid => properties::LayoutKind::GridLayout,
-                    };
-                    preview_state.property_range_declarations = Some(ui::ui_set_properties(
+                    let in_layout = properties_layout_kind(parent_layout_kind);
+                    let others = group_nodes
+                        .iter()
+                        .map(|en| {
+                            (
+                                en.clone(),
+                                properties_layout_kind(element_selection::parent_layout_kind(en)),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let properties =
+                        properties::query_properties(&uri, version, &selection, in_layout)
+                            .ok()
+                            .map(|mut response| {
+                                properties::retain_common_properties(&mut response, &others);
+                                response
+                            });
+                    let declarations = ui::ui_set_properties(ui, &document_cache, properties);
+                    ui::ui_set_bound_properties(
                         ui,
-                        &document_cache,
-                        properties::query_properties(&uri, version, &selection, in_layout).ok(),
-                    ));
+                        &binding_dependencies::bound_properties(&selection.element),
+                    );
+                    preview_state.property_range_declarations = Some(declarations);
                 }
             }
         }
 
+        if let Some(ui) = &preview_state.ui {
+            ui::ui_set_outline(ui, &preview_state.outline, selection.as_ref(), &layer_overrides());
+        }
+
         preview_state.selected = selection;
+        preview_state.selected_group = group;
         preview_state.notify_editor_about_selection_after_update =
             notify_editor_about_selection_after_update;
     });
@@ -1665,10 +5801,37 @@ fn selected_element() -> Option<ElementSelection> {
     })
 }
 
+/// The elements selected in addition to the `selected_element()`
+fn selected_group() -> Vec<ElementSelection> {
+    PREVIEW_STATE.with(move |preview_state| {
+        let preview_state = preview_state.borrow();
+        preview_state.selected_group.clone()
+    })
+}
+
 fn component_instance() -> Option<ComponentInstance> {
     PREVIEW_STATE.with(move |preview_state| preview_state.borrow().component_instance())
 }
 
+/// The project the previewed component belongs to
+fn project() -> Option<PathBuf> {
+    PREVIEW_STATE.with(move |preview_state| preview_state.borrow().project.clone())
+}
+
+/// The rows kept for the array properties of the components of the project
+fn fixtures() -> model_fixtures::Fixtures {
+    PREVIEW_STATE.with(move |preview_state| preview_state.borrow().fixtures.clone())
+}
+
+/// Change the fixtures of the project and store them along with the other project settings
+fn change_fixtures(change: impl FnOnce(&mut model_fixtures::Fixtures)) {
+    PREVIEW_STATE.with(move |preview_state| {
+        let mut preview_state = preview_state.borrow_mut();
+        change(&mut preview_state.fixtures);
+        schedule_storing_project_settings(&mut preview_state);
+    })
+}
+
 /// This is a *read-only* snapshot of the raw type loader, use this when you
 /// need to know the exact state the compiled resources were in.
 fn document_cache() -> Option<Rc<common::DocumentCache>> {
@@ -1732,29 +5895,59 @@ fn set_status_text(text: &str) {
 
 /// This ensure that the preview window is visible and runs `set_preview_factory`
 fn update_preview_area(
-    compiled: Option<ComponentDefinition>,
+    compiled: Option<Compiled>,
     behavior: LoadBehavior,
     open_import_fallback: common::document_cache::OpenImportFallback,
     source_file_versions: Rc<RefCell<common::document_cache::SourceFileVersionMap>>,
 ) -> Result<(), PlatformError> {
     PREVIEW_STATE.with(move |preview_state| {
         let mut preview_state = preview_state.borrow_mut();
-        preview_state.workspace_edit_sent = false;
+        if std::mem::take(&mut preview_state.workspace_edit_sent) {
+            // The edit made it to the editor and came back to us
+            if let Some(entry) = preview_state.journaled_edit.take() {
+                edit_journal::remove(entry);
+            }
+            if !preview_state.replayed_edits.is_empty() {
+                let _ = i_slint_core::api::invoke_from_event_loop(replay_next_edit);
+            }
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
         native::open_ui_impl(&mut preview_state)?;
 
+        if compiled.is_some() {
+            if let Some(previous) = preview_state.component_instance() {
+                preview_state.compare_definition = Some(previous.definition());
+            }
+        }
+        if let Some(compiled) = &compiled {
+            preview_state.preview_is_partial = matches!(compiled, Compiled::Partial(_));
+        }
+
         let ui = preview_state.ui.as_ref().unwrap();
 
         let shared_handle = preview_state.handle.clone();
         let shared_document_cache = preview_state.document_cache.clone();
+        let shared_safe_area_insets = preview_state.safe_area_insets.clone();
+        let shared_environment = preview_state.environment.clone();
+        let ui_weak = ui.as_weak();
 
         if let Some(compiled) = compiled {
+            let (compiled, is_partial, keep_document_cache) = match compiled {
+                Compiled::Complete(compiled) => (compiled, false, false),
+                Compiled::Partial(compiled) => (compiled, true, true),
+                Compiled::LayersHidden(compiled) => (compiled, false, true),
+            };
+            ui.global::<ui::Api>().set_preview_is_partial(is_partial);
             set_preview_factory(
                 ui,
                 compiled,
                 Box::new(move |instance| {
-                    if let Some(rtl) = instance.definition().raw_type_loader() {
+                    // Keep the document cache of the last complete compilation: The
+                    // partial one and the one with hidden layers do not match the document.
+                    if let Some(rtl) =
+                        instance.definition().raw_type_loader().filter(|_| !keep_document_cache)
+                    {
                         shared_document_cache.replace(Some(Rc::new(
                             common::DocumentCache::new_from_raw_parts(
                                 rtl,
@@ -1763,6 +5956,21 @@ fn update_preview_area(
                             ),
                         )));
                     }
+                    devices::apply_insets(&instance, shared_safe_area_insets.get());
+                    environment::apply(&instance, &shared_environment.borrow());
+                    watch::attach(&instance);
+                    breakpoints::forget_stop();
+                    if let Some(ui) = ui_weak.upgrade() {
+                        let api = ui.global::<ui::Api>();
+                        api.set_environment_global_found(environment::has_global(&instance));
+                        if api.get_show_compare() {
+                            // The new instance is not in place yet
+                            slint::Timer::single_shot(
+                                std::time::Duration::ZERO,
+                                update_compare_image,
+                            );
+                        }
+                    }
                     shared_handle.replace(Some(instance));
                 }),
                 behavior,
@@ -1789,6 +5997,12 @@ fn update_preview_area(
 
 pub fn lsp_to_preview_message(message: crate::common::LspToPreviewMessage) {
     use crate::common::LspToPreviewMessage as M;
+    // Any message shows that the LSP is alive
+    let pong = match &message {
+        M::Pong { id } => Some(*id),
+        _ => None,
+    };
+    let _ = i_slint_core::api::invoke_from_event_loop(move || connection::message_received(pong));
     match message {
         M::InvalidateContents { url } => invalidate_contents(&url),
         M::ForgetFile { url } => delete_document(&url),
@@ -1804,6 +6018,19 @@ pub fn lsp_to_preview_message(message: crate::common::LspToPreviewMessage) {
         M::HighlightFromEditor { url, offset } => {
             highlight(url, offset.into());
         }
+        M::FlashElement { url, offset } => {
+            flash_element(url, offset.into());
+        }
+        M::EditorCursorMoved { url, offset } => {
+            follow_editor_cursor(url, offset.into());
+        }
+        M::DocumentSaved { url } => {
+            document_saved(&url);
+        }
+        M::ReportIssue { path } => {
+            let _ = run_in_ui_thread(move || async move { report_issue(path) });
+        }
+        M::Pong { .. } => {}
     }
 }
 
@@ -1828,10 +6055,19 @@ pub fn interpret_test_with_sources(
     pub fn reinterpret_test_with_sources(
         style: &str,
         code: HashMap<PathBuf, String>,
+    ) -> ComponentInstance {
+        reinterpret_test_file(style, main_test_file_name(), code)
+    }
+
+    /// Interpret the file at `path` of `code`
+    #[track_caller]
+    pub fn reinterpret_test_file(
+        style: &str,
+        path: PathBuf,
+        code: HashMap<PathBuf, String>,
     ) -> ComponentInstance {
         let code = Rc::new(code);
 
-        let path = main_test_file_name();
         let source_code = code.get(&path).unwrap().clone();
         let (diagnostics, component_definition, _, _) = spin_on::spin_on(super::parse_source(
             vec![],
@@ -1868,3 +6104,104 @@ pub fn interpret_test(style: &str, source_code: &str) -> ComponentInstance {
         interpret_test_with_sources(style, code)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn std_widget(name: &str) -> ComponentInformation {
+        ComponentInformation {
+            is_builtin: false,
+            is_std_widget: true,
+            ..common::test::test_component(name, "Basic Widgets")
+        }
+    }
+
+    #[test]
+    fn test_library_component_wrapper() {
+        let (url, source_code) = library_component_wrapper(&std_widget("Button")).unwrap();
+        assert_eq!(url.scheme(), LIBRARY_WRAPPER_SCHEME);
+        assert!(url.path().ends_with("/Button.slint"));
+
+        // The wrapper gets compiled under its URL, like the preview does it
+        let path = PathBuf::from(url.to_string());
+        let code = std::collections::HashMap::from([(path.clone(), source_code.clone())]);
+        i_slint_backend_testing::init_no_event_loop();
+        for style in ["fluent", "material", "cosmic"] {
+            let component_instance = test::reinterpret_test_file(style, path.clone(), code.clone());
+            assert_eq!(component_instance.definition().name(), "Button");
+        }
+
+        let mut library_component = std_widget("Card");
+        library_component.is_std_widget = false;
+        library_component.defined_at = Some(common::Position::new(
+            Url::parse("file:///@material/card.slint").unwrap(),
+            0.into(),
+        ));
+        let (_, source_code) = library_component_wrapper(&library_component).unwrap();
+        assert_eq!(
+            source_code,
+            "import { Card as Base } from \"@material/card.slint\";\nexport component Card inherits Base { }\n"
+        );
+
+        // Components in workspace files and builtins have a file to preview already
+        library_component.defined_at = Some(common::Position::new(
+            Url::parse("file:///home/user/card.slint").unwrap(),
+            0.into(),
+        ));
+        assert!(library_component_wrapper(&library_component).is_none());
+
+        let mut builtin = std_widget("Rectangle");
+        builtin.is_std_widget = false;
+        builtin.is_builtin = true;
+        assert!(library_component_wrapper(&builtin).is_none());
+    }
+
+    #[test]
+    fn test_drop_stale_library_component_wrappers() {
+        let mut cache = ContentCache::default();
+        let mut wrap = |name: &str| {
+            let (url, code) = library_component_wrapper(&std_widget(name)).unwrap();
+            cache.source_code.insert(url.clone(), SourceCodeCacheEntry { version: None, code });
+            url
+        };
+        let button = wrap("Button");
+        let slider = wrap("Slider");
+        let file = Url::parse("file:///main.slint").unwrap();
+        cache
+            .source_code
+            .insert(file.clone(), SourceCodeCacheEntry { version: None, code: String::new() });
+
+        let preview = |url: &Url| PreviewComponent {
+            url: url.clone(),
+            component: None,
+            style: String::new(),
+        };
+        cache.set_current_component(preview(&slider));
+        assert!(!cache.source_code.contains_key(&button));
+        assert!(cache.source_code.contains_key(&slider));
+        cache.set_current_component(preview(&file));
+        assert_eq!(cache.source_code.keys().collect::<Vec<_>>(), [&file]);
+    }
+
+    #[test]
+    fn test_set_color_scheme_override() {
+        let component_instance = test::interpret_test(
+            "fluent",
+            r#"import { Palette } from "std-widgets.slint";
+export component Main {
+    out property <bool> dark: Palette.color-scheme == ColorScheme.dark;
+}"#,
+        );
+        let is_dark = || component_instance.get_property("dark").unwrap();
+        let window = component_instance.window();
+
+        set_color_scheme_override(window, ColorScheme::Dark);
+        assert_eq!(is_dark(), slint_interpreter::Value::Bool(true));
+        set_color_scheme_override(window, ColorScheme::Light);
+        assert_eq!(is_dark(), slint_interpreter::Value::Bool(false));
+        // The testing platform has no color scheme of its own
+        set_color_scheme_override(window, ColorScheme::Unknown);
+        assert_eq!(is_dark(), slint_interpreter::Value::Bool(false));
+    }
+}
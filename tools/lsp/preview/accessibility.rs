@@ -0,0 +1,139 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The accessible elements of the preview as assistive technology like screen readers sees
+//! them, and the accessibility actions they support
+
+use i_slint_core::accessibility::{
+    AccessibilityAction, AccessibleStringProperty, SupportedAccessibilityAction,
+};
+use i_slint_core::item_tree::ItemWeak;
+use i_slint_core::items::{AccessibleRole, ItemRc};
+use i_slint_core::lengths::LogicalRect;
+
+/// The actions the inspector can simulate, with the names shown for them
+const ACTIONS: [(SupportedAccessibilityAction, &str); 4] = [
+    (SupportedAccessibilityAction::Default, "default"),
+    (SupportedAccessibilityAction::Increment, "increment"),
+    (SupportedAccessibilityAction::Decrement, "decrement"),
+    (SupportedAccessibilityAction::Expand, "expand"),
+];
+
+/// An element of the preview with an accessible role
+#[derive(Clone)]
+pub struct AccessibleElement {
+    pub geometry: LogicalRect,
+    pub role: AccessibleRole,
+    /// The type name of the element, with its id if it has one
+    pub element: String,
+    pub label: String,
+    pub value: String,
+    pub description: String,
+    pub actions: SupportedAccessibilityAction,
+    item: ItemWeak,
+}
+
+impl AccessibleElement {
+    /// Screen readers have nothing to announce for the element but its role
+    pub fn is_missing_label(&self) -> bool {
+        is_missing_label(self.role, &self.label)
+    }
+
+    /// The names of the actions supported by the element
+    pub fn action_names(&self) -> Vec<&'static str> {
+        ACTIONS
+            .iter()
+            .filter(|(action, _)| self.actions.contains(*action))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    /// Trigger the action called `name` on the element, as assistive technology would
+    pub fn perform(&self, name: &str) -> bool {
+        let Some(item) = self.item.upgrade() else {
+            return false;
+        };
+        let action = match name {
+            "default" => AccessibilityAction::Default,
+            "increment" => AccessibilityAction::Increment,
+            "decrement" => AccessibilityAction::Decrement,
+            "expand" => AccessibilityAction::Expand,
+            _ => return false,
+        };
+        item.accessible_action(&action);
+        true
+    }
+}
+
+fn is_missing_label(role: AccessibleRole, label: &str) -> bool {
+    // Texts are labeled by their text, containers by the elements in them
+    !matches!(
+        role,
+        AccessibleRole::None
+            | AccessibleRole::Text
+            | AccessibleRole::Groupbox
+            | AccessibleRole::List
+            | AccessibleRole::Table
+            | AccessibleRole::Tree
+            | AccessibleRole::TabList
+            | AccessibleRole::TabPanel
+    ) && label.trim().is_empty()
+}
+
+pub fn element_name(item: &ItemRc) -> String {
+    let (type_name, id) = item
+        .element_type_names_and_ids(0)
+        .and_then(|names| names.into_iter().next())
+        .unwrap_or_default();
+    if id.is_empty() {
+        type_name.to_string()
+    } else {
+        // Ids are prefixed with the name of the component declaring them
+        format!("{} := {type_name}", id.rsplit("::").next().unwrap_or_default())
+    }
+}
+
+/// The accessible elements of the preview shown in `window`, in the order they are drawn in
+pub fn collect(window: &slint::Window) -> Vec<AccessibleElement> {
+    let mut elements = vec![];
+    super::render_stats::visit_window_items(window, |item| {
+        if !item.is_accessible() || !item.is_visible() {
+            return;
+        }
+        let role = item.accessible_role();
+        if role == AccessibleRole::None {
+            return;
+        }
+        let Some(geometry) = super::render_stats::geometry_in_preview(item) else {
+            return;
+        };
+        let string_property =
+            |what| item.accessible_string_property(what).map(|s| s.to_string()).unwrap_or_default();
+        elements.push(AccessibleElement {
+            geometry,
+            role,
+            element: element_name(item),
+            label: string_property(AccessibleStringProperty::Label),
+            value: string_property(AccessibleStringProperty::Value),
+            description: string_property(AccessibleStringProperty::Description),
+            actions: item.supported_accessibility_actions(),
+            item: item.downgrade(),
+        });
+    });
+    elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_missing_label() {
+        assert!(is_missing_label(AccessibleRole::Button, ""));
+        assert!(is_missing_label(AccessibleRole::Slider, "  "));
+        assert!(!is_missing_label(AccessibleRole::Button, "OK"));
+        assert!(!is_missing_label(AccessibleRole::Text, ""));
+        assert!(!is_missing_label(AccessibleRole::None, ""));
+        assert!(!is_missing_label(AccessibleRole::TabPanel, ""));
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Sticky notes for design reviews in the preview: Notes are anchored to an element of a
+//! component or to a position on its canvas, and kept in a file next to the file of the
+//! component, so that they can be shared along with it.
+
+use std::path::{Path, PathBuf};
+
+use i_slint_core::lengths::{LogicalPoint, LogicalVector};
+use slint_interpreter::ComponentInstance;
+
+use crate::common;
+use crate::preview::{element_locks, element_selection, ext::ElementRcNodeExt};
+
+/// The extension replacing `.slint` in the name of the file the notes of a file are stored in
+pub const NOTES_EXTENSION: &str = "notes.json";
+
+/// A note on a component
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Note {
+    pub component: String,
+    /// The indices of the children leading from the root element of the component to the
+    /// element the note is anchored to. The note is placed on the canvas if there are none.
+    pub element: Option<Vec<usize>>,
+    /// The position of the note relative to its element, or on the canvas
+    pub x: f32,
+    pub y: f32,
+    pub text: String,
+}
+
+impl Note {
+    /// A note with `text` on the `element_node`, or on the canvas at `position` if there is no
+    /// element or it can not be anchored to
+    pub fn new(
+        component: &str,
+        element_node: Option<&common::ElementRcNode>,
+        position: LogicalPoint,
+        text: &str,
+    ) -> Self {
+        let element = element_node.and_then(|en| {
+            let (path, _) = en.path_and_offset();
+            en.with_element_node(|node| element_locks::address(&path, node))
+                .filter(|address| address.component == component)
+        });
+        Note {
+            component: component.to_string(),
+            element: element.map(|address| address.children),
+            x: position.x,
+            y: position.y,
+            text: text.to_string(),
+        }
+    }
+}
+
+/// A note of the previewed component, as the preview shows it
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlacedNote {
+    /// The index of the note among all notes of the file
+    pub index: usize,
+    /// Where the note is in the preview. `None` if its element is gone.
+    pub position: Option<LogicalPoint>,
+    /// The type of the element the note is anchored to, empty for notes on the canvas
+    pub anchor: String,
+    pub text: String,
+}
+
+/// The file the notes of the `.slint` file at `path` are stored in
+pub fn notes_path(path: &Path) -> PathBuf {
+    path.with_extension(NOTES_EXTENSION)
+}
+
+/// The notes of the `.slint` file at `path`
+pub fn load(path: &Path) -> Vec<Note> {
+    std::fs::read(notes_path(path))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Store the notes of the `.slint` file at `path`, removing their file if there are none
+pub fn store(path: &Path, notes: &[Note]) -> Result<(), String> {
+    let path = notes_path(path);
+    let result = if notes.is_empty() {
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    } else {
+        serde_json::to_vec_pretty(notes)
+            .map_err(std::io::Error::from)
+            .and_then(|data| std::fs::write(&path, data))
+    };
+    result.map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// The `notes` on the component shown by `component_instance`, placed next to their elements
+pub fn place(notes: &[Note], component_instance: &ComponentInstance) -> Vec<PlacedNote> {
+    let definition = component_instance.definition();
+    let component = definition.name();
+    let root = common::ElementRcNode::new(element_selection::root_element(component_instance), 0);
+    notes
+        .iter()
+        .enumerate()
+        .filter(|(_, note)| note.component == component)
+        .map(|(index, note)| {
+            let offset = LogicalVector::new(note.x, note.y);
+            let (position, anchor) = match &note.element {
+                Some(children) => {
+                    let element_node =
+                        root.as_ref().and_then(|r| element_locks::element_below(r, children));
+                    let position = element_node.as_ref().and_then(|en| {
+                        let geometry = en.geometries(component_instance).first().copied()?;
+                        Some(geometry.origin + offset)
+                    });
+                    let anchor = element_node
+                        .map(|en| en.component_type().trim().to_string())
+                        .unwrap_or_default();
+                    (position, anchor)
+                }
+                None => (Some(offset.to_point()), String::new()),
+            };
+            PlacedNote { index, position, anchor, text: note.text.clone() }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_notes() {
+        let dir = std::env::temp_dir().join(format!("slint-preview-notes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("main.slint");
+        assert_eq!(notes_path(&path), dir.join("main.notes.json"));
+        assert!(load(&path).is_empty());
+
+        let notes = vec![
+            Note {
+                component: "Main".into(),
+                element: Some(vec![0, 1]),
+                x: 10.0,
+                y: -4.0,
+                text: "Too much padding?".into(),
+            },
+            Note { component: "Main".into(), text: "Check on mobile".into(), ..Default::default() },
+        ];
+        store(&path, &notes).unwrap();
+        assert_eq!(load(&path), notes);
+
+        store(&path, &[]).unwrap();
+        assert!(!notes_path(&path).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_place_notes() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+export component Main {
+    width: 200px;
+    height: 100px;
+    Rectangle {
+        x: 20px;
+        y: 30px;
+        width: 50px;
+        height: 50px;
+        Text { x: 5px; y: 5px; text: "Hello"; }
+    }
+}
+"#,
+        );
+        let root =
+            common::ElementRcNode::new(element_selection::root_element(&component_instance), 0)
+                .unwrap();
+        let rectangle = element_locks::element_below(&root, &[0]).unwrap();
+
+        let notes = vec![
+            Note::new("Main", Some(&rectangle), LogicalPoint::new(50.0, 0.0), "On the element"),
+            Note::new("Main", None, LogicalPoint::new(150.0, 10.0), "On the canvas"),
+            Note::new("Other", Some(&rectangle), LogicalPoint::new(0.0, 0.0), "Elsewhere"),
+            Note { element: Some(vec![3]), ..Note::new("Main", None, Default::default(), "Gone") },
+        ];
+        assert_eq!(notes[0].element, Some(vec![0]));
+        assert_eq!(notes[2].element, None);
+
+        assert_eq!(
+            place(&notes, &component_instance),
+            vec![
+                PlacedNote {
+                    index: 0,
+                    position: Some(LogicalPoint::new(70.0, 30.0)),
+                    anchor: "Rectangle".into(),
+                    text: "On the element".into(),
+                },
+                PlacedNote {
+                    index: 1,
+                    position: Some(LogicalPoint::new(150.0, 10.0)),
+                    anchor: String::new(),
+                    text: "On the canvas".into(),
+                },
+                PlacedNote { index: 3, position: None, anchor: String::new(), text: "Gone".into() },
+            ]
+        );
+    }
+}
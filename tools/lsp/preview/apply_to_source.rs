@@ -0,0 +1,198 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Keep a value tried out in the preview data: The runtime value of a property of the previewed
+//! component or of a global gets written as the binding of the property in the source code.
+
+use i_slint_compiler::langtype::Type;
+use lsp_types::Url;
+use slint_interpreter::{ComponentInstance, Value};
+
+use crate::common;
+use crate::preview::preview_data::{self, PropertyContainer};
+use crate::preview::{globals, properties};
+
+fn number_to_code(number: f64, unit: &str) -> Option<String> {
+    number.is_finite().then(|| format!("{number}{unit}"))
+}
+
+fn color_to_code(color: slint::Color) -> String {
+    let (r, g, b, a) = (color.red(), color.green(), color.blue(), color.alpha());
+    if a == 255 {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+fn gradient_stops_to_code<'a>(
+    stops: impl Iterator<Item = &'a i_slint_core::graphics::GradientStop>,
+) -> String {
+    stops.map(|s| format!(", {} {}%", color_to_code(s.color), s.position * 100.0)).collect()
+}
+
+fn brush_to_code(brush: &slint::Brush) -> Option<String> {
+    match brush {
+        slint::Brush::SolidColor(color) => Some(color_to_code(*color)),
+        slint::Brush::LinearGradient(g) => {
+            Some(format!("@linear-gradient({}deg{})", g.angle(), gradient_stops_to_code(g.stops())))
+        }
+        slint::Brush::RadialGradient(g) => {
+            Some(format!("@radial-gradient(circle{})", gradient_stops_to_code(g.stops())))
+        }
+        _ => None,
+    }
+}
+
+/// The Slint code of the `value` of type `ty`. `None` for values that can not be written as
+/// literal, like images.
+fn value_to_code(ty: &Type, value: &Value) -> Option<String> {
+    match (ty, value) {
+        (Type::Int32, Value::Number(n)) => number_to_code(n.round(), ""),
+        (Type::Float32, Value::Number(n)) => number_to_code(*n, ""),
+        (Type::LogicalLength, Value::Number(n)) => number_to_code(*n, "px"),
+        (Type::PhysicalLength, Value::Number(n)) => number_to_code(*n, "phx"),
+        (Type::Rem, Value::Number(n)) => number_to_code(*n, "rem"),
+        (Type::Duration, Value::Number(n)) => number_to_code(*n, "ms"),
+        (Type::Angle, Value::Number(n)) => number_to_code(*n, "deg"),
+        (Type::Percent, Value::Number(n)) => number_to_code(*n, "%"),
+        (Type::Bool, Value::Bool(b)) => Some(b.to_string()),
+        (Type::String, Value::String(s)) => Some(format!("\"{}\"", s.as_str().escape_debug())),
+        (Type::Color | Type::Brush, Value::Brush(brush)) => brush_to_code(brush),
+        (Type::Enumeration(e), Value::EnumerationValue(_, v)) => Some(format!("{}.{v}", e.name)),
+        (Type::Struct(s), Value::Struct(value)) => {
+            let fields = s
+                .fields
+                .iter()
+                .map(|(name, ty)| {
+                    Some(format!("{name}: {}", value_to_code(ty, value.get_field(name)?)?))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(if fields.is_empty() {
+                "{}".into()
+            } else {
+                format!("{{ {} }}", fields.join(", "))
+            })
+        }
+        (Type::Array(row_type), Value::Model(model)) => {
+            let rows = slint::Model::iter(model)
+                .map(|row| value_to_code(row_type, &row))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("[{}]", rows.join(", ")))
+        }
+        _ => None,
+    }
+}
+
+/// The element declaring the properties of `container`: The root element of the component
+/// `component_name` defined in the document at `url`, or of a global it exports
+fn declaring_element(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    container: &PropertyContainer,
+    component_name: &str,
+) -> Option<common::ElementRcNode> {
+    match container {
+        PropertyContainer::Main => {
+            let document = document_cache.get_document(url)?;
+            let component =
+                document.inner_components.iter().find(|c| c.id == component_name)?.clone();
+            common::ElementRcNode::new(component.root_element.clone(), 0)
+        }
+        PropertyContainer::Global(name) => globals::exported_globals(document_cache, url)
+            .into_iter()
+            .find_map(|(n, root)| (&n == name).then_some(root)),
+    }
+}
+
+/// The edit setting the binding of the property `property_name` of `container` to the value
+/// it has in the `component_instance` previewing the document at `url`
+pub fn binding_edit(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    component_instance: &ComponentInstance,
+    container: &PropertyContainer,
+    property_name: &str,
+) -> Result<lsp_types::WorkspaceEdit, String> {
+    let preview_data = preview_data::get_preview_data(
+        component_instance,
+        container.clone(),
+        property_name.to_string(),
+    )
+    .filter(|pd| pd.is_property())
+    .ok_or_else(|| format!("Property {property_name} not found"))?;
+    let code = preview_data
+        .value
+        .as_ref()
+        .and_then(|value| value_to_code(&preview_data.ty, value))
+        .ok_or_else(|| format!("The value of {property_name} can not be written as code"))?;
+
+    let element =
+        declaring_element(document_cache, url, container, component_instance.definition().name())
+            .ok_or_else(|| format!("The declaration of {property_name} was not found"))?;
+    let current = properties::get_properties(&element, properties::LayoutKind::None)
+        .into_iter()
+        .find(|p| p.name == property_name)
+        .and_then(|p| p.defined_at)
+        .map(|d| d.code_block_or_expression.text().to_string());
+    if current.as_deref().map(str::trim) == Some(code.as_str()) {
+        return Err(format!("The source code already sets {property_name} to {code}"));
+    }
+
+    let (path, _) = element.path_and_offset();
+    let url = Url::from_file_path(&path)
+        .map_err(|()| format!("{} is not a valid file", path.display()))?;
+    let version = document_cache.document_version(&url);
+    properties::set_binding(url, version, &element, property_name, code)
+        .ok_or_else(|| format!("Failed to set the binding of {property_name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_to_code() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+                export enum Mode { compact, wide }
+                export struct Item { label: string, count: int }
+
+                export component MainComponent {
+                    in property <int> count: 3;
+                    in property <length> size: 12.5px;
+                    in property <duration> delay: 250ms;
+                    in property <string> title: "Say \"hi\"\n";
+                    in property <color> tint: #ff000080;
+                    in property <brush> fill: @linear-gradient(90deg, #f00 0%, #ff0 100%);
+                    in property <Mode> mode: Mode.wide;
+                    in property <[Item]> items: [{ label: "a", count: 1 }];
+                    in property <image> icon;
+                }
+            "#,
+        );
+        let code = |name: &str| {
+            let pd = preview_data::get_preview_data(
+                &component_instance,
+                PropertyContainer::Main,
+                name.to_string(),
+            )
+            .unwrap();
+            value_to_code(&pd.ty, pd.value.as_ref().unwrap())
+        };
+
+        assert_eq!(code("count").as_deref(), Some("3"));
+        assert_eq!(code("size").as_deref(), Some("12.5px"));
+        assert_eq!(code("delay").as_deref(), Some("250ms"));
+        assert_eq!(code("title").as_deref(), Some(r#""Say \"hi\"\n""#));
+        assert_eq!(code("tint").as_deref(), Some("#ff000080"));
+        assert_eq!(
+            code("fill").as_deref(),
+            Some("@linear-gradient(90deg, #ff0000 0%, #ffff00 100%)")
+        );
+        assert_eq!(code("mode").as_deref(), Some("Mode.wide"));
+        assert_eq!(code("items").as_deref(), Some(r#"[{ count: 1, label: "a" }]"#));
+        assert_eq!(code("icon"), None);
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The baseline grid: Horizontal lines every `rhythm` pixels, which the baselines of texts
+//! moved around in the preview snap to, for designs that keep a vertical rhythm.
+
+use i_slint_core::items::{ComplexText, FontMetrics, SimpleText, TextInput, TextVerticalAlignment};
+use i_slint_core::lengths::{LogicalPoint, LogicalRect};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BaselineGrid {
+    /// The distance between two lines
+    pub rhythm: f32,
+    /// The position of the first line
+    pub offset: f32,
+}
+
+impl BaselineGrid {
+    /// The line closest to `y`
+    pub fn nearest_line(&self, y: f32) -> f32 {
+        self.offset + ((y - self.offset) / self.rhythm).round() * self.rhythm
+    }
+
+    /// Move `rect` vertically so that its baseline, `baseline` below its top, is on a line.
+    /// Returns the moved rectangle and the line.
+    pub fn snap(&self, rect: LogicalRect, baseline: f32) -> (LogicalRect, f32) {
+        let line = self.nearest_line(rect.min_y() + baseline);
+        (LogicalRect::new(LogicalPoint::new(rect.min_x(), line - baseline), rect.size), line)
+    }
+}
+
+/// The distance of the baseline of the first line of a text from the top of its element
+pub fn baseline_offset(
+    metrics: &FontMetrics,
+    height: f32,
+    alignment: TextVerticalAlignment,
+) -> f32 {
+    let line_height = metrics.ascent - metrics.descent;
+    let top = match alignment {
+        TextVerticalAlignment::Top => 0.0,
+        TextVerticalAlignment::Center => (height - line_height) / 2.0,
+        TextVerticalAlignment::Bottom => height - line_height,
+    };
+    top + metrics.ascent
+}
+
+/// The baseline offset of the text shown at `geometry` in the preview, `None` if there is no
+/// text there
+pub fn text_baseline(window: &slint::Window, geometry: LogicalRect) -> Option<f32> {
+    let window_adapter = i_slint_core::window::WindowInner::from_pub(window).window_adapter();
+    let same = |a: f32, b: f32| (a - b).abs() < 0.5;
+    let mut result = None;
+    super::render_stats::visit_window_items(window, |item| {
+        if result.is_some() {
+            return;
+        }
+        let Some(item_geometry) = super::render_stats::geometry_in_preview(item) else {
+            return;
+        };
+        if !same(item_geometry.min_x(), geometry.min_x())
+            || !same(item_geometry.min_y(), geometry.min_y())
+            || !same(item_geometry.height(), geometry.height())
+        {
+            return;
+        }
+        let height = item_geometry.height();
+        let (metrics, alignment) = if let Some(text) = item.downcast::<SimpleText>() {
+            let text = text.as_pin_ref();
+            (text.font_metrics(&window_adapter), text.vertical_alignment())
+        } else if let Some(text) = item.downcast::<ComplexText>() {
+            let text = text.as_pin_ref();
+            (text.font_metrics(&window_adapter), text.vertical_alignment())
+        } else if let Some(text) = item.downcast::<TextInput>() {
+            let text = text.as_pin_ref();
+            (text.font_metrics(&window_adapter), text.vertical_alignment())
+        } else {
+            return;
+        };
+        result = Some(baseline_offset(&metrics, height, alignment));
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use i_slint_core::lengths::LogicalSize;
+
+    #[test]
+    fn test_baseline_offset() {
+        let metrics = FontMetrics { ascent: 12.0, descent: -4.0, x_height: 6.0, cap_height: 9.0 };
+        assert_eq!(baseline_offset(&metrics, 30.0, TextVerticalAlignment::Top), 12.0);
+        assert_eq!(baseline_offset(&metrics, 30.0, TextVerticalAlignment::Center), 19.0);
+        assert_eq!(baseline_offset(&metrics, 30.0, TextVerticalAlignment::Bottom), 26.0);
+    }
+
+    #[test]
+    fn test_snap() {
+        let grid = BaselineGrid { rhythm: 8.0, offset: 2.0 };
+        assert_eq!(grid.nearest_line(13.0), 10.0);
+        assert_eq!(grid.nearest_line(15.0), 18.0);
+
+        let rect = LogicalRect::new(LogicalPoint::new(5.0, 20.0), LogicalSize::new(40.0, 16.0));
+        let (snapped, line) = grid.snap(rect, 12.0);
+        assert_eq!(line, 34.0);
+        assert_eq!(snapped.origin, LogicalPoint::new(5.0, 22.0));
+        assert_eq!(snapped.size, rect.size);
+    }
+}
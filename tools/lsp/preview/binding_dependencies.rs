@@ -0,0 +1,188 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The dependencies between the bindings of a component, from the resolved expressions: Which
+//! properties the binding of a property reads, which bindings read that property, and whether
+//! it is part of a binding loop.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use i_slint_compiler::expression_tree::Expression;
+use i_slint_compiler::langtype::Type;
+use i_slint_compiler::namedreference::NamedReference;
+use i_slint_compiler::object_tree::{self, ElementRc};
+
+/// A property in the dependency graph
+#[derive(Clone, Debug, PartialEq)]
+pub struct Property {
+    /// The element and property name, like `root.width`
+    pub label: String,
+    /// The file, line and column of the binding, `None` if it has none
+    pub location: Option<(PathBuf, usize, usize)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dependencies {
+    /// The properties the binding reads
+    pub dependencies: Vec<Property>,
+    /// The properties with a binding that reads the property
+    pub dependents: Vec<Property>,
+    /// The properties of the binding loop the property is part of, starting with it. Empty if
+    /// there is none.
+    pub binding_loop: Vec<Property>,
+}
+
+fn is_binding(element: &ElementRc, name: &str) -> bool {
+    !matches!(
+        element.borrow().lookup_property(name).property_type,
+        Type::Callback(_) | Type::Function(_)
+    )
+}
+
+/// The properties the binding of `property` reads, including the ones it is linked to
+fn reads(property: &NamedReference) -> Vec<NamedReference> {
+    let element = property.element();
+    let element = element.borrow();
+    let Some(binding) = element.bindings.get(property.name()) else {
+        return vec![];
+    };
+    let binding = binding.borrow();
+    let mut result = binding.two_way_bindings.clone();
+    binding.expression.visit_recursive(&mut |e| {
+        if let Expression::PropertyReference(nr) = e {
+            if !result.contains(nr) {
+                result.push(nr.clone());
+            }
+        }
+    });
+    result
+}
+
+fn element_label(element: &ElementRc) -> String {
+    if let Some(component) = element.borrow().enclosing_component.upgrade() {
+        if Rc::ptr_eq(&component.root_element, element) {
+            return if component.is_global() { component.id.to_string() } else { "root".into() };
+        }
+    }
+    let element = element.borrow();
+    if element.id.is_empty() {
+        element.base_type.to_string()
+    } else {
+        element.id.to_string()
+    }
+}
+
+fn property(nr: &NamedReference) -> Property {
+    let element = nr.element();
+    let location = element.borrow().bindings.get(nr.name()).and_then(|binding| {
+        let span = binding.borrow().span.clone()?;
+        let source_file = span.source_file.as_ref()?;
+        let (line, column) = source_file.line_column(span.span.offset);
+        Some((source_file.path().to_path_buf(), line, column))
+    });
+    Property { label: format!("{}.{}", element_label(&element), nr.name()), location }
+}
+
+/// The properties of `element` that have a binding, callback handlers excluded
+pub fn bound_properties(element: &ElementRc) -> Vec<String> {
+    let names = element.borrow().bindings.keys().cloned().collect::<Vec<_>>();
+    names.into_iter().filter(|name| is_binding(element, name)).map(|n| n.to_string()).collect()
+}
+
+/// A path of bindings from `start` back to itself, starting with it
+fn binding_loop(start: &NamedReference) -> Vec<NamedReference> {
+    let mut previous: HashMap<NamedReference, NamedReference> = HashMap::new();
+    let mut queue = VecDeque::from([start.clone()]);
+    while let Some(current) = queue.pop_front() {
+        for next in reads(&current) {
+            if &next == start {
+                let mut path = vec![current.clone()];
+                let mut at = current;
+                while let Some(p) = previous.get(&at) {
+                    path.push(p.clone());
+                    at = p.clone();
+                }
+                path.reverse();
+                return path;
+            }
+            if !previous.contains_key(&next) {
+                previous.insert(next.clone(), current.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    vec![]
+}
+
+/// The dependencies of the `name` property of `element` within its component
+pub fn dependencies(element: &ElementRc, name: &str) -> Dependencies {
+    let target = NamedReference::new(element, name.into());
+
+    let mut dependents = vec![];
+    let component = element.borrow().enclosing_component.upgrade();
+    if let Some(component) = component {
+        object_tree::recurse_elem(&component.root_element, &(), &mut |e, _| {
+            let names = e.borrow().bindings.keys().cloned().collect::<Vec<_>>();
+            for name in names.into_iter().filter(|name| is_binding(e, name)) {
+                let nr = NamedReference::new(e, name);
+                if reads(&nr).contains(&target) {
+                    dependents.push(property(&nr));
+                }
+            }
+        });
+    }
+
+    Dependencies {
+        dependencies: reads(&target).iter().map(property).collect(),
+        dependents,
+        binding_loop: binding_loop(&target).iter().map(property).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    #[test]
+    fn test_dependencies() {
+        let source = r#"
+export component Main {
+    in-out property <length> a: b + 1px;
+    property <length> b: c * 2;
+    property <length> c: a;
+    property <length> d: 5px;
+    callback clicked();
+    clicked => { d = b; }
+    t := Text { text: "x"; x: root.b; }
+}
+"#;
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        let offset = source.find("Text {").unwrap() as u32;
+        let text = dc.element_at_offset(&url, offset.into()).unwrap();
+        let root = text.parent().unwrap();
+        let labels = |properties: &[Property]| {
+            properties.iter().map(|p| p.label.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(bound_properties(&root.element), vec!["a", "b", "c", "d"]);
+
+        let b = dependencies(&root.element, "b");
+        assert_eq!(labels(&b.dependencies), vec!["root.c"]);
+        assert_eq!(labels(&b.dependents), vec!["root.a", "t.x"]);
+        assert_eq!(labels(&b.binding_loop), vec!["root.b", "root.c", "root.a"]);
+        assert_eq!(b.dependencies[0].location.as_ref().unwrap().1, 5);
+
+        let x = dependencies(&text.element, "x");
+        assert_eq!(labels(&x.dependencies), vec!["root.b"]);
+        assert!(x.dependents.is_empty());
+        assert!(x.binding_loop.is_empty());
+
+        let d = dependencies(&root.element, "d");
+        assert!(d.dependencies.is_empty());
+        assert!(d.dependents.is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Breakpoints on the callback handlers of the previewed component. The preview stops taking
+//! input right after a handler with a breakpoint got invoked, so that its arguments and the
+//! state it left the preview in can be looked at. Stepping stops again at the next handler
+//! that gets invoked. Timers and animations keep running while stopped.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use i_slint_compiler::diagnostics::SourceLocation;
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, SyntaxNode};
+use slint_interpreter::CallbackInvocation;
+
+/// A callback handler declared in the previewed document
+#[derive(Clone, Debug, PartialEq)]
+pub struct Handler {
+    /// The id of the element, or its type if it has none
+    pub element: String,
+    pub callback: String,
+    pub path: PathBuf,
+    /// The offset of the handler, where the interpreter reports it to be declared
+    pub offset: u32,
+    pub line: usize,
+}
+
+/// The invocation of a handler the preview stopped at
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hit {
+    pub callback: String,
+    pub arguments: Vec<String>,
+    pub location: Option<(PathBuf, usize, usize)>,
+}
+
+fn element_label(element: &SyntaxNode) -> String {
+    let type_name = || {
+        element
+            .child_node(SyntaxKind::QualifiedName)
+            .map(|n| n.text().to_string().trim().to_string())
+            .unwrap_or_default()
+    };
+    match element.parent() {
+        Some(parent) if parent.kind() == SyntaxKind::SubElement => parent
+            .child_text(SyntaxKind::Identifier)
+            .map(|id| id.to_string())
+            .unwrap_or_else(type_name),
+        Some(parent) if parent.kind() == SyntaxKind::Component => parent
+            .child_node(SyntaxKind::DeclaredIdentifier)
+            .map(|n| n.text().to_string().trim().to_string())
+            .unwrap_or_else(type_name),
+        _ => type_name(),
+    }
+}
+
+/// The callback handlers declared in `document`, in source order
+pub fn handlers(document: &syntax_nodes::Document) -> Vec<Handler> {
+    let path = document.source_file.path().to_path_buf();
+    document
+        .descendants()
+        .filter(|n| n.kind() == SyntaxKind::CallbackConnection)
+        .filter_map(|connection| {
+            let callback = connection.child_text(SyntaxKind::Identifier)?.to_string();
+            let element = connection.parent().map(|e| element_label(&e)).unwrap_or_default();
+            let offset = u32::from(connection.text_range().start());
+            let (line, _) = document.source_file.line_column(offset as usize);
+            Some(Handler { element, callback, path: path.clone(), offset, line })
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Debugger {
+    breakpoints: HashSet<(PathBuf, u32)>,
+    /// Stop at the next handler, whether it has a breakpoint or not
+    step: bool,
+    hit: Option<Hit>,
+    changed: Option<fn()>,
+}
+
+impl Debugger {
+    fn should_stop(&self, location: Option<&SourceLocation>) -> bool {
+        self.hit.is_none()
+            && (self.step
+                || location.is_some_and(|l| {
+                    let Some(source_file) = l.source_file.as_ref() else {
+                        return false;
+                    };
+                    let key = (source_file.path().to_path_buf(), l.span.offset as u32);
+                    self.breakpoints.contains(&key)
+                }))
+    }
+}
+
+thread_local! {static DEBUGGER: RefCell<Debugger> = RefCell::default();}
+
+fn notify() {
+    if let Some(changed) = DEBUGGER.with(|debugger| debugger.borrow().changed) {
+        // Handlers get invoked while the preview is busy with an event, so update it later
+        slint::Timer::single_shot(std::time::Duration::ZERO, changed);
+    }
+}
+
+fn hit(invocation: &CallbackInvocation) -> Hit {
+    let location = invocation.location.and_then(|l| {
+        let source_file = l.source_file.as_ref()?;
+        let (line, column) = source_file.line_column(l.span.offset);
+        Some((source_file.path().to_path_buf(), line, column))
+    });
+    Hit {
+        callback: invocation.name.to_string(),
+        arguments: invocation.arguments.iter().map(super::expression_editor::value_text).collect(),
+        location,
+    }
+}
+
+/// Watch the handlers invoked in this thread for breakpoints. This needs to happen before the
+/// previewed component gets instantiated. `changed` gets called from the event loop when a
+/// breakpoint got hit or the preview continues.
+pub fn install(changed: fn()) {
+    DEBUGGER.with(|debugger| debugger.borrow_mut().changed = Some(changed));
+    slint_interpreter::set_callback_hook(Some(Box::new(|invocation| {
+        super::output_search::record_callback(invocation);
+        let stopped = DEBUGGER.with(|debugger| {
+            let mut debugger = debugger.borrow_mut();
+            if !debugger.should_stop(invocation.location) {
+                return false;
+            }
+            debugger.step = false;
+            debugger.hit = Some(hit(invocation));
+            true
+        });
+        if stopped {
+            notify();
+        }
+    })));
+}
+
+pub fn set_breakpoint(path: PathBuf, offset: u32, enabled: bool) {
+    DEBUGGER.with(|debugger| {
+        let mut debugger = debugger.borrow_mut();
+        if enabled {
+            debugger.breakpoints.insert((path, offset));
+        } else {
+            debugger.breakpoints.remove(&(path, offset));
+        }
+    });
+}
+
+pub fn has_breakpoint(handler: &Handler) -> bool {
+    DEBUGGER.with(|debugger| {
+        debugger.borrow().breakpoints.contains(&(handler.path.clone(), handler.offset))
+    })
+}
+
+/// Drop the breakpoints of `path` that are not on one of its `handlers` anymore
+pub fn retain(path: &std::path::Path, handlers: &[Handler]) {
+    DEBUGGER.with(|debugger| {
+        debugger
+            .borrow_mut()
+            .breakpoints
+            .retain(|(p, offset)| p != path || handlers.iter().any(|h| h.offset == *offset))
+    });
+}
+
+/// The handler invocation the preview is stopped at
+pub fn stopped() -> Option<Hit> {
+    DEBUGGER.with(|debugger| debugger.borrow().hit.clone())
+}
+
+/// Continue, and stop at the next handler that gets invoked
+pub fn step() {
+    DEBUGGER.with(|debugger| {
+        let mut debugger = debugger.borrow_mut();
+        debugger.step = true;
+        debugger.hit = None;
+    });
+    notify();
+}
+
+/// Continue until a breakpoint gets hit again
+pub fn resume() {
+    DEBUGGER.with(|debugger| {
+        let mut debugger = debugger.borrow_mut();
+        debugger.step = false;
+        debugger.hit = None;
+    });
+    notify();
+}
+
+/// Continue, as the component the preview stopped in was replaced
+pub fn forget_stop() {
+    let was_stopped = DEBUGGER.with(|debugger| {
+        let mut debugger = debugger.borrow_mut();
+        debugger.step = false;
+        debugger.hit.take().is_some()
+    });
+    if was_stopped {
+        notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    const SOURCE: &str = r#"
+export component Main {
+    in-out property <int> count;
+    callback add(int);
+    add(value) => {
+        count += value;
+    }
+    button := TouchArea {
+        clicked => {
+            root.add(2);
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_handlers() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let document = dc.get_document(&url).unwrap().node.clone().unwrap();
+        let handlers = handlers(&document);
+        assert_eq!(handlers.len(), 2);
+        assert_eq!((handlers[0].element.as_str(), handlers[0].callback.as_str()), ("Main", "add"));
+        assert_eq!(handlers[0].line, 5);
+        assert_eq!(
+            (handlers[1].element.as_str(), handlers[1].callback.as_str()),
+            ("button", "clicked")
+        );
+    }
+
+    #[test]
+    fn test_breakpoint() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let document = dc.get_document(&url).unwrap().node.clone().unwrap();
+        let handlers = handlers(&document);
+
+        install(|| {});
+        let instance = crate::preview::test::interpret_test("fluent", SOURCE);
+        // The test interprets the source under another path
+        let path = crate::common::test::main_test_file_name();
+        for handler in &handlers {
+            set_breakpoint(path.clone(), handler.offset, false);
+        }
+        forget_stop();
+        set_breakpoint(path.clone(), handlers[0].offset, true);
+
+        let count = || instance.get_property("count").unwrap();
+        instance.invoke("add", &[slint_interpreter::Value::Number(3.)]).unwrap();
+        // The handler ran, and the preview stopped after it
+        assert_eq!(count(), slint_interpreter::Value::Number(3.));
+        let hit = stopped().unwrap();
+        assert_eq!(hit.callback, "add");
+        assert_eq!(hit.arguments, vec!["3".to_string()]);
+        assert_eq!(hit.location.map(|(_, line, _)| line), Some(handlers[0].line));
+
+        // Only the first hit is kept until continuing
+        instance.invoke("add", &[slint_interpreter::Value::Number(2.)]).unwrap();
+        assert_eq!(count(), slint_interpreter::Value::Number(5.));
+        assert_eq!(stopped().unwrap().arguments, vec!["3".to_string()]);
+
+        resume();
+        assert!(stopped().is_none());
+        set_breakpoint(path, handlers[0].offset, false);
+        instance.invoke("add", &[slint_interpreter::Value::Number(1.)]).unwrap();
+        assert_eq!(count(), slint_interpreter::Value::Number(6.));
+        assert!(stopped().is_none());
+
+        step();
+        instance.invoke("add", &[slint_interpreter::Value::Number(1.)]).unwrap();
+        assert_eq!(stopped().unwrap().arguments, vec!["1".to_string()]);
+        resume();
+        slint_interpreter::set_callback_hook(None);
+    }
+}
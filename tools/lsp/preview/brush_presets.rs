@@ -0,0 +1,333 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Named brushes, solid colors as well as gradients, kept as properties of the [`GLOBAL_NAME`]
+//! global in the [`FILE_NAME`] file at the root of the project. Properties get bound to a
+//! preset, and existing brush literals can be replaced with references to one.
+
+use i_slint_compiler::diagnostics::BuildDiagnostics;
+use i_slint_compiler::literals;
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, TextRange};
+use lsp_types::Url;
+
+use crate::common;
+use crate::language::completion;
+use crate::util;
+
+/// The name of the palette file in the project directory
+pub const FILE_NAME: &str = "palette.slint";
+/// The name of the global holding the presets
+pub const GLOBAL_NAME: &str = "Brushes";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    /// The brush as Slint expression
+    pub expression: String,
+}
+
+impl Preset {
+    pub fn reference(&self) -> String {
+        format!("{GLOBAL_NAME}.{}", self.name)
+    }
+}
+
+/// The palette file of a project
+#[derive(Clone, Debug, Default)]
+pub struct Palette {
+    pub url: Option<Url>,
+    /// The parsed file, `None` if it does not exist yet
+    document: Option<syntax_nodes::Document>,
+    pub presets: Vec<Preset>,
+}
+
+/// Whether `name` can be used as the name of a preset
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The palette file of the project `document` belongs to
+pub fn palette_url(document: &Url) -> Option<Url> {
+    let path = document.to_file_path().ok()?;
+    Url::from_file_path(super::project_settings::project_of(&path).join(FILE_NAME)).ok()
+}
+
+fn global(document: &syntax_nodes::Document) -> Option<syntax_nodes::Component> {
+    document.ExportsList().filter_map(|e| e.Component()).find(|c| {
+        c.child_text(SyntaxKind::Identifier).as_deref() == Some("global")
+            && c.DeclaredIdentifier().text().to_string().trim() == GLOBAL_NAME
+    })
+}
+
+/// The `brush` and `color` properties of the global, with their value
+fn preset_declarations(
+    global: &syntax_nodes::Component,
+) -> Vec<(Preset, syntax_nodes::Expression)> {
+    global
+        .Element()
+        .PropertyDeclaration()
+        .filter(|d| {
+            d.Type().is_some_and(|t| matches!(t.text().to_string().trim(), "brush" | "color"))
+        })
+        .filter_map(|declaration| {
+            let expression = declaration.BindingExpression()?.Expression()?;
+            let name = declaration.DeclaredIdentifier().text().to_string().trim().to_string();
+            let preset = Preset { name, expression: expression.text().to_string().trim().into() };
+            Some((preset, expression))
+        })
+        .collect()
+}
+
+/// Load the palette file at `url`, from the document cache if it is loaded there
+pub fn load(document_cache: &common::DocumentCache, url: Url) -> Palette {
+    let document = match document_cache.get_document(&url) {
+        Some(document) => document.node.clone(),
+        None => url.to_file_path().ok().and_then(|path| {
+            let source = std::fs::read_to_string(&path).ok()?;
+            let mut diag = BuildDiagnostics::default();
+            let node = i_slint_compiler::parser::parse(source, Some(&path), &mut diag);
+            syntax_nodes::Document::new(node)
+        }),
+    };
+    let presets = document
+        .as_ref()
+        .and_then(global)
+        .map(|g| preset_declarations(&g).into_iter().map(|(p, _)| p).collect())
+        .unwrap_or_default();
+    Palette { url: Some(url), document, presets }
+}
+
+/// Save `expression` as preset called `name`, replacing the value of an existing one
+pub fn save_preset(
+    document_cache: &common::DocumentCache,
+    palette: &Palette,
+    name: &str,
+    expression: &str,
+) -> Option<common::WorkspaceEditBuilder> {
+    if !is_valid_name(name) || expression.trim().is_empty() {
+        return None;
+    }
+    let url = palette.url.clone()?;
+    let declaration = format!("out property <brush> {name}: {expression};");
+    let new_global = format!("export global {GLOBAL_NAME} {{\n    {declaration}\n}}\n");
+
+    let mut builder = common::WorkspaceEditBuilder::new("Save brush preset");
+    let Some(document) = &palette.document else {
+        builder.create_file(url, new_global);
+        return Some(builder);
+    };
+    let version = document_cache.document_version(&url);
+    let edit = match global(document) {
+        Some(global) => {
+            match preset_declarations(&global).into_iter().find(|(p, _)| p.name == name) {
+                Some((_, value)) => lsp_types::TextEdit {
+                    range: util::node_to_lsp_range(&value),
+                    new_text: expression.to_string(),
+                },
+                None => {
+                    let close_brace = global.Element().child_token(SyntaxKind::RBrace)?;
+                    lsp_types::TextEdit {
+                        range: util::text_range_to_lsp_range(
+                            &global.source_file,
+                            TextRange::empty(close_brace.text_range().start()),
+                        ),
+                        new_text: format!("    {declaration}\n"),
+                    }
+                }
+            }
+        }
+        None => lsp_types::TextEdit {
+            range: util::text_range_to_lsp_range(
+                &document.source_file,
+                TextRange::empty(document.text_range().end()),
+            ),
+            new_text: format!("\n{new_global}"),
+        },
+    };
+    builder.add_text_edit(url, version, edit);
+    Some(builder)
+}
+
+/// Import the global into the document at `url`, unless that is the palette itself
+fn add_import(
+    document_cache: &common::DocumentCache,
+    builder: &mut common::WorkspaceEditBuilder,
+    palette_url: &Url,
+    url: &Url,
+) -> Option<()> {
+    if url == palette_url {
+        return Some(());
+    }
+    let document = document_cache.get_document(url)?;
+    let import_file = url.make_relative(palette_url);
+    if let Some(import) = completion::create_import_edit(document, GLOBAL_NAME, &import_file) {
+        builder.add_text_edit(url.clone(), document_cache.document_version(url), import);
+    }
+    Some(())
+}
+
+/// Bind `property` of `element` to `preset`
+pub fn apply_preset(
+    document_cache: &common::DocumentCache,
+    palette: &Palette,
+    preset: &Preset,
+    element: &common::ElementRcNode,
+    property: &str,
+) -> Option<common::WorkspaceEditBuilder> {
+    let palette_url = palette.url.as_ref()?;
+    let url = Url::from_file_path(element.path_and_offset().0).ok()?;
+    let version = document_cache.document_version(&url);
+    let edit = super::properties::set_binding(
+        url.clone(),
+        version,
+        element,
+        property,
+        preset.reference(),
+    )?;
+
+    let mut builder = common::WorkspaceEditBuilder::new("Use brush preset");
+    builder.add_workspace_edit(edit);
+    add_import(document_cache, &mut builder, palette_url, &url)?;
+    Some(builder)
+}
+
+/// The text of a brush expression without the parts that do not change its meaning
+fn normalized(expression: &str) -> String {
+    expression.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase()
+}
+
+/// Replace the brush literals of the project that are equal to `preset` with references to it
+pub fn replace_literals(
+    document_cache: &common::DocumentCache,
+    palette: &Palette,
+    preset: &Preset,
+) -> Option<common::WorkspaceEditBuilder> {
+    let palette_url = palette.url.as_ref()?;
+    let color = literals::parse_color_literal(&preset.expression);
+    let gradient = normalized(&preset.expression);
+
+    let mut builder = common::WorkspaceEditBuilder::new("Replace brushes with preset");
+    for (url, document) in document_cache.all_url_documents() {
+        if url.scheme() == "builtin" || &url == palette_url {
+            continue;
+        }
+        let version = document_cache.document_version(&url);
+        let mut replaced = false;
+        for element in document.node.descendants_with_tokens() {
+            let matches = if let Some(token) = element.as_token() {
+                token.kind() == SyntaxKind::ColorLiteral
+                    && color.is_some()
+                    && literals::parse_color_literal(token.text()) == color
+            } else {
+                element.kind() == SyntaxKind::AtGradient
+                    && color.is_none()
+                    && normalized(&element.to_string()) == gradient
+            };
+            if !matches {
+                continue;
+            }
+            let range = util::text_range_to_lsp_range(&document.source_file, element.text_range());
+            builder.add_text_edit(
+                url.clone(),
+                version,
+                lsp_types::TextEdit { range, new_text: preset.reference() },
+            );
+            replaced = true;
+        }
+        if replaced {
+            add_import(document_cache, &mut builder, palette_url, &url)?;
+        }
+    }
+    (!builder.is_empty()).then_some(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    const SOURCE: &str = r#"
+export global Brushes {
+    out property <brush> accent: #3884ed;
+    out property <brush> sunset: @linear-gradient(90deg, #f00 0%, #ff0 100%);
+    out property <length> radius: 4px;
+}
+
+export component Main {
+    Rectangle { background: #3884ED; }
+    Rectangle { background: @linear-gradient(90deg,#f00 0%,  #ff0 100%); }
+    Rectangle { background: @linear-gradient(0deg, #f00 0%, #ff0 100%); }
+}
+"#;
+
+    fn apply(dc: &common::DocumentCache, builder: common::WorkspaceEditBuilder) -> String {
+        let (_, edit) = builder.build().unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(dc, &edit).unwrap();
+        result.first().unwrap().contents.clone()
+    }
+
+    #[test]
+    fn test_load() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let palette = load(&dc, url);
+        assert_eq!(
+            palette.presets,
+            vec![
+                Preset { name: "accent".into(), expression: "#3884ed".into() },
+                Preset {
+                    name: "sunset".into(),
+                    expression: "@linear-gradient(90deg, #f00 0%, #ff0 100%)".into()
+                },
+            ]
+        );
+        assert_eq!(palette.presets[1].reference(), "Brushes.sunset");
+    }
+
+    #[test]
+    fn test_save_preset() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let palette = load(&dc, url);
+        let result = apply(&dc, save_preset(&dc, &palette, "accent", "#ff0000").unwrap());
+        assert!(result.contains("out property <brush> accent: #ff0000;"));
+
+        let result = apply(&dc, save_preset(&dc, &palette, "muted", "#888").unwrap());
+        assert!(result.contains("    out property <brush> muted: #888;\n}"));
+        assert!(save_preset(&dc, &palette, "not valid", "#888").is_none());
+    }
+
+    #[test]
+    fn test_replace_literals() {
+        let source = r#"
+export component Main {
+    Rectangle { background: #3884ED; }
+    Rectangle { background: @linear-gradient(90deg,#f00 0%,  #ff0 100%); }
+    Rectangle { background: @linear-gradient(0deg, #f00 0%, #ff0 100%); }
+}
+"#;
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        let palette = Palette {
+            url: Some(url.join(FILE_NAME).unwrap()),
+            document: None,
+            presets: load(&dc, url).presets,
+        };
+        let (dc_with_palette, url, _) = loaded_document_cache(SOURCE.to_string());
+        let presets = load(&dc_with_palette, url).presets;
+
+        let result = apply(&dc, replace_literals(&dc, &palette, &presets[1]).unwrap());
+        assert!(result.contains("background: Brushes.sunset;"));
+        assert!(result.contains("@linear-gradient(0deg, #f00 0%, #ff0 100%)"));
+        assert!(result.contains("import { Brushes } from \"palette.slint\";"));
+
+        let result = apply(&dc, replace_literals(&dc, &palette, &presets[0]).unwrap());
+        assert!(result.contains("background: Brushes.accent;"));
+        assert!(replace_literals(
+            &dc,
+            &palette,
+            &Preset { name: "x".into(), expression: "#123".into() }
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Bundle what the preview shows into one archive, so that others can reproduce it: The sources
+//! of the previewed component and everything it imports, the values of its properties, the
+//! fixtures, the preview settings, the diagnostics and a screenshot. A manifest describes the
+//! rest of the archive. The archive is an uncompressed tar file, which every platform can open.
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+/// The name of the manifest in the archive
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Manifest {
+    /// The version of the language server that wrote the report
+    pub version: String,
+    pub os: String,
+    /// The file of the previewed component in the archive, and its name
+    pub component: Option<(String, String)>,
+    pub style: String,
+    /// Where the include and library paths of the configuration are in the archive
+    pub include_paths: Vec<String>,
+    pub library_paths: BTreeMap<String, String>,
+    pub diagnostics: Vec<String>,
+    /// Why the screenshot is missing, if it is
+    pub screenshot_error: Option<String>,
+    /// All files in the archive, except for the manifest
+    pub files: Vec<String>,
+}
+
+/// A bug report, as it is being put together
+#[derive(Default)]
+pub struct Report {
+    pub manifest: Manifest,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        let manifest = Manifest {
+            version: env!("CARGO_PKG_VERSION").into(),
+            os: std::env::consts::OS.into(),
+            ..Default::default()
+        };
+        Self { manifest, files: Vec::new() }
+    }
+
+    pub fn add_file(&mut self, name: String, data: Vec<u8>) {
+        self.manifest.files.push(name.clone());
+        self.files.push((name, data));
+    }
+
+    /// Add a JSON file, named `name`, containing `value`
+    pub fn add_json(&mut self, name: &str, value: &impl serde::Serialize) -> Result<(), String> {
+        let data = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+        self.add_file(name.into(), data);
+        Ok(())
+    }
+
+    /// The uncompressed tar archive of the report, starting with the manifest
+    pub fn to_tar(&self) -> Result<Vec<u8>, String> {
+        let manifest = serde_json::to_vec_pretty(&self.manifest).map_err(|e| e.to_string())?;
+        let mut tar = Vec::new();
+        append_to_tar(&mut tar, MANIFEST_FILE, &manifest)?;
+        for (name, data) in &self.files {
+            append_to_tar(&mut tar, name, data)?;
+        }
+        // The end of the archive is marked by two empty blocks
+        tar.resize(tar.len() + 2 * BLOCK_SIZE, 0);
+        Ok(tar)
+    }
+}
+
+/// The name of the file at `path` in the archive: Relative to `root` below `sources/`, or with
+/// its full path below `external/` if it is not in `root`
+pub fn archive_path(root: &Path, path: &Path) -> String {
+    let (prefix, relative) = match path.strip_prefix(root) {
+        Ok(relative) => ("sources", relative),
+        Err(_) => ("external", path),
+    };
+    let components = relative.components().filter_map(|c| match c {
+        Component::Normal(c) => Some(c.to_string_lossy()),
+        _ => None,
+    });
+    std::iter::once(prefix.into()).chain(components).collect::<Vec<_>>().join("/")
+}
+
+const BLOCK_SIZE: usize = 512;
+
+/// Write `value` as octal number with leading zeros into `field`, ending with a NUL byte
+fn write_octal(field: &mut [u8], value: u64) -> Result<(), String> {
+    let digits = format!("{value:0width$o}", width = field.len() - 1);
+    if digits.len() >= field.len() {
+        return Err(format!("{value} does not fit into a tar header"));
+    }
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    Ok(())
+}
+
+fn append_to_tar(tar: &mut Vec<u8>, name: &str, data: &[u8]) -> Result<(), String> {
+    let mut header = [0_u8; BLOCK_SIZE];
+    // Names longer than 100 bytes get split into a prefix and a name at a slash
+    let (prefix, name) = if name.len() <= 100 {
+        ("", name)
+    } else {
+        name.char_indices()
+            .filter(|(i, c)| *c == '/' && *i <= 155 && name.len() - i - 1 <= 100)
+            .map(|(i, _)| (&name[..i], &name[i + 1..]))
+            .next()
+            .ok_or_else(|| format!("The path {name} is too long for the archive"))?
+    };
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], 0o644)?;
+    write_octal(&mut header[108..116], 0)?;
+    write_octal(&mut header[116..124], 0)?;
+    write_octal(&mut header[124..136], data.len() as u64)?;
+    let mtime = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    write_octal(&mut header[136..148], mtime)?;
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+    // The checksum is computed with the checksum field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum = header.iter().map(|b| *b as u64).sum::<u64>();
+    write_octal(&mut header[148..155], checksum)?;
+
+    tar.extend_from_slice(&header);
+    tar.extend_from_slice(data);
+    tar.resize(tar.len().next_multiple_of(BLOCK_SIZE), 0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn octal(field: &[u8]) -> u64 {
+        let digits = std::str::from_utf8(field).unwrap().trim_end_matches(['\0', ' ']);
+        u64::from_str_radix(digits, 8).unwrap()
+    }
+
+    /// The names and contents of the files in `tar`
+    fn read_tar(tar: &[u8]) -> Vec<(String, Vec<u8>)> {
+        assert_eq!(tar.len() % BLOCK_SIZE, 0);
+        let mut files = Vec::new();
+        let mut rest = tar;
+        while rest[..BLOCK_SIZE].iter().any(|b| *b != 0) {
+            let header = &rest[..BLOCK_SIZE];
+            let mut unchecked = header.to_vec();
+            unchecked[148..156].fill(b' ');
+            assert_eq!(octal(&header[148..156]), unchecked.iter().map(|b| *b as u64).sum::<u64>());
+            assert_eq!(&header[257..263], b"ustar\0");
+
+            let text = |field: &[u8]| {
+                String::from_utf8(field.iter().copied().take_while(|b| *b != 0).collect()).unwrap()
+            };
+            let (prefix, name) = (text(&header[345..500]), text(&header[..100]));
+            let name = if prefix.is_empty() { name } else { format!("{prefix}/{name}") };
+            let size = octal(&header[124..136]) as usize;
+            files.push((name, rest[BLOCK_SIZE..BLOCK_SIZE + size].to_vec()));
+            rest = &rest[BLOCK_SIZE + size.next_multiple_of(BLOCK_SIZE)..];
+        }
+        assert_eq!(rest, &[0; 2 * BLOCK_SIZE][..]);
+        files
+    }
+
+    #[test]
+    fn test_tar() {
+        let mut report = Report::new();
+        report.manifest.component = Some(("sources/ui/main.slint".into(), "Main".into()));
+        report.add_file("sources/ui/main.slint".into(), b"export component Main {}".to_vec());
+        let long_name = format!("external/{}/{}.slint", "directory".repeat(12), "file".repeat(20));
+        report.add_file(long_name.clone(), vec![7; 1000]);
+        report.add_json("overrides.json", &BTreeMap::from([("width", 100)])).unwrap();
+
+        let files = read_tar(&report.to_tar().unwrap());
+        assert_eq!(
+            files.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+            [MANIFEST_FILE, "sources/ui/main.slint", long_name.as_str(), "overrides.json"]
+        );
+        let manifest: Manifest = serde_json::from_slice(&files[0].1).unwrap();
+        assert_eq!(manifest, report.manifest);
+        assert_eq!(manifest.files.len(), 3);
+        assert_eq!(files[1].1, b"export component Main {}");
+        assert_eq!(files[2].1, vec![7; 1000]);
+
+        let mut report = Report::new();
+        report.add_file("x/".repeat(200), Vec::new());
+        assert!(report.to_tar().is_err());
+    }
+
+    #[test]
+    fn test_archive_path() {
+        let root = std::env::temp_dir().join("project");
+        assert_eq!(
+            archive_path(&root, &root.join("ui").join("main.slint")),
+            "sources/ui/main.slint"
+        );
+        let outside = archive_path(&root, &std::env::temp_dir().join("library").join("lib.slint"));
+        assert!(outside.starts_with("external/"));
+        assert!(outside.ends_with("/library/lib.slint"));
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! All color literals of the project grouped by value, and the palette entries they can
+//! be replaced with
+
+use std::collections::HashMap;
+
+use i_slint_compiler::literals;
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, SyntaxToken};
+use lsp_types::Url;
+
+use crate::common;
+use crate::language::completion;
+use crate::util;
+
+/// A color literal somewhere in the project
+#[derive(Clone, Debug, PartialEq)]
+pub struct Usage {
+    pub url: Url,
+    pub range: lsp_types::Range,
+}
+
+/// All color literals with the same value
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorUsages {
+    /// The color as `0xAARRGGBB`
+    pub argb: u32,
+    pub usages: Vec<Usage>,
+}
+
+impl ColorUsages {
+    pub fn count_in(&self, url: &Url) -> usize {
+        self.usages.iter().filter(|u| &u.url == url).count()
+    }
+}
+
+/// A `color` or `brush` property of an exported global, initialized with a color literal
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaletteEntry {
+    pub global: String,
+    pub property: String,
+    pub argb: u32,
+    pub url: Url,
+}
+
+impl PaletteEntry {
+    pub fn expression(&self) -> String {
+        format!("{}.{}", self.global, self.property)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Audit {
+    /// The previewed document
+    pub document: Option<Url>,
+    /// Most used colors first
+    pub colors: Vec<ColorUsages>,
+    pub palette: Vec<PaletteEntry>,
+}
+
+impl Audit {
+    /// The colors used in the previewed document or in the whole project
+    pub fn colors_in(&self, whole_project: bool) -> Vec<&ColorUsages> {
+        let url = if whole_project { None } else { self.document.as_ref() };
+        let mut colors = self
+            .colors
+            .iter()
+            .filter(|c| url.is_none_or(|url| c.count_in(url) > 0))
+            .collect::<Vec<_>>();
+        if let Some(url) = url {
+            colors.sort_by_key(|c| std::cmp::Reverse(c.count_in(url)));
+        }
+        colors
+    }
+
+    pub fn palette_entry_for(&self, argb: u32) -> Option<&PaletteEntry> {
+        self.palette.iter().find(|e| e.argb == argb)
+    }
+}
+
+/// `#rrggbb`, or `#rrggbbaa` for colors that are not opaque
+pub fn color_text(argb: u32) -> String {
+    let [a, r, g, b] = argb.to_be_bytes();
+    if a == 0xff {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+fn color_literals(node: &i_slint_compiler::parser::SyntaxNode) -> Vec<SyntaxToken> {
+    node.node
+        .descendants_with_tokens()
+        .filter_map(|t| t.into_token())
+        .filter(|t| t.kind() == SyntaxKind::ColorLiteral)
+        .map(|token| SyntaxToken { token, source_file: node.source_file.clone() })
+        .collect()
+}
+
+fn palette_entries(
+    url: &Url,
+    document: &syntax_nodes::Document,
+) -> Vec<(PaletteEntry, SyntaxToken)> {
+    let mut result = vec![];
+    for component in document.ExportsList().filter_map(|e| e.Component()) {
+        if component.child_text(SyntaxKind::Identifier).as_deref() != Some("global") {
+            continue;
+        }
+        let global = component.DeclaredIdentifier().text().to_string().trim().to_string();
+        for declaration in component.Element().PropertyDeclaration() {
+            let is_color = declaration
+                .Type()
+                .is_some_and(|t| matches!(t.text().to_string().trim(), "color" | "brush"));
+            let Some(expression) = declaration.BindingExpression() else {
+                continue;
+            };
+            let literals = color_literals(&expression);
+            let [literal] = literals.as_slice() else {
+                continue;
+            };
+            // Only plain colors, not gradients or expressions using a color
+            if !is_color
+                || expression.text().to_string().trim().trim_end_matches(';') != literal.text()
+            {
+                continue;
+            }
+            let Some(argb) = literals::parse_color_literal(literal.text()) else {
+                continue;
+            };
+            let property = declaration.DeclaredIdentifier().text().to_string().trim().to_string();
+            result.push((
+                PaletteEntry { global: global.clone(), property, argb, url: url.clone() },
+                literal.clone(),
+            ));
+        }
+    }
+    result
+}
+
+/// Collect all color literals and palette entries of the documents in the cache
+pub fn audit(document_cache: &common::DocumentCache, document: Option<Url>) -> Audit {
+    let mut audit = Audit { document, ..Default::default() };
+    let mut colors: HashMap<u32, Vec<Usage>> = HashMap::new();
+
+    for (url, document) in document_cache.all_url_documents() {
+        if url.scheme() == "builtin" {
+            continue;
+        }
+        let palette = palette_entries(&url, document);
+        for token in color_literals(document) {
+            // The definitions of the palette are not usages
+            if palette.iter().any(|(_, t)| t.text_range() == token.text_range()) {
+                continue;
+            }
+            let Some(argb) = literals::parse_color_literal(token.text()) else {
+                continue;
+            };
+            colors
+                .entry(argb)
+                .or_default()
+                .push(Usage { url: url.clone(), range: util::token_to_lsp_range(&token) });
+        }
+        audit.palette.extend(palette.into_iter().map(|(entry, _)| entry));
+    }
+
+    audit.colors =
+        colors.into_iter().map(|(argb, usages)| ColorUsages { argb, usages }).collect::<Vec<_>>();
+    audit.colors.sort_by_key(|c| (std::cmp::Reverse(c.usages.len()), c.argb));
+    audit.palette.sort_by(|a, b| (&a.global, &a.property).cmp(&(&b.global, &b.property)));
+    audit
+}
+
+/// Replace the usages of `color` in `url` (or in the whole project if `url` is `None`)
+/// with a reference to `entry`, importing the global where needed
+pub fn replace_with_palette_entry(
+    document_cache: &common::DocumentCache,
+    color: &ColorUsages,
+    entry: &PaletteEntry,
+    url: Option<&Url>,
+) -> Option<lsp_types::WorkspaceEdit> {
+    let mut edits: Vec<(Url, Vec<lsp_types::TextEdit>)> = vec![];
+    for usage in color.usages.iter().filter(|u| url.is_none_or(|url| &u.url == url)) {
+        let edit = lsp_types::TextEdit { range: usage.range, new_text: entry.expression() };
+        match edits.iter_mut().find(|(u, _)| u == &usage.url) {
+            Some((_, e)) => e.push(edit),
+            None => edits.push((usage.url.clone(), vec![edit])),
+        }
+    }
+    if edits.is_empty() {
+        return None;
+    }
+
+    let edits = edits
+        .into_iter()
+        .map(|(url, mut text_edits)| {
+            if url != entry.url {
+                let document = document_cache.get_document(&url)?;
+                let import_file = url.make_relative(&entry.url);
+                if let Some(import) =
+                    completion::create_import_edit(document, &entry.global, &import_file)
+                {
+                    text_edits.push(import);
+                }
+            }
+            let version = document_cache.document_version(&url);
+            Some(common::create_text_document_edit(url, version, text_edits))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(common::create_workspace_edit_from_text_document_edits(edits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    const SOURCE: &str = r#"
+export global Theme {
+    out property <color> accent: #3884ed;
+    out property <brush> background: #fff;
+    out property <brush> fancy: @linear-gradient(90deg, #fff 0%, #000 100%);
+}
+
+export component Main {
+    Rectangle { background: #FFFFFF; }
+    Rectangle { background: #ffff; border-color: #3884ed; }
+    Text { color: #3884edff; }
+}
+"#;
+
+    #[test]
+    fn test_audit() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let audit = audit(&dc, Some(url.clone()));
+
+        assert_eq!(
+            audit.palette.iter().map(|e| (e.expression(), e.argb)).collect::<Vec<_>>(),
+            vec![
+                ("Theme.accent".to_string(), 0xff3884ed),
+                ("Theme.background".to_string(), 0xffffffff)
+            ]
+        );
+        // Different spellings of the same color are grouped, the gradient counts too
+        assert_eq!(
+            audit
+                .colors_in(false)
+                .iter()
+                .map(|c| (color_text(c.argb), c.count_in(&url)))
+                .collect::<Vec<_>>(),
+            vec![
+                ("#ffffff".to_string(), 3),
+                ("#3884ed".to_string(), 2),
+                ("#000000".to_string(), 1)
+            ]
+        );
+        assert_eq!(audit.palette_entry_for(0xff000000), None);
+        assert_eq!(color_text(0x803884ed), "#3884ed80");
+    }
+
+    #[test]
+    fn test_replace_with_palette_entry() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let audit = audit(&dc, Some(url.clone()));
+        let color = audit.colors.iter().find(|c| c.argb == 0xff3884ed).unwrap();
+        let entry = audit.palette_entry_for(color.argb).unwrap();
+
+        let edit = replace_with_palette_entry(&dc, color, entry, Some(&url)).unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&dc, &edit).unwrap();
+        let result = &result.first().unwrap().contents;
+        assert!(result.contains("out property <color> accent: #3884ed;"));
+        assert!(result.contains("border-color: Theme.accent;"));
+        assert!(result.contains("color: Theme.accent;"));
+        assert!(!result.contains("import"));
+    }
+}
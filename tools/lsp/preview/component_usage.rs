@@ -0,0 +1,106 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! How often and how recently the components of the library got dropped into the
+//! preview, remembered per project across sessions in the project settings
+
+use std::collections::HashMap;
+
+use crate::common::ComponentInformation;
+
+/// How many components the "Favorites" and "Recently used" categories show at most
+const SHOWN_COMPONENTS: usize = 5;
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Usage {
+    count: u32,
+    /// Larger for components used more recently
+    last_used: u64,
+}
+
+/// The component usage of one project
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ProjectUsage {
+    components: HashMap<String, Usage>,
+}
+
+fn key(component: &ComponentInformation) -> String {
+    match &component.defined_at {
+        Some(position) => format!("{}@{}", component.name, position.url()),
+        None => component.name.clone(),
+    }
+}
+
+impl ProjectUsage {
+    pub fn record(&mut self, component: &ComponentInformation) {
+        let last_used = self.components.values().map(|u| u.last_used).max().unwrap_or_default();
+        let usage = self.components.entry(key(component)).or_default();
+        usage.count += 1;
+        usage.last_used = last_used + 1;
+    }
+
+    /// The indices into `known_components` of the used components, ordered by `sort_key`
+    fn used<K: Ord>(
+        &self,
+        known_components: &[ComponentInformation],
+        sort_key: impl Fn(&Usage) -> K,
+    ) -> Vec<usize> {
+        let mut used = known_components
+            .iter()
+            .enumerate()
+            .filter_map(|(index, ci)| Some((self.components.get(&key(ci))?, index)))
+            .collect::<Vec<_>>();
+        used.sort_by_key(|(usage, _)| std::cmp::Reverse(sort_key(usage)));
+        used.into_iter().take(SHOWN_COMPONENTS).map(|(_, index)| index).collect()
+    }
+
+    /// The components dropped most often, that were dropped more than once
+    pub fn favorites(&self, known_components: &[ComponentInformation]) -> Vec<usize> {
+        self.used(known_components, |u| (u.count, u.last_used))
+            .into_iter()
+            .filter(|index| self.components[&key(&known_components[*index])].count > 1)
+            .collect()
+    }
+
+    /// The components dropped most recently
+    pub fn recently_used(&self, known_components: &[ComponentInformation]) -> Vec<usize> {
+        self.used(known_components, |u| u.last_used)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(name: &str) -> ComponentInformation {
+        crate::common::test::test_component(name, "Basics")
+    }
+
+    #[test]
+    fn test_favorites_and_recently_used() {
+        let known_components = ["Rectangle", "Text", "Image", "TouchArea"]
+            .map(component)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut usage = ProjectUsage::default();
+        assert!(usage.favorites(&known_components).is_empty());
+        assert!(usage.recently_used(&known_components).is_empty());
+
+        for name in ["Text", "Image", "Text", "Rectangle", "Image", "Text", "Rectangle"] {
+            usage.record(&component(name));
+        }
+        // Something no longer known is skipped
+        usage.record(&component("Gone"));
+
+        assert_eq!(usage.favorites(&known_components), vec![1, 0, 2]);
+        assert_eq!(usage.recently_used(&known_components), vec![0, 1, 2]);
+
+        usage.record(&component("TouchArea"));
+        assert_eq!(usage.favorites(&known_components), vec![1, 0, 2]);
+        assert_eq!(usage.recently_used(&known_components), vec![3, 0, 1, 2]);
+
+        // Survives a round trip through the serialization
+        let data = serde_json::to_vec(&usage).unwrap();
+        assert_eq!(serde_json::from_slice::<ProjectUsage>(&data).unwrap(), usage);
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Supervision of the transport to the LSP: A heartbeat finds out when the LSP stops answering
+//! or can not be reached anymore. Until it is back, the messages for it are buffered, and then
+//! sent in order, after asking the LSP for its state again. The state of the preview is kept
+//! meanwhile.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use i_slint_core::animations::Instant;
+
+use crate::common::PreviewToLspMessage;
+
+/// How long the LSP may stay silent before it gets pinged
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(2);
+/// How long the LSP may take to answer a ping before it counts as unresponsive
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(6);
+/// The number of messages besides edits buffered at most, older ones get dropped. Edits are
+/// never dropped, the changes of the user would get lost.
+const MAX_BUFFERED_MESSAGES: usize = 500;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Health {
+    #[default]
+    Connected,
+    /// The LSP did not answer the last ping in time
+    Unresponsive,
+    /// Messages can not be sent to the LSP
+    Disconnected,
+}
+
+#[derive(Default)]
+struct Connection {
+    health: Health,
+    last_ping_id: u32,
+    /// The ping waiting for its answer, and when it was sent
+    pending_ping: Option<(u32, Instant)>,
+    last_heard: Option<Instant>,
+    /// The messages waiting for the LSP to come back, oldest first
+    outbox: VecDeque<PreviewToLspMessage>,
+}
+
+impl Connection {
+    fn buffer(&mut self, message: PreviewToLspMessage) {
+        use PreviewToLspMessage as M;
+
+        // Only the latest of these matters
+        let replaces = |old: &PreviewToLspMessage| match (old, &message) {
+            (M::Diagnostics { uri: a, .. }, M::Diagnostics { uri: b, .. }) => a == b,
+            (M::ShowDocument { .. }, M::ShowDocument { .. })
+            | (M::PreviewTypeChanged { .. }, M::PreviewTypeChanged { .. }) => true,
+            _ => false,
+        };
+        self.outbox.retain(|old| !replaces(old));
+
+        // These get sent anew once the LSP is back
+        if matches!(message, M::RequestState { .. } | M::Ping { .. }) {
+            return;
+        }
+        let is_edit = |m: &PreviewToLspMessage| matches!(m, M::SendWorkspaceEdit { .. });
+        if !is_edit(&message)
+            && self.outbox.iter().filter(|m| !is_edit(m)).count() == MAX_BUFFERED_MESSAGES
+        {
+            if let Some(oldest) = self.outbox.iter().position(|m| !is_edit(m)) {
+                self.outbox.remove(oldest);
+            }
+        }
+        self.outbox.push_back(message);
+    }
+
+    /// Send `message` with `deliver`, or buffer it while the LSP can not take it. `deliver`
+    /// returns whether the message got sent.
+    fn send(
+        &mut self,
+        message: PreviewToLspMessage,
+        deliver: &dyn Fn(&PreviewToLspMessage) -> bool,
+    ) {
+        if self.health != Health::Connected {
+            self.buffer(message);
+        } else if !deliver(&message) {
+            self.health = Health::Disconnected;
+            self.buffer(message);
+        }
+    }
+
+    /// Send the buffered messages in order, stopping at the first one that fails
+    fn flush(&mut self, deliver: &dyn Fn(&PreviewToLspMessage) -> bool) {
+        while let Some(message) = self.outbox.front() {
+            if !deliver(message) {
+                self.health = Health::Disconnected;
+                return;
+            }
+            self.outbox.pop_front();
+        }
+    }
+
+    /// Any message from the LSP shows that it is alive. `pong` is the id of an answered ping.
+    /// Returns whether the LSP was not considered connected before.
+    fn message_received(&mut self, now: Instant, pong: Option<u32>) -> bool {
+        self.last_heard = Some(now);
+        if pong.is_some() && self.pending_ping.map(|(id, _)| id) == pong {
+            self.pending_ping = None;
+        }
+        std::mem::replace(&mut self.health, Health::Connected) != Health::Connected
+    }
+
+    /// Ping the LSP when it was silent for a while, and notice when it does not answer
+    fn tick(&mut self, now: Instant, deliver: &dyn Fn(&PreviewToLspMessage) -> bool) {
+        if let Some((_, sent)) = self.pending_ping {
+            if now - sent < RESPONSE_TIMEOUT {
+                return;
+            }
+            let heard_recently = self.last_heard.is_some_and(|h| now - h < RESPONSE_TIMEOUT);
+            if self.health == Health::Connected && !heard_recently {
+                self.health = Health::Unresponsive;
+            }
+            // The ping might have been lost, ping again
+            self.pending_ping = None;
+        } else if self.last_heard.is_some_and(|h| now - h < HEARTBEAT_INTERVAL) {
+            return;
+        }
+
+        self.last_ping_id = self.last_ping_id.wrapping_add(1);
+        if deliver(&PreviewToLspMessage::Ping { id: self.last_ping_id }) {
+            self.pending_ping = Some((self.last_ping_id, now));
+        } else {
+            self.health = Health::Disconnected;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Supervisor {
+    connection: Connection,
+    heartbeat: Option<slint::Timer>,
+    changed: Option<fn()>,
+}
+
+thread_local! {static SUPERVISOR: RefCell<Supervisor> = RefCell::default();}
+/// The thread the supervisor got installed in, the UI thread
+static SUPERVISOR_THREAD: std::sync::OnceLock<std::thread::ThreadId> = std::sync::OnceLock::new();
+
+fn with_connection(f: impl FnOnce(&mut Connection)) {
+    let (changed, before, after) = SUPERVISOR.with(|s| {
+        let mut s = s.borrow_mut();
+        let before = (s.connection.health, s.connection.outbox.len());
+        f(&mut s.connection);
+        (s.changed, before, (s.connection.health, s.connection.outbox.len()))
+    });
+    if before != after {
+        if let Some(changed) = changed {
+            // Messages get sent while the preview state is borrowed, so do not touch the UI
+            // right away
+            slint::Timer::single_shot(Duration::ZERO, changed);
+        }
+    }
+}
+
+/// Start the heartbeat. `changed` gets called when the health of the connection or the number
+/// of buffered messages changes.
+pub fn install(changed: fn()) {
+    let _ = SUPERVISOR_THREAD.set(std::thread::current().id());
+    SUPERVISOR.with(|s| {
+        let mut s = s.borrow_mut();
+        s.changed = Some(changed);
+        // The UI might get created again, keep the running heartbeat then
+        s.heartbeat.get_or_insert_with(|| {
+            let timer = slint::Timer::default();
+            timer.start(slint::TimerMode::Repeated, HEARTBEAT_INTERVAL, || {
+                with_connection(|c| c.tick(Instant::now(), &super::deliver_message_to_lsp))
+            });
+            timer
+        });
+    });
+}
+
+/// Send `message` to the LSP, or buffer it until the LSP is back
+pub fn send(message: PreviewToLspMessage) {
+    if SUPERVISOR_THREAD.get().is_some_and(|thread| *thread != std::thread::current().id()) {
+        // The connection is kept in the UI thread, sending from elsewhere would bypass it
+        let _ = i_slint_core::api::invoke_from_event_loop(move || send(message));
+        return;
+    }
+    with_connection(|c| c.send(message, &super::deliver_message_to_lsp))
+}
+
+/// Note that a message from the LSP arrived, `pong` is the id of the ping it answers
+pub fn message_received(pong: Option<u32>) {
+    with_connection(|c| {
+        if c.message_received(Instant::now(), pong) {
+            resynchronize(c);
+        }
+    })
+}
+
+/// The LSP is back: Get its state again and send what it missed
+fn resynchronize(connection: &mut Connection) {
+    let request_state = PreviewToLspMessage::RequestState { unused: true };
+    if super::deliver_message_to_lsp(&request_state) {
+        connection.flush(&super::deliver_message_to_lsp);
+    } else {
+        connection.health = Health::Disconnected;
+    }
+}
+
+/// Try to reach the LSP again right away, instead of waiting for the next heartbeat
+pub fn reconnect() {
+    with_connection(|c| {
+        c.pending_ping = None;
+        c.last_heard = None;
+        c.tick(Instant::now(), &super::deliver_message_to_lsp);
+    })
+}
+
+pub fn health() -> Health {
+    SUPERVISOR.with(|s| s.borrow().connection.health)
+}
+
+/// The number of messages waiting for the LSP to come back
+pub fn buffered_messages() -> usize {
+    SUPERVISOR.with(|s| s.borrow().connection.outbox.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    fn edit(label: &str) -> PreviewToLspMessage {
+        PreviewToLspMessage::SendWorkspaceEdit {
+            label: Some(label.into()),
+            edit: Default::default(),
+        }
+    }
+
+    fn show_document(line: u32) -> PreviewToLspMessage {
+        let position = lsp_types::Position::new(line, 0);
+        PreviewToLspMessage::ShowDocument {
+            file: lsp_types::Url::parse("file:///main.slint").unwrap(),
+            selection: lsp_types::Range::new(position, position),
+            take_focus: false,
+        }
+    }
+
+    fn labels(connection: &Connection) -> Vec<String> {
+        connection
+            .outbox
+            .iter()
+            .map(|m| match m {
+                PreviewToLspMessage::SendWorkspaceEdit { label, .. } => label.clone().unwrap(),
+                PreviewToLspMessage::ShowDocument { selection, .. } => {
+                    format!("show {}", selection.start.line)
+                }
+                m => format!("{m:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_buffer_while_disconnected() {
+        let mut connection = Connection::default();
+        let sent = RefCell::new(Vec::new());
+        let online = Cell::new(true);
+        let deliver = |m: &PreviewToLspMessage| {
+            if online.get() {
+                sent.borrow_mut().push(m.clone());
+            }
+            online.get()
+        };
+
+        connection.send(edit("a"), &deliver);
+        assert_eq!(connection.health, Health::Connected);
+        assert_eq!(sent.borrow().len(), 1);
+
+        online.set(false);
+        connection.send(edit("b"), &deliver);
+        assert_eq!(connection.health, Health::Disconnected);
+        connection.send(show_document(1), &deliver);
+        connection.send(edit("c"), &deliver);
+        // Only the last selection is of interest
+        connection.send(show_document(2), &deliver);
+        connection.send(PreviewToLspMessage::RequestState { unused: true }, &deliver);
+        assert_eq!(labels(&connection), ["b", "c", "show 2"]);
+
+        online.set(true);
+        assert!(connection.message_received(Instant(0), None));
+        connection.flush(&deliver);
+        assert!(connection.outbox.is_empty());
+        assert_eq!(sent.borrow().len(), 4);
+        assert!(!connection.message_received(Instant(1), None));
+    }
+
+    #[test]
+    fn test_never_drop_edits() {
+        let mut connection = Connection::default();
+        let deliver = |_: &PreviewToLspMessage| false;
+        connection.send(edit("first"), &deliver);
+        for index in 0..MAX_BUFFERED_MESSAGES {
+            let diagnostics = PreviewToLspMessage::Diagnostics {
+                uri: lsp_types::Url::parse(&format!("file:///{index}.slint")).unwrap(),
+                version: None,
+                diagnostics: Vec::new(),
+            };
+            connection.send(diagnostics, &deliver);
+        }
+        connection.send(edit("second"), &deliver);
+        assert_eq!(connection.outbox.len(), MAX_BUFFERED_MESSAGES + 2);
+
+        // The oldest message that is no edit makes room
+        connection.send(show_document(1), &deliver);
+        let labels = labels(&connection);
+        assert_eq!(labels.len(), MAX_BUFFERED_MESSAGES + 2);
+        assert_eq!(labels[0], "first");
+        assert!(labels[1].contains("1.slint"));
+        assert_eq!(labels[labels.len() - 2..], ["second", "show 1"]);
+    }
+
+    #[test]
+    fn test_heartbeat() {
+        let mut connection = Connection::default();
+        let pings = RefCell::new(Vec::new());
+        let deliver = |m: &PreviewToLspMessage| {
+            if let PreviewToLspMessage::Ping { id } = m {
+                pings.borrow_mut().push(*id);
+            }
+            true
+        };
+        let second = |s: u64| Instant(s * 1000);
+
+        connection.tick(second(0), &deliver);
+        assert_eq!(*pings.borrow(), [1]);
+        // The ping is still waiting for its answer
+        connection.tick(second(2), &deliver);
+        assert_eq!(*pings.borrow(), [1]);
+        assert!(!connection.message_received(second(3), Some(1)));
+
+        // Silent for a while
+        connection.tick(second(4), &deliver);
+        assert_eq!(*pings.borrow(), [1]);
+        connection.tick(second(5), &deliver);
+        assert_eq!(*pings.borrow(), [1, 2]);
+        connection.tick(second(10), &deliver);
+        assert_eq!(connection.health, Health::Connected);
+        connection.tick(second(11), &deliver);
+        assert_eq!(connection.health, Health::Unresponsive);
+        assert_eq!(*pings.borrow(), [1, 2, 3]);
+
+        connection.send(edit("a"), &deliver);
+        assert_eq!(labels(&connection), ["a"]);
+        assert!(connection.message_received(second(12), Some(3)));
+        assert_eq!(connection.health, Health::Connected);
+        assert_eq!(connection.pending_ping, None);
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Collect the output of `debug()` and the runtime warnings and errors of the previewed
+//! component, so that the preview can list them in its console panel.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use i_slint_compiler::diagnostics::SourceLocation;
+use i_slint_core::animations::Instant;
+pub use slint_interpreter::LogLevel;
+
+/// The number of messages kept, older ones get dropped
+const MAX_MESSAGES: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsoleMessage {
+    pub level: LogLevel,
+    /// When the message got logged
+    pub time: Instant,
+    pub text: String,
+    /// The file, line and column the message was logged from, if known
+    pub location: Option<(PathBuf, usize, usize)>,
+}
+
+impl ConsoleMessage {
+    fn new(level: LogLevel, location: Option<&SourceLocation>, text: &str) -> Self {
+        let location = location.and_then(|l| {
+            let source_file = l.source_file.as_ref()?;
+            if !l.span.is_valid() {
+                return None;
+            }
+            let (line, column) = source_file.line_column(l.span.offset);
+            Some((source_file.path().to_path_buf(), line, column))
+        });
+        Self { level, time: Instant::now(), text: text.to_string(), location }
+    }
+}
+
+/// Which levels of messages to show
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Filter {
+    pub debug: bool,
+    pub warnings: bool,
+    pub errors: bool,
+}
+
+impl Filter {
+    fn accepts(&self, level: LogLevel) -> bool {
+        match level {
+            LogLevel::Debug => self.debug,
+            LogLevel::Warning => self.warnings,
+            LogLevel::Error => self.errors,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Console {
+    messages: VecDeque<ConsoleMessage>,
+    update_pending: bool,
+}
+
+impl Console {
+    /// Returns whether the UI needs to be told about new messages
+    fn push(&mut self, message: ConsoleMessage) -> bool {
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message);
+        !std::mem::replace(&mut self.update_pending, true)
+    }
+}
+
+thread_local! {static CONSOLE: RefCell<Console> = RefCell::default();}
+
+/// Collect the messages the interpreter logs in this thread. `changed` gets called from the
+/// event loop after new messages arrived.
+pub fn install(changed: fn()) {
+    slint_interpreter::set_log_handler(Some(Box::new(move |level, location, text| {
+        // Still print the message, for those looking at the output of the LSP
+        i_slint_core::debug_log!("{text}");
+        let message = ConsoleMessage::new(level, location, text);
+        if CONSOLE.with(|console| console.borrow_mut().push(message)) {
+            // The message might be logged while evaluating a binding, so do not touch the
+            // UI right away
+            slint::Timer::single_shot(std::time::Duration::ZERO, move || {
+                CONSOLE.with(|console| console.borrow_mut().update_pending = false);
+                changed();
+            });
+        }
+    })));
+}
+
+/// The messages passing `filter`, oldest first
+pub fn messages(filter: Filter) -> Vec<ConsoleMessage> {
+    CONSOLE.with(|console| {
+        console.borrow().messages.iter().filter(|m| filter.accepts(m.level)).cloned().collect()
+    })
+}
+
+/// The number of warnings and errors logged
+pub fn problem_count() -> usize {
+    CONSOLE.with(|console| {
+        console.borrow().messages.iter().filter(|m| m.level != LogLevel::Debug).count()
+    })
+}
+
+pub fn clear() {
+    CONSOLE.with(|console| console.borrow_mut().messages.clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_output() {
+        let source = r#"
+            export component Main {
+                callback clicked();
+                clicked => {
+                    debug("Hello", 42);
+                }
+            }
+        "#;
+        let instance = crate::preview::test::interpret_test("fluent", source);
+        install(|| {});
+        clear();
+        instance.invoke("clicked", &[]).unwrap();
+        slint_interpreter::set_log_handler(None);
+
+        let all = Filter { debug: true, warnings: true, errors: true };
+        let messages = messages(all);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].level, LogLevel::Debug);
+        assert_eq!(messages[0].text, "Hello 42");
+        let (_, line, _) = messages[0].location.clone().unwrap();
+        assert_eq!(line, 5);
+        assert_eq!(problem_count(), 0);
+
+        assert!(super::messages(Filter { debug: false, ..all }).is_empty());
+        clear();
+        assert!(super::messages(all).is_empty());
+    }
+
+    #[test]
+    fn test_message_limit() {
+        let mut console = Console::default();
+        let message = |i: usize| ConsoleMessage {
+            level: LogLevel::Warning,
+            time: Instant(i as u64),
+            text: i.to_string(),
+            location: None,
+        };
+        assert!(console.push(message(0)));
+        assert!(!console.push(message(1)));
+        for i in 2..MAX_MESSAGES + 10 {
+            console.push(message(i));
+        }
+        assert_eq!(console.messages.len(), MAX_MESSAGES);
+        assert_eq!(console.messages.front().unwrap().text, "10");
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Devices the preview can take the size of, to check responsive layouts without
+//! resizing the preview by hand
+
+/// The space at the edges of a screen that is covered by hardware or system UI, in
+/// logical pixels
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Insets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Insets {
+    const fn new(top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Self { top, right, bottom, left }
+    }
+}
+
+/// Where the screen of a device is obscured, in logical pixels
+pub struct SafeArea {
+    pub portrait: Insets,
+    pub landscape: Insets,
+    /// The radius of the rounded screen corners
+    pub corner_radius: f32,
+    /// The size of the notch or camera cutout centered at the top edge in portrait orientation
+    pub notch_width: f32,
+    pub notch_height: f32,
+    /// A home indicator bar is drawn over the bottom edge
+    pub home_indicator: bool,
+}
+
+impl SafeArea {
+    const NONE: SafeArea = SafeArea {
+        portrait: Insets::new(0.0, 0.0, 0.0, 0.0),
+        landscape: Insets::new(0.0, 0.0, 0.0, 0.0),
+        corner_radius: 0.0,
+        notch_width: 0.0,
+        notch_height: 0.0,
+        home_indicator: false,
+    };
+}
+
+pub struct Device {
+    pub name: &'static str,
+    /// In physical pixels, in portrait orientation
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    pub safe_area: SafeArea,
+}
+
+pub const DEVICES: &[Device] = &[
+    Device {
+        name: "Small Phone",
+        width: 750,
+        height: 1334,
+        scale_factor: 2.0,
+        safe_area: SafeArea {
+            portrait: Insets::new(20.0, 0.0, 0.0, 0.0),
+            landscape: Insets::new(0.0, 0.0, 0.0, 0.0),
+            ..SafeArea::NONE
+        },
+    },
+    Device {
+        name: "Phone",
+        width: 1170,
+        height: 2532,
+        scale_factor: 3.0,
+        safe_area: SafeArea {
+            portrait: Insets::new(47.0, 0.0, 34.0, 0.0),
+            landscape: Insets::new(0.0, 47.0, 21.0, 47.0),
+            corner_radius: 47.0,
+            notch_width: 162.0,
+            notch_height: 34.0,
+            home_indicator: true,
+        },
+    },
+    Device {
+        name: "Large Phone",
+        width: 1440,
+        height: 3200,
+        scale_factor: 4.0,
+        safe_area: SafeArea {
+            portrait: Insets::new(32.0, 0.0, 16.0, 0.0),
+            landscape: Insets::new(0.0, 0.0, 16.0, 32.0),
+            corner_radius: 32.0,
+            notch_width: 24.0,
+            notch_height: 24.0,
+            home_indicator: false,
+        },
+    },
+    Device {
+        name: "Tablet",
+        width: 1640,
+        height: 2360,
+        scale_factor: 2.0,
+        safe_area: SafeArea {
+            portrait: Insets::new(24.0, 0.0, 20.0, 0.0),
+            landscape: Insets::new(24.0, 0.0, 20.0, 0.0),
+            corner_radius: 18.0,
+            home_indicator: true,
+            ..SafeArea::NONE
+        },
+    },
+    Device {
+        name: "Large Tablet",
+        width: 2048,
+        height: 2732,
+        scale_factor: 2.0,
+        safe_area: SafeArea {
+            portrait: Insets::new(24.0, 0.0, 20.0, 0.0),
+            landscape: Insets::new(24.0, 0.0, 20.0, 0.0),
+            corner_radius: 18.0,
+            home_indicator: true,
+            ..SafeArea::NONE
+        },
+    },
+    Device {
+        name: "Laptop",
+        width: 1800,
+        height: 2880,
+        scale_factor: 2.0,
+        safe_area: SafeArea::NONE,
+    },
+    Device {
+        name: "Desktop",
+        width: 1080,
+        height: 1920,
+        scale_factor: 1.0,
+        safe_area: SafeArea::NONE,
+    },
+];
+
+/// The properties a previewed component can declare to get the safe area insets of the
+/// simulated device, in the order of the fields of [`Insets`]
+pub const INSET_PROPERTIES: [&str; 4] = [
+    "safe-area-inset-top",
+    "safe-area-inset-right",
+    "safe-area-inset-bottom",
+    "safe-area-inset-left",
+];
+
+/// Set the `INSET_PROPERTIES` the previewed component declares to the `insets`
+pub fn apply_insets(component_instance: &slint_interpreter::ComponentInstance, insets: Insets) {
+    let declared = component_instance
+        .definition()
+        .properties()
+        .map(|(name, _)| name)
+        .collect::<std::collections::HashSet<_>>();
+    for (name, value) in
+        INSET_PROPERTIES.iter().zip([insets.top, insets.right, insets.bottom, insets.left])
+    {
+        if declared.contains(*name) {
+            let _ = component_instance
+                .set_property(name, slint_interpreter::Value::Number(value as f64));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devices() {
+        for device in DEVICES {
+            assert!(device.width <= device.height, "{} is not in portrait", device.name);
+            // The previewed component gets a size of whole logical pixels
+            let logical_width = device.width as f32 / device.scale_factor;
+            let logical_height = device.height as f32 / device.scale_factor;
+            assert_eq!(
+                (logical_width.fract(), logical_height.fract()),
+                (0.0, 0.0),
+                "{}",
+                device.name
+            );
+
+            let safe_area = &device.safe_area;
+            for (insets, width, height) in [
+                (safe_area.portrait, logical_width, logical_height),
+                (safe_area.landscape, logical_height, logical_width),
+            ] {
+                assert!(insets.left + insets.right < width, "{}", device.name);
+                assert!(insets.top + insets.bottom < height, "{}", device.name);
+            }
+            // The notch is outside of the safe area
+            assert!(safe_area.notch_height <= safe_area.portrait.top, "{}", device.name);
+            assert!(safe_area.notch_height <= safe_area.landscape.left, "{}", device.name);
+        }
+    }
+}
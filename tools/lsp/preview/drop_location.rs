@@ -2,15 +2,18 @@
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 
 use i_slint_compiler::diagnostics::{BuildDiagnostics, SourceFile};
+use i_slint_compiler::langtype::Type;
 use i_slint_compiler::object_tree;
 use i_slint_compiler::parser::{
     syntax_nodes, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize,
 };
 use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
 use slint_interpreter::ComponentInstance;
+use smol_str::SmolStr;
 
 use crate::common::{self, text_edit};
 use crate::language::completion;
@@ -802,6 +805,9 @@ pub fn workspace_edit_compiles(
     let Ok(mut result) = text_edit::apply_workspace_edit(document_cache, workspace_edit) else {
         return false;
     };
+    // New files need to be known before loading the files importing them
+    let created_files = text_edit::created_files(workspace_edit);
+    result.sort_by_key(|r| !created_files.contains(&&r.url));
 
     let mut diag = BuildDiagnostics::default();
 
@@ -982,21 +988,30 @@ fn property_ranges(element: &common::ElementRcNode, remove_properties: &[&str])
 fn extract_text_of_element(
     element: &common::ElementRcNode,
     remove_properties: &[&str],
+) -> Vec<String> {
+    let replacements = property_ranges(element, remove_properties)
+        .into_iter()
+        .map(|r| (r, String::new()))
+        .collect::<Vec<_>>();
+    extract_text_of_element_with_replacements(element, replacements)
+}
+
+/// Like `extract_text_of_element`, but replaces the text in the given (non-overlapping) ranges
+fn extract_text_of_element_with_replacements(
+    element: &common::ElementRcNode,
+    mut replacements: Vec<(TextRange, String)>,
 ) -> Vec<String> {
     let (start_offset, mut text) = element.with_decorated_node(|node| {
         (usize::from(node.text_range().start()), node.text().to_string())
     });
 
-    let mut to_delete_ranges = property_ranges(element, remove_properties);
-    to_delete_ranges.sort_by(|a, b| u32::from(a.start()).cmp(&u32::from(b.start())));
-    let mut offset = start_offset;
-    for dr in to_delete_ranges {
-        let start = usize::from(dr.start()) - offset;
-        let end = usize::from(dr.end()) - offset;
-
-        offset += end - start;
+    // Back to front, so that the earlier ranges stay valid
+    replacements.sort_by_key(|(r, _)| std::cmp::Reverse(r.start()));
+    for (range, replacement) in replacements {
+        let start = usize::from(range.start()) - start_offset;
+        let end = usize::from(range.end()) - start_offset;
 
-        text.drain(start..end);
+        text.replace_range(start..end, &replacement);
     }
 
     // Trim leading WS to get "raw" lines
@@ -1017,6 +1032,31 @@ fn extract_text_of_element(
     lines
 }
 
+// Indent the lines of an element extracted by `extract_text_of_element` to fit at the insert position
+fn indented_element_text(element_text_lines: &[String], insert_info: &InsertInformation) -> String {
+    if element_text_lines.is_empty() {
+        return String::new();
+    }
+
+    let mut tmp =
+        format!("{}{}\n", insert_info.pre_indent, element_text_lines.first().expect("Not empty"));
+
+    for l in element_text_lines.iter().take(element_text_lines.len() - 1).skip(1) {
+        tmp.push_str(&format!("{}{l}\n", insert_info.indent));
+    }
+
+    if element_text_lines.len() >= 2 {
+        tmp.push_str(&format!(
+            "{}{}\n{}",
+            insert_info.indent,
+            element_text_lines.last().expect("Length was checked"),
+            insert_info.post_indent
+        ));
+    }
+
+    tmp
+}
+
 fn node_removal_text_edit(
     document_cache: &common::DocumentCache,
     node: &SyntaxNode,
@@ -1108,7 +1148,6 @@ pub fn create_move_element_workspace_edit(
     instance_index: usize,
     position: LogicalPoint,
 ) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
-    let component_type = element.component_type();
     let parent_of_element = element.parent();
 
     let placeholder_text = if Some(&drop_info.target_element_node) == parent_of_element.as_ref() {
@@ -1137,49 +1176,1180 @@ pub fn create_move_element_workspace_edit(
             }
         }
 
-        /* fall trough to the general case here */
-        String::new()
-    } else if parent_of_element.map(|p| p.children().len()).unwrap_or_default() == 1 {
-        placeholder()
-    } else {
-        String::new()
+        /* fall trough to the general case here */
+        String::new()
+    } else if parent_of_element.map(|p| p.children().len()).unwrap_or_default() == 1 {
+        placeholder()
+    } else {
+        String::new()
+    };
+
+    move_element_workspace_edit(drop_info, element, &placeholder_text, &["x", "y"])
+}
+
+/// Move `element` to the place described by `drop_info`, leaving `placeholder_text` behind
+/// and dropping the `remove_properties` of the element
+fn move_element_workspace_edit(
+    drop_info: &DropInformation,
+    element: &common::ElementRcNode,
+    placeholder_text: &str,
+    remove_properties: &[&str],
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let component_type = element.component_type();
+    let mut lines = extract_text_of_element(element, remove_properties);
+    // The ids of the element must not clash with the ones of the component it moves into
+    if !element.is_same_component_as(&drop_info.target_element_node) {
+        let ids = element.with_decorated_node(|node| declared_ids(&node));
+        let used_ids = drop_info.target_element_node.with_element_node(|node| component_ids(node));
+        let renames = unique_id_renames(&ids, &used_ids);
+        lines = rename_identifiers(&lines.join("\n"), &renames)
+            .split('\n')
+            .map(|l| l.to_string())
+            .collect();
+    }
+    let new_text = indented_element_text(&lines, &drop_info.insert_info);
+
+    let (path, _) = drop_info.target_element_node.path_and_offset();
+
+    let document_cache = preview::document_cache()?;
+    let doc = document_cache.get_document_by_path(&path)?;
+    let source_file = doc.node.as_ref().unwrap().source_file.clone();
+
+    let mut selection_offset = drop_info.insert_info.insertion_position.offset()
+        + TextSize::new(
+            new_text.chars().take_while(|c| c.is_whitespace()).map(|c| c.len_utf8()).sum::<usize>()
+                as u32,
+        );
+
+    let mut edits = common::WorkspaceEditBuilder::new("Move element");
+
+    let remove_me = element.with_decorated_node(|node| {
+        node_removal_text_edit(&document_cache, &node, placeholder_text.to_string())
+    })?;
+    if remove_me.url.to_file_path().as_ref().map(|p| p.as_path()) == Ok(source_file.path()) {
+        selection_offset = text_edit::TextOffsetAdjustment::new(&remove_me.edit, &source_file)
+            .adjust(selection_offset);
+    }
+    edits.add_single_text_edit(remove_me);
+
+    if let Some(component_info) = preview::get_component_info(&component_type) {
+        let import_file =
+            component_info.import_file_name(&lsp_types::Url::from_file_path(&path).ok());
+        if let Some(edit) = completion::create_import_edit(doc, &component_type, &import_file) {
+            if let Some(sf) = doc.node.as_ref().map(|n| &n.source_file) {
+                selection_offset =
+                    text_edit::TextOffsetAdjustment::new(&edit, sf).adjust(selection_offset);
+            }
+            edits.add_single_text_edit(common::SingleTextEdit::from_path(
+                &document_cache,
+                source_file.path(),
+                edit,
+            )?);
+        }
+    }
+
+    for te in drop_ignored_elements_from_node(&drop_info.target_element_node, &source_file) {
+        selection_offset =
+            text_edit::TextOffsetAdjustment::new(&te, &source_file).adjust(selection_offset);
+        if let Some(edit) =
+            common::SingleTextEdit::from_path(&document_cache, source_file.path(), te)
+        {
+            edits.add_single_text_edit(edit);
+        }
+    }
+
+    let start_pos = util::text_size_to_lsp_position(
+        &source_file,
+        drop_info.insert_info.insertion_position.offset(),
+    );
+    let end_pos = util::text_size_to_lsp_position(
+        &source_file,
+        drop_info.insert_info.insertion_position.offset()
+            + TextSize::new(drop_info.insert_info.replacement_range),
+    );
+    edits.add_single_text_edit(common::SingleTextEdit::from_path(
+        &document_cache,
+        source_file.path(),
+        lsp_types::TextEdit { range: lsp_types::Range::new(start_pos, end_pos), new_text },
+    )?);
+
+    let (_, edit) = edits.build().ok()?;
+    Some((edit, DropData { selection_offset, path }))
+}
+
+/// Where to move an element to, relative to another element of the element tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreePosition {
+    Before,
+    Into,
+    After,
+}
+
+/// Move `element` before, after or into `target` in the element tree, keeping its
+/// position if it stays in the same parent.
+///
+/// Return a WorkspaceEdit to send to the editor and extra info for the live preview in
+/// the DropData struct, or `None` if the move is impossible or changes nothing.
+pub fn move_element_in_tree(
+    document_cache: &common::DocumentCache,
+    element: &common::ElementRcNode,
+    target: &common::ElementRcNode,
+    position: TreePosition,
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let current_parent = element.parent()?;
+    let (element_path, _) = element.path_and_offset();
+    let element_range = element.with_decorated_node(|n| n.text_range());
+    let (target_path, target_offset) = target.path_and_offset();
+    if element_path == target_path && element_range.contains_inclusive(target_offset) {
+        // Can not move into itself
+        return None;
+    }
+
+    let (parent, child_index) = if position == TreePosition::Into {
+        (target.clone(), usize::MAX)
+    } else {
+        let parent = target.parent()?;
+        let children = parent.children();
+        let index = children.iter().position(|c| c == target)?;
+        let index = if position == TreePosition::After { index + 1 } else { index };
+        (parent, if index >= children.len() { usize::MAX } else { index })
+    };
+
+    let stays_in_parent = parent == current_parent;
+    if stays_in_parent {
+        let children = parent.children();
+        let current_index = children.iter().position(|c| c == element)?;
+        let new_index = if child_index == usize::MAX { children.len() } else { child_index };
+        if new_index == current_index || new_index == current_index + 1 {
+            return None;
+        }
+    }
+
+    let insert_info = if child_index == usize::MAX {
+        insert_position_at_end(&parent)
+    } else {
+        insert_position_before_child(&parent, child_index)
+    }?;
+    let drop_info =
+        DropInformation { target_element_node: parent, insert_info, drop_mark: None, child_index };
+
+    let placeholder_text = if !stays_in_parent && current_parent.children().len() == 1 {
+        placeholder()
+    } else {
+        String::new()
+    };
+    // x and y are meaningless in a different parent
+    let remove_properties: &[&str] = if stays_in_parent { &[] } else { &["x", "y"] };
+
+    move_element_workspace_edit(&drop_info, element, &placeholder_text, remove_properties)
+        .filter(|(e, _)| workspace_edit_compiles(document_cache, e))
+}
+
+/// Wrap `elements`, which need to share a parent, into a new `layout` element placed
+/// where the first of them is.
+///
+/// The elements lose their `x` and `y` bindings, the layout is placed at `position`
+/// (relative to the parent) if given.
+pub fn wrap_in_layout(
+    document_cache: &common::DocumentCache,
+    elements: &[common::ElementRcNode],
+    layout: &str,
+    position: Option<LogicalPoint>,
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let parent = elements.first()?.parent()?;
+    if elements.iter().any(|e| e.parent().as_ref() != Some(&parent)) {
+        return None;
+    }
+    let mut elements = elements.to_vec();
+    elements.sort_by_key(|e| e.path_and_offset().1);
+    elements.dedup();
+    let first = elements.first()?;
+
+    let indent = util::find_element_indent(first).unwrap_or_default();
+    let child_indent = format!("{indent}    ");
+    let mut new_text = format!("{layout} {{\n");
+    if let Some(position) = position {
+        new_text += &format!(
+            "{child_indent}x: {}px;\n{child_indent}y: {}px;\n",
+            position.x.round(),
+            position.y.round()
+        );
+    }
+    for element in &elements {
+        for line in extract_text_of_element(element, &["x", "y"]) {
+            if line.trim().is_empty() {
+                new_text.push('\n');
+            } else {
+                new_text += &format!("{child_indent}{line}\n");
+            }
+        }
+    }
+    new_text += &format!("{indent}}}");
+
+    let (path, _) = first.path_and_offset();
+    let (range, selection_offset) = first.with_decorated_node(|node| {
+        (
+            util::text_range_to_lsp_range(&node.source_file, node.text_range()),
+            node.text_range().start(),
+        )
+    });
+
+    let mut edits = common::WorkspaceEditBuilder::new("Wrap in layout");
+    edits.add_single_text_edit(common::SingleTextEdit::from_path(
+        document_cache,
+        &path,
+        lsp_types::TextEdit::new(range, new_text),
+    )?);
+    for element in elements.iter().skip(1) {
+        edits.add_single_text_edit(element.with_decorated_node(|node| {
+            node_removal_text_edit(document_cache, &node, String::new())
+        })?);
+    }
+
+    let (_, edit) = edits.build().ok()?;
+    workspace_edit_compiles(document_cache, &edit)
+        .then_some((edit, DropData { selection_offset, path }))
+}
+
+/// The builtin layout `element` is, or inherits from
+fn layout_type(element: &common::ElementRcNode) -> Option<&'static str> {
+    let builtin = element.as_element().borrow().builtin_type()?;
+    ["HorizontalLayout", "VerticalLayout", "GridLayout"].into_iter().find(|l| builtin.name == *l)
+}
+
+/// The size classes created by `add_responsive_variants` with the conditions showing them
+const SIZE_CLASSES: &[(&str, &str)] = &[
+    ("compact", "root.width < root.medium-min-width"),
+    ("medium", "root.width >= root.medium-min-width && root.width < root.expanded-min-width"),
+    ("expanded", "root.width >= root.expanded-min-width"),
+];
+
+/// Move the children of the component `root` into one conditional element per size class,
+/// shown depending on the width of the component.
+///
+/// Every size class gets its own copy of the children to rearrange. The ids in all but
+/// the first copy get the name of the size class appended.
+pub fn add_responsive_variants(
+    document_cache: &common::DocumentCache,
+    root: &common::ElementRcNode,
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let children = root.children();
+    let first = children.first()?;
+
+    let container = layout_type(root).unwrap_or("Rectangle");
+    let lines = children
+        .iter()
+        .map(|c| extract_text_of_element(c, &[]).join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let ids = children
+        .iter()
+        .flat_map(|c| c.with_decorated_node(|n| declared_ids(&n)))
+        .collect::<Vec<_>>();
+
+    let mut taken = root.with_element_node(|node| component_ids(node));
+
+    let indent = util::find_element_indent(first).unwrap_or_default();
+    let mut new_text = [
+        "// The size classes: Adjust the breakpoints and the elements shown for each class",
+        "in property <length> medium-min-width: 600px;",
+        "in property <length> expanded-min-width: 840px;",
+    ]
+    .join(&format!("\n{indent}"));
+    new_text += "\n";
+    let mut selection_offset = None;
+    for (index, (class, condition)) in SIZE_CLASSES.iter().enumerate() {
+        let renames = if index == 0 {
+            HashMap::new()
+        } else {
+            ids.iter()
+                .map(|id| (id.clone(), take_unique_id(&format!("{id}-{class}"), &mut taken)))
+                .collect()
+        };
+        new_text += &format!("\n{indent}// {class}\n{indent}");
+        selection_offset.get_or_insert(new_text.len());
+        new_text += &format!("if {condition}: {container} {{\n");
+        for line in rename_identifiers(&lines, &renames).split('\n') {
+            if line.trim().is_empty() {
+                new_text.push('\n');
+            } else {
+                new_text += &format!("{indent}    {line}\n");
+            }
+        }
+        new_text += &format!("{indent}}}\n");
+    }
+    let new_text = new_text.trim_end().to_string();
+
+    let (path, _) = first.path_and_offset();
+    let (range, start) = first.with_decorated_node(|node| {
+        (
+            util::text_range_to_lsp_range(&node.source_file, node.text_range()),
+            node.text_range().start(),
+        )
+    });
+    let selection_offset = start + TextSize::try_from(selection_offset?).ok()?;
+
+    let mut edits = common::WorkspaceEditBuilder::new("Add size classes");
+    edits.add_single_text_edit(common::SingleTextEdit::from_path(
+        document_cache,
+        &path,
+        lsp_types::TextEdit::new(range, new_text),
+    )?);
+    for child in children.iter().skip(1) {
+        edits.add_single_text_edit(child.with_decorated_node(|node| {
+            node_removal_text_edit(document_cache, &node, String::new())
+        })?);
+    }
+
+    let (_, edit) = edits.build().ok()?;
+    workspace_edit_compiles(document_cache, &edit)
+        .then_some((edit, DropData { selection_offset, path }))
+}
+
+/// Properties placing an element in its parent: They stay with the instance when
+/// extracting the element into a new component
+const PLACEMENT_PROPERTIES: &[&str] = &["x", "y", "col", "row", "colspan", "rowspan"];
+
+/// A property of an element around the elements to extract, used by them
+struct OutsideReference {
+    /// The `foo.bar` part of `foo.bar.baz`
+    range: TextRange,
+    qualifier: SmolStr,
+    property: SmolStr,
+    property_type: Type,
+    /// The property is assigned to or bound two-way
+    is_written: bool,
+}
+
+fn find_element_by_id(root: &object_tree::ElementRc, id: &str) -> Option<object_tree::ElementRc> {
+    let mut result = None;
+    object_tree::recurse_elem(root, &(), &mut |element, _| {
+        if result.is_none() && element.borrow().id == id {
+            result = Some(element.clone());
+        }
+    });
+    result
+}
+
+/// The first two identifiers of all qualified names used in expressions in `node`
+fn qualified_names_in_expressions(
+    node: &SyntaxNode,
+) -> Vec<(i_slint_compiler::parser::SyntaxNode, Vec<SyntaxToken>)> {
+    node.descendants()
+        .filter(|n| {
+            n.kind() == SyntaxKind::QualifiedName
+                && n.parent().is_some_and(|p| p.kind() == SyntaxKind::Expression)
+        })
+        .map(|qn| {
+            let identifiers = qn
+                .node
+                .children_with_tokens()
+                .filter_map(|t| t.into_token())
+                .filter(|t| t.kind() == SyntaxKind::Identifier)
+                .take(2)
+                .map(|token| SyntaxToken { token, source_file: qn.source_file.clone() })
+                .collect::<Vec<_>>();
+            (qn, identifiers)
+        })
+        .collect()
+}
+
+/// Find the properties of elements outside of `elements` used by them
+///
+/// Returns `None` if the elements use something that can not become a property of
+/// a new component, like a callback.
+fn outside_references(
+    elements: &[common::ElementRcNode],
+    parent: &common::ElementRcNode,
+) -> Option<Vec<OutsideReference>> {
+    let root = parent.as_element().borrow().enclosing_component.upgrade()?.root_element.clone();
+    let inner_ids = elements
+        .iter()
+        .flat_map(|e| e.with_decorated_node(|n| declared_ids(&n)))
+        .map(|id| i_slint_compiler::parser::normalize_identifier(&id))
+        .collect::<HashSet<_>>();
+
+    let mut result = vec![];
+    for element in elements {
+        let element_range = element.with_element_node(|n| n.text_range());
+        for (qn, identifiers) in element.with_element_node(|n| qualified_names_in_expressions(n)) {
+            let [qualifier, property] = identifiers.as_slice() else {
+                continue;
+            };
+            let qualifier_name = i_slint_compiler::parser::normalize_identifier(qualifier.text());
+            let target = match qualifier_name.as_str() {
+                "self" => continue,
+                "root" => root.clone(),
+                "parent" => {
+                    // `parent` only leaves the extracted elements in their own bindings
+                    let owner = qn.ancestors().find(|n| n.kind() == SyntaxKind::Element)?;
+                    if owner.text_range() != element_range {
+                        continue;
+                    }
+                    parent.as_element().clone()
+                }
+                id if inner_ids.contains(id) => continue,
+                id => match find_element_by_id(&root, id) {
+                    Some(e) => e,
+                    // A global, an enum value, ...
+                    None => continue,
+                },
+            };
+
+            let property_name = i_slint_compiler::parser::normalize_identifier(property.text());
+            let property_type = target.borrow().lookup_property(&property_name).property_type;
+            if !property_type.is_property_type() {
+                return None;
+            }
+
+            let expression = qn.parent()?;
+            let is_written = expression.parent().is_some_and(|p| {
+                p.kind() == SyntaxKind::TwoWayBinding
+                    || (p.kind() == SyntaxKind::SelfAssignment
+                        && p.first_child().is_some_and(|c| c.node == expression.node))
+            });
+
+            result.push(OutsideReference {
+                range: TextRange::new(qualifier.text_range().start(), property.text_range().end()),
+                qualifier: qualifier_name,
+                property: property_name,
+                property_type,
+                is_written,
+            });
+        }
+    }
+    Some(result)
+}
+
+/// A property of an extracted component, bound to a property outside of it
+struct PromotedProperty {
+    name: String,
+    qualifier: SmolStr,
+    property: SmolStr,
+    property_type: Type,
+    is_written: bool,
+}
+
+impl PromotedProperty {
+    fn declaration(&self) -> String {
+        let property_type = match &self.property_type {
+            Type::Enumeration(e) => e.name.to_string(),
+            t => t.to_string(),
+        };
+        let visibility = if self.is_written { "in-out" } else { "in" };
+        format!("{visibility} property <{property_type}> {};", self.name)
+    }
+
+    fn binding(&self) -> String {
+        let operator = if self.is_written { " <=>" } else { ":" };
+        format!("{}{operator} {}.{};", self.name, self.qualifier, self.property)
+    }
+}
+
+/// The `import` statements of `document` reduced to the `used_names`
+fn imports_for(document: &syntax_nodes::Document, used_names: &HashSet<SmolStr>) -> String {
+    let mut result = String::new();
+    for import in document.ImportSpecifier() {
+        let Some(file) = import.child_text(SyntaxKind::StringLiteral) else {
+            continue;
+        };
+        let identifiers = import
+            .ImportIdentifierList()
+            .into_iter()
+            .flat_map(|l| l.ImportIdentifier())
+            .filter(|i| {
+                let name = i
+                    .InternalName()
+                    .map_or_else(|| i.ExternalName().text().to_string(), |n| n.text().to_string());
+                used_names.contains(&i_slint_compiler::parser::normalize_identifier(name.trim()))
+            })
+            .map(|i| i.text().to_string().trim().to_string())
+            .collect::<Vec<_>>();
+        if !identifiers.is_empty() {
+            result += &format!("import {{ {} }} from {file};\n", identifiers.join(", "));
+        }
+    }
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    result
+}
+
+/// Move `elements` into a new component called `component_name` and put an instance of
+/// that component where they were
+///
+/// The new component goes in front of the component the elements are in, or into the
+/// new file at `new_file`. A single element becomes the root of the new component, several
+/// elements its children. Properties of other elements used by the extracted elements turn
+/// into properties of the new component, which the instance binds to the original ones.
+pub fn extract_component(
+    document_cache: &common::DocumentCache,
+    elements: &[common::ElementRcNode],
+    component_name: &str,
+    new_file: Option<&lsp_types::Url>,
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let parent = elements.first()?.parent()?;
+    if elements.iter().any(|e| e.parent().as_ref() != Some(&parent)) {
+        return None;
+    }
+    let mut elements = elements.to_vec();
+    elements.sort_by_key(|e| e.path_and_offset().1);
+    elements.dedup();
+    // Repeated and conditional elements can not be the root of a component
+    if elements.iter().any(|e| e.with_decorated_node(|n| n.kind() != SyntaxKind::SubElement)) {
+        return None;
+    }
+    let first = elements.first()?;
+    let single = (elements.len() == 1).then_some(first);
+
+    let base_type = match single {
+        Some(element) => Some(element.component_type().trim().to_string()),
+        None => layout_type(&parent).map(str::to_string),
+    };
+    let is_taken = |name: &str| {
+        if i_slint_compiler::typeregister::reserved_property(name).property_type != Type::Invalid {
+            return true;
+        }
+        let base_property_type = match single {
+            Some(element) => {
+                element.as_element().borrow().base_type.lookup_property(name).property_type
+            }
+            None => base_type
+                .as_ref()
+                .and_then(|b| document_cache.global_type_registry().lookup_element(b).ok())
+                .map(|t| t.lookup_property(name).property_type)
+                .unwrap_or(Type::Invalid),
+        };
+        base_property_type != Type::Invalid
+    };
+
+    // Turn the used properties into properties of the new component
+    let references = outside_references(&elements, &parent)?;
+    let mut promoted: Vec<PromotedProperty> = vec![];
+    let mut replacements = vec![];
+    for reference in references {
+        let index = match promoted
+            .iter()
+            .position(|p| p.qualifier == reference.qualifier && p.property == reference.property)
+        {
+            Some(index) => index,
+            None => {
+                let base = if reference.qualifier == "root" {
+                    reference.property.to_string()
+                } else {
+                    format!("{}-{}", reference.qualifier, reference.property)
+                };
+                let mut name = base.clone();
+                let mut counter = 2;
+                while is_taken(&name) || promoted.iter().any(|p| p.name == name) {
+                    name = format!("{base}-{counter}");
+                    counter += 1;
+                }
+                promoted.push(PromotedProperty {
+                    name,
+                    qualifier: reference.qualifier.clone(),
+                    property: reference.property.clone(),
+                    property_type: reference.property_type.clone(),
+                    is_written: false,
+                });
+                promoted.len() - 1
+            }
+        };
+        promoted[index].is_written |= reference.is_written;
+        replacements.push((reference.range, format!("root.{}", promoted[index].name)));
+    }
+
+    let indent = util::find_element_indent(first).unwrap_or_default();
+    let declarations =
+        promoted.iter().map(|p| format!("    {}\n", p.declaration())).collect::<String>();
+    let header = match &base_type {
+        Some(base_type) => format!("component {component_name} inherits {base_type} {{"),
+        None => format!("component {component_name} {{"),
+    };
+
+    let (component_text, instance_text) = if let Some(element) = single {
+        // The id and placement stay with the instance, the element itself becomes `root`
+        let (id, lbrace, placement, own_id_references) = element.with_decorated_node(|node| {
+            let id = node.child_text(SyntaxKind::Identifier);
+            let element_node = syntax_nodes::SubElement::new(node.clone())?.Element();
+            let lbrace = element_node.child_token(SyntaxKind::LBrace)?;
+            let placement = element_node
+                .Binding()
+                .filter(|b| {
+                    b.first_token().is_some_and(|t| PLACEMENT_PROPERTIES.contains(&t.text()))
+                })
+                .map(|b| b.text().to_string().trim().to_string())
+                .collect::<Vec<_>>();
+            let own_id_references = id
+                .as_ref()
+                .map(|id| {
+                    qualified_names_in_expressions(&node)
+                        .into_iter()
+                        .filter_map(|(_, identifiers)| identifiers.first().cloned())
+                        .filter(|t| {
+                            i_slint_compiler::parser::normalize_identifier(t.text())
+                                == i_slint_compiler::parser::normalize_identifier(id)
+                        })
+                        .map(|t| t.text_range())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            Some((id, lbrace, placement, own_id_references))
+        })?;
+        let decorated_start = element.with_decorated_node(|n| n.text_range().start());
+
+        replacements.extend(own_id_references.into_iter().map(|r| (r, "root".to_string())));
+        replacements.extend(
+            property_ranges(element, PLACEMENT_PROPERTIES).into_iter().map(|r| (r, String::new())),
+        );
+        let header = if declarations.is_empty() {
+            header
+        } else {
+            format!("{header}\n{}", declarations.trim_end_matches('\n'))
+                .replace('\n', &format!("\n{indent}"))
+        };
+        replacements.push((TextRange::new(decorated_start, lbrace.text_range().end()), header));
+        let component_text =
+            extract_text_of_element_with_replacements(element, replacements).join("\n");
+
+        let bindings =
+            placement.into_iter().chain(promoted.iter().map(|p| p.binding())).collect::<Vec<_>>();
+        let id = id.map(|id| format!("{id} := ")).unwrap_or_default();
+        (component_text, instance_text(&id, component_name, &bindings, &indent))
+    } else {
+        let mut component_text = format!("{header}\n{declarations}");
+        for element in &elements {
+            let element_range = element.with_decorated_node(|n| n.text_range());
+            let element_replacements = replacements
+                .iter()
+                .filter(|(r, _)| element_range.contains_range(*r))
+                .cloned()
+                .collect::<Vec<_>>();
+            for line in extract_text_of_element_with_replacements(element, element_replacements) {
+                if line.trim().is_empty() {
+                    component_text.push('\n');
+                } else {
+                    component_text += &format!("    {line}\n");
+                }
+            }
+        }
+        component_text.push('}');
+
+        let bindings = promoted.iter().map(|p| p.binding()).collect::<Vec<_>>();
+        (component_text, instance_text("", component_name, &bindings, &indent))
+    };
+
+    let (path, _) = first.path_and_offset();
+    let (range, selection_offset) = first.with_decorated_node(|node| {
+        (
+            util::text_range_to_lsp_range(&node.source_file, node.text_range()),
+            node.text_range().start(),
+        )
+    });
+
+    let mut edits = common::WorkspaceEditBuilder::new("Extract component");
+    edits.add_single_text_edit(common::SingleTextEdit::from_path(
+        document_cache,
+        &path,
+        lsp_types::TextEdit::new(range, instance_text),
+    )?);
+    for element in elements.iter().skip(1) {
+        edits.add_single_text_edit(element.with_decorated_node(|node| {
+            node_removal_text_edit(document_cache, &node, String::new())
+        })?);
+    }
+
+    let source_file = first.with_element_node(|n| n.source_file.clone());
+    let document = document_cache.get_document_for_source_file(&source_file)?;
+    let document_node = document.node.as_ref()?;
+    if let Some(new_file) = new_file {
+        let used_names = elements
+            .iter()
+            .flat_map(|e| {
+                e.with_decorated_node(|n| {
+                    n.descendants()
+                        .filter(|n| n.kind() == SyntaxKind::QualifiedName)
+                        .filter_map(|qn| qn.child_text(SyntaxKind::Identifier))
+                        .map(|t| i_slint_compiler::parser::normalize_identifier(&t))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .chain(base_type.iter().map(|b| i_slint_compiler::parser::normalize_identifier(b)))
+            .collect::<HashSet<_>>();
+        edits.create_file(
+            new_file.clone(),
+            format!("{}export {component_text}\n", imports_for(document_node, &used_names)),
+        );
+
+        let url = lsp_types::Url::from_file_path(&path).ok()?;
+        let import_file = url.make_relative(new_file);
+        if let Some(edit) = completion::create_import_edit(document, component_name, &import_file) {
+            edits.add_single_text_edit(common::SingleTextEdit::from_path(
+                document_cache,
+                &path,
+                edit,
+            )?);
+        }
+    } else {
+        // In front of the component (and its `export`) containing the elements
+        let component_start = first.with_element_node(|n| {
+            n.ancestors()
+                .filter(|a| matches!(a.kind(), SyntaxKind::Component | SyntaxKind::ExportsList))
+                .last()
+                .map(|a| a.text_range().start())
+        })?;
+        let position = util::text_size_to_lsp_position(&source_file, component_start);
+        edits.add_single_text_edit(common::SingleTextEdit::from_path(
+            document_cache,
+            &path,
+            lsp_types::TextEdit::new(
+                lsp_types::Range::new(position, position),
+                format!("{component_text}\n\n"),
+            ),
+        )?);
+    }
+
+    let (_, edit) = edits.build().ok()?;
+    workspace_edit_compiles(document_cache, &edit)
+        .then_some((edit, DropData { selection_offset, path }))
+}
+
+/// `Name { bindings }`, on one line if there are no bindings
+fn instance_text(id: &str, component_name: &str, bindings: &[String], indent: &str) -> String {
+    if bindings.is_empty() {
+        return format!("{id}{component_name} {{ }}");
+    }
+    let mut result = format!("{id}{component_name} {{\n");
+    for binding in bindings {
+        result += &format!("{indent}    {binding}\n");
+    }
+    result += &format!("{indent}}}");
+    result
+}
+
+/// Find a location in a file that would be a good place to insert the new component at
+///
+/// Return a WorkspaceEdit to send to the editor and extra info for the live preview in
+/// the DropData struct.
+pub fn move_element_to(
+    document_cache: &common::DocumentCache,
+    element: common::ElementRcNode,
+    instance_index: usize,
+    position: LogicalPoint,
+    mouse_position: LogicalPoint,
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let component_instance = preview::component_instance()?;
+    let Some(drop_info) = find_move_location(
+        &component_instance,
+        mouse_position,
+        &element,
+        &element.component_type(),
+    ) else {
+        // Can not drop here: Ignore the move
+        return None;
+    };
+    create_move_element_workspace_edit(
+        &component_instance,
+        &drop_info,
+        &element,
+        instance_index,
+        position,
+    )
+    .and_then(|(e, d)| workspace_edit_compiles(document_cache, &e).then_some((e, d)))
+}
+
+/// An element copied in the preview, ready to get pasted somewhere else
+#[derive(Clone, Debug)]
+pub struct ElementClipboard {
+    pub component_type: String,
+    /// The source code of the element, with the indentation removed
+    lines: Vec<String>,
+    /// The ids declared in the copied element or its children
+    ids: Vec<SmolStr>,
+}
+
+// Collect all ids declared in `node` and its children
+fn declared_ids(node: &SyntaxNode) -> Vec<SmolStr> {
+    node.descendants()
+        .filter(|n| n.kind() == SyntaxKind::SubElement)
+        .filter_map(|n| n.child_text(SyntaxKind::Identifier))
+        .collect()
+}
+
+pub fn copy_element(element: &common::ElementRcNode) -> ElementClipboard {
+    ElementClipboard {
+        component_type: element.component_type().trim().to_string(),
+        lines: extract_text_of_element(element, &["x", "y"]),
+        ids: element.with_decorated_node(|node| declared_ids(&node)),
+    }
+}
+
+/// The ids declared in the component `node` is part of
+fn component_ids(node: &SyntaxNode) -> HashSet<SmolStr> {
+    std::iter::successors(Some(node.clone()), |n| n.parent())
+        .find(|n| n.kind() == SyntaxKind::Component)
+        .map(|c| declared_ids(&c).into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Names that can not be used as ids
+const RESERVED_IDS: &[&str] = &["root", "parent", "self"];
+
+/// `id` if it is not `taken` yet, or `id` with a counter appended. The result is
+/// `taken` afterwards.
+fn take_unique_id(id: &str, taken: &mut HashSet<SmolStr>) -> SmolStr {
+    if !RESERVED_IDS.contains(&id) && taken.insert(id.into()) {
+        return id.into();
+    }
+
+    // Count up from `foo-2` instead of producing `foo-2-2`
+    let base = match id.rsplit_once('-') {
+        Some((base, counter)) if counter.parse::<u32>().is_ok() => base,
+        _ => id,
+    };
+    let new_id = (2..)
+        .map(|i| SmolStr::from(format!("{base}-{i}")))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("There is always a free id");
+    taken.insert(new_id.clone());
+    new_id
+}
+
+/// Pick new names for those `ids` that are already `used`, by appending a counter
+fn unique_id_renames(ids: &[SmolStr], used: &HashSet<SmolStr>) -> HashMap<SmolStr, SmolStr> {
+    let mut taken = used.clone();
+    let mut renames = HashMap::new();
+    for id in ids {
+        let new_id = take_unique_id(id, &mut taken);
+        if new_id != *id {
+            renames.insert(id.clone(), new_id);
+        }
+    }
+    renames
+}
+
+/// Properties telling what an element is for, in the order they are looked at
+const ROLE_PROPERTIES: &[&str] = &["text", "title", "placeholder-text", "source", "icon"];
+
+/// The first words of the string literal in `expression`, or the name of the file
+/// for an `@image-url`, as part of an id
+fn role_of(expression: &str) -> Option<String> {
+    let literal = i_slint_compiler::lexer::lex(expression)
+        .into_iter()
+        .find(|t| t.kind == SyntaxKind::StringLiteral)?;
+    let mut text = literal.text.trim_matches('"');
+    if expression.contains("@image-url") {
+        text = text.rsplit('/').next().unwrap_or(text);
+        text = text.split('.').next().unwrap_or(text);
+    }
+    let words = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(2)
+        .map(|w| w.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    words.first().filter(|w| w.starts_with(|c: char| c.is_ascii_alphabetic()))?;
+    Some(words.join("-"))
+}
+
+/// A readable id for an element of type `type_name`, like `save-button` for a `Button`
+/// with the `role` "save"
+fn suggested_id(type_name: &str, role: Option<&str>) -> String {
+    let type_name = type_name.rsplit('.').next().unwrap_or(type_name).trim();
+    let mut id = String::new();
+    for (i, c) in type_name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 && !id.ends_with('-') {
+            id.push('-');
+        }
+        id.extend(c.to_lowercase());
+    }
+    match role {
+        Some(role) => format!("{role}-{id}"),
+        None => id,
+    }
+}
+
+/// Give those `elements` that have no id yet a readable one, that is unique in their
+/// component. Root elements can not have an id and are skipped.
+///
+/// The DropData selects the first element again.
+pub fn assign_ids(
+    document_cache: &common::DocumentCache,
+    elements: &[common::ElementRcNode],
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let (first_path, mut selection_offset) = elements.first()?.path_and_offset();
+    let first_offset = selection_offset;
+
+    let mut builder = common::WorkspaceEditBuilder::new("Assign ids");
+    let mut assigned = HashSet::new();
+    for element in elements {
+        let Some((path, offset, role, mut taken)) = element.with_element_node(|node| {
+            let sub_element = node.parent().filter(|p| p.kind() == SyntaxKind::SubElement)?;
+            if sub_element.child_text(SyntaxKind::Identifier).is_some() {
+                return None;
+            }
+            let role = ROLE_PROPERTIES.iter().find_map(|property| {
+                node.Binding()
+                    .find(|b| b.child_text(SyntaxKind::Identifier).as_deref() == Some(property))
+                    .and_then(|b| role_of(&b.BindingExpression().text().to_string()))
+            });
+            let offset = node.text_range().start();
+            Some((node.source_file.path().to_path_buf(), offset, role, component_ids(node)))
+        }) else {
+            continue;
+        };
+        taken.extend(assigned.iter().cloned());
+        let id =
+            take_unique_id(&suggested_id(&element.component_type(), role.as_deref()), &mut taken);
+        let new_text = format!("{id} := ");
+        if path == first_path && offset <= first_offset {
+            selection_offset += TextSize::of(new_text.as_str());
+        }
+        let position = element
+            .with_element_node(|node| util::text_size_to_lsp_position(&node.source_file, offset));
+        builder.add_single_text_edit(common::SingleTextEdit::from_path(
+            document_cache,
+            &path,
+            lsp_types::TextEdit::new(lsp_types::Range::new(position, position), new_text),
+        )?);
+        assigned.insert(id);
+    }
+
+    if assigned.is_empty() {
+        return None;
+    }
+    let (_, edit) = builder.build().ok()?;
+    Some((edit, DropData { selection_offset, path: first_path }))
+}
+
+/// Change the id of `element` to `new_id`, along with all references to it in its component,
+/// like renaming the id in the editor does
+///
+/// An element without id gets `new_id` added. An empty `new_id` removes the id, as long as
+/// nothing refers to it. The DropData selects the element again.
+pub fn rename_element_id(
+    document_cache: &common::DocumentCache,
+    element: &common::ElementRcNode,
+    new_id: &str,
+) -> Result<(lsp_types::WorkspaceEdit, DropData), String> {
+    let new_id = new_id.trim();
+    let (path, offset) = element.path_and_offset();
+    let (old_id, taken, source_file) = element.with_element_node(|node| {
+        let sub_element = node.parent().filter(|p| p.kind() == SyntaxKind::SubElement);
+        (
+            sub_element.map(|s| s.child_token(SyntaxKind::Identifier)),
+            component_ids(node),
+            node.source_file.clone(),
+        )
+    });
+    let Some(old_id) = old_id else {
+        return Err("The root element of a component can not have an id".into());
+    };
+
+    let normalized = i_slint_compiler::parser::normalize_identifier(new_id);
+    if old_id.as_ref().map(|t| i_slint_compiler::parser::normalize_identifier(t.text()))
+        == Some(normalized.clone())
+    {
+        return Err(format!("The id is {new_id} already"));
+    }
+    if !new_id.is_empty() {
+        let tokens = i_slint_compiler::lexer::lex(new_id);
+        if tokens.len() != 1
+            || tokens[0].kind != SyntaxKind::Identifier
+            || RESERVED_IDS.contains(&normalized.as_str())
+        {
+            return Err(format!("{new_id} is not a valid id"));
+        }
+        if taken.iter().any(|id| i_slint_compiler::parser::normalize_identifier(id) == normalized) {
+            return Err(format!("The id {new_id} is used in the component already"));
+        }
+    }
+
+    let edits = match (&old_id, new_id.is_empty()) {
+        (None, true) => return Err("The element has no id".into()),
+        (None, false) => vec![(TextRange::empty(offset), format!("{new_id} := "))],
+        (Some(old_id), true) => {
+            let references =
+                crate::language::find_element_id_for_highlight(old_id, &old_id.parent())
+                    .unwrap_or_default();
+            if references.len() > 1 {
+                return Err(format!("{} is still referred to", old_id.text()));
+            }
+            vec![(TextRange::new(old_id.text_range().start(), offset), String::new())]
+        }
+        (Some(old_id), false) => {
+            crate::language::find_element_id_for_highlight(old_id, &old_id.parent())
+                .ok_or_else(|| format!("Can not rename {}", old_id.text()))?
+                .into_iter()
+                .map(|range| (range, new_id.to_string()))
+                .collect()
+        }
     };
 
-    let new_text = {
-        let element_text_lines = extract_text_of_element(element, &["x", "y"]);
+    // The element moves by what got changed in front of it
+    let selection_offset = edits
+        .iter()
+        .filter(|(range, _)| range.end() <= offset)
+        .fold(offset, |o, (range, text)| o + TextSize::of(text.as_str()) - range.len());
 
-        if element_text_lines.is_empty() {
-            String::new()
-        } else {
-            let mut tmp = format!(
-                "{}{}\n",
-                drop_info.insert_info.pre_indent,
-                element_text_lines.first().expect("Not empty")
-            );
-
-            for l in element_text_lines.iter().take(element_text_lines.len() - 1).skip(1) {
-                tmp.push_str(&format!("{}{l}\n", drop_info.insert_info.indent));
-            }
+    let edits = edits
+        .into_iter()
+        .map(|(range, new_text)| {
+            lsp_types::TextEdit::new(util::text_range_to_lsp_range(&source_file, range), new_text)
+        })
+        .collect();
+    let edit = common::create_workspace_edit_from_path(document_cache, &path, edits)
+        .ok_or_else(|| format!("Can not edit {path:?}"))?;
+    Ok((edit, DropData { selection_offset, path }))
+}
 
-            if element_text_lines.len() >= 2 {
-                tmp.push_str(&format!(
-                    "{}{}\n{}",
-                    drop_info.insert_info.indent,
-                    element_text_lines.last().expect("Length was checked"),
-                    drop_info.insert_info.post_indent
-                ));
+// Replace all identifiers that got renamed in `text`
+//
+// Names of properties and callbacks can be the same as an id, so identifiers following a `.`
+// or followed by `:`, `<=>`, `=>` or `(` stay as they are.
+fn rename_identifiers(text: &str, renames: &HashMap<SmolStr, SmolStr>) -> String {
+    if renames.is_empty() {
+        return text.to_string();
+    }
+    let tokens = i_slint_compiler::lexer::lex(text);
+    let is_trivia = |k: SyntaxKind| matches!(k, SyntaxKind::Whitespace | SyntaxKind::Comment);
+    let is_id_reference = |index: usize| {
+        let previous = tokens[..index].iter().rev().find(|t| !is_trivia(t.kind));
+        let next = tokens[index + 1..].iter().find(|t| !is_trivia(t.kind));
+        previous.is_none_or(|t| t.kind != SyntaxKind::Dot)
+            && next.is_none_or(|t| {
+                !matches!(
+                    t.kind,
+                    SyntaxKind::Colon
+                        | SyntaxKind::DoubleArrow
+                        | SyntaxKind::FatArrow
+                        | SyntaxKind::LParent
+                )
+            })
+    };
+    tokens
+        .iter()
+        .enumerate()
+        .map(|(i, t)| match renames.get(&t.text) {
+            Some(new_id) if t.kind == SyntaxKind::Identifier && is_id_reference(i) => {
+                new_id.as_str()
             }
+            _ => t.text.as_str(),
+        })
+        .collect()
+}
+
+/// How far a duplicate gets moved from its original, so that it does not hide it
+const DUPLICATE_OFFSET: f32 = 10.0;
+
+/// Insert a copy of `element` right after it
+///
+/// The ids declared in the copy get renamed to be unique in the component, and the references
+/// to them inside of the copy follow. `position` is the position of `element` relative to its
+/// parent when it is not in a layout: The copy gets moved a bit from there.
+pub fn duplicate_element(
+    document_cache: &common::DocumentCache,
+    element: &common::ElementRcNode,
+    position: Option<LogicalPoint>,
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    // The root element has no place to put a copy
+    if element.with_decorated_node(|node| node.parent())?.kind() == SyntaxKind::Component {
+        return None;
+    }
 
-            tmp
+    let (path, element_offset) = element.path_and_offset();
+    let (decorated_range, used_ids, ids) = element
+        .with_decorated_node(|node| (node.text_range(), component_ids(&node), declared_ids(&node)));
+    let indent = util::find_element_indent(element).unwrap_or_default();
+
+    let mut replacements = vec![];
+    if let Some(position) = position {
+        let (bindings, lbrace) = element.with_element_node(|node| {
+            let bindings = ["x", "y"].map(|name| {
+                let binding = node
+                    .Binding()
+                    .find(|b| b.child_text(SyntaxKind::Identifier).as_deref() == Some(name));
+                let two_way = node
+                    .TwoWayBinding()
+                    .any(|b| b.child_text(SyntaxKind::Identifier).as_deref() == Some(name));
+                (binding.map(|b| b.BindingExpression().text_range()), two_way)
+            });
+            let lbrace = node.child_token(SyntaxKind::LBrace).map(|t| {
+                let is_multiline = t
+                    .next_token()
+                    .is_some_and(|n| n.kind() == SyntaxKind::Whitespace && n.text().contains('\n'));
+                (t.text_range().end(), is_multiline)
+            });
+            (bindings, lbrace)
+        });
+        let (lbrace_end, is_multiline) = lbrace?;
+
+        let mut missing = String::new();
+        let values = [position.x, position.y].map(|v| (v + DUPLICATE_OFFSET).round());
+        for ((name, value), (binding, two_way)) in ["x", "y"].iter().zip(values).zip(bindings) {
+            match binding {
+                Some(range) => replacements.push((range, format!("{value}px;"))),
+                None if two_way => {}
+                None if is_multiline => missing += &format!("\n{indent}    {name}: {value}px;"),
+                None => missing += &format!(" {name}: {value}px;"),
+            }
         }
-    };
+        if !missing.is_empty() {
+            replacements.push((TextRange::empty(lbrace_end), missing));
+        }
+    }
 
-    let (path, _) = drop_info.target_element_node.path_and_offset();
+    let renames = unique_id_renames(&ids, &used_ids);
+    let lines = extract_text_of_element_with_replacements(element, replacements);
+    let text = rename_identifiers(&lines.join("\n"), &renames);
+    let new_text = text
+        .split('\n')
+        .map(|l| if l.is_empty() { String::new() } else { format!("{indent}{l}") })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // The id in front of the element might have gotten renamed
+    let prefix = element.with_decorated_node(|node| {
+        let start = usize::from(element_offset - node.text_range().start());
+        node.text().to_string()[..start].to_string()
+    });
+    let prefix = rename_identifiers(&prefix, &renames);
+    let selection_offset = decorated_range.end()
+        + TextSize::of('\n')
+        + TextSize::of(indent.as_str())
+        + TextSize::of(prefix.as_str());
+
+    let source_file = element.with_element_node(|node| node.source_file.clone());
+    let insert_position = util::text_size_to_lsp_position(&source_file, decorated_range.end());
+    let edit = common::create_workspace_edit_from_path(
+        document_cache,
+        &path,
+        vec![lsp_types::TextEdit::new(
+            lsp_types::Range::new(insert_position, insert_position),
+            format!("\n{new_text}"),
+        )],
+    )?;
+    workspace_edit_compiles(document_cache, &edit)
+        .then_some((edit, DropData { selection_offset, path }))
+}
 
-    let document_cache = preview::document_cache()?;
+fn create_paste_element_workspace_edit(
+    document_cache: &common::DocumentCache,
+    drop_info: &DropInformation,
+    clipboard: &ElementClipboard,
+) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
+    let (path, _) = drop_info.target_element_node.path_and_offset();
     let doc = document_cache.get_document_by_path(&path)?;
     let source_file = doc.node.as_ref().unwrap().source_file.clone();
 
+    let used_ids = drop_info.target_element_node.with_element_node(|node| component_ids(node));
+    let renames = unique_id_renames(&clipboard.ids, &used_ids);
+    let lines = rename_identifiers(&clipboard.lines.join("\n"), &renames)
+        .split('\n')
+        .map(|l| l.to_string())
+        .collect::<Vec<_>>();
+    let new_text = indented_element_text(&lines, &drop_info.insert_info);
+
     let mut selection_offset = drop_info.insert_info.insertion_position.offset()
         + TextSize::new(
             new_text.chars().take_while(|c| c.is_whitespace()).map(|c| c.len_utf8()).sum::<usize>()
@@ -1187,40 +2357,24 @@ pub fn create_move_element_workspace_edit(
         );
 
     let mut edits = Vec::with_capacity(3);
-
-    let remove_me = element.with_decorated_node(|node| {
-        node_removal_text_edit(&document_cache, &node, placeholder_text.clone())
-    })?;
-    if remove_me.url.to_file_path().as_ref().map(|p| p.as_path()) == Ok(source_file.path()) {
-        selection_offset = text_edit::TextOffsetAdjustment::new(&remove_me.edit, &source_file)
-            .adjust(selection_offset);
-    }
-    edits.push(remove_me);
-
-    if let Some(component_info) = preview::get_component_info(&component_type) {
+    if let Some(component_info) = preview::get_component_info(&clipboard.component_type) {
         let import_file =
             component_info.import_file_name(&lsp_types::Url::from_file_path(&path).ok());
-        if let Some(edit) = completion::create_import_edit(doc, &component_type, &import_file) {
-            if let Some(sf) = doc.node.as_ref().map(|n| &n.source_file) {
-                selection_offset =
-                    text_edit::TextOffsetAdjustment::new(&edit, sf).adjust(selection_offset);
-            }
-            edits.push(common::SingleTextEdit::from_path(
-                &document_cache,
-                source_file.path(),
-                edit,
-            )?);
+        if let Some(edit) =
+            completion::create_import_edit(doc, &clipboard.component_type, &import_file)
+        {
+            selection_offset =
+                text_edit::TextOffsetAdjustment::new(&edit, &source_file).adjust(selection_offset);
+            edits.push(edit);
         }
     }
 
     edits.extend(
         drop_ignored_elements_from_node(&drop_info.target_element_node, &source_file)
             .drain(..)
-            .filter_map(|te| {
-                // Abuse map somewhat...
-                selection_offset = text_edit::TextOffsetAdjustment::new(&te, &source_file)
-                    .adjust(selection_offset);
-                common::SingleTextEdit::from_path(&document_cache, source_file.path(), te)
+            .inspect(|te| {
+                selection_offset =
+                    text_edit::TextOffsetAdjustment::new(te, &source_file).adjust(selection_offset);
             }),
     );
 
@@ -1233,47 +2387,29 @@ pub fn create_move_element_workspace_edit(
         drop_info.insert_info.insertion_position.offset()
             + TextSize::new(drop_info.insert_info.replacement_range),
     );
-    edits.push(common::SingleTextEdit::from_path(
-        &document_cache,
-        source_file.path(),
-        lsp_types::TextEdit { range: lsp_types::Range::new(start_pos, end_pos), new_text },
-    )?);
+    edits.push(lsp_types::TextEdit { range: lsp_types::Range::new(start_pos, end_pos), new_text });
 
     Some((
-        common::create_workspace_edit_from_single_text_edits(edits),
+        common::create_workspace_edit_from_path(document_cache, source_file.path(), edits)?,
         DropData { selection_offset, path },
     ))
 }
 
-/// Find a location in a file that would be a good place to insert the new component at
+/// Insert the element from the `clipboard` into the element found at `position`
 ///
 /// Return a WorkspaceEdit to send to the editor and extra info for the live preview in
 /// the DropData struct.
-pub fn move_element_to(
+pub fn paste_at(
     document_cache: &common::DocumentCache,
-    element: common::ElementRcNode,
-    instance_index: usize,
     position: LogicalPoint,
-    mouse_position: LogicalPoint,
+    clipboard: &ElementClipboard,
 ) -> Option<(lsp_types::WorkspaceEdit, DropData)> {
     let component_instance = preview::component_instance()?;
-    let Some(drop_info) = find_move_location(
-        &component_instance,
-        mouse_position,
-        &element,
-        &element.component_type(),
-    ) else {
-        // Can not drop here: Ignore the move
-        return None;
-    };
-    create_move_element_workspace_edit(
-        &component_instance,
-        &drop_info,
-        &element,
-        instance_index,
-        position,
-    )
-    .and_then(|(e, d)| workspace_edit_compiles(document_cache, &e).then_some((e, d)))
+
+    let drop_info = find_drop_location(&component_instance, position, &clipboard.component_type)?;
+
+    create_paste_element_workspace_edit(document_cache, &drop_info, clipboard)
+        .and_then(|(e, d)| workspace_edit_compiles(document_cache, &e).then_some((e, d)))
 }
 
 #[cfg(test)]
@@ -1281,6 +2417,7 @@ mod tests {
     use i_slint_compiler::parser::{TextRange, TextSize};
     use lsp_types::Url;
 
+    use smol_str::SmolStr;
     use std::collections::HashMap;
 
     use crate::{
@@ -1587,4 +2724,502 @@ fn test_add_new_component_into_struct_sp_nl_sp_component() {
             26,
         );
     }
+
+    #[test]
+    fn test_unique_id_renames() {
+        let used = ["foo", "bar", "bar-2"].into_iter().map(SmolStr::from).collect();
+        let ids = ["foo", "baz", "bar-2", "root"].map(SmolStr::from);
+
+        let renames = super::unique_id_renames(&ids, &used);
+        assert_eq!(renames.len(), 3);
+        assert_eq!(renames.get("foo").unwrap(), "foo-2");
+        assert_eq!(renames.get("bar-2").unwrap(), "bar-3");
+        assert_eq!(renames.get("root").unwrap(), "root-2");
+        assert!(!renames.contains_key("baz"));
+
+        // Unused ids do not get renamed
+        assert!(super::unique_id_renames(&ids[1..2], &used).is_empty());
+    }
+
+    #[test]
+    fn test_suggested_id() {
+        assert_eq!(super::suggested_id("TouchArea", None), "touch-area");
+        assert_eq!(super::suggested_id("Button", Some("save")), "save-button");
+        assert_eq!(super::role_of("\"Save all changes\""), Some("save-all".into()));
+        assert_eq!(super::role_of("@tr(\"Hello {}\", name)"), Some("hello".into()));
+        assert_eq!(
+            super::role_of("@image-url(\"../icons/app_logo.svg\")"),
+            Some("app-logo".into())
+        );
+        assert_eq!(super::role_of("\"42 items\""), None);
+        assert_eq!(super::role_of("root.title"), None);
+    }
+
+    #[test]
+    fn test_assign_ids() {
+        let source = r#"export component Main {
+    touch-area := TouchArea { }
+    VerticalLayout {
+        Text { text: "Hello World"; }
+        TouchArea { }
+        TouchArea { }
+        title := Text { text: "Title"; }
+    }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let element = |text: &str, nth: usize| {
+            let offset = source.match_indices(text).nth(nth).unwrap().0;
+            document_cache.element_at_offset(&url, TextSize::new(offset as u32)).unwrap()
+        };
+        let elements = [
+            element("Main", 0),
+            element("Text {", 0),
+            element("TouchArea { }", 1),
+            element("TouchArea { }", 2),
+            element("Text {", 1),
+        ];
+
+        let (edit, drop_data) = super::assign_ids(&document_cache, &elements).unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert_eq!(
+            result[0].contents,
+            r#"export component Main {
+    touch-area := TouchArea { }
+    VerticalLayout {
+        hello-world-text := Text { text: "Hello World"; }
+        touch-area-2 := TouchArea { }
+        touch-area-3 := TouchArea { }
+        title := Text { text: "Title"; }
+    }
+}
+"#
+        );
+        // The root element is still selected
+        assert_eq!(drop_data.selection_offset, elements[0].path_and_offset().1);
+
+        // Nothing to do for the root element and elements with an id
+        assert!(super::assign_ids(&document_cache, &elements[..1]).is_none());
+        assert!(super::assign_ids(&document_cache, &[element("Text {", 1)]).is_none());
+    }
+
+    #[test]
+    fn test_copy_element() {
+        let source = r#"export component Main {
+    Rectangle {
+        row := Rectangle {
+            x: 10px;
+            label := Text { text: "a"; x: 5px; }
+            Text { text: label.text + "label"; }
+        }
+    }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let offset = TextSize::new(source.find("Rectangle {\n            x").unwrap() as u32);
+        let element = document_cache.element_at_offset(&url, offset).unwrap();
+
+        let clipboard = super::copy_element(&element);
+        assert_eq!(clipboard.component_type, "Rectangle");
+        assert_eq!(clipboard.ids, vec![SmolStr::from("row"), SmolStr::from("label")]);
+        assert_eq!(
+            clipboard.lines,
+            vec![
+                "row := Rectangle {",
+                "    label := Text { text: \"a\"; x: 5px; }",
+                "    Text { text: label.text + \"label\"; }",
+                "}"
+            ]
+        );
+
+        let used = ["row", "label"].into_iter().map(SmolStr::from).collect();
+        let renames = super::unique_id_renames(&clipboard.ids, &used);
+        assert_eq!(
+            super::rename_identifiers(&clipboard.lines.join("\n"), &renames),
+            "row-2 := Rectangle {\n    label-2 := Text { text: \"a\"; x: 5px; }\n    Text { text: label-2.text + \"label\"; }\n}"
+        );
+    }
+    #[test]
+    fn test_rename_element_id() {
+        let source = r#"export component Main {
+    Rectangle {
+        a := Rectangle { }
+        Text { width: a.width; }
+        Rectangle { height: 5px; }
+    }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let element_at = |needle: &str| {
+            let offset = TextSize::new(source.find(needle).unwrap() as u32);
+            document_cache.element_at_offset(&url, offset).unwrap()
+        };
+        let a = element_at("Rectangle { }");
+        let other = element_at("Rectangle { height");
+
+        let (edit, drop_data) = super::rename_element_id(&document_cache, &a, "card").unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert!(result[0].contents.contains("card := Rectangle { }"));
+        assert!(result[0].contents.contains("width: card.width;"));
+        let selection_offset = result[0].contents.find("Rectangle { }").unwrap();
+        assert_eq!(drop_data.selection_offset, TextSize::new(selection_offset as u32));
+
+        let (edit, drop_data) = super::rename_element_id(&document_cache, &other, "box").unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert!(result[0].contents.contains("box := Rectangle { height: 5px; }"));
+        let selection_offset = result[0].contents.find("Rectangle { height").unwrap();
+        assert_eq!(drop_data.selection_offset, TextSize::new(selection_offset as u32));
+
+        // `a` is still in use
+        assert!(super::rename_element_id(&document_cache, &a, "").is_err());
+        assert!(super::rename_element_id(&document_cache, &other, "a").is_err());
+        assert!(super::rename_element_id(&document_cache, &other, "root").is_err());
+        assert!(super::rename_element_id(&document_cache, &other, "two words").is_err());
+        let root = element_at("Rectangle {\n        a").parent().unwrap();
+        assert!(super::rename_element_id(&document_cache, &root, "main").is_err());
+    }
+
+    #[test]
+    fn test_duplicate_element() {
+        let source = r#"export component Main {
+    Rectangle {
+        card := Rectangle {
+            x: 10px;
+            width: 20px;
+            text := Text { text: "a"; }
+            Text { text: text.text; }
+        }
+        Rectangle { }
+    }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let element_at = |needle: &str| {
+            let offset = TextSize::new(source.find(needle).unwrap() as u32);
+            document_cache.element_at_offset(&url, offset).unwrap()
+        };
+
+        let card = element_at("Rectangle {\n            x");
+        let (edit, drop_data) = super::duplicate_element(
+            &document_cache,
+            &card,
+            Some(super::LogicalPoint::new(10.0, 0.0)),
+        )
+        .unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert_eq!(
+            result[0].contents,
+            r#"export component Main {
+    Rectangle {
+        card := Rectangle {
+            x: 10px;
+            width: 20px;
+            text := Text { text: "a"; }
+            Text { text: text.text; }
+        }
+        card-2 := Rectangle {
+            y: 10px;
+            x: 20px;
+            width: 20px;
+            text-2 := Text { text: "a"; }
+            Text { text: text-2.text; }
+        }
+        Rectangle { }
+    }
+}
+"#
+        );
+        let selection_offset = result[0].contents.find("Rectangle {\n            y").unwrap();
+        assert_eq!(drop_data.selection_offset, TextSize::new(selection_offset as u32));
+
+        // In a layout, the copy stays where the layout puts it
+        let empty = element_at("Rectangle { }");
+        let (edit, _) = super::duplicate_element(&document_cache, &empty, None).unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert!(result[0].contents.contains("Rectangle { }\n        Rectangle { }\n"));
+
+        // The root element can not be duplicated
+        let root = element_at("Rectangle {\n        card");
+        let root = root.parent().unwrap();
+        assert!(super::duplicate_element(&document_cache, &root, None).is_none());
+    }
+
+    #[test]
+    fn test_wrap_in_layout() {
+        let source = r#"export component Main {
+    Rectangle {
+        a := Rectangle { x: 10px; y: 5px; width: 20px; }
+        Text { text: "between"; }
+        b := Text {
+            x: 40px;
+            text: "b";
+        }
+    }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let element_at = |needle: &str| {
+            let offset = TextSize::new(source.find(needle).unwrap() as u32);
+            document_cache.element_at_offset(&url, offset).unwrap()
+        };
+        let a = element_at("Rectangle { x: 10px");
+        let b = element_at("Text {\n            x");
+
+        let (edit, drop_data) = super::wrap_in_layout(
+            &document_cache,
+            &[b, a],
+            "VerticalLayout",
+            Some(super::LogicalPoint::new(10.0, 5.0)),
+        )
+        .unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert_eq!(
+            result[0].contents,
+            r#"export component Main {
+    Rectangle {
+        VerticalLayout {
+            x: 10px;
+            y: 5px;
+            a := Rectangle { width: 20px; }
+            b := Text {
+                text: "b";
+            }
+        }
+        Text { text: "between"; }
+    }
+}
+"#
+        );
+        assert_eq!(drop_data.selection_offset, TextSize::new(source.find("a := ").unwrap() as u32));
+
+        // Elements in different parents can not be wrapped together
+        let root = element_at("Rectangle {\n        a");
+        let a = element_at("Rectangle { x: 10px");
+        assert!(
+            super::wrap_in_layout(&document_cache, &[root, a], "HorizontalLayout", None).is_none()
+        );
+    }
+
+    #[test]
+    fn test_extract_component() {
+        let source = r#"export component Main {
+    in-out property <string> title: "Hello";
+    Rectangle {
+        header := Text { x: 10px; text: root.title; color: other.color; }
+        other := Text { text: "other"; }
+        Text { text: "between"; }
+        TouchArea { clicked => { root.title = "Clicked"; } }
+    }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let element_at = |needle: &str| {
+            let offset = TextSize::new(source.find(needle).unwrap() as u32);
+            document_cache.element_at_offset(&url, offset).unwrap()
+        };
+
+        // A single element becomes the root of the new component
+        let header = element_at("Text { x: 10px");
+        let (edit, drop_data) =
+            super::extract_component(&document_cache, &[header], "Header", None).unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert_eq!(
+            result[0].contents,
+            r#"component Header inherits Text {
+    in property <string> title;
+    in property <brush> other-color; text: root.title; color: root.other-color; }
+
+export component Main {
+    in-out property <string> title: "Hello";
+    Rectangle {
+        header := Header {
+            x: 10px;
+            title: root.title;
+            other-color: other.color;
+        }
+        other := Text { text: "other"; }
+        Text { text: "between"; }
+        TouchArea { clicked => { root.title = "Clicked"; } }
+    }
+}
+"#
+        );
+        assert_eq!(
+            drop_data.selection_offset,
+            TextSize::new(source.find("header := ").unwrap() as u32)
+        );
+
+        // Several elements become children of the new component, written properties are
+        // bound two-way
+        let between = element_at("Text { text: \"between\"");
+        let touch_area = element_at("TouchArea");
+        let (edit, _) =
+            super::extract_component(&document_cache, &[touch_area, between], "Parts", None)
+                .unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert_eq!(
+            result[0].contents,
+            r#"component Parts {
+    in-out property <string> title;
+    Text { text: "between"; }
+    TouchArea { clicked => { root.title = "Clicked"; } }
+}
+
+export component Main {
+    in-out property <string> title: "Hello";
+    Rectangle {
+        header := Text { x: 10px; text: root.title; color: other.color; }
+        other := Text { text: "other"; }
+        Parts {
+            title <=> root.title;
+        }
+    }
+}
+"#
+        );
+
+        // Elements in different parents can not be extracted together
+        let rectangle = element_at("Rectangle {");
+        let other = element_at("Text { text: \"other\"");
+        assert!(
+            super::extract_component(&document_cache, &[rectangle, other], "Other", None).is_none()
+        );
+    }
+
+    #[test]
+    fn test_add_responsive_variants() {
+        let source = r#"export component Empty { width: 100px; }
+export component Main inherits VerticalLayout {
+    padding: 10px;
+    title := Text { text: "Hello"; }
+    Text { text: title.text; }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let root = document_cache
+            .element_at_offset(&url, TextSize::new(source.find("Main").unwrap() as u32))
+            .unwrap();
+
+        let (edit, drop_data) = super::add_responsive_variants(&document_cache, &root).unwrap();
+        let result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        assert_eq!(
+            result[0].contents,
+            r#"export component Empty { width: 100px; }
+export component Main inherits VerticalLayout {
+    padding: 10px;
+    // The size classes: Adjust the breakpoints and the elements shown for each class
+    in property <length> medium-min-width: 600px;
+    in property <length> expanded-min-width: 840px;
+
+    // compact
+    if root.width < root.medium-min-width: VerticalLayout {
+        title := Text { text: "Hello"; }
+
+        Text { text: title.text; }
+    }
+
+    // medium
+    if root.width >= root.medium-min-width && root.width < root.expanded-min-width: VerticalLayout {
+        title-medium := Text { text: "Hello"; }
+
+        Text { text: title-medium.text; }
+    }
+
+    // expanded
+    if root.width >= root.expanded-min-width: VerticalLayout {
+        title-expanded := Text { text: "Hello"; }
+
+        Text { text: title-expanded.text; }
+    }
+}
+"#
+        );
+        assert!(result[0].contents[usize::from(drop_data.selection_offset)..]
+            .starts_with("if root.width < "));
+
+        // Nothing to arrange
+        let root = document_cache.element_at_offset(&url, TextSize::new(17)).unwrap();
+        assert!(super::add_responsive_variants(&document_cache, &root).is_none());
+    }
+
+    #[test]
+    fn test_extract_component_into_new_file() {
+        let source = r#"import { Button } from "std-widgets.slint";
+export component Main {
+    VerticalLayout {
+        Button { text: "Ok"; }
+    }
+}
+"#;
+        let url = Url::from_file_path(test::main_test_file_name()).unwrap();
+        let new_url = Url::from_file_path(test::test_file_name("my-button.slint")).unwrap();
+        let document_cache = test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), source.to_string())]),
+            false,
+        );
+        let button = document_cache
+            .element_at_offset(&url, TextSize::new(source.find("Button {").unwrap() as u32))
+            .unwrap();
+
+        let (edit, _) =
+            super::extract_component(&document_cache, &[button], "MyButton", Some(&new_url))
+                .unwrap();
+        assert_eq!(text_edit::created_files(&edit), vec![&new_url]);
+        let mut result = text_edit::apply_workspace_edit(&document_cache, &edit).unwrap();
+        result.sort_by_key(|r| r.url != url);
+        assert_eq!(
+            result[0].contents,
+            r#"import { Button } from "std-widgets.slint";
+import { MyButton } from "my-button.slint";
+export component Main {
+    VerticalLayout {
+        MyButton { }
+    }
+}
+"#
+        );
+        assert_eq!(
+            result[1].contents,
+            r#"import { Button } from "std-widgets.slint";
+
+export component MyButton inherits Button { text: "Ok"; }
+"#
+        );
+    }
 }
@@ -0,0 +1,130 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Easing curves for the curve editor: The easings built into Slint and `cubic-bezier(...)`,
+//! as the control points of the curve the editor shows.
+
+/// The control points of a cubic Bézier curve from (0, 0) to (1, 1): x1, y1, x2, y2
+pub type ControlPoints = [f32; 4];
+
+/// The easings built into Slint, with their control points. Elastic and bounce easings are no
+/// cubic Bézier curves, so the editor can not show them.
+pub const PRESETS: &[(&str, Option<ControlPoints>)] = &[
+    ("linear", Some([0.0, 0.0, 1.0, 1.0])),
+    ("ease", Some([0.25, 0.1, 0.25, 1.0])),
+    ("ease-in", Some([0.42, 0.0, 1.0, 1.0])),
+    ("ease-out", Some([0.0, 0.0, 0.58, 1.0])),
+    ("ease-in-out", Some([0.42, 0.0, 0.58, 1.0])),
+    ("ease-in-quad", Some([0.11, 0.0, 0.5, 0.0])),
+    ("ease-out-quad", Some([0.5, 1.0, 0.89, 1.0])),
+    ("ease-in-out-quad", Some([0.45, 0.0, 0.55, 1.0])),
+    ("ease-in-quart", Some([0.5, 0.0, 0.75, 0.0])),
+    ("ease-out-quart", Some([0.25, 1.0, 0.5, 1.0])),
+    ("ease-in-out-quart", Some([0.76, 0.0, 0.24, 1.0])),
+    ("ease-in-quint", Some([0.64, 0.0, 0.78, 0.0])),
+    ("ease-out-quint", Some([0.22, 1.0, 0.36, 1.0])),
+    ("ease-in-out-quint", Some([0.83, 0.0, 0.17, 1.0])),
+    ("ease-in-expo", Some([0.7, 0.0, 0.84, 0.0])),
+    ("ease-out-expo", Some([0.16, 1.0, 0.3, 1.0])),
+    ("ease-in-out-expo", Some([0.87, 0.0, 0.13, 1.0])),
+    ("ease-in-back", Some([0.36, 0.0, 0.66, -0.56])),
+    ("ease-out-back", Some([0.34, 1.56, 0.64, 1.0])),
+    ("ease-in-out-back", Some([0.68, -0.6, 0.32, 1.6])),
+    ("ease-in-sine", Some([0.12, 0.0, 0.39, 0.0])),
+    ("ease-out-sine", Some([0.61, 1.0, 0.88, 1.0])),
+    ("ease-in-out-sine", Some([0.37, 0.0, 0.63, 1.0])),
+    ("ease-in-circ", Some([0.55, 0.0, 1.0, 0.45])),
+    ("ease-out-circ", Some([0.0, 0.55, 0.45, 1.0])),
+    ("ease-in-out-circ", Some([0.85, 0.0, 0.15, 1.0])),
+    ("ease-in-elastic", None),
+    ("ease-out-elastic", None),
+    ("ease-in-out-elastic", None),
+    ("ease-in-bounce", None),
+    ("ease-out-bounce", None),
+    ("ease-in-out-bounce", None),
+];
+
+/// Control points this close to those of a preset are written as the preset
+const TOLERANCE: f32 = 0.005;
+
+/// Whether `code` is an easing the editor understands: A built-in one or a `cubic-bezier(...)`
+pub fn is_easing(code: &str) -> bool {
+    let code = code.trim();
+    PRESETS.iter().any(|(name, _)| *name == code) || parse_cubic_bezier(code).is_some()
+}
+
+fn parse_cubic_bezier(code: &str) -> Option<ControlPoints> {
+    let arguments = code.strip_prefix("cubic-bezier")?.trim_start().strip_prefix('(')?;
+    let arguments = arguments.strip_suffix(')')?;
+    let numbers = arguments
+        .split(',')
+        .map(|n| n.trim().parse::<f32>().ok().filter(|n| n.is_finite()))
+        .collect::<Option<Vec<_>>>()?;
+    numbers.try_into().ok()
+}
+
+/// The control points of the easing in `code`, `None` if it is no cubic Bézier curve
+pub fn control_points(code: &str) -> Option<ControlPoints> {
+    let code = code.trim();
+    PRESETS
+        .iter()
+        .find(|(name, _)| *name == code)
+        .map_or_else(|| parse_cubic_bezier(code), |(_, points)| *points)
+}
+
+fn format_number(n: f32) -> String {
+    let n = (n * 100.0).round() / 100.0;
+    if n == 0.0 {
+        "0".into()
+    } else {
+        n.to_string()
+    }
+}
+
+/// The code for the curve with the `points`: The name of a built-in easing if there is one
+/// for it, `cubic-bezier(...)` otherwise. The x coordinates get clamped to 0..=1, as Slint
+/// requires them to be.
+pub fn to_code(points: ControlPoints) -> String {
+    let [x1, y1, x2, y2] = points;
+    let points = [x1.clamp(0.0, 1.0), y1, x2.clamp(0.0, 1.0), y2];
+    let preset = PRESETS.iter().find(|(_, p)| {
+        p.is_some_and(|p| p.iter().zip(&points).all(|(a, b)| (a - b).abs() < TOLERANCE))
+    });
+    match preset {
+        Some((name, _)) => name.to_string(),
+        None => format!(
+            "cubic-bezier({})",
+            points.iter().map(|n| format_number(*n)).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_points() {
+        assert_eq!(control_points("ease-in"), Some([0.42, 0.0, 1.0, 1.0]));
+        assert_eq!(
+            control_points(" cubic-bezier(0.1, -0.5, 0.9,1.5) "),
+            Some([0.1, -0.5, 0.9, 1.5])
+        );
+        assert_eq!(control_points("ease-out-bounce"), None);
+        assert_eq!(control_points("cubic-bezier(0.1, 0.2, 0.3)"), None);
+        assert_eq!(control_points("root.easing"), None);
+
+        assert!(is_easing("ease-out-bounce"));
+        assert!(is_easing("cubic-bezier(0, 0, 1, 1)"));
+        assert!(!is_easing("root.easing"));
+    }
+
+    #[test]
+    fn test_to_code() {
+        assert_eq!(to_code([0.42, 0.0, 0.58, 1.0]), "ease-in-out");
+        assert_eq!(to_code([0.421, 0.001, 0.58, 1.0]), "ease-in-out");
+        assert_eq!(to_code([0.0, 0.0, 1.0, 1.0]), "linear");
+        assert_eq!(to_code([0.123, -0.5, 0.9, 1.25]), "cubic-bezier(0.12, -0.5, 0.9, 1.25)");
+        assert_eq!(to_code([-0.5, 0.0, 1.5, 0.3]), "cubic-bezier(0, 0, 1, 0.3)");
+    }
+}
@@ -0,0 +1,296 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! A small on-disk journal of workspace edits the preview sent to the editor
+//! but has not yet seen come back as a document update.
+//!
+//! Should the preview or the LSP go down before the edit got applied, the
+//! next session in the same workspace finds the entries and can offer to replay
+//! or discard them. Each entry remembers the text of the documents it changes, so
+//! that it only gets replayed onto the documents it was made for.
+//!
+//! The journal is written by a thread of its own, the UI thread only queues the
+//! changes to it.
+
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use lsp_types::{Url, WorkspaceEdit};
+
+use crate::common::text_edit;
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct JournalEntry {
+    pub label: String,
+    pub edit: WorkspaceEdit,
+    /// The documents changed by the edit, with their text before the edit
+    pub documents: Vec<(Url, String)>,
+}
+
+impl JournalEntry {
+    /// The entry for `edit`, with the current text of the documents it changes from `source`
+    pub fn new(
+        label: String,
+        edit: WorkspaceEdit,
+        source: impl Fn(&Url) -> Option<String>,
+    ) -> Self {
+        let created_files = text_edit::created_files(&edit);
+        let mut documents: Vec<(Url, String)> = Vec::new();
+        for (document, _) in text_edit::EditIterator::new(&edit) {
+            if created_files.contains(&&document.uri)
+                || documents.iter().any(|(url, _)| *url == document.uri)
+            {
+                continue;
+            }
+            let text = source(&document.uri).unwrap_or_default();
+            documents.push((document.uri, text));
+        }
+        Self { label, edit, documents }
+    }
+
+    /// Whether the documents still have the text the edit was made for, so that the
+    /// edit can be replayed onto them
+    pub fn applies_to(&self, source: impl Fn(&Url) -> Option<String>) -> bool {
+        self.documents.iter().all(|(url, text)| source(url).as_ref() == Some(text))
+    }
+
+    /// The edit without the document versions: The versions recorded in a previous
+    /// session are meaningless to the editor now, so a replayed edit must not be
+    /// rejected because of them.
+    pub fn unversioned_edit(&self) -> WorkspaceEdit {
+        let mut edit = self.edit.clone();
+        match edit.document_changes.as_mut() {
+            Some(lsp_types::DocumentChanges::Edits(edits)) => {
+                for e in edits.iter_mut() {
+                    e.text_document.version = None;
+                }
+            }
+            Some(lsp_types::DocumentChanges::Operations(ops)) => {
+                for op in ops.iter_mut() {
+                    if let lsp_types::DocumentChangeOperation::Edit(e) = op {
+                        e.text_document.version = None;
+                    }
+                }
+            }
+            None => {}
+        }
+        edit
+    }
+}
+
+/// The journal of the workspace in `project`
+#[cfg(not(target_arch = "wasm32"))]
+fn journal_path(project: &Path) -> PathBuf {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::hash::DefaultHasher::new();
+    project.hash(&mut hasher);
+    std::env::temp_dir().join(format!("slint-preview-edit-journal-{:016x}.json", hasher.finish()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_from(path: &Path) -> Vec<JournalEntry> {
+    std::fs::read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice::<Vec<JournalEntry>>(&data).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn store_to(path: &Path, entries: &[JournalEntry]) -> std::io::Result<()> {
+    if entries.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            r => r,
+        };
+    }
+    // Write to a temporary file first, so that a crash while writing does not
+    // leave a truncated journal behind.
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, serde_json::to_vec(entries)?)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type Recovered = Box<dyn FnOnce(Vec<JournalEntry>) + Send>;
+
+#[cfg(not(target_arch = "wasm32"))]
+enum Job {
+    Open(PathBuf, Recovered),
+    Record(JournalEntry),
+    Remove(JournalEntry),
+}
+
+/// The journal of one workspace, as the journal thread keeps it
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct Journal {
+    path: Option<PathBuf>,
+    entries: Vec<JournalEntry>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Journal {
+    fn run(&mut self, job: Job) {
+        match job {
+            Job::Open(path, recovered) => {
+                if self.path.as_ref() != Some(&path) {
+                    self.entries = load_from(&path);
+                    self.path = Some(path);
+                }
+                recovered(self.entries.clone());
+                return;
+            }
+            Job::Record(entry) => self.entries.push(entry),
+            Job::Remove(entry) => {
+                let Some(index) = self.entries.iter().position(|e| *e == entry) else {
+                    return;
+                };
+                self.entries.remove(index);
+            }
+        }
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(e) = store_to(path, &self.entries) {
+            eprintln!("Failed to write edit journal {path:?}: {e}");
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn queue(job: Job) {
+    static JOURNAL: std::sync::OnceLock<std::sync::mpsc::Sender<Job>> = std::sync::OnceLock::new();
+    let sender = JOURNAL.get_or_init(|| {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("Slint preview edit journal".into())
+            .spawn(move || {
+                let mut journal = Journal::default();
+                for job in receiver {
+                    journal.run(job);
+                }
+            })
+            .expect("Failed to start the edit journal thread");
+        sender
+    });
+    let _ = sender.send(job);
+}
+
+/// Switch to the journal of the workspace in `project`, passing the edits it holds
+/// to `recovered`. Nothing gets journaled before a workspace got opened.
+pub fn open(project: &Path, recovered: impl FnOnce(Vec<JournalEntry>) + Send + 'static) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = project;
+        recovered(Vec::new());
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    queue(Job::Open(journal_path(project), Box::new(recovered)));
+}
+
+/// Journal an edit before it gets sent to the editor
+pub fn record(entry: JournalEntry) {
+    #[cfg(not(target_arch = "wasm32"))]
+    queue(Job::Record(entry));
+    #[cfg(target_arch = "wasm32")]
+    let _ = entry;
+}
+
+/// Forget about an edit once the editor applied it, or it got discarded
+pub fn remove(entry: JournalEntry) {
+    #[cfg(not(target_arch = "wasm32"))]
+    queue(Job::Remove(entry));
+    #[cfg(target_arch = "wasm32")]
+    let _ = entry;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_edit(version: Option<i32>) -> WorkspaceEdit {
+        let uri = Url::parse("file:///tmp/test.slint").unwrap();
+        WorkspaceEdit {
+            document_changes: Some(lsp_types::DocumentChanges::Edits(vec![
+                lsp_types::TextDocumentEdit {
+                    text_document: lsp_types::OptionalVersionedTextDocumentIdentifier {
+                        uri,
+                        version,
+                    },
+                    edits: vec![lsp_types::OneOf::Left(lsp_types::TextEdit {
+                        range: lsp_types::Range::new(
+                            lsp_types::Position::new(1, 2),
+                            lsp_types::Position::new(1, 4),
+                        ),
+                        new_text: "Foo".to_string(),
+                    })],
+                },
+            ])),
+            ..Default::default()
+        }
+    }
+
+    fn test_entry(label: &str, version: Option<i32>) -> JournalEntry {
+        JournalEntry::new(label.to_string(), test_edit(version), |_| Some("Old".into()))
+    }
+
+    #[test]
+    fn test_journal_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("slint-preview-edit-journal-test-{}.json", std::process::id()));
+
+        assert!(load_from(&path).is_empty());
+
+        let entries = vec![test_entry("Move element", Some(3)), test_entry("Delete element", None)];
+        store_to(&path, &entries).unwrap();
+        assert_eq!(load_from(&path), entries);
+
+        store_to(&path, &[]).unwrap();
+        assert!(!path.exists());
+        assert!(load_from(&path).is_empty());
+        // Clearing twice is fine
+        store_to(&path, &[]).unwrap();
+
+        // A garbage journal is ignored
+        std::fs::write(&path, b"{ not json").unwrap();
+        assert!(load_from(&path).is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_remove_acknowledged_entry() {
+        let path = std::env::temp_dir()
+            .join(format!("slint-preview-edit-journal-remove-{}.json", std::process::id()));
+        let first = test_entry("Move element", Some(3));
+        let second = test_entry("Delete element", Some(4));
+        store_to(&path, &[first.clone(), second.clone()]).unwrap();
+
+        let mut journal = Journal::default();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        journal
+            .run(Job::Open(path.clone(), Box::new(move |entries| sender.send(entries).unwrap())));
+        assert_eq!(receiver.recv().unwrap(), vec![first.clone(), second.clone()]);
+
+        journal.run(Job::Remove(second));
+        assert_eq!(load_from(&path), vec![first.clone()]);
+        journal.run(Job::Remove(first));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_replay_checks() {
+        let entry = test_entry("Move element", Some(42));
+        assert_eq!(
+            entry.documents,
+            vec![(Url::parse("file:///tmp/test.slint").unwrap(), "Old".into())]
+        );
+        assert!(entry.applies_to(|_| Some("Old".into())));
+        assert!(!entry.applies_to(|_| Some("Changed".into())));
+        assert!(!entry.applies_to(|_| None));
+
+        assert_eq!(entry.unversioned_edit(), test_edit(None));
+        assert_eq!(test_entry("Move element", None).unversioned_edit(), test_edit(None));
+    }
+}
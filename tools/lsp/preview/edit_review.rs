@@ -0,0 +1,244 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Review of the edits that remove or restructure code, like deleting elements or extracting
+//! them into a component, before they go to the editor: Each text edit is shown as a hunk of a
+//! unified diff that can be accepted or rejected on its own.
+
+use i_slint_compiler::diagnostics::SourceFileInner;
+use lsp_types::{TextEdit, Url, WorkspaceEdit};
+
+use crate::common::{self, text_edit};
+
+/// The number of unchanged lines shown before and after the changed ones
+const CONTEXT_LINES: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineKind {
+    Context,
+    Removed,
+    Added,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hunk {
+    pub url: Url,
+    /// Like `@@ -3,5 +3,2 @@`
+    pub header: String,
+    pub lines: Vec<(LineKind, String)>,
+    pub accepted: bool,
+}
+
+/// An edit waiting for the user to review it
+pub struct PendingEdit {
+    pub label: String,
+    pub edit: WorkspaceEdit,
+    /// One per text edit, in the order of `text_edit::EditIterator`
+    pub hunks: Vec<Hunk>,
+    /// Whether the edit needs to compile to be applied
+    pub test_edit: bool,
+}
+
+impl PendingEdit {
+    /// Prepare the `edit` for review, with the sources of the documents it changes from `source`
+    pub fn new(
+        label: String,
+        edit: WorkspaceEdit,
+        test_edit: bool,
+        source: impl Fn(&Url) -> Option<String>,
+    ) -> Self {
+        let edits = text_edit::EditIterator::new(&edit).collect::<Vec<_>>();
+        let hunks = edits
+            .iter()
+            .map(|(document, e)| {
+                let source = source(&document.uri).unwrap_or_default();
+                // Earlier edits in the document move the lines of this one
+                let line_delta = edits
+                    .iter()
+                    .filter(|(d, other)| d.uri == document.uri && other.range.end <= e.range.start)
+                    .filter(|(_, other)| !std::ptr::eq(*other, *e))
+                    .map(|(_, other)| line_growth(&source, other))
+                    .sum();
+                hunk(document.uri.clone(), &source, e, line_delta)
+            })
+            .collect();
+        Self { label, edit, hunks, test_edit }
+    }
+
+    pub fn is_partially_accepted(&self) -> bool {
+        self.hunks.iter().any(|h| !h.accepted)
+    }
+
+    /// The edit made of the accepted hunks, `None` if there is none of them
+    pub fn accepted_edit(&self) -> Option<WorkspaceEdit> {
+        let created_files = text_edit::created_files(&self.edit);
+        let mut builder = common::WorkspaceEditBuilder::new(self.label.clone());
+        for ((document, edit), hunk) in text_edit::EditIterator::new(&self.edit).zip(&self.hunks) {
+            if !hunk.accepted {
+                continue;
+            }
+            if created_files.contains(&&document.uri) {
+                builder.create_file(document.uri, edit.new_text.clone());
+            } else {
+                builder.add_text_edit(document.uri, document.version, edit.clone());
+            }
+        }
+        if builder.is_empty() {
+            return None;
+        }
+        builder.build().ok().map(|(_, edit)| edit)
+    }
+}
+
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// The byte range of `edit` in `source`, and that of the lines it touches
+fn ranges(source: &str, edit: &TextEdit) -> (usize, usize, usize, usize) {
+    let source_file = SourceFileInner::new(Default::default(), source.to_string());
+    let position = |p: lsp_types::Position| {
+        floor_char_boundary(
+            source,
+            source_file.offset(p.line as usize + 1, p.character as usize + 1),
+        )
+    };
+    let start = position(edit.range.start);
+    let end = position(edit.range.end).max(start);
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[end..].find('\n').map_or(source.len(), |i| end + i);
+    (start, end, line_start, line_end)
+}
+
+/// How many lines `edit` adds to `source`, negative if it removes lines
+fn line_growth(source: &str, edit: &TextEdit) -> isize {
+    let (start, end, _, _) = ranges(source, edit);
+    edit.new_text.matches('\n').count() as isize - source[start..end].matches('\n').count() as isize
+}
+
+fn hunk(url: Url, source: &str, edit: &TextEdit, line_delta: isize) -> Hunk {
+    let (start, end, line_start, line_end) = ranges(source, edit);
+    let old_text = &source[line_start..line_end];
+    let new_text =
+        format!("{}{}{}", &source[line_start..start], edit.new_text, &source[end..line_end]);
+
+    let mut old_lines = old_text.split('\n').collect::<Vec<_>>();
+    let mut new_lines = new_text.split('\n').collect::<Vec<_>>();
+    // A new file has no lines yet
+    if source.is_empty() {
+        old_lines.clear();
+    }
+
+    // Lines the edit keeps as they are are context
+    let common_prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let common_suffix = old_lines[common_prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[common_prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    old_lines.truncate(old_lines.len() - common_suffix);
+    new_lines.truncate(new_lines.len() - common_suffix);
+    old_lines.drain(..common_prefix);
+    new_lines.drain(..common_prefix);
+
+    let all_lines = source.lines().collect::<Vec<_>>();
+    let first_changed = source[..line_start].matches('\n').count() + common_prefix;
+    let context_start = first_changed.saturating_sub(CONTEXT_LINES);
+    let after_changed = first_changed + old_lines.len();
+    let context_end = (after_changed + CONTEXT_LINES).min(all_lines.len()).max(after_changed);
+
+    let before = all_lines.get(context_start..first_changed).unwrap_or_default();
+    let after = all_lines.get(after_changed..context_end).unwrap_or_default();
+
+    let line = |kind, text: &&str| (kind, text.trim_end_matches('\r').to_string());
+    let lines = before
+        .iter()
+        .map(|l| line(LineKind::Context, l))
+        .chain(old_lines.iter().map(|l| line(LineKind::Removed, l)))
+        .chain(new_lines.iter().map(|l| line(LineKind::Added, l)))
+        .chain(after.iter().map(|l| line(LineKind::Context, l)))
+        .collect::<Vec<_>>();
+
+    let context = before.len() + after.len();
+    let old_start = context_start + 1;
+    let new_start = (old_start as isize + line_delta).max(1);
+    let header = format!(
+        "@@ -{old_start},{} +{new_start},{} @@",
+        old_lines.len() + context,
+        new_lines.len() + context
+    );
+    Hunk { url, header, lines, accepted: true }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: (u32, u32), end: (u32, u32), new_text: &str) -> TextEdit {
+        TextEdit {
+            range: lsp_types::Range::new(
+                lsp_types::Position::new(start.0, start.1),
+                lsp_types::Position::new(end.0, end.1),
+            ),
+            new_text: new_text.into(),
+        }
+    }
+
+    const SOURCE: &str =
+        "export component Main {\n    Rectangle {\n        Text { }\n    }\n    Image { }\n}\n";
+
+    fn pending(edits: Vec<TextEdit>) -> PendingEdit {
+        let url = Url::parse("file:///main.slint").unwrap();
+        let edit = common::create_workspace_edit(url, Some(1), edits);
+        PendingEdit::new("Delete elements".into(), edit, false, |_| Some(SOURCE.into()))
+    }
+
+    #[test]
+    fn test_hunks() {
+        // Delete the Rectangle with its child, and the Image
+        let review =
+            pending(vec![edit((1, 4), (3, 5), ""), edit((4, 4), (4, 13), "Placeholder { }")]);
+        assert_eq!(review.hunks.len(), 2);
+
+        let hunk = &review.hunks[0];
+        assert_eq!(hunk.header, "@@ -1,6 +1,4 @@");
+        assert_eq!(
+            hunk.lines,
+            [
+                (LineKind::Context, "export component Main {".into()),
+                (LineKind::Removed, "    Rectangle {".into()),
+                (LineKind::Removed, "        Text { }".into()),
+                (LineKind::Removed, "    }".into()),
+                (LineKind::Added, "    ".into()),
+                (LineKind::Context, "    Image { }".into()),
+                (LineKind::Context, "}".into()),
+            ]
+        );
+
+        // The lines of the second hunk moved up by the deletion of the first one
+        assert_eq!(review.hunks[1].header, "@@ -3,4 +1,4 @@");
+        assert!(review.hunks[1].lines.contains(&(LineKind::Added, "    Placeholder { }".into())));
+    }
+
+    #[test]
+    fn test_accepted_edit() {
+        let mut review =
+            pending(vec![edit((1, 4), (3, 5), ""), edit((4, 4), (4, 13), "Placeholder { }")]);
+        assert!(!review.is_partially_accepted());
+
+        review.hunks[0].accepted = false;
+        assert!(review.is_partially_accepted());
+        let accepted = review.accepted_edit().unwrap();
+        let edits =
+            text_edit::EditIterator::new(&accepted).map(|(_, e)| e.clone()).collect::<Vec<_>>();
+        assert_eq!(edits, [edit((4, 4), (4, 13), "Placeholder { }")]);
+
+        review.hunks[1].accepted = false;
+        assert_eq!(review.accepted_edit(), None);
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Elements locked from the outline, to protect the finished parts of a screen while working on
+//! others: Locked elements and their children can not be selected in the preview, nor be moved
+//! or deleted from it. Locks are kept in the project settings, so elements are remembered by
+//! their place in the element tree of their component rather than by their offset in the file.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, SyntaxNode};
+
+use crate::common;
+
+/// An element, by the file and the component it is in, and the indices of the children leading
+/// to it from the root element of the component
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ElementAddress {
+    pub path: PathBuf,
+    pub component: String,
+    pub children: Vec<usize>,
+}
+
+impl ElementAddress {
+    /// Whether this is the element at `other` or one of its ancestors
+    fn contains(&self, other: &ElementAddress) -> bool {
+        self.path == other.path
+            && self.component == other.component
+            && other.children.starts_with(&self.children)
+    }
+}
+
+thread_local! {static LOCKED: RefCell<Vec<ElementAddress>> = RefCell::default();}
+
+fn is_child(node: &SyntaxNode) -> bool {
+    matches!(
+        node.kind(),
+        SyntaxKind::SubElement | SyntaxKind::RepeatedElement | SyntaxKind::ConditionalElement
+    )
+}
+
+/// The address of the `element` in the file at `path`. `None` for the root element of a
+/// component, which can not be locked.
+pub fn address(path: &Path, element: &SyntaxNode) -> Option<ElementAddress> {
+    let mut children = vec![];
+    let mut element = element.clone();
+    let component = loop {
+        let parent = element.parent()?;
+        if let Some(component) = syntax_nodes::Component::new(parent.clone()) {
+            break component.DeclaredIdentifier().text().to_string().trim().to_string();
+        }
+        if parent.kind() != SyntaxKind::SubElement {
+            return None;
+        }
+        // `for` and `if` wrap the element
+        let child = parent.parent().filter(is_child).unwrap_or(parent);
+        let container = child.parent().filter(|p| p.kind() == SyntaxKind::Element)?;
+        children.push(container.children().filter(is_child).position(|c| c.node == child.node)?);
+        element = container;
+    };
+    if children.is_empty() {
+        return None;
+    }
+    children.reverse();
+    Some(ElementAddress { path: path.to_path_buf(), component, children })
+}
+
+/// The element reached from the `root` element of a component by the indices of `children`, as
+/// `address` collects them
+pub fn element_below(
+    root: &common::ElementRcNode,
+    children: &[usize],
+) -> Option<common::ElementRcNode> {
+    let mut element: SyntaxNode = root.with_element_node(|node| node.clone().into());
+    for index in children {
+        let child = element.children().filter(is_child).nth(*index)?;
+        // `for` and `if` wrap the element
+        let sub_element = if child.kind() == SyntaxKind::SubElement {
+            child
+        } else {
+            child.children().find(|c| c.kind() == SyntaxKind::SubElement)?
+        };
+        element = sub_element.children().find(|c| c.kind() == SyntaxKind::Element)?;
+    }
+    let (path, _) = root.path_and_offset();
+    common::ElementRcNode::find_in_or_below(
+        root.as_element().clone(),
+        &path,
+        element.text_range().start().into(),
+    )
+}
+
+fn address_of(element_node: &common::ElementRcNode) -> Option<ElementAddress> {
+    let (path, _) = element_node.path_and_offset();
+    element_node.with_element_node(|node| address(&path, node))
+}
+
+/// Whether the element at `address` or one of its ancestors is locked
+pub fn is_locked_at(address: &ElementAddress) -> bool {
+    LOCKED.with(|locked| locked.borrow().iter().any(|l| l.contains(address)))
+}
+
+/// Whether the element at `address` is locked because one of its ancestors is
+pub fn is_locked_by_ancestor(address: &ElementAddress) -> bool {
+    LOCKED.with(|locked| locked.borrow().iter().any(|l| l != address && l.contains(address)))
+}
+
+/// Whether the element is protected against changes from the preview
+pub fn is_locked(element_node: &common::ElementRcNode) -> bool {
+    address_of(element_node).is_some_and(|address| is_locked_at(&address))
+}
+
+/// Lock the element at `address`, or unlock it
+pub fn toggle(address: &ElementAddress) {
+    LOCKED.with(|locked| {
+        let mut locked = locked.borrow_mut();
+        if let Some(index) = locked.iter().position(|l| l == address) {
+            locked.remove(index);
+        } else {
+            locked.push(address.clone());
+        }
+    });
+}
+
+/// The locks to keep in the settings of the `project`, with paths relative to it
+pub fn project_locks(project: &Path) -> Vec<ElementAddress> {
+    LOCKED.with(|locked| {
+        locked
+            .borrow()
+            .iter()
+            .filter_map(|l| {
+                let path = l.path.strip_prefix(project).ok()?.to_path_buf();
+                Some(ElementAddress { path, ..l.clone() })
+            })
+            .collect()
+    })
+}
+
+/// Lock the elements kept in the settings of the `project`, instead of those locked before
+pub fn restore(project: &Path, locks: &[ElementAddress]) {
+    let locks =
+        locks.iter().map(|l| ElementAddress { path: project.join(&l.path), ..l.clone() }).collect();
+    LOCKED.with(|locked| *locked.borrow_mut() = locks);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use i_slint_compiler::parser::TextSize;
+    use lsp_types::Url;
+    use std::collections::HashMap;
+
+    const SOURCE: &str = r#"
+export component Main {
+    VerticalLayout {
+        title := Text { text: "Hello"; }
+        for i in 3: Rectangle { }
+        if true: Image {
+            Rectangle { }
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_address() {
+        let url = Url::from_file_path(common::test::main_test_file_name()).unwrap();
+        let document_cache = common::test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), SOURCE.to_string())]),
+            false,
+        );
+        let element_at = |text: &str| {
+            let offset = TextSize::new(SOURCE.find(text).unwrap() as u32);
+            document_cache.element_at_offset(&url, offset).unwrap()
+        };
+        let address_at =
+            |text: &str| address_of(&element_at(text)).map(|a| (a.component, a.children));
+
+        assert_eq!(address_at("{\n    VerticalLayout"), None);
+        assert_eq!(address_at("VerticalLayout"), Some(("Main".into(), vec![0])));
+        assert_eq!(address_at("Text {"), Some(("Main".into(), vec![0, 0])));
+        assert_eq!(address_at("Rectangle { }\n        if"), Some(("Main".into(), vec![0, 1])));
+        assert_eq!(address_at("Image"), Some(("Main".into(), vec![0, 2])));
+        assert_eq!(address_at("Rectangle { }\n        }"), Some(("Main".into(), vec![0, 2, 0])));
+
+        let root = element_at("{\n    VerticalLayout");
+        let element = element_below(&root, &[0, 2, 0]).unwrap();
+        assert_eq!(
+            element.path_and_offset(),
+            element_at("Rectangle { }\n        }").path_and_offset()
+        );
+        assert!(element_below(&root, &[0, 3]).is_none());
+    }
+
+    #[test]
+    fn test_locks() {
+        let project = PathBuf::from("/project");
+        let at = |children: Vec<usize>| ElementAddress {
+            path: project.join("ui/main.slint"),
+            component: "Main".into(),
+            children,
+        };
+        restore(&project, &[]);
+
+        toggle(&at(vec![0, 2]));
+        assert!(is_locked_at(&at(vec![0, 2])));
+        assert!(is_locked_at(&at(vec![0, 2, 0])));
+        assert!(is_locked_by_ancestor(&at(vec![0, 2, 0])));
+        assert!(!is_locked_by_ancestor(&at(vec![0, 2])));
+        assert!(!is_locked_at(&at(vec![0])));
+        assert!(!is_locked_at(&at(vec![0, 1])));
+        assert!(!is_locked_at(&ElementAddress { component: "Other".into(), ..at(vec![0, 2]) }));
+
+        let locks = project_locks(&project);
+        assert_eq!(locks, vec![ElementAddress { path: "ui/main.slint".into(), ..at(vec![0, 2]) }]);
+
+        toggle(&at(vec![0, 2]));
+        assert!(!is_locked_at(&at(vec![0, 2, 0])));
+
+        restore(&project, &locks);
+        assert!(is_locked_at(&at(vec![0, 2])));
+    }
+}
@@ -7,7 +7,7 @@
     object_tree::ElementRc,
     parser::{SyntaxKind, TextSize},
 };
-use i_slint_core::lengths::{LogicalPoint, LogicalRect};
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
 use slint_interpreter::{ComponentHandle, ComponentInstance};
 
 use crate::common;
@@ -495,9 +495,14 @@ fn filter_nodes_for_selection(
         return None;
     }
 
-    selection_candidate.as_element_node().filter(|en| {
-        en.with_element_node(|n| n.parent().map_or(true, |p| p.kind() != SyntaxKind::Component))
-    })
+    selection_candidate.as_element_node().filter(is_selectable)
+}
+
+// Elements that are the root of a component can not get selected
+fn is_selectable(element_node: &common::ElementRcNode) -> bool {
+    element_node
+        .with_element_node(|n| n.parent().is_none_or(|p| p.kind() != SyntaxKind::Component))
+        && !super::element_locks::is_locked(element_node)
 }
 
 pub fn select_element_behind_impl(
@@ -561,6 +566,90 @@ pub fn select_element_behind(x: f32, y: f32, enter_component: bool, reverse: boo
     select_element_node(&component_instance, &en, Some(position));
 }
 
+pub fn parent_element_impl(
+    selected_element_node: &common::ElementRcNode,
+) -> Option<common::ElementRcNode> {
+    selected_element_node.parent().filter(is_selectable)
+}
+
+pub fn first_child_element_impl(
+    component_instance: &ComponentInstance,
+    selected_element_node: Option<&common::ElementRcNode>,
+) -> Option<common::ElementRcNode> {
+    let children = if let Some(selected_element_node) = selected_element_node {
+        selected_element_node.children()
+    } else {
+        // Nothing selected yet: Start with the children of the root element
+        let root_element = root_element(component_instance);
+        let debug_count = root_element.borrow().debug.len();
+        (0..debug_count)
+            .filter_map(|i| common::ElementRcNode::new(root_element.clone(), i))
+            .flat_map(|en| en.children())
+            .collect()
+    };
+
+    children.into_iter().find(is_selectable)
+}
+
+pub fn sibling_element_impl(
+    selected_element_node: &common::ElementRcNode,
+    reverse: bool,
+) -> Option<common::ElementRcNode> {
+    let siblings = selected_element_node.parent()?.children();
+    let current = selected_element_node.path_and_offset();
+    let current_position = siblings.iter().position(|en| en.path_and_offset() == current)?;
+
+    if reverse {
+        siblings[..current_position].iter().rev().find(|en| is_selectable(en)).cloned()
+    } else {
+        siblings[current_position + 1..].iter().find(|en| is_selectable(en)).cloned()
+    }
+}
+
+// Called from UI thread!
+pub fn select_parent_element() {
+    let Some(component_instance) = super::component_instance() else {
+        return;
+    };
+    let Some(en) = super::selected_element()
+        .and_then(|sel| sel.as_element_node())
+        .and_then(|en| parent_element_impl(&en))
+    else {
+        return;
+    };
+
+    select_element_node(&component_instance, &en, None);
+}
+
+// Called from UI thread!
+pub fn select_first_child_element() {
+    let Some(component_instance) = super::component_instance() else {
+        return;
+    };
+    let selected_element_node = super::selected_element().and_then(|sel| sel.as_element_node());
+    let Some(en) = first_child_element_impl(&component_instance, selected_element_node.as_ref())
+    else {
+        return;
+    };
+
+    select_element_node(&component_instance, &en, None);
+}
+
+// Called from UI thread!
+pub fn select_sibling_element(reverse: bool) {
+    let Some(component_instance) = super::component_instance() else {
+        return;
+    };
+    let Some(en) = super::selected_element()
+        .and_then(|sel| sel.as_element_node())
+        .and_then(|en| sibling_element_impl(&en, reverse))
+    else {
+        return;
+    };
+
+    select_element_node(&component_instance, &en, None);
+}
+
 // Called from UI thread!
 pub fn reselect_element() {
     let Some(selected) = super::selected_element() else {
@@ -572,7 +661,336 @@ pub fn reselect_element() {
     };
     let positions = component_instance.component_positions(&selected.path, selected.offset.into());
 
-    super::set_selected_element(Some(selected), &positions, SelectionNotification::Never);
+    super::set_selected_elements(
+        Some(selected),
+        super::selected_group(),
+        &positions,
+        SelectionNotification::Never,
+    );
+}
+
+fn element_selection_of(element_node: &common::ElementRcNode) -> ElementSelection {
+    let (path, offset) = element_node.path_and_offset();
+    ElementSelection { path, offset, instance_index: 0 }
+}
+
+/// Add the element at `x`/`y` to the selection, or remove it if it is already selected
+// Called from UI thread!
+pub fn toggle_select_element_at(x: f32, y: f32, enter_component: bool) {
+    let Some(component_instance) = super::component_instance() else {
+        return;
+    };
+
+    let position = LogicalPoint::new(x, y);
+
+    let Some(en) = select_element_at_impl(&component_instance, position, enter_component) else {
+        return;
+    };
+
+    let Some(primary) = super::selected_element() else {
+        select_element_node(&component_instance, &en, Some(position));
+        return;
+    };
+
+    let mut selection = vec![primary];
+    selection.extend(super::selected_group());
+
+    let (path, offset) = en.path_and_offset();
+    if let Some(index) = selection.iter().position(|s| s.path == path && s.offset == offset) {
+        selection.remove(index);
+    } else {
+        selection.push(element_selection_of(&en));
+    }
+
+    select_elements(&component_instance, selection);
+}
+
+fn select_elements(component_instance: &ComponentInstance, mut selection: Vec<ElementSelection>) {
+    if selection.is_empty() {
+        unselect_element();
+        return;
+    }
+    let primary = selection.remove(0);
+    let positions = component_instance.component_positions(&primary.path, primary.offset.into());
+    super::set_selected_elements(
+        Some(primary),
+        selection,
+        &positions,
+        SelectionNotification::Never,
+    );
+}
+
+fn collect_element_nodes_in_rect(
+    component_instance: &ComponentInstance,
+    rect: &LogicalRect,
+    element_node: common::ElementRcNode,
+    result: &mut Vec<common::ElementRcNode>,
+) {
+    if is_selectable(&element_node)
+        && element_node.geometries(component_instance).iter().any(|g| rect.contains_rect(g))
+    {
+        // Do not bother with the children: They move along with their parent anyway
+        result.push(element_node);
+        return;
+    }
+
+    for c in element_node.children() {
+        collect_element_nodes_in_rect(component_instance, rect, c, result);
+    }
+}
+
+/// Find the outermost elements of the previewed component that are fully inside `rect`
+pub fn element_nodes_in_rect(
+    component_instance: &ComponentInstance,
+    rect: LogicalRect,
+) -> Vec<common::ElementRcNode> {
+    let root_element = root_element(component_instance);
+    let debug_count = root_element.borrow().debug.len();
+
+    let mut result = Vec::new();
+    for c in (0..debug_count)
+        .filter_map(|i| common::ElementRcNode::new(root_element.clone(), i))
+        .flat_map(|en| en.children())
+    {
+        collect_element_nodes_in_rect(component_instance, &rect, c, &mut result);
+    }
+    result
+}
+
+/// Select all elements inside of the rectangle spanned by `x`, `y`, `width` and `height`
+// Called from UI thread!
+pub fn select_elements_in_rect(x: f32, y: f32, width: f32, height: f32) {
+    let Some(component_instance) = super::component_instance() else {
+        return;
+    };
+
+    let rect = LogicalRect::new(LogicalPoint::new(x, y), LogicalSize::new(width, height));
+    let selection = element_nodes_in_rect(&component_instance, rect)
+        .iter()
+        .map(element_selection_of)
+        .collect::<Vec<_>>();
+
+    select_elements(&component_instance, selection);
+}
+
+/// The lines a moved or resized element can snap to: The edges and centers of
+/// its parent and siblings
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapTargets {
+    /// x coordinates of vertical lines
+    pub vertical: Vec<f32>,
+    /// y coordinates of horizontal lines
+    pub horizontal: Vec<f32>,
+}
+
+impl SnapTargets {
+    fn add(&mut self, rect: &LogicalRect) {
+        self.vertical.extend([rect.min_x(), rect.center().x, rect.max_x()]);
+        self.horizontal.extend([rect.min_y(), rect.center().y, rect.max_y()]);
+    }
+}
+
+/// A line an element got aligned to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlignmentGuide {
+    Vertical(f32),
+    Horizontal(f32),
+}
+
+/// Collect the edges and centers of the parent and siblings of `element`
+pub fn snap_targets(
+    component_instance: &ComponentInstance,
+    element: &common::ElementRcNode,
+) -> SnapTargets {
+    let mut targets = SnapTargets::default();
+    let Some(parent) = element.parent() else {
+        return targets;
+    };
+    let self_path = element.path_and_offset();
+
+    parent.geometries(component_instance).iter().for_each(|g| targets.add(g));
+    for sibling in parent.children().iter().filter(|c| c.path_and_offset() != self_path) {
+        sibling.geometries(component_instance).iter().for_each(|g| targets.add(g));
+    }
+    targets
+}
+
+/// The smallest offset moving one of `points` onto one of `targets`, if that is
+/// within `threshold`. Returns the offset and the target.
+fn closest_snap(points: &[f32], targets: &[f32], threshold: f32) -> Option<(f32, f32)> {
+    points
+        .iter()
+        .flat_map(|p| targets.iter().map(move |t| (t - p, *t)))
+        .filter(|(delta, _)| delta.abs() <= threshold)
+        .min_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))
+}
+
+fn grid_snap(value: f32, grid: f32) -> f32 {
+    (value / grid).round() * grid - value
+}
+
+/// Snap one axis: `start` and `end` are the edges of the rectangle, `changed` tells
+/// which of them got dragged (both when moving)
+fn snap_axis(
+    start: f32,
+    end: f32,
+    changed: (bool, bool),
+    targets: &[f32],
+    grid: Option<f32>,
+    threshold: f32,
+    guides: &mut Vec<f32>,
+) -> (f32, f32) {
+    let mut snap = |points: &[f32]| {
+        if let Some((delta, target)) = closest_snap(points, targets, threshold) {
+            guides.push(target);
+            delta
+        } else {
+            grid.map(|grid| grid_snap(points[0], grid)).unwrap_or_default()
+        }
+    };
+
+    match changed {
+        (true, true) => {
+            let delta = snap(&[start, (start + end) / 2.0, end]);
+            (start + delta, end + delta)
+        }
+        (true, false) => (start + snap(&[start]), end),
+        (false, true) => (start, end + snap(&[end])),
+        (false, false) => (start, end),
+    }
+}
+
+/// Snap `rect` onto the closest target within `threshold` or, failing that, onto
+/// the `grid`. When `resizing`, only the edges differing from `original` move.
+///
+/// Returns the snapped rectangle and the guides it got aligned to.
+pub fn snap_rect(
+    targets: &SnapTargets,
+    original: LogicalRect,
+    rect: LogicalRect,
+    resizing: bool,
+    grid: Option<f32>,
+    threshold: f32,
+) -> (LogicalRect, Vec<AlignmentGuide>) {
+    let changed = |old: f32, new: f32| !resizing || old != new;
+
+    let mut vertical = vec![];
+    let (x1, x2) = snap_axis(
+        rect.min_x(),
+        rect.max_x(),
+        (changed(original.min_x(), rect.min_x()), changed(original.max_x(), rect.max_x())),
+        &targets.vertical,
+        grid,
+        threshold,
+        &mut vertical,
+    );
+    let mut horizontal = vec![];
+    let (y1, y2) = snap_axis(
+        rect.min_y(),
+        rect.max_y(),
+        (changed(original.min_y(), rect.min_y()), changed(original.max_y(), rect.max_y())),
+        &targets.horizontal,
+        grid,
+        threshold,
+        &mut horizontal,
+    );
+
+    let guides = vertical
+        .into_iter()
+        .map(AlignmentGuide::Vertical)
+        .chain(horizontal.into_iter().map(AlignmentGuide::Horizontal))
+        .collect();
+    (
+        LogicalRect::new(
+            LogicalPoint::new(x1, y1),
+            LogicalSize::new((x2 - x1).max(0.0), (y2 - y1).max(0.0)),
+        ),
+        guides,
+    )
+}
+
+/// A distance between two elements, shown as a line from `start` to `end`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measurement {
+    pub start: LogicalPoint,
+    pub end: LogicalPoint,
+}
+
+impl Measurement {
+    pub fn distance(&self) -> f32 {
+        (self.end - self.start).length()
+    }
+}
+
+/// The distances between the ranges `from` and `to` on one axis: The gap between
+/// them if they do not overlap, the distances between their edges otherwise
+fn axis_distances(from: (f32, f32), to: (f32, f32)) -> Vec<(f32, f32)> {
+    if from.1 <= to.0 {
+        vec![(from.1, to.0)]
+    } else if to.1 <= from.0 {
+        vec![(to.1, from.0)]
+    } else {
+        [(from.0, to.0), (from.1, to.1)]
+            .into_iter()
+            .filter(|(a, b)| a != b)
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect()
+    }
+}
+
+/// Where to draw the lines measuring the other axis: In the middle of the range
+/// covered by both `from` and `to`, or in the middle of `from` if there is none
+fn cross_position(from: (f32, f32), to: (f32, f32)) -> f32 {
+    let (start, end) = (from.0.max(to.0), from.1.min(to.1));
+    if start <= end {
+        (start + end) / 2.0
+    } else {
+        (from.0 + from.1) / 2.0
+    }
+}
+
+fn is_separated(from: (f32, f32), to: (f32, f32)) -> bool {
+    from.1 <= to.0 || to.1 <= from.0
+}
+
+/// The horizontal and vertical distances between the rectangles `from` and `to`
+///
+/// Rectangles next to each other only get the gap between them measured.
+pub fn measure(from: &LogicalRect, to: &LogicalRect) -> Vec<Measurement> {
+    let (from_x, to_x) = ((from.min_x(), from.max_x()), (to.min_x(), to.max_x()));
+    let (from_y, to_y) = ((from.min_y(), from.max_y()), (to.min_y(), to.max_y()));
+    let (separated_x, separated_y) = (is_separated(from_x, to_x), is_separated(from_y, to_y));
+
+    let mut result = vec![];
+    if separated_x || !separated_y {
+        let y = cross_position(from_y, to_y);
+        result.extend(axis_distances(from_x, to_x).into_iter().map(|(x1, x2)| Measurement {
+            start: LogicalPoint::new(x1, y),
+            end: LogicalPoint::new(x2, y),
+        }));
+    }
+    if separated_y || !separated_x {
+        let x = cross_position(from_x, to_x);
+        result.extend(axis_distances(from_y, to_y).into_iter().map(|(y1, y2)| Measurement {
+            start: LogicalPoint::new(x, y1),
+            end: LogicalPoint::new(x, y2),
+        }));
+    }
+    result
+}
+
+/// The geometry of the element at `position` to measure the distance to from
+/// `selected_element`: The topmost selectable element that is not the selected one
+pub fn measure_target_at(
+    component_instance: &ComponentInstance,
+    selected_element: &common::ElementRcNode,
+    position: LogicalPoint,
+) -> Option<LogicalRect> {
+    collect_all_element_nodes_covering(position, component_instance)
+        .into_iter()
+        .filter(|sc| !sc.is_selected_element_node(selected_element))
+        .find(|sc| filter_nodes_for_selection(sc, false).is_some())
+        .map(|sc| sc.geometry)
 }
 
 #[cfg(test)]
@@ -581,7 +999,7 @@ mod tests {
 
     use std::path::PathBuf;
 
-    use i_slint_core::lengths::LogicalPoint;
+    use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
     use slint_interpreter::ComponentInstance;
 
     fn demo_app() -> ComponentInstance {
@@ -851,4 +1269,215 @@ fn test_element_selection() {
         )
         .is_none());
     }
+
+    #[test]
+    fn test_element_navigation() {
+        let component_instance = demo_app();
+        let test_file = test::test_file_name("test_data.slint");
+        let root_element = super::root_element(&component_instance);
+
+        let node_at = |offset: u32| {
+            crate::common::ElementRcNode::find_in_or_below(root_element.clone(), &test_file, offset)
+                .unwrap()
+        };
+        let offset_of = |en: Option<crate::common::ElementRcNode>| {
+            en.map(|en| u32::from(en.path_and_offset().1))
+        };
+
+        // Nothing selected: Enter the root element
+        assert_eq!(
+            offset_of(super::first_child_element_impl(&component_instance, None)),
+            Some(160)
+        );
+
+        // Walk down the tree
+        assert_eq!(
+            offset_of(super::first_child_element_impl(&component_instance, Some(&node_at(160)))),
+            Some(194)
+        );
+        assert_eq!(
+            offset_of(super::first_child_element_impl(&component_instance, Some(&node_at(194)))),
+            Some(225)
+        );
+        assert_eq!(
+            offset_of(super::first_child_element_impl(&component_instance, Some(&node_at(225)))),
+            Some(264)
+        );
+        assert_eq!(
+            offset_of(super::first_child_element_impl(&component_instance, Some(&node_at(264)))),
+            None
+        );
+
+        // Walk up the tree, but never select the root of the component
+        assert_eq!(offset_of(super::parent_element_impl(&node_at(264))), Some(225));
+        assert_eq!(offset_of(super::parent_element_impl(&node_at(194))), Some(160));
+        assert_eq!(offset_of(super::parent_element_impl(&node_at(160))), None);
+
+        // No siblings anywhere
+        assert_eq!(offset_of(super::sibling_element_impl(&node_at(194), false)), None);
+        assert_eq!(offset_of(super::sibling_element_impl(&node_at(194), true)), None);
+    }
+
+    #[test]
+    fn test_element_sibling_navigation() {
+        let source = r#"export component Main {
+    width: 200px;
+    height: 200px;
+
+    VerticalLayout {
+        Rectangle { }
+        Text { }
+        TouchArea { }
+    }
+}
+"#;
+        let component_instance = crate::preview::test::interpret_test("fluent", source);
+        let test_file = test::test_file_name("test_data.slint");
+        let root_element = super::root_element(&component_instance);
+
+        let offset_in_source = |text: &str| u32::try_from(source.find(text).unwrap()).unwrap();
+        let node_at = |text: &str| {
+            crate::common::ElementRcNode::find_in_or_below(
+                root_element.clone(),
+                &test_file,
+                offset_in_source(text),
+            )
+            .unwrap()
+        };
+        let offset_of = |en: Option<crate::common::ElementRcNode>| {
+            en.map(|en| u32::from(en.path_and_offset().1))
+        };
+
+        let rectangle = Some(offset_in_source("Rectangle"));
+        let text = Some(offset_in_source("Text"));
+        let touch_area = Some(offset_in_source("TouchArea"));
+
+        assert_eq!(offset_of(super::sibling_element_impl(&node_at("Rectangle"), true)), None);
+        assert_eq!(offset_of(super::sibling_element_impl(&node_at("Rectangle"), false)), text);
+        assert_eq!(offset_of(super::sibling_element_impl(&node_at("Text"), false)), touch_area);
+        assert_eq!(offset_of(super::sibling_element_impl(&node_at("Text"), true)), rectangle);
+        assert_eq!(offset_of(super::sibling_element_impl(&node_at("TouchArea"), false)), None);
+        assert_eq!(
+            offset_of(super::parent_element_impl(&node_at("Text"))),
+            Some(offset_in_source("VerticalLayout"))
+        );
+    }
+
+    #[test]
+    fn test_element_nodes_in_rect() {
+        let source = r#"export component Main {
+    width: 200px;
+    height: 200px;
+
+    Rectangle {
+        x: 10px;
+        y: 10px;
+        width: 50px;
+        height: 50px;
+
+        Text { x: 20px; y: 20px; width: 10px; height: 10px; }
+    }
+    TouchArea { x: 100px; y: 100px; width: 50px; height: 50px; }
+}
+"#;
+        let component_instance = crate::preview::test::interpret_test("fluent", source);
+
+        let offset_in_source = |text: &str| u32::try_from(source.find(text).unwrap()).unwrap();
+        let in_rect = |x: f32, y: f32, width: f32, height: f32| {
+            super::element_nodes_in_rect(
+                &component_instance,
+                LogicalRect::new(LogicalPoint::new(x, y), LogicalSize::new(width, height)),
+            )
+            .iter()
+            .map(|en| u32::from(en.path_and_offset().1))
+            .collect::<Vec<_>>()
+        };
+
+        let rectangle = offset_in_source("Rectangle");
+        let text = offset_in_source("Text");
+        let touch_area = offset_in_source("TouchArea");
+
+        // Children of selected elements are not selected separately
+        assert_eq!(in_rect(0.0, 0.0, 200.0, 200.0), vec![rectangle, touch_area]);
+        assert_eq!(in_rect(0.0, 0.0, 80.0, 80.0), vec![rectangle]);
+        // Partially covered elements are not selected, but their children can be
+        assert_eq!(in_rect(20.0, 20.0, 150.0, 150.0), vec![text, touch_area]);
+        assert!(in_rect(70.0, 70.0, 20.0, 20.0).is_empty());
+    }
+
+    #[test]
+    fn test_snap_rect() {
+        use super::{snap_rect, AlignmentGuide, SnapTargets};
+
+        let rect = |x: f32, y: f32, w: f32, h: f32| {
+            LogicalRect::new(LogicalPoint::new(x, y), LogicalSize::new(w, h))
+        };
+        // A parent at 0,0 200x200 and a sibling at 50,50 20x20
+        let targets = SnapTargets {
+            vertical: vec![0.0, 100.0, 200.0, 50.0, 60.0, 70.0],
+            horizontal: vec![0.0, 100.0, 200.0, 50.0, 60.0, 70.0],
+        };
+        let original = rect(10.0, 10.0, 10.0, 10.0);
+
+        // Moving: The right edge snaps to the sibling's left edge, nothing snaps vertically
+        assert_eq!(
+            snap_rect(&targets, original, rect(38.0, 120.0, 10.0, 10.0), false, None, 4.0),
+            (rect(40.0, 120.0, 10.0, 10.0), vec![AlignmentGuide::Vertical(50.0)])
+        );
+        // Moving: Centers snap, the grid is used for the axis without a close target
+        assert_eq!(
+            snap_rect(&targets, original, rect(97.0, 121.0, 10.0, 10.0), false, Some(8.0), 4.0),
+            (rect(95.0, 120.0, 10.0, 10.0), vec![AlignmentGuide::Vertical(100.0)])
+        );
+        // Resizing: Only the dragged edge moves
+        assert_eq!(
+            snap_rect(&targets, original, rect(10.0, 10.0, 38.0, 10.0), true, None, 4.0),
+            (rect(10.0, 10.0, 40.0, 10.0), vec![AlignmentGuide::Vertical(50.0)])
+        );
+        assert_eq!(
+            snap_rect(&targets, original, rect(10.0, 3.0, 10.0, 17.0), true, Some(8.0), 2.0),
+            (rect(10.0, 0.0, 10.0, 20.0), vec![])
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        use super::measure;
+
+        let rect = |x: f32, y: f32, w: f32, h: f32| {
+            LogicalRect::new(LogicalPoint::new(x, y), LogicalSize::new(w, h))
+        };
+        let lines = |from: LogicalRect, to: LogicalRect| {
+            measure(&from, &to)
+                .into_iter()
+                .map(|m| (m.start.x, m.start.y, m.end.x, m.end.y, m.distance()))
+                .collect::<Vec<_>>()
+        };
+
+        // Side by side: The horizontal gap is measured in the middle of the shared range
+        assert_eq!(
+            lines(rect(0.0, 0.0, 10.0, 20.0), rect(30.0, 10.0, 10.0, 20.0)),
+            vec![(10.0, 15.0, 30.0, 15.0, 20.0)]
+        );
+        // Diagonal: Both gaps are measured from the middle of the selection
+        assert_eq!(
+            lines(rect(0.0, 0.0, 10.0, 10.0), rect(20.0, 30.0, 10.0, 10.0)),
+            vec![(10.0, 5.0, 20.0, 5.0, 10.0), (5.0, 10.0, 5.0, 30.0, 20.0)]
+        );
+        // Inside of the other element: Distances to all its edges
+        assert_eq!(
+            lines(rect(10.0, 20.0, 10.0, 10.0), rect(0.0, 0.0, 100.0, 50.0)),
+            vec![
+                (0.0, 25.0, 10.0, 25.0, 10.0),
+                (20.0, 25.0, 100.0, 25.0, 80.0),
+                (15.0, 0.0, 15.0, 20.0, 20.0),
+                (15.0, 30.0, 15.0, 50.0, 20.0),
+            ]
+        );
+        // Aligned edges are not measured
+        assert_eq!(
+            lines(rect(0.0, 0.0, 10.0, 10.0), rect(0.0, 0.0, 10.0, 20.0)),
+            vec![(5.0, 10.0, 5.0, 20.0, 10.0)]
+        );
+    }
 }
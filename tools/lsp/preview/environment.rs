@@ -0,0 +1,125 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Synthetic sensor and environment inputs for the preview: A previewed document exporting
+//! a global called [`GLOBAL_NAME`] gets its well-known properties set to the values picked in
+//! the preview, so that device UIs can be tried in all conditions without the hardware.
+
+use slint_interpreter::{ComponentInstance, Value, ValueType};
+
+/// The name of the global the preview sets the properties of
+pub const GLOBAL_NAME: &str = "PreviewEnvironment";
+
+/// The state of the simulated environment
+#[derive(Clone, Debug, PartialEq)]
+pub struct Environment {
+    /// Between 0 and 1
+    pub battery_level: f32,
+    pub battery_charging: bool,
+    pub network_connected: bool,
+    /// `wifi`, `cellular` or `ethernet`
+    pub network_type: String,
+    /// In lux
+    pub ambient_light: f32,
+    pub hour: i32,
+    pub minute: i32,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            battery_level: 1.0,
+            battery_charging: false,
+            network_connected: true,
+            network_type: "wifi".into(),
+            ambient_light: 300.0,
+            hour: 12,
+            minute: 0,
+        }
+    }
+}
+
+impl Environment {
+    /// The properties of the global with their values
+    fn values(&self) -> [(&'static str, Value); 7] {
+        [
+            ("battery-level", Value::Number(self.battery_level as f64)),
+            ("battery-charging", Value::Bool(self.battery_charging)),
+            ("network-connected", Value::Bool(self.network_connected)),
+            ("network-type", Value::String(self.network_type.as_str().into())),
+            ("ambient-light", Value::Number(self.ambient_light as f64)),
+            ("hour", Value::Number(self.hour as f64)),
+            ("minute", Value::Number(self.minute as f64)),
+        ]
+    }
+}
+
+fn value_type(value: &Value) -> ValueType {
+    match value {
+        Value::Bool(_) => ValueType::Bool,
+        Value::String(_) => ValueType::String,
+        _ => ValueType::Number,
+    }
+}
+
+/// Whether the previewed component has the global the environment gets set on
+pub fn has_global(component_instance: &ComponentInstance) -> bool {
+    component_instance.definition().globals().any(|g| g == GLOBAL_NAME)
+}
+
+/// Set the properties of the [`GLOBAL_NAME`] global that are declared with the expected type
+pub fn apply(component_instance: &ComponentInstance, environment: &Environment) {
+    let Some(declared) = component_instance
+        .definition()
+        .global_properties(GLOBAL_NAME)
+        .map(|properties| properties.collect::<Vec<_>>())
+    else {
+        return;
+    };
+    for (name, value) in environment.values() {
+        let expected = value_type(&value);
+        if declared.iter().any(|(n, t)| n == name && *t == expected) {
+            let _ = component_instance.set_global_property(GLOBAL_NAME, name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let source = r#"
+            export global PreviewEnvironment {
+                in property <float> battery-level;
+                in property <bool> network-connected;
+                in property <string> network-type;
+                // The wrong type is left alone
+                in property <string> hour: "noon";
+            }
+            export component Main {
+                out property <string> status: PreviewEnvironment.network-connected
+                    ? "\{PreviewEnvironment.network-type} \{PreviewEnvironment.battery-level * 100}%"
+                    : "offline";
+            }
+        "#;
+        let instance = crate::preview::test::interpret_test("fluent", source);
+        assert!(has_global(&instance));
+
+        let environment = Environment {
+            battery_level: 0.5,
+            network_type: "cellular".into(),
+            ..Default::default()
+        };
+        apply(&instance, &environment);
+        assert_eq!(instance.get_property("status").unwrap(), Value::String("cellular 50%".into()));
+        assert_eq!(
+            instance.get_global_property(GLOBAL_NAME, "hour").unwrap(),
+            Value::String("noon".into())
+        );
+
+        apply(&instance, &Environment { network_connected: false, ..Default::default() });
+        assert_eq!(instance.get_property("status").unwrap(), Value::String("offline".into()));
+    }
+}
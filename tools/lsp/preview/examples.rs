@@ -0,0 +1,108 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Small example projects to start from, opened in a scratch workspace outside of the
+//! user's projects
+
+use std::path::PathBuf;
+
+pub struct Example {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub file_name: &'static str,
+    pub source: &'static str,
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "Hello World",
+        description: "A window with some text",
+        file_name: "hello-world.slint",
+        source: include_str!("examples/hello-world.slint"),
+    },
+    Example {
+        name: "Counter",
+        description: "A button changing a property",
+        file_name: "counter.slint",
+        source: include_str!("examples/counter.slint"),
+    },
+    Example {
+        name: "Login Form",
+        description: "Input fields arranged in a grid, with two-way bindings",
+        file_name: "login-form.slint",
+        source: include_str!("examples/login-form.slint"),
+    },
+    Example {
+        name: "Todo List",
+        description: "A list of structs shown with a repeater",
+        file_name: "todo-list.slint",
+        source: include_str!("examples/todo-list.slint"),
+    },
+];
+
+/// The directory the examples get copied to, so that they can be edited freely
+fn scratch_workspace() -> Option<PathBuf> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Some(std::env::temp_dir().join("slint-preview-examples"))
+    }
+}
+
+/// The examples that can be opened: None without a file system to put them in
+pub fn available() -> &'static [Example] {
+    if scratch_workspace().is_some() {
+        EXAMPLES
+    } else {
+        &[]
+    }
+}
+
+/// Copy `example` into the scratch workspace, replacing earlier changes to it
+pub fn copy_to_scratch_workspace(example: &Example) -> std::io::Result<PathBuf> {
+    let directory = scratch_workspace()
+        .ok_or_else(|| std::io::Error::other("No scratch workspace available"))?
+        .join(example.file_name.trim_end_matches(".slint"));
+    std::fs::create_dir_all(&directory)?;
+    let path = directory.join(example.file_name);
+    std::fs::write(&path, example.source)?;
+    Ok(path)
+}
+
+/// Returns true the first time the preview starts on this machine
+pub fn is_first_start() -> bool {
+    let Some(marker) = scratch_workspace().map(|w| w.join(".welcome-shown")) else {
+        return false;
+    };
+    if marker.exists() {
+        return false;
+    }
+    let _ = marker.parent().map(std::fs::create_dir_all);
+    let _ = std::fs::write(marker, "");
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_compile() {
+        for example in EXAMPLES {
+            let (_, _, diagnostics) =
+                crate::language::test::loaded_document_cache(example.source.to_string());
+            for (url, diagnostics) in diagnostics {
+                assert!(
+                    diagnostics
+                        .iter()
+                        .all(|d| d.severity != Some(lsp_types::DiagnosticSeverity::ERROR)),
+                    "{}: {url}: {diagnostics:?}",
+                    example.name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,323 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Editing the expression bound to a property in the property panel: Completion and
+//! diagnostics while typing, and a way to evaluate the expression before applying it
+
+use i_slint_compiler::diagnostics::{BuildDiagnostics, DiagnosticLevel, SourceFileInner};
+use i_slint_compiler::langtype::Type;
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, TextRange, TextSize};
+use itertools::Itertools;
+use lsp_types::Url;
+
+use crate::common::{self, text_edit};
+use crate::language::{self, completion};
+use crate::util;
+
+use super::properties;
+
+/// How many completions get suggested at most
+const MAX_COMPLETIONS: usize = 30;
+
+/// The property declared to evaluate an expression in
+const EVALUATED_PROPERTY: &str = "slint-preview-evaluated";
+/// The id given to the element to evaluate an expression for, if it has none
+const EVALUATED_ELEMENT: &str = "slint-preview-evaluated-element";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpressionCompletion {
+    pub label: String,
+    /// The whole expression with the completion applied
+    pub expression: String,
+}
+
+/// The source of the document with the `expression` bound to the property
+struct EditedDocument {
+    source: String,
+    /// Where the expression is in the `source`
+    expression: TextRange,
+}
+
+fn edited_document(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    element: &common::ElementRcNode,
+    property_name: &str,
+    expression: &str,
+) -> Option<EditedDocument> {
+    let version = document_cache.document_version(url);
+    let document_edit = properties::set_binding_impl(
+        url.clone(),
+        version,
+        element,
+        property_name,
+        expression.to_string(),
+    )?;
+    let lsp_types::OneOf::Left(edit) = document_edit.edits.first()? else {
+        return None;
+    };
+    let start = element.with_element_node(|node| {
+        util::lsp_position_to_text_size(&node.source_file, edit.range.start)
+    }) + TextSize::try_from(edit.new_text.find(expression)?).ok()?;
+
+    let workspace_edit =
+        common::create_workspace_edit_from_text_document_edits(vec![document_edit.clone()]);
+    let source = text_edit::apply_workspace_edit(document_cache, &workspace_edit)
+        .ok()?
+        .into_iter()
+        .next()?
+        .contents;
+    Some(EditedDocument { source, expression: TextRange::at(start, TextSize::of(expression)) })
+}
+
+/// A copy of the `document_cache` with the `edited` document loaded into it
+fn load_edited(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    edited: &EditedDocument,
+) -> Option<(common::DocumentCache, BuildDiagnostics)> {
+    let mut document_cache = document_cache.snapshot()?;
+    let mut diag = BuildDiagnostics::default();
+    super::poll_once(document_cache.load_url(url, None, edited.source.clone(), &mut diag))?.ok()?;
+    Some((document_cache, diag))
+}
+
+/// The completions for the end of `expression`, bound to the property
+pub fn complete(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    element: &common::ElementRcNode,
+    property_name: &str,
+    expression: &str,
+) -> Vec<ExpressionCompletion> {
+    let prefix_start = expression
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .map_or(0, |p| p + 1);
+    let (head, prefix) = expression.split_at(prefix_start);
+
+    let completions = || {
+        let edited = edited_document(document_cache, url, element, property_name, expression)?;
+        let (mut document_cache, _) = load_edited(document_cache, url, &edited)?;
+        let offset = edited.expression.end();
+        let token =
+            language::token_at_offset(document_cache.get_document(url)?.node.as_ref()?, offset)?;
+        completion::completion_at(&mut document_cache, token, offset, None)
+    };
+
+    completions()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| c.label)
+        .filter(|label| label.starts_with(prefix) && label != prefix)
+        .unique()
+        .sorted_by_key(|label| label.len())
+        .take(MAX_COMPLETIONS)
+        .map(|label| ExpressionCompletion { expression: format!("{head}{label}"), label })
+        .collect()
+}
+
+/// The errors the `expression` causes when bound to the property
+pub fn check(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    element: &common::ElementRcNode,
+    property_name: &str,
+    expression: &str,
+) -> Vec<String> {
+    if expression.trim().is_empty() {
+        return vec![];
+    }
+    let Some(edited) = edited_document(document_cache, url, element, property_name, expression)
+    else {
+        return vec!["The expression can not be bound to the property".into()];
+    };
+    let Some((_, diag)) = load_edited(document_cache, url, &edited) else {
+        return vec![];
+    };
+
+    let path = element.with_element_node(|node| node.source_file.path().to_path_buf());
+    let source_file = SourceFileInner::new(path.clone(), edited.source.clone());
+    let start = source_file.line_column(edited.expression.start().into());
+    // Up to the end of the line, as missing semicolons and such are reported after it
+    let line_end = edited.source[usize::from(edited.expression.end())..]
+        .find('\n')
+        .map_or(edited.source.len(), |p| p + usize::from(edited.expression.end()));
+    let end = source_file.line_column(line_end);
+
+    diag.iter()
+        .filter(|d| d.level() == DiagnosticLevel::Error && d.source_file() == Some(path.as_path()))
+        .filter(|d| (start..=end).contains(&d.line_column()))
+        .map(|d| d.message().to_string())
+        .unique()
+        .collect()
+}
+
+/// The source of the document `element` is in, with a property declared that holds the
+/// value of `expression` evaluated for the `element`, readable from outside of the
+/// component. Also returns the name of the component.
+pub fn evaluation_source(
+    element: &common::ElementRcNode,
+    property_name: &str,
+    expression: &str,
+) -> Option<(String, String)> {
+    let ty = element.as_element().borrow().lookup_property(property_name).property_type;
+    if !ty.is_property_type() {
+        return None;
+    }
+    let ty = match ty {
+        Type::Enumeration(e) => e.name.to_string(),
+        t => t.to_string(),
+    };
+
+    element.with_element_node(|node| {
+        let source = node.source_file.source()?;
+        let component = std::iter::successors(node.parent(), |n| n.parent())
+            .find_map(syntax_nodes::Component::new)?;
+        let component_name = component.DeclaredIdentifier().text().to_string().trim().to_string();
+        let root_brace = component.Element().child_token(SyntaxKind::LBrace)?.text_range().end();
+
+        let mut insertions = vec![];
+        if component.Element().text_range() == node.text_range() {
+            insertions.push((
+                root_brace,
+                format!(" out property <{ty}> {EVALUATED_PROPERTY}: {expression};"),
+            ));
+        } else {
+            let id = match node
+                .parent()
+                .filter(|p| p.kind() == SyntaxKind::SubElement)
+                .and_then(|p| p.child_text(SyntaxKind::Identifier))
+            {
+                Some(id) => id.to_string(),
+                None => {
+                    insertions
+                        .push((node.text_range().start(), format!("{EVALUATED_ELEMENT} := ")));
+                    EVALUATED_ELEMENT.to_string()
+                }
+            };
+            insertions.push((
+                root_brace,
+                format!(" out property <{ty}> {EVALUATED_PROPERTY}: {id}.{EVALUATED_PROPERTY};"),
+            ));
+            insertions.push((
+                node.child_token(SyntaxKind::LBrace)?.text_range().end(),
+                format!(" property <{ty}> {EVALUATED_PROPERTY}: {expression};"),
+            ));
+        }
+
+        let mut source = source.to_string();
+        for (offset, text) in insertions.into_iter().sorted_by_key(|(o, _)| *o).rev() {
+            source.insert_str(offset.into(), &text);
+        }
+        Some((source, component_name))
+    })
+}
+
+/// The text shown for the result of an evaluation
+pub fn value_text(value: &slint_interpreter::Value) -> String {
+    slint_interpreter::json::value_to_json(value)
+        .ok()
+        .and_then(|json| serde_json::to_string(&json).ok())
+        .unwrap_or_else(|| format!("{value:?}"))
+}
+
+pub fn evaluated_value(
+    instance: &slint_interpreter::ComponentInstance,
+) -> Option<slint_interpreter::Value> {
+    instance.get_property(EVALUATED_PROPERTY).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const SOURCE: &str = r#"export enum Mode { compact, wide }
+export global Theme { in property <length> spacing: 4px; }
+export component Main {
+    in property <Mode> mode;
+    property <int> count: 3;
+    Rectangle {
+        width: 10px;
+    }
+    label := Text { text: "Hello"; }
+}
+"#;
+
+    fn document_cache() -> (common::DocumentCache, Url) {
+        let url = Url::from_file_path(common::test::main_test_file_name()).unwrap();
+        let document_cache = common::test::compile_test_with_sources(
+            "fluent",
+            HashMap::from([(url.clone(), SOURCE.to_string())]),
+            false,
+        );
+        (document_cache, url)
+    }
+
+    fn element(
+        document_cache: &common::DocumentCache,
+        url: &Url,
+        text: &str,
+    ) -> common::ElementRcNode {
+        let offset = TextSize::new(SOURCE.find(text).unwrap() as u32);
+        document_cache.element_at_offset(url, offset).unwrap()
+    }
+
+    #[test]
+    fn test_complete_and_check() {
+        let (document_cache, url) = document_cache();
+        let rectangle = element(&document_cache, &url, "Rectangle");
+
+        let labels = |expression: &str| {
+            complete(&document_cache, &url, &rectangle, "width", expression)
+                .into_iter()
+                .map(|c| c.label)
+                .collect::<Vec<_>>()
+        };
+        assert!(labels("Theme.").contains(&"spacing".to_string()));
+        assert!(labels("root.cou").contains(&"count".to_string()));
+        assert!(labels("Mode.w").contains(&"wide".to_string()));
+        assert!(!labels("Mode.w").contains(&"compact".to_string()));
+
+        let completion = complete(&document_cache, &url, &rectangle, "width", "Theme.spa");
+        assert_eq!(completion[0].expression, "Theme.spacing");
+
+        assert!(check(&document_cache, &url, &rectangle, "width", "Theme.spacing * 2").is_empty());
+        assert!(check(&document_cache, &url, &rectangle, "width", "").is_empty());
+        assert!(!check(&document_cache, &url, &rectangle, "width", "Theme.nope").is_empty());
+        assert!(!check(&document_cache, &url, &rectangle, "width", "\"text\"").is_empty());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let (document_cache, url) = document_cache();
+        let reinterpret = |source: &str| {
+            super::super::test::reinterpret_test_with_sources(
+                "fluent",
+                HashMap::from([(common::test::main_test_file_name(), source.to_string())]),
+            )
+        };
+
+        let (source, component) = evaluation_source(
+            &element(&document_cache, &url, "Rectangle"),
+            "width",
+            "parent.count * 2px",
+        )
+        .unwrap();
+        assert_eq!(component, "Main");
+        let instance = reinterpret(&source);
+        assert_eq!(value_text(&evaluated_value(&instance).unwrap()), "6");
+
+        let (source, _) =
+            evaluation_source(&element(&document_cache, &url, "Text"), "text", "self.text + \"!\"")
+                .unwrap();
+        let instance = reinterpret(&source);
+        assert_eq!(value_text(&evaluated_value(&instance).unwrap()), "\"Hello!\"");
+
+        let (source, _) =
+            evaluation_source(&element(&document_cache, &url, "Main"), "mode", "Mode.wide")
+                .unwrap();
+        let instance = reinterpret(&source);
+        assert_eq!(value_text(&evaluated_value(&instance).unwrap()), "\"Mode.wide\"");
+    }
+}
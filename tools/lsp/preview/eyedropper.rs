@@ -0,0 +1,187 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Pick a color from the screen, or from the rendered preview. The screen is read with the
+//! API of the platform where there is one: X11, Windows and macOS. Elsewhere, like on Wayland
+//! and in the browser, the color gets sampled from the last frame of the preview window.
+
+/// A red, green, blue and alpha value
+pub type Rgba = (u8, u8, u8, u8);
+
+/// The color of the pixel at `x`, `y` (in logical pixels) of `window`, as it got rendered last
+pub fn pick_from_window(window: &slint::Window, x: f32, y: f32) -> Result<Rgba, String> {
+    let snapshot =
+        window.take_snapshot().map_err(|e| format!("Can not read the preview window: {e}"))?;
+    let scale_factor = window.scale_factor();
+    let (px, py) = ((x * scale_factor).floor(), (y * scale_factor).floor());
+    if px < 0.0 || py < 0.0 || px >= snapshot.width() as f32 || py >= snapshot.height() as f32 {
+        return Err("The position is outside of the preview window".into());
+    }
+    let pixel = snapshot.as_slice()[py as usize * snapshot.width() as usize + px as usize];
+    Ok((pixel.r, pixel.g, pixel.b, pixel.a))
+}
+
+/// Whether [`pick_from_screen`] is available on this platform. It may still fail, for example
+/// when the user did not allow the preview to record the screen.
+pub const CAN_PICK_FROM_SCREEN: bool = cfg!(any(
+    all(unix, not(target_vendor = "apple"), not(target_arch = "wasm32")),
+    target_os = "windows",
+    target_os = "macos"
+));
+
+/// The color of the pixel under the mouse pointer, anywhere on the screen
+pub fn pick_from_screen() -> Result<Rgba, String> {
+    platform::pick_from_screen()
+}
+
+/// The red, green and blue value of `pixel` with the channels at the bits of the masks
+#[cfg_attr(
+    not(all(unix, not(target_vendor = "apple"), not(target_arch = "wasm32"))),
+    allow(dead_code)
+)]
+fn rgba_from_masks(pixel: u32, (red_mask, green_mask, blue_mask): (u32, u32, u32)) -> Rgba {
+    let channel = |mask: u32| {
+        if mask == 0 {
+            return 0;
+        }
+        let max = u32::MAX >> (32 - mask.count_ones());
+        (((pixel & mask) >> mask.trailing_zeros()) * 255 / max) as u8
+    };
+    (channel(red_mask), channel(green_mask), channel(blue_mask), 255)
+}
+
+#[cfg(all(unix, not(target_vendor = "apple"), not(target_arch = "wasm32")))]
+mod platform {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ConnectionExt as _, ImageFormat, ImageOrder};
+
+    pub fn pick_from_screen() -> Result<super::Rgba, String> {
+        let error = |e: &dyn std::fmt::Display| format!("Can not read the screen: {e}");
+        let (connection, screen) = x11rb::connect(None).map_err(|e| error(&e))?;
+        let setup = connection.setup();
+        let screen = &setup.roots[screen];
+        let pointer = connection
+            .query_pointer(screen.root)
+            .map_err(|e| error(&e))?
+            .reply()
+            .map_err(|e| error(&e))?;
+        let image = connection
+            .get_image(ImageFormat::Z_PIXMAP, screen.root, pointer.root_x, pointer.root_y, 1, 1, !0)
+            .map_err(|e| error(&e))?
+            .reply()
+            .map_err(|e| error(&e))?;
+
+        let visual = screen
+            .allowed_depths
+            .iter()
+            .flat_map(|d| d.visuals.iter())
+            .find(|v| v.visual_id == image.visual)
+            .ok_or_else(|| error(&"Unknown visual"))?;
+        let bits_per_pixel = setup
+            .pixmap_formats
+            .iter()
+            .find(|f| f.depth == image.depth)
+            .map_or(0, |f| f.bits_per_pixel);
+        let bytes = match bits_per_pixel {
+            24 | 32 => image.data.get(..bits_per_pixel as usize / 8),
+            _ => None,
+        }
+        .ok_or_else(|| error(&format!("Unsupported pixel format ({bits_per_pixel} bits)")))?;
+        let pixel = if setup.image_byte_order == ImageOrder::LSB_FIRST {
+            bytes.iter().rev().fold(0, |pixel, byte| pixel << 8 | u32::from(*byte))
+        } else {
+            bytes.iter().fold(0, |pixel, byte| pixel << 8 | u32::from(*byte))
+        };
+        Ok(super::rgba_from_masks(pixel, (visual.red_mask, visual.green_mask, visual.blue_mask)))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::Foundation::POINT;
+    use windows_sys::Win32::Graphics::Gdi::{GetDC, GetPixel, ReleaseDC, CLR_INVALID};
+    use windows_sys::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+    pub fn pick_from_screen() -> Result<super::Rgba, String> {
+        let mut position = POINT { x: 0, y: 0 };
+        // SAFETY: The device context of the screen is released again before returning
+        let color = unsafe {
+            if GetCursorPos(&mut position) == 0 {
+                return Err("Can not find the mouse pointer".into());
+            }
+            let dc = GetDC(std::ptr::null_mut());
+            if dc.is_null() {
+                return Err("Can not read the screen".into());
+            }
+            let color = GetPixel(dc, position.x, position.y);
+            ReleaseDC(std::ptr::null_mut(), dc);
+            color
+        };
+        if color == CLR_INVALID {
+            return Err("Can not read the screen at the mouse pointer".into());
+        }
+        // A COLORREF is 0x00bbggrr
+        let [r, g, b, _] = color.to_le_bytes();
+        Ok((r, g, b, 255))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use core_graphics::display::{
+        kCGNullWindowID, kCGWindowImageDefault, kCGWindowListOptionOnScreenOnly, CGDisplay,
+    };
+    use core_graphics::event::CGEvent;
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+    use core_graphics::geometry::{CGRect, CGSize};
+
+    pub fn pick_from_screen() -> Result<super::Rgba, String> {
+        let position = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
+            .and_then(CGEvent::new)
+            .map_err(|()| "Can not find the mouse pointer".to_string())?
+            .location();
+        let image = CGDisplay::screenshot(
+            CGRect::new(&position, &CGSize::new(1.0, 1.0)),
+            kCGWindowListOptionOnScreenOnly,
+            kCGNullWindowID,
+            kCGWindowImageDefault,
+        )
+        .ok_or_else(|| {
+            "Can not read the screen, the preview may need the permission to record it".to_string()
+        })?;
+        // Screenshots are 32 bit BGRA
+        match image.data().bytes() {
+            [b, g, r, _, ..] if image.bits_per_pixel() == 32 => Ok((*r, *g, *b, 255)),
+            _ => Err("Unsupported pixel format of the screen".into()),
+        }
+    }
+}
+
+#[cfg(not(any(
+    all(unix, not(target_vendor = "apple"), not(target_arch = "wasm32")),
+    target_os = "windows",
+    target_os = "macos"
+)))]
+mod platform {
+    pub fn pick_from_screen() -> Result<super::Rgba, String> {
+        Err("Picking colors from the screen is not supported on this platform".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgba_from_masks() {
+        let masks = (0xff0000, 0x00ff00, 0x0000ff);
+        assert_eq!(rgba_from_masks(0x12ab34, masks), (0x12, 0xab, 0x34, 255));
+        assert_eq!(rgba_from_masks(0xff12ab34, masks), (0x12, 0xab, 0x34, 255));
+        // 16 bit, 5-6-5
+        let masks = (0xf800, 0x07e0, 0x001f);
+        assert_eq!(rgba_from_masks(0xffff, masks), (255, 255, 255, 255));
+        assert_eq!(rgba_from_masks(0xf800, masks), (255, 0, 0, 255));
+        assert_eq!(rgba_from_masks(0x0400, masks), (0, 129, 0, 255));
+        assert_eq!(rgba_from_masks(0, (0, 0, 0)), (0, 0, 0, 255));
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Files dropped onto the preview from outside: Images become `Image` elements, fonts
+//! get imported into the previewed document
+
+use std::path::{Component, Path, PathBuf};
+
+use i_slint_compiler::parser::syntax_nodes;
+
+use crate::common;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "svg", "webp", "bmp"];
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc"];
+
+/// The directory next to the document that assets from outside of the project get copied to
+const ASSETS_DIRECTORY: &str = "assets";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileKind {
+    Image,
+    Font,
+}
+
+pub fn file_kind(path: &Path) -> Option<FileKind> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        Some(FileKind::Image)
+    } else if FONT_EXTENSIONS.contains(&extension.as_str()) {
+        Some(FileKind::Font)
+    } else {
+        None
+    }
+}
+
+/// The path of `file` relative to `directory`, with `/` as separator as used in `.slint` files
+///
+/// Returns `None` if there is no relative path, like for files on different drives.
+pub fn relative_path(directory: &Path, file: &Path) -> Option<String> {
+    let directory = directory.components().collect::<Vec<_>>();
+    let file = file.components().collect::<Vec<_>>();
+    if directory.first() != file.first() {
+        return None;
+    }
+    let common = directory.iter().zip(&file).take_while(|(d, f)| d == f).count();
+
+    let parts = std::iter::repeat_n("..".to_string(), directory.len() - common)
+        .chain(file[common..].iter().map(|c| match c {
+            Component::Normal(n) => n.to_string_lossy().to_string(),
+            other => other.as_os_str().to_string_lossy().to_string(),
+        }))
+        .collect::<Vec<_>>();
+    Some(parts.join("/"))
+}
+
+/// The file to reference for the dropped `file`: The file itself if it is part of the
+/// `project`, otherwise a copy in the assets directory next to the `document`.
+pub fn asset_for(file: &Path, document: &Path, project: &Path) -> std::io::Result<PathBuf> {
+    if file.starts_with(project) {
+        return Ok(file.to_path_buf());
+    }
+    let directory = document.parent().unwrap_or(document).join(ASSETS_DIRECTORY);
+    std::fs::create_dir_all(&directory)?;
+
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let extension =
+        file.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let contents = std::fs::read(file)?;
+    for counter in 1.. {
+        let name = if counter == 1 {
+            format!("{stem}{extension}")
+        } else {
+            format!("{stem}-{counter}{extension}")
+        };
+        let target = directory.join(name);
+        match std::fs::read(&target) {
+            // Dropped before
+            Ok(existing) if existing == contents => return Ok(target),
+            Ok(_) => continue,
+            Err(_) => {
+                std::fs::write(&target, &contents)?;
+                return Ok(target);
+            }
+        }
+    }
+    unreachable!("There is always a free file name")
+}
+
+/// Import the font at `font_path` (relative to the document) in `document`, unless that
+/// happened before
+pub fn font_import_edit(
+    document: &syntax_nodes::Document,
+    font_path: &str,
+) -> Option<lsp_types::TextEdit> {
+    let imports = document.ImportSpecifier().collect::<Vec<_>>();
+    let import_text = format!("\"{font_path}\"");
+    if imports.iter().any(|i| {
+        i.child_token(i_slint_compiler::parser::SyntaxKind::StringLiteral)
+            .is_some_and(|t| t.text() == import_text)
+    }) {
+        return None;
+    }
+
+    // After the last import, or at the very top
+    let (offset, text) = match imports.last() {
+        Some(last) => (last.text_range().end(), format!("\nimport {import_text};")),
+        None => (0.into(), format!("import {import_text};\n")),
+    };
+    let position = crate::util::text_size_to_lsp_position(&document.source_file, offset);
+    Some(lsp_types::TextEdit { range: lsp_types::Range::new(position, position), new_text: text })
+}
+
+/// The component information to drop an `Image` showing `image_path` (relative to the document)
+pub fn image_component(
+    known_components: &[common::ComponentInformation],
+    image_path: &str,
+) -> Option<common::ComponentInformation> {
+    let mut image = known_components.iter().find(|ci| ci.is_builtin && ci.name == "Image")?.clone();
+    image.default_properties.retain(|p| p.name != "source");
+    image
+        .default_properties
+        .push(common::PropertyChange::new("source", format!("@image-url(\"{image_path}\")")));
+    Some(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_kind() {
+        assert_eq!(file_kind(Path::new("/a/logo.PNG")), Some(FileKind::Image));
+        assert_eq!(file_kind(Path::new("/a/icon.svg")), Some(FileKind::Image));
+        assert_eq!(file_kind(Path::new("/a/Inter.ttf")), Some(FileKind::Font));
+        assert_eq!(file_kind(Path::new("/a/notes.txt")), None);
+        assert_eq!(file_kind(Path::new("/a/Makefile")), None);
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let directory = Path::new("/project/ui");
+        assert_eq!(
+            relative_path(directory, Path::new("/project/ui/images/logo.png")).as_deref(),
+            Some("images/logo.png")
+        );
+        assert_eq!(
+            relative_path(directory, Path::new("/project/assets/logo.png")).as_deref(),
+            Some("../assets/logo.png")
+        );
+        assert_eq!(
+            relative_path(directory, Path::new("/other/logo.png")).as_deref(),
+            Some("../../other/logo.png")
+        );
+    }
+
+    #[test]
+    fn test_font_import_edit() {
+        let source = "import { Button } from \"std-widgets.slint\";\nimport \"fonts/Inter.ttf\";\n\nexport component Main { }\n";
+        let (dc, url, _) = crate::language::test::loaded_document_cache(source.to_string());
+        let document = dc.get_document(&url).unwrap().node.clone().unwrap();
+
+        assert!(font_import_edit(&document, "fonts/Inter.ttf").is_none());
+        let edit = font_import_edit(&document, "fonts/Mono.ttf").unwrap();
+        assert_eq!(edit.range.start, lsp_types::Position::new(1, 25));
+        assert_eq!(edit.new_text, "\nimport \"fonts/Mono.ttf\";");
+    }
+
+    #[test]
+    fn test_image_component() {
+        let (dc, _, _) = crate::language::test::loaded_document_cache(String::new());
+        let mut known_components = vec![];
+        crate::common::component_catalog::builtin_components(&dc, &mut known_components);
+
+        let image = image_component(&known_components, "../logo.png").unwrap();
+        assert_eq!(image.name, "Image");
+        assert_eq!(
+            image.default_properties.iter().find(|p| p.name == "source").unwrap().value,
+            "@image-url(\"../logo.png\")"
+        );
+    }
+}
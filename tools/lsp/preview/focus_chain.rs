@@ -0,0 +1,101 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The elements of the preview that get the keyboard focus when pressing tab, in the order they
+//! get it. The order follows the element tree, so it changes by moving elements before or after
+//! their siblings.
+
+use i_slint_core::items::{FocusScope, ItemRc, TextInput};
+use i_slint_core::lengths::LogicalRect;
+
+/// The most items followed along the focus chain, in case it never leads back to its start
+const MAX_STEPS: usize = 10_000;
+
+/// An element of the preview that takes the focus
+#[derive(Clone, Debug)]
+pub struct FocusStop {
+    pub geometry: LogicalRect,
+    /// The type name of the element, with its id if it has one
+    pub element: String,
+}
+
+fn is_focusable(item: &ItemRc) -> bool {
+    if let Some(i) = item.downcast::<FocusScope>() {
+        i.as_pin_ref().enabled()
+    } else if let Some(i) = item.downcast::<TextInput>() {
+        i.as_pin_ref().enabled()
+    } else {
+        false
+    }
+}
+
+/// The focusable elements of the preview shown in `window`, in tab order
+pub fn collect(window: &slint::Window) -> Vec<FocusStop> {
+    let Some(root) = i_slint_core::window::WindowInner::from_pub(window).window_item_rc() else {
+        return vec![];
+    };
+    let start = root.next_focus_item();
+    let mut item = start.clone();
+    let mut stops = vec![];
+    for _ in 0..MAX_STEPS {
+        if is_focusable(&item) && item.is_visible() {
+            // Items outside of the preview belong to the UI around it
+            if let Some(geometry) = super::render_stats::geometry_in_preview(&item) {
+                stops.push(FocusStop {
+                    geometry,
+                    element: super::accessibility::element_name(&item),
+                });
+            }
+        }
+        item = item.next_focus_item();
+        if item == start {
+            break;
+        }
+    }
+    stops
+}
+
+/// The sibling to move the sibling at `index` in front of (`earlier`) or behind of, to change
+/// its place in the focus chain: The closest sibling in that direction with a focus stop in it.
+/// `siblings` are the geometries of the siblings in the preview.
+pub fn tab_order_neighbor(
+    siblings: &[Option<LogicalRect>],
+    stops: &[FocusStop],
+    index: usize,
+    earlier: bool,
+) -> Option<usize> {
+    let has_stop = |i: &usize| {
+        siblings[*i].is_some_and(|r| stops.iter().any(|s| r.contains(s.geometry.center())))
+    };
+    if earlier {
+        (0..index).rev().find(has_stop)
+    } else {
+        (index + 1..siblings.len()).find(has_stop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i_slint_core::lengths::{LogicalPoint, LogicalSize};
+
+    #[test]
+    fn test_tab_order_neighbor() {
+        let rect =
+            |x: f32| LogicalRect::new(LogicalPoint::new(x, 0.0), LogicalSize::new(50.0, 20.0));
+        let stop = |x: f32| FocusStop {
+            geometry: LogicalRect::new(LogicalPoint::new(x, 0.0), LogicalSize::new(10.0, 10.0)),
+            element: "TextInput".into(),
+        };
+        // Siblings at 0, 100, 200, 300 and one not shown: Only 0, 200 and 300 can get the focus
+        let siblings =
+            [Some(rect(0.0)), Some(rect(100.0)), None, Some(rect(200.0)), Some(rect(300.0))];
+        let stops = [stop(10.0), stop(210.0), stop(320.0)];
+
+        assert_eq!(tab_order_neighbor(&siblings, &stops, 3, true), Some(0));
+        assert_eq!(tab_order_neighbor(&siblings, &stops, 3, false), Some(4));
+        assert_eq!(tab_order_neighbor(&siblings, &stops, 1, false), Some(3));
+        assert_eq!(tab_order_neighbor(&siblings, &stops, 0, true), None);
+        assert_eq!(tab_order_neighbor(&siblings, &stops, 4, false), None);
+    }
+}
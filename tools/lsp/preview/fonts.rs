@@ -0,0 +1,53 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The font families the preview can render, for the editor of `font-family` properties: Those
+//! of the system and those imported into documents, as known to the font database of Slint.
+
+/// The names of the font families, sorted and without duplicates
+fn unique_families(names: impl Iterator<Item = String>) -> Vec<String> {
+    let mut families = names.filter(|n| !n.trim().is_empty()).collect::<Vec<_>>();
+    families.sort_by_key(|n| n.to_lowercase());
+    families.dedup();
+    families
+}
+
+/// The font families available to the preview
+pub fn font_families() -> Vec<String> {
+    i_slint_common::sharedfontdb::FONT_DB.with_borrow(|db| {
+        unique_families(db.faces().flat_map(|face| face.families.iter().map(|(n, _)| n.clone())))
+    })
+}
+
+/// The `families` whose name contains all words of the `filter`
+pub fn filter_families(families: &[String], filter: &str) -> Vec<String> {
+    let words = filter.split_whitespace().map(|w| w.to_lowercase()).collect::<Vec<_>>();
+    families
+        .iter()
+        .filter(|family| {
+            let family = family.to_lowercase();
+            words.iter().all(|w| family.contains(w))
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_families() {
+        let families = unique_families(
+            ["Noto Sans", "DejaVu Sans", "noto serif", "Noto Sans", " "]
+                .into_iter()
+                .map(String::from),
+        );
+        assert_eq!(families, ["DejaVu Sans", "Noto Sans", "noto serif"]);
+
+        assert_eq!(filter_families(&families, "NOTO"), ["Noto Sans", "noto serif"]);
+        assert_eq!(filter_families(&families, "sans noto"), ["Noto Sans"]);
+        assert_eq!(filter_families(&families, "  "), families);
+        assert!(filter_families(&families, "mono").is_empty());
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Form components generated from a struct, or from a JSON example of one: A labeled input
+//! widget per field, editing a `value` property of the struct type, and a `submit` callback
+//! carrying it.
+
+use i_slint_compiler::langtype::Type;
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, TextRange};
+use lsp_types::Url;
+
+use crate::common;
+use crate::language::completion;
+use crate::util;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    /// The type as written in Slint, like `string` or `[int]`
+    pub type_name: String,
+}
+
+/// A struct declared in one of the documents
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructDeclaration {
+    pub name: String,
+    pub url: Url,
+    /// In the order they are declared in
+    pub fields: Vec<Field>,
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn struct_declaration(url: &Url, node: &syntax_nodes::StructDeclaration) -> StructDeclaration {
+    let fields = node
+        .ObjectType()
+        .ObjectTypeMember()
+        .filter_map(|member| {
+            let name = member.child_text(SyntaxKind::Identifier)?;
+            Some(Field {
+                name: name.to_string(),
+                type_name: member.Type().text().to_string().trim().to_string(),
+            })
+        })
+        .collect();
+    StructDeclaration {
+        name: node.DeclaredIdentifier().text().to_string().trim().to_string(),
+        url: url.clone(),
+        fields,
+    }
+}
+
+/// The structs declared in the documents of the project, sorted by name
+pub fn structs(document_cache: &common::DocumentCache) -> Vec<StructDeclaration> {
+    let mut result = document_cache
+        .all_url_documents()
+        .filter(|(url, _)| url.scheme() == "file")
+        .flat_map(|(url, document)| {
+            document
+                .StructDeclaration()
+                .chain(document.ExportsList().flat_map(|e| e.StructDeclaration()))
+                .map(|s| struct_declaration(&url, &s))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+/// The fields of the struct a JSON object is an example of
+pub fn fields_from_json(json: &str) -> Result<Vec<Field>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let serde_json::Value::Object(object) = value else {
+        return Err("The example needs to be a JSON object".into());
+    };
+    object
+        .iter()
+        .map(|(name, value)| {
+            if !is_identifier(name) {
+                return Err(format!("`{name}` is not a valid field name"));
+            }
+            let type_name = match value {
+                serde_json::Value::String(_) => "string",
+                serde_json::Value::Bool(_) => "bool",
+                serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => "int",
+                serde_json::Value::Number(_) => "float",
+                _ => {
+                    return Err(format!(
+                        "The field `{name}` holds neither text, a number nor a bool"
+                    ))
+                }
+            };
+            Ok(Field { name: name.clone(), type_name: type_name.into() })
+        })
+        .collect()
+}
+
+/// The label of the input of the field called `name`: `first-name` becomes `First name`
+fn label(name: &str) -> String {
+    let words = name.replace(['-', '_'], " ");
+    let mut chars = words.trim().chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+const TEXT_INPUT: &str = "LineEdit {
+    text: VALUE;
+    edited(text) => {
+        VALUE = text;
+    }
+}";
+
+const BOOL_INPUT: &str = "CheckBox {
+    checked: VALUE;
+    toggled => {
+        VALUE = self.checked;
+    }
+}";
+
+const NUMBER_INPUT: &str = "LineEdit {
+    input-type: INPUT_TYPE;
+    text: VALUE / 1UNIT;
+    edited(text) => {
+        VALUE = text.to-float() * 1UNIT;
+    }
+}";
+
+const ROW: &str = "
+HorizontalLayout {
+    spacing: 8px;
+
+    Text {
+        min-width: 120px;
+        vertical-alignment: center;
+        text: LABEL;
+    }
+
+INPUT
+}
+";
+
+const FORM: &str = "export component NAME inherits VerticalLayout {
+    in-out property <STRUCT> value;
+
+    callback submit(value: STRUCT);
+
+    spacing: 8px;
+ROWS
+    Button {
+        text: \"Submit\";
+        primary: true;
+        clicked => {
+            root.submit(root.value);
+        }
+    }
+}
+";
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|l| if l.is_empty() { String::new() } else { format!("    {l}") })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The unit numbers of the type get edited in, `None` if there is no input for the type
+fn numeric_unit(type_name: &str) -> Option<&'static str> {
+    match type_name {
+        "int" | "float" => Some(""),
+        "length" => Some("px"),
+        "physical-length" => Some("phx"),
+        "relative-font-size" => Some("rem"),
+        "duration" => Some("ms"),
+        "angle" => Some("deg"),
+        "percent" => Some("%"),
+        _ => None,
+    }
+}
+
+/// The input widget for `field`, and the name of that widget
+fn input(field: &Field) -> Option<(&'static str, String)> {
+    let (widget, input) = match field.type_name.as_str() {
+        "string" => ("LineEdit", TEXT_INPUT.to_string()),
+        "bool" => ("CheckBox", BOOL_INPUT.to_string()),
+        type_name => {
+            let unit = numeric_unit(type_name)?;
+            let input_type = if type_name == "int" { "number" } else { "decimal" };
+            let input = if unit.is_empty() {
+                NUMBER_INPUT.replace(" / 1UNIT", "").replace(" * 1UNIT", "")
+            } else {
+                NUMBER_INPUT.replace("UNIT", unit)
+            };
+            ("LineEdit", input.replace("INPUT_TYPE", input_type))
+        }
+    };
+    Some((widget, input.replace("VALUE", &format!("root.value.{}", field.name))))
+}
+
+/// The source of the form component called `name`, and the std-widgets it uses
+pub fn form_source(name: &str, struct_name: &str, fields: &[Field]) -> (String, Vec<&'static str>) {
+    let mut widgets = vec!["Button"];
+    let mut rows = String::new();
+    for field in fields {
+        let Some((widget, input)) = input(field) else {
+            rows += &format!(
+                "\n    // `{}` is a {}, which has no input widget\n",
+                field.name, field.type_name
+            );
+            continue;
+        };
+        if !widgets.contains(&widget) {
+            widgets.push(widget);
+        }
+        let row = ROW
+            .replace("LABEL", &format!("{:?}", label(&field.name)))
+            .replace("INPUT", &indent(&input));
+        rows += &indent(&row);
+        rows += "\n";
+    }
+    widgets.sort();
+
+    let source = FORM.replace("STRUCT", struct_name).replace("NAME", name).replace("ROWS", &rows);
+    (source, widgets)
+}
+
+/// The source of a struct called `name` with `fields`
+pub fn struct_source(name: &str, fields: &[Field]) -> String {
+    let fields =
+        fields.iter().map(|f| format!("    {}: {},\n", f.name, f.type_name)).collect::<String>();
+    format!("export struct {name} {{\n{fields}}}\n")
+}
+
+/// Add a form component called `name` to the end of `document`, editing `declaration`, or a
+/// struct with `fields` declared along with the form
+pub fn add_form(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    name: &str,
+    source: Result<&StructDeclaration, &[Field]>,
+) -> Result<common::WorkspaceEditBuilder, String> {
+    if !is_identifier(name) {
+        return Err(format!("`{name}` is not a valid component name"));
+    }
+    let document = document_cache.get_document(url).ok_or("The document is not loaded")?;
+    let document_node = document.node.as_ref().ok_or("The document is not loaded")?;
+    if document.local_registry.lookup_element(name).is_ok() {
+        return Err(format!("There is a `{name}` already"));
+    }
+
+    let version = document_cache.document_version(url);
+    let mut builder = common::WorkspaceEditBuilder::new("Add form");
+    let (struct_name, fields, declared_struct) = match source {
+        Ok(declaration) => {
+            let known = document.local_registry.lookup(&declaration.name) != Type::Invalid;
+            if &declaration.url != url && !known {
+                let import_file = url.make_relative(&declaration.url);
+                if let Some(import) =
+                    completion::create_import_edit(document, &declaration.name, &import_file)
+                {
+                    builder.add_text_edit(url.clone(), version, import);
+                }
+            }
+            (declaration.name.clone(), declaration.fields.as_slice(), String::new())
+        }
+        Err(fields) => {
+            let struct_name = format!("{name}Data");
+            if document.local_registry.lookup(&struct_name) != Type::Invalid {
+                return Err(format!("There is a `{struct_name}` already"));
+            }
+            let declared = struct_source(&struct_name, fields);
+            (struct_name, fields, format!("\n{declared}"))
+        }
+    };
+
+    let (form, widgets) = form_source(name, &struct_name, fields);
+    let missing = widgets
+        .into_iter()
+        .filter(|w| document.local_registry.lookup_element(w).is_err())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let import = completion::create_import_edit(
+            document,
+            &missing.join(", "),
+            &Some("std-widgets.slint".to_string()),
+        );
+        if let Some(import) = import {
+            builder.add_text_edit(url.clone(), version, import);
+        }
+    }
+
+    let end = util::text_range_to_lsp_range(
+        &document_node.source_file,
+        TextRange::empty(document_node.text_range().end()),
+    );
+    builder.add_text_edit(
+        url.clone(),
+        version,
+        lsp_types::TextEdit { range: end, new_text: format!("{declared_struct}\n{form}") },
+    );
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    #[test]
+    fn test_fields_from_json() {
+        let fields =
+            fields_from_json(r#"{"name": "Ann", "age": 42, "score": 1.5, "active": true}"#)
+                .unwrap()
+                .into_iter()
+                .map(|f| (f.name, f.type_name))
+                .collect::<Vec<_>>();
+        assert!(fields.contains(&("name".into(), "string".into())));
+        assert!(fields.contains(&("age".into(), "int".into())));
+        assert!(fields.contains(&("score".into(), "float".into())));
+        assert!(fields.contains(&("active".into(), "bool".into())));
+        assert!(fields_from_json("[1, 2]").is_err());
+        assert!(fields_from_json(r#"{"tags": []}"#).is_err());
+        assert!(fields_from_json(r#"{"first name": ""}"#).is_err());
+    }
+
+    #[test]
+    fn test_add_form() {
+        let source = r#"import { Button } from "std-widgets.slint";
+
+export struct Person {
+    first-name: string,
+    height: length,
+    active: bool,
+    tint: color,
+}
+
+export component Main { }
+"#;
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        let declarations = structs(&dc);
+        assert_eq!(declarations.len(), 1);
+        let person = &declarations[0];
+        assert_eq!(person.name, "Person");
+        assert_eq!(
+            person.fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+            vec!["first-name", "height", "active", "tint"]
+        );
+
+        let (_, edit) = add_form(&dc, &url, "PersonForm", Ok(person)).unwrap().build().unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&dc, &edit).unwrap();
+        let result = &result.first().unwrap().contents;
+        assert!(
+            result.starts_with("import { Button, CheckBox, LineEdit } from \"std-widgets.slint\";")
+        );
+        assert!(result.contains("export component PersonForm inherits VerticalLayout {"));
+        assert!(result.contains("in-out property <Person> value;"));
+        assert!(result.contains("callback submit(value: Person);"));
+        assert!(result.contains("text: \"First name\";"));
+        assert!(result.contains("root.value.height = text.to-float() * 1px;"));
+        assert!(result.contains("root.value.active = self.checked;"));
+        assert!(result.contains("// `tint` is a color, which has no input widget"));
+
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        assert!(add_form(&dc, &url, "Main", Ok(person)).is_err());
+        assert!(add_form(&dc, &url, "2Form", Ok(person)).is_err());
+
+        let fields = fields_from_json(r#"{"title": "x"}"#).unwrap();
+        let (_, edit) = add_form(&dc, &url, "NoteForm", Err(&fields)).unwrap().build().unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&dc, &edit).unwrap();
+        let result = &result.first().unwrap().contents;
+        assert!(result.contains("export struct NoteFormData {\n    title: string,\n}\n"));
+        assert!(result.contains("in-out property <NoteFormData> value;"));
+    }
+}
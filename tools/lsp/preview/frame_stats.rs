@@ -0,0 +1,156 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Count the frames the preview window renders, how long they take and how many items of
+//! the previewed component get redrawn in them, to spot bindings and animations that cause
+//! more repaints than needed.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use i_slint_core::animations::Instant;
+
+/// What the preview rendered during some time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub frames_per_second: f32,
+    pub average_frame_time: Duration,
+    pub max_frame_time: Duration,
+    pub average_items: f32,
+}
+
+#[derive(Default)]
+pub struct FrameStats {
+    frame_start: Option<Instant>,
+    items_in_frame: u32,
+    frames: u32,
+    items: u32,
+    total_frame_time: Duration,
+    max_frame_time: Duration,
+}
+
+impl FrameStats {
+    fn begin_frame(&mut self, now: Instant) {
+        self.frame_start = Some(now);
+        self.items_in_frame = 0;
+    }
+
+    fn end_frame(&mut self, now: Instant) {
+        let Some(start) = self.frame_start.take() else {
+            return;
+        };
+        let frame_time = now.duration_since(start);
+        self.frames += 1;
+        self.items += self.items_in_frame;
+        self.total_frame_time += frame_time;
+        self.max_frame_time = self.max_frame_time.max(frame_time);
+    }
+
+    fn item_rendered(&mut self) {
+        self.items_in_frame += 1;
+    }
+
+    /// Summarize the frames rendered during `elapsed` since the last call and start over
+    fn take_summary(&mut self, elapsed: Duration) -> Summary {
+        let summary = if self.frames == 0 {
+            Summary {
+                frames_per_second: 0.0,
+                average_frame_time: Duration::ZERO,
+                max_frame_time: Duration::ZERO,
+                average_items: 0.0,
+            }
+        } else {
+            Summary {
+                frames_per_second: self.frames as f32 / elapsed.as_secs_f32().max(f32::EPSILON),
+                average_frame_time: self.total_frame_time / self.frames,
+                max_frame_time: self.max_frame_time,
+                average_items: self.items as f32 / self.frames as f32,
+            }
+        };
+        *self = FrameStats { frame_start: self.frame_start, ..Default::default() };
+        summary
+    }
+}
+
+thread_local! {static FRAME_STATS: RefCell<Option<FrameStats>> = const { RefCell::new(None) };}
+
+pub fn is_active() -> bool {
+    FRAME_STATS.with(|stats| stats.borrow().is_some())
+}
+
+/// Count an item of the previewed component as redrawn in the current frame
+pub fn item_rendered() {
+    FRAME_STATS.with(|stats| {
+        if let Some(stats) = stats.borrow_mut().as_mut() {
+            stats.item_rendered();
+        }
+    })
+}
+
+/// Start counting the frames rendered in `window`
+pub fn start(window: &slint::Window) -> Result<(), slint::SetRenderingNotifierError> {
+    // The notifier can not be removed again, so it stays registered once it got set
+    match window.set_rendering_notifier(|state, _| {
+        FRAME_STATS.with(|stats| {
+            let mut stats = stats.borrow_mut();
+            let Some(stats) = stats.as_mut() else {
+                return;
+            };
+            match state {
+                slint::RenderingState::BeforeRendering => stats.begin_frame(Instant::now()),
+                slint::RenderingState::AfterRendering => stats.end_frame(Instant::now()),
+                _ => {}
+            }
+        })
+    }) {
+        Ok(()) | Err(slint::SetRenderingNotifierError::AlreadySet) => {}
+        Err(err) => return Err(err),
+    }
+    FRAME_STATS.with(|stats| *stats.borrow_mut() = Some(FrameStats::default()));
+    super::render_stats::update_profiler();
+    Ok(())
+}
+
+pub fn stop() {
+    FRAME_STATS.with(|stats| *stats.borrow_mut() = None);
+    super::render_stats::update_profiler();
+}
+
+/// Summarize the frames rendered during `elapsed` since the last call
+pub fn take_summary(elapsed: Duration) -> Option<Summary> {
+    FRAME_STATS.with(|stats| stats.borrow_mut().as_mut().map(|stats| stats.take_summary(elapsed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_summary() {
+        let mut stats = FrameStats::default();
+        let second = Duration::from_secs(1);
+        assert_eq!(stats.take_summary(second).frames_per_second, 0.0);
+
+        stats.begin_frame(Instant(100));
+        stats.item_rendered();
+        stats.item_rendered();
+        stats.end_frame(Instant(104));
+        stats.begin_frame(Instant(200));
+        stats.item_rendered();
+        stats.end_frame(Instant(208));
+        // A frame still being rendered is not counted yet
+        stats.begin_frame(Instant(300));
+
+        let summary = stats.take_summary(second);
+        assert_eq!(summary.frames_per_second, 2.0);
+        assert_eq!(summary.average_frame_time, Duration::from_millis(6));
+        assert_eq!(summary.max_frame_time, Duration::from_millis(8));
+        assert_eq!(summary.average_items, 1.5);
+
+        stats.end_frame(Instant(302));
+        let summary = stats.take_summary(second / 2);
+        assert_eq!(summary.frames_per_second, 2.0);
+        assert_eq!(summary.max_frame_time, Duration::from_millis(2));
+        assert_eq!(summary.average_items, 0.0);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Fuzzing the previewed component: Its settable properties get random values of their types,
+//! biased towards those that tend to break layouts, like empty and very long texts, zero and
+//! huge sizes, and empty and long models.
+//!
+//! The values only depend on a seed, so that the values that broke the component can be set
+//! again with it.
+
+use i_slint_compiler::langtype::Type;
+use slint_interpreter::{ComponentInstance, Value};
+
+use super::preview_data::{self, PropertyContainer};
+
+/// The most rows a random model gets
+const MAX_ROWS: usize = 50;
+
+/// Texts that tend to break layouts: Empty, long, multi-line, and in other scripts
+const TEXTS: &[&str] = &[
+    "",
+    " ",
+    "A",
+    "Lorem ipsum",
+    "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.",
+    "Supercalifragilisticexpialidocious-Donaudampfschifffahrtsgesellschaftskapitän",
+    "First line\nSecond line\nThird line",
+    "مرحبا بالعالم",
+    "こんにちは世界",
+    "👩‍👩‍👧‍👦🎉🚀",
+    "Ünïcödé àçcéñts",
+    "<b>&amp;</b> %s {} \\n",
+];
+
+/// Numbers that tend to break layouts and computations
+const NUMBERS: &[f64] = &[0.0, 1.0, -1.0, 0.5, 100.0, 10_000.0, 1_000_000.0, -1_000_000.0];
+
+/// A random number generator that gives the same numbers for the same seed (SplitMix64)
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, `n` must not be 0
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `0.0..1.0`
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+fn random_number(rng: &mut Rng, scale: f64) -> f64 {
+    if rng.below(2) == 0 {
+        *rng.pick(NUMBERS)
+    } else {
+        (rng.unit() * scale).round()
+    }
+}
+
+/// A random value of type `ty`, `None` for types that do not get fuzzed
+pub fn random_value(ty: &Type, rng: &mut Rng) -> Option<Value> {
+    Some(match ty {
+        Type::String => {
+            if rng.below(4) == 0 {
+                // Longer than any text in the list
+                Value::String(rng.pick(TEXTS).repeat(2 + rng.below(20)).into())
+            } else {
+                Value::String((*rng.pick(TEXTS)).into())
+            }
+        }
+        Type::Int32 => Value::Number(random_number(rng, 1000.0).round()),
+        Type::Float32
+        | Type::Duration
+        | Type::Angle
+        | Type::LogicalLength
+        | Type::PhysicalLength
+        | Type::Rem => Value::Number(random_number(rng, 1000.0)),
+        Type::Percent => Value::Number(random_number(rng, 200.0)),
+        Type::Bool => Value::Bool(rng.below(2) == 0),
+        Type::Color | Type::Brush => {
+            let [r, g, b, a] = (rng.next_u64() as u32).to_le_bytes();
+            Value::Brush(slint::Brush::SolidColor(slint::Color::from_argb_u8(a, r, g, b)))
+        }
+        Type::Enumeration(enumeration) if !enumeration.values.is_empty() => {
+            Value::EnumerationValue(
+                enumeration.name.to_string(),
+                rng.pick(&enumeration.values).to_string(),
+            )
+        }
+        Type::Struct(s) => Value::Struct(
+            s.fields
+                .iter()
+                .map(|(field, ty)| {
+                    let value = random_value(ty, rng)
+                        .unwrap_or_else(|| slint_interpreter::default_value_for_type(ty));
+                    (field.to_string(), value)
+                })
+                .collect(),
+        ),
+        Type::Array(row_type) => {
+            let count = match rng.below(4) {
+                0 => 0,
+                1 => MAX_ROWS,
+                _ => rng.below(MAX_ROWS),
+            };
+            let rows = (0..count)
+                .map(|_| {
+                    random_value(row_type, rng)
+                        .unwrap_or_else(|| slint_interpreter::default_value_for_type(row_type))
+                })
+                .collect::<Vec<_>>();
+            Value::Model(slint::ModelRc::new(slint::VecModel::from(rows)))
+        }
+        _ => return None,
+    })
+}
+
+/// Set the settable properties of the `component_instance` to random values from `seed`.
+/// Returns the properties that could not be set.
+pub fn randomize(component_instance: &ComponentInstance, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let properties = preview_data::query_preview_data_properties_and_callbacks(component_instance)
+        .remove(&PropertyContainer::Main)
+        .unwrap_or_default();
+
+    properties
+        .iter()
+        .filter(|p| p.is_property() && p.has_setter())
+        .filter_map(|p| {
+            let value = random_value(&p.ty, &mut rng)?;
+            component_instance
+                .set_property(&p.name, value)
+                .err()
+                .map(|e| format!("{}: {e}", p.name))
+        })
+        .collect()
+}
+
+/// The seed as shown to the user
+pub fn format_seed(seed: u64) -> String {
+    format!("{seed:016x}")
+}
+
+/// The seed shown to the user, with or without `0x` in front
+pub fn parse_seed(text: &str) -> Option<u64> {
+    u64::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_value() {
+        let values = |seed| {
+            let mut rng = Rng::new(seed);
+            [Type::String, Type::Float32, Type::Bool, Type::Color]
+                .iter()
+                .cycle()
+                .take(20)
+                .map(|ty| random_value(ty, &mut rng).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(42), values(42));
+        assert_ne!(values(42), values(43));
+
+        let ty = Type::Array(std::rc::Rc::new(Type::String));
+        let mut rng = Rng::new(1);
+        for _ in 0..100 {
+            let Some(Value::Model(model)) = random_value(&ty, &mut rng) else {
+                panic!("Not a model");
+            };
+            assert!(slint::Model::row_count(&model) <= MAX_ROWS);
+        }
+        assert_eq!(random_value(&Type::Image, &mut rng), None);
+    }
+
+    #[test]
+    fn test_seed() {
+        assert_eq!(format_seed(0xabc), "0000000000000abc");
+        assert_eq!(parse_seed(" 0000000000000abc"), Some(0xabc));
+        assert_eq!(parse_seed("0xABC"), Some(0xabc));
+        assert_eq!(parse_seed("xyz"), None);
+    }
+}
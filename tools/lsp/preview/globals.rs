@@ -0,0 +1,83 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The global singletons exported by the previewed document, whose default bindings can be
+//! edited like the properties of a selected element.
+
+use lsp_types::Url;
+
+use crate::common;
+use crate::preview::properties;
+
+/// The exported globals of the document at `url`, by exported name
+pub fn exported_globals(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+) -> Vec<(String, common::ElementRcNode)> {
+    let Some(document) = document_cache.get_document(url) else {
+        return vec![];
+    };
+    document
+        .exports
+        .iter()
+        .filter_map(|(name, e)| {
+            let component = e.as_ref().left()?;
+            if !component.is_global() {
+                return None;
+            }
+            let root = common::ElementRcNode::new(component.root_element.clone(), 0)?;
+            Some((name.name.to_string(), root))
+        })
+        .collect()
+}
+
+/// The properties of the exported global `name` of the document at `url`, with the url of the
+/// file the global is defined in
+pub fn global_properties(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    name: &str,
+) -> Option<properties::QueryPropertyResponse> {
+    let (_, root) = exported_globals(document_cache, url).into_iter().find(|(n, _)| n == name)?;
+    let path = root.path_and_offset().0;
+    let url = Url::from_file_path(path).ok()?;
+    let version = document_cache.document_version(&url);
+    properties::query_properties(&url, version, &root, properties::LayoutKind::None).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    #[test]
+    fn test_exported_globals() {
+        let source = r#"
+global Internal {
+    in-out property <int> count: 1;
+}
+export global Settings {
+    in-out property <string> title: "Hello";
+    in-out property <bool> dark;
+}
+export global Theme {
+    in-out property <color> accent: blue;
+}
+export component Main {
+    Text { text: Settings.title + Internal.count; }
+}
+"#;
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        let mut names = exported_globals(&dc, &url).into_iter().map(|(n, _)| n).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["Settings", "Theme"]);
+
+        let properties = global_properties(&dc, &url, "Settings").unwrap();
+        let names = properties.properties.iter().map(|p| p.name.to_string()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["dark", "title"]);
+        let title = properties.properties.iter().find(|p| p.name == "title").unwrap();
+        assert!(title.defined_at.is_some());
+        assert!(global_properties(&dc, &url, "Internal").is_none());
+    }
+}
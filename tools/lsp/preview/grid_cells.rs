@@ -0,0 +1,396 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The cells of a `GridLayout`: Where its children end up, the rows and columns they form in
+//! the preview, and the `row`, `col`, `rowspan` and `colspan` bindings needed to move a child
+//! into another cell without moving the others.
+
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind};
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
+use slint_interpreter::ComponentInstance;
+
+use crate::common::{self, ElementRcNode};
+use crate::preview::ext::ElementRcNodeExt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cell {
+    pub row: u16,
+    pub col: u16,
+    pub rowspan: u16,
+    pub colspan: u16,
+}
+
+/// A child of a grid layout, with the placement written in the source
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Child {
+    pub row: Option<u16>,
+    pub col: Option<u16>,
+    pub rowspan: Option<u16>,
+    pub colspan: Option<u16>,
+    /// Identifies the `Row` element the child is in, if any
+    pub row_group: Option<u32>,
+}
+
+/// Place the `children` like the compiler does: Each child goes into the column after the
+/// previous one, unless it sets its `row` or `col`. Setting `row` starts over in column 0,
+/// and a `Row` element starts and ends a row of its own.
+pub fn place(children: &[Child]) -> Vec<Cell> {
+    let (mut row, mut col) = (0, 0);
+    let mut row_group = None;
+    children
+        .iter()
+        .map(|child| {
+            if child.row_group != row_group {
+                if col > 0 {
+                    row += 1;
+                    col = 0;
+                }
+                row_group = child.row_group;
+            }
+            if let Some(r) = child.row {
+                row = r;
+                col = 0;
+            }
+            if let Some(c) = child.col {
+                col = c;
+            }
+            let cell = Cell {
+                row,
+                col,
+                rowspan: child.rowspan.unwrap_or(1),
+                colspan: child.colspan.unwrap_or(1),
+            };
+            col = col.saturating_add(1);
+            cell
+        })
+        .collect()
+}
+
+/// The bindings to set on the `children` to put the child at `index` into `target`
+///
+/// A child already in the target cell swaps places with it. All other children stay where
+/// they are, the ones that were placed relative to the moved child get pinned to their cell.
+/// The result holds the index of every child to change and its bindings.
+pub fn edits(
+    children: &[Child],
+    index: usize,
+    target: Cell,
+) -> Vec<(usize, Vec<(&'static str, u16)>)> {
+    let before = place(children);
+    let Some(current) = before.get(index).copied() else {
+        return vec![];
+    };
+    let mut after = children.to_vec();
+
+    let mut pinned = vec![index];
+    if (current.row, current.col) != (target.row, target.col) {
+        after[index].row = Some(target.row);
+        after[index].col = Some(target.col);
+
+        if let Some(other) = (0..children.len())
+            .find(|&i| i != index && (before[i].row, before[i].col) == (target.row, target.col))
+        {
+            after[other].row = Some(current.row);
+            after[other].col = Some(current.col);
+            pinned.push(other);
+        }
+    }
+    if current.rowspan != target.rowspan {
+        after[index].rowspan = Some(target.rowspan.max(1));
+    }
+    if current.colspan != target.colspan {
+        after[index].colspan = Some(target.colspan.max(1));
+    }
+
+    for i in 0..children.len() {
+        if pinned.contains(&i) {
+            continue;
+        }
+        let cell = place(&after)[i];
+        if (cell.row, cell.col) != (before[i].row, before[i].col) {
+            after[i].row = Some(before[i].row);
+            after[i].col = Some(before[i].col);
+        }
+    }
+
+    children
+        .iter()
+        .zip(&after)
+        .enumerate()
+        .filter_map(|(i, (old, new))| {
+            let changed = [
+                ("row", old.row, new.row),
+                ("col", old.col, new.col),
+                ("rowspan", old.rowspan, new.rowspan),
+                ("colspan", old.colspan, new.colspan),
+            ]
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .filter_map(|(name, _, new)| Some((name, new?)))
+            .collect::<Vec<_>>();
+            (!changed.is_empty()).then_some((i, changed))
+        })
+        .collect()
+}
+
+/// The `(start, end)` of the tracks (rows or columns) of a grid, from the `(index, span,
+/// start, end)` of its children along one axis
+///
+/// Tracks no child starts or ends in get their bounds from their neighbors.
+pub fn tracks(spans: &[(u16, u16, f32, f32)]) -> Vec<(f32, f32)> {
+    let count = spans
+        .iter()
+        .map(|&(index, span, ..)| index as usize + span.max(1) as usize)
+        .max()
+        .unwrap_or_default();
+    let mut starts = vec![None::<f32>; count];
+    let mut ends = vec![None::<f32>; count];
+    for &(index, span, start, end) in spans {
+        let first = index as usize;
+        let last = first + span.max(1) as usize - 1;
+        starts[first] = Some(starts[first].map_or(start, |s| s.min(start)));
+        ends[last] = Some(ends[last].map_or(end, |e| e.max(end)));
+    }
+
+    let mut result: Vec<(f32, f32)> = Vec::with_capacity(count);
+    for i in 0..count {
+        let start = starts[i]
+            .or(result.last().map(|t| t.1))
+            .or(ends[i])
+            .or_else(|| starts[i..].iter().chain(&ends[i..]).find_map(|s| *s))
+            .unwrap_or_default();
+        let end = ends[i].or(starts.get(i + 1).copied().flatten()).unwrap_or(start).max(start);
+        result.push((start, end));
+    }
+    result
+}
+
+/// The index of the track at `position`, the nearest one when it is between two tracks, or
+/// a new track after the last one when it is behind all of them
+pub fn track_at(tracks: &[(f32, f32)], position: f32) -> u16 {
+    if tracks.last().is_some_and(|t| position > t.1) {
+        return tracks.len() as u16;
+    }
+    let distance = |(start, end): (f32, f32)| {
+        if position < start {
+            start - position
+        } else {
+            f32::max(position - end, 0.0)
+        }
+    };
+    tracks
+        .iter()
+        .enumerate()
+        .min_by(|a, b| distance(*a.1).total_cmp(&distance(*b.1)))
+        .map(|(i, _)| i as u16)
+        .unwrap_or_default()
+}
+
+/// A grid layout as shown in the preview, with one of its children selected
+pub struct Grid {
+    /// The children of the layout in source order
+    pub children: Vec<(Child, ElementRcNode)>,
+    pub cells: Vec<Cell>,
+    pub selected: usize,
+    pub geometry: LogicalRect,
+    pub rows: Vec<(f32, f32)>,
+    pub columns: Vec<(f32, f32)>,
+}
+
+impl Grid {
+    /// The cell at `position` in the preview
+    pub fn cell_at(&self, position: LogicalPoint) -> (u16, u16) {
+        (track_at(&self.rows, position.y), track_at(&self.columns, position.x))
+    }
+
+    pub fn selected_cell(&self) -> Cell {
+        self.cells[self.selected]
+    }
+
+    /// The geometry of `cell` in the preview
+    pub fn cell_geometry(&self, cell: Cell) -> Option<LogicalRect> {
+        let span = |tracks: &[(f32, f32)], index: u16, span: u16| {
+            let first = tracks.get(index as usize)?;
+            let last = tracks.get((index + span.max(1) - 1) as usize).unwrap_or(first);
+            Some((first.0, last.1))
+        };
+        let (y1, y2) = span(&self.rows, cell.row, cell.rowspan)?;
+        let (x1, x2) = span(&self.columns, cell.col, cell.colspan)?;
+        Some(LogicalRect::new(LogicalPoint::new(x1, y1), LogicalSize::new(x2 - x1, y2 - y1)))
+    }
+}
+
+fn placement_binding(element: &syntax_nodes::Element, name: &str) -> Option<u16> {
+    let binding = element
+        .Binding()
+        .find(|b| b.child_text(SyntaxKind::Identifier).as_deref() == Some(name))?;
+    binding.BindingExpression().text().to_string().trim().trim_end_matches(';').trim().parse().ok()
+}
+
+fn child_of(element: &syntax_nodes::Element, row_group: Option<u32>) -> Child {
+    Child {
+        row: placement_binding(element, "row"),
+        col: placement_binding(element, "col"),
+        rowspan: placement_binding(element, "rowspan"),
+        colspan: placement_binding(element, "colspan"),
+        row_group,
+    }
+}
+
+/// The grid layout `element_node` is in, shown at `geometry` in the preview
+pub fn grid(
+    component_instance: &ComponentInstance,
+    element_node: &ElementRcNode,
+    geometry: LogicalRect,
+) -> Option<Grid> {
+    let layout = element_node.parent()?;
+    let layout_geometry = layout
+        .geometries(component_instance)
+        .into_iter()
+        .find(|g| g.contains(geometry.center()))?;
+
+    let children = layout.with_element_node(|node| {
+        let mut children = vec![];
+        for element in node.children().filter_map(common::extract_element) {
+            let is_row =
+                element.QualifiedName().is_some_and(|qn| qn.text().to_string().trim() == "Row");
+            if is_row {
+                let row_group = Some(u32::from(element.text_range().start()));
+                for cell in element.children().filter_map(common::extract_element) {
+                    children.push((child_of(&cell, row_group), cell));
+                }
+            } else {
+                children.push((child_of(&element, None), element));
+            }
+        }
+        children
+            .into_iter()
+            .filter_map(|(child, element)| {
+                let node = ElementRcNode::find_in_or_below(
+                    layout.as_element().clone(),
+                    element.source_file.path(),
+                    u32::from(element.text_range().start()),
+                )?;
+                Some((child, node))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let (path, offset) = element_node.path_and_offset();
+    let selected =
+        children.iter().position(|(_, node)| node.path_and_offset() == (path.clone(), offset))?;
+
+    let cells = place(&children.iter().map(|(child, _)| *child).collect::<Vec<_>>());
+    let geometries = children
+        .iter()
+        .map(|(_, node)| {
+            node.geometries(component_instance)
+                .into_iter()
+                .find(|g| layout_geometry.contains(g.center()))
+        })
+        .collect::<Vec<_>>();
+
+    let spans = |axis: fn(&Cell, &LogicalRect) -> (u16, u16, f32, f32)| {
+        cells
+            .iter()
+            .zip(&geometries)
+            .filter_map(|(cell, geometry)| Some(axis(cell, geometry.as_ref()?)))
+            .collect::<Vec<_>>()
+    };
+    let rows = tracks(&spans(|c, g| (c.row, c.rowspan, g.min_y(), g.max_y())));
+    let columns = tracks(&spans(|c, g| (c.col, c.colspan, g.min_x(), g.max_x())));
+
+    Some(Grid { children, cells, selected, geometry: layout_geometry, rows, columns })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto() -> Child {
+        Child::default()
+    }
+
+    fn at(row: u16, col: u16) -> Child {
+        Child { row: Some(row), col: Some(col), ..Default::default() }
+    }
+
+    fn in_row(group: u32) -> Child {
+        Child { row_group: Some(group), ..Default::default() }
+    }
+
+    fn positions(children: &[Child]) -> Vec<(u16, u16)> {
+        place(children).iter().map(|c| (c.row, c.col)).collect()
+    }
+
+    #[test]
+    fn test_place() {
+        assert_eq!(positions(&[auto(), auto(), auto()]), [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(
+            positions(&[auto(), Child { row: Some(2), ..auto() }, auto()]),
+            [(0, 0), (2, 0), (2, 1)]
+        );
+        assert_eq!(
+            positions(&[auto(), Child { col: Some(3), ..auto() }, auto()]),
+            [(0, 0), (0, 3), (0, 4)]
+        );
+        assert_eq!(
+            positions(&[in_row(1), in_row(1), in_row(2), auto(), in_row(3)]),
+            [(0, 0), (0, 1), (1, 0), (2, 0), (3, 0)]
+        );
+
+        let spanned = Child { rowspan: Some(2), colspan: Some(3), ..auto() };
+        assert_eq!(place(&[spanned]), [Cell { row: 0, col: 0, rowspan: 2, colspan: 3 }]);
+    }
+
+    #[test]
+    fn test_edits() {
+        let cell = |row, col| Cell { row, col, rowspan: 1, colspan: 1 };
+
+        // Moving the first child pins the ones following it
+        let children = [auto(), auto(), auto()];
+        assert_eq!(
+            edits(&children, 0, cell(1, 0)),
+            [(0, vec![("row", 1), ("col", 0)]), (1, vec![("row", 0), ("col", 1)])]
+        );
+
+        // Swapping places
+        let children = [auto(), auto()];
+        assert_eq!(
+            edits(&children, 1, cell(0, 0)),
+            [(0, vec![("row", 0), ("col", 1)]), (1, vec![("row", 0), ("col", 0)])]
+        );
+
+        // Moving the last child needs no other change
+        let children = [at(0, 0), at(0, 1), auto()];
+        assert_eq!(edits(&children, 2, cell(3, 2)), [(2, vec![("row", 3), ("col", 2)])]);
+
+        // Spanning keeps the position
+        let children = [auto(), auto()];
+        assert_eq!(
+            edits(&children, 0, Cell { row: 0, col: 0, rowspan: 2, colspan: 1 }),
+            [(0, vec![("rowspan", 2)])]
+        );
+        assert!(edits(&children, 1, cell(0, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_tracks() {
+        assert!(tracks(&[]).is_empty());
+        assert_eq!(
+            tracks(&[(0, 1, 0.0, 10.0), (1, 1, 15.0, 30.0), (0, 2, 0.0, 30.0)]),
+            [(0.0, 10.0), (15.0, 30.0)]
+        );
+        // Track 1 is only spanned over
+        assert_eq!(
+            tracks(&[(0, 3, 0.0, 50.0), (0, 1, 0.0, 10.0), (2, 1, 40.0, 50.0)]),
+            [(0.0, 10.0), (10.0, 40.0), (40.0, 50.0)]
+        );
+
+        let tracks = [(0.0, 10.0), (15.0, 30.0)];
+        assert_eq!(track_at(&tracks, 5.0), 0);
+        assert_eq!(track_at(&tracks, 13.0), 1);
+        assert_eq!(track_at(&tracks, -5.0), 0);
+        assert_eq!(track_at(&tracks, 31.0), 2);
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The images of a project, for the editor of image properties: Found in the directories of the
+//! project, and written as `@image-url(...)` with a path relative to the document using them.
+
+use std::path::{Path, PathBuf};
+
+use i_slint_compiler::parser::{SyntaxKind, SyntaxNode};
+
+use super::file_drop::{self, FileKind};
+
+/// Directories that hold build output or tooling rather than assets
+const SKIPPED_DIRECTORIES: &[&str] = &["target", "node_modules", "build"];
+
+/// The number of images listed at most, large projects would take too long to show otherwise
+const MAX_IMAGES: usize = 200;
+
+/// The arguments of an `@image-url(...)`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageUrl {
+    pub path: String,
+    /// The borders of the nine-slice scaling: One, two or four numbers, or none
+    pub nine_slice: Vec<u16>,
+}
+
+impl ImageUrl {
+    /// The `@image-url(...)` in the `expression`, if it is just that
+    pub fn from_expression(expression: &SyntaxNode) -> Option<Self> {
+        let url = expression.child_node(SyntaxKind::AtImageUrl)?;
+        let path = url
+            .child_text(SyntaxKind::StringLiteral)
+            .and_then(|s| i_slint_compiler::literals::unescape_string(&s))?;
+        let nine_slice = url
+            .children_with_tokens()
+            .filter_map(|t| t.into_token())
+            .filter(|t| t.kind() == SyntaxKind::NumberLiteral)
+            .map(|t| t.text().parse::<u16>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { path: path.to_string(), nine_slice })
+    }
+
+    pub fn to_code(&self) -> String {
+        let path = format!("\"{}\"", self.path.escape_debug());
+        if self.nine_slice.is_empty() {
+            format!("@image-url({path})")
+        } else {
+            let numbers = self.nine_slice.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            format!("@image-url({path}, nine-slice({}))", numbers.join(" "))
+        }
+    }
+}
+
+/// Read the borders of a nine-slice as written by the user: One, two or four numbers separated
+/// by spaces or commas, or nothing
+pub fn parse_nine_slice(text: &str) -> Option<Vec<u16>> {
+    let numbers = text
+        .split([' ', ','])
+        .filter(|n| !n.is_empty())
+        .map(|n| n.parse::<u16>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    matches!(numbers.len(), 0 | 1 | 2 | 4).then_some(numbers)
+}
+
+fn collect(directory: &Path, result: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    let mut entries = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect::<Vec<_>>();
+    entries.sort();
+    for path in entries {
+        if result.len() >= MAX_IMAGES {
+            return;
+        }
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                collect(&path, result);
+            }
+        } else if file_drop::file_kind(&path) == Some(FileKind::Image) {
+            result.push(path);
+        }
+    }
+}
+
+/// The images in `root` and its subdirectories, in the order of their paths
+pub fn find_images(root: &Path) -> Vec<PathBuf> {
+    let mut result = vec![];
+    collect(root, &mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_expression(code: &str) -> SyntaxNode {
+        let mut diag = i_slint_compiler::diagnostics::BuildDiagnostics::default();
+        let source = format!("export component Test {{ in property <image> test: {code}; }}");
+        let document = i_slint_compiler::parser::parse(source, None, &mut diag);
+        document.descendants().find(|n| n.kind() == SyntaxKind::Expression).unwrap()
+    }
+
+    #[test]
+    fn test_image_url() {
+        let url = ImageUrl::from_expression(&parse_expression(
+            r#"@image-url("images/button.png", nine-slice(4 8))"#,
+        ))
+        .unwrap();
+        assert_eq!(url, ImageUrl { path: "images/button.png".into(), nine_slice: vec![4, 8] });
+        assert_eq!(url.to_code(), r#"@image-url("images/button.png", nine-slice(4 8))"#);
+
+        let url = ImageUrl::from_expression(&parse_expression(r#"@image-url("a \"b\".svg")"#));
+        assert_eq!(url.as_ref().map(|u| u.path.as_str()), Some(r#"a "b".svg"#));
+        assert_eq!(url.unwrap().to_code(), r#"@image-url("a \"b\".svg")"#);
+
+        assert_eq!(ImageUrl::from_expression(&parse_expression("root.icon")), None);
+
+        assert_eq!(parse_nine_slice(""), Some(vec![]));
+        assert_eq!(parse_nine_slice("1, 2 3 4"), Some(vec![1, 2, 3, 4]));
+        assert_eq!(parse_nine_slice("1 2 3"), None);
+        assert_eq!(parse_nine_slice("-1"), None);
+    }
+
+    #[test]
+    fn test_find_images() {
+        let root = std::env::temp_dir().join(format!("slint-image-assets-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("icons")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        for file in ["icons/b.svg", "a.PNG", "main.slint", "target/c.png", ".git/d.png"] {
+            std::fs::write(root.join(file), b"").unwrap();
+        }
+        let images = find_images(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(images, [root.join("a.PNG"), root.join("icons").join("b.svg")]);
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The areas of the preview that accept pointer or keyboard input, to find out why a
+//! click does not end up where it is supposed to
+
+use i_slint_core::items::{
+    Flickable, FocusScope, ItemRc, SwipeGestureHandler, TextInput, TouchArea,
+};
+use i_slint_core::lengths::{LogicalPoint, LogicalRect};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionKind {
+    TouchArea,
+    FocusScope,
+    SwipeGestureHandler,
+    Flickable,
+    TextInput,
+}
+
+impl RegionKind {
+    fn of(item: &ItemRc) -> Option<(Self, bool)> {
+        if let Some(i) = item.downcast::<TouchArea>() {
+            Some((RegionKind::TouchArea, i.as_pin_ref().enabled()))
+        } else if let Some(i) = item.downcast::<FocusScope>() {
+            Some((RegionKind::FocusScope, i.as_pin_ref().enabled()))
+        } else if let Some(i) = item.downcast::<SwipeGestureHandler>() {
+            Some((RegionKind::SwipeGestureHandler, i.as_pin_ref().enabled()))
+        } else if let Some(i) = item.downcast::<TextInput>() {
+            Some((RegionKind::TextInput, i.as_pin_ref().enabled()))
+        } else if item.downcast::<Flickable>().is_some() {
+            Some((RegionKind::Flickable, true))
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RegionKind::TouchArea => "TouchArea",
+            RegionKind::FocusScope => "FocusScope",
+            RegionKind::SwipeGestureHandler => "SwipeGestureHandler",
+            RegionKind::Flickable => "Flickable",
+            RegionKind::TextInput => "TextInput",
+        }
+    }
+}
+
+/// An item of the preview handling input
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputRegion {
+    pub geometry: LogicalRect,
+    pub kind: RegionKind,
+    /// Disabled regions let the input pass through to the regions below them
+    pub enabled: bool,
+}
+
+/// The input regions of the preview shown in `window`, in the order they are drawn in:
+/// Later regions are on top of earlier ones.
+pub fn collect(window: &slint::Window) -> Vec<InputRegion> {
+    let mut regions = vec![];
+    super::render_stats::visit_window_items(window, |item| {
+        let Some((kind, enabled)) = RegionKind::of(item) else {
+            return;
+        };
+        // Items clipped away do not get any input
+        if let Some(geometry) =
+            super::render_stats::geometry_in_preview(item).filter(|_| item.is_visible())
+        {
+            regions.push(InputRegion { geometry, kind, enabled });
+        }
+    });
+    regions
+}
+
+/// The index of the region in `regions` receiving a click at `position`: The topmost
+/// enabled region containing it
+pub fn region_at(regions: &[InputRegion], position: LogicalPoint) -> Option<usize> {
+    regions.iter().rposition(|r| r.enabled && r.geometry.contains(position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i_slint_core::lengths::LogicalSize;
+
+    #[test]
+    fn test_region_at() {
+        let region = |x: f32, kind, enabled| InputRegion {
+            geometry: LogicalRect::new(LogicalPoint::new(x, 0.0), LogicalSize::new(50.0, 50.0)),
+            kind,
+            enabled,
+        };
+        let regions = [
+            region(0.0, RegionKind::Flickable, true),
+            region(20.0, RegionKind::TouchArea, true),
+            region(30.0, RegionKind::TouchArea, false),
+        ];
+
+        assert_eq!(region_at(&regions, LogicalPoint::new(10.0, 10.0)), Some(0));
+        assert_eq!(region_at(&regions, LogicalPoint::new(25.0, 10.0)), Some(1));
+        // The disabled region on top lets the click through
+        assert_eq!(region_at(&regions, LogicalPoint::new(40.0, 10.0)), Some(1));
+        assert_eq!(region_at(&regions, LogicalPoint::new(75.0, 10.0)), None);
+        assert_eq!(region_at(&regions, LogicalPoint::new(10.0, 60.0)), None);
+    }
+}
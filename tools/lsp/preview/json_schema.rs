@@ -0,0 +1,366 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! A JSON schema for the values of a Slint type, to edit the preview data of properties too
+//! complex for the other editors as JSON: Completion of the field names and enum values at the
+//! end of the JSON text, and validation of its structure before setting it.
+
+use i_slint_compiler::langtype::Type;
+use serde_json::{json, Value};
+
+/// How many completions get suggested at most
+const MAX_COMPLETIONS: usize = 30;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonCompletion {
+    pub label: String,
+    /// The whole JSON text with the completion applied
+    pub text: String,
+}
+
+/// The schema of the JSON values `slint_interpreter::json::value_from_json()` accepts for `ty`
+pub fn schema(ty: &Type) -> Value {
+    match ty {
+        Type::Bool => json!({ "type": "boolean" }),
+        Type::Int32 => json!({ "type": "integer" }),
+        Type::Float32
+        | Type::Duration
+        | Type::PhysicalLength
+        | Type::LogicalLength
+        | Type::Rem
+        | Type::Angle
+        | Type::Percent => json!({ "type": "number" }),
+        Type::String | Type::Color | Type::Brush | Type::Image => json!({ "type": "string" }),
+        Type::Enumeration(e) => {
+            json!({ "enum": e.values.iter().map(|v| v.as_str()).collect::<Vec<_>>() })
+        }
+        Type::Array(item) => json!({ "type": "array", "items": schema(item) }),
+        Type::Struct(s) => {
+            let properties =
+                s.fields.iter().map(|(name, ty)| (name.to_string(), schema(ty))).collect();
+            json!({
+                "type": "object",
+                "properties": Value::Object(properties),
+                "additionalProperties": false,
+            })
+        }
+        // Anything goes
+        _ => json!({}),
+    }
+}
+
+fn type_matches(ty: &str, value: &Value) -> bool {
+    match ty {
+        "boolean" => value.is_boolean(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let at = if path.is_empty() { "/" } else { path };
+
+    if let Some(ty) = schema.get("type").and_then(Value::as_str) {
+        if !type_matches(ty, value) {
+            let expected = match ty {
+                "array" | "integer" | "object" => format!("an {ty}"),
+                _ => format!("a {ty}"),
+            };
+            errors.push(format!("{at}: Expected {expected}, found {}", describe(value)));
+            return;
+        }
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        if !values.contains(value) {
+            let values = values.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(", ");
+            errors.push(format!("{at}: Expected one of {values}"));
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (index, item) in array.iter().enumerate() {
+            validate_at(items, item, &format!("{path}/{index}"), errors);
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+        for (name, field) in object {
+            match properties.and_then(|p| p.get(name)) {
+                Some(field_schema) => {
+                    validate_at(field_schema, field, &format!("{path}/{name}"), errors)
+                }
+                None if closed => errors.push(format!("{at}: Unknown field {name}")),
+                None => {}
+            }
+        }
+    }
+}
+
+/// The ways `value` does not follow the `schema`, with the JSON pointer to where each one is
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "", &mut errors);
+    errors
+}
+
+/// An object or array that is still open at the end of the text
+enum Container {
+    Object {
+        keys: Vec<String>,
+        /// The key of the current field, once it is complete
+        key: Option<String>,
+        after_colon: bool,
+    },
+    Array,
+}
+
+/// Where the end of the text is, in the structure of the JSON value
+enum Position {
+    Key { keys: Vec<String> },
+    Value,
+    Other,
+}
+
+/// The schema of the value the end of `text` is in, and whether that is a field name or a value.
+/// Also returns where the incomplete string or literal at the end of the text starts.
+fn position_at_end<'a>(schema: &'a Value, text: &str) -> (Option<&'a Value>, Position, usize) {
+    let mut containers: Vec<(Container, Option<&'a Value>)> = Vec::new();
+    // The schema of the value that starts at the current position
+    let value_schema = |containers: &[(Container, Option<&'a Value>)]| match containers.last() {
+        None => Some(schema),
+        Some((Container::Object { key, .. }, s)) => {
+            s.and_then(|s| s.get("properties")?.get(key.as_deref()?))
+        }
+        Some((Container::Array, s)) => s.and_then(|s| s.get("items")),
+    };
+
+    let mut chars = text.char_indices();
+    let mut open_string = None;
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut content = String::new();
+                let mut closed = false;
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                content.push(escaped);
+                            }
+                        }
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        c => content.push(c),
+                    }
+                }
+                if !closed {
+                    open_string = Some(index);
+                    break;
+                }
+                if let Some((Container::Object { keys, key, after_colon: false }, _)) =
+                    containers.last_mut()
+                {
+                    keys.push(content.clone());
+                    *key = Some(content);
+                }
+            }
+            ':' => {
+                if let Some((Container::Object { after_colon, .. }, _)) = containers.last_mut() {
+                    *after_colon = true;
+                }
+            }
+            ',' => {
+                if let Some((Container::Object { key, after_colon, .. }, _)) = containers.last_mut()
+                {
+                    *key = None;
+                    *after_colon = false;
+                }
+            }
+            '{' => {
+                let s = value_schema(&containers);
+                let object = Container::Object { keys: Vec::new(), key: None, after_colon: false };
+                containers.push((object, s));
+            }
+            '[' => {
+                let s = value_schema(&containers);
+                containers.push((Container::Array, s));
+            }
+            '}' | ']' => {
+                containers.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let start = match open_string {
+        Some(start) => start,
+        // A literal like `tru`
+        None => text.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len(),
+    };
+    let ends_with_literal = open_string.is_none() && start < text.len();
+
+    match containers.last() {
+        Some((Container::Object { after_colon: false, key: None, keys }, s))
+            if !ends_with_literal =>
+        {
+            (*s, Position::Key { keys: keys.clone() }, start)
+        }
+        Some((Container::Object { after_colon: false, .. }, _)) => (None, Position::Other, start),
+        _ => {
+            // A value starts after these, or at the start of the text
+            let trimmed = text[..start].trim_end();
+            if trimmed.is_empty() || trimmed.ends_with([':', ',', '[']) {
+                (value_schema(&containers), Position::Value, start)
+            } else {
+                (None, Position::Other, start)
+            }
+        }
+    }
+}
+
+/// Completions for the end of the JSON `text`: The fields of an object that are not set yet,
+/// and the values of enums and booleans
+pub fn complete(schema: &Value, text: &str) -> Vec<JsonCompletion> {
+    let (value_schema, position, start) = position_at_end(schema, text);
+    let Some(value_schema) = value_schema else {
+        return Vec::new();
+    };
+    let prefix = &text[start..];
+    let partial = prefix.trim_start_matches('"');
+    let completion = |label: String, inserted: String| JsonCompletion {
+        label,
+        text: format!("{}{inserted}", &text[..start]),
+    };
+
+    let completions = match position {
+        Position::Key { keys } => value_schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|p| p.keys())
+            .filter(|name| !keys.contains(name))
+            .map(|name| completion(name.clone(), format!("\"{name}\": ")))
+            .collect::<Vec<_>>(),
+        Position::Value => {
+            let enum_values = value_schema
+                .get("enum")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(|value| completion(value.to_string(), format!("\"{value}\"")));
+            let booleans = (value_schema.get("type") == Some(&json!("boolean")))
+                .then_some(["true", "false"])
+                .into_iter()
+                .flatten()
+                .map(|value| completion(value.to_string(), value.to_string()));
+            enum_values.chain(booleans).collect()
+        }
+        Position::Other => Vec::new(),
+    };
+
+    completions
+        .into_iter()
+        .filter(|c| c.label.starts_with(partial) && c.label != partial)
+        .take(MAX_COMPLETIONS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::preview::preview_data;
+
+    fn person_type() -> Type {
+        let instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+            enum Role { admin, guest }
+            struct Person { name: string, age: int, role: Role, active: bool }
+            export component Test { in property <[Person]> people; }
+            "#,
+        );
+        let people = preview_data::get_preview_data(
+            &instance,
+            preview_data::PropertyContainer::Main,
+            "people".into(),
+        );
+        people.unwrap().ty
+    }
+
+    fn labels(completions: Vec<JsonCompletion>) -> Vec<String> {
+        completions.into_iter().map(|c| c.label).collect()
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema = schema(&person_type());
+        assert_eq!(schema["type"], "array");
+        let person = &schema["items"];
+        assert_eq!(person["type"], "object");
+        assert_eq!(person["additionalProperties"], false);
+        assert_eq!(person["properties"]["age"], json!({ "type": "integer" }));
+        assert_eq!(person["properties"]["role"], json!({ "enum": ["admin", "guest"] }));
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = schema(&person_type());
+        let valid = json!([{ "name": "Jane", "age": 42, "role": "admin" }]);
+        assert_eq!(validate(&schema, &valid), Vec::<String>::new());
+
+        let invalid = json!([{ "name": 7, "age": 1.5 }, { "role": "owner", "nick": "J" }]);
+        assert_eq!(
+            validate(&schema, &invalid),
+            [
+                "/0/age: Expected an integer, found a number",
+                "/0/name: Expected a string, found a number",
+                "/1: Unknown field nick",
+                "/1/role: Expected one of admin, guest",
+            ]
+        );
+
+        assert_eq!(validate(&schema, &json!({})), ["/: Expected an array, found an object"]);
+    }
+
+    #[test]
+    fn test_complete() {
+        let schema = schema(&person_type());
+
+        assert_eq!(labels(complete(&schema, "[{")), ["active", "age", "name", "role"]);
+        assert_eq!(labels(complete(&schema, r#"[{"name": "Jane", "a"#)), ["active", "age"]);
+        let completions = complete(&schema, r#"[{"name": "Jane", "ag"#);
+        assert_eq!(completions[0].text, r#"[{"name": "Jane", "age": "#);
+
+        assert_eq!(labels(complete(&schema, r#"[{"role": "#)), ["admin", "guest"]);
+        let completions = complete(&schema, r#"[{"role": "g"#);
+        assert_eq!(completions[0].text, r#"[{"role": "guest""#);
+        assert_eq!(labels(complete(&schema, r#"[{"active": t"#)), ["true"]);
+
+        // Nothing to complete in a name or after a complete value
+        assert!(complete(&schema, r#"[{"name": "Ja"#).is_empty());
+        assert!(complete(&schema, r#"[{"role": "admin" "#).is_empty());
+        assert!(complete(&schema, r#"[{"role" "#).is_empty());
+    }
+}
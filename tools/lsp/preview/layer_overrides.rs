@@ -0,0 +1,298 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Hide elements of the preview to look at what is below them, without changing the document:
+//! Each element in the outline can be hidden, or soloed to hide all its siblings.
+//!
+//! Slint only creates the machinery for `visible` on elements that have a binding for it, so
+//! the preview gets compiled from a copy of the sources with `visible: false` set on the hidden
+//! elements. The copy keeps the elements where they are in the document as far as the
+//! indentation around the changes allows.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use i_slint_compiler::parser::{SyntaxKind, SyntaxNode, TextRange, TextSize};
+
+use super::outline::OutlineNode;
+
+/// An element, by the file it is in and where it starts
+pub type Element = (PathBuf, TextSize);
+
+const HIDDEN_BINDING: &str = "visible:false;";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Overrides {
+    /// The elements hidden with their eye toggle
+    pub hidden: Vec<Element>,
+    /// The soloed element
+    pub solo: Option<Element>,
+    /// The siblings hidden to solo `solo`
+    pub solo_siblings: Vec<Element>,
+}
+
+impl Overrides {
+    pub fn is_empty(&self) -> bool {
+        self.hidden.is_empty() && self.solo.is_none()
+    }
+
+    pub fn is_hidden(&self, element: &Element) -> bool {
+        self.hidden.contains(element) || self.solo_siblings.contains(element)
+    }
+
+    /// Hide the element at `index` of the `outline`, or show it again
+    pub fn toggle_hidden(&mut self, outline: &[OutlineNode], index: usize) {
+        let Some(node) = outline.get(index).filter(|n| can_hide(n)) else {
+            return;
+        };
+        let element = (node.path.clone(), node.offset);
+        if self.solo_siblings.contains(&element) {
+            self.solo_siblings.retain(|e| *e != element);
+        } else if self.hidden.contains(&element) {
+            self.hidden.retain(|e| *e != element);
+        } else {
+            self.hidden.push(element);
+        }
+    }
+
+    /// Hide the siblings of the element at `index` of the `outline`, or show them again
+    pub fn toggle_solo(&mut self, outline: &[OutlineNode], index: usize) {
+        let Some(node) = outline.get(index) else {
+            return;
+        };
+        let element = (node.path.clone(), node.offset);
+        if self.solo.as_ref() == Some(&element) {
+            self.solo = None;
+            self.solo_siblings.clear();
+        } else {
+            self.hidden.retain(|e| *e != element);
+            self.solo_siblings = siblings(outline, index);
+            self.solo = Some(element);
+        }
+    }
+
+    /// Where the elements to hide in the file at `path` start
+    pub fn hidden_in(&self, path: &Path) -> Vec<TextSize> {
+        self.hidden
+            .iter()
+            .chain(&self.solo_siblings)
+            .filter(|(p, _)| p == path)
+            .map(|(_, offset)| *offset)
+            .collect()
+    }
+}
+
+/// The root element and layouts can not be hidden: `visible` is not applied to them
+pub fn can_hide(node: &OutlineNode) -> bool {
+    node.depth > 0 && !node.is_layout
+}
+
+/// The siblings of the element at `index` of the `outline` that can be hidden. Siblings
+/// declared in another file, like the children of a component, are left alone.
+pub fn siblings(outline: &[OutlineNode], index: usize) -> Vec<Element> {
+    let Some(node) = outline.get(index).filter(|n| n.depth > 0) else {
+        return vec![];
+    };
+    let Some(parent) = outline[..index].iter().rposition(|n| n.depth < node.depth) else {
+        return vec![];
+    };
+    outline[parent + 1..]
+        .iter()
+        .enumerate()
+        .take_while(|(_, n)| n.depth >= node.depth)
+        .filter(|(i, n)| {
+            parent + 1 + i != index && n.depth == node.depth && n.path == node.path && can_hide(n)
+        })
+        .map(|(_, n)| (n.path.clone(), n.offset))
+        .collect()
+}
+
+/// What to change to hide `element`: Its `visible` binding, or the spot right before its
+/// closing brace if it has none
+fn hiding_edit(element: &SyntaxNode) -> Option<TextRange> {
+    let binding = element.children().find(|c| {
+        matches!(c.kind(), SyntaxKind::Binding | SyntaxKind::TwoWayBinding)
+            && c.child_text(SyntaxKind::Identifier).is_some_and(|name| name == "visible")
+    });
+    match binding {
+        Some(binding) => Some(binding.text_range()),
+        None => {
+            let brace = element.last_token().filter(|t| t.kind() == SyntaxKind::RBrace)?;
+            Some(TextRange::empty(brace.text_range().start()))
+        }
+    }
+}
+
+/// The indentation between `from` and `to`: Spaces and tabs at the start of a line
+fn indentation(document: &SyntaxNode, from: TextSize, to: TextSize) -> Vec<usize> {
+    document
+        .descendants_with_tokens()
+        .filter_map(|t| t.into_token())
+        .filter(|t| t.kind() == SyntaxKind::Whitespace)
+        .filter(|t| t.text_range().start() >= from && t.text_range().end() <= to)
+        .flat_map(|t| {
+            let start = usize::from(t.text_range().start());
+            let text = t.text().to_string();
+            let mut at_line_start = false;
+            let mut positions = vec![];
+            for (i, b) in text.bytes().enumerate() {
+                match b {
+                    b'\n' => at_line_start = true,
+                    b' ' | b'\t' if at_line_start => positions.push(start + i),
+                    _ => at_line_start = false,
+                }
+            }
+            positions
+        })
+        .collect()
+}
+
+/// The source of the `document` with `visible: false` set on the elements starting at
+/// `offsets`, or `None` if there is none of them.
+///
+/// To keep the elements where they are in the document, the text added gets compensated by
+/// removing indentation between the elements around the change. If there is not enough of it,
+/// the elements after the change move.
+pub fn hide_elements(document: &SyntaxNode, offsets: &[TextSize]) -> Option<String> {
+    let elements =
+        document.descendants().filter(|n| n.kind() == SyntaxKind::Element).collect::<Vec<_>>();
+    let starts = elements.iter().map(|e| e.text_range().start()).collect::<Vec<_>>();
+
+    let mut edits = elements
+        .iter()
+        .filter(|e| offsets.contains(&e.text_range().start()))
+        .filter_map(hiding_edit)
+        .collect::<Vec<_>>();
+    if edits.is_empty() {
+        return None;
+    }
+    edits.sort_by_key(|r| r.start());
+
+    let source = document.text().to_string();
+    let mut removed = BTreeSet::new();
+    for range in &edits {
+        let growth = HIDDEN_BINDING.len().saturating_sub(usize::from(range.len()));
+        let from = starts.iter().rev().find(|s| **s < range.start()).copied().unwrap_or_default();
+        let to = starts
+            .iter()
+            .find(|s| **s >= range.end())
+            .copied()
+            .unwrap_or(document.text_range().end());
+        let available = indentation(document, from, to)
+            .into_iter()
+            .filter(|p| !removed.contains(p))
+            .filter(|p| edits.iter().all(|e| !e.contains(TextSize::from(*p as u32))))
+            .take(growth)
+            .collect::<Vec<_>>();
+        removed.extend(available);
+    }
+
+    let mut changes = edits
+        .iter()
+        .map(|range| {
+            let replaced = &source[std::ops::Range::<usize>::from(*range)];
+            (
+                std::ops::Range::<usize>::from(*range),
+                super::states::replacement(HIDDEN_BINDING, replaced),
+            )
+        })
+        .chain(removed.into_iter().map(|p| (p..p + 1, String::new())))
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|(range, _)| range.start);
+
+    let mut source = source;
+    for (range, text) in changes.into_iter().rev() {
+        source.replace_range(range, &text);
+    }
+    Some(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> SyntaxNode {
+        let mut diag = i_slint_compiler::diagnostics::BuildDiagnostics::default();
+        i_slint_compiler::parser::parse(source.into(), None, &mut diag)
+    }
+
+    fn element_starts(document: &SyntaxNode) -> Vec<TextSize> {
+        document
+            .descendants()
+            .filter(|n| n.kind() == SyntaxKind::Element)
+            .map(|n| n.text_range().start())
+            .collect()
+    }
+
+    fn node(depth: usize, offset: u32, is_layout: bool) -> OutlineNode {
+        OutlineNode {
+            label: String::new(),
+            depth,
+            is_layout,
+            path: PathBuf::from("main.slint"),
+            offset: offset.into(),
+            address: None,
+        }
+    }
+
+    #[test]
+    fn test_hide_elements() {
+        let source = r#"
+export component Main {
+    Rectangle {
+        Text {
+            text: "a";
+        }
+        Text { visible: a; }
+    }
+    Text {
+        visible: root.width > 100px;
+    }
+}
+"#;
+        let document = parse(source);
+        let starts = element_starts(&document);
+        let hidden = hide_elements(&document, &starts[2..]).unwrap();
+
+        let hidden_document = parse(&hidden);
+        assert_eq!(element_starts(&hidden_document), starts);
+        assert_eq!(hidden.matches(HIDDEN_BINDING).count(), 3);
+        assert!(!hidden.contains("visible: a"));
+        assert!(!hidden.contains("root.width"));
+        assert_eq!(hidden.lines().count(), source.lines().count());
+
+        assert_eq!(hide_elements(&document, &[1000.into()]), None);
+    }
+
+    #[test]
+    fn test_siblings() {
+        let outline = [
+            node(0, 0, false),
+            node(1, 10, true),
+            node(2, 20, false),
+            node(2, 30, false),
+            node(3, 40, false),
+            node(2, 50, false),
+            node(1, 60, false),
+        ];
+        let offsets =
+            |index| siblings(&outline, index).into_iter().map(|(_, o)| o).collect::<Vec<_>>();
+        assert_eq!(offsets(3), [TextSize::from(20), TextSize::from(50)]);
+        // Layouts can not be hidden
+        assert_eq!(offsets(6), []);
+        assert_eq!(offsets(0), []);
+        assert_eq!(offsets(4), []);
+
+        let mut overrides = Overrides::default();
+        overrides.toggle_solo(&outline, 3);
+        overrides.toggle_hidden(&outline, 4);
+        assert_eq!(overrides.hidden_in(Path::new("main.slint")).len(), 3);
+        overrides.toggle_hidden(&outline, 2);
+        assert!(!overrides.is_hidden(&(outline[2].path.clone(), outline[2].offset)));
+        overrides.toggle_solo(&outline, 3);
+        assert_eq!(overrides.hidden_in(Path::new("main.slint")), [TextSize::from(40)]);
+        overrides.toggle_hidden(&outline, 0);
+        overrides.toggle_hidden(&outline, 4);
+        assert!(overrides.is_empty());
+    }
+}
@@ -0,0 +1,385 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Elements of the preview whose geometry is likely a mistake: Elements without a size,
+//! elements outside of the previewed component, children of a layout overlapping each other,
+//! and images stretched out of their aspect ratio. Also content that does not fit: Elided or
+//! cut off text, images larger than their element, and children cut off by a clipping parent.
+
+use std::path::PathBuf;
+
+use i_slint_compiler::parser::SyntaxKind;
+use i_slint_core::graphics::IntSize;
+use i_slint_core::item_tree::ItemRc;
+use i_slint_core::items::{
+    ClippedImage, ComplexText, Flickable, ImageFit, ImageItem, SimpleText, TextOverflow, TextWrap,
+};
+use i_slint_core::layout::Orientation;
+use i_slint_core::lengths::{LogicalRect, LogicalSize};
+use slint_interpreter::{ComponentHandle, ComponentInstance};
+
+use crate::common;
+use crate::preview::ext::ElementRcNodeExt;
+use crate::preview::ui;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Problem {
+    ZeroSize,
+    /// Completely outside of the previewed component
+    OffScreen,
+    /// Overlaps the sibling with the given label in a layout
+    Overlap(String),
+    /// The image is shown with another aspect ratio than its source has
+    Distorted {
+        shown: f32,
+        source: f32,
+    },
+    /// The text does not fit, and gets elided or cut off
+    TextCutOff {
+        elided: bool,
+    },
+    /// The image is shown at its source size, which is larger than the element
+    ImageCutOff {
+        source: LogicalSize,
+    },
+    /// Not completely inside the clipping parent with the given label
+    Clipped(String),
+}
+
+impl Problem {
+    pub fn label(&self) -> String {
+        match self {
+            Problem::ZeroSize => "has a width or height of zero".into(),
+            Problem::OffScreen => "is outside of the component".into(),
+            Problem::Overlap(other) => format!("overlaps {other} in the layout"),
+            Problem::Distorted { shown, source } => {
+                format!("is stretched to an aspect ratio of {shown:.2} instead of {source:.2}")
+            }
+            Problem::TextCutOff { elided: true } => "has elided text".into(),
+            Problem::TextCutOff { elided: false } => "has text that is cut off".into(),
+            Problem::ImageCutOff { source } => format!(
+                "is cut off, its image is {}x{} large",
+                source.width.round(),
+                source.height.round()
+            ),
+            Problem::Clipped(parent) => format!("is cut off by the clipping of {parent}"),
+        }
+    }
+
+    /// Whether content of the element is not shown
+    pub fn is_overflow(&self) -> bool {
+        matches!(
+            self,
+            Problem::TextCutOff { .. } | Problem::ImageCutOff { .. } | Problem::Clipped(_)
+        )
+    }
+}
+
+/// An element of the preview with problems
+#[derive(Clone, Debug, PartialEq)]
+pub struct Anomaly {
+    pub geometry: LogicalRect,
+    /// The type name of the element, with its id if it has one
+    pub element: String,
+    pub problems: Vec<Problem>,
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+fn is_outside(rect: &LogicalRect, bounds: &LogicalRect) -> bool {
+    rect.max_x() <= bounds.min_x()
+        || rect.min_x() >= bounds.max_x()
+        || rect.max_y() <= bounds.min_y()
+        || rect.min_y() >= bounds.max_y()
+}
+
+/// Whether `rect` reaches out of `bounds`, ignoring rounding errors of the layout
+fn exceeds(rect: &LogicalRect, bounds: &LogicalRect) -> bool {
+    rect.min_x() < bounds.min_x() - 0.5
+        || rect.min_y() < bounds.min_y() - 0.5
+        || rect.max_x() > bounds.max_x() + 0.5
+        || rect.max_y() > bounds.max_y() + 0.5
+}
+
+fn overlaps(a: &LogicalRect, b: &LogicalRect) -> bool {
+    // Ignore rounding errors of the layout
+    a.intersection(b).is_some_and(|r| r.width() > 0.5 && r.height() > 0.5)
+}
+
+/// The problem of showing an image of `source` size at `shown` size, if the aspect ratios
+/// differ by more than 2%
+fn distortion(shown: LogicalSize, source: IntSize) -> Option<Problem> {
+    if shown.is_empty() || source.is_empty() {
+        return None;
+    }
+    let shown = shown.width / shown.height;
+    let source = source.width as f32 / source.height as f32;
+    ((shown / source - 1.0).abs() > 0.02).then_some(Problem::Distorted { shown, source })
+}
+
+/// The first result of `f` for the items of the preview at the top left corner of `geometry`,
+/// called with their geometry in the preview
+fn visit_items_at<T>(
+    window: &slint::Window,
+    geometry: &LogicalRect,
+    mut f: impl FnMut(&ItemRc, LogicalRect) -> Option<T>,
+) -> Option<T> {
+    let same = |a: f32, b: f32| (a - b).abs() < 0.5;
+    let mut result = None;
+    super::render_stats::visit_window_items(window, |item| {
+        if result.is_some() {
+            return;
+        }
+        let Some(item_geometry) = super::render_stats::geometry_in_preview(item) else {
+            return;
+        };
+        if !same(item_geometry.min_x(), geometry.min_x())
+            || !same(item_geometry.min_y(), geometry.min_y())
+        {
+            return;
+        }
+        result = f(item, item_geometry);
+    });
+    result
+}
+
+/// The distortion of the image shown at `geometry` in the preview, if it fills its element, or
+/// the size of its source if it is shown at that size and does not fit
+fn image_problem(window: &slint::Window, geometry: &LogicalRect) -> Option<Problem> {
+    visit_items_at(window, geometry, |item, item_geometry| {
+        let (fit, source) = if let Some(image) = item.downcast::<ImageItem>() {
+            let image = image.as_pin_ref();
+            (image.image_fit(), image.source().size())
+        } else if let Some(image) = item.downcast::<ClippedImage>() {
+            let image = image.as_pin_ref();
+            let clip = IntSize::new(
+                image.source_clip_width().max(0) as u32,
+                image.source_clip_height().max(0) as u32,
+            );
+            (image.image_fit(), if clip.is_empty() { image.source().size() } else { clip })
+        } else {
+            return None;
+        };
+        Some(match fit {
+            ImageFit::Fill => distortion(item_geometry.size, source),
+            // The source is shown at its size in logical pixels
+            ImageFit::Preserve => {
+                let source = LogicalSize::new(source.width as f32, source.height as f32);
+                (source.width > item_geometry.width() + 0.5
+                    || source.height > item_geometry.height() + 0.5)
+                    .then_some(Problem::ImageCutOff { source })
+            }
+            _ => None,
+        })
+    })
+    .flatten()
+}
+
+/// Whether the text shown at `geometry` in the preview needs more space than it has
+fn text_problem(window: &slint::Window, geometry: &LogicalRect) -> Option<Problem> {
+    let window_adapter = i_slint_core::window::WindowInner::from_pub(window).window_adapter();
+    visit_items_at(window, geometry, |item, item_geometry| {
+        let (overflow, wrap) = if let Some(text) = item.downcast::<ComplexText>() {
+            let text = text.as_pin_ref();
+            (text.overflow(), text.wrap())
+        } else if item.downcast::<SimpleText>().is_some() {
+            (TextOverflow::Clip, TextWrap::NoWrap)
+        } else {
+            return None;
+        };
+        let preferred = |orientation| {
+            item.borrow().as_ref().layout_info(orientation, &window_adapter).preferred
+        };
+        // Wrapped text only needs the width of its longest word
+        let too_narrow = wrap == TextWrap::NoWrap
+            && preferred(Orientation::Horizontal) > item_geometry.width() + 0.5;
+        let too_low = preferred(Orientation::Vertical) > item_geometry.height() + 0.5;
+        Some(
+            (too_narrow || too_low)
+                .then_some(Problem::TextCutOff { elided: overflow == TextOverflow::Elide }),
+        )
+    })
+    .flatten()
+}
+
+/// Whether the element at `geometry` in the preview cuts off its children. Flickables are
+/// left out, showing only a part of their content is what they are for.
+fn clips_children(window: &slint::Window, geometry: &LogicalRect) -> bool {
+    let same = |a: f32, b: f32| (a - b).abs() < 0.5;
+    visit_items_at(window, geometry, |item, item_geometry| {
+        (same(item_geometry.width(), geometry.width())
+            && same(item_geometry.height(), geometry.height())
+            && item.borrow().as_ref().clips_children()
+            && item.downcast::<Flickable>().is_none())
+        .then_some(())
+    })
+    .is_some()
+}
+
+fn element_label(element: &common::ElementRcNode) -> String {
+    let type_name = element.component_type().trim().to_string();
+    let id = element.with_decorated_node(|n| {
+        (n.kind() == SyntaxKind::SubElement).then(|| n.child_text(SyntaxKind::Identifier)).flatten()
+    });
+    match id {
+        Some(id) => format!("{id} := {type_name}"),
+        None => type_name,
+    }
+}
+
+fn collect_children(
+    component_instance: &ComponentInstance,
+    bounds: &LogicalRect,
+    parent: &common::ElementRcNode,
+    // The geometry and label of the closest parent that clips its children
+    clip: Option<&(LogicalRect, String)>,
+    result: &mut Vec<Anomaly>,
+) {
+    let in_layout = parent.layout_kind() != ui::LayoutKind::None;
+    let children = parent
+        .children()
+        .into_iter()
+        .filter(|c| !c.with_element_node(common::is_element_node_ignored))
+        .map(|c| {
+            let geometries = c.geometries(component_instance);
+            (c, geometries)
+        })
+        .collect::<Vec<_>>();
+
+    for (index, (child, geometries)) in children.iter().enumerate() {
+        let type_name = child.component_type().trim().to_string();
+        let window = component_instance.window();
+        for geometry in geometries {
+            let mut problems = vec![];
+            if geometry.is_empty() {
+                problems.push(Problem::ZeroSize);
+            } else if is_outside(geometry, bounds) {
+                problems.push(Problem::OffScreen);
+            }
+            if in_layout {
+                for (other, other_geometries) in &children[..index] {
+                    if other_geometries.iter().any(|g| overlaps(geometry, g)) {
+                        problems.push(Problem::Overlap(element_label(other)));
+                    }
+                }
+            }
+            if let Some((clip_geometry, clip_label)) = clip {
+                if !geometry.is_empty() && exceeds(geometry, clip_geometry) {
+                    problems.push(Problem::Clipped(clip_label.clone()));
+                }
+            }
+            match type_name.as_str() {
+                "Image" => problems.extend(image_problem(window, geometry)),
+                "Text" => problems.extend(text_problem(window, geometry)),
+                _ => {}
+            }
+            if problems.is_empty() {
+                continue;
+            }
+            let (path, line, column) = child.with_decorated_node(|n| {
+                let (line, column) = n.source_file.line_column(usize::from(n.text_range().start()));
+                (n.source_file.path().to_path_buf(), line, column)
+            });
+            result.push(Anomaly {
+                geometry: *geometry,
+                element: element_label(child),
+                problems,
+                path,
+                line,
+                column,
+            });
+        }
+        // The children of repeated elements can not be told apart
+        let child_clip = match geometries.as_slice() {
+            [geometry] if clips_children(window, geometry) => {
+                Some((*geometry, element_label(child)))
+            }
+            _ => None,
+        };
+        collect_children(component_instance, bounds, child, child_clip.as_ref().or(clip), result);
+    }
+}
+
+/// The elements of the previewed component with problems, parents before their children
+pub fn collect(component_instance: &ComponentInstance) -> Vec<Anomaly> {
+    let root_element = super::element_selection::root_element(component_instance);
+    let Some(root) = common::ElementRcNode::new(root_element, 0) else {
+        return vec![];
+    };
+    let Some(bounds) = root.geometries(component_instance).first().copied() else {
+        return vec![];
+    };
+    let mut result = vec![];
+    collect_children(component_instance, &bounds, &root, None, &mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distortion() {
+        let source = IntSize::new(200, 100);
+        assert_eq!(distortion(LogicalSize::new(100.0, 50.0), source), None);
+        assert_eq!(distortion(LogicalSize::new(101.0, 50.0), source), None);
+        assert_eq!(
+            distortion(LogicalSize::new(100.0, 100.0), source),
+            Some(Problem::Distorted { shown: 1.0, source: 2.0 })
+        );
+        assert_eq!(distortion(LogicalSize::new(0.0, 100.0), source), None);
+        assert_eq!(distortion(LogicalSize::new(100.0, 100.0), IntSize::new(0, 0)), None);
+    }
+
+    #[test]
+    fn test_exceeds() {
+        let rect = |x, y, w, h| LogicalRect::new((x, y).into(), LogicalSize::new(w, h));
+        let bounds = rect(10.0, 10.0, 100.0, 100.0);
+        assert!(!exceeds(&rect(10.0, 10.0, 100.0, 100.0), &bounds));
+        assert!(!exceeds(&rect(20.0, 20.0, 90.2, 50.0), &bounds));
+        assert!(exceeds(&rect(20.0, 20.0, 100.0, 50.0), &bounds));
+        assert!(exceeds(&rect(5.0, 20.0, 10.0, 10.0), &bounds));
+        assert!(exceeds(&rect(200.0, 200.0, 10.0, 10.0), &bounds));
+
+        assert!(Problem::Clipped("Rectangle".into()).is_overflow());
+        assert!(Problem::TextCutOff { elided: true }.is_overflow());
+        assert!(!Problem::ZeroSize.is_overflow());
+    }
+
+    #[test]
+    fn test_collect() {
+        let source = r#"export component Main {
+    width: 200px;
+    height: 200px;
+
+    empty := Rectangle { width: 0px; }
+    Rectangle { x: 300px; width: 10px; height: 10px; }
+    HorizontalLayout {
+        y: 100px;
+        height: 50px;
+        spacing: -20px;
+        first := Rectangle { min-width: 50px; }
+        second := Rectangle { min-width: 50px; }
+    }
+}
+"#;
+        let component_instance = crate::preview::test::interpret_test("fluent", source);
+        let anomalies = collect(&component_instance)
+            .into_iter()
+            .map(|a| (a.element, a.problems, a.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            anomalies,
+            vec![
+                ("empty := Rectangle".to_string(), vec![Problem::ZeroSize], 5),
+                ("Rectangle".to_string(), vec![Problem::OffScreen], 6),
+                (
+                    "second := Rectangle".to_string(),
+                    vec![Problem::Overlap("first := Rectangle".into())],
+                    12
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The empty areas of a layout: The padding around its children and the spacing
+//! between them
+
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gap {
+    pub geometry: LogicalRect,
+    /// Padding between the layout and its children, spacing between children otherwise
+    pub is_padding: bool,
+}
+
+/// Merge the overlapping `(start, end)` ranges
+fn merge(mut ranges: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut result: Vec<(f32, f32)> = vec![];
+    for (start, end) in ranges {
+        match result.last_mut() {
+            Some(last) if start <= last.1 => last.1 = f32::max(last.1, end),
+            _ => result.push((start, end)),
+        }
+    }
+    result
+}
+
+/// The ranges between the merged `ranges`
+fn gaps_between(ranges: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    merge(ranges).windows(2).map(|w| (w[0].1, w[1].0)).collect()
+}
+
+/// The gaps of a layout at `layout` with its `children` placed inside of it
+///
+/// The spacing between rows is found between the children projected on the vertical axis,
+/// the spacing between columns between the children of each row projected on the horizontal
+/// axis. This works the same for box and grid layouts.
+pub fn gaps(layout: LogicalRect, children: &[LogicalRect]) -> Vec<Gap> {
+    let Some(content) = children.iter().copied().reduce(|a, b| a.union(&b)) else {
+        return vec![];
+    };
+    let content = content.intersection(&layout).unwrap_or(content);
+
+    let rect = |x1: f32, y1: f32, x2: f32, y2: f32| {
+        LogicalRect::new(LogicalPoint::new(x1, y1), LogicalSize::new(x2 - x1, y2 - y1))
+    };
+    let padding = [
+        rect(layout.min_x(), layout.min_y(), layout.max_x(), content.min_y()),
+        rect(layout.min_x(), content.max_y(), layout.max_x(), layout.max_y()),
+        rect(layout.min_x(), content.min_y(), content.min_x(), content.max_y()),
+        rect(content.max_x(), content.min_y(), layout.max_x(), content.max_y()),
+    ];
+
+    let row_ranges = merge(children.iter().map(|c| (c.min_y(), c.max_y())).collect());
+    let rows = row_ranges
+        .windows(2)
+        .map(|w| rect(content.min_x(), w[0].1, content.max_x(), w[1].0))
+        .collect::<Vec<_>>();
+    let columns = row_ranges.iter().flat_map(|&(y1, y2)| {
+        let in_row = children.iter().filter(|c| c.min_y() >= y1 && c.max_y() <= y2);
+        gaps_between(in_row.map(|c| (c.min_x(), c.max_x())).collect())
+            .into_iter()
+            .map(move |(x1, x2)| rect(x1, y1, x2, y2))
+    });
+
+    padding
+        .into_iter()
+        .map(|geometry| Gap { geometry, is_padding: true })
+        .chain(columns.chain(rows).map(|geometry| Gap { geometry, is_padding: false }))
+        .filter(|g| g.geometry.width() > 0.0 && g.geometry.height() > 0.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> LogicalRect {
+        LogicalRect::new(LogicalPoint::new(x, y), LogicalSize::new(width, height))
+    }
+
+    #[test]
+    fn test_box_layout_gaps() {
+        // A horizontal layout with 10px padding and 5px spacing
+        let gaps = gaps(
+            rect(0.0, 0.0, 100.0, 50.0),
+            &[
+                rect(10.0, 10.0, 20.0, 30.0),
+                rect(35.0, 10.0, 20.0, 30.0),
+                rect(60.0, 10.0, 30.0, 30.0),
+            ],
+        );
+        assert_eq!(
+            gaps,
+            vec![
+                Gap { geometry: rect(0.0, 0.0, 100.0, 10.0), is_padding: true },
+                Gap { geometry: rect(0.0, 40.0, 100.0, 10.0), is_padding: true },
+                Gap { geometry: rect(0.0, 10.0, 10.0, 30.0), is_padding: true },
+                Gap { geometry: rect(90.0, 10.0, 10.0, 30.0), is_padding: true },
+                Gap { geometry: rect(30.0, 10.0, 5.0, 30.0), is_padding: false },
+                Gap { geometry: rect(55.0, 10.0, 5.0, 30.0), is_padding: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grid_layout_gaps() {
+        // Two columns and two rows without padding, the second row spans both columns
+        let gaps = gaps(
+            rect(0.0, 0.0, 50.0, 50.0),
+            &[rect(0.0, 0.0, 20.0, 20.0), rect(30.0, 0.0, 20.0, 20.0), rect(0.0, 30.0, 50.0, 20.0)],
+        );
+        assert_eq!(
+            gaps,
+            vec![
+                Gap { geometry: rect(20.0, 0.0, 10.0, 20.0), is_padding: false },
+                Gap { geometry: rect(0.0, 20.0, 50.0, 10.0), is_padding: false },
+            ]
+        );
+        assert!(super::gaps(rect(0.0, 0.0, 50.0, 50.0), &[]).is_empty());
+    }
+}
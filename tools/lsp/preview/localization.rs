@@ -0,0 +1,428 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! A report on how much of the user visible text of the previewed component is localized
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use i_slint_compiler::langtype::ElementType;
+use i_slint_compiler::parser::{syntax_nodes, NodeOrToken, SyntaxKind, SyntaxNode, TextRange};
+
+use crate::common;
+use crate::util;
+
+/// The translations of one language, as found in a gettext `.po` file
+#[derive(Clone, Debug, Default)]
+pub struct Catalog {
+    pub language: String,
+    /// Context and message id of all messages with a translation
+    translated: HashSet<(String, String)>,
+}
+
+/// Undo the escaping of a string in a `.po` file, `line` includes the quotes
+fn po_string(line: &str) -> String {
+    let line = line.trim();
+    let line = line.strip_prefix('"').and_then(|l| l.strip_suffix('"')).unwrap_or(line);
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
+
+impl Catalog {
+    pub fn parse(language: impl Into<String>, source: &str) -> Self {
+        #[derive(PartialEq)]
+        enum Field {
+            None,
+            Context,
+            Id,
+            Translation,
+        }
+
+        let mut translated = HashSet::new();
+        let (mut context, mut id, mut translation) = (String::new(), String::new(), String::new());
+        let (mut is_fuzzy, mut field) = (false, Field::None);
+
+        let mut finish_message =
+            |context: &mut String, id: &mut String, translation: &mut String, is_fuzzy: bool| {
+                if !id.is_empty() && !translation.is_empty() && !is_fuzzy {
+                    translated.insert((std::mem::take(context), std::mem::take(id)));
+                }
+                context.clear();
+                id.clear();
+                translation.clear();
+            };
+
+        for line in source.lines().map(str::trim) {
+            if line.starts_with('"') {
+                match field {
+                    Field::Context => context.push_str(&po_string(line)),
+                    Field::Id => id.push_str(&po_string(line)),
+                    Field::Translation => translation.push_str(&po_string(line)),
+                    Field::None => {}
+                }
+                continue;
+            }
+            if field == Field::Translation && !line.starts_with("msgstr") {
+                finish_message(&mut context, &mut id, &mut translation, is_fuzzy);
+                is_fuzzy = false;
+                field = Field::None;
+            }
+            if let Some(flags) = line.strip_prefix("#,") {
+                is_fuzzy = flags.split(',').any(|f| f.trim() == "fuzzy");
+            } else if let Some(rest) = line.strip_prefix("msgctxt ") {
+                context = po_string(rest);
+                field = Field::Context;
+            } else if let Some(rest) = line.strip_prefix("msgid ") {
+                id = po_string(rest);
+                field = Field::Id;
+            } else if line.starts_with("msgid_plural ") {
+                field = Field::None;
+            } else if let Some(rest) = line.strip_prefix("msgstr") {
+                // Plural forms: Only the first form is checked
+                if !rest.starts_with('[') || rest.starts_with("[0]") {
+                    translation = po_string(rest.split_once(' ').map(|(_, s)| s).unwrap_or(""));
+                }
+                field = Field::Translation;
+            }
+        }
+        finish_message(&mut context, &mut id, &mut translation, is_fuzzy);
+
+        Self { language: language.into(), translated }
+    }
+
+    pub fn contains(&self, context: &str, id: &str) -> bool {
+        self.translated.contains(&(context.to_string(), id.to_string()))
+    }
+}
+
+/// Load the catalogs from the closest `lang/<language>/LC_MESSAGES/*.po` next to `file`
+/// or in any of its parent directories
+pub fn load_catalogs(file: &Path) -> Vec<Catalog> {
+    let Some(lang_dir) = file.ancestors().skip(1).map(|d| d.join("lang")).find(|d| d.is_dir())
+    else {
+        return vec![];
+    };
+    let Ok(languages) = std::fs::read_dir(lang_dir) else {
+        return vec![];
+    };
+
+    let mut result = languages
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let language = entry.file_name().to_string_lossy().to_string();
+            let files = std::fs::read_dir(entry.path().join("LC_MESSAGES")).ok()?;
+            let source = files
+                .filter_map(|f| f.ok().map(|f| f.path()))
+                .filter(|p| p.extension().is_some_and(|e| e == "po"))
+                .filter_map(|p| std::fs::read_to_string(p).ok())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            Some(Catalog::parse(language, &source))
+        })
+        .collect::<Vec<_>>();
+    result.sort_by(|a, b| a.language.cmp(&b.language));
+    result
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Problem {
+    /// The text is not wrapped in `@tr`
+    NotTranslatable,
+    /// The text is wrapped in `@tr`, but these languages have no translation for it
+    MissingTranslations(Vec<String>),
+}
+
+/// A user visible text that is not fully localized
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub text: String,
+    pub problem: Problem,
+    pub path: PathBuf,
+    pub range: lsp_types::Range,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    /// The number of user visible texts
+    pub total: usize,
+    pub findings: Vec<Finding>,
+    /// The languages of the catalogs the texts were checked against
+    pub languages: Vec<String>,
+}
+
+impl Report {
+    /// The percentage of user visible texts that are fully localized
+    pub fn coverage(&self) -> f32 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        ((self.total - self.findings.len()) as f32 * 100.0) / self.total as f32
+    }
+}
+
+fn finding(node: &SyntaxNode, range: TextRange, text: String, problem: Problem) -> Finding {
+    Finding {
+        text,
+        problem,
+        path: node.source_file.path().to_path_buf(),
+        range: util::text_range_to_lsp_range(&node.source_file, range),
+    }
+}
+
+/// String literals in `node` that are not part of a `@tr`
+fn untranslated_literals(node: &SyntaxNode, result: &mut Vec<(TextRange, String)>) {
+    for child in node.children_with_tokens() {
+        match child {
+            NodeOrToken::Node(n) if n.kind() == SyntaxKind::AtTr => {}
+            NodeOrToken::Node(n) => untranslated_literals(&n, result),
+            NodeOrToken::Token(t) if t.kind() == SyntaxKind::StringLiteral => {
+                let text = i_slint_compiler::literals::unescape_string(t.text())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| t.text().to_string());
+                // Ignore things like "", "%" or "42"
+                if text.chars().any(char::is_alphabetic) {
+                    result.push((t.text_range(), text));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_binding(
+    binding: &syntax_nodes::Binding,
+    component_name: &str,
+    catalogs: &[Catalog],
+    report: &mut Report,
+) {
+    let expression = binding.BindingExpression();
+
+    for at_tr in expression.descendants().filter(|n| n.kind() == SyntaxKind::AtTr) {
+        let Some(id) = at_tr.child_token(SyntaxKind::StringLiteral) else {
+            continue;
+        };
+        let text = i_slint_compiler::literals::unescape_string(id.text())
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        // The context defaults to the name of the component
+        let context = at_tr
+            .child_node(SyntaxKind::TrContext)
+            .and_then(|c| c.child_token(SyntaxKind::StringLiteral))
+            .and_then(|c| i_slint_compiler::literals::unescape_string(c.text()))
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| component_name.to_string());
+
+        report.total += 1;
+        let missing = catalogs
+            .iter()
+            .filter(|c| !c.contains(&context, &text))
+            .map(|c| c.language.clone())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            report.findings.push(finding(
+                &at_tr,
+                at_tr.text_range(),
+                text,
+                Problem::MissingTranslations(missing),
+            ));
+        }
+    }
+
+    let mut literals = vec![];
+    untranslated_literals(&expression, &mut literals);
+    for (range, text) in literals {
+        report.total += 1;
+        report.findings.push(finding(&expression, range, text, Problem::NotTranslatable));
+    }
+}
+
+fn check_component(
+    document_cache: &common::DocumentCache,
+    component: &syntax_nodes::Component,
+    catalogs: &[Catalog],
+    visited: &mut HashSet<(PathBuf, TextRange)>,
+    report: &mut Report,
+) {
+    if !visited.insert((component.source_file.path().to_path_buf(), component.text_range())) {
+        return;
+    }
+    let component_name = i_slint_compiler::parser::identifier_text(&component.DeclaredIdentifier())
+        .map(|n| n.to_string())
+        .unwrap_or_default();
+
+    for binding in component.descendants().filter_map(syntax_nodes::Binding::new) {
+        let name = i_slint_compiler::parser::identifier_text(&binding).unwrap_or_default();
+        if common::USER_VISIBLE_TEXT_PROPERTIES.contains(&name.as_str()) {
+            check_binding(&binding, &component_name, catalogs, report);
+        }
+    }
+
+    let Some(document) = document_cache.get_document_by_path(component.source_file.path()) else {
+        return;
+    };
+    // Continue with the components used in this one
+    for element in component.descendants().filter_map(syntax_nodes::Element::new) {
+        let Some(type_name) = element.QualifiedName().map(|qn| qn.text().to_string()) else {
+            continue;
+        };
+        let Ok(ElementType::Component(c)) =
+            document.local_registry.lookup_element(type_name.trim())
+        else {
+            continue;
+        };
+        let Some(node) = c.node.clone().and_then(syntax_nodes::Component::new) else {
+            continue;
+        };
+        if node.source_file.path().starts_with("builtin:/") {
+            continue;
+        }
+        check_component(document_cache, &node, catalogs, visited, report);
+    }
+}
+
+/// Check the previewed component against the catalogs found next to it
+pub fn report(
+    document_cache: &common::DocumentCache,
+    component_instance: &slint_interpreter::ComponentInstance,
+) -> Report {
+    let Some(component) =
+        common::ElementRcNode::new(super::element_selection::root_element(component_instance), 0)
+            .and_then(|root| {
+                root.with_element_node(|n| n.parent().and_then(syntax_nodes::Component::new))
+            })
+    else {
+        return Report::default();
+    };
+    let catalogs = load_catalogs(component.source_file.path());
+    analyze(document_cache, &component, &catalogs)
+}
+
+/// Check the user visible texts of `component` and all components it uses against
+/// `catalogs`
+pub fn analyze(
+    document_cache: &common::DocumentCache,
+    component: &syntax_nodes::Component,
+    catalogs: &[Catalog],
+) -> Report {
+    let mut report = Report {
+        languages: catalogs.iter().map(|c| c.language.clone()).collect(),
+        ..Default::default()
+    };
+    check_component(document_cache, component, catalogs, &mut HashSet::new(), &mut report);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_catalog() {
+        let catalog = Catalog::parse(
+            "de",
+            r#"
+#, fuzzy
+msgid ""
+msgstr ""
+"Language: de\n"
+
+msgctxt "App"
+msgid "Hello"
+msgstr "Hallo"
+
+#, fuzzy
+msgctxt "App"
+msgid "World"
+msgstr "Welt"
+
+msgctxt "App"
+msgid "Empty"
+msgstr ""
+
+msgctxt "App"
+msgid ""
+"Multi "
+"line"
+msgstr "Mehrere Zeilen"
+
+msgctxt "App"
+msgid "One file"
+msgid_plural "{n} files"
+msgstr[0] "Eine Datei"
+msgstr[1] "{n} Dateien"
+"#,
+        );
+        assert!(catalog.contains("App", "Hello"));
+        assert!(!catalog.contains("Other", "Hello"));
+        assert!(!catalog.contains("App", "World"));
+        assert!(!catalog.contains("App", "Empty"));
+        assert!(catalog.contains("App", "Multi line"));
+        assert!(catalog.contains("App", "One file"));
+    }
+
+    #[test]
+    fn test_analyze() {
+        let source = r#"
+component Label {
+    in property <string> caption;
+    Text { text: "Label"; }
+}
+
+export component Main {
+    VerticalLayout {
+        Text { text: @tr("Hello"); }
+        Text { text: @tr("Other" => "World"); }
+        Text { text: "Untranslated"; }
+        Text { text: "42"; }
+        Text { text: true ? @tr("Yes") : "No"; }
+        Label { caption: "not user visible"; }
+    }
+}
+"#;
+        let (document_cache, url, _) =
+            crate::language::test::loaded_document_cache(source.to_string());
+        let document = document_cache.get_document(&url).unwrap();
+        let component = document
+            .node
+            .as_ref()
+            .unwrap()
+            .ExportsList()
+            .filter_map(|el| el.Component())
+            .last()
+            .unwrap();
+
+        let catalogs = [Catalog::parse(
+            "de",
+            "msgctxt \"Main\"\nmsgid \"Hello\"\nmsgstr \"Hallo\"\n\nmsgctxt \"Main\"\nmsgid \"Yes\"\nmsgstr \"Ja\"\n",
+        )];
+        let report = analyze(&document_cache, &component, &catalogs);
+
+        let findings =
+            report.findings.iter().map(|f| (f.text.as_str(), &f.problem)).collect::<Vec<_>>();
+        assert_eq!(
+            findings,
+            vec![
+                ("World", &Problem::MissingTranslations(vec!["de".to_string()])),
+                ("Untranslated", &Problem::NotTranslatable),
+                ("No", &Problem::NotTranslatable),
+                ("Label", &Problem::NotTranslatable),
+            ]
+        );
+        assert_eq!(report.total, 6);
+        assert_eq!(report.coverage(), 2.0 * 100.0 / 6.0);
+        assert_eq!(report.languages, vec!["de".to_string()]);
+    }
+}
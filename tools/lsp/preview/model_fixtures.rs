@@ -0,0 +1,379 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Rows for the array properties of the previewed component: Pasted as CSV from a spreadsheet,
+//! and kept as fixtures in the project settings, so that they survive reloads and sessions.
+
+use std::collections::BTreeMap;
+
+use i_slint_compiler::langtype::Type;
+use slint_interpreter::ComponentInstance;
+
+use super::preview_data::{self, PropertyContainer};
+
+/// The JSON values of properties, by component name and then by property name. Properties of
+/// globals are stored as `Global.property`.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Fixtures(BTreeMap<String, BTreeMap<String, serde_json::Value>>);
+
+fn property_key(container: &PropertyContainer, property_name: &str) -> String {
+    match container {
+        PropertyContainer::Main => property_name.to_string(),
+        PropertyContainer::Global(g) => format!("{g}.{property_name}"),
+    }
+}
+
+impl Fixtures {
+    /// Set the fixture of a property of `component`, or remove it if `value` is `None`
+    pub fn set(
+        &mut self,
+        component: &str,
+        container: &PropertyContainer,
+        property_name: &str,
+        value: Option<serde_json::Value>,
+    ) {
+        let key = property_key(container, property_name);
+        match value {
+            Some(value) => {
+                self.0.entry(component.to_string()).or_default().insert(key, value);
+            }
+            None => {
+                if let Some(properties) = self.0.get_mut(component) {
+                    properties.remove(&key);
+                    if properties.is_empty() {
+                        self.0.remove(component);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The fixtures of the properties of `component`
+    pub fn of(
+        &self,
+        component: &str,
+    ) -> impl Iterator<Item = (PropertyContainer, &str, &serde_json::Value)> {
+        self.0.get(component).into_iter().flatten().map(|(key, value)| match key.split_once('.') {
+            Some((g, name)) => (PropertyContainer::Global(g.to_string()), name, value),
+            None => (PropertyContainer::Main, key.as_str(), value),
+        })
+    }
+}
+
+/// Set the properties of `component_instance` that have a fixture. Returns the errors of the
+/// fixtures that no longer fit the component.
+pub fn apply(fixtures: &Fixtures, component_instance: &ComponentInstance) -> Vec<String> {
+    let component = component_instance.definition().name().to_string();
+    fixtures
+        .of(&component)
+        .filter_map(|(container, name, value)| {
+            preview_data::set_json_preview_data(
+                component_instance,
+                container,
+                Some(name.to_string()),
+                value.clone(),
+            )
+            .err()
+            .map(|e| format!("Fixture of {name}: {}", e.join(", ")))
+        })
+        .collect()
+}
+
+/// Split `text` into records and cells. Cells are separated by tabs when the first line
+/// contains one, as when copying from a spreadsheet, and by commas otherwise. Cells can be
+/// quoted with `"`, with `""` for a quote inside.
+fn records(text: &str) -> Vec<Vec<String>> {
+    let separator = if text.lines().next().unwrap_or_default().contains('\t') { '\t' } else { ',' };
+
+    let mut result = vec![];
+    let mut record = vec![];
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if cell.trim().is_empty() => {
+                cell.clear();
+                in_quotes = true;
+            }
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut cell));
+                if record.iter().any(|c| !c.trim().is_empty()) {
+                    result.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            c if c == separator && !in_quotes => record.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    record.push(cell);
+    if record.iter().any(|c| !c.trim().is_empty()) {
+        result.push(record);
+    }
+    result
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('_', "-")
+}
+
+/// The JSON value of the cell `text` in a column of type `ty`
+fn cell_value(ty: &Type, column: &str, text: &str) -> Result<serde_json::Value, String> {
+    let text = text.trim();
+    match ty {
+        Type::Int32
+        | Type::Float32
+        | Type::Percent
+        | Type::LogicalLength
+        | Type::PhysicalLength
+        | Type::Rem
+        | Type::Duration
+        | Type::Angle => {
+            // Units as the table shows them, like `10px`
+            const UNITS: &[&str] = &[
+                "px", "phx", "rem", "cm", "mm", "in", "pt", "ms", "s", "deg", "grad", "turn",
+                "rad", "%",
+            ];
+            let number =
+                UNITS.iter().find_map(|unit| text.strip_suffix(unit)).unwrap_or(text).trim();
+            let number = if text.is_empty() { Some(0.0) } else { number.parse::<f64>().ok() };
+            number
+                .and_then(serde_json::Number::from_f64)
+                .map(serde_json::Value::Number)
+                .ok_or_else(|| format!("{column}: '{text}' is not a number"))
+        }
+        Type::Bool => match text.to_lowercase().as_str() {
+            "true" | "yes" | "1" | "x" => Ok(true.into()),
+            "false" | "no" | "0" | "" => Ok(false.into()),
+            _ => Err(format!("{column}: '{text}' is neither true nor false")),
+        },
+        Type::String | Type::Color | Type::Brush | Type::Image | Type::Enumeration(_) => {
+            Ok(text.into())
+        }
+        _ => Err(format!("{column} can not be filled from a table")),
+    }
+}
+
+/// The columns of a table of `ty` values, like the preview data table shows them: The fields
+/// of nested structs flattened, each with its path of field names
+fn columns(ty: &Type, path: &mut Vec<String>, result: &mut Vec<(Vec<String>, Type)>) {
+    match ty {
+        Type::Struct(s) => {
+            for (name, ty) in s.fields.iter() {
+                path.push(name.to_string());
+                columns(ty, path, result);
+                path.pop();
+            }
+        }
+        ty => result.push((path.clone(), ty.clone())),
+    }
+}
+
+/// Set the field at `path` in `object`, creating the structs on the way
+fn insert(object: &mut serde_json::Value, path: &[String], value: serde_json::Value) {
+    let Some((first, rest)) = path.split_first() else {
+        *object = value;
+        return;
+    };
+    if !object.is_object() {
+        *object = serde_json::Value::Object(Default::default());
+    }
+    if let Some(fields) = object.as_object_mut() {
+        insert(fields.entry(first.clone()).or_insert(serde_json::Value::Null), rest, value);
+    }
+}
+
+/// The rows of an array of `row_type` in the CSV `text`, as a JSON array. The first record
+/// names the columns if all its cells are column names, like `address.city` for fields of
+/// nested structs. Otherwise the columns are in the order of the preview data table.
+pub fn rows_from_csv(row_type: &Type, text: &str) -> Result<serde_json::Value, String> {
+    let mut all_columns = vec![];
+    columns(row_type, &mut vec![], &mut all_columns);
+    let column_name =
+        |path: &[String]| if path.is_empty() { "value".to_string() } else { path.join(".") };
+
+    let mut records = records(text);
+    if records.is_empty() {
+        return Err("There are no rows to paste".into());
+    }
+
+    let find_column = |cell: &String| {
+        let cell = normalize(cell);
+        all_columns.iter().find(|(path, _)| !path.is_empty() && path.join(".") == cell)
+    };
+    let columns = match records[0].iter().map(find_column).collect::<Option<Vec<_>>>() {
+        Some(columns) => {
+            records.remove(0);
+            columns
+        }
+        None => all_columns.iter().collect(),
+    };
+
+    records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            if record.len() > columns.len() {
+                return Err(format!(
+                    "Row {} has {} cells, but there are only {} columns",
+                    index + 1,
+                    record.len(),
+                    columns.len()
+                ));
+            }
+            let mut row = serde_json::Value::Null;
+            for ((path, ty), cell) in columns.iter().zip(record) {
+                insert(&mut row, path, cell_value(ty, &column_name(path), cell)?);
+            }
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(serde_json::Value::Array)
+}
+
+/// Set the cell in the `column` of the preview data table at `row` of `value`, a JSON value of
+/// type `ty`, to `text`
+pub fn set_cell(
+    ty: &Type,
+    value: &mut serde_json::Value,
+    row: usize,
+    column: &str,
+    text: &str,
+) -> Result<(), String> {
+    let (row_type, row_value) = match ty {
+        Type::Array(row_type) => {
+            (&**row_type, value.get_mut(row).ok_or_else(|| format!("Row {row} not found"))?)
+        }
+        ty => (ty, value),
+    };
+    let mut all_columns = vec![];
+    columns(row_type, &mut vec![], &mut all_columns);
+    let (path, ty) = all_columns
+        .iter()
+        .find(|(path, _)| path.join(".") == column)
+        .ok_or_else(|| format!("Column {column} not found"))?;
+    insert(row_value, path, cell_value(ty, column, text)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::rc::Rc;
+
+    use i_slint_compiler::langtype::Struct;
+    use serde_json::json;
+
+    fn struct_type(fields: &[(&str, Type)]) -> Type {
+        Type::Struct(Rc::new(Struct {
+            fields: fields.iter().map(|(name, ty)| ((*name).into(), ty.clone())).collect(),
+            name: None,
+            node: None,
+            rust_attributes: None,
+        }))
+    }
+
+    #[test]
+    fn test_records() {
+        assert_eq!(
+            records("a,\"b, c\",\"say \"\"hi\"\"\"\r\n\n1,2\n"),
+            vec![vec!["a", "b, c", "say \"hi\""], vec!["1", "2"]]
+        );
+        assert_eq!(records("a\tb,c\nd\te"), vec![vec!["a", "b,c"], vec!["d", "e"]]);
+        assert_eq!(records("\"two\nlines\",x"), vec![vec!["two\nlines", "x"]]);
+        assert!(records("\n \n").is_empty());
+    }
+
+    #[test]
+    fn test_rows_from_csv() {
+        let ty = struct_type(&[
+            ("city", Type::String),
+            ("zip-code", Type::Int32),
+            ("favorite", Type::Bool),
+        ]);
+        // Columns in field order, as in the table, without a header
+        assert_eq!(
+            rows_from_csv(&ty, "Berlin,yes,10115\nOslo,,150").unwrap(),
+            json!([
+                { "city": "Berlin", "zip-code": 10115.0, "favorite": true },
+                { "city": "Oslo", "zip-code": 150.0, "favorite": false },
+            ])
+        );
+        // A header picks and orders the columns
+        assert_eq!(
+            rows_from_csv(&ty, "Zip_Code\tCity\n8000\tAarhus").unwrap(),
+            json!([{ "zip-code": 8000.0, "city": "Aarhus" }])
+        );
+        assert!(rows_from_csv(&ty, "Berlin,yes,ten").unwrap_err().contains("zip-code"));
+        assert!(rows_from_csv(&ty, "a,true,1,extra").unwrap_err().contains("Row 1"));
+        assert!(rows_from_csv(&ty, "").is_err());
+
+        let person = struct_type(&[("name", Type::String), ("address", ty)]);
+        assert_eq!(
+            rows_from_csv(&person, "address.city,name\nRome,Ada").unwrap(),
+            json!([{ "address": { "city": "Rome" }, "name": "Ada" }])
+        );
+
+        assert_eq!(rows_from_csv(&Type::String, "one\ntwo").unwrap(), json!(["one", "two"]));
+    }
+
+    #[test]
+    fn test_set_cell() {
+        let ty = struct_type(&[("title", Type::String), ("width", Type::LogicalLength)]);
+        let array = Type::Array(Rc::new(ty.clone()));
+        let mut value = json!([{ "title": "a", "width": 1 }, { "title": "b", "width": 2 }]);
+        set_cell(&array, &mut value, 1, "width", "20px").unwrap();
+        set_cell(&array, &mut value, 0, "title", "first").unwrap();
+        assert_eq!(
+            value,
+            json!([{ "title": "first", "width": 1 }, { "title": "b", "width": 20.0 }])
+        );
+        assert!(set_cell(&array, &mut value, 2, "title", "x").is_err());
+        assert!(set_cell(&array, &mut value, 0, "height", "x").is_err());
+
+        let mut value = json!({ "title": "a", "width": 1 });
+        set_cell(&ty, &mut value, 0, "title", "b").unwrap();
+        assert_eq!(value["title"], "b");
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let mut fixtures = Fixtures::default();
+        fixtures.set("Main", &PropertyContainer::Main, "rows", Some(json!([1, 2])));
+        fixtures.set(
+            "Main",
+            &PropertyContainer::Global("Data".into()),
+            "names",
+            Some(json!(["a"])),
+        );
+        let of = |fixtures: &Fixtures| {
+            fixtures
+                .of("Main")
+                .map(|(c, name, value)| (c, name.to_string(), value.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            of(&fixtures),
+            vec![
+                (PropertyContainer::Global("Data".into()), "names".into(), json!(["a"])),
+                (PropertyContainer::Main, "rows".into(), json!([1, 2])),
+            ]
+        );
+
+        // Survives a round trip through the serialization
+        let data = serde_json::to_vec(&fixtures).unwrap();
+        assert_eq!(serde_json::from_slice::<Fixtures>(&data).unwrap(), fixtures);
+
+        fixtures.set("Main", &PropertyContainer::Main, "rows", None);
+        fixtures.set("Main", &PropertyContainer::Global("Data".into()), "names", None);
+        assert_eq!(fixtures, Fixtures::default());
+    }
+}
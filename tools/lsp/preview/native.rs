@@ -185,6 +185,20 @@ pub(super) fn open_ui_impl(preview_state: &mut PreviewState) -> Result<(), slint
     let api = ui.global::<crate::preview::ui::Api>();
     api.set_show_preview_ui(show_preview_ui);
     ui.window().set_fullscreen(fullscreen);
+    // Files dragged onto the window from the file manager
+    {
+        use i_slint_backend_winit::{winit::event::WindowEvent, WinitWindowAccessor};
+        ui.window().on_winit_window_event(|window, event| {
+            if let WindowEvent::DroppedFile(path) = event {
+                let path = path.clone();
+                let _ = i_slint_core::api::invoke_from_event_loop(move || super::drop_file(path));
+            }
+            if super::recorder::is_recording() {
+                record_winit_event(window, event);
+            }
+            i_slint_backend_winit::WinitWindowEventResult::Propagate
+        });
+    }
     ui.window().on_close_requested(|| {
         let mut cache = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
         cache.ui_is_visible = false;
@@ -193,6 +207,75 @@ pub(super) fn open_ui_impl(preview_state: &mut PreviewState) -> Result<(), slint
     Ok(())
 }
 
+/// Pass the input events of the preview window on to the interaction recorder
+fn record_winit_event(
+    window: &slint::Window,
+    event: &i_slint_backend_winit::winit::event::WindowEvent,
+) {
+    use i_slint_backend_winit::winit::event::{
+        ElementState, MouseButton, MouseScrollDelta, WindowEvent,
+    };
+    use i_slint_backend_winit::winit::keyboard::{Key, NamedKey};
+    use slint::platform::Key as SlintKey;
+
+    let scale_factor = window.scale_factor() as f64;
+    match event {
+        WindowEvent::CursorMoved { position, .. } => {
+            let position = position.to_logical::<f32>(scale_factor);
+            super::recorder::pointer_moved(
+                window,
+                i_slint_core::lengths::LogicalPoint::new(position.x, position.y),
+            );
+        }
+        WindowEvent::MouseInput { state, button, .. } => {
+            let button = match button {
+                MouseButton::Left => super::recorder::Button::Left,
+                MouseButton::Right => super::recorder::Button::Right,
+                MouseButton::Middle => super::recorder::Button::Middle,
+                _ => return,
+            };
+            super::recorder::pointer_button(window, button, *state == ElementState::Pressed);
+        }
+        WindowEvent::MouseWheel { delta, .. } => {
+            // Converted like the winit backend does
+            let (delta_x, delta_y) = match delta {
+                MouseScrollDelta::LineDelta(x, y) => (x * 60., y * 60.),
+                MouseScrollDelta::PixelDelta(d) => {
+                    let d = d.to_logical::<f32>(scale_factor);
+                    (d.x, d.y)
+                }
+            };
+            super::recorder::pointer_scrolled(window, delta_x, delta_y);
+        }
+        WindowEvent::KeyboardInput { event, is_synthetic: false, .. } => {
+            let key = |key: SlintKey| Some(slint::SharedString::from(key).to_string());
+            let text = match &event.logical_key {
+                Key::Character(text) => Some(text.to_string()),
+                Key::Named(NamedKey::Enter) => key(SlintKey::Return),
+                Key::Named(NamedKey::Tab) => key(SlintKey::Tab),
+                Key::Named(NamedKey::Backspace) => key(SlintKey::Backspace),
+                Key::Named(NamedKey::Delete) => key(SlintKey::Delete),
+                Key::Named(NamedKey::Escape) => key(SlintKey::Escape),
+                Key::Named(NamedKey::Space) => key(SlintKey::Space),
+                Key::Named(NamedKey::ArrowUp) => key(SlintKey::UpArrow),
+                Key::Named(NamedKey::ArrowDown) => key(SlintKey::DownArrow),
+                Key::Named(NamedKey::ArrowLeft) => key(SlintKey::LeftArrow),
+                Key::Named(NamedKey::ArrowRight) => key(SlintKey::RightArrow),
+                Key::Named(NamedKey::Home) => key(SlintKey::Home),
+                Key::Named(NamedKey::End) => key(SlintKey::End),
+                Key::Named(NamedKey::Shift) => key(SlintKey::Shift),
+                Key::Named(NamedKey::Control) => key(SlintKey::Control),
+                Key::Named(NamedKey::Alt) => key(SlintKey::Alt),
+                _ => event.text.as_ref().map(|t| t.to_string()),
+            };
+            if let Some(text) = text {
+                super::recorder::key(text, event.state == ElementState::Pressed);
+            }
+        }
+        _ => {}
+    }
+}
+
 pub fn close_ui() {
     {
         let mut cache = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
@@ -263,10 +346,15 @@ pub fn ask_editor_to_show_document(file: &str, selection: lsp_types::Range, take
 }
 
 pub fn send_message_to_lsp(message: PreviewToLspMessage) {
+    super::connection::send(message);
+}
+
+/// Returns false when there is no LSP to send `message` to
+pub fn deliver_message_to_lsp(message: &PreviewToLspMessage) -> bool {
     let Some(sender) = SERVER_NOTIFIER.lock().unwrap().clone() else {
-        return;
+        return false;
     };
-    sender.send_message_to_lsp(message);
+    sender.send_message_to_lsp(message.clone())
 }
 
 // This function overrides the default app menu and makes the "Quit" item merely hide the UI,
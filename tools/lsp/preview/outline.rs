@@ -0,0 +1,136 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The element tree of the previewed component, flattened into rows for the outline view
+
+use std::path::PathBuf;
+
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, TextSize};
+use slint_interpreter::ComponentInstance;
+
+use crate::common;
+use crate::preview::{element_locks, element_selection, ext::ElementRcNodeExt, ui};
+
+/// One element in the outline
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlineNode {
+    /// The type of the element, prefixed by its id if it has one
+    pub label: String,
+    /// How deep the element is nested into the root element of the component
+    pub depth: usize,
+    pub is_layout: bool,
+    pub path: PathBuf,
+    pub offset: TextSize,
+    /// Where the element is in the element tree, to lock it. `None` for the root element.
+    pub address: Option<element_locks::ElementAddress>,
+}
+
+impl OutlineNode {
+    pub fn as_element_selection(&self) -> element_selection::ElementSelection {
+        element_selection::ElementSelection {
+            path: self.path.clone(),
+            offset: self.offset,
+            instance_index: 0,
+        }
+    }
+}
+
+fn label(element_node: &common::ElementRcNode) -> String {
+    element_node.with_element_node(|node| {
+        // The root element is labelled with the name of the component
+        if let Some(component) = node.parent().and_then(syntax_nodes::Component::new) {
+            return component.DeclaredIdentifier().text().to_string().trim().to_string();
+        }
+        let type_name = node
+            .QualifiedName()
+            .map(|qn| qn.text().to_string().trim().to_string())
+            .unwrap_or_default();
+        let sub_element = node.parent().filter(|p| p.kind() == SyntaxKind::SubElement);
+        let id = sub_element.as_ref().and_then(|p| p.child_text(SyntaxKind::Identifier));
+        let prefix = match sub_element.and_then(|p| p.parent()).map(|p| p.kind()) {
+            Some(SyntaxKind::RepeatedElement) => "for ",
+            Some(SyntaxKind::ConditionalElement) => "if ",
+            _ => "",
+        };
+        match id {
+            Some(id) => format!("{prefix}{id} := {type_name}"),
+            None => format!("{prefix}{type_name}"),
+        }
+    })
+}
+
+fn collect(element_node: &common::ElementRcNode, depth: usize, result: &mut Vec<OutlineNode>) {
+    if element_node.with_element_node(common::is_element_node_ignored) {
+        return;
+    }
+    let (path, offset) = element_node.path_and_offset();
+    let address = element_node.with_element_node(|node| element_locks::address(&path, node));
+    result.push(OutlineNode {
+        label: label(element_node),
+        depth,
+        is_layout: element_node.layout_kind() != ui::LayoutKind::None,
+        path,
+        offset,
+        address,
+    });
+    for child in element_node.children() {
+        collect(&child, depth + 1, result);
+    }
+}
+
+/// All elements of the previewed component in source order, with their children
+/// following right after them
+pub fn outline(component_instance: &ComponentInstance) -> Vec<OutlineNode> {
+    let mut result = vec![];
+    if let Some(root) =
+        common::ElementRcNode::new(element_selection::root_element(component_instance), 0)
+    {
+        collect(&root, 0, &mut result);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+component Card {
+    Rectangle { }
+    @children
+}
+
+export component Main {
+    VerticalLayout {
+        title := Text { text: "Hello"; }
+        Card {
+            for i in 3: Rectangle { }
+            if true: Image { }
+        }
+    }
+    Rectangle { /* @lsp:ignore-node */ }
+}
+"#,
+        );
+
+        let outline = outline(&component_instance)
+            .into_iter()
+            .map(|n| (n.label, n.depth, n.is_layout))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outline,
+            vec![
+                ("Main".to_string(), 0, false),
+                ("VerticalLayout".to_string(), 1, true),
+                ("title := Text".to_string(), 2, false),
+                ("Card".to_string(), 2, false),
+                ("for Rectangle".to_string(), 3, false),
+                ("if Image".to_string(), 3, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Search over everything the preview reports while debugging: The console, the trace of
+//! invoked callbacks, the diagnostics of the last compilation and the history of property
+//! edits. The callback trace and the property history are only kept here.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use i_slint_compiler::diagnostics::{DiagnosticLevel, SourceLocation};
+use i_slint_core::animations::Instant;
+use slint_interpreter::CallbackInvocation;
+
+use super::console::{self, LogLevel};
+
+/// The number of callback invocations and property edits kept, older ones get dropped
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Console,
+    Callback,
+    Diagnostic,
+    Property,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub source: Source,
+    pub level: LogLevel,
+    /// When the entry got recorded
+    pub time: Instant,
+    pub text: String,
+    /// The file, line and column the entry refers to, if known
+    pub location: Option<(PathBuf, usize, usize)>,
+}
+
+/// Which entries to find
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    pub pattern: String,
+    /// Treat `pattern` as regular expression instead of plain text
+    pub regex: bool,
+    pub match_case: bool,
+    /// The sources to search, all of them if empty
+    pub sources: Vec<Source>,
+    /// Only entries recorded at or after this time
+    pub from: Option<Instant>,
+    /// Only entries recorded at or before this time
+    pub to: Option<Instant>,
+}
+
+enum Matcher {
+    Text(String),
+    Regex(regex::Regex),
+}
+
+impl Matcher {
+    fn new(query: &Query) -> Result<Self, String> {
+        if query.regex {
+            regex::RegexBuilder::new(&query.pattern)
+                .case_insensitive(!query.match_case)
+                .build()
+                .map(Matcher::Regex)
+                .map_err(|e| e.to_string())
+        } else if query.match_case {
+            Ok(Matcher::Text(query.pattern.clone()))
+        } else {
+            Ok(Matcher::Text(query.pattern.to_lowercase()))
+        }
+    }
+
+    fn is_match(&self, text: &str, match_case: bool) -> bool {
+        match self {
+            Matcher::Text(pattern) if match_case => text.contains(pattern.as_str()),
+            Matcher::Text(pattern) => text.to_lowercase().contains(pattern.as_str()),
+            Matcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+#[derive(Default)]
+struct History {
+    callbacks: VecDeque<Entry>,
+    properties: VecDeque<Entry>,
+    diagnostics: Vec<Entry>,
+    /// Warnings about content of the preview that gets cut off, listed with the diagnostics
+    overflow_warnings: Vec<Entry>,
+    update_pending: bool,
+    changed: Option<fn()>,
+}
+
+impl History {
+    /// Returns whether the UI needs to be told about new entries
+    fn push(&mut self, entry: Entry) -> bool {
+        let entries = match entry.source {
+            Source::Callback => &mut self.callbacks,
+            _ => &mut self.properties,
+        };
+        if entries.len() == MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+        !std::mem::replace(&mut self.update_pending, true)
+    }
+}
+
+thread_local! {static HISTORY: RefCell<History> = RefCell::default();}
+
+fn location(location: Option<&SourceLocation>) -> Option<(PathBuf, usize, usize)> {
+    let location = location?;
+    let source_file = location.source_file.as_ref()?;
+    if !location.span.is_valid() {
+        return None;
+    }
+    let (line, column) = source_file.line_column(location.span.offset);
+    Some((source_file.path().to_path_buf(), line, column))
+}
+
+fn record(entry: Entry) {
+    let changed = HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        if history.push(entry) {
+            history.changed
+        } else {
+            None
+        }
+    });
+    if let Some(changed) = changed {
+        // Callbacks get invoked while the preview is busy with an event, so update it later
+        slint::Timer::single_shot(std::time::Duration::ZERO, move || {
+            HISTORY.with(|history| history.borrow_mut().update_pending = false);
+            changed();
+        });
+    }
+}
+
+/// `changed` gets called from the event loop after new entries got recorded
+pub fn install(changed: fn()) {
+    HISTORY.with(|history| history.borrow_mut().changed = Some(changed));
+}
+
+/// Trace the invocation of a callback handler
+pub fn record_callback(invocation: &CallbackInvocation) {
+    let arguments = invocation
+        .arguments
+        .iter()
+        .map(super::expression_editor::value_text)
+        .collect::<Vec<_>>()
+        .join(", ");
+    record(Entry {
+        source: Source::Callback,
+        level: LogLevel::Debug,
+        time: Instant::now(),
+        text: format!("{}({arguments})", invocation.name),
+        location: location(invocation.location),
+    });
+}
+
+/// Remember that `property` got set to `value` on the element at `location`
+pub fn record_property_edit(
+    property: &str,
+    value: &str,
+    location: Option<(PathBuf, usize, usize)>,
+) {
+    record(Entry {
+        source: Source::Property,
+        level: LogLevel::Debug,
+        time: Instant::now(),
+        text: format!("{property}: {value}"),
+        location,
+    });
+}
+
+/// Replace the diagnostics with those of the latest compilation
+pub fn set_diagnostics(diagnostics: &[slint_interpreter::Diagnostic]) {
+    let time = Instant::now();
+    let entries = diagnostics
+        .iter()
+        .map(|d| {
+            let location = d.source_file().map(|path| {
+                let (line, column) = d.line_column();
+                (path.to_path_buf(), line, column)
+            });
+            Entry {
+                source: Source::Diagnostic,
+                level: match d.level() {
+                    DiagnosticLevel::Error => LogLevel::Error,
+                    DiagnosticLevel::Warning => LogLevel::Warning,
+                    _ => LogLevel::Debug,
+                },
+                time,
+                text: d.message().to_string(),
+                location,
+            }
+        })
+        .collect();
+    HISTORY.with(|history| history.borrow_mut().diagnostics = entries);
+}
+
+/// Replace the warnings about content that gets cut off with those of the latest check. The
+/// warnings found before keep the time they were found at.
+pub fn set_overflow_warnings(warnings: Vec<(String, Option<(PathBuf, usize, usize)>)>) {
+    let time = Instant::now();
+    HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        let entries = warnings
+            .into_iter()
+            .map(|(text, location)| {
+                let time = history
+                    .overflow_warnings
+                    .iter()
+                    .find(|e| e.text == text && e.location == location)
+                    .map_or(time, |e| e.time);
+                Entry { source: Source::Diagnostic, level: LogLevel::Warning, time, text, location }
+            })
+            .collect();
+        history.overflow_warnings = entries;
+    });
+}
+
+pub fn clear() {
+    HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        history.callbacks.clear();
+        history.properties.clear();
+    });
+}
+
+/// Parse a point in time of the session, given as seconds or as `minutes:seconds`
+pub fn parse_time(text: &str) -> Option<Instant> {
+    let text = text.trim();
+    let (minutes, seconds) = match text.split_once(':') {
+        Some((minutes, seconds)) => (minutes.trim().parse::<u64>().ok()?, seconds.trim()),
+        None => (0, text),
+    };
+    let seconds = seconds.parse::<f64>().ok().filter(|s| s.is_finite() && *s >= 0.0)?;
+    Some(Instant(minutes * 60_000 + (seconds * 1000.0).round() as u64))
+}
+
+/// Format `time` the way `parse_time` reads it
+pub fn format_time(time: Instant) -> String {
+    let millis = time.0;
+    format!("{}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+fn search_in(entries: Vec<Entry>, query: &Query) -> Result<Vec<Entry>, String> {
+    let matcher = Matcher::new(query)?;
+    let mut found = entries
+        .into_iter()
+        .filter(|e| query.sources.is_empty() || query.sources.contains(&e.source))
+        .filter(|e| query.from.is_none_or(|from| e.time >= from))
+        .filter(|e| query.to.is_none_or(|to| e.time <= to))
+        .filter(|e| {
+            matcher.is_match(&e.text, query.match_case)
+                || e.location.as_ref().is_some_and(|(path, _, _)| {
+                    matcher.is_match(&path.to_string_lossy(), query.match_case)
+                })
+        })
+        .collect::<Vec<_>>();
+    found.sort_by_key(|e| e.time);
+    Ok(found)
+}
+
+/// The entries of all sources matching `query`, oldest first. Fails if the regular
+/// expression of the query is invalid.
+pub fn search(query: &Query) -> Result<Vec<Entry>, String> {
+    let all = console::Filter { debug: true, warnings: true, errors: true };
+    let mut entries = console::messages(all)
+        .into_iter()
+        .map(|m| Entry {
+            source: Source::Console,
+            level: m.level,
+            time: m.time,
+            text: m.text,
+            location: m.location,
+        })
+        .collect::<Vec<_>>();
+    HISTORY.with(|history| {
+        let history = history.borrow();
+        entries.extend(history.callbacks.iter().cloned());
+        entries.extend(history.properties.iter().cloned());
+        entries.extend(history.diagnostics.iter().cloned());
+        entries.extend(history.overflow_warnings.iter().cloned());
+    });
+    search_in(entries, query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(source: Source, time: u64, text: &str) -> Entry {
+        Entry {
+            source,
+            level: LogLevel::Debug,
+            time: Instant(time),
+            text: text.into(),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let entries = vec![
+            entry(Source::Property, 3000, "width: 100px"),
+            entry(Source::Console, 1000, "Clicked 42 times"),
+            entry(Source::Callback, 2000, "clicked(42)"),
+            entry(Source::Diagnostic, 4000, "Unknown property clicked"),
+        ];
+        let texts = |query: Query| {
+            search_in(entries.clone(), &query)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.text)
+                .collect::<Vec<_>>()
+        };
+
+        let query = Query { pattern: "clicked".into(), ..Default::default() };
+        assert_eq!(
+            texts(query.clone()),
+            ["Clicked 42 times", "clicked(42)", "Unknown property clicked"]
+        );
+        assert_eq!(
+            texts(Query { match_case: true, ..query.clone() }),
+            ["clicked(42)", "Unknown property clicked"]
+        );
+        assert_eq!(
+            texts(Query { sources: vec![Source::Console, Source::Callback], ..query.clone() }),
+            ["Clicked 42 times", "clicked(42)"]
+        );
+        assert_eq!(
+            texts(Query { from: Some(Instant(1500)), to: Some(Instant(3500)), ..query }),
+            ["clicked(42)"]
+        );
+
+        let regex = Query { pattern: r"^\w+\(\d+\)$".into(), regex: true, ..Default::default() };
+        assert_eq!(texts(regex), ["clicked(42)"]);
+        let invalid = Query { pattern: "(".into(), regex: true, ..Default::default() };
+        assert!(search_in(entries.clone(), &invalid).is_err());
+
+        assert_eq!(texts(Query::default()).len(), 4);
+    }
+
+    #[test]
+    fn test_times() {
+        assert_eq!(parse_time("12"), Some(Instant(12_000)));
+        assert_eq!(parse_time(" 1.5 "), Some(Instant(1500)));
+        assert_eq!(parse_time("2:03.25"), Some(Instant(123_250)));
+        assert_eq!(parse_time("-1"), None);
+        assert_eq!(parse_time("abc"), None);
+        assert_eq!(parse_time(""), None);
+        assert_eq!(format_time(Instant(123_250)), "2:03.250");
+        assert_eq!(parse_time(&format_time(Instant(61_007))), Some(Instant(61_007)));
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = History::default();
+        assert!(history.push(entry(Source::Callback, 0, "0")));
+        assert!(!history.push(entry(Source::Property, 0, "x")));
+        for i in 1..MAX_ENTRIES + 10 {
+            history.push(entry(Source::Callback, i as u64, &i.to_string()));
+        }
+        assert_eq!(history.callbacks.len(), MAX_ENTRIES);
+        assert_eq!(history.callbacks.front().unwrap().text, "10");
+        assert_eq!(history.properties.len(), 1);
+    }
+}
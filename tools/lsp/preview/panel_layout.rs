@@ -0,0 +1,51 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Where the panels of the preview are docked, remembered per project in the project settings
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DockArea {
+    Left,
+    Right,
+    Bottom,
+    /// Above the preview, wherever the panel got dragged to
+    Floating,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub library: DockArea,
+    pub properties: DockArea,
+    pub outline: DockArea,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self { library: DockArea::Left, properties: DockArea::Right, outline: DockArea::Right }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialization() {
+        let layout = PanelLayout {
+            library: DockArea::Bottom,
+            properties: DockArea::Left,
+            outline: DockArea::Floating,
+        };
+        let data = serde_json::to_string(&layout).unwrap();
+        assert_eq!(data, r#"{"library":"bottom","properties":"left","outline":"floating"}"#);
+        assert_eq!(serde_json::from_str::<PanelLayout>(&data).unwrap(), layout);
+
+        // Panels unknown to the stored layout get their default area
+        assert_eq!(
+            serde_json::from_str::<PanelLayout>(r#"{"library":"right"}"#).unwrap(),
+            PanelLayout { library: DockArea::Right, ..PanelLayout::default() }
+        );
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Render a document that fails to compile with the erroneous elements
+//! replaced by placeholders.
+
+use std::path::Path;
+
+use i_slint_compiler::diagnostics::{BuildDiagnostics, Diagnostic, DiagnosticLevel};
+use i_slint_compiler::parser::{SyntaxKind, TextRange, TextSize};
+
+use crate::common;
+
+/// How often erroneous elements get replaced before giving up: Replacing an
+/// element can break references to it, which then need replacing in turn.
+pub const MAX_ATTEMPTS: usize = 4;
+
+fn placeholder() -> String {
+    format!(
+        "Rectangle {{ min-width: 16px; min-height: 16px; background: #ff000020; border-width: 1px; border-color: #ff0000; /* {} */ }}",
+        common::NODE_IGNORE_COMMENT
+    )
+}
+
+/// The (line, column) positions of all errors, or `None` if some error is not
+/// in the file at `path` and can thus not be fixed up by replacing elements in it.
+pub fn error_positions(diagnostics: &[Diagnostic], path: &Path) -> Option<Vec<(usize, usize)>> {
+    let path = i_slint_compiler::pathutils::clean_path(path);
+    diagnostics
+        .iter()
+        .filter(|d| d.level() == DiagnosticLevel::Error)
+        .map(|d| {
+            (d.source_file().map(i_slint_compiler::pathutils::clean_path) == Some(path.clone()))
+                .then(|| d.line_column())
+        })
+        .collect()
+}
+
+/// Replace every sub-element containing one of the `errors` positions with
+/// a placeholder, keeping its id around.
+///
+/// Returns `None` when an error is not inside any sub-element.
+pub fn replace_erroneous_elements(
+    source: &str,
+    path: &Path,
+    errors: &[(usize, usize)],
+) -> Option<String> {
+    if errors.is_empty() {
+        return None;
+    }
+
+    let mut diag = BuildDiagnostics::default();
+    let doc = i_slint_compiler::parser::parse(source.to_string(), Some(path), &mut diag);
+    let sub_elements =
+        doc.descendants().filter(|n| n.kind() == SyntaxKind::SubElement).collect::<Vec<_>>();
+
+    let mut ranges: Vec<TextRange> = vec![];
+    for (line, column) in errors {
+        let offset = TextSize::new(doc.source_file.offset(*line, *column) as u32);
+        let element = sub_elements
+            .iter()
+            .filter(|n| n.text_range().contains_inclusive(offset))
+            .min_by_key(|n| n.text_range().len())?
+            .child_node(SyntaxKind::Element)?;
+        ranges.push(element.text_range());
+    }
+
+    // Drop elements nested into other replaced elements
+    ranges.sort_by_key(|r| (r.start(), std::cmp::Reverse(r.end())));
+    ranges.dedup_by(|inner, outer| outer.contains_range(*inner));
+
+    let placeholder = placeholder();
+    let mut result = source.to_string();
+    for range in ranges.iter().rev() {
+        result.replace_range(usize::from(range.start())..usize::from(range.end()), &placeholder);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_erroneous_elements() {
+        let path = Path::new("/tmp/test.slint");
+        let source = r#"export component Test {
+    Rectangle {
+        label := Text { text: 42 + ; }
+        Rectangle {
+            Image { source: @image-url("x.png"); colour: red; }
+        }
+    }
+}
+"#;
+        let result = replace_erroneous_elements(source, path, &[(3, 34), (5, 50)]).unwrap();
+        assert_eq!(
+            result,
+            format!(
+                r#"export component Test {{
+    Rectangle {{
+        label := {0}
+        Rectangle {{
+            {0}
+        }}
+    }}
+}}
+"#,
+                placeholder()
+            )
+        );
+
+        // The outer element wins when errors are nested
+        let result = replace_erroneous_elements(source, path, &[(5, 50), (4, 10)]).unwrap();
+        assert!(result.contains("label := Text"));
+        assert!(!result.contains("Image"));
+        assert_eq!(result.matches("@lsp:ignore-node").count(), 1);
+
+        // Errors outside of sub-elements can not be fixed up
+        assert!(replace_erroneous_elements(source, path, &[(1, 5)]).is_none());
+        assert!(replace_erroneous_elements(source, path, &[]).is_none());
+    }
+}
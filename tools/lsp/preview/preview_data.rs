@@ -1,7 +1,10 @@
 // Copyright © SixtyFPS GmbH <info@slint.dev>
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
 
 use slint_interpreter::ComponentInstance;
 
@@ -65,6 +68,38 @@ pub fn has_getter(&self) -> bool {
     pub fn has_setter(&self) -> bool {
         has_setter(&self.visibility)
     }
+
+    pub fn is_callable(&self) -> bool {
+        matches!(
+            self.ty,
+            i_slint_compiler::langtype::Type::Function(_)
+                | i_slint_compiler::langtype::Type::Callback(_)
+        )
+    }
+
+    /// The names and types of the arguments of a callback or function
+    ///
+    /// Unnamed arguments are named after their position.
+    pub fn arguments(&self) -> Vec<(String, i_slint_compiler::langtype::Type)> {
+        let (i_slint_compiler::langtype::Type::Function(f)
+        | i_slint_compiler::langtype::Type::Callback(f)) = &self.ty
+        else {
+            return vec![];
+        };
+        f.args
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                let name = f
+                    .arg_names
+                    .get(i)
+                    .filter(|n| !n.is_empty())
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| format!("arg{i}"));
+                (name, ty.clone())
+            })
+            .collect()
+    }
 }
 
 pub fn get_preview_data(
@@ -267,6 +302,279 @@ pub fn set_json_preview_data(
     }
 }
 
+/// The values of the properties of `component_instance` and of its globals, as JSON. Properties
+/// of globals are named `Global.property`, like in the fixtures. The properties of the main
+/// component keep their name, so `slint-viewer --load-data` reads the result.
+pub fn get_json_preview_data(
+    component_instance: &ComponentInstance,
+) -> BTreeMap<String, serde_json::Value> {
+    query_preview_data_properties_and_callbacks(component_instance)
+        .into_iter()
+        .flat_map(|(container, data)| {
+            // `in` properties are included, like `slint-viewer --save-data` does
+            data.into_iter().filter(|d| d.is_property()).filter_map(move |d| {
+                let value = slint_interpreter::json::value_to_json(d.value.as_ref()?).ok()?;
+                let name = match &container {
+                    PropertyContainer::Main => d.name,
+                    PropertyContainer::Global(g) => format!("{g}.{}", d.name),
+                };
+                Some((name, value))
+            })
+        })
+        .collect()
+}
+
+/// Set the properties named in the JSON object `json`, as written by [`get_json_preview_data`]
+/// or `slint-viewer --save-data`. Properties that can only be read are skipped, so that
+/// exported data can be imported again.
+///
+/// Returns the number of properties set. Errors do not stop the other properties from being set.
+pub fn set_all_json_preview_data(
+    component_instance: &ComponentInstance,
+    json: &serde_json::Value,
+) -> Result<usize, Vec<String>> {
+    let Some(object) = json.as_object() else {
+        return Err(vec!["The data is not a JSON object".to_string()]);
+    };
+
+    let mut properties_set = 0_usize;
+    let mut failed_properties = vec![];
+
+    for (key, json_value) in object {
+        let (container, name) = match key.split_once('.') {
+            Some((g, name)) => (PropertyContainer::Global(g.to_string()), name),
+            None => (PropertyContainer::Main, key.as_str()),
+        };
+        let Some(preview_data) =
+            get_preview_data(component_instance, container.clone(), name.to_string())
+                .filter(|pd| pd.is_property())
+        else {
+            failed_properties.push(format!("Property {key} not found"));
+            continue;
+        };
+        if !preview_data.has_setter() {
+            continue;
+        }
+        let Ok(value) = slint_interpreter::json::value_from_json(&preview_data.ty, json_value)
+        else {
+            failed_properties.push(format!("Could not convert JSON value for property {key}"));
+            continue;
+        };
+
+        let result = match &container {
+            PropertyContainer::Main => component_instance.set_property(name, value),
+            PropertyContainer::Global(g) => component_instance.set_global_property(g, name, value),
+        };
+        match result {
+            Ok(()) => properties_set += 1,
+            Err(msg) => failed_properties.push(format!("Could not set property {key}: {msg}")),
+        }
+    }
+
+    if failed_properties.is_empty() {
+        Ok(properties_set)
+    } else {
+        Err(failed_properties)
+    }
+}
+
+/// A change to the rows of an array-valued property
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RowOperation {
+    /// Append a row with default values
+    Append,
+    /// Insert a copy of the row at the index right after it
+    Duplicate(usize),
+    /// Remove the row at the index
+    Remove(usize),
+    /// Replace all rows with the given number of rows of mock data
+    Mock(usize),
+}
+
+const LOREM_IPSUM: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+];
+
+const NAMES: &[&str] =
+    &["Alice", "Bob", "Carol", "Dave", "Erin", "Frank", "Grace", "Heidi", "Ivan", "Judy"];
+
+/// Some words of lorem ipsum, different for each row
+fn lorem_ipsum(row: usize) -> String {
+    let words = (0..2 + row % 3)
+        .map(|i| LOREM_IPSUM[(row * 3 + i) % LOREM_IPSUM.len()])
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut chars = words.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// A plausible value of type `ty` for the field or property called `name` in the row at index
+/// `row`: Text for strings, counters for numbers, and colors spread over the hues
+fn mock_value(
+    ty: &i_slint_compiler::langtype::Type,
+    name: &str,
+    row: usize,
+) -> slint_interpreter::Value {
+    use i_slint_compiler::langtype::Type;
+    use slint_interpreter::Value;
+
+    match ty {
+        Type::String => {
+            let name = name.to_lowercase();
+            let text = if name.contains("mail") {
+                format!("{}@example.com", NAMES[row % NAMES.len()].to_lowercase())
+            } else if name.contains("name") || name.contains("author") {
+                NAMES[row % NAMES.len()].to_string()
+            } else {
+                lorem_ipsum(row)
+            };
+            Value::String(text.into())
+        }
+        Type::Int32 => Value::Number((row + 1) as f64),
+        Type::Float32
+        | Type::Percent
+        | Type::LogicalLength
+        | Type::PhysicalLength
+        | Type::Rem
+        | Type::Duration
+        | Type::Angle => Value::Number(((row + 1) * 10) as f64),
+        Type::Bool => Value::Bool(row % 2 == 0),
+        Type::Color | Type::Brush => {
+            // The golden angle keeps neighboring rows apart
+            let color = slint::Color::from_hsva((row as f32 * 137.5) % 360.0, 0.6, 0.9, 1.0);
+            Value::Brush(slint::Brush::SolidColor(color))
+        }
+        Type::Enumeration(enumeration) if !enumeration.values.is_empty() => {
+            Value::EnumerationValue(
+                enumeration.name.to_string(),
+                enumeration.values[row % enumeration.values.len()].to_string(),
+            )
+        }
+        Type::Struct(s) => Value::Struct(
+            s.fields
+                .iter()
+                .map(|(field, ty)| (field.to_string(), mock_value(ty, field, row)))
+                .collect(),
+        ),
+        Type::Array(row_type) => {
+            let rows = (0..3).map(|r| mock_value(row_type, name, row + r)).collect::<Vec<_>>();
+            Value::Model(slint::ModelRc::new(slint::VecModel::from(rows)))
+        }
+        _ => slint_interpreter::default_value_for_type(ty),
+    }
+}
+
+pub fn change_preview_data_rows(
+    component_instance: &ComponentInstance,
+    container: PropertyContainer,
+    property_name: &str,
+    operation: RowOperation,
+) -> Result<(), String> {
+    let preview_data =
+        get_preview_data(component_instance, container.clone(), property_name.to_string())
+            .ok_or_else(|| format!("Property {property_name} not found"))?;
+
+    if !preview_data.has_setter() {
+        return Err(format!("Property {property_name} can not be set"));
+    }
+    let i_slint_compiler::langtype::Type::Array(row_type) = &preview_data.ty else {
+        return Err(format!("Property {property_name} is not an array"));
+    };
+
+    let mut rows = match preview_data.value {
+        Some(slint_interpreter::Value::Model(model)) => {
+            slint::Model::iter(&model).collect::<Vec<_>>()
+        }
+        _ => vec![],
+    };
+
+    match operation {
+        RowOperation::Append => {
+            rows.push(slint_interpreter::default_value_for_type(row_type));
+        }
+        RowOperation::Duplicate(row) => {
+            let value = rows.get(row).cloned().ok_or_else(|| format!("Row {row} not found"))?;
+            rows.insert(row + 1, value);
+        }
+        RowOperation::Remove(row) => {
+            if row >= rows.len() {
+                return Err(format!("Row {row} not found"));
+            }
+            rows.remove(row);
+        }
+        RowOperation::Mock(count) => {
+            rows = (0..count).map(|row| mock_value(row_type, property_name, row)).collect();
+        }
+    }
+
+    let value = slint_interpreter::Value::Model(slint::ModelRc::new(slint::VecModel::from(rows)));
+
+    match &container {
+        PropertyContainer::Main => component_instance.set_property(property_name, value),
+        PropertyContainer::Global(g) => {
+            component_instance.set_global_property(g, property_name, value)
+        }
+    }
+    .map_err(|e| format!("Could not set property {property_name}: {e}"))
+}
+
+/// Invoke a callback or function with arguments given as JSON
+pub fn invoke_preview_data_callable(
+    component_instance: &ComponentInstance,
+    container: PropertyContainer,
+    callable_name: &str,
+    arguments: &[serde_json::Value],
+) -> Result<slint_interpreter::Value, String> {
+    let preview_data =
+        get_preview_data(component_instance, container.clone(), callable_name.to_string())
+            .filter(|pd| pd.is_callable())
+            .ok_or_else(|| format!("Callback or function {callable_name} not found"))?;
+
+    let argument_types = preview_data.arguments();
+    if argument_types.len() != arguments.len() {
+        return Err(format!(
+            "{callable_name} expects {} arguments, got {}",
+            argument_types.len(),
+            arguments.len()
+        ));
+    }
+
+    let arguments = argument_types
+        .iter()
+        .zip(arguments)
+        .map(|((name, ty), json)| {
+            slint_interpreter::json::value_from_json(ty, json)
+                .map_err(|e| format!("Could not convert JSON value for argument {name}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match &container {
+        PropertyContainer::Main => component_instance.invoke(callable_name, &arguments),
+        PropertyContainer::Global(g) => {
+            component_instance.invoke_global(g, callable_name, &arguments)
+        }
+    }
+    .map_err(|e| format!("Could not invoke {callable_name}: {e}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -462,4 +770,338 @@ fn test_query_preview_data_properties_and_callbacks() {
             }
         );
     }
+
+    #[test]
+    fn test_mock_rows() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+                export enum Status { open, closed }
+                export struct Contact {
+                    name: string,
+                    email: string,
+                    note: string,
+                    count: int,
+                    active: bool,
+                    tint: color,
+                    status: Status,
+                }
+
+                export component MainComponent {
+                    in property <[Contact]> contacts;
+                    in property <[int]> numbers;
+                }
+            "#,
+        );
+
+        change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "contacts",
+            RowOperation::Mock(4),
+        )
+        .unwrap();
+        let Some(slint_interpreter::Value::Model(model)) =
+            get_preview_data(&component_instance, PropertyContainer::Main, "contacts".into())
+                .unwrap()
+                .value
+        else {
+            panic!("Not a model");
+        };
+        assert_eq!(slint::Model::row_count(&model), 4);
+        let Some(slint_interpreter::Value::Struct(second)) = slint::Model::row_data(&model, 1)
+        else {
+            panic!("Not a struct");
+        };
+        let field = |name: &str| second.get_field(name).unwrap().clone();
+        assert_eq!(field("name"), slint_interpreter::Value::String("Bob".into()));
+        assert_eq!(field("email"), slint_interpreter::Value::String("bob@example.com".into()));
+        assert_eq!(field("count"), slint_interpreter::Value::Number(2.));
+        assert_eq!(field("active"), slint_interpreter::Value::Bool(false));
+        assert_eq!(
+            field("status"),
+            slint_interpreter::Value::EnumerationValue("Status".into(), "closed".into())
+        );
+        let slint_interpreter::Value::String(note) = field("note") else {
+            panic!("Not a string");
+        };
+        assert!(note.starts_with(|c: char| c.is_uppercase()));
+        assert_ne!(field("tint"), mock_value(&i_slint_compiler::langtype::Type::Color, "tint", 0));
+
+        change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "numbers",
+            RowOperation::Mock(3),
+        )
+        .unwrap();
+        let value =
+            get_preview_data(&component_instance, PropertyContainer::Main, "numbers".into())
+                .unwrap()
+                .value;
+        let Some(slint_interpreter::Value::Model(model)) = value else {
+            panic!("Not a model");
+        };
+        assert_eq!(
+            slint::Model::iter(&model).collect::<Vec<_>>(),
+            [1., 2., 3.].map(slint_interpreter::Value::Number).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_change_preview_data_rows() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+                export struct Row {
+                    name: string,
+                    count: int,
+                }
+
+                export global Data {
+                    in-out property <[Row]> global-rows: [{ name: "g", count: 3 }];
+                }
+
+                export component MainComponent {
+                    in-out property <[Row]> rows: [{ name: "a", count: 1 }, { name: "b", count: 2 }];
+                    out property <[int]> read-only: [1, 2, 3];
+                    in property <int> not-an-array: 42;
+                }
+            "#,
+        );
+
+        let rows = |container: PropertyContainer, name: &str| {
+            let Some(slint_interpreter::Value::Model(model)) =
+                get_preview_data(&component_instance, container, name.to_string()).unwrap().value
+            else {
+                panic!("Not a model");
+            };
+            slint::Model::iter(&model)
+                .map(|v| {
+                    let slint_interpreter::Value::Struct(s) = v else {
+                        panic!("Not a struct");
+                    };
+                    let slint_interpreter::Value::String(name) = s.get_field("name").unwrap()
+                    else {
+                        panic!("Not a string");
+                    };
+                    name.to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(rows(PropertyContainer::Main, "rows"), vec!["a", "b"]);
+
+        change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "rows",
+            RowOperation::Duplicate(0),
+        )
+        .unwrap();
+        assert_eq!(rows(PropertyContainer::Main, "rows"), vec!["a", "a", "b"]);
+
+        change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "rows",
+            RowOperation::Remove(1),
+        )
+        .unwrap();
+        assert_eq!(rows(PropertyContainer::Main, "rows"), vec!["a", "b"]);
+
+        change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "rows",
+            RowOperation::Append,
+        )
+        .unwrap();
+        assert_eq!(rows(PropertyContainer::Main, "rows"), vec!["a", "b", ""]);
+
+        change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Global("Data".into()),
+            "global-rows",
+            RowOperation::Duplicate(0),
+        )
+        .unwrap();
+        assert_eq!(rows(PropertyContainer::Global("Data".into()), "global-rows"), vec!["g", "g"]);
+
+        // Errors:
+        assert!(change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "rows",
+            RowOperation::Remove(3),
+        )
+        .is_err());
+        assert!(change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "rows",
+            RowOperation::Duplicate(3),
+        )
+        .is_err());
+        assert!(change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "read-only",
+            RowOperation::Append,
+        )
+        .is_err());
+        assert!(change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "not-an-array",
+            RowOperation::Append,
+        )
+        .is_err());
+        assert!(change_preview_data_rows(
+            &component_instance,
+            PropertyContainer::Main,
+            "does-not-exist",
+            RowOperation::Append,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_invoke_preview_data_callable() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+                export global Logic {
+                    public pure function double(value: int) -> int {
+                        return value * 2;
+                    }
+                }
+
+                export component MainComponent {
+                    in-out property <string> last;
+                    callback greet(name: string, int) -> string;
+                    greet(name, count) => {
+                        self.last = name;
+                        return "Hello " + name + count;
+                    }
+                }
+            "#,
+        );
+
+        let main_data =
+            get_preview_data(&component_instance, PropertyContainer::Main, "greet".into()).unwrap();
+        assert!(main_data.is_callable());
+        assert!(!main_data.is_property());
+        assert_eq!(
+            main_data.arguments(),
+            vec![
+                ("name".to_string(), i_slint_compiler::langtype::Type::String),
+                ("arg1".to_string(), i_slint_compiler::langtype::Type::Int32),
+            ]
+        );
+
+        assert_eq!(
+            invoke_preview_data_callable(
+                &component_instance,
+                PropertyContainer::Main,
+                "greet",
+                &[serde_json::json!("World"), serde_json::json!(3)],
+            ),
+            Ok(slint_interpreter::Value::String("Hello World3".into()))
+        );
+        assert_eq!(
+            component_instance.get_property("last"),
+            Ok(slint_interpreter::Value::String("World".into()))
+        );
+
+        assert_eq!(
+            invoke_preview_data_callable(
+                &component_instance,
+                PropertyContainer::Global("Logic".into()),
+                "double",
+                &[serde_json::json!(21)],
+            ),
+            Ok(slint_interpreter::Value::Number(42.0))
+        );
+
+        // Errors:
+        assert!(invoke_preview_data_callable(
+            &component_instance,
+            PropertyContainer::Main,
+            "greet",
+            &[serde_json::json!("World")],
+        )
+        .is_err());
+        assert!(invoke_preview_data_callable(
+            &component_instance,
+            PropertyContainer::Main,
+            "greet",
+            &[serde_json::json!({}), serde_json::json!(3)],
+        )
+        .is_err());
+        assert!(invoke_preview_data_callable(
+            &component_instance,
+            PropertyContainer::Main,
+            "last",
+            &[],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_json_preview_data_round_trip() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+                export global Theme {
+                    in-out property <bool> dark;
+                }
+
+                export component MainComponent {
+                    in-out property <string> title: "Hello";
+                    in property <[int]> numbers: [1, 2];
+                    out property <int> count: self.numbers.length;
+                }
+            "#,
+        );
+
+        let exported = get_json_preview_data(&component_instance);
+        assert_eq!(
+            serde_json::to_value(&exported).unwrap(),
+            serde_json::json!({ "Theme.dark": false, "count": 2, "numbers": [1, 2], "title": "Hello" })
+        );
+
+        // What got exported can be imported again, the output property is skipped
+        let data = serde_json::json!({
+            "Theme.dark": true,
+            "count": 7,
+            "numbers": [4, 5, 6],
+            "title": "World",
+        });
+        assert_eq!(set_all_json_preview_data(&component_instance, &data), Ok(3));
+        assert_eq!(
+            serde_json::to_value(get_json_preview_data(&component_instance)).unwrap(),
+            serde_json::json!({
+                "Theme.dark": true,
+                "count": 3,
+                "numbers": [4, 5, 6],
+                "title": "World",
+            })
+        );
+
+        // Errors:
+        let errors = set_all_json_preview_data(
+            &component_instance,
+            &serde_json::json!({ "title": "Again", "unknown": 1, "Theme.dark": "yes" }),
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            get_preview_data(&component_instance, PropertyContainer::Main, "title".into())
+                .unwrap()
+                .value,
+            Some(slint_interpreter::Value::String("Again".into()))
+        );
+        assert!(set_all_json_preview_data(&component_instance, &serde_json::json!([])).is_err());
+    }
 }
@@ -0,0 +1,224 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Further windows showing components of the project next to the preview, e.g. the main screen
+//! in one window and a dialog in another. Each window has a style and color scheme of its own.
+//! The windows get compiled from the same sources as the preview and reload together with it,
+//! but have none of its editing tools.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use i_slint_compiler::diagnostics::DiagnosticLevel;
+use i_slint_core::items::ColorScheme;
+use lsp_types::Url;
+use slint::ComponentHandle;
+use slint_interpreter::ComponentInstance;
+
+use crate::common;
+
+/// What a window shows
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowContent {
+    pub url: Url,
+    /// The component to show, the last exported one if `None`
+    pub component: Option<String>,
+    pub style: String,
+    pub color_scheme: ColorScheme,
+}
+
+impl WindowContent {
+    /// A short description of the window showing the component `name`, like
+    /// `Settings (fluent, dark)`
+    pub fn description(&self, name: &str) -> String {
+        let color_scheme = match self.color_scheme {
+            ColorScheme::Dark => Some("dark"),
+            ColorScheme::Light => Some("light"),
+            _ => None,
+        };
+        let details = [Some(self.style.as_str()).filter(|s| !s.is_empty()), color_scheme]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        if details.is_empty() {
+            name.to_string()
+        } else {
+            format!("{name} ({})", details.join(", "))
+        }
+    }
+}
+
+struct PreviewWindow {
+    id: usize,
+    content: WindowContent,
+    /// The name of the component shown
+    name: String,
+    instance: ComponentInstance,
+}
+
+#[derive(Default)]
+struct Windows {
+    next_id: usize,
+    windows: Vec<PreviewWindow>,
+}
+
+thread_local! {static WINDOWS: RefCell<Windows> = RefCell::default();}
+
+/// Loads the files imported by the components of the windows from the cache of the preview,
+/// as they are in the editor
+fn file_loader(
+    path: String,
+) -> core::pin::Pin<
+    Box<
+        dyn core::future::Future<
+            Output = Option<std::io::Result<(common::SourceFileVersion, String)>>,
+        >,
+    >,
+> {
+    Box::pin(async move {
+        let url = Url::from_file_path(PathBuf::from(&path)).map_err(|()| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "Failed to convert path to URL")
+        });
+        Some(url.map(|url| source(&url)))
+    })
+}
+
+fn source(url: &Url) -> (common::SourceFileVersion, String) {
+    let mut cache = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    cache.window_dependencies.insert(url.clone());
+    cache.source_code.get(url).map(|r| (r.version, r.code.clone())).unwrap_or_default()
+}
+
+/// The component shown in a window with the `content`, and its name
+async fn compile(content: &WindowContent) -> Result<(ComponentInstance, String), String> {
+    let path =
+        content.url.to_file_path().map_err(|()| format!("{} is not a local file", content.url))?;
+    let config = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().config.clone();
+    let (version, source_code) = source(&content.url);
+    let (diagnostics, compiled, _, _) = super::parse_source(
+        config.include_paths,
+        config.library_paths,
+        path,
+        version,
+        source_code,
+        content.style.clone(),
+        content.component.clone(),
+        file_loader,
+    )
+    .await;
+    let Some(definition) = compiled else {
+        let errors = diagnostics.iter().filter(|d| d.level() == DiagnosticLevel::Error).count();
+        return Err(format!("The component has {errors} errors"));
+    };
+    let instance = definition.create().map_err(|e| e.to_string())?;
+    Ok((instance, definition.name().to_string()))
+}
+
+/// Show the `instance` in the window with the `id`, in place of the `previous` one
+fn show(
+    id: usize,
+    instance: &ComponentInstance,
+    content: &WindowContent,
+    previous: Option<&ComponentInstance>,
+) -> Result<(), String> {
+    let window = instance.window();
+    super::set_color_scheme_override(window, content.color_scheme);
+    if let Some(previous) = previous {
+        // Keep the window where the user put it, which may be on another monitor
+        window.set_position(previous.window().position());
+        window.set_size(previous.window().size());
+    }
+    window.on_close_requested(move || {
+        // Do not drop the window while it handles the event
+        slint::Timer::single_shot(std::time::Duration::ZERO, move || {
+            WINDOWS.with(|windows| windows.borrow_mut().windows.retain(|w| w.id != id));
+        });
+        slint::CloseRequestResponse::HideWindow
+    });
+    instance.show().map_err(|e| e.to_string())?;
+    if let Some(previous) = previous {
+        previous.hide().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Open a further window with the `content`. Returns its description.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn open(content: WindowContent) -> Result<String, String> {
+    let (instance, name) = compile(&content).await?;
+    let id = WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        windows.next_id += 1;
+        windows.next_id
+    });
+    show(id, &instance, &content, None)?;
+    let description = content.description(&name);
+    WINDOWS.with(|windows| {
+        windows.borrow_mut().windows.push(PreviewWindow { id, content, name, instance })
+    });
+    Ok(description)
+}
+
+/// Compile the components of all windows again and show them in place of the old ones.
+/// Windows whose component fails to compile keep showing the old one. Returns why they failed.
+pub async fn reload() -> Vec<String> {
+    let contents = WINDOWS.with(|windows| {
+        let windows = windows.borrow();
+        windows
+            .windows
+            .iter()
+            .map(|w| (w.id, w.content.clone(), w.name.clone()))
+            .collect::<Vec<_>>()
+    });
+    if contents.is_empty() {
+        return vec![];
+    }
+    super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().window_dependencies.clear();
+
+    let mut errors = vec![];
+    for (id, content, name) in contents {
+        let instance = match compile(&content).await {
+            Ok((instance, _)) => instance,
+            Err(e) => {
+                errors.push(format!("{}: {e}", content.description(&name)));
+                continue;
+            }
+        };
+        let previous = WINDOWS.with(|windows| {
+            windows.borrow().windows.iter().find(|w| w.id == id).map(|w| w.instance.clone_strong())
+        });
+        // The window got closed meanwhile
+        let Some(previous) = previous else {
+            continue;
+        };
+        if let Err(e) = show(id, &instance, &content, Some(&previous)) {
+            errors.push(format!("{}: {e}", content.description(&name)));
+            continue;
+        }
+        WINDOWS.with(|windows| {
+            if let Some(window) = windows.borrow_mut().windows.iter_mut().find(|w| w.id == id) {
+                window.instance = instance;
+            }
+        });
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_description() {
+        let content = WindowContent {
+            url: Url::parse("file:///project/settings.slint").unwrap(),
+            component: Some("Settings".into()),
+            style: "fluent".into(),
+            color_scheme: ColorScheme::Dark,
+        };
+        assert_eq!(content.description("Settings"), "Settings (fluent, dark)");
+        let content =
+            WindowContent { style: String::new(), color_scheme: ColorScheme::Unknown, ..content };
+        assert_eq!(content.description("Settings"), "Settings");
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The design session of a project, restored when the preview shows one of its components
+//! again: The style, window size, zoom level, the previewed component, the panel layout,
+//! whether the selection follows the editor, which library components got used, which elements
+//! are locked and the fixtures of array properties.
+//!
+//! The settings outlive the session only when the user asks for it, the preview does not put
+//! files into a project on its own. They are then kept in the [`SETTINGS_FILE`] of the project,
+//! to be shared along with it.
+
+use std::path::{Path, PathBuf};
+
+use lsp_types::Url;
+
+use super::component_usage::ProjectUsage;
+use super::element_locks::ElementAddress;
+use super::model_fixtures::Fixtures;
+use super::panel_layout::PanelLayout;
+
+/// The name of the file in the project directory the settings are stored in
+pub const SETTINGS_FILE: &str = ".slint-preview-settings.json";
+
+/// Files marking the root directory of a project
+const PROJECT_MARKERS: &[&str] =
+    &[".git", "Cargo.toml", "package.json", "CMakeLists.txt", "pyproject.toml"];
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    pub style: Option<String>,
+    /// The size of the preview window in logical pixels
+    pub window_size: Option<(f32, f32)>,
+    pub zoom: Option<f32>,
+    /// The file of the previewed component, relative to the project, and its name
+    pub component: Option<(PathBuf, String)>,
+    pub panel_layout: Option<PanelLayout>,
+    /// Select the element at the text cursor of the editor
+    pub follow_editor_cursor: bool,
+    pub component_usage: ProjectUsage,
+    /// The elements locked against changes from the preview, relative to the project
+    pub locked_elements: Vec<ElementAddress>,
+    /// The rows kept for the array properties of the components
+    pub fixtures: Fixtures,
+}
+
+impl ProjectSettings {
+    /// Remember the component called `name` in the file at `url` as the previewed one
+    pub fn set_component(&mut self, project: &Path, url: &Url, name: &str) {
+        self.component = url
+            .to_file_path()
+            .ok()
+            .and_then(|path| Some(path.strip_prefix(project).ok()?.to_path_buf()))
+            .map(|path| (path, name.to_string()));
+    }
+
+    /// The component previewed last, if it is in the file at `url`
+    pub fn component_in(&self, project: &Path, url: &Url) -> Option<String> {
+        let (path, name) = self.component.as_ref()?;
+        (url.to_file_path().ok()? == project.join(path)).then(|| name.clone())
+    }
+}
+
+/// The directory of the project `file` belongs to: The closest directory containing
+/// one of the `PROJECT_MARKERS`, or the directory of the file.
+pub fn project_of(file: &Path) -> PathBuf {
+    let directory = file.parent().unwrap_or(file);
+    directory
+        .ancestors()
+        .find(|d| PROJECT_MARKERS.iter().any(|m| d.join(m).exists()))
+        .unwrap_or(directory)
+        .to_path_buf()
+}
+
+fn settings_path(project: &Path) -> PathBuf {
+    project.join(SETTINGS_FILE)
+}
+
+/// Whether the user asked to keep the settings in `project`
+pub fn is_stored(project: &Path) -> bool {
+    settings_path(project).exists()
+}
+
+/// The settings stored in `project`, or the defaults
+pub fn load(project: &Path) -> ProjectSettings {
+    std::fs::read(settings_path(project))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Store the settings of `project`
+pub fn store(project: &Path, settings: &ProjectSettings) -> Result<(), String> {
+    let path = settings_path(project);
+    serde_json::to_vec_pretty(settings)
+        .map_err(std::io::Error::from)
+        .and_then(|data| std::fs::write(&path, data))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Stop keeping the settings in `project`, removing their file
+pub fn forget(project: &Path) -> Result<(), String> {
+    let path = settings_path(project);
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {e}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use super::super::panel_layout::DockArea;
+    use super::super::preview_data::PropertyContainer;
+
+    /// A directory of its own for each test and test process, removed again when dropped
+    struct TestDirectory(PathBuf);
+
+    impl TestDirectory {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("slint-project-settings-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TestDirectory {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_load_and_store() {
+        let directory = TestDirectory::new("load-and-store");
+        let project = &directory.0;
+        std::fs::create_dir_all(project.join("ui")).unwrap();
+        assert_eq!(load(project), ProjectSettings::default());
+
+        let url = Url::from_file_path(project.join("ui").join("main.slint")).unwrap();
+        let mut settings = ProjectSettings {
+            style: Some("cupertino".into()),
+            window_size: Some((800.0, 600.0)),
+            zoom: Some(1.5),
+            panel_layout: Some(PanelLayout { library: DockArea::Bottom, ..Default::default() }),
+            follow_editor_cursor: true,
+            ..Default::default()
+        };
+        settings.set_component(project, &url, "Main");
+        assert_eq!(
+            settings.component,
+            Some((PathBuf::from("ui").join("main.slint"), "Main".into()))
+        );
+        assert_eq!(settings.component_in(project, &url), Some("Main".into()));
+        let other = Url::from_file_path(project.join("other.slint")).unwrap();
+        assert_eq!(settings.component_in(project, &other), None);
+
+        settings.fixtures.set(
+            "Main",
+            &PropertyContainer::Main,
+            "rows",
+            Some(serde_json::json!([1, 2])),
+        );
+
+        assert!(!is_stored(project));
+        store(project, &settings).unwrap();
+        assert!(is_stored(project));
+        assert_eq!(load(project), settings);
+
+        // Settings unknown to the stored file keep their defaults
+        std::fs::write(project.join(SETTINGS_FILE), r#"{"zoom":2.0}"#).unwrap();
+        assert_eq!(load(project), ProjectSettings { zoom: Some(2.0), ..Default::default() });
+
+        forget(project).unwrap();
+        assert!(!is_stored(project));
+        // Forgetting twice is fine
+        forget(project).unwrap();
+    }
+
+    #[test]
+    fn test_project_of() {
+        let test_directory = TestDirectory::new("project-of");
+        let directory = &test_directory.0;
+        let ui = directory.join("ui");
+        std::fs::create_dir_all(&ui).unwrap();
+        std::fs::write(directory.join("Cargo.toml"), "").unwrap();
+
+        assert_eq!(project_of(&ui.join("main.slint")), *directory);
+    }
+}
@@ -54,6 +54,8 @@ pub struct DefinitionInformation {
     pub property_definition_range: TextRange,
     pub selection_range: TextRange,
     pub code_block_or_expression: CodeBlockOrExpression,
+    /// For a two-way binding: The range of the `<=>`, with the white-space before it
+    pub two_way_arrow_range: Option<TextRange>,
 }
 
 #[derive(Clone, Debug)]
@@ -62,6 +64,53 @@ pub struct DeclarationInformation {
     pub start_position: TextSize,
 }
 
+/// The settings of an `animate` block, empty for the settings not set in it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationSettings {
+    pub duration: String,
+    pub delay: String,
+    pub easing: String,
+    pub iteration_count: String,
+}
+
+impl AnimationSettings {
+    fn bindings(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("duration", self.duration.as_str()),
+            ("delay", self.delay.as_str()),
+            ("easing", self.easing.as_str()),
+            ("iteration-count", self.iteration_count.as_str()),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name, value.trim()))
+        .filter(|(_, value)| !value.is_empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings().next().is_none()
+    }
+
+    /// The `animate` block for the `properties` with these settings
+    fn to_source(&self, properties: &[&str], indent: &str) -> String {
+        let bindings = self
+            .bindings()
+            .map(|(name, value)| format!("\n{indent}        {name}: {value};"))
+            .collect::<String>();
+        format!("animate {} {{{bindings}\n{indent}    }}", properties.join(", "))
+    }
+}
+
+/// An `animate` block in an element
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationInformation {
+    pub range: TextRange,
+    /// The range of the names of the animated properties
+    pub names_range: TextRange,
+    /// All properties animated by the block
+    pub properties: Vec<SmolStr>,
+    pub settings: AnimationSettings,
+}
+
 #[derive(Clone, Debug)]
 pub struct PropertyInformation {
     pub name: SmolStr,
@@ -74,6 +123,10 @@ pub struct PropertyInformation {
     pub default_value: Option<Expression>,
     pub group: SmolStr,
     pub group_priority: u32,
+    /// The `animate` block animating the property, if any
+    pub animation: Option<AnimationInformation>,
+    /// Where the property this one is bound to with `<=>` is declared, or else set
+    pub two_way_binding_peer: Option<DeclarationInformation>,
 }
 
 #[derive(Clone, Debug)]
@@ -110,6 +163,8 @@ fn get_reserved_properties<'a>(
             default_value: None,
             group: group.into(),
             group_priority,
+            animation: None,
+            two_way_binding_peer: None,
         }
     })
 }
@@ -157,6 +212,8 @@ fn add_element_properties(
             default_value: None,
             group: group.into(),
             group_priority,
+            animation: None,
+            two_way_binding_peer: None,
         })
     }))
 }
@@ -234,6 +291,7 @@ fn find_code_block_or_expression(
     let mut selection_range = None;
     let mut code_block_or_expression = None;
     let mut property_definition_range = None;
+    let mut two_way_arrow_range = None;
 
     if let Some(token) = element.token_at_offset(offset.into()).right_biased() {
         for ancestor in token.parent_ancestors() {
@@ -244,8 +302,23 @@ fn find_code_block_or_expression(
                 }
                 continue;
             }
+            // `foo <=> bar;` in an element, or the `<=> bar` of a property declaration
+            let is_two_way_binding = ancestor.kind() == SyntaxKind::TwoWayBinding;
+            if is_two_way_binding {
+                let arrow = ancestor.child_token(SyntaxKind::DoubleArrow)?;
+                let start = arrow
+                    .prev_token()
+                    .filter(|t| t.kind() == SyntaxKind::Whitespace)
+                    .map_or(arrow.text_range().start(), |t| t.text_range().start());
+                two_way_arrow_range = Some(TextRange::new(start, arrow.text_range().end()));
+                code_block_or_expression = ancestor
+                    .child_node(SyntaxKind::Expression)
+                    .and_then(CodeBlockOrExpression::new);
+            }
             if (ancestor.kind() == SyntaxKind::Binding)
                 || (ancestor.kind() == SyntaxKind::PropertyDeclaration)
+                || (is_two_way_binding
+                    && ancestor.parent().is_some_and(|p| p.kind() == SyntaxKind::Element))
             {
                 property_definition_range = Some(ancestor.text_range());
                 selection_range = Some(TextRange::new(
@@ -265,6 +338,7 @@ fn find_code_block_or_expression(
         property_definition_range: property_definition_range?,
         selection_range: selection_range?,
         code_block_or_expression: code_block_or_expression?,
+        two_way_arrow_range,
     })
 }
 
@@ -291,7 +365,63 @@ fn find_property_binding_offset(
     None
 }
 
-#[derive(Debug)]
+fn find_animations(element: &syntax_nodes::Element) -> Vec<AnimationInformation> {
+    element
+        .PropertyAnimation()
+        .filter_map(|animation| {
+            let names = animation.QualifiedName().collect::<Vec<_>>();
+            // `animate *` is only allowed in transitions
+            let last = names.last()?;
+            // Without the white-space before the `{`
+            let names_range = TextRange::new(
+                names.first()?.text_range().start(),
+                last.text_range().start() + TextSize::of(last.text().to_string().trim_end()),
+            );
+            let properties = names
+                .iter()
+                .map(|n| {
+                    i_slint_compiler::parser::normalize_identifier(n.text().to_string().trim())
+                })
+                .collect();
+
+            let mut settings = AnimationSettings::default();
+            for binding in animation.Binding() {
+                let value = binding.BindingExpression().text().to_string();
+                let value = value.trim().trim_end_matches(';').trim_end().to_string();
+                match i_slint_compiler::parser::identifier_text(&binding).as_deref() {
+                    Some("duration") => settings.duration = value,
+                    Some("delay") => settings.delay = value,
+                    Some("easing") => settings.easing = value,
+                    Some("iteration-count") => settings.iteration_count = value,
+                    _ => {}
+                }
+            }
+            Some(AnimationInformation {
+                range: animation.text_range(),
+                names_range,
+                properties,
+                settings,
+            })
+        })
+        .collect()
+}
+
+/// Whether properties of type `ty` can have an `animate` block
+pub fn is_animatable(ty: &Type) -> bool {
+    // The types the compiler supports property animations for
+    matches!(
+        ty,
+        Type::Float32
+            | Type::Int32
+            | Type::Color
+            | Type::PhysicalLength
+            | Type::LogicalLength
+            | Type::Brush
+            | Type::Angle
+    )
+}
+
+#[derive(Clone, Copy, Debug)]
 pub enum LayoutKind {
     None,
     HorizontalBox,
@@ -333,11 +463,43 @@ fn binding_value(element: &ElementRc, prop: &str, count: &mut usize) -> Expressi
         }
     }
 
+    /// Where the property that `prop` of `element` is bound to with `<=>` is declared, or else
+    /// set, or else the element of that property
+    fn two_way_binding_peer(element: &ElementRc, prop: &str) -> Option<DeclarationInformation> {
+        let nr = element.borrow().bindings.get(prop)?.borrow().two_way_bindings.first()?.clone();
+        let peer = nr.element();
+        let peer = peer.borrow();
+        let at_node = |node: &SyntaxNode| DeclarationInformation {
+            path: node.source_file.path().to_path_buf(),
+            start_position: node.text_range().start(),
+        };
+
+        if let Some(node) = peer.property_declarations.get(nr.name()).and_then(|d| d.node.as_ref())
+        {
+            return Some(at_node(node));
+        }
+        let span = peer.bindings.get(nr.name()).and_then(|b| b.borrow().span.clone());
+        if let Some(span) = span {
+            if let Some(source_file) = &span.source_file {
+                return Some(DeclarationInformation {
+                    path: source_file.path().to_path_buf(),
+                    start_position: TextSize::new(span.span.offset as u32),
+                });
+            }
+        }
+        peer.debug.first().map(|d| at_node(&d.node))
+    }
+
+    let animations = element.with_element_node(find_animations);
+
     for prop_info in properties.iter_mut() {
+        prop_info.animation =
+            animations.iter().find(|a| a.properties.contains(&prop_info.name)).cloned();
         if let Some(offset) = find_property_binding_offset(element, prop_info.name.as_str()) {
             prop_info.defined_at =
                 element.with_element_node(|node| find_code_block_or_expression(node, offset));
         }
+        prop_info.two_way_binding_peer = two_way_binding_peer(&element.element, &prop_info.name);
         let def_val = binding_value(&element.element, &prop_info.name, &mut 0);
         if !matches!(def_val, Expression::Invalid) {
             prop_info.default_value = Some(def_val);
@@ -406,6 +568,8 @@ pub(super) fn get_properties(
                         default_value: t.default_value.expr(&current_element),
                         group: b.name.clone(),
                         group_priority: depth,
+                        animation: None,
+                        two_way_binding_peer: None,
                     })
                 }));
 
@@ -419,6 +583,8 @@ pub(super) fn get_properties(
                         default_value: Some(Expression::BoolLiteral(false)),
                         group: b.name.clone(),
                         group_priority: depth,
+                        animation: None,
+                        two_way_binding_peer: None,
                     });
 
                     result.extend(get_reserved_properties(
@@ -439,6 +605,8 @@ pub(super) fn get_properties(
                     default_value: Some(Expression::NumberLiteral(1.0, Unit::None)),
                     group: b.name.clone(),
                     group_priority: depth,
+                    animation: None,
+                    two_way_binding_peer: None,
                 });
                 result.push(PropertyInformation {
                     name: "visible".into(),
@@ -449,6 +617,8 @@ pub(super) fn get_properties(
                     default_value: Some(Expression::BoolLiteral(true)),
                     group: b.name.clone(),
                     group_priority: depth,
+                    animation: None,
+                    two_way_binding_peer: None,
                 });
 
                 if b.name == "Image" {
@@ -532,6 +702,8 @@ pub(super) fn get_properties(
             default_value: None,
             group: "accessibility".into(),
             group_priority: depth + 10000,
+            animation: None,
+            two_way_binding_peer: None,
         });
         if current_element.borrow().is_binding_set("accessible-role", true) {
             result.extend(get_reserved_properties(
@@ -582,6 +754,19 @@ pub(crate) fn query_properties(
     })
 }
 
+/// Drop all properties from `response` that are not also available with the same type
+/// on all the `others` elements, so that they can get edited on all of them at once.
+pub(crate) fn retain_common_properties(
+    response: &mut QueryPropertyResponse,
+    others: &[(common::ElementRcNode, LayoutKind)],
+) {
+    let other_properties =
+        others.iter().map(|(e, in_layout)| get_properties(e, *in_layout)).collect::<Vec<_>>();
+    response.properties.retain(|p| {
+        other_properties.iter().all(|op| op.iter().any(|o| o.name == p.name && o.ty == p.ty))
+    });
+}
+
 fn get_property_information(
     properties: &[PropertyInformation],
     property_name: &str,
@@ -787,6 +972,43 @@ fn create_workspace_edit_for_remove_binding(
     common::create_workspace_edit(uri.clone(), version, vec![edit])
 }
 
+/// The range to remove for `node`, including the white-space and comments around it
+/// up to the end of its line
+pub fn removal_range(node: &SyntaxNode) -> Option<TextRange> {
+    let start = {
+        let token = left_extend(node.first_token()?);
+        let start = token.text_range().start();
+        token
+            .prev_token()
+            .and_then(|t| {
+                if t.kind() == SyntaxKind::Whitespace && t.text().contains('\n') {
+                    let to_sub = t.text().split('\n').next_back().unwrap_or_default().len() as u32;
+                    start.checked_sub(to_sub.into())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(start)
+    };
+    let end = {
+        let token = right_extend(util::last_non_ws_token(node)?);
+        let end = token.text_range().end();
+        token
+            .next_token()
+            .and_then(|t| {
+                if t.kind() == SyntaxKind::Whitespace && t.text().contains('\n') {
+                    let to_add = t.text().split('\n').next().unwrap_or_default().len() as u32;
+                    end.checked_add((to_add + 1/* <cr> */).into())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(end)
+    };
+
+    Some(TextRange::new(start, end))
+}
+
 pub fn remove_binding(
     uri: Url,
     version: SourceFileVersion,
@@ -803,46 +1025,11 @@ pub fn remove_binding(
             for ancestor in token.parent_ancestors() {
                 if (ancestor.kind() == SyntaxKind::Binding)
                     || (ancestor.kind() == SyntaxKind::PropertyDeclaration)
+                    || (ancestor.kind() == SyntaxKind::TwoWayBinding
+                        && ancestor.parent().is_some_and(|p| p.kind() == SyntaxKind::Element))
                 {
-                    let start = {
-                        let token = left_extend(ancestor.first_token()?);
-                        let start = token.text_range().start();
-                        token
-                            .prev_token()
-                            .and_then(|t| {
-                                if t.kind() == SyntaxKind::Whitespace && t.text().contains('\n') {
-                                    let to_sub =
-                                        t.text().split('\n').last().unwrap_or_default().len()
-                                            as u32;
-                                    start.checked_sub(to_sub.into())
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or(start)
-                    };
-                    let end = {
-                        let token = right_extend(ancestor.last_token()?);
-                        let end = token.text_range().end();
-                        token
-                            .next_token()
-                            .and_then(|t| {
-                                if t.kind() == SyntaxKind::Whitespace && t.text().contains('\n') {
-                                    let to_add =
-                                        t.text().split('\n').next().unwrap_or_default().len()
-                                            as u32;
-                                    end.checked_add((to_add + 1/* <cr> */).into())
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or(end)
-                    };
-
-                    return Some(util::text_range_to_lsp_range(
-                        &source_file,
-                        TextRange::new(start, end),
-                    ));
+                    return removal_range(&ancestor)
+                        .map(|range| util::text_range_to_lsp_range(&source_file, range));
                 }
                 if ancestor.kind() == SyntaxKind::Element {
                     // There should have been a binding before the element!
@@ -856,6 +1043,93 @@ pub fn remove_binding(
     Ok(create_workspace_edit_for_remove_binding(uri, version, range))
 }
 
+/// Turn the two-way binding of `property_name` of `element` into a binding to the current
+/// value of its peer: `foo <=> bar;` becomes `foo: bar;`
+pub fn break_two_way_binding(
+    uri: Url,
+    version: SourceFileVersion,
+    element: &common::ElementRcNode,
+    property_name: &str,
+) -> Option<lsp_types::WorkspaceEdit> {
+    let properties = get_properties(element, LayoutKind::None);
+    let property = get_property_information(&properties, property_name).ok()?;
+    let range = property.defined_at?.two_way_arrow_range?;
+
+    let source_file = element.with_element_node(|node| node.source_file.clone());
+    let edit = lsp_types::TextEdit {
+        range: util::text_range_to_lsp_range(&source_file, range),
+        new_text: ":".into(),
+    };
+    Some(common::create_workspace_edit(uri, version, vec![edit]))
+}
+
+/// Animate `property_name` of `element` with `settings`: Add, change or remove (with empty
+/// `settings`) the `animate` block of the property. A property sharing its `animate` block
+/// with other properties gets a block of its own.
+pub fn set_animation(
+    uri: Url,
+    version: SourceFileVersion,
+    element: &common::ElementRcNode,
+    property_name: &str,
+    settings: &AnimationSettings,
+) -> Option<lsp_types::WorkspaceEdit> {
+    let properties = get_properties(element, LayoutKind::None);
+    let property = get_property_information(&properties, property_name).ok()?;
+    if !is_animatable(&property.ty) {
+        return None;
+    }
+
+    let source_file = element.with_element_node(|node| node.source_file.clone());
+    let indent = util::find_element_indent(element).unwrap_or_default();
+    let text_edit = |range, new_text| lsp_types::TextEdit {
+        range: util::text_range_to_lsp_range(&source_file, range),
+        new_text,
+    };
+    let block = (!settings.is_empty()).then(|| settings.to_source(&[property_name], &indent));
+
+    let edits = match (&property.animation, block) {
+        (None, None) => return None,
+        (None, Some(block)) => {
+            // Right after the binding of the property, or at the start of the element
+            let offset = match &property.defined_at {
+                Some(defined_at) => defined_at.selection_range.end(),
+                None => find_block_range(element)?.start().checked_add(1.into())?,
+            };
+            vec![text_edit(TextRange::empty(offset), format!("\n{indent}    {block}"))]
+        }
+        (Some(animation), block) if animation.properties.len() == 1 => match block {
+            Some(block) => vec![text_edit(animation.range, block)],
+            None => {
+                let range = element.with_element_node(|node| {
+                    let node =
+                        node.PropertyAnimation().find(|a| a.text_range() == animation.range)?;
+                    removal_range(&node)
+                })?;
+                vec![text_edit(range, String::new())]
+            }
+        },
+        (Some(animation), block) => {
+            let others = animation
+                .properties
+                .iter()
+                .filter(|p| p.as_str() != property_name)
+                .map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut edits = vec![text_edit(animation.names_range, others)];
+            if let Some(block) = block {
+                edits.push(text_edit(
+                    TextRange::empty(animation.range.end()),
+                    format!("\n{indent}    {block}"),
+                ));
+            }
+            edits
+        }
+    };
+
+    Some(common::create_workspace_edit(uri, version, edits))
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -938,6 +1212,28 @@ fn test_get_properties() {
         assert!(find_property(&result, "clicked").is_none());
     }
 
+    #[test]
+    fn test_retain_common_properties() {
+        let (dc, url, _) = complex_document_cache();
+        let (text, _) = properties_at_position_in_cache(15, 14, &dc, &url).unwrap();
+        let (rectangle, _) = properties_at_position_in_cache(17, 16, &dc, &url).unwrap();
+
+        let mut response = query_properties(&url, None, &text, LayoutKind::None).unwrap();
+        assert!(find_property(&response.properties, "text").is_some());
+
+        // Nothing else selected: Nothing changes
+        let count = response.properties.len();
+        retain_common_properties(&mut response, &[]);
+        assert_eq!(response.properties.len(), count);
+
+        retain_common_properties(&mut response, &[(rectangle, LayoutKind::None)]);
+        assert!(response.properties.len() < count);
+        assert!(find_property(&response.properties, "text").is_none());
+        assert!(find_property(&response.properties, "color").is_none());
+        assert_eq!(&find_property(&response.properties, "width").unwrap().ty, &Type::LogicalLength);
+        assert_eq!(&find_property(&response.properties, "x").unwrap().ty, &Type::LogicalLength);
+    }
+
     #[test]
     fn test_element_information() {
         let (document_cache, url, _) = complex_document_cache();
@@ -1804,4 +2100,136 @@ fn test_set_binding_valid() {
         assert_eq!(tc.range.start, lsp_types::Position { line: 17, character: 27 });
         assert_eq!(tc.range.end, lsp_types::Position { line: 17, character: 32 });
     }
+
+    fn animation_edit_result(
+        source: &str,
+        property_name: &str,
+        settings: &AnimationSettings,
+    ) -> String {
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        let offset = source.find("Rectangle {").unwrap() as u32;
+        let element = dc.element_at_offset(&url, offset.into()).unwrap();
+        let edit = set_animation(url, None, &element, property_name, settings).unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&dc, &edit).unwrap();
+        result[0].contents.clone()
+    }
+
+    #[test]
+    fn test_get_animation() {
+        let source = r#"export component Main {
+    Rectangle {
+        x: 10px;
+        animate x, y { duration: 200ms; easing: ease-in; }
+        animate background {
+            duration: 1s;
+            iteration_count: -1;
+        }
+    }
+}"#;
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        let element =
+            dc.element_at_offset(&url, (source.find("Rectangle").unwrap() as u32).into()).unwrap();
+        let properties = get_properties(&element, LayoutKind::None);
+
+        let x = find_property(&properties, "x").unwrap().animation.clone().unwrap();
+        assert_eq!(x.properties, vec![SmolStr::from("x"), SmolStr::from("y")]);
+        assert_eq!(
+            x.settings,
+            AnimationSettings {
+                duration: "200ms".into(),
+                easing: "ease-in".into(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(find_property(&properties, "y").unwrap().animation, Some(x));
+
+        let background = find_property(&properties, "background").unwrap().animation.clone();
+        let background = background.unwrap().settings;
+        assert_eq!(background.duration, "1s");
+        assert_eq!(background.iteration_count, "-1");
+
+        assert!(find_property(&properties, "width").unwrap().animation.is_none());
+        assert!(is_animatable(&find_property(&properties, "width").unwrap().ty));
+        assert!(!is_animatable(&find_property(&properties, "visible").unwrap().ty));
+    }
+
+    #[test]
+    fn test_set_animation() {
+        let settings = AnimationSettings { duration: "300ms".into(), ..Default::default() };
+
+        // Add a new block after the binding
+        let source = "export component Main {\n    Rectangle {\n        x: 10px;\n    }\n}";
+        assert_eq!(
+            animation_edit_result(source, "x", &settings),
+            "export component Main {\n    Rectangle {\n        x: 10px;\n        animate x {\n            duration: 300ms;\n        }\n    }\n}"
+        );
+
+        // Change an existing block
+        let source = "export component Main {\n    Rectangle {\n        animate x { duration: 1s; }\n    }\n}";
+        assert_eq!(
+            animation_edit_result(source, "x", &settings),
+            "export component Main {\n    Rectangle {\n        animate x {\n            duration: 300ms;\n        }\n    }\n}"
+        );
+
+        // Remove it
+        assert_eq!(
+            animation_edit_result(source, "x", &AnimationSettings::default()),
+            "export component Main {\n    Rectangle {\n    }\n}"
+        );
+
+        // Split a shared block
+        let source = "export component Main {\n    Rectangle {\n        animate x, y { duration: 1s; }\n    }\n}";
+        assert_eq!(
+            animation_edit_result(source, "x", &settings),
+            "export component Main {\n    Rectangle {\n        animate y { duration: 1s; }\n        animate x {\n            duration: 300ms;\n        }\n    }\n}"
+        );
+    }
+
+    #[test]
+    fn test_two_way_binding() {
+        let source = r#"export component Main {
+    in-out property <length> size: 10px;
+    in-out property <string> label <=> txt.text;
+    txt := Text {
+        font-size <=> root.size;
+    }
+}"#;
+        let (dc, url, _) = loaded_document_cache(source.to_string());
+        let source_file = dc.get_document(&url).unwrap().node.as_ref().unwrap().source_file.clone();
+        let element =
+            dc.element_at_offset(&url, (source.find("txt :=").unwrap() as u32).into()).unwrap();
+        let properties = get_properties(&element, LayoutKind::None);
+
+        let font_size = find_property(&properties, "font-size").unwrap();
+        let defined_at = font_size.defined_at.as_ref().unwrap();
+        assert_eq!(defined_at.code_block_or_expression.text().to_string(), "root.size");
+        let arrow_range = defined_at.two_way_arrow_range.unwrap();
+        assert_eq!(&source[arrow_range], " <=>");
+        let peer = font_size.two_way_binding_peer.as_ref().unwrap();
+        assert_eq!(peer.path, source_file.path());
+        assert_eq!(util::text_size_to_lsp_position(&source_file, peer.start_position).line, 1);
+
+        let color = find_property(&properties, "color").unwrap();
+        assert!(color.two_way_binding_peer.is_none());
+
+        let edit = break_two_way_binding(url.clone(), None, &element, "font-size").unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&dc, &edit).unwrap();
+        assert!(result[0].contents.contains("        font-size: root.size;\n"));
+        assert!(break_two_way_binding(url.clone(), None, &element, "color").is_none());
+
+        let edit = remove_binding(url.clone(), None, &element, "font-size").unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&dc, &edit).unwrap();
+        assert!(result[0].contents.contains("    txt := Text {\n    }\n"));
+
+        // The two-way binding of a property declaration
+        let offset = source.find("in-out property <string>").unwrap() as u32;
+        let element = dc.element_at_offset(&url, offset.into()).unwrap();
+        let properties = get_properties(&element, LayoutKind::None);
+        let label = find_property(&properties, "label").unwrap();
+        let defined_at = label.defined_at.as_ref().unwrap();
+        assert_eq!(defined_at.code_block_or_expression.text().to_string(), "txt.text");
+        let edit = break_two_way_binding(url, None, &element, "label").unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&dc, &edit).unwrap();
+        assert!(result[0].contents.contains("    in-out property <string> label: txt.text;\n"));
+    }
 }
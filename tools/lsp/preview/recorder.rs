@@ -0,0 +1,377 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Record the pointer and keyboard events the previewed component receives, to replay them
+//! later as JSON event trace or as Rust test.
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use i_slint_core::animations::Instant;
+use i_slint_core::items::{ComponentContainer, ItemRef};
+use i_slint_core::lengths::{LogicalPoint, LogicalSize};
+use slint::platform::{PointerEventButton, WindowEvent};
+use slint::LogicalPosition;
+
+/// An event delivered to the previewed component, in the coordinates of the component
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RecordedEvent {
+    PointerPressed { x: f32, y: f32, button: Button },
+    PointerReleased { x: f32, y: f32, button: Button },
+    PointerMoved { x: f32, y: f32 },
+    PointerScrolled { x: f32, y: f32, delta_x: f32, delta_y: f32 },
+    KeyPressed { text: String },
+    KeyReleased { text: String },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Button {
+    Left,
+    Right,
+    Middle,
+}
+
+impl From<Button> for PointerEventButton {
+    fn from(button: Button) -> Self {
+        match button {
+            Button::Left => PointerEventButton::Left,
+            Button::Right => PointerEventButton::Right,
+            Button::Middle => PointerEventButton::Middle,
+        }
+    }
+}
+
+impl RecordedEvent {
+    /// The event to dispatch, with the positions mapped by `map`
+    pub fn to_window_event(&self, map: impl Fn(f32, f32) -> LogicalPosition) -> WindowEvent {
+        match self {
+            RecordedEvent::PointerPressed { x, y, button } => {
+                WindowEvent::PointerPressed { position: map(*x, *y), button: (*button).into() }
+            }
+            RecordedEvent::PointerReleased { x, y, button } => {
+                WindowEvent::PointerReleased { position: map(*x, *y), button: (*button).into() }
+            }
+            RecordedEvent::PointerMoved { x, y } => {
+                WindowEvent::PointerMoved { position: map(*x, *y) }
+            }
+            RecordedEvent::PointerScrolled { x, y, delta_x, delta_y } => {
+                WindowEvent::PointerScrolled {
+                    position: map(*x, *y),
+                    delta_x: *delta_x,
+                    delta_y: *delta_y,
+                }
+            }
+            RecordedEvent::KeyPressed { text } => {
+                WindowEvent::KeyPressed { text: text.as_str().into() }
+            }
+            RecordedEvent::KeyReleased { text } => {
+                WindowEvent::KeyReleased { text: text.as_str().into() }
+            }
+        }
+    }
+
+    /// The Rust code dispatching the event to `window`
+    fn to_rust(&self) -> String {
+        let position = |x: &f32, y: &f32| format!("LogicalPosition::new({x:?}, {y:?})");
+        let event = match self {
+            RecordedEvent::PointerPressed { x, y, button } => format!(
+                "WindowEvent::PointerPressed {{ position: {}, button: PointerEventButton::{button:?} }}",
+                position(x, y)
+            ),
+            RecordedEvent::PointerReleased { x, y, button } => format!(
+                "WindowEvent::PointerReleased {{ position: {}, button: PointerEventButton::{button:?} }}",
+                position(x, y)
+            ),
+            RecordedEvent::PointerMoved { x, y } => {
+                format!("WindowEvent::PointerMoved {{ position: {} }}", position(x, y))
+            }
+            RecordedEvent::PointerScrolled { x, y, delta_x, delta_y } => format!(
+                "WindowEvent::PointerScrolled {{ position: {}, delta_x: {delta_x:?}, delta_y: {delta_y:?} }}",
+                position(x, y)
+            ),
+            RecordedEvent::KeyPressed { text } => {
+                format!("WindowEvent::KeyPressed {{ text: {text:?}.into() }}")
+            }
+            RecordedEvent::KeyReleased { text } => {
+                format!("WindowEvent::KeyReleased {{ text: {text:?}.into() }}")
+            }
+        };
+        format!("window.dispatch_event({event});")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TimedEvent {
+    /// Milliseconds since the recording started
+    pub time: u64,
+    #[serde(flatten)]
+    pub event: RecordedEvent,
+}
+
+/// A recorded interaction with a component
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Trace {
+    pub component: String,
+    pub file: Option<PathBuf>,
+    /// The size of the component while recording
+    pub width: f32,
+    pub height: f32,
+    pub events: Vec<TimedEvent>,
+}
+
+impl Trace {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// A Rust test loading the component and sending it the recorded events, with the time
+    /// between the events mocked
+    pub fn to_rust_test(&self) -> String {
+        let file = self.file.as_ref().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+        let mut test = format!(
+            r#"// Recorded in the Slint live-preview
+#[test]
+fn recorded_interaction() {{
+    use slint::platform::{{PointerEventButton, WindowEvent}};
+    use slint::LogicalPosition;
+    use std::time::Duration;
+
+    i_slint_backend_testing::init_integration_test_with_mock_time();
+    let compiler = slint_interpreter::Compiler::default();
+    let result = spin_on::spin_on(compiler.build_from_path({file:?}));
+    let instance = result.component({:?}).unwrap().create().unwrap();
+    instance.window().set_size(slint::LogicalSize::new({:?}, {:?}));
+    instance.show().unwrap();
+    let window = instance.window();
+
+"#,
+            self.component, self.width, self.height
+        );
+        let mut time = 0;
+        for event in &self.events {
+            if event.time > time {
+                let _ = writeln!(
+                    test,
+                    "    i_slint_backend_testing::mock_elapsed_time(Duration::from_millis({}));",
+                    event.time - time
+                );
+                time = event.time;
+            }
+            let _ = writeln!(test, "    {}", event.event.to_rust());
+        }
+        test.push_str("\n    // TODO: Check the state of the component\n}\n");
+        test
+    }
+}
+
+/// Send the events of `trace` to `window`, without waiting in between. The positions are
+/// relative to the previewed component if `window` is the preview window.
+pub fn replay(window: &slint::Window, trace: &Trace) {
+    let (origin, zoom) =
+        preview_transform(window).map_or((LogicalPoint::default(), 1.0), |(o, _, z)| (o, z));
+    for event in &trace.events {
+        window.dispatch_event(event.event.to_window_event(|x, y| {
+            LogicalPosition::new(origin.x + x * zoom, origin.y + y * zoom)
+        }));
+    }
+}
+
+struct Recorder {
+    trace: Trace,
+    start: Instant,
+    /// Where the pointer is in the preview window, as pressing buttons does not tell
+    cursor: LogicalPoint,
+}
+
+thread_local! {static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };}
+
+pub fn is_recording() -> bool {
+    RECORDER.with(|recorder| recorder.borrow().is_some())
+}
+
+/// Start recording the interaction with `component`
+pub fn start(component: String, file: Option<PathBuf>, width: f32, height: f32) {
+    RECORDER.with(|recorder| {
+        *recorder.borrow_mut() = Some(Recorder {
+            trace: Trace { component, file, width, height, events: vec![] },
+            start: Instant::now(),
+            cursor: LogicalPoint::default(),
+        })
+    });
+}
+
+/// Stop recording and return what got recorded
+pub fn stop() -> Option<Trace> {
+    RECORDER.with(|recorder| recorder.borrow_mut().take().map(|r| r.trace))
+}
+
+fn record(event: RecordedEvent) {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            let time = Instant::now().duration_since(recorder.start);
+            recorder.trace.events.push(TimedEvent { time: time.as_millis() as u64, event });
+        }
+    })
+}
+
+/// Where the component container showing the preview is in `window`, its size and zoom
+fn preview_transform(window: &slint::Window) -> Option<(LogicalPoint, LogicalSize, f32)> {
+    let mut result = None;
+    super::render_stats::visit_window_items(window, |item| {
+        if result.is_some() {
+            return;
+        }
+        if let Some(container) = ItemRef::downcast_pin::<ComponentContainer>(item.borrow()) {
+            let origin = item.map_to_window(LogicalPoint::default());
+            result = Some((origin, item.geometry().size, container.effective_zoom()));
+        }
+    });
+    result
+}
+
+/// The position in the previewed component of the `position` in the preview window, or
+/// `None` if it is outside of the previewed component
+fn position_in_preview(window: &slint::Window, position: LogicalPoint) -> Option<(f32, f32)> {
+    let (origin, size, zoom) = preview_transform(window)?;
+    let (x, y) = (position.x - origin.x, position.y - origin.y);
+    (x >= 0.0 && y >= 0.0 && x < size.width && y < size.height).then(|| (x / zoom, y / zoom))
+}
+
+/// The pointer moved to `position` in the preview window
+pub fn pointer_moved(window: &slint::Window, position: LogicalPoint) {
+    let Some(previous) = RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        let recorder = recorder.as_mut()?;
+        Some(std::mem::replace(&mut recorder.cursor, position))
+    }) else {
+        return;
+    };
+    if previous == position {
+        return;
+    }
+    if let Some((x, y)) = position_in_preview(window, position) {
+        record(RecordedEvent::PointerMoved { x, y });
+    }
+}
+
+pub fn pointer_button(window: &slint::Window, button: Button, pressed: bool) {
+    let Some(cursor) =
+        RECORDER.with(|recorder| recorder.borrow().as_ref().map(|recorder| recorder.cursor))
+    else {
+        return;
+    };
+    if let Some((x, y)) = position_in_preview(window, cursor) {
+        record(if pressed {
+            RecordedEvent::PointerPressed { x, y, button }
+        } else {
+            RecordedEvent::PointerReleased { x, y, button }
+        });
+    }
+}
+
+pub fn pointer_scrolled(window: &slint::Window, delta_x: f32, delta_y: f32) {
+    let Some(cursor) =
+        RECORDER.with(|recorder| recorder.borrow().as_ref().map(|recorder| recorder.cursor))
+    else {
+        return;
+    };
+    if let Some((x, y)) = position_in_preview(window, cursor) {
+        record(RecordedEvent::PointerScrolled { x, y, delta_x, delta_y });
+    }
+}
+
+/// A key got pressed or released, `text` as in Slint's `KeyEvent`
+pub fn key(text: String, pressed: bool) {
+    record(if pressed {
+        RecordedEvent::KeyPressed { text }
+    } else {
+        RecordedEvent::KeyReleased { text }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use slint::ComponentHandle;
+
+    fn trace() -> Trace {
+        Trace {
+            component: "Main".into(),
+            file: Some("/tmp/main.slint".into()),
+            width: 100.0,
+            height: 50.0,
+            events: vec![
+                TimedEvent {
+                    time: 0,
+                    event: RecordedEvent::PointerPressed { x: 10.0, y: 10.0, button: Button::Left },
+                },
+                TimedEvent {
+                    time: 80,
+                    event: RecordedEvent::PointerReleased {
+                        x: 10.0,
+                        y: 10.0,
+                        button: Button::Left,
+                    },
+                },
+                TimedEvent { time: 200, event: RecordedEvent::KeyPressed { text: "a".into() } },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let trace = trace();
+        let json = trace.to_json();
+        assert!(json.contains("\"type\": \"pointer-pressed\""));
+        assert!(json.contains("\"button\": \"left\""));
+        assert_eq!(serde_json::from_str::<Trace>(&json).unwrap(), trace);
+    }
+
+    #[test]
+    fn test_to_rust_test() {
+        let test = trace().to_rust_test();
+        assert!(test.contains("result.component(\"Main\")"));
+        assert!(test.contains("window.dispatch_event(WindowEvent::PointerPressed { position: LogicalPosition::new(10.0, 10.0), button: PointerEventButton::Left });"));
+        assert!(test.contains("mock_elapsed_time(Duration::from_millis(80));"));
+        assert!(test.contains("mock_elapsed_time(Duration::from_millis(120));"));
+        assert!(test.contains("WindowEvent::KeyPressed { text: \"a\".into() }"));
+    }
+
+    #[test]
+    fn test_replay() {
+        let instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+                export component Main inherits Window {
+                    width: 100px;
+                    height: 50px;
+                    out property <int> clicks;
+                    out property <string> typed;
+                    forward-focus: scope;
+                    scope := FocusScope {
+                        key-pressed(event) => {
+                            root.typed += event.text;
+                            accept
+                        }
+                    }
+                    TouchArea {
+                        clicked => {
+                            root.clicks += 1;
+                        }
+                    }
+                }
+            "#,
+        );
+        instance.show().unwrap();
+        replay(instance.window(), &trace());
+        assert_eq!(instance.get_property("clicks").unwrap(), slint_interpreter::Value::Number(1.0));
+        assert_eq!(
+            instance.get_property("typed").unwrap(),
+            slint_interpreter::Value::String("a".into())
+        );
+    }
+}
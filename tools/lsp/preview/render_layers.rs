@@ -0,0 +1,73 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The items of the preview making the renderer draw their children into a layer of their
+//! own or clip them: Expensive, and not visible in the preview otherwise
+
+use i_slint_core::items::{Clip, ItemRc, Layer, Opacity};
+use i_slint_core::lengths::LogicalRect;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayerKind {
+    /// Children get drawn into a layer that is then blended with the given opacity
+    Opacity(f32),
+    Clip,
+    /// Children get drawn into a layer that is cached across frames
+    Cache,
+}
+
+impl LayerKind {
+    fn of(item: &ItemRc) -> Option<Self> {
+        if let Some(i) = item.downcast::<Opacity>() {
+            let opacity = i.as_pin_ref().opacity();
+            (opacity < 1.0).then_some(LayerKind::Opacity(opacity))
+        } else if let Some(i) = item.downcast::<Clip>() {
+            i.as_pin_ref().clip().then_some(LayerKind::Clip)
+        } else if let Some(i) = item.downcast::<Layer>() {
+            i.as_pin_ref().cache_rendering_hint().then_some(LayerKind::Cache)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            LayerKind::Opacity(opacity) => format!("opacity {}%", (opacity * 100.0).round()),
+            LayerKind::Clip => "clip".into(),
+            LayerKind::Cache => "cached layer".into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderLayer {
+    pub geometry: LogicalRect,
+    pub kind: LayerKind,
+}
+
+/// The layers of the preview shown in `window`, outer layers before the ones nested into them
+pub fn collect(window: &slint::Window) -> Vec<RenderLayer> {
+    let mut layers = vec![];
+    super::render_stats::visit_window_items(window, |item| {
+        let Some(kind) = LayerKind::of(item) else {
+            return;
+        };
+        if let Some(geometry) = super::render_stats::geometry_in_preview(item) {
+            layers.push(RenderLayer { geometry, kind });
+        }
+    });
+    layers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        assert_eq!(LayerKind::Opacity(0.5).label(), "opacity 50%");
+        assert_eq!(LayerKind::Opacity(0.333).label(), "opacity 33%");
+        assert_eq!(LayerKind::Clip.label(), "clip");
+        assert_eq!(LayerKind::Cache.label(), "cached layer");
+    }
+}
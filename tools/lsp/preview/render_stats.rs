@@ -0,0 +1,224 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Sample the time the renderer spends on each item of the previewed component,
+//! so that the preview can tint elements by their rendering cost.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use i_slint_core::items::{
+    ClippedImage, ComplexText, ComponentContainer, ImageItem, ItemRc, ItemRef, Path, SimpleText,
+    TextInput,
+};
+use i_slint_core::lengths::LogicalRect;
+
+/// What the renderer is mostly busy with when drawing an item
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CostKind {
+    TextLayout,
+    PathTessellation,
+    TextureUpload,
+    Other,
+}
+
+impl CostKind {
+    fn of(item: &ItemRc) -> Self {
+        let item = item.borrow();
+        if ItemRef::downcast_pin::<SimpleText>(item).is_some()
+            || ItemRef::downcast_pin::<ComplexText>(item).is_some()
+            || ItemRef::downcast_pin::<TextInput>(item).is_some()
+        {
+            CostKind::TextLayout
+        } else if ItemRef::downcast_pin::<Path>(item).is_some() {
+            CostKind::PathTessellation
+        } else if ItemRef::downcast_pin::<ImageItem>(item).is_some()
+            || ItemRef::downcast_pin::<ClippedImage>(item).is_some()
+        {
+            CostKind::TextureUpload
+        } else {
+            CostKind::Other
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CostKind::TextLayout => "text layout",
+            CostKind::PathTessellation => "path tessellation",
+            CostKind::TextureUpload => "texture upload",
+            CostKind::Other => "drawing",
+        }
+    }
+}
+
+/// The rendering cost of one item in the preview
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemCost {
+    pub geometry: LogicalRect,
+    pub kind: CostKind,
+    /// The average time spent per frame
+    pub average: Duration,
+    /// `average` relative to the most expensive item, between 0 and 1
+    pub relative: f32,
+}
+
+struct ItemSamples {
+    geometry: LogicalRect,
+    kind: CostKind,
+    total: Duration,
+    count: u32,
+}
+
+#[derive(Default)]
+pub struct RenderStats {
+    samples: HashMap<(usize, u32), ItemSamples>,
+}
+
+impl RenderStats {
+    fn record(&mut self, key: (usize, u32), geometry: LogicalRect, kind: CostKind, time: Duration) {
+        let samples = self.samples.entry(key).or_insert(ItemSamples {
+            geometry,
+            kind,
+            total: Duration::ZERO,
+            count: 0,
+        });
+        // The item might have moved since the last frame
+        samples.geometry = geometry;
+        samples.total += time;
+        samples.count += 1;
+    }
+
+    /// Return the costs collected since the last call, the most expensive item first
+    pub fn take_costs(&mut self) -> Vec<ItemCost> {
+        let mut costs = self
+            .samples
+            .drain()
+            .map(|(_, s)| ItemCost {
+                geometry: s.geometry,
+                kind: s.kind,
+                average: s.total / s.count,
+                relative: 0.0,
+            })
+            .collect::<Vec<_>>();
+        costs.sort_by_key(|c| std::cmp::Reverse(c.average));
+
+        let max = costs.first().map(|c| c.average.as_secs_f32()).unwrap_or_default();
+        if max > 0.0 {
+            for c in costs.iter_mut() {
+                c.relative = c.average.as_secs_f32() / max;
+            }
+        }
+        costs
+    }
+}
+
+/// The geometry of `item` relative to the component container showing the preview,
+/// or `None` if the item is not part of the preview
+pub fn geometry_in_preview(item: &ItemRc) -> Option<LogicalRect> {
+    let geometry = item.geometry();
+    let mut origin = geometry.origin;
+    let mut current = item.clone();
+    while let Some(parent) = current.parent_item() {
+        if ItemRef::downcast_pin::<ComponentContainer>(parent.borrow()).is_some() {
+            return Some(LogicalRect::new(origin, geometry.size));
+        }
+        origin += parent.geometry().origin.to_vector();
+        current = parent;
+    }
+    None
+}
+
+fn visit_items(item: &ItemRc, visitor: &mut impl FnMut(&ItemRc)) {
+    visitor(item);
+    let mut child = item.first_child();
+    while let Some(c) = child {
+        visit_items(&c, visitor);
+        child = c.next_sibling();
+    }
+}
+
+/// Call `visitor` for all items in `window` in the order they are drawn in, so that later
+/// items are on top of earlier ones
+pub fn visit_window_items(window: &slint::Window, mut visitor: impl FnMut(&ItemRc)) {
+    if let Some(root) = i_slint_core::window::WindowInner::from_pub(window).window_item_rc() {
+        visit_items(&root, &mut visitor);
+    }
+}
+
+thread_local! {static COLLECTOR: RefCell<Option<Rc<RefCell<RenderStats>>>> = const { RefCell::new(None) };}
+
+/// Install the item render profiler if rendering costs get collected or frames get counted,
+/// or remove it
+pub fn update_profiler() {
+    let collecting = COLLECTOR.with(|collector| collector.borrow().is_some());
+    if !collecting && !super::frame_stats::is_active() {
+        i_slint_core::item_rendering::set_item_render_profiler(None);
+        return;
+    }
+    i_slint_core::item_rendering::set_item_render_profiler(Some(Box::new(
+        move |item: &ItemRc, time| {
+            let Some(geometry) = geometry_in_preview(item) else {
+                return;
+            };
+            super::frame_stats::item_rendered();
+            COLLECTOR.with(|collector| {
+                if let Some(collector) = collector.borrow().as_ref() {
+                    let key = (&**item.item_tree() as *const _ as *const u8 as usize, item.index());
+                    collector.borrow_mut().record(key, geometry, CostKind::of(item), time);
+                }
+            });
+        },
+    )));
+}
+
+/// Start collecting rendering costs of the preview
+pub fn start() -> Rc<RefCell<RenderStats>> {
+    let stats = Rc::new(RefCell::new(RenderStats::default()));
+    COLLECTOR.with(|collector| *collector.borrow_mut() = Some(stats.clone()));
+    update_profiler();
+    stats
+}
+
+pub fn stop() {
+    COLLECTOR.with(|collector| *collector.borrow_mut() = None);
+    update_profiler();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i_slint_core::lengths::LogicalPoint;
+
+    #[test]
+    fn test_take_costs() {
+        let rect = |x: f32| LogicalRect::new(LogicalPoint::new(x, 0.0), Default::default());
+        let ms = Duration::from_millis;
+
+        let mut stats = RenderStats::default();
+        assert!(stats.take_costs().is_empty());
+
+        stats.record((1, 1), rect(1.0), CostKind::Other, ms(1));
+        stats.record((1, 2), rect(2.0), CostKind::TextLayout, ms(4));
+        stats.record((1, 2), rect(3.0), CostKind::TextLayout, ms(8));
+        stats.record((2, 1), rect(4.0), CostKind::PathTessellation, ms(3));
+
+        let costs = stats
+            .take_costs()
+            .into_iter()
+            .map(|c| (c.geometry.origin.x, c.kind, c.average, (c.relative * 100.0).round()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            costs,
+            vec![
+                (3.0, CostKind::TextLayout, ms(6), 100.0),
+                (4.0, CostKind::PathTessellation, ms(3), 50.0),
+                (1.0, CostKind::Other, ms(1), 17.0),
+            ]
+        );
+
+        // Samples are only reported once
+        assert!(stats.take_costs().is_empty());
+    }
+}
@@ -0,0 +1,609 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Render the previewed component into an image file: A PNG drawn by the software renderer,
+//! or an SVG with the rectangles and texts of the component for vector friendly content.
+//! All exported components of a set of files can be rendered in one go, too.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use i_slint_core::graphics::Color;
+use i_slint_core::items::{
+    BasicBorderRectangle, BorderRectangle, ColorScheme, ComplexText, ItemRc, Rectangle, SimpleText,
+    TextHorizontalAlignment,
+};
+use i_slint_core::lengths::LogicalPoint;
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+use slint::platform::WindowAdapter;
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+use slint::ComponentHandle;
+use slint_interpreter::{ComponentDefinition, ComponentInstance};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Svg,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Svg => "svg",
+        }
+    }
+}
+
+/// Create a new instance of `definition` in a window of its own that is `size` logical pixels
+/// large, or as large as the component prefers to be without a size. The public properties of
+/// `current` get copied over so that the screenshot shows the data the preview shows.
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+fn instantiate(
+    definition: &ComponentDefinition,
+    current: Option<&ComponentInstance>,
+    size: Option<slint::LogicalSize>,
+    scale_factor: f32,
+    color_scheme: ColorScheme,
+) -> Result<
+    (std::rc::Rc<slint::platform::software_renderer::MinimalSoftwareWindow>, ComponentInstance),
+    String,
+> {
+    use slint::platform::software_renderer::{MinimalSoftwareWindow, RepaintBufferType};
+
+    let window = MinimalSoftwareWindow::new(RepaintBufferType::NewBuffer);
+    window
+        .window()
+        .dispatch_event(slint::platform::WindowEvent::ScaleFactorChanged { scale_factor });
+    let instance = definition
+        .create_with_existing_window(window.window())
+        .map_err(|e| format!("The component can not be instantiated for the screenshot: {e}"))?;
+    if let Some(current) = current {
+        for (name, _) in definition.properties() {
+            if let Ok(value) = current.get_property(&name) {
+                // Fails for output properties, which follow the others anyway
+                let _ = instance.set_property(&name, value);
+            }
+        }
+    }
+    i_slint_core::window::WindowInner::from_pub(window.window())
+        .set_color_scheme_override(color_scheme);
+    window.set_size(size.unwrap_or_else(|| preferred_size(window.window())));
+    instance.show().map_err(|e| e.to_string())?;
+    Ok((window, instance))
+}
+
+/// The size the component shown in `window` is laid out in by default
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+fn preferred_size(window: &slint::Window) -> slint::LogicalSize {
+    use i_slint_core::item_tree::ItemTreeRc;
+    use i_slint_core::layout::Orientation;
+
+    let component = i_slint_core::window::WindowInner::from_pub(window).component();
+    let component = ItemTreeRc::borrow_pin(&component);
+    let h = component.as_ref().layout_info(Orientation::Horizontal);
+    let v = component.as_ref().layout_info(Orientation::Vertical);
+    slint::LogicalSize::new(
+        h.preferred_bounded().max(1.0),
+        v.preferred_bounded().max(1.0),
+    )
+}
+
+/// Render the component as `format`, `width` x `height` logical pixels large
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+pub fn render(
+    definition: &ComponentDefinition,
+    current: Option<&ComponentInstance>,
+    width: f32,
+    height: f32,
+    scale_factor: f32,
+    format: Format,
+) -> Result<Vec<u8>, String> {
+    let size = slint::LogicalSize::new(width, height);
+    let (window, _instance) =
+        instantiate(definition, current, Some(size), scale_factor, ColorScheme::Unknown)?;
+    match format {
+        Format::Png => encode_png(&window),
+        Format::Svg => Ok(svg(window.window()).into_bytes()),
+    }
+}
+
+/// Render the component into an image, to show it next to the preview
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+pub fn render_image(
+    definition: &ComponentDefinition,
+    current: Option<&ComponentInstance>,
+    width: f32,
+    height: f32,
+    scale_factor: f32,
+) -> Result<slint::Image, String> {
+    let size = slint::LogicalSize::new(width, height);
+    let (window, _instance) =
+        instantiate(definition, current, Some(size), scale_factor, ColorScheme::Unknown)?;
+    Ok(slint::Image::from_rgba8(render_pixels(&window)))
+}
+
+/// Render the component in its preferred size into an image, scaled down to be at most
+/// `max_size` logical pixels wide and high
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+pub fn render_thumbnail(
+    definition: &ComponentDefinition,
+    max_size: f32,
+) -> Result<slint::Image, String> {
+    let (window, _instance) = instantiate(definition, None, None, 1.0, ColorScheme::Unknown)?;
+    let size = window.window().size().to_logical(1.0);
+    if size.width <= 0.0 || size.height <= 0.0 {
+        return Err("The component has no size".into());
+    }
+    let scale_factor = (max_size / size.width.max(size.height)).min(1.0);
+    if scale_factor < 1.0 {
+        window
+            .window()
+            .dispatch_event(slint::platform::WindowEvent::ScaleFactorChanged { scale_factor });
+        window.set_size(size);
+    }
+    Ok(slint::Image::from_rgba8(render_pixels(&window)))
+}
+
+#[cfg(not(all(feature = "renderer-software", not(target_arch = "wasm32"))))]
+pub fn render_thumbnail(
+    _definition: &ComponentDefinition,
+    _max_size: f32,
+) -> Result<slint::Image, String> {
+    Err("Rendering thumbnails needs the software renderer".into())
+}
+
+#[cfg(not(all(feature = "renderer-software", not(target_arch = "wasm32"))))]
+pub fn render_image(
+    _definition: &ComponentDefinition,
+    _current: Option<&ComponentInstance>,
+    _width: f32,
+    _height: f32,
+    _scale_factor: f32,
+) -> Result<slint::Image, String> {
+    Err("Rendering the preview into an image needs the software renderer".into())
+}
+
+#[cfg(not(all(feature = "renderer-software", not(target_arch = "wasm32"))))]
+pub fn render(
+    _definition: &ComponentDefinition,
+    _current: Option<&ComponentInstance>,
+    _width: f32,
+    _height: f32,
+    _scale_factor: f32,
+    _format: Format,
+) -> Result<Vec<u8>, String> {
+    Err("Screenshots need the software renderer".into())
+}
+
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+fn render_pixels(
+    window: &slint::platform::software_renderer::MinimalSoftwareWindow,
+) -> slint::SharedPixelBuffer<slint::Rgba8Pixel> {
+    use slint::platform::software_renderer::PremultipliedRgbaColor;
+
+    let size = window.window().size();
+    let mut buffer =
+        vec![PremultipliedRgbaColor::default(); size.width as usize * size.height as usize];
+    window.request_redraw();
+    window.draw_if_needed(|renderer| {
+        renderer.render(&mut buffer, size.width as usize);
+    });
+
+    let mut pixels = slint::SharedPixelBuffer::<slint::Rgba8Pixel>::new(size.width, size.height);
+    for (pixel, p) in pixels.make_mut_slice().iter_mut().zip(buffer) {
+        let demultiply = |c: u8| match p.alpha {
+            0 => 0,
+            a => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+        };
+        *pixel = slint::Rgba8Pixel::new(
+            demultiply(p.red),
+            demultiply(p.green),
+            demultiply(p.blue),
+            p.alpha,
+        );
+    }
+    pixels
+}
+
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+fn encode_png(
+    window: &slint::platform::software_renderer::MinimalSoftwareWindow,
+) -> Result<Vec<u8>, String> {
+    let pixels = render_pixels(window);
+    let image =
+        image::RgbaImage::from_raw(pixels.width(), pixels.height(), pixels.as_bytes().to_vec())
+            .ok_or_else(|| "The screenshot has no pixels".to_string())?;
+    let mut png = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut png, image::ImageFormat::Png).map_err(|e| e.to_string())?;
+    Ok(png.into_inner())
+}
+
+/// The variants every exported component gets rendered in by [`export_all`]
+#[derive(Clone, Debug)]
+pub struct BatchSettings {
+    /// In logical pixels. Components get rendered in their preferred size if this is empty.
+    pub sizes: Vec<(u32, u32)>,
+    pub styles: Vec<String>,
+    pub color_schemes: Vec<ColorScheme>,
+    pub scale_factor: f32,
+    pub include_paths: Vec<PathBuf>,
+    pub library_paths: HashMap<String, PathBuf>,
+}
+
+/// What [`export_all`] did
+#[derive(Clone, Debug, Default)]
+pub struct BatchReport {
+    pub written: Vec<PathBuf>,
+    /// Files that did not compile and components that could not be rendered
+    pub errors: Vec<String>,
+}
+
+fn color_scheme_name(color_scheme: ColorScheme) -> &'static str {
+    match color_scheme {
+        ColorScheme::Dark => "dark",
+        ColorScheme::Light => "light",
+        _ => "default",
+    }
+}
+
+/// The name of the PNG file for a component rendered in a style, color scheme and size
+fn batch_file_name(
+    component: &str,
+    style: &str,
+    color_scheme: ColorScheme,
+    size: slint::LogicalSize,
+) -> String {
+    format!(
+        "{component}-{style}-{}-{}x{}.png",
+        color_scheme_name(color_scheme),
+        size.width.round(),
+        size.height.round()
+    )
+}
+
+/// Render all exported components of `files` into PNG files in `output_dir`, once for every
+/// combination of style, color scheme and size in `settings`
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+pub fn export_all(files: &[PathBuf], settings: &BatchSettings, output_dir: &Path) -> BatchReport {
+    let mut report = BatchReport::default();
+    if let Err(e) = std::fs::create_dir_all(output_dir) {
+        report.errors.push(format!("{}: {e}", output_dir.display()));
+        return report;
+    }
+    let sizes = if settings.sizes.is_empty() {
+        vec![None]
+    } else {
+        settings
+            .sizes
+            .iter()
+            .map(|(w, h)| Some(slint::LogicalSize::new(*w as f32, *h as f32)))
+            .collect()
+    };
+
+    for style in &settings.styles {
+        let mut compiler = slint_interpreter::Compiler::default();
+        compiler.set_style(style.clone());
+        compiler.set_include_paths(settings.include_paths.clone());
+        compiler.set_library_paths(settings.library_paths.clone());
+
+        for file in files {
+            // Files are read synchronously, so the compilation is done after the first poll
+            let Some(result) = super::poll_once(compiler.build_from_path(file)) else {
+                report.errors.push(format!("{}: Loading the file did not finish", file.display()));
+                continue;
+            };
+            if result.has_errors() {
+                let errors = result
+                    .diagnostics()
+                    .filter(|d| d.level() == slint_interpreter::DiagnosticLevel::Error)
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>();
+                report.errors.push(format!("{}: {}", file.display(), errors.join("\n")));
+                continue;
+            }
+
+            for definition in result.components() {
+                for color_scheme in &settings.color_schemes {
+                    for size in &sizes {
+                        let rendered = instantiate(
+                            &definition,
+                            None,
+                            *size,
+                            settings.scale_factor,
+                            *color_scheme,
+                        )
+                        .and_then(|(window, _instance)| {
+                            let size = window.window().size().to_logical(settings.scale_factor);
+                            Ok((size, encode_png(&window)?))
+                        });
+                        let written = rendered.and_then(|(size, png)| {
+                            let path = output_dir.join(batch_file_name(
+                                definition.name(),
+                                style,
+                                *color_scheme,
+                                size,
+                            ));
+                            std::fs::write(&path, png).map_err(|e| e.to_string())?;
+                            Ok(path)
+                        });
+                        match written {
+                            Ok(path) => report.written.push(path),
+                            Err(e) => report.errors.push(format!("{}: {e}", definition.name())),
+                        }
+                    }
+                }
+            }
+        }
+    }
+    report
+}
+
+#[cfg(not(all(feature = "renderer-software", not(target_arch = "wasm32"))))]
+pub fn export_all(
+    _files: &[PathBuf],
+    _settings: &BatchSettings,
+    _output_dir: &Path,
+) -> BatchReport {
+    BatchReport {
+        errors: vec!["Screenshots need the software renderer".into()],
+        ..Default::default()
+    }
+}
+
+/// Renders with the software renderer only, so that screenshots can be taken without a display
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+struct HeadlessPlatform;
+
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+impl slint::platform::Platform for HeadlessPlatform {
+    fn create_window_adapter(
+        &self,
+    ) -> Result<std::rc::Rc<dyn slint::platform::WindowAdapter>, slint::PlatformError> {
+        Ok(slint::platform::software_renderer::MinimalSoftwareWindow::new(
+            slint::platform::software_renderer::RepaintBufferType::NewBuffer,
+        ))
+    }
+}
+
+/// Collect the `.slint` files in `paths`, looking into directories recursively
+pub fn collect_slint_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            let Ok(entries) = std::fs::read_dir(path) else {
+                continue;
+            };
+            let mut children = entries.filter_map(|e| Some(e.ok()?.path())).collect::<Vec<_>>();
+            children.sort();
+            files.extend(collect_slint_files(&children));
+        } else if path.extension().is_some_and(|e| e == "slint") {
+            files.push(path.clone());
+        }
+    }
+    files
+}
+
+/// Export all components from the command line, without showing any window
+#[cfg(all(feature = "renderer-software", not(target_arch = "wasm32")))]
+pub fn run_batch_export(
+    paths: &[PathBuf],
+    settings: &BatchSettings,
+    output_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    slint::platform::set_platform(Box::new(HeadlessPlatform)).map_err(|e| e.to_string())?;
+    let report = export_all(&collect_slint_files(paths), settings, output_dir);
+    if report.errors.is_empty() {
+        Ok(report.written)
+    } else {
+        Err(report.errors.join("\n"))
+    }
+}
+
+#[cfg(not(all(feature = "renderer-software", not(target_arch = "wasm32"))))]
+pub fn run_batch_export(
+    _paths: &[PathBuf],
+    _settings: &BatchSettings,
+    _output_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    Err("Screenshots need the software renderer".into())
+}
+
+/// The color as SVG paint attribute value, or `None` for transparent colors
+fn svg_paint(color: Color) -> Option<String> {
+    match color.alpha() {
+        0 => None,
+        255 => Some(format!("#{:02x}{:02x}{:02x}", color.red(), color.green(), color.blue())),
+        a => Some(format!(
+            "#{:02x}{:02x}{:02x}\" fill-opacity=\"{:.3}",
+            color.red(),
+            color.green(),
+            color.blue(),
+            a as f32 / 255.0
+        )),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn write_rect(
+    svg: &mut String,
+    origin: LogicalPoint,
+    item: &ItemRc,
+    background: Color,
+    border: Option<(f32, Color, f32)>,
+) {
+    let size = item.geometry().size;
+    let fill = svg_paint(background);
+    let stroke = border.and_then(|(width, color, _)| {
+        svg_paint(color).filter(|_| width > 0.0).map(|c| (width, c.replace("fill-", "stroke-")))
+    });
+    if fill.is_none() && stroke.is_none() {
+        return;
+    }
+    let radius = border.map_or(0.0, |(_, _, radius)| radius);
+    let _ = write!(
+        svg,
+        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{radius}\" fill=\"{}\"",
+        origin.x,
+        origin.y,
+        size.width,
+        size.height,
+        fill.as_deref().unwrap_or("none")
+    );
+    if let Some((width, stroke)) = stroke {
+        let _ = write!(svg, " stroke=\"{stroke}\" stroke-width=\"{width}\"");
+    }
+    svg.push_str("/>\n");
+}
+
+fn write_text(
+    svg: &mut String,
+    origin: LogicalPoint,
+    item: &ItemRc,
+    text: &str,
+    color: Color,
+    font_size: f32,
+    alignment: TextHorizontalAlignment,
+) {
+    let Some(fill) = svg_paint(color).filter(|_| !text.is_empty()) else {
+        return;
+    };
+    let size = item.geometry().size;
+    let font_size = if font_size > 0.0 { font_size } else { 12.0 };
+    let (x, anchor) = match alignment {
+        TextHorizontalAlignment::Center => (origin.x + size.width / 2.0, "middle"),
+        TextHorizontalAlignment::Right => (origin.x + size.width, "end"),
+        _ => (origin.x, "start"),
+    };
+    let _ = writeln!(
+        svg,
+        "  <text x=\"{x}\" y=\"{}\" font-size=\"{font_size}\" text-anchor=\"{anchor}\" dominant-baseline=\"middle\" fill=\"{fill}\">{}</text>",
+        origin.y + size.height / 2.0,
+        escape(text)
+    );
+}
+
+/// The rectangles and texts of the component shown in `window` as SVG document. Other
+/// elements, like images and paths, are left out.
+pub fn svg(window: &slint::Window) -> String {
+    let size = window.size().to_logical(window.scale_factor());
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        size.width, size.height
+    );
+    super::render_stats::visit_window_items(window, |item| {
+        if !item.is_visible() {
+            return;
+        }
+        let origin = item.map_to_window(item.geometry().origin);
+        if let Some(i) = item.downcast::<Rectangle>() {
+            write_rect(&mut svg, origin, item, i.as_pin_ref().background().color(), None);
+        } else if let Some(i) = item.downcast::<BasicBorderRectangle>() {
+            let i = i.as_pin_ref();
+            let border =
+                (i.border_width().get(), i.border_color().color(), i.border_radius().get());
+            write_rect(&mut svg, origin, item, i.background().color(), Some(border));
+        } else if let Some(i) = item.downcast::<BorderRectangle>() {
+            let i = i.as_pin_ref();
+            let border =
+                (i.border_width().get(), i.border_color().color(), i.border_radius().get());
+            write_rect(&mut svg, origin, item, i.background().color(), Some(border));
+        } else if let Some(i) = item.downcast::<SimpleText>() {
+            let i = i.as_pin_ref();
+            write_text(
+                &mut svg,
+                origin,
+                item,
+                &i.text(),
+                i.color().color(),
+                i.font_size().get(),
+                i.horizontal_alignment(),
+            );
+        } else if let Some(i) = item.downcast::<ComplexText>() {
+            let i = i.as_pin_ref();
+            write_text(
+                &mut svg,
+                origin,
+                item,
+                &i.text(),
+                i.color().color(),
+                i.font_size().get(),
+                i.horizontal_alignment(),
+            );
+        }
+    });
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_slint_files() {
+        let dir =
+            std::env::temp_dir().join(format!("slint-lsp-screenshots-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        for file in ["b.slint", "a.slint", "readme.md", "sub/c.slint"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        let files = collect_slint_files(std::slice::from_ref(&dir));
+        assert_eq!(files, vec![dir.join("a.slint"), dir.join("b.slint"), dir.join("sub/c.slint")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_batch_file_name() {
+        assert_eq!(
+            batch_file_name(
+                "Button",
+                "fluent",
+                ColorScheme::Dark,
+                slint::LogicalSize::new(120.4, 32.0)
+            ),
+            "Button-fluent-dark-120x32.png"
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let component_instance = crate::preview::test::interpret_test(
+            "fluent",
+            r#"
+                export component Main inherits Window {
+                    width: 100px;
+                    height: 50px;
+                    Rectangle {
+                        x: 10px;
+                        y: 10px;
+                        width: 20px;
+                        height: 20px;
+                        background: #ff0000;
+                        border-radius: 4px;
+                        Text {
+                            text: "a < b";
+                            color: #0000ff80;
+                            font-size: 10px;
+                            horizontal-alignment: center;
+                        }
+                    }
+                }
+            "#,
+        );
+        // The preview sizes its window, the test window has the default size
+        component_instance.window().set_size(slint::LogicalSize::new(100., 50.));
+        let svg = svg(component_instance.window());
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\""));
+        assert!(svg.contains(
+            "<rect x=\"10\" y=\"10\" width=\"20\" height=\"20\" rx=\"4\" fill=\"#ff0000\"/>"
+        ));
+        assert!(svg.contains("fill=\"#0000ff\" fill-opacity=\"0.502\">a &lt; b</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
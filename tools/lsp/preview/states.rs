@@ -0,0 +1,301 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! The `states` of the previewed component: Listed, edited, and forced active in the
+//! preview without having to trigger their conditions
+
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, SyntaxNode, TextRange, TextSize};
+
+use crate::util;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateOverride {
+    /// The (qualified) name of the property changed by the state
+    pub property: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateInformation {
+    pub name: String,
+    /// The `id` of the element the state is declared on, empty for the root element
+    pub element: String,
+    /// The `when` condition, empty if there is none
+    pub condition: String,
+    pub overrides: Vec<StateOverride>,
+    /// Where the state starts, identifying it
+    pub offset: TextSize,
+}
+
+/// The component named `component` in the `document`, or the last one if there is no name
+pub fn component(
+    document: &syntax_nodes::Document,
+    component: Option<&str>,
+) -> Option<syntax_nodes::Component> {
+    let identifier = match component {
+        Some(name) => super::find_component_identifiers(document, name).last().cloned(),
+        None => super::find_last_component_identifier(document),
+    }?;
+    syntax_nodes::Component::new(identifier.parent()?)
+}
+
+fn element_id(states: &syntax_nodes::States) -> String {
+    states
+        .parent()
+        .and_then(|element| element.parent())
+        .filter(|p| p.kind() == SyntaxKind::SubElement)
+        .and_then(|p| p.child_text(SyntaxKind::Identifier))
+        .map(|id| id.to_string())
+        .unwrap_or_default()
+}
+
+fn all_states(component: &syntax_nodes::Component) -> impl Iterator<Item = syntax_nodes::State> {
+    component.descendants().filter_map(syntax_nodes::State::new)
+}
+
+fn value_node(change: &syntax_nodes::StatePropertyChange) -> Option<SyntaxNode> {
+    change.BindingExpression().children().next()
+}
+
+/// The states declared on any element of `component`, in source order
+pub fn states(component: &syntax_nodes::Component) -> Vec<StateInformation> {
+    all_states(component)
+        .map(|state| {
+            let element = state.parent().and_then(syntax_nodes::States::new);
+            StateInformation {
+                name: state.DeclaredIdentifier().text().to_string().trim().to_string(),
+                element: element.map(|s| element_id(&s)).unwrap_or_default(),
+                condition: state
+                    .Expression()
+                    .map(|e| e.text().to_string().trim().to_string())
+                    .unwrap_or_default(),
+                overrides: state
+                    .StatePropertyChange()
+                    .map(|change| StateOverride {
+                        property: change.QualifiedName().text().to_string().trim().to_string(),
+                        value: value_node(&change)
+                            .map(|v| v.text().to_string().trim().to_string())
+                            .unwrap_or_default(),
+                    })
+                    .collect(),
+                offset: state.text_range().start(),
+            }
+        })
+        .collect()
+}
+
+/// The state starting at `offset` in `component`
+pub fn find_state(
+    component: &syntax_nodes::Component,
+    offset: TextSize,
+) -> Option<syntax_nodes::State> {
+    all_states(component).find(|s| s.text_range().start() == offset)
+}
+
+/// The range from the end of the state name up to the `:`, covering a `when` condition
+fn condition_range(state: &syntax_nodes::State) -> Option<TextRange> {
+    let colon = state.child_token(SyntaxKind::Colon)?;
+    Some(TextRange::new(state.DeclaredIdentifier().text_range().end(), colon.text_range().start()))
+}
+
+/// `text` in place of `replaced`, padded with white-space to keep the length of the
+/// source the same if `text` is shorter
+pub fn replacement(text: &str, replaced: &str) -> String {
+    let padding = replaced.bytes().skip(text.len()).map(|b| if b == b'\n' { '\n' } else { ' ' });
+    text.chars().chain(padding).collect()
+}
+
+/// The source of the `document` with the conditions of its states changed so that the
+/// state at `offset` becomes active: Its condition is `true`, and the conditions of the
+/// states declared before it, which take precedence, are `false`.
+///
+/// Conditions get replaced without changing the length of the source where possible, so
+/// that the positions of the elements stay where they are in the editor.
+pub fn force_state(document: &SyntaxNode, offset: TextSize) -> Option<String> {
+    let state = document
+        .descendants()
+        .filter_map(syntax_nodes::State::new)
+        .find(|s| s.text_range().start() == offset)?;
+    let states = syntax_nodes::States::new(state.parent()?)?;
+
+    let mut source = document.text().to_string();
+    let mut edits = vec![];
+    for s in states.State() {
+        let is_forced = s.text_range() == state.text_range();
+        let range = condition_range(&s)?;
+        let condition = if is_forced { " when true " } else { " when false " };
+        if s.Expression().is_some() || is_forced {
+            edits.push((range, replacement(condition, &source[range])));
+        }
+        if is_forced {
+            break;
+        }
+    }
+
+    for (range, text) in edits.into_iter().rev() {
+        source.replace_range(std::ops::Range::<usize>::from(range), &text);
+    }
+    Some(source)
+}
+
+/// Set the `when` condition of the `state`, removing it if `condition` is empty
+pub fn condition_edit(state: &syntax_nodes::State, condition: &str) -> Option<lsp_types::TextEdit> {
+    let range = condition_range(state)?;
+    let condition = condition.trim();
+    let new_text =
+        if condition.is_empty() { " ".to_string() } else { format!(" when {condition} ") };
+    Some(lsp_types::TextEdit {
+        range: util::text_range_to_lsp_range(&state.source_file, range),
+        new_text,
+    })
+}
+
+/// Set the value `property` gets in the `state`, removing the override if `value` is empty
+pub fn override_edit(
+    state: &syntax_nodes::State,
+    property: &str,
+    value: &str,
+) -> Option<lsp_types::TextEdit> {
+    let property = property.trim();
+    let value = value.trim();
+    let text_edit = |range, new_text| {
+        Some(lsp_types::TextEdit {
+            range: util::text_range_to_lsp_range(&state.source_file, range),
+            new_text,
+        })
+    };
+
+    let existing = state
+        .StatePropertyChange()
+        .find(|c| c.QualifiedName().text().to_string().trim() == property);
+    match (existing, value.is_empty()) {
+        (None, true) => None,
+        (Some(change), true) => {
+            text_edit(super::properties::removal_range(&change)?, String::new())
+        }
+        (Some(change), false) => text_edit(value_node(&change)?.text_range(), value.to_string()),
+        (None, false) => {
+            if property.is_empty() {
+                return None;
+            }
+            // After the last override, or at the start of the block
+            let offset = match state.StatePropertyChange().last() {
+                Some(last) => util::last_non_ws_token(&last)?.text_range().end(),
+                None => state.child_token(SyntaxKind::LBrace)?.text_range().end(),
+            };
+            let source = state.source_file.source()?;
+            let line_start =
+                source[..usize::from(state.text_range().start())].rfind('\n').map_or(0, |p| p + 1);
+            let indent = source[line_start..]
+                .chars()
+                .take_while(|c| c.is_whitespace() && *c != '\n')
+                .collect::<String>();
+            text_edit(TextRange::empty(offset), format!("\n{indent}    {property}: {value};"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"export component Main {
+    in property <bool> pressed;
+    in property <bool> hovered;
+    states [
+        down when pressed && hovered: {
+            background: red;
+            label.text: "Down";
+        }
+        over when hovered : { background: blue; }
+        idle : { }
+    ]
+    label := Text {
+        states [
+            hidden when !root.visible : { opacity: 0; }
+        ]
+    }
+}
+"#;
+
+    fn parse(source: &str) -> syntax_nodes::Document {
+        let mut diag = i_slint_compiler::diagnostics::BuildDiagnostics::default();
+        let node = i_slint_compiler::parser::parse(source.to_string(), None, &mut diag);
+        syntax_nodes::Document::new(node).unwrap()
+    }
+
+    fn offset_of(source: &str, text: &str) -> TextSize {
+        TextSize::from(source.find(text).unwrap() as u32)
+    }
+
+    fn apply(source: &str, edit: lsp_types::TextEdit) -> String {
+        let document = parse(source);
+        let range = util::lsp_range_to_text_range(&document.source_file, edit.range);
+        let mut source = source.to_string();
+        source.replace_range(std::ops::Range::<usize>::from(range), &edit.new_text);
+        source
+    }
+
+    #[test]
+    fn test_states() {
+        let document = parse(SOURCE);
+        let states = states(&component(&document, None).unwrap());
+
+        assert_eq!(
+            states.iter().map(|s| (&*s.name, &*s.element, &*s.condition)).collect::<Vec<_>>(),
+            vec![
+                ("down", "", "pressed && hovered"),
+                ("over", "", "hovered"),
+                ("idle", "", ""),
+                ("hidden", "label", "!root.visible"),
+            ]
+        );
+        assert_eq!(
+            states[0].overrides,
+            vec![
+                StateOverride { property: "background".into(), value: "red".into() },
+                StateOverride { property: "label.text".into(), value: "\"Down\"".into() },
+            ]
+        );
+        assert_eq!(states[1].offset, offset_of(SOURCE, "over when"));
+    }
+
+    #[test]
+    fn test_force_state() {
+        let document = parse(SOURCE);
+
+        let forced = force_state(&document, offset_of(SOURCE, "over when")).unwrap();
+        assert_eq!(forced.len(), SOURCE.len());
+        assert!(forced.contains(&format!("down when false{}: {{", " ".repeat(13))));
+        assert!(forced.contains(&format!("over when true{}: {{", " ".repeat(4))));
+        assert!(forced.contains("idle : { }"));
+
+        // Without a condition to replace, the length of the source changes
+        let forced = force_state(&document, offset_of(SOURCE, "idle")).unwrap();
+        assert!(forced.contains(&format!("over when false{}: {{", " ".repeat(3))));
+        assert!(forced.contains("idle when true : { }"));
+
+        assert!(force_state(&document, offset_of(SOURCE, "background")).is_none());
+    }
+
+    #[test]
+    fn test_edits() {
+        let document = parse(SOURCE);
+        let component = component(&document, Some("Main")).unwrap();
+        let state = |name: &str| find_state(&component, offset_of(SOURCE, name)).unwrap();
+
+        let edited = apply(SOURCE, condition_edit(&state("idle"), "!hovered").unwrap());
+        assert!(edited.contains("idle when !hovered : { }"));
+        let edited = apply(SOURCE, condition_edit(&state("over when"), "").unwrap());
+        assert!(edited.contains("over : { background: blue; }"));
+
+        let edited =
+            apply(SOURCE, override_edit(&state("down when"), "background", "green").unwrap());
+        assert!(edited.contains("            background: green;\n"));
+        let edited = apply(SOURCE, override_edit(&state("down when"), "label.text", "").unwrap());
+        assert!(edited.contains("background: red;\n        }"));
+        let edited = apply(SOURCE, override_edit(&state("idle"), "opacity", "0.5").unwrap());
+        assert!(edited.contains("idle : {\n            opacity: 0.5; }"));
+        assert!(override_edit(&state("idle"), "opacity", "").is_none());
+    }
+}
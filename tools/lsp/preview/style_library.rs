@@ -0,0 +1,242 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Styles that are not built into Slint: A directory with a `std-widgets.slint` file in one of
+//! the include paths, or a library path pointing to such a directory. Editors only report
+//! changes to the files of the workspace, so the files of the style in use get watched here and
+//! the preview gets rebuilt when they change.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use lsp_types::Url;
+
+use crate::common;
+
+/// How often the files of the style in use are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomStyle {
+    pub name: String,
+    pub directory: PathBuf,
+}
+
+fn is_style_directory(directory: &Path) -> bool {
+    directory.join("std-widgets.slint").is_file()
+}
+
+fn custom_style(directory: PathBuf) -> Option<CustomStyle> {
+    let name = directory.file_name()?.to_str()?.to_string();
+    Some(CustomStyle { name, directory })
+}
+
+/// The custom styles found in the `include_paths` and `library_paths`. Styles named like a
+/// builtin one are left out, as the compiler picks the builtin one.
+pub fn custom_styles(
+    include_paths: &[PathBuf],
+    library_paths: &HashMap<String, PathBuf>,
+) -> Vec<CustomStyle> {
+    let mut directories = vec![];
+    for include_path in include_paths {
+        let Ok(entries) = std::fs::read_dir(include_path) else {
+            continue;
+        };
+        let mut styles = entries
+            .filter_map(|e| Some(e.ok()?.path()))
+            .filter(|p| is_style_directory(p))
+            .collect::<Vec<_>>();
+        styles.sort();
+        directories.extend(styles);
+    }
+    let mut libraries = library_paths.iter().collect::<Vec<_>>();
+    libraries.sort();
+    directories
+        .extend(libraries.into_iter().map(|(_, p)| p.clone()).filter(|p| is_style_directory(p)));
+
+    let mut names = i_slint_compiler::fileaccess::styles()
+        .into_iter()
+        .chain(std::iter::once("native"))
+        .map(String::from)
+        .collect::<HashSet<_>>();
+    directories
+        .into_iter()
+        .filter_map(custom_style)
+        .filter(|style| names.insert(style.name.clone()))
+        .collect()
+}
+
+/// The include path the compiler needs to find the `style` in, for styles from library paths
+pub fn include_path(style: &str, library_paths: &HashMap<String, PathBuf>) -> Option<PathBuf> {
+    library_paths
+        .values()
+        .filter(|p| is_style_directory(p))
+        .find(|p| p.file_name().and_then(|n| n.to_str()) == Some(style))
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+}
+
+#[derive(Default)]
+struct Watcher {
+    directory: Option<PathBuf>,
+    /// The `.slint` files of the style, with the time they got modified
+    files: HashMap<PathBuf, SystemTime>,
+    timer: Option<slint::Timer>,
+}
+
+thread_local! {static WATCHER: RefCell<Watcher> = RefCell::default();}
+
+fn slint_files(directory: &Path) -> HashMap<PathBuf, SystemTime> {
+    super::screenshot::collect_slint_files(&[directory.to_path_buf()])
+        .into_iter()
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// The files that got added or modified, and the files that got removed from `old` to `new`
+fn changes(
+    old: &HashMap<PathBuf, SystemTime>,
+    new: &HashMap<PathBuf, SystemTime>,
+) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let mut changed = new
+        .iter()
+        .filter(|(p, t)| old.get(*p) != Some(*t))
+        .map(|(p, _)| p.clone())
+        .collect::<Vec<_>>();
+    let mut removed = old.keys().filter(|p| !new.contains_key(*p)).cloned().collect::<Vec<_>>();
+    changed.sort();
+    removed.sort();
+    (changed, removed)
+}
+
+/// The source of the file on disk, or `None` while it is open in the editor, which knows the
+/// more recent one
+fn read_from_disk(path: &Path) -> Option<(Url, String)> {
+    let url = Url::from_file_path(path).ok()?;
+    let cache = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    if cache.source_code.get(&url).is_some_and(|entry| entry.version.is_some()) {
+        return None;
+    }
+    drop(cache);
+    Some((url, std::fs::read_to_string(path).ok()?))
+}
+
+/// Watch the files of the `style`, or stop watching with `None`. The files get loaded, so that
+/// the preview can be compiled with the style.
+pub fn watch(style: Option<&CustomStyle>) {
+    let directory = style.map(|s| s.directory.clone());
+    let files = WATCHER.with(|w| {
+        let mut w = w.borrow_mut();
+        if w.directory == directory {
+            return vec![];
+        }
+        w.files = directory.as_deref().map(slint_files).unwrap_or_default();
+        w.timer = directory.is_some().then(|| {
+            let timer = slint::Timer::default();
+            timer.start(slint::TimerMode::Repeated, POLL_INTERVAL, poll);
+            timer
+        });
+        w.directory = directory;
+        w.files.keys().cloned().collect()
+    });
+
+    let mut cache = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    for path in files {
+        let Ok(url) = Url::from_file_path(&path) else {
+            continue;
+        };
+        if cache.source_code.contains_key(&url) {
+            continue;
+        }
+        if let Ok(code) = std::fs::read_to_string(&path) {
+            cache.source_code.insert(url, super::SourceCodeCacheEntry { version: None, code });
+        }
+    }
+}
+
+fn poll() {
+    let (changed, removed) = WATCHER.with(|w| {
+        let mut w = w.borrow_mut();
+        let Some(directory) = w.directory.clone() else {
+            return Default::default();
+        };
+        let files = slint_files(&directory);
+        let changes = changes(&w.files, &files);
+        w.files = files;
+        changes
+    });
+
+    // Reloads the preview when it uses the files
+    for path in changed {
+        if let Some((url, code)) = read_from_disk(&path) {
+            super::set_contents(&common::VersionedUrl::new(url, None), code);
+        }
+    }
+    for path in removed {
+        if let Ok(url) = Url::from_file_path(&path) {
+            super::delete_document(&url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_styles() {
+        let dir =
+            std::env::temp_dir().join(format!("slint-lsp-style-library-{}", std::process::id()));
+        for style in ["include/Ocean", "include/fluent", "include/empty", "libs/Forest"] {
+            std::fs::create_dir_all(dir.join(style)).unwrap();
+        }
+        for file in ["include/Ocean", "include/fluent", "libs/Forest"] {
+            std::fs::write(dir.join(file).join("std-widgets.slint"), "").unwrap();
+        }
+        let library_paths = HashMap::from([
+            ("forest".to_string(), dir.join("libs/Forest")),
+            ("empty".to_string(), dir.join("include/empty")),
+        ]);
+
+        let styles = custom_styles(&[dir.join("include")], &library_paths);
+        assert_eq!(
+            styles,
+            [
+                CustomStyle { name: "Ocean".into(), directory: dir.join("include/Ocean") },
+                CustomStyle { name: "Forest".into(), directory: dir.join("libs/Forest") },
+            ]
+        );
+        assert_eq!(include_path("Forest", &library_paths), Some(dir.join("libs")));
+        assert_eq!(include_path("Ocean", &library_paths), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_changes() {
+        let time = |s: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(s);
+        let old = HashMap::from([
+            (PathBuf::from("a.slint"), time(1)),
+            (PathBuf::from("b.slint"), time(1)),
+            (PathBuf::from("c.slint"), time(1)),
+        ]);
+        let new = HashMap::from([
+            (PathBuf::from("a.slint"), time(1)),
+            (PathBuf::from("b.slint"), time(2)),
+            (PathBuf::from("d.slint"), time(2)),
+        ]);
+
+        assert_eq!(
+            changes(&old, &new),
+            (
+                vec![PathBuf::from("b.slint"), PathBuf::from("d.slint")],
+                vec![PathBuf::from("c.slint")]
+            )
+        );
+        assert_eq!(changes(&new, &new), (vec![], vec![]));
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Suggesting a text color that is readable on a background, for all texts on it
+
+use i_slint_compiler::object_tree::ElementRc;
+use lsp_types::Url;
+
+use crate::common;
+use crate::language::completion;
+
+use super::color_audit::{self, PaletteEntry};
+use super::properties;
+
+/// The contrast ratio the WCAG asks for normal text (level AA)
+pub const MINIMUM_CONTRAST: f32 = 4.5;
+
+const BLACK: u32 = 0xff000000;
+const WHITE: u32 = 0xffffffff;
+
+/// The relative luminance of the color `0xAARRGGBB`, as defined by the WCAG
+pub fn relative_luminance(argb: u32) -> f32 {
+    let [_, r, g, b] = argb.to_be_bytes();
+    let linear = |c: u8| {
+        let c = c as f32 / 255.;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// The contrast ratio between two colors, from 1 to 21
+pub fn contrast_ratio(a: u32, b: u32) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    /// The code to set the text color to
+    pub expression: String,
+    pub argb: u32,
+    pub contrast: f32,
+}
+
+/// The text color for the `background`: The opaque palette entry with the highest contrast if
+/// that is enough, black or white otherwise
+pub fn suggest(background: u32, palette: &[PaletteEntry]) -> Suggestion {
+    let palette_suggestion = palette
+        .iter()
+        .filter(|e| e.argb >> 24 == 0xff)
+        .map(|e| Suggestion {
+            expression: e.expression(),
+            argb: e.argb,
+            contrast: contrast_ratio(background, e.argb),
+        })
+        .filter(|s| s.contrast >= MINIMUM_CONTRAST)
+        .max_by(|a, b| a.contrast.total_cmp(&b.contrast));
+
+    palette_suggestion.unwrap_or_else(|| {
+        [BLACK, WHITE]
+            .into_iter()
+            .map(|argb| Suggestion {
+                expression: color_audit::color_text(argb),
+                argb,
+                contrast: contrast_ratio(background, argb),
+            })
+            .max_by(|a, b| a.contrast.total_cmp(&b.contrast))
+            .unwrap()
+    })
+}
+
+fn collect_text_elements(element: &ElementRc, result: &mut Vec<common::ElementRcNode>) {
+    for child in &element.borrow().children {
+        if child.borrow().builtin_type().is_some_and(|b| b.name == "Text") {
+            result.extend(common::ElementRcNode::new(child.clone(), 0));
+        }
+        collect_text_elements(child, result);
+    }
+}
+
+/// The `Text` elements inside of `element`
+pub fn text_elements(element: &common::ElementRcNode) -> Vec<common::ElementRcNode> {
+    let mut result = vec![];
+    collect_text_elements(&element.element, &mut result);
+    result
+}
+
+/// Set the color of all `Text` elements inside of `element` to the `suggestion`, importing
+/// the palette global if needed
+pub fn apply(
+    document_cache: &common::DocumentCache,
+    url: &Url,
+    element: &common::ElementRcNode,
+    suggestion: &Suggestion,
+    palette: &[PaletteEntry],
+) -> Option<lsp_types::WorkspaceEdit> {
+    let version = document_cache.document_version(url);
+    let mut edits = text_elements(element)
+        .iter()
+        .filter_map(|text| {
+            properties::set_binding_impl(
+                url.clone(),
+                version,
+                text,
+                "color",
+                suggestion.expression.clone(),
+            )
+        })
+        .flat_map(|edit| edit.edits)
+        .filter_map(|edit| match edit {
+            lsp_types::OneOf::Left(edit) => Some(edit),
+            lsp_types::OneOf::Right(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if edits.is_empty() {
+        return None;
+    }
+
+    if let Some(entry) = palette.iter().find(|e| e.expression() == suggestion.expression) {
+        if &entry.url != url {
+            let document = document_cache.get_document(url)?;
+            let import_file = url.make_relative(&entry.url);
+            edits.extend(completion::create_import_edit(document, &entry.global, &import_file));
+        }
+    }
+
+    Some(common::create_workspace_edit_from_text_document_edits(vec![
+        common::create_text_document_edit(url.clone(), version, edits),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio(BLACK, WHITE) - 21.).abs() < 0.01);
+        assert!((contrast_ratio(WHITE, WHITE) - 1.).abs() < 0.01);
+        assert!((contrast_ratio(0xff777777, WHITE) - 4.48).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_suggest_and_apply() {
+        let source = r#"
+export global Theme {
+    out property <color> ink: #202020;
+    out property <color> paper: #fafafa;
+}
+
+export component Main {
+    panel := Rectangle {
+        background: #101080;
+        Text { text: "a"; }
+        HorizontalLayout {
+            Text { text: "b"; color: red; }
+        }
+        Image { }
+    }
+    Text { text: "outside"; }
+}
+"#;
+        let (document_cache, url, _) = loaded_document_cache(source.to_string());
+        let audit = color_audit::audit(&document_cache, Some(url.clone()));
+
+        let dark = suggest(0xff101080, &audit.palette);
+        assert_eq!(dark.expression, "Theme.paper");
+        assert!(dark.contrast >= MINIMUM_CONTRAST);
+        let light = suggest(0xffffffff, &audit.palette);
+        assert_eq!(light.expression, "Theme.ink");
+        let middle = suggest(0xff808080, &[]);
+        assert_eq!(middle.expression, "#000000");
+
+        let offset = source.find("Rectangle").unwrap() as u32;
+        let panel = document_cache.element_at_offset(&url, offset.into()).unwrap();
+        assert_eq!(text_elements(&panel).len(), 2);
+
+        let edit = apply(&document_cache, &url, &panel, &dark, &audit.palette).unwrap();
+        let result = crate::common::text_edit::apply_workspace_edit(&document_cache, &edit)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+            .contents;
+        assert!(
+            result.contains("Text { color: Theme.paper;\n            text: \"a\"; }"),
+            "{result}"
+        );
+        assert!(result.contains(r#"Text { text: "b"; color: Theme.paper; }"#), "{result}");
+        assert!(result.contains(r#"Text { text: "outside"; }"#), "{result}");
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Named text style presets, kept in a generated [`GLOBAL_NAME`] global: A preset called
+//! `heading` is made of the `heading-font-family`, `heading-font-size`, `heading-font-weight`
+//! and `heading-letter-spacing` properties. Text elements using the preset bind to those, so
+//! they all follow when the preset changes.
+
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, TextRange};
+use lsp_types::Url;
+
+use crate::common;
+use crate::language::completion;
+use crate::util;
+
+/// The name of the global holding the presets
+pub const GLOBAL_NAME: &str = "TextStyles";
+
+/// The properties of a Text element a preset sets, with their type
+pub const PROPERTIES: [(&str, &str); 4] = [
+    ("font-family", "string"),
+    ("font-size", "length"),
+    ("font-weight", "int"),
+    ("letter-spacing", "length"),
+];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextStyle {
+    pub name: String,
+    /// The expressions of the [`PROPERTIES`], empty where the preset does not set one
+    pub values: [String; 4],
+}
+
+impl TextStyle {
+    fn property_name(&self, index: usize) -> String {
+        format!("{}-{}", self.name, PROPERTIES[index].0)
+    }
+}
+
+/// The presets of the project
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextStyles {
+    /// The document declaring the global, if there is one
+    pub url: Option<Url>,
+    /// Sorted by name
+    pub styles: Vec<TextStyle>,
+}
+
+/// Whether `name` can be used as the name of a preset
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn find_global(document_cache: &common::DocumentCache) -> Option<(Url, syntax_nodes::Component)> {
+    document_cache.all_url_documents().filter(|(url, _)| url.scheme() != "builtin").find_map(
+        |(url, document)| {
+            let component = document.ExportsList().filter_map(|e| e.Component()).find(|c| {
+                c.child_text(SyntaxKind::Identifier).as_deref() == Some("global")
+                    && c.DeclaredIdentifier().text().to_string().trim() == GLOBAL_NAME
+            })?;
+            Some((url, component))
+        },
+    )
+}
+
+/// The declarations of the global that belong to a preset, with the preset name and the
+/// index into [`PROPERTIES`]
+fn preset_declarations(
+    global: &syntax_nodes::Component,
+) -> Vec<(String, usize, syntax_nodes::PropertyDeclaration)> {
+    global
+        .Element()
+        .PropertyDeclaration()
+        .filter_map(|declaration| {
+            let name = declaration.DeclaredIdentifier().text().to_string().trim().replace('_', "-");
+            let (index, preset) = PROPERTIES.iter().enumerate().find_map(|(index, (p, _))| {
+                let preset = name.strip_suffix(p)?.strip_suffix('-')?;
+                (!preset.is_empty()).then(|| (index, preset.to_string()))
+            })?;
+            Some((preset, index, declaration))
+        })
+        .collect()
+}
+
+/// Collect the presets declared in the documents of the cache
+pub fn text_styles(document_cache: &common::DocumentCache) -> TextStyles {
+    let Some((url, global)) = find_global(document_cache) else {
+        return TextStyles::default();
+    };
+    let mut styles: Vec<TextStyle> = vec![];
+    for (name, index, declaration) in preset_declarations(&global) {
+        let value = declaration
+            .BindingExpression()
+            .map(|e| e.text().to_string().trim().trim_end_matches(';').trim().to_string())
+            .unwrap_or_default();
+        match styles.iter_mut().find(|s| s.name == name) {
+            Some(style) => style.values[index] = value,
+            None => {
+                let mut style = TextStyle { name, ..Default::default() };
+                style.values[index] = value;
+                styles.push(style);
+            }
+        }
+    }
+    styles.sort_by(|a, b| a.name.cmp(&b.name));
+    TextStyles { url: Some(url), styles }
+}
+
+fn declaration_text(style: &TextStyle, index: usize) -> String {
+    format!(
+        "out property <{}> {}: {};",
+        PROPERTIES[index].1,
+        style.property_name(index),
+        style.values[index]
+    )
+}
+
+/// Create `style` or change the existing preset of that name. The global gets added to
+/// `document` if there is none yet.
+pub fn set_style(
+    document_cache: &common::DocumentCache,
+    document: &Url,
+    style: &TextStyle,
+) -> Option<lsp_types::WorkspaceEdit> {
+    if !is_valid_name(&style.name) {
+        return None;
+    }
+    let set = (0..PROPERTIES.len()).filter(|i| !style.values[*i].is_empty());
+
+    let (url, edits) = match find_global(document_cache) {
+        Some((url, global)) => {
+            let source_file = global.source_file.clone();
+            let declarations = preset_declarations(&global);
+            let element = global.Element();
+            let close_brace = element.child_token(SyntaxKind::RBrace)?;
+            let end = util::text_range_to_lsp_range(
+                &source_file,
+                TextRange::empty(close_brace.text_range().start()),
+            );
+
+            let edits = set
+                .map(|index| {
+                    let declaration = declarations
+                        .iter()
+                        .find(|(n, i, _)| n == &style.name && *i == index)
+                        .map(|(_, _, declaration)| declaration);
+                    match declaration {
+                        // Keep how the property is declared, just change the value
+                        Some(declaration) => {
+                            match declaration.BindingExpression().and_then(|b| b.Expression()) {
+                                Some(expression) => lsp_types::TextEdit {
+                                    range: util::node_to_lsp_range(&expression),
+                                    new_text: style.values[index].clone(),
+                                },
+                                None => lsp_types::TextEdit {
+                                    range: util::node_to_lsp_range(declaration),
+                                    new_text: declaration_text(style, index),
+                                },
+                            }
+                        }
+                        None => lsp_types::TextEdit {
+                            range: end,
+                            new_text: format!("    {}\n", declaration_text(style, index)),
+                        },
+                    }
+                })
+                .collect::<Vec<_>>();
+            (url, edits)
+        }
+        None => {
+            let doc = document_cache.get_document(document)?.node.as_ref()?;
+            let end = util::text_range_to_lsp_range(
+                &doc.source_file,
+                TextRange::empty(doc.text_range().end()),
+            );
+            let declarations = set
+                .map(|index| format!("    {}\n", declaration_text(style, index)))
+                .collect::<String>();
+            let new_text = format!("\nexport global {GLOBAL_NAME} {{\n{declarations}}}\n");
+            (document.clone(), vec![lsp_types::TextEdit { range: end, new_text }])
+        }
+    };
+    if edits.is_empty() {
+        return None;
+    }
+
+    let version = document_cache.document_version(&url);
+    Some(common::create_workspace_edit_from_text_document_edits(vec![
+        common::create_text_document_edit(url, version, edits),
+    ]))
+}
+
+/// Bind the font properties of `elements` to those of `style`, importing the global where needed
+pub fn apply_style(
+    document_cache: &common::DocumentCache,
+    styles: &TextStyles,
+    style: &TextStyle,
+    elements: &[common::ElementRcNode],
+) -> Option<common::WorkspaceEditBuilder> {
+    let styles_url = styles.url.as_ref()?;
+    let changes = (0..PROPERTIES.len())
+        .filter(|i| !style.values[*i].is_empty())
+        .map(|index| {
+            common::PropertyChange::new(
+                PROPERTIES[index].0,
+                format!("{GLOBAL_NAME}.{}", style.property_name(index)),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut builder = common::WorkspaceEditBuilder::new("Apply text style");
+    let mut imported = vec![];
+    for element in elements {
+        let url = Url::from_file_path(element.path_and_offset().0).ok()?;
+        let version = document_cache.document_version(&url);
+        builder.add_workspace_edit(super::properties::set_bindings(
+            url.clone(),
+            version,
+            element,
+            &changes,
+        )?);
+
+        if &url != styles_url && !imported.contains(&url) {
+            let document = document_cache.get_document(&url)?;
+            let import_file = url.make_relative(styles_url);
+            if let Some(import) =
+                completion::create_import_edit(document, GLOBAL_NAME, &import_file)
+            {
+                builder.add_text_edit(url.clone(), version, import);
+            }
+            imported.push(url);
+        }
+    }
+    Some(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::language::test::loaded_document_cache;
+
+    const SOURCE: &str = r#"
+export global TextStyles {
+    out property <string> heading-font-family: "Inter";
+    out property <length> heading-font-size: 24px;
+    out property <int> heading-font-weight: 700;
+    out property <length> body-font-size: 14px;
+    out property <color> heading-color: red;
+}
+
+export component Main {
+    Text { text: "Hello"; }
+}
+"#;
+
+    fn apply(dc: &common::DocumentCache, edit: &lsp_types::WorkspaceEdit) -> String {
+        let result = crate::common::text_edit::apply_workspace_edit(dc, edit).unwrap();
+        result.first().unwrap().contents.clone()
+    }
+
+    #[test]
+    fn test_text_styles() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let styles = text_styles(&dc);
+        assert_eq!(styles.url, Some(url));
+        assert_eq!(
+            styles.styles,
+            vec![
+                TextStyle {
+                    name: "body".into(),
+                    values: [String::new(), "14px".into(), String::new(), String::new()],
+                },
+                TextStyle {
+                    name: "heading".into(),
+                    values: ["\"Inter\"".into(), "24px".into(), "700".into(), String::new()],
+                },
+            ]
+        );
+        assert!(is_valid_name("caption-2"));
+        assert!(!is_valid_name("2caption"));
+        assert!(!is_valid_name("big title"));
+    }
+
+    #[test]
+    fn test_set_style() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let mut style = text_styles(&dc).styles.pop().unwrap();
+        style.values[1] = "32px".into();
+        style.values[3] = "1px".into();
+
+        let result = apply(&dc, &set_style(&dc, &url, &style).unwrap());
+        assert!(result.contains("out property <length> heading-font-size: 32px;"));
+        assert!(result.contains("    out property <length> heading-letter-spacing: 1px;\n}"));
+        assert!(!result.contains("24px"));
+
+        let (dc, url, _) = loaded_document_cache("export component Main { Text { } }".to_string());
+        let style = TextStyle {
+            name: "caption".into(),
+            values: [String::new(), "10px".into(), String::new(), String::new()],
+        };
+        let result = apply(&dc, &set_style(&dc, &url, &style).unwrap());
+        assert!(result.ends_with(
+            "\nexport global TextStyles {\n    out property <length> caption-font-size: 10px;\n}\n"
+        ));
+        assert!(set_style(&dc, &url, &TextStyle { name: "a b".into(), ..style }).is_none());
+    }
+
+    #[test]
+    fn test_apply_style() {
+        let (dc, url, _) = loaded_document_cache(SOURCE.to_string());
+        let styles = text_styles(&dc);
+        let offset = SOURCE.find("Text {").unwrap() as u32;
+        let element = dc.element_at_offset(&url, offset.into()).unwrap();
+
+        let (_, edit) =
+            apply_style(&dc, &styles, &styles.styles[1], &[element]).unwrap().build().unwrap();
+        let result = apply(&dc, &edit);
+        assert!(result.contains("font-family: TextStyles.heading-font-family;"));
+        assert!(result.contains("font-size: TextStyles.heading-font-size;"));
+        assert!(result.contains("font-weight: TextStyles.heading-font-weight;"));
+        assert!(!result.contains("letter-spacing: TextStyles"));
+    }
+}
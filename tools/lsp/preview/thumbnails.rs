@@ -0,0 +1,226 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Thumbnails of the user defined components for the component library. They get rendered
+//! off-screen by the software renderer, one component per iteration of the event loop so that
+//! the preview stays responsive, and are kept until a file the component is compiled from or
+//! the style changes.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use lsp_types::Url;
+use slint_interpreter::ComponentDefinition;
+
+use crate::common::{self, ComponentInformation};
+
+/// How large thumbnails get at most, in logical pixels
+const THUMBNAIL_SIZE: f32 = 64.0;
+
+/// The file a component is defined in, and its name
+type Key = (Url, String);
+
+struct Thumbnail {
+    /// `None` when the component can not be rendered
+    image: Option<slint::Image>,
+    style: String,
+    /// The files the component got compiled from, with a hash of their contents at the time
+    sources: Vec<(Url, u64)>,
+}
+
+#[derive(Default)]
+struct Thumbnails {
+    cache: HashMap<Key, Thumbnail>,
+    /// The components waiting for their thumbnail
+    queue: Vec<Key>,
+}
+
+thread_local! {static THUMBNAILS: RefCell<Thumbnails> = RefCell::default();}
+thread_local! {static LOADED_SOURCES: RefCell<Vec<(Url, u64)>> = RefCell::default();}
+
+fn content_hash(code: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    code.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cached_source(url: &Url) -> (common::SourceFileVersion, String) {
+    let cache = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+    cache.source_code.get(url).map(|r| (r.version, r.code.clone())).unwrap_or_default()
+}
+
+/// The source of the file at `url` as it is in the editor. Notes that the thumbnail being
+/// rendered depends on it, and registers it as a dependency of the preview like
+/// `get_url_from_cache` does, so that the LSP side loads it when it is not in the cache yet.
+fn source(url: &Url) -> (common::SourceFileVersion, String) {
+    let source = {
+        let mut cache = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap();
+        cache.dependencies.insert(url.to_owned());
+        cache.source_code.get(url).map(|r| (r.version, r.code.clone())).unwrap_or_default()
+    };
+    LOADED_SOURCES.with(|s| s.borrow_mut().push((url.clone(), content_hash(&source.1))));
+    source
+}
+
+/// Loads the files imported by the components from the cache of the preview
+fn file_loader(
+    path: String,
+) -> core::pin::Pin<
+    Box<
+        dyn core::future::Future<
+            Output = Option<std::io::Result<(common::SourceFileVersion, String)>>,
+        >,
+    >,
+> {
+    Box::pin(async move {
+        let url = Url::from_file_path(PathBuf::from(&path)).map_err(|()| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "Failed to convert path to URL")
+        });
+        Some(url.map(|url| source(&url)))
+    })
+}
+
+/// Whether one of the `sources` changed since the thumbnail got rendered, `current` being the
+/// hash of the current content of a file
+fn is_outdated(sources: &[(Url, u64)], current: impl Fn(&Url) -> u64) -> bool {
+    sources.iter().any(|(url, hash)| current(url) != *hash)
+}
+
+/// Thumbnails are made for the components defined in the files of the project
+fn key(component: &ComponentInformation) -> Option<Key> {
+    if component.is_global || component.is_builtin || component.is_std_widget {
+        return None;
+    }
+    let url = component.defined_at.as_ref()?.url();
+    if url.scheme() != "file" {
+        return None;
+    }
+    Some((url.clone(), component.name.clone()))
+}
+
+/// The thumbnail of the `component`, which may show it before the latest changes. Empty while
+/// there is none.
+pub fn thumbnail(component: &ComponentInformation) -> slint::Image {
+    key(component)
+        .and_then(|key| THUMBNAILS.with(|t| t.borrow().cache.get(&key)?.image.clone()))
+        .unwrap_or_default()
+}
+
+/// Render the thumbnails of the `components` that have none or an outdated one. The component
+/// library gets updated once they are all done.
+pub fn update(components: &[ComponentInformation]) {
+    if !cfg!(all(feature = "renderer-software", not(target_arch = "wasm32"))) {
+        return;
+    }
+    let style = super::get_current_style();
+    let current = |url: &Url| content_hash(&cached_source(url).1);
+
+    let start = THUMBNAILS.with(|t| {
+        let mut t = t.borrow_mut();
+        let keys = components.iter().filter_map(key).collect::<HashSet<_>>();
+        t.cache.retain(|key, _| keys.contains(key));
+        let queue = keys
+            .into_iter()
+            .filter(|key| {
+                t.cache.get(key).is_none_or(|thumbnail| {
+                    thumbnail.style != style || is_outdated(&thumbnail.sources, current)
+                })
+            })
+            .collect::<Vec<_>>();
+        let was_idle = t.queue.is_empty();
+        t.queue = queue;
+        was_idle && !t.queue.is_empty()
+    });
+    if start {
+        slint::Timer::single_shot(Duration::ZERO, render_next);
+    }
+}
+
+fn compile(url: &Url, name: &str, style: String) -> Result<ComponentDefinition, String> {
+    let path = url.to_file_path().map_err(|()| format!("{url} is not a local file"))?;
+    let config = super::CONTENT_CACHE.get_or_init(Default::default).lock().unwrap().config.clone();
+    let (version, source_code) = source(url);
+    // The files are taken from the cache, so the compilation is done after the first poll
+    let (_, compiled, _, _) = super::poll_once(super::parse_source(
+        config.include_paths,
+        config.library_paths,
+        path,
+        version,
+        source_code,
+        style,
+        Some(name.to_string()),
+        file_loader,
+    ))
+    .ok_or_else(|| format!("Compiling {name} did not finish"))?;
+    compiled.ok_or_else(|| format!("{name} does not compile"))
+}
+
+fn render((url, name): &Key) -> Thumbnail {
+    let style = super::get_current_style();
+    LOADED_SOURCES.with(|s| s.borrow_mut().clear());
+    let image = compile(url, name, style.clone())
+        .and_then(|definition| super::screenshot::render_thumbnail(&definition, THUMBNAIL_SIZE));
+    Thumbnail { image: image.ok(), style, sources: LOADED_SOURCES.with(|s| s.take()) }
+}
+
+fn render_next() {
+    let Some(key) = THUMBNAILS.with(|t| t.borrow_mut().queue.pop()) else {
+        return;
+    };
+    let thumbnail = render(&key);
+    let done = THUMBNAILS.with(|t| {
+        let mut t = t.borrow_mut();
+        t.cache.insert(key, thumbnail);
+        t.queue.is_empty()
+    });
+    if done {
+        super::update_known_components_ui();
+    } else {
+        slint::Timer::single_shot(Duration::ZERO, render_next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_outdated() {
+        let main = Url::parse("file:///main.slint").unwrap();
+        let button = Url::parse("file:///button.slint").unwrap();
+        let sources = [(main.clone(), content_hash("a")), (button, content_hash("b"))];
+        let contents = |main_code: &'static str, button_code: &'static str| {
+            let main = main.clone();
+            move |url: &Url| content_hash(if *url == main { main_code } else { button_code })
+        };
+
+        assert!(!is_outdated(&sources, contents("a", "b")));
+        assert!(is_outdated(&sources, contents("changed", "b")));
+        assert!(is_outdated(&sources, contents("a", "changed")));
+        assert!(!is_outdated(&[], contents("changed", "changed")));
+    }
+
+    #[test]
+    fn test_key() {
+        let component = |name: &str, url: Option<&str>| ComponentInformation {
+            is_builtin: false,
+            defined_at: url.map(|url| common::Position::new(Url::parse(url).unwrap(), 0.into())),
+            ..common::test::test_component(name, "Basics")
+        };
+
+        assert_eq!(
+            key(&component("Card", Some("file:///ui/card.slint"))),
+            Some((Url::parse("file:///ui/card.slint").unwrap(), "Card".into()))
+        );
+        assert_eq!(key(&component("Rectangle", None)), None);
+        assert_eq!(key(&component("Button", Some("builtin:/fluent/button.slint"))), None);
+        let global = ComponentInformation {
+            is_global: true,
+            ..component("Theme", Some("file:///ui/theme.slint"))
+        };
+        assert_eq!(key(&global), None);
+    }
+}
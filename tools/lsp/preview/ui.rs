@@ -4,16 +4,19 @@
 use std::path::PathBuf;
 use std::{collections::HashMap, iter::once, rc::Rc};
 
-use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, TextRange};
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, TextRange, TextSize};
 use i_slint_compiler::{expression_tree, langtype, literals};
 use itertools::Itertools;
 use lsp_types::Url;
-use slint::{Model, SharedString, VecModel};
+use slint::{ComponentHandle, Model, SharedString, VecModel};
 use slint_interpreter::{DiagnosticLevel, PlatformError};
 use smol_str::SmolStr;
 
 use crate::common::{self, ComponentInformation};
-use crate::preview::{self, preview_data, properties, SelectionNotification};
+use crate::preview::{
+    self, easing, edit_review, fonts, image_assets, json_schema, model_fixtures, panel_layout,
+    preview_data, properties, SelectionNotification,
+};
 
 #[cfg(target_arch = "wasm32")]
 use crate::wasm_prelude::*;
@@ -22,16 +25,40 @@
 
 pub type PropertyDeclarations = HashMap<SmolStr, PropertyDeclaration>;
 
-pub fn create_ui(style: String, experimental: bool) -> Result<PreviewUi, PlatformError> {
-    let ui = PreviewUi::new()?;
-
-    // styles:
-    let known_styles = once(&"native")
+/// The styles built into Slint that the preview can use
+fn builtin_styles() -> Vec<&'static str> {
+    once(&"native")
         .chain(i_slint_compiler::fileaccess::styles().iter())
         .filter(|s| s != &&"qt" || i_slint_backend_selector::HAS_NATIVE_STYLE)
         .cloned()
         .sorted()
+        .collect()
+}
+
+/// Offer the `custom_styles` next to the builtin ones
+pub fn ui_set_custom_styles(ui: &PreviewUi, custom_styles: &[super::style_library::CustomStyle]) {
+    let styles = builtin_styles()
+        .into_iter()
+        .map(SharedString::from)
+        .chain(custom_styles.iter().map(|s| SharedString::from(s.name.as_str())))
         .collect::<Vec<_>>();
+    let api = ui.global::<Api>();
+    if api.get_known_styles().iter().eq(styles.iter().cloned()) {
+        return;
+    }
+    api.set_known_styles(Rc::new(VecModel::from(styles)).into());
+}
+
+pub fn create_ui(style: String, experimental: bool) -> Result<PreviewUi, PlatformError> {
+    let ui = PreviewUi::new()?;
+    super::console::install(super::update_console);
+    super::output_search::install(super::update_output_search);
+    super::watch::install(super::update_watch_expressions);
+    super::breakpoints::install(super::update_breakpoints);
+    super::connection::install(super::update_connection_health);
+
+    // styles:
+    let known_styles = builtin_styles();
     let style = if known_styles.contains(&style.as_str()) {
         style
     } else {
@@ -68,9 +95,12 @@ pub fn create_ui(style: String, experimental: bool) -> Result<PreviewUi, Platfor
     api.on_show_document_offset_range(super::show_document_offset_range);
     api.on_show_preview_for(super::show_preview_for);
     api.on_reload_preview(super::reload_preview);
+    api.on_reload_settings_changed(super::reload_settings_changed);
     api.on_unselect(super::element_selection::unselect_element);
     api.on_reselect(super::element_selection::reselect_element);
     api.on_select_at(super::element_selection::select_element_at);
+    api.on_toggle_select_at(super::element_selection::toggle_select_element_at);
+    api.on_select_in_rect(super::element_selection::select_elements_in_rect);
     api.on_selection_stack_at(super::element_selection::selection_stack_at);
     api.on_filter_sort_selection_stack(super::element_selection::filter_sort_selection_stack);
     api.on_find_selected_selection_stack_frame(|stack| {
@@ -85,21 +115,268 @@ pub fn create_ui(style: String, experimental: bool) -> Result<PreviewUi, Platfor
         );
     });
     api.on_select_behind(super::element_selection::select_element_behind);
+    api.on_select_parent(super::element_selection::select_parent_element);
+    api.on_select_first_child(super::element_selection::select_first_child_element);
+    api.on_select_sibling(super::element_selection::select_sibling_element);
     api.on_can_drop(super::can_drop_component);
     api.on_drop(super::drop_component);
     api.on_selected_element_resize(super::resize_selected_element);
     api.on_selected_element_can_move_to(super::can_move_selected_element);
     api.on_selected_element_move(super::move_selected_element);
+    api.on_selected_element_move_to_grid_cell(super::move_selected_element_to_grid_cell);
+    api.on_selected_element_span_to_grid_cell(super::span_selected_element_to_grid_cell);
+    api.on_selected_element_nudge(super::nudge_selected_element);
+    api.on_selected_element_snap(super::snap_selected_element);
+    api.on_selected_element_snap_done(super::snap_selected_element_done);
+    api.on_measure_at(super::measure_at);
+    api.on_outline_select(super::select_outline_node);
+    api.on_outline_move(super::move_outline_node);
+    api.on_outline_toggle_hidden(super::toggle_outline_node_hidden);
+    api.on_outline_toggle_solo(super::toggle_outline_node_solo);
+    api.on_outline_toggle_locked(super::toggle_outline_node_locked);
+    api.on_outline_show_all(super::show_all_outline_nodes);
+    api.on_open_preview_window(super::open_preview_window);
+    api.on_load_image_assets(super::load_image_assets);
+    api.on_image_url_to_code(|path, nine_slice| {
+        image_assets::parse_nine_slice(nine_slice.as_str())
+            .map(|nine_slice| {
+                image_assets::ImageUrl { path: path.to_string(), nine_slice }.to_code()
+            })
+            .unwrap_or_default()
+            .into()
+    });
+    api.on_load_font_families(super::load_font_families);
+    api.on_filter_font_families(|families, filter| {
+        let families = families.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+        let filtered = fonts::filter_families(&families, filter.as_str())
+            .into_iter()
+            .map(SharedString::from)
+            .collect::<Vec<_>>();
+        Rc::new(VecModel::from(filtered)).into()
+    });
+    api.set_easing_presets(
+        Rc::new(VecModel::from(
+            easing::PRESETS.iter().map(|(name, _)| SharedString::from(*name)).collect::<Vec<_>>(),
+        ))
+        .into(),
+    );
+    api.on_easing_control_points(|code| {
+        let points = easing::control_points(code.as_str()).map(Vec::from).unwrap_or_default();
+        Rc::new(VecModel::from(points)).into()
+    });
+    api.on_easing_to_code(|x1, y1, x2, y2| easing::to_code([x1, y1, x2, y2]).into());
+    api.on_selected_element_change_z_order(super::change_z_order_of_selected_element);
+    api.on_selected_elements_wrap_in_layout(super::wrap_selected_elements_in_layout);
+    api.on_show_localization_finding(super::show_localization_finding);
+    api.on_color_audit_scope_changed(super::color_audit_scope_changed);
+    api.on_show_color_usage(super::show_color_usage);
+    api.on_selected_elements_extract_component(super::extract_selected_elements_into_component);
+    api.on_selected_elements_assign_ids(super::assign_ids_to_selected_elements);
+    api.on_add_responsive_variants(super::add_responsive_variants);
+    api.on_open_example(super::open_example);
+
+    let examples = super::examples::available()
+        .iter()
+        .map(|e| ExampleProject { name: e.name.into(), description: e.description.into() })
+        .collect::<Vec<_>>();
+    api.set_examples(Rc::new(VecModel::from(examples)).into());
+    api.set_show_welcome(super::examples::is_first_start());
+
+    let devices = super::devices::DEVICES
+        .iter()
+        .map(|d| DevicePreset {
+            name: d.name.into(),
+            width: d.width as i32,
+            height: d.height as i32,
+            scale_factor: d.scale_factor,
+            portrait_insets: to_ui_insets(d.safe_area.portrait),
+            landscape_insets: to_ui_insets(d.safe_area.landscape),
+            corner_radius: d.safe_area.corner_radius,
+            notch_width: d.safe_area.notch_width,
+            notch_height: d.safe_area.notch_height,
+            home_indicator: d.safe_area.home_indicator,
+        })
+        .collect::<Vec<_>>();
+    let device_names = devices.iter().map(|d| d.name.clone()).collect::<Vec<SharedString>>();
+    api.set_device_presets(Rc::new(VecModel::from(devices)).into());
+    api.set_device_preset_names(Rc::new(VecModel::from(device_names)).into());
+
+    // The layout of the project gets restored along with the other project settings
+    let panel_layout = panel_layout::PanelLayout::default();
+    api.set_library_dock(panel_layout.library.into());
+    api.set_properties_dock(panel_layout.properties.into());
+    api.set_outline_dock(panel_layout.outline.into());
+    api.on_project_settings_changed(super::project_settings_changed);
+    api.on_replace_color_usages(super::replace_color_usages);
+    api.on_render_costs_toggled(super::render_costs_toggled);
+    api.on_performance_overlay_toggled(super::performance_overlay_toggled);
+    api.on_set_animation(super::set_animation);
+    api.on_show_two_way_binding_peer(super::show_two_way_binding_peer);
+    api.on_break_two_way_binding(super::break_two_way_binding);
+    api.on_force_state(super::force_state);
+    api.on_set_state_condition(super::set_state_condition);
+    api.on_set_state_override(super::set_state_override);
+    api.on_input_regions_toggled(super::input_regions_toggled);
+    api.on_render_layers_toggled(super::render_layers_toggled);
+    api.on_layout_anomalies_toggled(super::layout_anomalies_toggled);
+    api.on_accessibility_inspector_toggled(super::accessibility_inspector_toggled);
+    api.on_show_accessible_element(super::show_accessible_element);
+    api.on_perform_accessibility_action(super::perform_accessibility_action);
+    api.on_focus_chain_toggled(super::focus_chain_toggled);
+    api.on_select_focus_stop(super::select_focus_stop);
+    api.on_selected_element_move_in_focus_chain(super::move_selected_element_in_focus_chain);
+    api.on_selected_element_focus_first(super::focus_selected_element_first);
+    api.on_notes_toggled(super::notes_toggled);
+    api.on_add_note(super::add_note);
+    api.on_edit_note(super::edit_note);
+    api.on_move_note(super::move_note);
+    api.on_remove_note(super::remove_note);
+    api.on_hit_test_at(super::hit_test_at);
+    api.on_console_filter_changed(super::update_console);
+    api.on_apply_text_style(super::apply_text_style);
+    api.on_set_text_style(super::set_text_style);
+    api.on_apply_brush_preset(super::apply_brush_preset);
+    api.on_save_brush_preset(super::save_brush_preset);
+    api.on_replace_with_brush_preset(super::replace_with_brush_preset);
+    api.on_clear_console(super::clear_console);
+    api.on_pick_color(super::pick_color);
+    api.on_pick_preview_color(super::pick_preview_color);
+    api.on_cancel_color_picking(super::cancel_color_picking);
+    api.on_output_search_changed(super::update_output_search);
+    api.on_structural_search(super::structural_search);
+    api.on_structural_replace(super::structural_replace);
+    api.on_clear_output_history(super::clear_output_history);
+    api.on_add_watch_expression(|expression| super::watch::add(&expression));
+    api.on_remove_watch_expression(|index| super::watch::remove(index as usize));
+    api.on_set_breakpoint(super::set_breakpoint);
+    api.on_step_callback(super::breakpoints::step);
+    api.on_resume_preview(super::breakpoints::resume);
+    api.on_show_binding_dependencies(super::show_binding_dependencies);
+    api.on_add_form(super::add_form);
+    api.on_show_global(super::show_global);
+    api.on_prepare_screenshot(super::prepare_screenshot);
+    api.on_save_screenshot(super::save_screenshot);
+    api.on_report_issue(|| super::report_issue(None));
+    api.on_export_all_screenshots(super::export_all_screenshots);
+    api.on_export_preview_data(super::export_preview_data);
+    api.on_import_preview_data(super::import_preview_data);
+    api.on_compare_toggled(super::compare_toggled);
+    api.on_fuzzing_toggled(super::fuzzing_toggled);
+    api.on_fuzz_with_seed(super::fuzz_with_seed);
+    api.on_edit_review_toggle_hunk(super::edit_review_toggle_hunk);
+    api.on_edit_review_apply(super::edit_review_apply);
+    api.on_edit_review_cancel(super::edit_review_cancel);
+    api.on_recording_toggled(super::recording_toggled);
+    api.on_save_recording(super::save_recording);
+    api.on_discard_recording(super::discard_recording);
+    api.on_replay_recording(super::replay_recording);
     api.on_selected_element_delete(super::delete_selected_element);
+    api.on_selected_element_copy(super::copy_selected_element);
+    api.on_selected_element_duplicate(super::duplicate_selected_element);
+    api.on_rename_element_id(super::rename_element_id);
+    api.on_selected_element_cut(super::cut_selected_element);
+    api.on_paste_element(super::paste_element);
+    api.on_insert(super::insert_component);
+    api.on_filter_palette_entries(super::filter_palette_entries);
+    api.on_replay_recovered_edits(super::replay_recovered_edits);
+    api.on_discard_recovered_edits(super::discard_recovered_edits);
+    api.on_reconnect_lsp(super::connection::reconnect);
 
     api.on_test_code_binding(super::test_code_binding);
     api.on_set_code_binding(super::set_code_binding);
     api.on_set_color_binding(super::set_color_binding);
+    api.on_preview_color_scheme_changed(super::preview_color_scheme_changed);
+    api.on_environment_changed(|environment| {
+        super::environment_changed(super::environment::Environment {
+            battery_level: environment.battery_level,
+            battery_charging: environment.battery_charging,
+            network_connected: environment.network_connected,
+            network_type: environment.network_type.into(),
+            ambient_light: environment.ambient_light,
+            hour: environment.hour,
+            minute: environment.minute,
+        })
+    });
+    api.on_safe_area_changed(|insets| {
+        super::safe_area_changed(super::devices::Insets {
+            top: insets.top,
+            right: insets.right,
+            bottom: insets.bottom,
+            left: insets.left,
+        })
+    });
+    api.on_pseudo_localization_changed(i_slint_core::translations::set_pseudo_localization);
+    api.on_complete_expression(|url, offset, name, expression| {
+        let completions = super::complete_expression(&url, offset, &name, &expression)
+            .into_iter()
+            .map(|c| ExpressionCompletion {
+                label: c.label.into(),
+                expression: c.expression.into(),
+            })
+            .collect::<Vec<_>>();
+        Rc::new(VecModel::from(completions)).into()
+    });
+    api.on_check_expression(|url, offset, name, expression| {
+        super::check_expression(&url, offset, &name, &expression).join("\n").into()
+    });
+    api.on_evaluate_expression(|url, offset, name, expression| {
+        match super::evaluate_expression(&url, offset, &name, &expression) {
+            Ok(value) => value.into(),
+            Err(error) => error.into(),
+        }
+    });
+    api.on_text_contrast_suggestion(
+        |url, offset, background| match super::text_contrast_suggestion(&url, offset, &background) {
+            Some((text_count, suggestion)) => TextContrastSuggestion {
+                text_count: text_count as i32,
+                color: slint::Color::from_argb_encoded(suggestion.argb),
+                expression: suggestion.expression.into(),
+                contrast: suggestion.contrast,
+            },
+            None => Default::default(),
+        },
+    );
+    api.on_apply_text_contrast_suggestion(|url, offset, background| {
+        super::apply_text_contrast_suggestion(&url, offset, &background)
+    });
     api.on_property_declaration_ranges(super::property_declaration_ranges);
+    api.on_filter_property_groups(filter_property_groups);
+    api.on_filter_known_components(filter_known_components);
 
     api.on_get_property_value(get_property_value);
     api.on_get_property_value_table(get_property_value_table);
     api.on_set_json_preview_data(set_json_preview_data);
+    api.on_complete_json_preview_data(complete_json_preview_data);
+    api.on_append_preview_data_row(|container, property_name| {
+        change_preview_data_rows(container, property_name, preview_data::RowOperation::Append)
+    });
+    api.on_duplicate_preview_data_row(|container, property_name, row| {
+        match row_operation_at(row, preview_data::RowOperation::Duplicate) {
+            Ok(operation) => change_preview_data_rows(container, property_name, operation),
+            Err(e) => e,
+        }
+    });
+    api.on_remove_preview_data_row(|container, property_name, row| {
+        match row_operation_at(row, preview_data::RowOperation::Remove) {
+            Ok(operation) => change_preview_data_rows(container, property_name, operation),
+            Err(e) => e,
+        }
+    });
+    api.on_mock_preview_data_rows(|container, property_name, count| {
+        let operation = preview_data::RowOperation::Mock(count.max(0) as usize);
+        change_preview_data_rows(container, property_name, operation)
+    });
+    api.on_set_preview_data_cell(set_preview_data_cell);
+    api.on_paste_preview_data_csv(paste_preview_data_csv);
+    api.on_set_preview_data_fixture(set_preview_data_fixture);
+    api.on_apply_preview_data_to_source(|container, property_name| {
+        super::apply_preview_data_to_source(to_property_container(container), &property_name)
+    });
+    api.on_has_preview_data_fixture(has_preview_data_fixture);
+
+    api.on_get_callable_arguments(get_callable_arguments);
+    api.on_set_callable_argument(set_callable_argument);
+    api.on_invoke_callable(invoke_callable);
 
     api.on_string_to_code(string_to_code);
     api.on_string_to_color(|s| string_to_color(s.as_ref()).unwrap_or_default());
@@ -111,16 +388,12 @@ pub fn create_ui(style: String, experimental: bool) -> Result<PreviewUi, Platfor
         b: c.blue() as i32,
         text: color_to_string(c).into(),
     });
-    api.on_rgba_to_color(|r, g, b, a| {
-        if (0..256).contains(&r)
-            && (0..256).contains(&g)
-            && (0..256).contains(&b)
-            && (0..256).contains(&a)
-        {
-            slint::Color::from_argb_u8(a as u8, r as u8, g as u8, b as u8)
-        } else {
-            slint::Color::default()
-        }
+    api.on_rgba_to_color(rgba_to_color);
+    api.on_color_to_string(|color, format| color_to_string_as(color, format).into());
+    api.on_color_to_code(|color, format| color_to_code(color, format).into());
+    api.on_color_format(|text| color_format(text.as_str()).unwrap_or(ColorFormat::Hex));
+    api.on_convert_unit(|value, from, to| {
+        convert_unit_text(value.as_str(), from.as_str(), to.as_str()).unwrap_or(value)
     });
 
     api.on_as_json_brush(as_json_brush);
@@ -159,6 +432,15 @@ pub fn create_ui(style: String, experimental: bool) -> Result<PreviewUi, Platfor
     Ok(ui)
 }
 
+fn to_ui_insets(insets: super::devices::Insets) -> SafeAreaInsets {
+    SafeAreaInsets {
+        top: insets.top,
+        right: insets.right,
+        bottom: insets.bottom,
+        left: insets.left,
+    }
+}
+
 fn extract_definition_location(ci: &ComponentInformation) -> (SharedString, SharedString) {
     let Some(url) = ci.defined_at.as_ref().map(|da| da.url()) else {
         return (Default::default(), Default::default());
@@ -175,6 +457,691 @@ pub fn ui_set_uses_widgets(ui: &PreviewUi, uses_widgets: bool) {
     api.set_uses_widgets(uses_widgets);
 }
 
+pub fn ui_set_layout_gaps(ui: &PreviewUi, gaps: &[super::layout_gaps::Gap]) {
+    let gaps = gaps
+        .iter()
+        .map(|g| LayoutGap {
+            geometry: SelectionRectangle {
+                x: g.geometry.origin.x,
+                y: g.geometry.origin.y,
+                width: g.geometry.size.width,
+                height: g.geometry.size.height,
+            },
+            is_padding: g.is_padding,
+        })
+        .collect::<Vec<_>>();
+    let api = ui.global::<Api>();
+    api.set_layout_gaps(Rc::new(VecModel::from(gaps)).into());
+}
+
+pub fn ui_set_grid_cells(ui: &PreviewUi, grid: Option<&super::grid_cells::Grid>) {
+    let rect = |x: f32, y: f32, width: f32, height: f32| SelectionRectangle { x, y, width, height };
+    let (rows, columns, cell) = grid
+        .map(|grid| {
+            let layout = grid.geometry;
+            let rows = grid
+                .rows
+                .iter()
+                .map(|&(y1, y2)| rect(layout.origin.x, y1, layout.size.width, y2 - y1))
+                .collect::<Vec<_>>();
+            let columns = grid
+                .columns
+                .iter()
+                .map(|&(x1, x2)| rect(x1, layout.origin.y, x2 - x1, layout.size.height))
+                .collect::<Vec<_>>();
+            let cell = grid.selected_cell();
+            let geometry = grid
+                .cell_geometry(cell)
+                .map(|g| rect(g.origin.x, g.origin.y, g.size.width, g.size.height))
+                .unwrap_or_default();
+            let cell = GridCell {
+                geometry,
+                row: cell.row as i32,
+                col: cell.col as i32,
+                rowspan: cell.rowspan as i32,
+                colspan: cell.colspan as i32,
+            };
+            (rows, columns, cell)
+        })
+        .unwrap_or_default();
+
+    let api = ui.global::<Api>();
+    api.set_grid_rows(Rc::new(VecModel::from(rows)).into());
+    api.set_grid_columns(Rc::new(VecModel::from(columns)).into());
+    api.set_grid_selected_cell(cell);
+}
+
+pub fn ui_set_flashes(ui: &PreviewUi, geometries: &[i_slint_core::lengths::LogicalRect]) {
+    let flashes = geometries
+        .iter()
+        .map(|g| SelectionRectangle {
+            x: g.origin.x,
+            y: g.origin.y,
+            width: g.size.width,
+            height: g.size.height,
+        })
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_flashes(Rc::new(VecModel::from(flashes)).into());
+}
+
+pub fn ui_set_text_styles(ui: &PreviewUi, styles: &super::text_styles::TextStyles) {
+    let styles = styles
+        .styles
+        .iter()
+        .map(|s| {
+            let [font_family, font_size, font_weight, letter_spacing] =
+                s.values.clone().map(SharedString::from);
+            TextStylePreset {
+                name: s.name.as_str().into(),
+                font_family,
+                font_size,
+                font_weight,
+                letter_spacing,
+            }
+        })
+        .collect::<Vec<_>>();
+    let names = styles.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+    let api = ui.global::<Api>();
+    api.set_text_style_names(Rc::new(VecModel::from(names)).into());
+    api.set_text_styles(Rc::new(VecModel::from(styles)).into());
+}
+
+pub fn ui_set_brush_presets(ui: &PreviewUi, palette: &super::brush_presets::Palette) {
+    let presets = palette
+        .presets
+        .iter()
+        .map(|p| SharedString::from(format!("{} ({})", p.name, p.expression)))
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_brush_presets(Rc::new(VecModel::from(presets)).into());
+}
+
+pub fn ui_set_connection_health(ui: &PreviewUi) {
+    let api = ui.global::<Api>();
+    api.set_connection_health(match super::connection::health() {
+        super::connection::Health::Connected => ConnectionHealth::Connected,
+        super::connection::Health::Unresponsive => ConnectionHealth::Unresponsive,
+        super::connection::Health::Disconnected => ConnectionHealth::Disconnected,
+    });
+    api.set_buffered_lsp_messages(super::connection::buffered_messages() as i32);
+}
+
+pub fn ui_set_console_messages(ui: &PreviewUi) {
+    let api = ui.global::<Api>();
+    let filter = super::console::Filter {
+        debug: api.get_console_show_debug(),
+        warnings: api.get_console_show_warnings(),
+        errors: api.get_console_show_errors(),
+    };
+    let messages = super::console::messages(filter)
+        .into_iter()
+        .map(|m| {
+            let (file, line, column) = m
+                .location
+                .map(|(path, line, column)| {
+                    (path.to_string_lossy().to_string().into(), line as i32, column as i32)
+                })
+                .unwrap_or_default();
+            ConsoleMessage {
+                level: console_level(m.level),
+                text: m.text.into(),
+                file,
+                line,
+                column,
+            }
+        })
+        .collect::<Vec<_>>();
+    api.set_console_problems(super::console::problem_count() as i32);
+    api.set_console_messages(Rc::new(VecModel::from(messages)).into());
+}
+
+fn console_level(level: super::console::LogLevel) -> ConsoleLevel {
+    match level {
+        super::console::LogLevel::Debug => ConsoleLevel::Debug,
+        super::console::LogLevel::Warning => ConsoleLevel::Warning,
+        super::console::LogLevel::Error => ConsoleLevel::Error,
+    }
+}
+
+pub fn ui_set_output_search_results(ui: &PreviewUi) {
+    use super::output_search::{self, Source};
+
+    let api = ui.global::<Api>();
+    let time = |text: SharedString| {
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+        output_search::parse_time(&text)
+            .map(Some)
+            .ok_or_else(|| format!("Invalid time \"{text}\", use seconds or minutes:seconds"))
+    };
+    let query = time(api.get_output_search_from()).and_then(|from| {
+        let to = time(api.get_output_search_to())?;
+        let sources = [
+            (Source::Console, api.get_output_search_console()),
+            (Source::Callback, api.get_output_search_callbacks()),
+            (Source::Diagnostic, api.get_output_search_diagnostics()),
+            (Source::Property, api.get_output_search_properties()),
+        ];
+        Ok(output_search::Query {
+            pattern: api.get_output_search_text().to_string(),
+            regex: api.get_output_search_regex(),
+            match_case: api.get_output_search_match_case(),
+            sources: sources.into_iter().filter(|(_, on)| *on).map(|(s, _)| s).collect(),
+            from,
+            to,
+        })
+    });
+
+    let (results, error) = match query.and_then(|query| {
+        // No source selected finds nothing, rather than everything
+        if query.sources.is_empty() {
+            return Ok(Vec::new());
+        }
+        output_search::search(&query)
+    }) {
+        Ok(results) => (results, String::new()),
+        Err(e) => (Vec::new(), e),
+    };
+    let results = results
+        .into_iter()
+        .map(|e| {
+            let (file, line, column) = e
+                .location
+                .map(|(path, line, column)| {
+                    (path.to_string_lossy().to_string().into(), line as i32, column as i32)
+                })
+                .unwrap_or_default();
+            OutputEntry {
+                source: match e.source {
+                    Source::Console => OutputSource::Console,
+                    Source::Callback => OutputSource::Callback,
+                    Source::Diagnostic => OutputSource::Diagnostic,
+                    Source::Property => OutputSource::Property,
+                },
+                level: console_level(e.level),
+                time: output_search::format_time(e.time).into(),
+                text: e.text.into(),
+                file,
+                line,
+                column,
+            }
+        })
+        .collect::<Vec<_>>();
+    api.set_output_search_error(error.into());
+    api.set_output_search_results(Rc::new(VecModel::from(results)).into());
+}
+
+pub fn ui_set_structural_matches(
+    ui: &PreviewUi,
+    matches: Result<Vec<crate::common::structural_search::Match>, String>,
+) {
+    let api = ui.global::<Api>();
+    let (matches, error) = match matches {
+        Ok(matches) => (matches, String::new()),
+        Err(e) => (Vec::new(), e),
+    };
+    let matches = matches
+        .into_iter()
+        .map(|m| StructuralMatch {
+            label: m.label.into(),
+            file: m
+                .url
+                .to_file_path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default()
+                .into(),
+            line: m.range.start.line as i32 + 1,
+            column: m.range.start.character as i32 + 1,
+        })
+        .collect::<Vec<_>>();
+    api.set_structural_search_error(error.into());
+    api.set_structural_matches(Rc::new(VecModel::from(matches)).into());
+}
+
+/// Show the `pending` edit for review, or hide the review if there is none
+pub fn ui_set_edit_review(ui: &PreviewUi, pending: Option<&edit_review::PendingEdit>) {
+    let api = ui.global::<Api>();
+    let Some(pending) = pending else {
+        api.set_show_edit_review(false);
+        api.set_edit_review_hunks(Default::default());
+        return;
+    };
+    let hunks = pending
+        .hunks
+        .iter()
+        .map(|hunk| {
+            let lines = hunk
+                .lines
+                .iter()
+                .map(|(kind, text)| DiffLine {
+                    kind: match kind {
+                        edit_review::LineKind::Context => DiffLineKind::Context,
+                        edit_review::LineKind::Removed => DiffLineKind::Removed,
+                        edit_review::LineKind::Added => DiffLineKind::Added,
+                    },
+                    text: text.into(),
+                })
+                .collect::<Vec<_>>();
+            EditReviewHunk {
+                file: hunk
+                    .url
+                    .to_file_path()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| hunk.url.to_string())
+                    .into(),
+                header: hunk.header.as_str().into(),
+                lines: Rc::new(VecModel::from(lines)).into(),
+                accepted: hunk.accepted,
+            }
+        })
+        .collect::<Vec<_>>();
+    let accepted = pending.hunks.iter().filter(|hunk| hunk.accepted).count();
+    api.set_edit_review_label(pending.label.as_str().into());
+    api.set_edit_review_accepted(accepted as i32);
+    api.set_edit_review_hunks(Rc::new(VecModel::from(hunks)).into());
+    api.set_show_edit_review(true);
+}
+
+pub fn ui_set_watch_expressions(ui: &PreviewUi) {
+    let watches = super::watch::watches()
+        .into_iter()
+        .map(|(expression, evaluation)| WatchExpression {
+            expression: expression.into(),
+            value: evaluation.text.into(),
+            is_error: evaluation.is_error,
+        })
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_watch_expressions(Rc::new(VecModel::from(watches)).into());
+}
+
+pub fn ui_set_callback_handlers(ui: &PreviewUi, handlers: &[super::breakpoints::Handler]) {
+    let handlers = handlers
+        .iter()
+        .map(|h| CallbackHandler {
+            element: h.element.clone().into(),
+            callback: h.callback.clone().into(),
+            file: h.path.to_string_lossy().to_string().into(),
+            offset: h.offset as i32,
+            line: h.line as i32,
+            has_breakpoint: super::breakpoints::has_breakpoint(h),
+        })
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_callback_handlers(Rc::new(VecModel::from(handlers)).into());
+}
+
+pub fn ui_set_breakpoint_hit(ui: &PreviewUi) {
+    let api = ui.global::<Api>();
+    let Some(hit) = super::breakpoints::stopped() else {
+        api.set_breakpoint_hit(false);
+        return;
+    };
+    let arguments = hit.arguments.into_iter().map(SharedString::from).collect::<Vec<_>>();
+    let (file, line, column) = hit
+        .location
+        .map(|(path, line, column)| {
+            (path.to_string_lossy().to_string().into(), line as i32, column as i32)
+        })
+        .unwrap_or_default();
+    api.set_hit_callback(hit.callback.into());
+    api.set_hit_arguments(Rc::new(VecModel::from(arguments)).into());
+    api.set_hit_file(file);
+    api.set_hit_line(line);
+    api.set_hit_column(column);
+    api.set_breakpoint_hit(true);
+}
+
+pub fn ui_set_form_structs(ui: &PreviewUi, structs: &[super::form_scaffold::StructDeclaration]) {
+    let names = structs.iter().map(|s| SharedString::from(s.name.as_str())).collect::<Vec<_>>();
+    ui.global::<Api>().set_form_structs(Rc::new(VecModel::from(names)).into());
+}
+
+/// Restore the settings of a project, the panel layout of the last session stays where the
+/// project has none
+pub fn ui_set_project_settings(
+    ui: &PreviewUi,
+    settings: &super::project_settings::ProjectSettings,
+) {
+    let api = ui.global::<Api>();
+    if let Some(style) = &settings.style {
+        if api.get_known_styles().iter().any(|s| s == style) {
+            api.set_current_style(style.into());
+        }
+    }
+    if let Some(zoom) = settings.zoom {
+        api.set_zoom(zoom);
+    }
+    if let Some(layout) = &settings.panel_layout {
+        api.set_library_dock(layout.library.into());
+        api.set_properties_dock(layout.properties.into());
+        api.set_outline_dock(layout.outline.into());
+    }
+    api.set_follow_editor_cursor(settings.follow_editor_cursor);
+    if let Some((width, height)) = settings.window_size {
+        ui.window().set_size(slint::LogicalSize::new(width, height));
+    }
+}
+
+/// The settings of the project as the UI shows them
+pub fn project_settings(ui: &PreviewUi) -> super::project_settings::ProjectSettings {
+    let api = ui.global::<Api>();
+    let window = ui.window();
+    let size = window.size().to_logical(window.scale_factor());
+    super::project_settings::ProjectSettings {
+        style: Some(api.get_current_style().to_string()),
+        window_size: Some((size.width, size.height)),
+        zoom: Some(api.get_zoom()),
+        panel_layout: Some(panel_layout::PanelLayout {
+            library: api.get_library_dock().into(),
+            properties: api.get_properties_dock().into(),
+            outline: api.get_outline_dock().into(),
+        }),
+        follow_editor_cursor: api.get_follow_editor_cursor(),
+        ..Default::default()
+    }
+}
+
+pub fn ui_set_global_names(ui: &PreviewUi, names: &[String]) {
+    let names = names.iter().map(SharedString::from).collect::<Vec<_>>();
+    ui.global::<Api>().set_global_names(Rc::new(VecModel::from(names)).into());
+}
+
+/// Show the properties of the global `name` in the globals panel, like `ui_set_properties` does
+/// for the selected element
+pub fn ui_set_global_properties(
+    ui: &PreviewUi,
+    document_cache: &common::DocumentCache,
+    name: &str,
+    properties: Option<properties::QueryPropertyResponse>,
+) {
+    let api = ui.global::<Api>();
+    let Some((next_element, _, next_model)) = map_properties_to_ui(document_cache, properties)
+    else {
+        api.set_current_global(SharedString::new());
+        api.set_global_element(Default::default());
+        api.set_global_properties(Rc::new(VecModel::from(Vec::<PropertyGroup>::new())).into());
+        return;
+    };
+
+    if api.get_current_global() == name
+        && is_equal_element(&api.get_global_element(), &next_element)
+    {
+        api.set_global_properties(update_properties(api.get_global_properties(), next_model));
+    } else {
+        api.set_global_properties(next_model);
+    }
+    api.set_current_global(name.into());
+    api.set_global_element(next_element);
+}
+
+pub fn ui_set_bound_properties(ui: &PreviewUi, properties: &[String]) {
+    let properties = properties.iter().map(SharedString::from).collect::<Vec<_>>();
+    let api = ui.global::<Api>();
+    api.set_bound_properties(Rc::new(VecModel::from(properties)).into());
+    api.set_binding_dependencies(Default::default());
+}
+
+pub fn ui_set_binding_dependencies(
+    ui: &PreviewUi,
+    property: &str,
+    dependencies: &super::binding_dependencies::Dependencies,
+) {
+    let model = |properties: &[super::binding_dependencies::Property]| {
+        let properties = properties
+            .iter()
+            .map(|p| {
+                let (file, line, column) = p
+                    .location
+                    .as_ref()
+                    .map(|(path, line, column)| {
+                        (path.to_string_lossy().to_string().into(), *line as i32, *column as i32)
+                    })
+                    .unwrap_or_default();
+                BindingDependency { label: p.label.as_str().into(), file, line, column }
+            })
+            .collect::<Vec<_>>();
+        Rc::new(VecModel::from(properties)).into()
+    };
+    ui.global::<Api>().set_binding_dependencies(BindingDependencies {
+        property: property.into(),
+        dependencies: model(&dependencies.dependencies),
+        dependents: model(&dependencies.dependents),
+        binding_loop: model(&dependencies.binding_loop),
+    });
+}
+
+pub fn ui_set_layout_anomalies(ui: &PreviewUi, anomalies: &[super::layout_anomalies::Anomaly]) {
+    let anomalies = anomalies
+        .iter()
+        .map(|a| LayoutAnomaly {
+            geometry: SelectionRectangle {
+                x: a.geometry.origin.x,
+                y: a.geometry.origin.y,
+                width: a.geometry.size.width,
+                height: a.geometry.size.height,
+            },
+            element: a.element.as_str().into(),
+            details: a.problems.iter().map(|p| p.label()).join("\n").into(),
+            overflow: a.problems.iter().all(|p| p.is_overflow()),
+            file: a.path.to_string_lossy().to_string().into(),
+            line: a.line as i32,
+            column: a.column as i32,
+        })
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_layout_anomalies(Rc::new(VecModel::from(anomalies)).into());
+}
+
+pub fn ui_set_accessible_elements(
+    ui: &PreviewUi,
+    elements: &[super::accessibility::AccessibleElement],
+) {
+    let elements = elements
+        .iter()
+        .map(|e| AccessibleElement {
+            geometry: SelectionRectangle {
+                x: e.geometry.origin.x,
+                y: e.geometry.origin.y,
+                width: e.geometry.size.width,
+                height: e.geometry.size.height,
+            },
+            role: e.role.to_string().into(),
+            element: e.element.as_str().into(),
+            label: e.label.as_str().into(),
+            value: e.value.as_str().into(),
+            description: e.description.as_str().into(),
+            actions: Rc::new(VecModel::from(
+                e.action_names().into_iter().map(SharedString::from).collect::<Vec<_>>(),
+            ))
+            .into(),
+            missing_label: e.is_missing_label(),
+        })
+        .collect::<Vec<_>>();
+    let api = ui.global::<Api>();
+    api.set_accessible_missing_labels(elements.iter().filter(|e| e.missing_label).count() as i32);
+    api.set_accessible_elements(Rc::new(VecModel::from(elements)).into());
+}
+
+pub fn ui_set_focus_chain(ui: &PreviewUi, stops: &[super::focus_chain::FocusStop]) {
+    let stops = stops
+        .iter()
+        .map(|s| FocusStop {
+            geometry: SelectionRectangle {
+                x: s.geometry.origin.x,
+                y: s.geometry.origin.y,
+                width: s.geometry.size.width,
+                height: s.geometry.size.height,
+            },
+            element: s.element.as_str().into(),
+        })
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_focus_chain(Rc::new(VecModel::from(stops)).into());
+}
+
+pub fn ui_set_notes(ui: &PreviewUi, notes: &[super::annotations::PlacedNote]) {
+    let notes = notes
+        .iter()
+        .map(|n| {
+            let position = n.position.unwrap_or_default();
+            StickyNote {
+                index: n.index as i32,
+                x: position.x,
+                y: position.y,
+                is_placed: n.position.is_some(),
+                anchor: n.anchor.as_str().into(),
+                text: n.text.as_str().into(),
+            }
+        })
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_notes(Rc::new(VecModel::from(notes)).into());
+}
+
+pub fn ui_set_outline(
+    ui: &PreviewUi,
+    outline: &[super::outline::OutlineNode],
+    selected: Option<&super::element_selection::ElementSelection>,
+    overrides: &super::layer_overrides::Overrides,
+) {
+    let nodes = outline
+        .iter()
+        .map(|n| {
+            let element = (n.path.clone(), n.offset);
+            let address = n.address.as_ref();
+            OutlineNode {
+                label: n.label.clone().into(),
+                depth: n.depth as i32,
+                is_layout: n.is_layout,
+                is_selected: selected.is_some_and(|s| s.path == n.path && s.offset == n.offset),
+                can_hide: super::layer_overrides::can_hide(n),
+                is_hidden: overrides.is_hidden(&element),
+                is_solo: overrides.solo.as_ref() == Some(&element),
+                can_lock: address.is_some(),
+                is_locked: address.is_some_and(super::element_locks::is_locked_at),
+                is_lock_inherited: address.is_some_and(super::element_locks::is_locked_by_ancestor),
+            }
+        })
+        .collect::<Vec<_>>();
+    let api = ui.global::<Api>();
+    api.set_outline(Rc::new(VecModel::from(nodes)).into());
+    api.set_layers_hidden(!overrides.is_empty());
+}
+
+/// Show the images of the project in the image property editor, with their `paths` relative
+/// to the document of the selected element
+pub fn ui_set_image_assets(ui: &PreviewUi, images: &[(PathBuf, String)]) {
+    let assets = images
+        .iter()
+        .map(|(file, path)| ImageAsset {
+            path: path.into(),
+            name: file.file_name().unwrap_or_default().to_string_lossy().to_string().into(),
+            image: slint::Image::load_from_path(file).unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    ui.global::<Api>().set_image_assets(Rc::new(VecModel::from(assets)).into());
+}
+
+pub fn ui_set_font_families(ui: &PreviewUi, families: &[String]) {
+    let families = families.iter().map(SharedString::from).collect::<Vec<_>>();
+    ui.global::<Api>().set_font_families(Rc::new(VecModel::from(families)).into());
+}
+
+pub fn ui_set_localization_report(ui: &PreviewUi, report: &super::localization::Report) {
+    let findings = report
+        .findings
+        .iter()
+        .map(|f| {
+            let file_name = f.path.file_name().unwrap_or_default().to_string_lossy();
+            LocalizationFinding {
+                text: f.text.clone().into(),
+                problem: match &f.problem {
+                    super::localization::Problem::NotTranslatable => "Not wrapped in @tr".into(),
+                    super::localization::Problem::MissingTranslations(languages) => {
+                        format!("No translation for {}", languages.join(", ")).into()
+                    }
+                },
+                location: format!("{file_name}:{}", f.range.start.line + 1).into(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let api = ui.global::<Api>();
+    api.set_localization_coverage(report.coverage());
+    api.set_localization_languages(report.languages.join(", ").into());
+    api.set_localization_findings(Rc::new(VecModel::from(findings)).into());
+}
+
+pub fn ui_set_states(
+    ui: &PreviewUi,
+    states: &[super::states::StateInformation],
+    forced: Option<TextSize>,
+) {
+    let states = states
+        .iter()
+        .map(|s| StateInformation {
+            name: s.name.as_str().into(),
+            element: s.element.as_str().into(),
+            condition: s.condition.as_str().into(),
+            overrides: Rc::new(VecModel::from(
+                s.overrides
+                    .iter()
+                    .map(|o| StateOverride {
+                        property: o.property.as_str().into(),
+                        value: o.value.as_str().into(),
+                    })
+                    .collect::<Vec<_>>(),
+            ))
+            .into(),
+            offset: u32::from(s.offset) as i32,
+        })
+        .collect::<Vec<_>>();
+
+    let api = ui.global::<Api>();
+    api.set_states(Rc::new(VecModel::from(states)).into());
+    api.set_forced_state(forced.map(|o| u32::from(o) as i32).unwrap_or(-1));
+}
+
+pub fn ui_set_color_audit(ui: &PreviewUi, audit: &super::color_audit::Audit) {
+    let api = ui.global::<Api>();
+    let whole_project = api.get_color_audit_whole_project();
+    let usages = audit
+        .colors_in(whole_project)
+        .into_iter()
+        .map(|c| {
+            let [a, r, g, b] = c.argb.to_be_bytes();
+            ColorUsage {
+                color: slint::Color::from_argb_u8(a, r, g, b),
+                value: super::color_audit::color_text(c.argb).into(),
+                palette_entry: audit
+                    .palette_entry_for(c.argb)
+                    .map(|e| e.expression())
+                    .unwrap_or_default()
+                    .into(),
+                count: match audit.document.as_ref() {
+                    Some(url) if !whole_project => c.count_in(url),
+                    _ => c.usages.len(),
+                } as i32,
+            }
+        })
+        .collect::<Vec<_>>();
+    let palette = audit
+        .palette
+        .iter()
+        .map(|e| {
+            SharedString::from(format!(
+                "{} ({})",
+                e.expression(),
+                super::color_audit::color_text(e.argb)
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    api.set_color_usages(Rc::new(VecModel::from(usages)).into());
+    api.set_color_palette_entries(Rc::new(VecModel::from(palette)).into());
+}
+
+pub fn set_recovered_edits(ui: &PreviewUi, entries: &[super::edit_journal::JournalEntry]) {
+    let labels = entries.iter().map(|e| SharedString::from(&e.label)).collect::<Vec<_>>();
+    ui.global::<Api>().set_recovered_edits(Rc::new(VecModel::from(labels)).into());
+}
+
 pub fn set_diagnostics(ui: &PreviewUi, diagnostics: &[slint_interpreter::Diagnostic]) {
     let summary = diagnostics.iter().fold(DiagnosticSummary::NothingDetected, |acc, d| {
         match (acc, d.level()) {
@@ -194,6 +1161,7 @@ pub fn ui_set_known_components(
     ui: &PreviewUi,
     known_components: &[crate::common::ComponentInformation],
     current_component_index: usize,
+    usage: &super::component_usage::ProjectUsage,
 ) {
     let mut builtins_map: HashMap<String, Vec<ComponentItem>> = Default::default();
     let mut std_widgets_map: HashMap<String, Vec<ComponentItem>> = Default::default();
@@ -201,20 +1169,26 @@ pub fn ui_set_known_components(
     let mut library_map: HashMap<String, Vec<ComponentItem>> = Default::default();
     let mut longest_path_prefix = PathBuf::new();
 
-    for (idx, ci) in known_components.iter().enumerate() {
-        if ci.is_global {
-            continue;
-        }
+    let component_item = |idx: usize, ci: &ComponentInformation| {
         let (url, pretty_location) = extract_definition_location(ci);
-        let item = ComponentItem {
+        ComponentItem {
             name: ci.name.clone().into(),
             index: idx.try_into().unwrap(),
-            defined_at: url.clone(),
+            defined_at: url,
             pretty_location,
             is_user_defined: !(ci.is_builtin || ci.is_std_widget),
             is_currently_shown: idx == current_component_index,
             is_exported: ci.is_exported,
-        };
+            thumbnail: super::thumbnails::thumbnail(ci),
+        }
+    };
+
+    for (idx, ci) in known_components.iter().enumerate() {
+        if ci.is_global {
+            continue;
+        }
+        let item = component_item(idx, ci);
+        let url = item.defined_at.clone();
 
         if let Some(position) = &ci.defined_at {
             if let Some(library) = position.url().path().strip_prefix("/@") {
@@ -276,19 +1250,154 @@ fn sort_subset(mut input: HashMap<String, Vec<ComponentItem>>) -> Vec<ComponentL
             ComponentListItem { category: name.into(), file_url, components: model.into() }
         })
         .collect::<Vec<_>>();
-    file_components.sort_by_key(|k| PathBuf::from(k.category.to_string()));
+    file_components.sort_by_key(|k| PathBuf::from(k.category.to_string()));
+
+    let usage_components = [
+        (SharedString::from("Favorites"), usage.favorites(known_components)),
+        (SharedString::from("Recently used"), usage.recently_used(known_components)),
+    ]
+    .into_iter()
+    .filter(|(_, indices)| !indices.is_empty())
+    .map(|(category, indices)| {
+        let items = indices.into_iter().map(|idx| component_item(idx, &known_components[idx]));
+        ComponentListItem {
+            category,
+            file_url: SharedString::new(),
+            components: Rc::new(VecModel::from(items.collect::<Vec<_>>())).into(),
+        }
+    })
+    .collect::<Vec<_>>();
+
+    let mut all_components = Vec::with_capacity(
+        usage_components.len()
+            + builtin_components.len()
+            + library_components.len()
+            + file_components.len(),
+    );
+    all_components.extend_from_slice(&usage_components);
+    all_components.extend_from_slice(&builtin_components);
+    all_components.extend_from_slice(&std_widgets_components);
+    all_components.extend_from_slice(&library_components);
+    all_components.extend_from_slice(&file_components);
+
+    let result = Rc::new(VecModel::from(all_components));
+    let api = ui.global::<Api>();
+    api.set_known_components(result.into());
+}
+
+/// How well `pattern` matches `text`, ignoring case
+///
+/// All characters of the pattern need to appear in the text in the same order. Runs of
+/// consecutive characters and characters starting a word score higher.
+fn fuzzy_match_score(pattern: &str, text: &str) -> Option<u32> {
+    let mut pattern = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+
+    for (index, c) in text.chars().enumerate() {
+        let Some(&wanted) = pattern.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(std::iter::once(wanted));
+        if matched {
+            let starts_word = previous
+                .is_none_or(|p| !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()));
+            score += 1;
+            if previous_matched {
+                score += 5;
+            }
+            if starts_word {
+                score += 3;
+            }
+            if index == 0 {
+                score += 10;
+            }
+            pattern.next();
+        }
+        previous_matched = matched;
+        previous = Some(c);
+    }
+
+    pattern.peek().is_none().then_some(score)
+}
+
+// Only keep the components matching `filter_text` by name, category or file, best matches
+// first. Groups without matches disappear.
+fn filter_known_components(
+    groups: slint::ModelRc<ComponentListItem>,
+    filter_text: SharedString,
+) -> slint::ModelRc<ComponentListItem> {
+    let filter_text = filter_text.split_whitespace().collect::<String>();
+    if filter_text.is_empty() {
+        return groups;
+    }
+
+    let mut result = groups
+        .iter()
+        .filter_map(|group| {
+            let category_score = fuzzy_match_score(&filter_text, &group.category);
+            let mut components = group
+                .components
+                .iter()
+                .filter_map(|component| {
+                    let score = [
+                        fuzzy_match_score(&filter_text, &component.name).map(|s| s * 3),
+                        category_score,
+                        fuzzy_match_score(&filter_text, &component.pretty_location),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .max()?;
+                    Some((score, component))
+                })
+                .collect::<Vec<_>>();
+            components.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            let best = components.first()?.0;
+            let components = components.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+            Some((
+                best,
+                ComponentListItem {
+                    components: Rc::new(VecModel::from(components)).into(),
+                    ..group
+                },
+            ))
+        })
+        .collect::<Vec<_>>();
+    result.sort_by_key(|(best, _)| std::cmp::Reverse(*best));
 
-    let mut all_components = Vec::with_capacity(
-        builtin_components.len() + library_components.len() + file_components.len(),
-    );
-    all_components.extend_from_slice(&builtin_components);
-    all_components.extend_from_slice(&std_widgets_components);
-    all_components.extend_from_slice(&library_components);
-    all_components.extend_from_slice(&file_components);
+    Rc::new(VecModel::from(result.into_iter().map(|(_, g)| g).collect::<Vec<_>>())).into()
+}
 
-    let result = Rc::new(VecModel::from(all_components));
-    let api = ui.global::<Api>();
-    api.set_known_components(result.into());
+pub fn palette_entries(
+    known_components: &[ComponentInformation],
+    filter: &str,
+) -> Vec<PaletteEntry> {
+    let filter = filter.trim().to_lowercase();
+
+    known_components
+        .iter()
+        .enumerate()
+        .filter(|(_, ci)| !ci.is_global)
+        .filter_map(|(idx, ci)| {
+            let name = ci.name.to_lowercase();
+            let match_position = name.find(&filter)?;
+            Some((match_position != 0, idx, ci))
+        })
+        .sorted_by(|(lp, _, lc), (rp, _, rc)| lp.cmp(rp).then_with(|| lc.name.cmp(&rc.name)))
+        .map(|(_, idx, ci)| {
+            let (_, pretty_location) = extract_definition_location(ci);
+            PaletteEntry {
+                label: ci.name.clone().into(),
+                detail: if pretty_location.is_empty() {
+                    ci.category.clone().into()
+                } else {
+                    pretty_location
+                },
+                component_index: idx.try_into().unwrap(),
+            }
+        })
+        .collect()
 }
 
 fn to_ui_range(r: TextRange) -> Option<Range> {
@@ -402,6 +1511,23 @@ fn extract_value_with_unit_impl(
     None
 }
 
+/// `value` given in `from` in the unit `to`, so that it stays the same length, duration or angle.
+/// `None` if the units do not measure the same, like `px` and `rem` do not.
+fn convert_unit(value: f64, from: expression_tree::Unit, to: expression_tree::Unit) -> Option<f64> {
+    if from.ty() != to.ty() {
+        return None;
+    }
+    let converted = from.normalize(value) / to.normalize(1.0);
+    // Hide the rounding errors of the conversion factors
+    Some((converted * 10_000.0).round() / 10_000.0)
+}
+
+fn convert_unit_text(value: &str, from: &str, to: &str) -> Option<slint::SharedString> {
+    let value = value.trim().parse::<f64>().ok()?;
+    let converted = convert_unit(value, from.parse().ok()?, to.parse().ok()?)?;
+    Some(converted.to_string().into())
+}
+
 fn convert_simple_string(input: slint::SharedString) -> String {
     format!("\"{}\"", str::escape_debug(input.as_ref()))
 }
@@ -432,6 +1558,99 @@ fn string_to_code(
     .into()
 }
 
+pub fn rgba_to_color(r: i32, g: i32, b: i32, a: i32) -> slint::Color {
+    if (0..256).contains(&r)
+        && (0..256).contains(&g)
+        && (0..256).contains(&b)
+        && (0..256).contains(&a)
+    {
+        slint::Color::from_argb_u8(a as u8, r as u8, g as u8, b as u8)
+    } else {
+        slint::Color::default()
+    }
+}
+
+/// Hand a color picked with the eyedropper to the color editor that asked for it. `None` if
+/// the picking got cancelled.
+pub fn ui_set_picked_color(ui: &PreviewUi, rgba: Option<super::eyedropper::Rgba>) {
+    let api = ui.global::<Api>();
+    if let Some((r, g, b, a)) = rgba {
+        api.set_picked_color(rgba_to_color(r.into(), g.into(), b.into(), a.into()));
+        api.set_color_picks(api.get_color_picks() + 1);
+    }
+    api.set_picking_color(false);
+}
+
+/// Parse the arguments of `name(...)` in `text`, like `rgb(255, 128, 0)`. Each argument is a
+/// number and whether it was given in percent. Hues may have a `deg` unit.
+fn color_function_arguments(text: &str, names: &[&str]) -> Option<Vec<(f32, bool)>> {
+    let (name, arguments) = text.strip_suffix(')')?.split_once('(')?;
+    if !names.contains(&name.trim()) {
+        return None;
+    }
+    let arguments = arguments
+        .split(',')
+        .map(|a| {
+            let a = a.trim();
+            let (number, percent) = match a.strip_suffix('%') {
+                Some(number) => (number, true),
+                None => (a.strip_suffix("deg").unwrap_or(a), false),
+            };
+            let number = number.trim().parse::<f32>().ok().filter(|n| n.is_finite())?;
+            Some((number, percent))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (3..=4).contains(&arguments.len()).then_some(arguments)
+}
+
+/// A fraction between 0 and 1, given either as fraction or in percent
+fn fraction((number, percent): (f32, bool)) -> f32 {
+    let number = if percent { number / 100.0 } else { number };
+    number.clamp(0.0, 1.0)
+}
+
+fn alpha(arguments: &[(f32, bool)]) -> f32 {
+    arguments.get(3).copied().map_or(1.0, fraction)
+}
+
+/// Convert HSL saturation and lightness to HSV saturation and value
+fn hsl_to_hsv(saturation: f32, lightness: f32) -> (f32, f32) {
+    let value = lightness + saturation * lightness.min(1.0 - lightness);
+    let saturation = if value > 0.0 { 2.0 * (1.0 - lightness / value) } else { 0.0 };
+    (saturation, value)
+}
+
+/// Convert HSV saturation and value to HSL saturation and lightness
+fn hsv_to_hsl(saturation: f32, value: f32) -> (f32, f32) {
+    let lightness = value * (1.0 - saturation / 2.0);
+    let saturation = if lightness > 0.0 && lightness < 1.0 {
+        (value - lightness) / lightness.min(1.0 - lightness)
+    } else {
+        0.0
+    };
+    (saturation, lightness)
+}
+
+/// The name of `color` in the named colors, if it has one
+fn color_name(color: slint::Color) -> Option<&'static str> {
+    let argb = color.as_argb_encoded();
+    i_slint_compiler::lookup::named_colors::named_colors()
+        .iter()
+        .filter(|(_, c)| **c == argb)
+        .map(|(name, _)| *name)
+        .min()
+}
+
+/// Format `number` with at most `decimals` decimal places
+fn format_number(number: f32, decimals: usize) -> String {
+    let text = format!("{number:.decimals$}");
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
 fn color_to_string(color: slint::Color) -> String {
     let a = color.alpha();
     let r = color.red();
@@ -441,8 +1660,117 @@ fn color_to_string(color: slint::Color) -> String {
     format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
 }
 
+/// The text of `color` in `format`. Colors without a name get shown in hex.
+fn color_to_string_as(color: slint::Color, format: ColorFormat) -> String {
+    let alpha = match color.alpha() {
+        255 => String::new(),
+        a => format!(", {}", format_number(a as f32 / 255.0, 3)),
+    };
+    match format {
+        ColorFormat::Hex => color_to_string(color),
+        ColorFormat::Rgb => {
+            let name = if alpha.is_empty() { "rgb" } else { "rgba" };
+            format!("{name}({}, {}, {}{alpha})", color.red(), color.green(), color.blue())
+        }
+        ColorFormat::Hsv => {
+            let hsva = color.to_hsva();
+            format!(
+                "hsv({}, {}, {}{alpha})",
+                format_number(hsva.hue, 1),
+                format_number(hsva.saturation, 3),
+                format_number(hsva.value, 3)
+            )
+        }
+        ColorFormat::Hsl => {
+            let hsva = color.to_hsva();
+            let (saturation, lightness) = hsv_to_hsl(hsva.saturation, hsva.value);
+            format!(
+                "hsl({}, {}%, {}%{alpha})",
+                format_number(hsva.hue, 1),
+                format_number(saturation * 100.0, 1),
+                format_number(lightness * 100.0, 1)
+            )
+        }
+        ColorFormat::Named => {
+            color_name(color).map(|name| name.to_string()).unwrap_or_else(|| color_to_string(color))
+        }
+    }
+}
+
+/// The Slint code of `color` in `format`. Slint has no `hsl()`, so those colors are written
+/// as `hsv()`.
+fn color_to_code(color: slint::Color, format: ColorFormat) -> String {
+    match format {
+        ColorFormat::Hsl => color_to_string_as(color, ColorFormat::Hsv),
+        ColorFormat::Named => match color_name(color) {
+            Some(name) => format!("Colors.{name}"),
+            None => color_to_string(color),
+        },
+        format => color_to_string_as(color, format),
+    }
+}
+
+/// The format `text` is written in, if it is a color
+fn color_format(text: &str) -> Option<ColorFormat> {
+    let text = text.trim();
+    let name = text.strip_prefix("Colors.").unwrap_or(text);
+    if text.starts_with('#') {
+        literals::parse_color_literal(text).map(|_| ColorFormat::Hex)
+    } else if i_slint_compiler::lookup::named_colors::named_colors().contains_key(name) {
+        Some(ColorFormat::Named)
+    } else if color_function_arguments(text, &["rgb", "rgba"]).is_some() {
+        Some(ColorFormat::Rgb)
+    } else if color_function_arguments(text, &["hsv", "hsva"]).is_some() {
+        Some(ColorFormat::Hsv)
+    } else if color_function_arguments(text, &["hsl", "hsla"]).is_some() {
+        Some(ColorFormat::Hsl)
+    } else {
+        None
+    }
+}
+
+/// Parse a color in any of the formats of `ColorFormat`: `#ff8000`, `rgb(255, 128, 0)`,
+/// `rgba(100%, 50%, 0%, 0.5)`, `hsv(30, 1, 1)`, `hsl(30deg, 100%, 50%)`, `orange` or
+/// `Colors.orange`
 fn string_to_color(text: &str) -> Option<slint::Color> {
-    literals::parse_color_literal(text).map(slint::Color::from_argb_encoded)
+    let text = text.trim();
+    match color_format(text)? {
+        ColorFormat::Hex => {
+            literals::parse_color_literal(text).map(slint::Color::from_argb_encoded)
+        }
+        ColorFormat::Named => {
+            let name = text.strip_prefix("Colors.").unwrap_or(text);
+            let argb = i_slint_compiler::lookup::named_colors::named_colors().get(name)?;
+            Some(slint::Color::from_argb_encoded(*argb))
+        }
+        ColorFormat::Rgb => {
+            let arguments = color_function_arguments(text, &["rgb", "rgba"])?;
+            let channel = |(number, percent): (f32, bool)| {
+                let number = if percent { number * 2.55 } else { number };
+                number.round().clamp(0.0, 255.0) as u8
+            };
+            Some(slint::Color::from_argb_u8(
+                (alpha(&arguments) * 255.0).round() as u8,
+                channel(arguments[0]),
+                channel(arguments[1]),
+                channel(arguments[2]),
+            ))
+        }
+        ColorFormat::Hsv => {
+            let arguments = color_function_arguments(text, &["hsv", "hsva"])?;
+            Some(slint::Color::from_hsva(
+                arguments[0].0,
+                fraction(arguments[1]),
+                fraction(arguments[2]),
+                alpha(&arguments),
+            ))
+        }
+        ColorFormat::Hsl => {
+            let arguments = color_function_arguments(text, &["hsl", "hsla"])?;
+            let (saturation, value) = hsl_to_hsv(fraction(arguments[1]), fraction(arguments[2]));
+            Some(slint::Color::from_hsva(arguments[0].0, saturation, value, alpha(&arguments)))
+        }
+    }
 }
 
 fn unit_model(units: &[expression_tree::Unit]) -> slint::ModelRc<slint::SharedString> {
@@ -531,6 +1859,13 @@ fn simplify_value(prop_info: &super::properties::PropertyInformation) -> Propert
         ..Default::default()
     };
 
+    // `<=>` shares the value of another property, whatever that value is
+    if prop_info.defined_at.as_ref().is_some_and(|da| da.two_way_arrow_range.is_some()) {
+        value.kind = PropertyValueKind::TwoWayBinding;
+        value.value_string = value.code.clone();
+        return value;
+    }
+
     let def_val = prop_info.default_value.as_ref();
 
     match &prop_info.ty {
@@ -616,6 +1951,29 @@ fn simplify_value(prop_info: &super::properties::PropertyInformation) -> Propert
                 value.kind = PropertyValueKind::String;
             }
         }
+        Type::Image => {
+            let url = expression.as_ref().and_then(|e| image_assets::ImageUrl::from_expression(e));
+            if let Some(url) = url {
+                value.kind = PropertyValueKind::Image;
+                value.value_string = url.path.as_str().into();
+                if !url.nine_slice.is_empty() {
+                    let nine_slice = url.nine_slice.iter().map(|n| n.to_string()).join(" ");
+                    value.visual_items =
+                        Rc::new(VecModel::from(vec![SharedString::from(nine_slice)])).into();
+                }
+            } else if value.code.is_empty() {
+                value.kind = PropertyValueKind::Image;
+            }
+        }
+        Type::Easing => {
+            let code = expression.as_ref().map(|e| e.text().to_string());
+            if let Some(code) = code.filter(|c| easing::is_easing(c)) {
+                value.kind = PropertyValueKind::Easing;
+                value.value_string = code.trim().into();
+            } else if value.code.is_empty() {
+                value.kind = PropertyValueKind::Easing;
+            }
+        }
         Type::Enumeration(enumeration) => {
             value.kind = PropertyValueKind::Enum;
             value.value_string = enumeration.name.as_str().into();
@@ -656,6 +2014,15 @@ fn simplify_value(prop_info: &super::properties::PropertyInformation) -> Propert
         _ => {}
     }
 
+    // Fonts get pickers of their own
+    match (prop_info.name.as_str(), value.kind) {
+        ("font-family", PropertyValueKind::String) if !value.is_translatable => {
+            value.kind = PropertyValueKind::FontFamily;
+        }
+        ("font-weight", PropertyValueKind::Integer) => value.kind = PropertyValueKind::FontWeight,
+        _ => {}
+    }
+
     value
 }
 
@@ -728,6 +2095,19 @@ fn property_group_from(
                 type_name: pi.ty.to_string().into(),
                 value,
                 display_priority: i32::try_from(pi.priority).unwrap(),
+                is_animatable: properties::is_animatable(&pi.ty),
+                is_animated: pi.animation.is_some(),
+                animation: pi
+                    .animation
+                    .as_ref()
+                    .map(|a| AnimationSettings {
+                        duration: a.settings.duration.as_str().into(),
+                        delay: a.settings.delay.as_str().into(),
+                        easing: a.settings.easing.as_str().into(),
+                        iteration_count: a.settings.iteration_count.as_str().into(),
+                    })
+                    .unwrap_or_default(),
+                is_set: pi.defined_at.is_some(),
             },
         );
     }
@@ -1082,7 +2462,7 @@ fn map_color(
                     mapping.headers.push(mapping.name_prefix.clone());
                     mapping.current_values.push(PropertyValue {
                         kind: PropertyValueKind::Code,
-                        value_string: "???".into(),
+                        value_string: ty.to_string().into(),
                         code: get_code(value),
                         ..Default::default()
                     });
@@ -1123,6 +2503,7 @@ fn map_color(
                         .collect::<Vec<_>>(),
                 ))
                 .into(),
+                code: get_code(value),
                 ..Default::default()
             });
         }
@@ -1150,6 +2531,21 @@ fn map_color(
                     mapping.array_values.push(std::mem::take(&mut sub_mapping.array_values[0]));
                 }
             }
+
+            if model.row_count() == 0 {
+                // Infer the columns from the row type, so that an empty table can be pasted into
+                let mut sub_mapping = ValueMapping {
+                    name_prefix: mapping.name_prefix.clone(),
+                    ..Default::default()
+                };
+                let row = slint_interpreter::default_value_for_type(array_ty);
+                map_value_and_type(array_ty, &Some(row), &mut sub_mapping);
+                if sub_mapping.is_array || sub_mapping.is_too_complex {
+                    mapping.headers.push(mapping.name_prefix.clone());
+                } else {
+                    mapping.headers.extend_from_slice(&sub_mapping.headers);
+                }
+            }
         }
         Type::Struct(s) => {
             mapping.is_array = false;
@@ -1185,15 +2581,13 @@ fn map_color(
                 }
             }
         }
-        Type::Image | Type::Model | Type::PathData | Type::Easing | Type::UnitProduct(_) => {
-            mapping.headers.push(mapping.name_prefix.clone());
-            mapping.is_too_complex = true;
-        }
         _ => {
+            // Images, easing curves, path data, models, ...: There is no editor for these,
+            // so fall back to showing the code
             mapping.headers.push(mapping.name_prefix.clone());
             mapping.current_values.push(PropertyValue {
                 kind: PropertyValueKind::Code,
-                value_string: "???".into(),
+                value_string: ty.to_string().into(),
                 code: get_code(value),
                 ..Default::default()
             });
@@ -1263,6 +2657,14 @@ fn fill_container(
         container_id: String,
         properties: &[preview_data::PreviewData],
     ) -> PropertyContainer {
+        let callables = properties
+            .iter()
+            .filter(|pd| pd.is_callable())
+            .map(|pd| PreviewCallable {
+                name: pd.name.clone().into(),
+                signature: pd.ty.to_string().into(),
+            })
+            .collect::<Vec<_>>();
         let properties =
             properties.iter().filter_map(map_preview_data_property).collect::<Vec<_>>();
 
@@ -1270,6 +2672,7 @@ fn fill_container(
             container_name: container_name.into(),
             container_id: container_id.into(),
             properties: Rc::new(slint::VecModel::from(properties)).into(),
+            callables: Rc::new(slint::VecModel::from(callables)).into(),
         }
     }
 
@@ -1356,39 +2759,352 @@ fn get_property_value_table(
     ))
     .into();
 
-    PropertyValueTable { is_array, headers, values }
+    PropertyValueTable { is_array, headers, values }
+}
+
+fn set_json_preview_data(
+    container: SharedString,
+    property_name: SharedString,
+    json_string: SharedString,
+) -> SharedString {
+    let property_name = (!property_name.is_empty()).then_some(property_name.to_string());
+
+    let json = match serde_json::from_str::<serde_json::Value>(json_string.as_ref()) {
+        Ok(j) => j,
+        Err(e) => {
+            return SharedString::from(format!("Input is not valid JSON: {e}"));
+        }
+    };
+
+    if property_name.is_none() && !json.is_object() {
+        return SharedString::from("Input for Slint Element is not a JSON object");
+    }
+
+    if let Some(ci) = preview::component_instance() {
+        let preview_data = property_name.as_ref().and_then(|name| {
+            preview_data::get_preview_data(
+                &ci,
+                to_property_container(container.clone()),
+                name.clone(),
+            )
+        });
+        if let Some(preview_data) = preview_data {
+            let errors = json_schema::validate(&json_schema::schema(&preview_data.ty), &json);
+            if !errors.is_empty() {
+                return errors.join("\n").into();
+            }
+        }
+
+        match preview_data::set_json_preview_data(
+            &ci,
+            to_property_container(container),
+            property_name,
+            json,
+        ) {
+            Ok(()) => SharedString::new(),
+            Err(v) => v.first().cloned().unwrap_or_default().into(),
+        }
+    } else {
+        SharedString::from("No preview loaded")
+    }
+}
+
+fn complete_json_preview_data(
+    container: SharedString,
+    property_name: SharedString,
+    json_string: SharedString,
+) -> slint::ModelRc<ExpressionCompletion> {
+    let completions = preview::component_instance()
+        .and_then(|component_instance| {
+            preview_data::get_preview_data(
+                &component_instance,
+                to_property_container(container),
+                property_name.to_string(),
+            )
+        })
+        .map(|pd| json_schema::complete(&json_schema::schema(&pd.ty), &json_string))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| ExpressionCompletion { label: c.label.into(), expression: c.text.into() })
+        .collect::<Vec<_>>();
+    Rc::new(VecModel::from(completions)).into()
+}
+
+fn set_preview_data_cell(
+    container: SharedString,
+    property_name: SharedString,
+    row: i32,
+    column: SharedString,
+    text: SharedString,
+) -> SharedString {
+    let Some(component_instance) = preview::component_instance() else {
+        return SharedString::from("No preview loaded");
+    };
+    let container = to_property_container(container);
+    let Some(preview_data) = preview_data::get_preview_data(
+        &component_instance,
+        container.clone(),
+        property_name.to_string(),
+    ) else {
+        return format!("Property {property_name} not found").into();
+    };
+    let Ok(row) = usize::try_from(row) else {
+        return format!("Row {row} not found").into();
+    };
+
+    let result = preview_data
+        .value
+        .as_ref()
+        .ok_or_else(|| format!("Property {property_name} has no value"))
+        .and_then(slint_interpreter::json::value_to_json)
+        .and_then(|mut json| {
+            model_fixtures::set_cell(&preview_data.ty, &mut json, row, &column, &text)?;
+            preview_data::set_json_preview_data(
+                &component_instance,
+                container,
+                Some(property_name.to_string()),
+                json,
+            )
+            .map_err(|e| e.first().cloned().unwrap_or_default())
+        });
+    match result {
+        Ok(()) => SharedString::new(),
+        Err(e) => e.into(),
+    }
+}
+
+fn paste_preview_data_csv(
+    container: SharedString,
+    property_name: SharedString,
+    csv: SharedString,
+) -> SharedString {
+    let Some(component_instance) = preview::component_instance() else {
+        return SharedString::from("No preview loaded");
+    };
+    let container = to_property_container(container);
+    let Some(preview_data) = preview_data::get_preview_data(
+        &component_instance,
+        container.clone(),
+        property_name.to_string(),
+    ) else {
+        return format!("Property {property_name} not found").into();
+    };
+    let langtype::Type::Array(row_type) = &preview_data.ty else {
+        return format!("Property {property_name} is not an array").into();
+    };
+
+    let result = model_fixtures::rows_from_csv(row_type, &csv).and_then(|json| {
+        preview_data::set_json_preview_data(
+            &component_instance,
+            container.clone(),
+            Some(property_name.to_string()),
+            json,
+        )
+        .map_err(|e| e.first().cloned().unwrap_or_default())
+    });
+    // Pasted data is worth keeping
+    match result.and_then(|()| {
+        store_preview_data_fixture(&component_instance, &container, &property_name, true)
+    }) {
+        Ok(()) => SharedString::new(),
+        Err(e) => e.into(),
+    }
+}
+
+/// Remember the current value of the property in the fixtures of the project, or forget it
+fn store_preview_data_fixture(
+    component_instance: &slint_interpreter::ComponentInstance,
+    container: &preview_data::PropertyContainer,
+    property_name: &str,
+    keep: bool,
+) -> Result<(), String> {
+    preview::project().ok_or("The preview is not part of a project")?;
+    let value = if keep {
+        let value = preview_data::get_preview_data(
+            component_instance,
+            container.clone(),
+            property_name.to_string(),
+        )
+        .and_then(|pd| pd.value)
+        .ok_or_else(|| format!("Property {property_name} has no value"))?;
+        Some(slint_interpreter::json::value_to_json(&value)?)
+    } else {
+        None
+    };
+
+    preview::change_fixtures(|fixtures| {
+        fixtures.set(component_instance.definition().name(), container, property_name, value)
+    });
+    Ok(())
+}
+
+fn set_preview_data_fixture(
+    container: SharedString,
+    property_name: SharedString,
+    keep: bool,
+) -> SharedString {
+    let Some(component_instance) = preview::component_instance() else {
+        return SharedString::from("No preview loaded");
+    };
+    let container = to_property_container(container);
+    match store_preview_data_fixture(&component_instance, &container, &property_name, keep) {
+        Ok(()) => SharedString::new(),
+        Err(e) => e.into(),
+    }
+}
+
+fn has_preview_data_fixture(container: SharedString, property_name: SharedString) -> bool {
+    let Some(component_instance) = preview::component_instance() else {
+        return false;
+    };
+    let container = to_property_container(container);
+    preview::fixtures()
+        .of(component_instance.definition().name())
+        .any(|(c, name, _)| c == container && name == property_name.as_str())
+}
+
+fn change_preview_data_rows(
+    container: SharedString,
+    property_name: SharedString,
+    operation: preview_data::RowOperation,
+) -> SharedString {
+    let Some(component_instance) = preview::component_instance() else {
+        return SharedString::from("No preview loaded");
+    };
+
+    match preview_data::change_preview_data_rows(
+        &component_instance,
+        to_property_container(container),
+        property_name.as_str(),
+        operation,
+    ) {
+        Ok(()) => SharedString::new(),
+        Err(e) => e.into(),
+    }
+}
+
+fn row_operation_at(
+    row: i32,
+    operation: impl FnOnce(usize) -> preview_data::RowOperation,
+) -> Result<preview_data::RowOperation, SharedString> {
+    usize::try_from(row).map(operation).map_err(|_| format!("Row {row} not found").into())
+}
+
+fn map_callable_argument(
+    name: String,
+    ty: langtype::Type,
+    value: slint_interpreter::Value,
+) -> CallableArgument {
+    let preview_data = preview_data::PreviewData {
+        name,
+        ty,
+        visibility: i_slint_compiler::object_tree::PropertyVisibility::InOut,
+        value: Some(value),
+    };
+    let kind =
+        map_preview_data_property(&preview_data).map(|pd| pd.kind).unwrap_or(PreviewDataKind::Json);
+
+    CallableArgument {
+        name: preview_data.name.clone().into(),
+        type_name: preview_data.ty.to_string().into(),
+        kind,
+        value: map_preview_data_to_property_value(&preview_data).unwrap_or_default(),
+        json: get_code(&preview_data.value),
+    }
+}
+
+fn get_callable_preview_data(
+    container: SharedString,
+    callable_name: &str,
+) -> Option<preview_data::PreviewData> {
+    preview::component_instance()
+        .and_then(|component_instance| {
+            preview_data::get_preview_data(
+                &component_instance,
+                to_property_container(container),
+                callable_name.to_string(),
+            )
+        })
+        .filter(|pd| pd.is_callable())
+}
+
+fn get_callable_arguments(
+    container: SharedString,
+    callable_name: SharedString,
+) -> slint::ModelRc<CallableArgument> {
+    let arguments = get_callable_preview_data(container, callable_name.as_str())
+        .map(|pd| {
+            pd.arguments()
+                .into_iter()
+                .map(|(name, ty)| {
+                    let value = slint_interpreter::default_value_for_type(&ty);
+                    map_callable_argument(name, ty, value)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    Rc::new(VecModel::from(arguments)).into()
+}
+
+fn set_callable_argument(
+    container: SharedString,
+    callable_name: SharedString,
+    arguments: slint::ModelRc<CallableArgument>,
+    index: i32,
+    json_string: SharedString,
+) -> SharedString {
+    let Some(preview_data) = get_callable_preview_data(container, callable_name.as_str()) else {
+        return format!("Callback or function {callable_name} not found").into();
+    };
+    let Some((name, ty)) =
+        usize::try_from(index).ok().and_then(|i| preview_data.arguments().into_iter().nth(i))
+    else {
+        return format!("Argument {index} not found").into();
+    };
+    let value = match slint_interpreter::json::value_from_json_str(&ty, json_string.as_str()) {
+        Ok(v) => v,
+        Err(e) => return format!("Could not convert JSON value for argument {name}: {e}").into(),
+    };
+
+    let Some(model) = arguments.as_any().downcast_ref::<VecModel<CallableArgument>>() else {
+        return SharedString::from("Arguments can not be changed");
+    };
+    model.set_row_data(index as usize, map_callable_argument(name, ty, value));
+    SharedString::new()
 }
 
-fn set_json_preview_data(
+fn invoke_callable(
     container: SharedString,
-    property_name: SharedString,
-    json_string: SharedString,
-) -> SharedString {
-    let property_name = (!property_name.is_empty()).then_some(property_name.to_string());
+    callable_name: SharedString,
+    arguments: slint::ModelRc<CallableArgument>,
+) -> CallableResult {
+    let error = |text: String| CallableResult { is_error: true, text: text.into() };
 
-    let json = match serde_json::from_str::<serde_json::Value>(json_string.as_ref()) {
-        Ok(j) => j,
-        Err(e) => {
-            return SharedString::from(format!("Input is not valid JSON: {e}"));
-        }
+    let Some(component_instance) = preview::component_instance() else {
+        return error("No preview loaded".to_string());
     };
 
-    if property_name.is_none() && !json.is_object() {
-        return SharedString::from("Input for Slint Element is not a JSON object");
-    }
+    let arguments = match arguments
+        .iter()
+        .map(|a| serde_json::from_str::<serde_json::Value>(a.json.as_str()))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(a) => a,
+        Err(e) => return error(format!("Argument is not valid JSON: {e}")),
+    };
 
-    if let Some(ci) = preview::component_instance() {
-        match preview_data::set_json_preview_data(
-            &ci,
-            to_property_container(container),
-            property_name,
-            json,
-        ) {
-            Ok(()) => SharedString::new(),
-            Err(v) => v.first().cloned().unwrap_or_default().into(),
+    match preview_data::invoke_preview_data_callable(
+        &component_instance,
+        to_property_container(container),
+        callable_name.as_str(),
+        &arguments,
+    ) {
+        Ok(slint_interpreter::Value::Void) => {
+            CallableResult { is_error: false, text: SharedString::new() }
         }
-    } else {
-        SharedString::from("No preview loaded")
+        Ok(value) => CallableResult { is_error: false, text: get_code(&Some(value)) },
+        Err(e) => error(e),
     }
 }
 
@@ -1447,6 +3163,68 @@ pub fn ui_set_properties(
     declarations
 }
 
+impl From<panel_layout::DockArea> for DockArea {
+    fn from(area: panel_layout::DockArea) -> Self {
+        match area {
+            panel_layout::DockArea::Left => DockArea::Left,
+            panel_layout::DockArea::Right => DockArea::Right,
+            panel_layout::DockArea::Bottom => DockArea::Bottom,
+            panel_layout::DockArea::Floating => DockArea::Floating,
+        }
+    }
+}
+
+impl From<DockArea> for panel_layout::DockArea {
+    fn from(area: DockArea) -> Self {
+        match area {
+            DockArea::Left => panel_layout::DockArea::Left,
+            DockArea::Right => panel_layout::DockArea::Right,
+            DockArea::Bottom => panel_layout::DockArea::Bottom,
+            DockArea::Floating => panel_layout::DockArea::Floating,
+        }
+    }
+}
+
+fn filter_property_groups(
+    groups: slint::ModelRc<PropertyGroup>,
+    filter_text: SharedString,
+    changed_only: bool,
+) -> slint::ModelRc<PropertyGroup> {
+    use slint::ModelExt;
+
+    let filter_text = filter_text.trim().to_string();
+    if filter_text.is_empty() && !changed_only {
+        return groups;
+    }
+
+    let case_sensitive = filter_text.chars().any(|c| c.is_uppercase());
+    let contains = move |text: &str| {
+        if case_sensitive {
+            text.contains(&filter_text)
+        } else {
+            text.to_lowercase().contains(&filter_text)
+        }
+    };
+    // Match by type as well, to find all the colors or lengths of an element
+    let matches = Rc::new(move |property: &PropertyInformation| {
+        (!changed_only || property.is_set)
+            && (contains(&property.name) || contains(&property.type_name))
+    });
+
+    Rc::new(
+        groups
+            .map(move |group| {
+                let matches = matches.clone();
+                PropertyGroup {
+                    group_name: group.group_name,
+                    properties: Rc::new(group.properties.filter(move |p| matches(p))).into(),
+                }
+            })
+            .filter(|group| group.properties.row_count() > 0),
+    )
+    .into()
+}
+
 fn sorted_gradient_stops(
     stops: slint::ModelRc<GradientStop>,
 ) -> Vec<i_slint_core::graphics::GradientStop> {
@@ -1544,9 +3322,17 @@ fn properties_at_position(
     }
 
     fn property_conversion_test(contents: &str, property_line: u32) -> PropertyValue {
+        named_property_conversion_test(contents, property_line, "test1")
+    }
+
+    fn named_property_conversion_test(
+        contents: &str,
+        property_line: u32,
+        name: &str,
+    ) -> PropertyValue {
         let (_, pi, _, _) = properties_at_position(contents, property_line, 30).unwrap();
-        let test1 = pi.iter().find(|pi| pi.name == "test1").unwrap();
-        super::simplify_value(test1)
+        let property = pi.iter().find(|pi| pi.name == name).unwrap();
+        super::simplify_value(property)
     }
 
     #[test]
@@ -1880,6 +3666,63 @@ fn test_property_color() {
         assert_eq!(result.kind, PropertyValueKind::Code);
     }
 
+    #[test]
+    fn test_color_formats() {
+        use super::{
+            color_format, color_to_code, color_to_string_as, string_to_color, ColorFormat,
+        };
+
+        let orange = slint::Color::from_argb_u8(255, 255, 128, 0);
+        assert_eq!(string_to_color("#ff8000"), Some(orange));
+        assert_eq!(string_to_color(" rgb(255, 128, 0) "), Some(orange));
+        assert_eq!(
+            string_to_color("rgba(100%, 50%, 0%, 0.5)"),
+            Some(slint::Color::from_argb_u8(128, 255, 128, 0))
+        );
+        assert_eq!(string_to_color("hsl(30deg, 100%, 50%)"), string_to_color("hsv(30, 1, 1)"));
+        assert_eq!(string_to_color("Colors.orange"), string_to_color("#ffa500"));
+        assert_eq!(string_to_color("orange"), string_to_color("#ffa500"));
+        assert_eq!(string_to_color("rgb(1, 2)"), None);
+        assert_eq!(string_to_color("hsv(a, b, c)"), None);
+        assert_eq!(string_to_color("nonsense"), None);
+
+        assert_eq!(color_format("#fff"), Some(ColorFormat::Hex));
+        assert_eq!(color_format("rgba(1, 2, 3, 0.5)"), Some(ColorFormat::Rgb));
+        assert_eq!(color_format("hsv(1, 0.2, 0.3)"), Some(ColorFormat::Hsv));
+        assert_eq!(color_format("hsl(1, 2%, 3%)"), Some(ColorFormat::Hsl));
+        assert_eq!(color_format("Colors.red"), Some(ColorFormat::Named));
+        assert_eq!(color_format("rgb(1, 2, 3"), None);
+
+        assert_eq!(color_to_string_as(orange, ColorFormat::Hex), "#ff8000ff");
+        assert_eq!(color_to_string_as(orange, ColorFormat::Rgb), "rgb(255, 128, 0)");
+        assert_eq!(
+            color_to_string_as(slint::Color::from_argb_u8(128, 255, 128, 0), ColorFormat::Rgb),
+            "rgba(255, 128, 0, 0.502)"
+        );
+        let red = slint::Color::from_argb_u8(255, 255, 0, 0);
+        assert_eq!(color_to_string_as(red, ColorFormat::Hsv), "hsv(0, 1, 1)");
+        assert_eq!(color_to_string_as(red, ColorFormat::Hsl), "hsl(0, 100%, 50%)");
+        assert_eq!(color_to_string_as(red, ColorFormat::Named), "red");
+        assert_eq!(color_to_string_as(orange, ColorFormat::Named), "#ff8000ff");
+        assert_eq!(color_to_string_as(slint::Color::default(), ColorFormat::Named), "transparent");
+
+        assert_eq!(color_to_code(red, ColorFormat::Named), "Colors.red");
+        assert_eq!(color_to_code(red, ColorFormat::Hsl), "hsv(0, 1, 1)");
+        assert_eq!(color_to_code(orange, ColorFormat::Rgb), "rgb(255, 128, 0)");
+
+        // Every format reads back what it wrote, give or take rounding
+        let color = slint::Color::from_argb_u8(200, 12, 150, 230);
+        for format in [ColorFormat::Hex, ColorFormat::Rgb, ColorFormat::Hsv, ColorFormat::Hsl] {
+            let text = color_to_string_as(color, format);
+            assert_eq!(color_format(&text), Some(format), "{text}");
+            let read = string_to_color(&text).unwrap();
+            let channels = |c: slint::Color| [c.red(), c.green(), c.blue(), c.alpha()];
+            for (a, b) in channels(read).into_iter().zip(channels(color)) {
+                assert!(a.abs_diff(b) <= 1, "{text}: {:?}", channels(read));
+            }
+        }
+    }
+
     #[test]
     fn test_property_brush() {
         let result =
@@ -1928,6 +3771,99 @@ fn test_property_brush() {
         assert_eq!(result.kind, PropertyValueKind::Code);
     }
 
+    #[test]
+    fn test_property_font() {
+        let result = named_property_conversion_test(
+            r#"export component Test { in property <string> font-family: "Noto Sans"; }"#,
+            0,
+            "font-family",
+        );
+        assert_eq!(result.kind, PropertyValueKind::FontFamily);
+        assert_eq!(result.value_string, "Noto Sans");
+
+        let result = named_property_conversion_test(
+            r#"export component Test { in property <int> font-weight: 700; }"#,
+            0,
+            "font-weight",
+        );
+        assert_eq!(result.kind, PropertyValueKind::FontWeight);
+        assert_eq!(result.value_int, 700);
+
+        let result = named_property_conversion_test(
+            r#"export component Test { in property <string> font-family: @tr("Noto Sans"); }"#,
+            0,
+            "font-family",
+        );
+        assert_eq!(result.kind, PropertyValueKind::String);
+    }
+
+    #[test]
+    fn test_property_easing() {
+        let result =
+            property_conversion_test(r#"export component Test { in property <easing> test1; }"#, 0);
+        assert_eq!(result.kind, PropertyValueKind::Easing);
+        assert_eq!(result.value_string, "");
+
+        let result = property_conversion_test(
+            r#"export component Test { in property <easing> test1: cubic-bezier(0.1, 0.7, 1, 0.1); }"#,
+            0,
+        );
+        assert_eq!(result.kind, PropertyValueKind::Easing);
+        assert_eq!(result.value_string, "cubic-bezier(0.1, 0.7, 1, 0.1)");
+
+        let result = property_conversion_test(
+            r#"export component Test { in property <easing> test1: ease-out-bounce; }"#,
+            0,
+        );
+        assert_eq!(result.kind, PropertyValueKind::Easing);
+        assert_eq!(result.value_string, "ease-out-bounce");
+
+        let result = property_conversion_test(
+            r#"export component Test { in property <easing> test2; in property <easing> test1: root.test2; }"#,
+            0,
+        );
+        assert_eq!(result.kind, PropertyValueKind::Code);
+    }
+
+    #[test]
+    fn test_property_image() {
+        let result =
+            property_conversion_test(r#"export component Test { in property <image> test1; }"#, 0);
+        assert_eq!(result.kind, PropertyValueKind::Image);
+        assert_eq!(result.value_string, "");
+
+        let result = property_conversion_test(
+            r#"export component Test { in property <image> test1: @image-url("icons/frame.png", nine-slice(4 8)); }"#,
+            0,
+        );
+        assert_eq!(result.kind, PropertyValueKind::Image);
+        assert_eq!(result.value_string, "icons/frame.png");
+        assert_eq!(result.visual_items.iter().collect::<Vec<_>>(), ["4 8"]);
+
+        let result = property_conversion_test(
+            r#"export component Test { in property <image> test2; in property <image> test1: root.test2; }"#,
+            0,
+        );
+        assert_eq!(result.kind, PropertyValueKind::Code);
+    }
+
+    #[test]
+    fn test_property_two_way_binding() {
+        let result = property_conversion_test(
+            r#"export component Test { in property <length> test2; in property <length> test1 <=> root.test2; }"#,
+            0,
+        );
+        assert_eq!(result.kind, PropertyValueKind::TwoWayBinding);
+        assert_eq!(result.value_string, "root.test2");
+        assert_eq!(result.code, "root.test2");
+
+        let result = property_conversion_test(
+            r#"export component Test { in property <length> test2; in property <length> test1: root.test2; }"#,
+            0,
+        );
+        assert_eq!(result.kind, PropertyValueKind::Code);
+    }
+
     #[test]
     fn test_property_units() {
         let result =
@@ -1966,6 +3902,33 @@ fn test_property_units() {
         assert_eq!(result.kind, PropertyValueKind::Code);
     }
 
+    #[test]
+    fn test_convert_unit() {
+        use super::{convert_unit, convert_unit_text};
+        use i_slint_compiler::expression_tree::Unit;
+
+        assert_eq!(convert_unit(1.0, Unit::In, Unit::Px), Some(96.0));
+        assert_eq!(convert_unit(96.0, Unit::Px, Unit::In), Some(1.0));
+        assert_eq!(convert_unit(1.0, Unit::Cm, Unit::Mm), Some(10.0));
+        assert_eq!(convert_unit(72.0, Unit::Pt, Unit::In), Some(1.0));
+        assert_eq!(convert_unit(1.5, Unit::S, Unit::Ms), Some(1500.0));
+        assert_eq!(convert_unit(250.0, Unit::Ms, Unit::S), Some(0.25));
+        assert_eq!(convert_unit(0.5, Unit::Turn, Unit::Deg), Some(180.0));
+        let pi = (std::f64::consts::PI * 10_000.0).round() / 10_000.0;
+        assert_eq!(convert_unit(180.0, Unit::Deg, Unit::Rad), Some(pi));
+        assert_eq!(convert_unit(-1.0, Unit::Rad, Unit::Turn), Some(-0.1592));
+
+        // Physical pixels and rem depend on the window
+        assert_eq!(convert_unit(10.0, Unit::Px, Unit::Phx), None);
+        assert_eq!(convert_unit(10.0, Unit::Rem, Unit::Px), None);
+        assert_eq!(convert_unit(10.0, Unit::Px, Unit::Ms), None);
+
+        assert_eq!(convert_unit_text(" 2 ", "in", "pt"), Some("144".into()));
+        assert_eq!(convert_unit_text("2", "in", "phx"), None);
+        assert_eq!(convert_unit_text("root.width", "px", "cm"), None);
+        assert_eq!(convert_unit_text("2", "in", "lightyears"), None);
+    }
+
     #[test]
     fn test_property_with_default_values() {
         let source = r#"
@@ -2055,6 +4018,10 @@ fn create_test_property(name: &str, value: &str) -> PropertyInformation {
             name: name.into(),
             display_priority: 1000,
             type_name: "Sometype".into(),
+            is_animatable: false,
+            is_animated: false,
+            animation: Default::default(),
+            is_set: true,
             value: PropertyValue {
                 kind: PropertyValueKind::String,
                 value_string: value.into(),
@@ -2406,6 +4373,153 @@ fn test_map_preview_data_color() {
         );
     }
 
+    #[test]
+    fn test_map_preview_data_enum() {
+        validate_rp(
+            "in",
+            "enum Foo { first, second, third }",
+            "Foo",
+            "Foo.second",
+            super::PreviewData {
+                name: "test".into(),
+                has_setter: true,
+                kind: super::PreviewDataKind::Value,
+                ..Default::default()
+            },
+            super::PropertyValue {
+                code: "\"Foo.second\"".into(),
+                kind: super::PropertyValueKind::Enum,
+                value_string: "Foo".into(),
+                value_int: 1,
+                default_selection: 0,
+                visual_items: std::rc::Rc::new(slint::VecModel::from(vec![
+                    "first".into(),
+                    "second".into(),
+                    "third".into(),
+                ]))
+                .into(),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_map_preview_data_brush_solid() {
+        validate_rp(
+            "in",
+            "",
+            "brush",
+            "#aabbcc",
+            super::PreviewData {
+                name: "test".into(),
+                has_setter: true,
+                kind: super::PreviewDataKind::Value,
+                ..Default::default()
+            },
+            super::PropertyValue {
+                code: "\"#aabbccff\"".into(),
+                kind: super::PropertyValueKind::Brush,
+                value_string: "#aabbccff".into(),
+                value_brush: slint::Brush::SolidColor(slint::Color::from_argb_u8(
+                    0xff, 0xaa, 0xbb, 0xcc,
+                )),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_map_preview_data_brush_linear_gradient() {
+        validate_rp(
+            "in",
+            "",
+            "brush",
+            "@linear-gradient(90deg, #ff0000 0%, #0000ff 100%)",
+            super::PreviewData {
+                name: "test".into(),
+                has_setter: true,
+                kind: super::PreviewDataKind::Value,
+                ..Default::default()
+            },
+            super::PropertyValue {
+                code: "\"@linear-gradient(90deg, #ff0000ff 0%, #0000ffff 100%)\"".into(),
+                kind: super::PropertyValueKind::Brush,
+                brush_kind: super::BrushKind::Linear,
+                value_float: 90.0,
+                value_brush: slint::Brush::LinearGradient(
+                    i_slint_core::graphics::LinearGradientBrush::new(
+                        90.0,
+                        [
+                            i_slint_core::graphics::GradientStop {
+                                color: slint::Color::from_rgb_u8(0xff, 0x00, 0x00),
+                                position: 0.0,
+                            },
+                            i_slint_core::graphics::GradientStop {
+                                color: slint::Color::from_rgb_u8(0x00, 0x00, 0xff),
+                                position: 1.0,
+                            },
+                        ],
+                    ),
+                ),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_map_preview_data_image() {
+        validate_rp(
+            "in",
+            "",
+            "image",
+            "@image-url(\"\")",
+            super::PreviewData {
+                name: "test".into(),
+                has_setter: true,
+                kind: super::PreviewDataKind::Value,
+                ..Default::default()
+            },
+            // Images are shown as code, a plain string is no valid image
+            super::PropertyValue {
+                code: "".into(),
+                kind: super::PropertyValueKind::Code,
+                value_string: "image".into(),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_map_value_and_type_code_fallback() {
+        // Easing curves can not be part of the public API, so test the mapping directly
+        let mut mapping = super::ValueMapping::default();
+        super::map_value_and_type(
+            &i_slint_compiler::langtype::Type::Easing,
+            &Some(slint_interpreter::Value::EasingCurve(
+                i_slint_core::animations::EasingCurve::Linear,
+            )),
+            &mut mapping,
+        );
+
+        assert!(!mapping.is_too_complex);
+        assert!(!mapping.is_array);
+        assert_eq!(mapping.headers, vec![String::new()]);
+        assert_eq!(mapping.array_values.len(), 1);
+        assert_eq!(mapping.array_values[0].len(), 1);
+
+        let value = &mapping.array_values[0][0];
+        assert_eq!(value.kind, super::PropertyValueKind::Code);
+        compare_pv(
+            value,
+            &super::PropertyValue {
+                code: "".into(),
+                kind: super::PropertyValueKind::Code,
+                value_string: "easing".into(),
+                ..Default::default()
+            },
+        );
+    }
+
     #[test]
     fn test_map_preview_data_int() {
         validate_rp(
@@ -2716,4 +4830,166 @@ fn test_map_preview_data_bool_array() {
             ],
         );
     }
+
+    #[test]
+    fn test_filter_property_groups() {
+        use std::rc::Rc;
+
+        let property = |name: &str, type_name: &str| PropertyInformation {
+            name: name.into(),
+            type_name: type_name.into(),
+            is_set: name != "color" && name != "visible",
+            ..Default::default()
+        };
+        let group = |name: &str, properties: Vec<PropertyInformation>| super::PropertyGroup {
+            group_name: name.into(),
+            properties: Rc::new(slint::VecModel::from(properties)).into(),
+        };
+        let groups: slint::ModelRc<super::PropertyGroup> = Rc::new(slint::VecModel::from(vec![
+            group("Geometry", vec![property("width", "length"), property("height", "length")]),
+            group("Text", vec![property("color", "brush"), property("font-size", "length")]),
+            group("Other", vec![property("visible", "bool")]),
+        ]))
+        .into();
+
+        let names = |filter: &str, changed_only: bool| {
+            super::filter_property_groups(groups.clone(), filter.into(), changed_only)
+                .iter()
+                .map(|g| {
+                    let properties =
+                        g.properties.iter().map(|p| p.name.to_string()).collect::<Vec<_>>();
+                    format!("{}: {}", g.group_name, properties.join(","))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(" ", false).len(), 3);
+        assert!(names("IGHT", false).is_empty());
+        assert_eq!(names("eight", false), ["Geometry: height"]);
+        assert_eq!(names("length", false), ["Geometry: width,height", "Text: font-size"]);
+        assert_eq!(names("br", false), ["Text: color"]);
+        // Only upper case letters make the search case sensitive
+        assert_eq!(names("font-size", false), ["Text: font-size"]);
+        assert!(names("Font-size", false).is_empty());
+
+        // Groups without set properties are left out
+        assert_eq!(names("", true), ["Geometry: width,height", "Text: font-size"]);
+        assert_eq!(names("length", true), ["Geometry: width,height", "Text: font-size"]);
+        assert!(names("br", true).is_empty());
+    }
+
+    #[test]
+    fn test_palette_entries() {
+        let component =
+            |name: &str, category: &str, is_global: bool| common::ComponentInformation {
+                is_global,
+                ..common::test::test_component(name, category)
+            };
+        let known_components = [
+            component("TouchArea", "Input", false),
+            component("Text", "Basics", false),
+            component("Palette", "Globals", true),
+            component("TextInput", "Input", false),
+            component("Rectangle", "Basics", false),
+        ];
+
+        let labels = |filter: &str| {
+            super::palette_entries(&known_components, filter)
+                .iter()
+                .map(|e| (e.label.to_string(), e.component_index))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            labels(""),
+            vec![
+                ("Rectangle".to_string(), 4),
+                ("Text".to_string(), 1),
+                ("TextInput".to_string(), 3),
+                ("TouchArea".to_string(), 0),
+            ]
+        );
+        // Prefix matches sort before other matches
+        assert_eq!(
+            labels(" t"),
+            vec![
+                ("Text".to_string(), 1),
+                ("TextInput".to_string(), 3),
+                ("TouchArea".to_string(), 0),
+                ("Rectangle".to_string(), 4),
+            ]
+        );
+        assert_eq!(labels("INPUT"), vec![("TextInput".to_string(), 3)]);
+        assert!(labels("Palette").is_empty());
+
+        let entries = super::palette_entries(&known_components, "touch");
+        assert_eq!(entries[0].detail, "Input");
+    }
+
+    #[test]
+    fn test_fuzzy_match_score() {
+        use super::fuzzy_match_score;
+
+        assert_eq!(fuzzy_match_score("", "Text"), Some(0));
+        assert_eq!(fuzzy_match_score("xt", "Text"), Some(7));
+        assert!(fuzzy_match_score("xe", "Text").is_none());
+        assert!(fuzzy_match_score("textt", "Text").is_none());
+        // Word starts count more than other characters, the start of the text most
+        assert!(fuzzy_match_score("ti", "TextInput") > fuzzy_match_score("ti", "Rotation"));
+        assert!(fuzzy_match_score("ti", "TextInput") > fuzzy_match_score("ti", "my-text-input"));
+        assert!(fuzzy_match_score("touch", "TouchArea") > fuzzy_match_score("touch", "MyTouch"));
+    }
+
+    #[test]
+    fn test_filter_known_components() {
+        let item = |name: &str, pretty_location: &str| super::ComponentItem {
+            name: name.into(),
+            pretty_location: pretty_location.into(),
+            ..Default::default()
+        };
+        let group = |category: &str, items: Vec<super::ComponentItem>| super::ComponentListItem {
+            category: category.into(),
+            file_url: Default::default(),
+            components: std::rc::Rc::new(slint::VecModel::from(items)).into(),
+        };
+        let groups: slint::ModelRc<super::ComponentListItem> =
+            std::rc::Rc::new(slint::VecModel::from(vec![
+                group("Basics", vec![item("Rectangle", ""), item("Text", "")]),
+                group("Input", vec![item("TextInput", ""), item("TouchArea", "")]),
+                group("ui/main.slint", vec![item("MainWindow", "main.slint")]),
+            ]))
+            .into();
+
+        let filtered = |filter: &str| {
+            super::filter_known_components(groups.clone(), filter.into())
+                .iter()
+                .map(|g| {
+                    (
+                        g.category.to_string(),
+                        g.components.iter().map(|c| c.name.to_string()).collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(filtered(" ").len(), 3);
+        // Empty groups disappear, better matches come first
+        assert_eq!(filtered("tein"), vec![("Input".to_string(), vec!["TextInput".to_string()])]);
+        assert_eq!(
+            filtered("ta"),
+            vec![
+                ("Input".to_string(), vec!["TouchArea".to_string()]),
+                ("Basics".to_string(), vec!["Rectangle".to_string()]),
+            ]
+        );
+        // Matching the category or file keeps all components in it
+        assert_eq!(
+            filtered("input"),
+            vec![("Input".to_string(), vec!["TextInput".to_string(), "TouchArea".to_string()])]
+        );
+        assert_eq!(
+            filtered("main.slint"),
+            vec![("ui/main.slint".to_string(), vec!["MainWindow".to_string()])]
+        );
+    }
 }
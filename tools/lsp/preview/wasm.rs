@@ -200,12 +200,18 @@ pub fn resource_url_mapper(
 }
 
 pub fn send_message_to_lsp(message: crate::common::PreviewToLspMessage) {
+    super::connection::send(message);
+}
+
+/// Returns false when there is no LSP to send `message` to
+pub fn deliver_message_to_lsp(message: &crate::common::PreviewToLspMessage) -> bool {
     WASM_CALLBACKS.with_borrow(|callbacks| {
-        if let Some(callbacks) = &callbacks {
-            let notifier = js_sys::Function::from((callbacks.lsp_notifier).clone());
-            let value = serde_wasm_bindgen::to_value(&message).unwrap();
-            let _ = notifier.call1(&JsValue::UNDEFINED, &value);
-        }
+        let Some(callbacks) = &callbacks else {
+            return false;
+        };
+        let notifier = js_sys::Function::from((callbacks.lsp_notifier).clone());
+        let value = serde_wasm_bindgen::to_value(message).unwrap();
+        notifier.call1(&JsValue::UNDEFINED, &value).is_ok()
     })
 }
 
@@ -0,0 +1,189 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Expressions watched while the preview runs. They are evaluated by the interpreter in the
+//! scope of the root element of the previewed component, and evaluated again whenever one of
+//! the properties they depend on changes.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use i_slint_core::properties::ChangeTracker;
+use slint_interpreter::{ComponentHandle, ComponentInstance};
+
+/// The outcome of evaluating a watched expression
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Evaluation {
+    /// The value, or why there is none
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// Evaluate `expression` with the current state of `component`
+pub fn evaluate(component: &ComponentInstance, expression: &str) -> Evaluation {
+    // The compiler might have optimized away what the expression refers to, which the
+    // interpreter does not expect
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        component.evaluate_expression(expression)
+    }));
+    match result {
+        Ok(Ok(value)) => {
+            Evaluation { text: super::expression_editor::value_text(&value), is_error: false }
+        }
+        Ok(Err(error)) => Evaluation { text: error, is_error: true },
+        Err(_) => Evaluation { text: "The expression can not be evaluated".into(), is_error: true },
+    }
+}
+
+struct Watch {
+    expression: String,
+    evaluation: Rc<RefCell<Evaluation>>,
+    tracker: ChangeTracker,
+}
+
+impl Watch {
+    fn track(&self, component: slint_interpreter::Weak<ComponentInstance>) {
+        self.tracker.init(
+            (component, self.expression.clone(), self.evaluation.clone()),
+            |(component, expression, evaluation)| {
+                let Some(component) = component.upgrade() else {
+                    return Evaluation::default();
+                };
+                let result = evaluate(&component, expression);
+                evaluation.replace(result.clone());
+                result
+            },
+            |_, _| changed(),
+        );
+    }
+}
+
+#[derive(Default)]
+struct Watches {
+    watches: Vec<Watch>,
+    component: Option<slint_interpreter::Weak<ComponentInstance>>,
+    changed: Option<fn()>,
+    update_pending: bool,
+}
+
+thread_local! {static WATCHES: RefCell<Watches> = RefCell::default();}
+
+/// Tell the UI about a new value, from the event loop: Properties change while bindings
+/// are evaluated
+fn changed() {
+    let changed = WATCHES.with(|watches| {
+        let mut watches = watches.borrow_mut();
+        let changed = watches.changed?;
+        (!std::mem::replace(&mut watches.update_pending, true)).then_some(changed)
+    });
+    if let Some(changed) = changed {
+        slint::Timer::single_shot(std::time::Duration::ZERO, move || {
+            WATCHES.with(|watches| watches.borrow_mut().update_pending = false);
+            changed();
+        });
+    }
+}
+
+/// `changed` gets called from the event loop when the value of a watched expression changed
+pub fn install(changed: fn()) {
+    WATCHES.with(|watches| watches.borrow_mut().changed = Some(changed));
+}
+
+/// Evaluate the watched expressions with `component` from now on
+pub fn attach(component: &ComponentInstance) {
+    WATCHES.with(|watches| {
+        let mut watches = watches.borrow_mut();
+        let component = component.as_weak();
+        for watch in &watches.watches {
+            watch.track(component.clone());
+        }
+        watches.component = Some(component);
+    });
+    changed();
+}
+
+pub fn add(expression: &str) {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return;
+    }
+    WATCHES.with(|watches| {
+        let mut watches = watches.borrow_mut();
+        let watch = Watch {
+            expression: expression.to_string(),
+            evaluation: Default::default(),
+            tracker: ChangeTracker::default(),
+        };
+        if let Some(component) = watches.component.clone() {
+            watch.track(component);
+        }
+        watches.watches.push(watch);
+    });
+    changed();
+}
+
+pub fn remove(index: usize) {
+    WATCHES.with(|watches| {
+        let mut watches = watches.borrow_mut();
+        if index < watches.watches.len() {
+            watches.watches.remove(index);
+        }
+    });
+    changed();
+}
+
+/// The watched expressions with their latest evaluation
+pub fn watches() -> Vec<(String, Evaluation)> {
+    WATCHES.with(|watches| {
+        watches
+            .borrow()
+            .watches
+            .iter()
+            .map(|w| (w.expression.clone(), w.evaluation.borrow().clone()))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch() {
+        let source = r#"
+            export global Theme {
+                in-out property <length> spacing: 4px;
+            }
+            export component Main {
+                in-out property <int> count: 3;
+                out property <string> label: "Count " + count;
+                in-out property <length> gap: 100px;
+                Text { text: root.label; x: Theme.spacing; }
+            }
+        "#;
+        let instance = crate::preview::test::interpret_test("fluent", source);
+        attach(&instance);
+        add("root.count * 2");
+        add("label");
+        add("Theme.spacing + root.gap / 2");
+        add("root.unknown");
+        add("  ");
+
+        let text = |index: usize| watches()[index].1.text.clone();
+        assert_eq!(watches().len(), 4);
+        assert_eq!(text(0), "6");
+        assert_eq!(text(1), "\"Count 3\"");
+        assert_eq!(text(2), "54");
+        assert!(watches()[3].1.is_error);
+        assert!(!watches()[0].1.is_error);
+
+        instance.set_property("count", slint_interpreter::Value::Number(5.)).unwrap();
+        ChangeTracker::run_change_handlers();
+        assert_eq!(text(0), "10");
+        assert_eq!(text(1), "\"Count 5\"");
+
+        remove(0);
+        assert_eq!(watches().len(), 3);
+        assert_eq!(watches()[0].0, "label");
+    }
+}
@@ -222,6 +222,7 @@ pub fn create(
             server_notifier,
             to_show: Default::default(),
             open_urls: Default::default(),
+            spell_checker: Default::default(),
         }),
         reentry_guard,
         rh: Rc::new(rh),
@@ -302,6 +303,11 @@ pub async fn process_preview_to_lsp_message(
                     .server_notifier
                     .send_notification::<lsp_types::notification::ShowMessage>(message);
             }
+            M::Ping { id } => {
+                self.ctx
+                    .server_notifier
+                    .send_message_to_preview(crate::common::LspToPreviewMessage::Pong { id });
+            }
         }
         Ok(())
     }